@@ -2,12 +2,30 @@ cosmic-files = COSMIC Files
 empty-folder = Empty folder
 empty-folder-hidden = Empty folder (has hidden items)
 no-results = No results found
+search-running = Searching, {$count} {$count ->
+        [one] result
+        *[other] results
+    } found in {$seconds} s
+search-finished = {$count} {$count ->
+        [one] result
+        *[other] results
+    } found in {$seconds} s
+search-stopped = Search stopped, {$count} {$count ->
+        [one] result
+        *[other] results
+    } found in {$seconds} s
+search-stop = Stop
+offline-listing = Offline, showing the last known contents
+read-only-location = Read-only file system, items here cannot be created, changed or deleted
+remote-location = Network share, some actions may be slower
 filesystem = Filesystem
 home = Home
 networks = Networks
 notification-in-progress = File operations are in progress.
 trash = Trash
 recents = Recents
+computer = Computer
+starred = Starred
 undo = Undo
 today = Today
 
@@ -26,6 +44,54 @@ name = Name
 modified = Modified
 trashed-on = Trashed
 size = Size
+column-type = Type
+column-permissions = Permissions
+column-created = Created
+column-accessed = Accessed
+column-mime-type = MIME type
+original-location = Original location
+column-kind = {$kind ->
+        [folder] Folder
+        [image] Image
+        [video] Video
+        [audio] Audio
+        [text] Text
+        [font] Font
+        *[other] File
+    }
+
+# Trash view
+all-dates = All dates
+yesterday = Yesterday
+this-week = This week
+earlier-this-week = Earlier this week
+older = Older
+all-folders = All folders
+no-grouping = No grouping
+group-by-date = Group by date
+group-by-folder = Group by folder
+
+# Folders by date
+by-date = {$name} by date
+last-week = Last week
+earlier-this-month = Earlier this month
+last-month = Last month
+date-month = {$month ->
+        [1] January
+        [2] February
+        [3] March
+        [4] April
+        [5] May
+        [6] June
+        [7] July
+        [8] August
+        [9] September
+        [10] October
+        [11] November
+        *[12] December
+    } {$year}
+trash-size = {$size} in trash
+trash-group-size = {$group} ({$size})
 
 # Progress footer
 details = Details
@@ -45,22 +111,54 @@ resume = Resume
 
 ## Compress Dialog
 create-archive = Create archive
+compress-contents = Contents
+compress-drop-hint = Drop files here to add them to the archive.
+compress-empty = No items
+compress-location = Location
+compress-choose-folder = Choose folder...
+compress-to-title = Create archive in folder
+compress-here = Create here
+compression-level = Compression
+compression-fast = Fast
+compression-normal = Normal
+compression-best = Smallest
 
 ## Extract Dialog
 extract-password-required = Password required
 extract-to = Extract To...
 extract-to-title = Extract to folder
 
+## Eject Busy Dialog
+eject-busy = {$name} is in use
+ejecting = Ejecting {$name}…
+eject-busy-description = Close these applications and try again, or force the drive to be ejected, which may lose unsaved changes.
+eject-busy-finding = Finding the applications using the drive…
+eject-busy-unknown = No applications using the drive were found, they may belong to another user. Try again later, or force the drive to be ejected, which may lose unsaved changes.
+eject-busy-process = {$name} (process {$pid})
+eject-force = Force eject
+eject-error = Unable to eject {$name}
+
 ## Empty Trash Dialog
 empty-trash = Empty trash
 empty-trash-warning = Are you sure you want to permanently delete all the items in Trash?
+empty-trash-summary = {$items} {$items ->
+        [one] item
+        *[other] items
+    }, {$size} total
+largest-items = Largest items
+empty-trash-older = Delete items older than {$days} days
 
 ## Mount Error Dialog
 mount-error = Unable to access drive
+mount-connecting = Connecting to {$name}…
+mount-error-auth = Authentication failed for {$name}
+mount-error-unreachable = Unable to reach {$name}
+mount-error-timeout = Connection to {$name} timed out
 
 ## New File/Folder Dialog
 create-new-file = Create new file
 create-new-folder = Create new folder
+untitled-folder = Untitled folder
 file-name = File name
 folder-name = Folder name
 file-already-exists = A file with that name already exists.
@@ -68,6 +166,11 @@ folder-already-exists = A folder with that name already exists.
 name-hidden = Names starting with "." will be hidden.
 name-invalid = Name cannot be "{$filename}".
 name-no-slashes = Name cannot contain slashes.
+create-folders-title = Create {$folders ->
+        [one] a new folder
+        *[other] {$folders} new folders
+    }?
+create-folders-warning = "{$path}" does not exist yet. The new folders are removed if the file is not saved there.
 
 ## Open/Save Dialog
 cancel = Cancel
@@ -82,33 +185,146 @@ open-multiple-files = Open multiple files
 open-multiple-folders = Open multiple folders
 save = Save
 save-file = Save file
+location-not-writable = You do not have permission to create items in this folder
+
+## Open All Dialog
+open-all-question = Open {$launches} windows?
+open-all-warning = Opening {$files} {$files ->
+        [one] file
+        *[other] files
+    } and {$folders} {$folders ->
+        [one] folder
+        *[other] folders
+    } will start {$launches} applications and tabs.
 
 ## Open With Dialog
 open-with-title = How do you want to open "{$name}"?
 browse-store = Browse {$store}
 other-apps = Other applications
 related-apps = Related applications
+category-apps = Applications for {$category} files
+show-all-apps = Show all applications
+remember-app = Always use this application for {$mime}
+open-with-search = Search applications
+custom-command = Custom command
+custom-command-placeholder = Command to open the files with, like "app --option %f"
 
 ## Permanently delete Dialog
 selected-items = the {$items} selected items
 permanently-delete-question = Permanently delete
 delete = Delete
 permanently-delete-warning = Are you sure you want to permanently delete {$target}? This cannot be undone.
+permanently-delete-more = and {$items} more
+permanently-delete-summary = {$items} {$items ->
+        [one] item
+        *[other] items
+    }, {$size} total
+
+## Securely delete Dialog
+secure-delete-question = Securely delete
+secure-delete-warning = Are you sure you want to overwrite and delete {$target}? This cannot be undone.
+secure-delete-storage-warning = On SSDs and file systems that copy on write, like btrfs, the old contents may still be recovered
+
+## Preview Changes Dialog
+preview-changes = Preview changes
+apply = Apply
+planned-changes = {$changes} {$changes ->
+        [one] change
+        *[other] changes
+    }
+planned-changes-more = and {$changes} more
+planned-copy = Copy "{$from}" to "{$to}"
+planned-create-folder = Create folder "{$path}"
+planned-delete = Delete "{$path}"
+planned-move = Move "{$from}" to "{$to}"
+planned-rename = Rename "{$from}" to "{$to}"
+planned-replace = Replace "{$to}" with "{$from}"
 
 ## Rename Dialog
 rename-file = Rename file
 rename-folder = Rename folder
+bulk-rename = Rename {$items} items
+bulk-rename-find = Find
+bulk-rename-replace = Replace with
+bulk-rename-replace-placeholder = {"{"}n{"}"} inserts a number, {"{"}n:3{"}"} pads it to 3 digits
+bulk-rename-regex = Use regular expressions, $1 inserts a captured group
+bulk-rename-keep-extension = Keep file extensions
+bulk-rename-case-keep = Keep case
+bulk-rename-case-lower = lowercase
+bulk-rename-case-upper = UPPERCASE
+bulk-rename-case-title = Title Case
+bulk-rename-preview = Preview
+bulk-rename-invalid = Invalid name
+bulk-rename-duplicate = Name used more than once
+bulk-rename-exists = Name already exists
+bulk-rename-regex-error = Invalid regular expression: {$error}
+change-extension = Change extension...
+change-extension-title = Change the extension of {$items} {$items ->
+        [one] file
+        *[other] files
+    }
+change-extension-placeholder = New extension, leave empty to remove it
+change-extension-checking = Checking file contents...
+change-extension-mismatch = Contents look like {$mime}
+change-extension-mismatches = {$items ->
+        [one] One file does not look like
+        *[other] {$items} files do not look like
+    } this type of file
+
+## Verify Checksum Dialog
+verify-checksum = Verify checksum...
+verify-checksum-title = Verify checksum of "{$name}"
+verify = Verify
+checksum-placeholder = Paste an MD5, SHA-1, SHA-256 or BLAKE3 checksum
+load-checksum-file = Load checksum file...
+checksum-match = The file matches the {$algorithm} checksum
+checksum-mismatch = The file does not match the checksum
+checksum-invalid = Not a valid checksum
+checksum-not-found = No checksum for "{$name}" in the file
+
+## Tags Dialog
+edit-tags = Tags...
+edit-tags-title = Tags for "{$name}"
+tag-red = Red
+tag-orange = Orange
+tag-yellow = Yellow
+tag-green = Green
+tag-blue = Blue
+tag-purple = Purple
+tag-gray = Gray
 
 ## Replace Dialog
 replace = Replace
 replace-title = "{$filename}" already exists in this location.
 replace-warning = Do you want to replace it with the one you are saving? Replacing it will overwrite its content.
 replace-warning-operation = Do you want to replace it? Replacing it will overwrite its content.
+replace-warning-restore = It was added after this item was moved to the trash. Do you want to replace it? The replaced item is moved to the trash.
+restore-elsewhere = Restore elsewhere...
+restore-elsewhere-title = Restore to folder
+restore-missing-title = Restore to folder, the original folder is missing
+restore-here = Restore here
 original-file = Original file
 replace-with = Replace with
 apply-to-all = Apply to all
 keep-both = Keep both
 skip = Skip
+replace-rename = New name
+replace-larger = Larger
+replace-smaller = Smaller
+replace-newer = Newer
+replace-older = Older
+
+## Selection Statistics Dialog
+selection-statistics = Selection statistics
+selection-statistics-size = {$items} {$items ->
+        [one] item
+        *[other] items
+    }, {$size} in total, {$average} on average
+selection-statistics-kind = {$kind}: {$items}
+selection-statistics-largest = Largest: {$name} ({$size})
+selection-statistics-oldest = Oldest: {$name}, modified {$modified}
+selection-statistics-newest = Newest: {$name}, modified {$modified}
+close = Close
 
 ## Set as Executable and Launch Dialog
 set-executable-and-launch = Set as executable and launch
@@ -120,6 +336,8 @@ open-with = Open with
 owner = Owner
 group = Group
 other = Other
+apply-to-enclosed = Apply to enclosed items
+apply-to-enclosed-description = Set the permissions, owner, and group of everything in this folder to match it
 ### Mode 0
 none = None
 ### Mode 1 (unusual)
@@ -174,11 +392,18 @@ network-drive-schemes =
 network-drive-error = Unable to access network drive
 password = Password
 remember-password = Remember password
+sftp-password = Password for {$server}
+sftp-password-refused = The password was not accepted.
+sftp-host-key-unknown = Trust {$server}?
+sftp-host-key-description = The identity of this server is not known yet. Connect only if its key fingerprint is {$fingerprint}.
+sftp-host-key-changed = The identity of {$server} has changed since the last connection. It may be impersonated, so the connection was refused.
 try-again = Try again
 username = Username
 
 ## Operations
 cancelled = Cancelled
+not-enough-space = Not enough free space in "{$path}", {$shortfall} more is needed
+quota-exceeded = Copying to "{$path}" would exceed your disk quota of {$limit} by {$shortfall}
 edit-history = Edit history
 history = History
 no-history = No items in history.
@@ -186,6 +411,25 @@ pending = Pending
 progress = {$percent}%
 progress-cancelled = {$percent}%, cancelled
 progress-paused = {$percent}%, paused
+progress-queued = {$percent}%, queued
+operation-queue = Copy and move queue
+open-destination = Open destination when done
+queue-position = {$position} in queue
+move-earlier = Move up
+move-later = Move down
+transfer-rate = {$rate}/s
+seconds-left = {$seconds} {$seconds ->
+        [one] second
+        *[other] seconds
+    } left
+minutes-left = {$minutes} {$minutes ->
+        [one] minute
+        *[other] minutes
+    } left
+hours-left = {$hours} {$hours ->
+        [one] hour
+        *[other] hours
+    } left
 failed = Failed
 complete = Complete
 compressing = Compressing {$items} {$items ->
@@ -229,6 +473,37 @@ setting-executable-and-launching = Setting "{$name}" as executable and launching
 set-executable-and-launched = Set "{$name}" as executable and launched
 setting-permissions = Setting permissions for "{$name}" to {$mode}
 set-permissions = Set permissions for "{$name}" to {$mode}
+setting-permissions-recursive = Setting permissions for items in "{$name}" to {$mode} ({$progress})...
+set-permissions-recursive = Set permissions for items in "{$name}" to {$mode}
+removing-photo-metadata = Removing metadata from {$items} {$items ->
+        [one] photo
+        *[other] photos
+    } ({$progress})...
+removed-photo-metadata = Removed metadata from {$items} {$items ->
+        [one] photo
+        *[other] photos
+    }
+rotating-images = Rotating {$items} {$items ->
+        [one] image
+        *[other] images
+    } ({$progress})...
+rotated-images = Rotated {$items} {$items ->
+        [one] image
+        *[other] images
+    }
+flipping-images = Flipping {$items} {$items ->
+        [one] image
+        *[other] images
+    } ({$progress})...
+flipped-images = Flipped {$items} {$items ->
+        [one] image
+        *[other] images
+    }
+permission-errors = {$items} {$items ->
+        [one] item
+        *[other] items
+    } could not be changed:
+permission-errors-more = And {$items} more
 moving = Moving {$items} {$items ->
         [one] item
         *[other] items
@@ -255,6 +530,14 @@ restored = Restored {$items} {$items ->
         [one] item
         *[other] items
     } from {trash}
+securely-deleting = Securely deleting {$items} {$items ->
+        [one] item
+        *[other] items
+    } ({$progress})...
+securely-deleted = Securely deleted {$items} {$items ->
+        [one] item
+        *[other] items
+    }
 unknown-folder = unknown folder
 
 ## Open with
@@ -263,17 +546,121 @@ default-app = {$name} (default)
 
 ## Show details
 show-details = Show details
+details-preview = Preview
+details-properties = Properties
+details-permissions = Permissions
+details-versions = Versions
+no-permissions = Permissions cannot be changed here
+versions = Earlier versions
+versions-loading = Looking for snapshots...
+no-versions = No earlier versions found in file system snapshots
+version-snapshot = Snapshot {$snapshot}
+version-description = Modified {$modified}, {$size}
+details-advanced = Advanced
+attributes-loading = Reading access control list...
+attributes-error = Failed to read attributes: {$error}
+attributes-change-error = Failed to change attributes: {$error}
+no-attributes = Access control lists and extended attributes are not supported here
+acl = Access control list
+default-acl = Default for new items
+acl-user = User
+acl-group = Group
+acl-mask = Mask
+acl-mask-description = Limits the access of named users and groups
+acl-name-placeholder = User or group name
+acl-default = Apply to new items in this folder
+acl-unknown-name = No user or group named {$name}
+xattrs = Extended attributes
+xattr-name-placeholder = Name
+xattr-value-placeholder = Value
+xattr-invalid-name = Only attributes in the user namespace can be added
+add = Add
 type = Type: {$mime}
 items = Items: {$items}
 item-size = Size: {$size}
 item-created = Created: {$created}
 item-modified = Modified: {$modified}
 item-accessed = Accessed: {$accessed}
+time-with-relative = {$time} ({$relative})
+just-now = just now
+minutes-ago = {$minutes} {$minutes ->
+        [one] minute
+        *[other] minutes
+    } ago
+hours-ago = {$hours} {$hours ->
+        [one] hour
+        *[other] hours
+    } ago
+days-ago = {$days} {$days ->
+        [one] day
+        *[other] days
+    } ago
+item-original-location = Original location: {$path}
+item-deleted = Deleted: {$deleted}
+item-filesystem = File system: {$fs_type} on {$mount_point}
+item-free-space = Free space: {$free} of {$total}
+item-free-of-total = {$free} free of {$total}
+item-quota = Quota: {$used} of {$limit} used
 calculating = Calculating...
+item-size-calculating = Size: {$size} so far
+calculate-size = Calculate
+page-of = Page {$page} of {$pages}
+archive-contents = Archive contents
+archive-loading = Reading archive...
+archive-entry-size = {$size} ({$compressed} compressed)
+archive-more-entries = And {$items} more {$items ->
+        [one] item
+        *[other] items
+    }
+open-archive = Open archive
+checksums = Checksums
+calculate-checksums = Calculate checksums
+copy-checksum = Copy checksum
+audio-player-unavailable = Install ffmpeg to play audio files here
+audio-codec = Codec: {$codec}
+audio-duration = Duration: {$duration}
+audio-bitrate = Bitrate: {$bitrate} kbps
+audio-sample-rate = Sample rate: {$sample_rate} Hz
+audio-channels = Channels: {$channels}
+photo-camera = Camera: {$camera}
+photo-lens = Lens: {$lens}
+photo-exposure = Exposure: {$exposure}
+photo-exposure-time = {$time} s
+photo-focal-length = {$length} mm
+photo-iso = ISO {$iso}
+photo-location = Location: {$latitude}, {$longitude}
+photo-open-map = Open in maps
 
 ## Settings
 settings = Settings
 single-click = Single click to open
+double-click-parent = Double click empty space to open parent folder
+tree-view = Expand folders in list view
+grid-columns = Columns in grid view
+grid-columns-automatic = Fill the window width
+slideshow-interval = Gallery slideshow interval
+slideshow-seconds = {$seconds} {$seconds ->
+    [one] second
+    *[other] seconds
+  }
+slideshow-shuffle = Shuffle gallery slideshows
+slideshow-repeat = Loop gallery slideshows
+animated-preview = Play animations and videos on hover in grid view
+extension-badges = Show file extensions on icons in grid view
+secure-delete-setting = Offer secure delete in the context menu
+write-through-removable = Write directly to removable drives
+write-through-removable-description = Copying is slower, but progress is accurate and drives can be ejected quickly
+new-tab-drives = Open drives in a new tab
+new-tab-network = Open network locations in a new tab
+restore-session = Restore previous session
+restore-session-description = Reopen the windows and tabs that were open when the last window was closed
+show-frequent-folders = Show frequently used folders
+show-frequent-folders-description = A bar above the view with the folders opened most often lately
+pin-folder = Pin
+unpin-folder = Unpin
+open-destination-description = Copies and extractions open their destination in a new tab, with the new items selected
+hidden-patterns = Hidden file patterns
+hidden-patterns-description = Comma separated patterns of file names to hide, in addition to names starting with a dot
 
 ### Appearance
 appearance = Appearance
@@ -283,25 +670,97 @@ dark = Dark
 light = Light
 
 ### Type to Search
+toolbar = Toolbar
+extract-on-open = Extract archives when opened
+parent-folder = Parent folder
+details-position = Details pane position
+details-position-left = Left side of the window
+details-position-right = Right side of the window
+conflict-policy = When pasted items already exist
+conflict-policy-ask = Ask each time
+conflict-policy-keep-both = Always keep both
+conflict-policy-replace-older = Always replace older
+conflict-policy-skip = Always skip existing
+reduce-background-work = Reduce background work
+reduce-background-work-auto = On battery or in power saver mode
+reduce-background-work-always = Always
+reduce-background-work-never = Never
+trash-max-days = Delete trashed items automatically
+trash-max-days-never = Never
+trash-max-days-after = After {$days} {$days ->
+        [one] day
+        *[other] days
+    }
+trash-max-size = Maximum trash size
+trash-max-size-description = The oldest items are deleted when the trash grows larger
+trash-max-size-none = No limit
+default-apps = Default applications
+default-apps-images = Images
+default-apps-videos = Videos
+default-apps-music = Music
+default-apps-text = Text
+default-apps-archives = Archives
+default-apps-none = No applications installed
+file-associations = File associations
+file-associations-description = Applications that open each type of file
+file-associations-open = Manage
+file-associations-search = Search file types
+file-associations-reset = Reset to system default
+audit-log = Operation log
+audit-log-enable = Log destructive operations
+audit-log-description = Records deleting, moving, replacing, renaming and restoring files, and changes to their contents or permissions, to find out where a file went
+audit-log-view = Logged operations
+audit-log-open = View log
+audit-log-empty = No operations have been logged.
+audit-log-entry = {$time}: {$operation} {$from}
+audit-log-paths = {$path} and {$items} more
+audit-log-failed = Failed: {$error}
+audit-log-replaced = Replaced {$path}
+audit-log-skipped = Skipped {$path}, which already existed
+audit-log-renamed = Kept {$path}, wrote {$to} instead
+audit-log-more-conflicts = {$items} more items already existed
+tab-title = Tab and window titles
+tab-title-folder-name = Show the folder name
+tab-title-shortened = Show a shortened path
+tab-title-full-path = Show the full path
 type-to-search = Type to Search
 type-to-search-recursive = Searches the current folder and all sub-folders
 type-to-search-enter-path = Enters the path to the directory or file
 
 # Context menu
 add-to-sidebar = Add to sidebar
+pin-for-session = Pin for this session
 compress = Compress
+copy-path = Copy path
 delete-permanently = Delete permanently
+secure-delete = Securely delete
 extract-here = Extract
 new-file = New file...
+remove-photo-metadata = Remove photo metadata
+rotate-left = Rotate left
+rotate-right = Rotate right
+flip-horizontally = Flip horizontally
+flip-vertically = Flip vertically
 new-folder = New folder...
 open-in-terminal = Open in terminal
+open-in-terminal-with-file = Open in terminal with file
+open-folder-in-new-tab = Open this folder in new tab
+open-folder-in-new-window = Open this folder in new window
 move-to-trash = Move to trash
 restore-from-trash = Restore from trash
+restore-to = Restore to...
 remove-from-sidebar = Remove from sidebar
+unpin = Unpin
 sort-by-name = Sort by name
 sort-by-modified = Sort by modified
 sort-by-size = Sort by size
 sort-by-trashed = Sort by delete time
+sort-by-original-location = Sort by original location
+sort-by-type = Sort by type
+star = Star
+unstar = Unstar
+unmount = Unmount
+eject = Eject
 
 ## Desktop
 change-wallpaper = Change wallpaper...
@@ -324,21 +783,39 @@ edit = Edit
 cut = Cut
 copy = Copy
 paste = Paste
+paste-with = Paste and…
+paste-keep-both = Keep both
+paste-replace-older = Replace older
+paste-skip = Skip existing
+paste-preview = Preview paste…
+keep-both-here = Keep both when pasting here
 select-all = Select all
+select-all-including-hidden = Select all including hidden
 
 ## View
 zoom-in = Zoom in
 default-size = Default size
 zoom-out = Zoom out
+zoom = Zoom
+zoom-level = {$zoom}%
+zoom-reset = Reset
 view = View
 grid-view = Grid view
 list-view = List view
 show-hidden-files = Show hidden files
 list-directories-first = List directories first
+group-by-type = Group by type in grid view
+folder-sizes = Show folder sizes in list view
+browse-by-date = Browse by date
 gallery-preview = Gallery preview
 menu-settings = Settings...
 menu-about = About COSMIC Files...
 
+## Go
+go = Go
+back = Back
+forward = Forward
+
 ## Sort
 sort = Sort
 sort-a-z = A-Z
@@ -347,3 +824,6 @@ sort-newest-first = Newest first
 sort-oldest-first = Oldest first
 sort-smallest-to-largest = Smallest to largest
 sort-largest-to-smallest = Largest to smallest
+then-by-name = Then by name
+then-by-modified = Then by modified
+then-by-size = Then by size
@@ -13,7 +13,7 @@ use cosmic::iced::{
     Limits,
 };
 #[cfg(feature = "wayland")]
-use cosmic::iced_winit::commands::overlap_notify::overlap_notify;
+use cosmic::iced_winit::commands::{activation, overlap_notify::overlap_notify};
 use cosmic::{
     app::{self, context_drawer, Core, Task},
     cosmic_config, cosmic_theme, executor,
@@ -23,10 +23,18 @@ use cosmic::{
         core::SmolStr,
         event,
         futures::{self, SinkExt},
-        keyboard::{Event as KeyEvent, Key, Modifiers},
+        keyboard::{key::Named, Event as KeyEvent, Key, Modifiers},
         stream,
+        //TODO: export in cosmic::widget
+        widget::{scrollable::AbsoluteOffset, vertical_rule},
         window::{self, Event as WindowEvent, Id as WindowId},
-        Alignment, Event, Length, Point, Rectangle, Size, Subscription,
+        Alignment,
+        Event,
+        Length,
+        Point,
+        Rectangle,
+        Size,
+        Subscription,
     },
     iced_runtime::clipboard,
     style, theme,
@@ -40,7 +48,10 @@ use cosmic::{
     },
     Application, ApplicationExt, Element,
 };
-use cosmic::{iced::mouse::Event::CursorMoved, surface};
+use cosmic::{
+    iced::mouse::{self, Event::CursorMoved},
+    surface,
+};
 use mime_guess::Mime;
 use notify_debouncer_full::{
     new_debouncer,
@@ -51,7 +62,9 @@ use slotmap::Key as SlotMapKey;
 use std::{
     any::TypeId,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
-    env, fmt, fs,
+    env,
+    ffi::OsString,
+    fmt, fs,
     future::Future,
     io,
     num::NonZeroU16,
@@ -67,27 +80,78 @@ use trash::TrashItem;
 use wayland_client::{protocol::wl_output::WlOutput, Proxy};
 
 use crate::{
+    audit_log::{self, AuditConflict, AuditEntry},
+    checksum::{self, ChecksumAlgorithm},
     clipboard::{ClipboardCopy, ClipboardKind, ClipboardPaste},
     config::{
-        AppTheme, Config, DesktopConfig, Favorite, IconSizes, TabConfig, TimeConfig, TypeToSearch,
-        TIME_CONFIG_ID,
+        AppTheme, ColumnWidths, Config, ConflictPolicy, DesktopConfig, DetailsPosition, DetailsTab,
+        Favorite, IconSizes, NewTabConfig, ReduceBackgroundWork, SessionLocation, SessionTab,
+        SessionWindow, SlideshowConfig, TabConfig, TabTitle, TimeConfig, ToolbarButton,
+        TypeToSearch, DETAILS_WIDTH_MAX, DETAILS_WIDTH_MIN, SLIDESHOW_INTERVAL_MAX,
+        SLIDESHOW_INTERVAL_MIN, TIME_CONFIG_ID,
     },
-    dialog::{Dialog, DialogKind, DialogMessage, DialogResult},
-    fl, home_dir,
+    default_apps::AppCategory,
+    dialog::{
+        bulk_rename::{BulkRename, CaseTransform, ChangeExtension, RenameConflict, RenamePreview},
+        not_writable_reason, Dialog, DialogKind, DialogMessage, DialogResult,
+    },
+    fl,
+    frequent_folders::{self, FolderVisits},
+    home_dir,
+    image_transform::ImageTransform,
     key_bind::key_binds,
     localize::LANGUAGE_SORTER,
     menu,
     mime_app::{self, MimeApp, MimeAppCache},
     mime_icon,
-    mounter::{MounterAuth, MounterItem, MounterItems, MounterKey, MounterMessage, MOUNTERS},
+    mounter::{
+        self, blocking_processes, BlockingProcess, MounterAuth, MounterError, MounterErrorKind,
+        MounterItem, MounterItems, MounterKey, MounterMessage, MOUNTERS,
+    },
+    mouse_area,
     operation::{
-        Controller, Operation, OperationError, OperationErrorType, OperationSelection,
-        ReplaceResult,
+        self,
+        queue::{self, OperationQueue, Transfer},
+        valid_rename, Controller, Operation, OperationError, OperationErrorType,
+        OperationSelection, PlannedChange, ReplaceResult,
     },
     spawn_detached::spawn_detached,
     tab::{self, HeadingOptions, ItemMetadata, Location, Tab, HOVER_DURATION},
+    tag::{self, ColorTag},
+    trash_policy,
 };
 
+/// Opening more applications or windows than this at once asks for confirmation first
+const OPEN_ALL_CONFIRM_LAUNCHES: usize = 5;
+
+/// Conflicts listed for each entry of the operation log
+const AUDIT_LOG_CONFLICTS: usize = 3;
+
+/// Items listed by name in the permanent delete dialog
+const PERMANENTLY_DELETE_LISTED: usize = 5;
+
+/// Changes listed in the preview changes dialog, to keep large operations responsive
+const PREVIEW_CHANGES_LISTED: usize = 200;
+
+/// Most columns that can be chosen for grid view
+const GRID_COLUMNS_MAX: u16 = 16;
+
+/// Choices for deleting trashed items automatically, in days, 0 for never
+const TRASH_MAX_DAYS: [u32; 6] = [0, 7, 14, 30, 60, 90];
+
+/// Choices for the maximum trash size, in bytes, 0 for no limit
+const TRASH_MAX_SIZES: [u64; 6] = [
+    0,
+    1_000_000_000,
+    5_000_000_000,
+    10_000_000_000,
+    25_000_000_000,
+    50_000_000_000,
+];
+
+/// How often trashed items past the age or size limit are deleted
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Clone, Debug)]
 pub enum Mode {
     App,
@@ -100,13 +164,18 @@ pub struct Flags {
     pub config: Config,
     pub mode: Mode,
     pub locations: Vec<Location>,
+    /// Saved window to restore, see [`Config::restore_session`]
+    pub session_window_opt: Option<u64>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Action {
     About,
     AddToSidebar,
+    BrowseByDate,
+    ChangeExtension,
     Compress,
+    Computer,
     Copy,
     Cut,
     CosmicSettingsAppearance,
@@ -121,6 +190,7 @@ pub enum Action {
     ExecEntryAction(usize),
     ExtractHere,
     ExtractTo,
+    FocusDetails,
     Gallery,
     HistoryNext,
     HistoryPrevious,
@@ -136,33 +206,52 @@ pub enum Action {
     OpenInNewWindow,
     OpenItemLocation,
     OpenTerminal,
+    OpenTerminalWithFile,
     OpenWith,
+    EditTags,
     Paste,
+    PastePreview,
+    PasteWith(ConflictPolicy),
     PermanentlyDelete,
     Preview,
     Reload,
     Rename,
     RestoreFromTrash,
+    RestoreTo,
     SearchActivate,
+    SecureDelete,
+    SelectionStats,
     SelectFirst,
     SelectLast,
     SelectAll,
+    SelectAllIncludingHidden,
     SetSort(HeadingOptions, bool),
     Settings,
+    StripMetadata,
     TabClose,
     TabNew,
     TabNext,
     TabPrev,
     TabViewGrid,
     TabViewList,
+    ToggleFavorite,
+    ToggleFolderSizes,
     ToggleFoldersFirst,
+    ToggleGroupByKind,
+    ToggleKeepBoth,
+    TogglePin,
     ToggleShowHidden,
     ToggleSort(HeadingOptions),
+    ToggleSortSecondary(HeadingOptions),
+    TransformImages(ImageTransform),
+    VerifyChecksum,
     WindowClose,
     WindowNew,
     ZoomDefault,
     ZoomIn,
     ZoomOut,
+    /// Set the zoom of the view to a percentage
+    ZoomSet(u16),
     Recents,
 }
 
@@ -171,7 +260,10 @@ impl Action {
         match self {
             Action::About => Message::ToggleContextPage(ContextPage::About),
             Action::AddToSidebar => Message::AddToSidebar(entity_opt),
+            Action::BrowseByDate => Message::TabMessage(entity_opt, tab::Message::BrowseByDate),
+            Action::ChangeExtension => Message::ChangeExtension(entity_opt),
             Action::Compress => Message::Compress(entity_opt),
+            Action::Computer => Message::Computer,
             Action::Copy => Message::Copy(entity_opt),
             Action::Cut => Message::Cut(entity_opt),
             Action::CosmicSettingsAppearance => Message::CosmicSettings("appearance"),
@@ -190,6 +282,7 @@ impl Action {
             Action::ExecEntryAction(action) => {
                 Message::TabMessage(entity_opt, tab::Message::ExecEntryAction(None, *action))
             }
+            Action::FocusDetails => Message::FocusDetails,
             Action::Gallery => Message::TabMessage(entity_opt, tab::Message::GalleryToggle),
             Action::HistoryNext => Message::TabMessage(entity_opt, tab::Message::GoNext),
             Action::HistoryPrevious => Message::TabMessage(entity_opt, tab::Message::GoPrevious),
@@ -205,39 +298,61 @@ impl Action {
             Action::OpenInNewWindow => Message::OpenInNewWindow(entity_opt),
             Action::OpenItemLocation => Message::OpenItemLocation(entity_opt),
             Action::OpenTerminal => Message::OpenTerminal(entity_opt),
+            Action::OpenTerminalWithFile => Message::OpenTerminalWithFile(entity_opt),
             Action::OpenWith => Message::OpenWithDialog(entity_opt),
+            Action::EditTags => Message::EditTags(entity_opt),
             Action::Paste => Message::Paste(entity_opt),
+            Action::PastePreview => Message::PastePreview(entity_opt),
+            Action::PasteWith(conflict_policy) => Message::PasteWith(entity_opt, *conflict_policy),
             Action::PermanentlyDelete => Message::PermanentlyDelete(entity_opt),
             Action::Preview => Message::Preview(entity_opt),
             Action::Reload => Message::TabMessage(entity_opt, tab::Message::Reload),
             Action::Rename => Message::Rename(entity_opt),
             Action::RestoreFromTrash => Message::RestoreFromTrash(entity_opt),
+            Action::RestoreTo => Message::RestoreTo(entity_opt),
             Action::SearchActivate => Message::SearchActivate,
+            Action::SecureDelete => Message::SecureDelete(entity_opt),
+            Action::SelectionStats => Message::SelectionStats(entity_opt),
             Action::SelectAll => Message::TabMessage(entity_opt, tab::Message::SelectAll),
+            Action::SelectAllIncludingHidden => {
+                Message::TabMessage(entity_opt, tab::Message::SelectAllIncludingHidden)
+            }
             Action::SelectFirst => Message::TabMessage(entity_opt, tab::Message::SelectFirst),
             Action::SelectLast => Message::TabMessage(entity_opt, tab::Message::SelectLast),
             Action::SetSort(sort, dir) => {
                 Message::TabMessage(entity_opt, tab::Message::SetSort(*sort, *dir))
             }
             Action::Settings => Message::ToggleContextPage(ContextPage::Settings),
+            Action::StripMetadata => Message::StripMetadata(entity_opt),
             Action::TabClose => Message::TabClose(entity_opt),
             Action::TabNew => Message::TabNew,
             Action::TabNext => Message::TabNext,
             Action::TabPrev => Message::TabPrev,
             Action::TabViewGrid => Message::TabView(entity_opt, tab::View::Grid),
             Action::TabViewList => Message::TabView(entity_opt, tab::View::List),
+            Action::ToggleFavorite => Message::ToggleFavorite(entity_opt),
+            Action::ToggleFolderSizes => Message::ToggleFolderSizes,
             Action::ToggleFoldersFirst => Message::ToggleFoldersFirst,
+            Action::ToggleGroupByKind => Message::ToggleGroupByKind,
+            Action::ToggleKeepBoth => Message::ToggleKeepBoth(entity_opt),
+            Action::TogglePin => Message::TogglePin(entity_opt),
             Action::ToggleShowHidden => {
                 Message::TabMessage(entity_opt, tab::Message::ToggleShowHidden)
             }
             Action::ToggleSort(sort) => {
                 Message::TabMessage(entity_opt, tab::Message::ToggleSort(*sort))
             }
+            Action::ToggleSortSecondary(sort) => {
+                Message::TabMessage(entity_opt, tab::Message::ToggleSortSecondary(*sort))
+            }
+            Action::TransformImages(transform) => Message::TransformImages(entity_opt, *transform),
+            Action::VerifyChecksum => Message::VerifyChecksum(entity_opt),
             Action::WindowClose => Message::WindowClose,
             Action::WindowNew => Message::WindowNew,
             Action::ZoomDefault => Message::ZoomDefault(entity_opt),
             Action::ZoomIn => Message::ZoomIn(entity_opt),
             Action::ZoomOut => Message::ZoomOut(entity_opt),
+            Action::ZoomSet(zoom) => Message::ZoomSet(entity_opt, *zoom),
             Action::Recents => Message::Recents,
         }
     }
@@ -277,6 +392,8 @@ pub enum NavMenuAction {
     OpenInNewWindow(segmented_button::Entity),
     Preview(segmented_button::Entity),
     RemoveFromSidebar(segmented_button::Entity),
+    Unpin(segmented_button::Entity),
+    Unmount(segmented_button::Entity),
     EmptyTrash,
 }
 
@@ -293,76 +410,169 @@ impl MenuAction for NavMenuAction {
 pub enum Message {
     AddToSidebar(Option<Entity>),
     AppTheme(AppTheme),
+    AuditLog,
+    AuditLogResult(Vec<AuditEntry>),
+    BulkRename(BulkRename),
+    ChangeExtension(Option<Entity>),
+    ChangeExtensionInput(ChangeExtension),
+    /// Types found from the contents of the files whose extension is changed
+    ChangeExtensionContents(Vec<PathBuf>, HashMap<PathBuf, Mime>),
     CloseToast(widget::ToastId),
     Compress(Option<Entity>),
+    Computer,
     Config(Config),
+    Favorites,
     Copy(Option<Entity>),
     CosmicSettings(&'static str),
+    /// Files dropped on the list of the compress dialog
+    CompressDrop(Option<ClipboardPaste>),
+    CompressTo,
+    CompressToResult(DialogResult),
     CursorMoved(Point),
     Cut(Option<Entity>),
     Delete(Option<Entity>),
     DesktopConfig(DesktopConfig),
     DesktopViewOptions,
+    DetailsResizeStart,
+    DetailsTab(DetailsTab),
     DialogCancel,
     DialogComplete,
     FileDialogMessage(DialogMessage),
+    FocusDetails,
     DialogPush(DialogPage),
     DialogUpdate(DialogPage),
     DialogUpdateComplete(DialogPage),
+    EjectBusyProcesses(String, Vec<BlockingProcess>),
+    EjectOpenLocation(PathBuf),
+    /// Try ejecting the drive in the eject busy dialog again, forcing it if set
+    EjectRetry(bool),
     ExtractHere(Option<Entity>),
     ExtractTo(Option<Entity>),
     ExtractToResult(DialogResult),
     #[cfg(all(feature = "desktop", feature = "wayland"))]
     Focused(window::Id),
+    /// Pin or unpin a folder in the frequently used folders bar
+    FrequentFolderPin(PathBuf),
+    /// Forget the visits of a folder, removing it from the frequently used folders bar
+    FrequentFolderRemove(PathBuf),
+    HiddenPatternsInput(String),
+    HiddenPatternsSubmit,
     Key(Modifiers, Key, Option<SmolStr>),
     LaunchUrl(String),
     MaybeExit,
     ModifiersChanged(Modifiers),
     MounterItems(MounterKey, MounterItems),
-    MountResult(MounterKey, MounterItem, Result<bool, String>),
+    MountCancel,
+    MimeAppsSearch(String),
+    MimeCategoryDefault(AppCategory, usize),
+    /// Set the default application of a MIME type, by its index in the applications for the type
+    MimeDefault(Mime, usize),
+    MimeDefaultReset(Mime),
+    MountDismiss,
+    MountResult(MounterKey, MounterItem, Result<bool, MounterError>),
+    MountRetry,
     NavBarClose(Entity),
     NavBarContext(Entity),
     NavMenuAction(NavMenuAction),
     NetworkAuth(MounterKey, String, MounterAuth, mpsc::Sender<MounterAuth>),
+    NetworkChanged(Vec<String>),
     NetworkDriveInput(String),
     NetworkDriveSubmit,
-    NetworkResult(MounterKey, String, Result<bool, String>),
+    NetworkResult(MounterKey, String, Result<bool, MounterError>),
+    NewTabConfig(NewTabConfig),
     NewItem(Option<Entity>, bool),
     #[cfg(feature = "notify")]
     Notification(Arc<Mutex<notify_rust::NotificationHandle>>),
     NotifyEvents(Vec<DebouncedEvent>),
     NotifyWatcher(WatcherWrapper),
+    OfflineRetry,
     OpenTerminal(Option<Entity>),
+    OpenTerminalWithFile(Option<Entity>),
+    EditTags(Option<Entity>),
     OpenInNewTab(Option<Entity>),
     OpenInNewWindow(Option<Entity>),
     OpenItemLocation(Option<Entity>),
     OpenWithBrowse,
+    OpenWithCustomCommand(String),
     OpenWithDialog(Option<Entity>),
+    OpenWithRemember(bool),
+    OpenWithSearch(String),
     OpenWithSelection(usize),
+    OpenWithShowAll,
     #[cfg(all(feature = "desktop", feature = "wayland"))]
     Overlap(OverlapNotifyEvent, window::Id),
     Paste(Option<Entity>),
-    PasteContents(PathBuf, ClipboardPaste),
+    /// Show the changes a paste would make before pasting
+    PastePreview(Option<Entity>),
+    /// Paste, handling items that already exist by this policy instead of the default
+    PasteWith(Option<Entity>, ConflictPolicy),
+    PasteContents(PathBuf, ClipboardPaste, Option<ConflictPolicy>),
+    /// Paste into a network folder whose files a mounter reads itself
+    PasteNetwork(String, ClipboardPaste),
+    PastePreviewContents(PathBuf, ClipboardPaste),
     PendingCancel(u64),
     PendingCancelAll,
     PendingComplete(u64, OperationSelection),
     PendingDismiss,
     PendingError(u64, OperationError),
+    /// Open the destination of an operation in a new tab when it finishes
+    PendingOpenDestination(u64, bool),
     PendingPause(u64, bool),
     PendingPauseAll(bool),
+    /// Move a queued operation earlier or later
+    PendingReorder(u64, bool),
     PermanentlyDelete(Option<Entity>),
+    PermanentlyDeleteStats(Vec<PathBuf>, tab::SelectionStats),
+    PowerSaving(bool),
     Preview(Option<Entity>),
+    /// Show the changes of the operations before starting them
+    PreviewChanges(Vec<Operation>),
+    PreviewChangesResult(Result<Vec<PlannedChange>, String>),
+    /// Delete trashed items past the age or size limit
+    PurgeTrash,
+    PurgeTrashItems(Vec<TrashItem>),
     RescanTrash,
     Rename(Option<Entity>),
     ReplaceResult(ReplaceResult),
+    /// Left mouse button released, ending a resize of the details pane or list columns
+    ResizeEnd,
+    /// Choose a folder to restore a trashed item into, instead of replacing an item
+    RestoreElsewhere,
+    RestoreElsewhereResult(DialogResult),
     RestoreFromTrash(Option<Entity>),
+    /// Restore the selected trashed items into a folder chosen in a dialog
+    RestoreTo(Option<Entity>),
+    RestoreToResult(DialogResult),
+    /// Save the visits of the frequently used folders bar
+    SaveFolderVisits,
+    /// Save the tabs of this window, with true if the window is closing
+    SaveSession(bool),
     ScrollTab(i16),
     SearchActivate,
     SearchClear,
     SearchInput(String),
+    SecureDelete(Option<Entity>),
+    SelectionStats(Option<Entity>),
+    SelectionStatsResult(tab::SelectionStats),
+    SetAuditLog(bool),
+    SetConflictPolicy(ConflictPolicy),
+    SetDetailsPosition(DetailsPosition),
+    SetReduceBackgroundWork(ReduceBackgroundWork),
+    SetFolderColumns(PathBuf, Vec<HeadingOptions>),
+    SetFolderColumnWidths(PathBuf, ColumnWidths),
+    SetOpenDestination(bool),
+    SetRestoreSession(bool),
+    SetExtractOnOpen(Vec<String>),
     SetShowDetails(bool),
+    SetShowFrequentFolders(bool),
+    SetToolbar(Vec<ToolbarButton>),
+    SetTrashMaxDays(u32),
+    SetTrashMaxSize(u64),
     SetTypeToSearch(TypeToSearch),
+    SetWriteThroughRemovable(bool),
+    StripMetadata(Option<Entity>),
     SystemThemeModeChange(cosmic_theme::ThemeMode),
+    TrashSummary(tab::TrashSummary),
     Size(Size),
     TabActivate(Entity),
     TabNext,
@@ -378,20 +588,42 @@ pub enum Message {
         Vec<tab::Item>,
         Option<Vec<PathBuf>>,
     ),
+    TabRescanRemote(
+        Entity,
+        Location,
+        Result<(Option<tab::Item>, Vec<tab::Item>), String>,
+        Option<Vec<PathBuf>>,
+    ),
     TabView(Option<Entity>, tab::View),
     TimeConfigChange(TimeConfig),
     ToggleContextPage(ContextPage),
+    ToggleFavorite(Option<Entity>),
     ToggleFoldersFirst,
+    ToggleGroupByKind,
+    ToggleKeepBoth(Option<Entity>),
+    ToggleOperationQueue,
+    TogglePin(Option<Entity>),
+    TransformImages(Option<Entity>, ImageTransform),
     Undo(usize),
     UndoTrash(widget::ToastId, Arc<[PathBuf]>),
     UndoTrashStart(Vec<TrashItem>),
+    UnmountResult(MounterKey, MounterItem, Result<bool, MounterError>),
+    VerifyChecksum(Option<Entity>),
+    VerifyChecksumFile,
+    VerifyChecksumFileResult(DialogResult),
+    VerifyChecksumInput(String),
+    VerifyChecksumResult(String, Result<Option<ChecksumAlgorithm>, String>),
+    VerifyChecksumStart,
     WindowClose,
     WindowCloseRequested(window::Id),
     WindowNew,
+    /// Start a window process with these arguments and an activation token
+    WindowSpawn(Vec<OsString>, Option<String>),
     WindowUnfocus,
     ZoomDefault(Option<Entity>),
     ZoomIn(Option<Entity>),
     ZoomOut(Option<Entity>),
+    ZoomSet(Option<Entity>, u16),
     DndHoverLocTimeout(Location),
     DndHoverTabTimeout(Entity),
     DndEnterNav(Entity),
@@ -413,6 +645,8 @@ pub enum Message {
 pub enum ContextPage {
     About,
     EditHistory,
+    /// Default applications of MIME types, opened from the settings
+    MimeApps,
     NetworkDrive,
     Preview(Option<Entity>, PreviewKind),
     Settings,
@@ -420,24 +654,117 @@ pub enum ContextPage {
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub enum ArchiveType {
+    SevenZ,
     Tgz,
+    #[cfg(feature = "xz2")]
+    TarXz,
+    TarZst,
     #[default]
     Zip,
 }
 
 impl ArchiveType {
     pub fn all() -> &'static [Self] {
-        &[Self::Tgz, Self::Zip]
+        &[
+            Self::Tgz,
+            #[cfg(feature = "xz2")]
+            Self::TarXz,
+            Self::TarZst,
+            Self::Zip,
+            Self::SevenZ,
+        ]
     }
 
     pub fn extension(&self) -> &str {
         match self {
+            ArchiveType::SevenZ => ".7z",
             ArchiveType::Tgz => ".tgz",
+            #[cfg(feature = "xz2")]
+            ArchiveType::TarXz => ".tar.xz",
+            ArchiveType::TarZst => ".tar.zst",
             ArchiveType::Zip => ".zip",
         }
     }
+
+    /// Only zip and 7z archives are encrypted with a password
+    pub fn has_password(&self) -> bool {
+        matches!(self, ArchiveType::SevenZ | ArchiveType::Zip)
+    }
+
+    /// Archive type of an existing archive, based on its file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        #[cfg(feature = "xz2")]
+        if name.ends_with(".txz") || name.ends_with(".tar.xz") {
+            return Some(ArchiveType::TarXz);
+        }
+        if name.ends_with(".tgz") || name.ends_with(".tar.gz") {
+            Some(ArchiveType::Tgz)
+        } else if name.ends_with(".tzst") || name.ends_with(".tar.zst") {
+            Some(ArchiveType::TarZst)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveType::Zip)
+        } else if name.ends_with(".7z") {
+            Some(ArchiveType::SevenZ)
+        } else {
+            None
+        }
+    }
+}
+
+/// Compression level of a new archive, translated to the range of each format
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum CompressionLevel {
+    Fast,
+    #[default]
+    Normal,
+    Best,
+}
+
+impl CompressionLevel {
+    pub fn all() -> &'static [Self] {
+        &[Self::Fast, Self::Normal, Self::Best]
+    }
+
+    /// Level of deflate and xz compression, from 1 to 9
+    pub fn level(self) -> u32 {
+        match self {
+            Self::Fast => 1,
+            Self::Normal => 6,
+            Self::Best => 9,
+        }
+    }
+
+    /// Level of zstd compression, which goes up to 22 and defaults to 3
+    pub fn zstd_level(self) -> i32 {
+        match self {
+            Self::Fast => 1,
+            Self::Normal => 3,
+            Self::Best => 19,
+        }
+    }
 }
 
+/// Archive MIME types supported by [`Operation::Extract`]
+const EXTRACT_MIMES: &[&str] = &[
+    "application/gzip",
+    "application/x-compressed-tar",
+    "application/x-tar",
+    "application/zip",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip-compressed-tar",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip2",
+    #[cfg(feature = "bzip2")]
+    "application/x-bzip2-compressed-tar",
+    #[cfg(feature = "xz2")]
+    "application/x-xz",
+    #[cfg(feature = "xz2")]
+    "application/x-xz-compressed-tar",
+];
+
 impl AsRef<str> for ArchiveType {
     fn as_ref(&self) -> &str {
         self.extension()
@@ -446,48 +773,99 @@ impl AsRef<str> for ArchiveType {
 
 #[derive(Clone, Debug)]
 pub enum DialogPage {
+    AuditLog {
+        entries_opt: Option<Vec<AuditEntry>>,
+    },
+    BulkRename {
+        paths: Vec<PathBuf>,
+        rename: BulkRename,
+        preview: Result<Vec<RenamePreview>, String>,
+    },
+    ChangeExtension {
+        paths: Vec<PathBuf>,
+        change: ChangeExtension,
+        preview: Vec<RenamePreview>,
+        /// Types found from the contents, once the files have been read
+        contents_opt: Option<HashMap<PathBuf, Mime>>,
+        /// Files whose contents do not fit the new extension
+        mismatches: HashMap<PathBuf, Mime>,
+    },
     Compress {
         paths: Vec<PathBuf>,
         to: PathBuf,
         name: String,
         archive_type: ArchiveType,
+        level: CompressionLevel,
         password: Option<String>,
     },
-    EmptyTrash,
+    EditTags {
+        path: PathBuf,
+        tags: Vec<ColorTag>,
+    },
+    /// Drive that could not be ejected because files on it are open
+    EjectBusy {
+        mounter_key: MounterKey,
+        item: MounterItem,
+        /// Processes using the drive, once they have been found
+        processes_opt: Option<Vec<BlockingProcess>>,
+        ejecting: bool,
+    },
+    EmptyTrash {
+        summary_opt: Option<tab::TrashSummary>,
+        older_only: bool,
+    },
     FailedOperation(u64),
     ExtractPassword {
         id: u64,
         password: String,
     },
-    MountError {
-        mounter_key: MounterKey,
-        item: MounterItem,
-        error: String,
-    },
     NetworkAuth {
         mounter_key: MounterKey,
         uri: String,
         auth: MounterAuth,
         auth_tx: mpsc::Sender<MounterAuth>,
     },
-    NetworkError {
-        mounter_key: MounterKey,
-        uri: String,
-        error: String,
+    /// Confirm deleting items on a network location, which has no trash
+    NetworkDelete {
+        uris: Vec<String>,
+    },
+    NetworkRename {
+        from: String,
+        name: String,
+        dir: bool,
     },
     NewItem {
         parent: PathBuf,
         name: String,
         dir: bool,
     },
+    OpenAll {
+        files: Vec<PathBuf>,
+        folders: Vec<Location>,
+        launches: usize,
+    },
     OpenWith {
-        path: PathBuf,
+        paths: Vec<PathBuf>,
         mime: mime_guess::Mime,
         selected: usize,
         store_opt: Option<MimeApp>,
+        /// Set the chosen application as the default for the MIME type
+        remember: bool,
+        /// Show applications that are not known to open files of the same category
+        show_all: bool,
+        /// Filter for application names, which searches all applications
+        search: String,
+        /// Command line used instead of the selected application when not empty
+        custom_command: String,
     },
     PermanentlyDelete {
         paths: Vec<PathBuf>,
+        /// Number and size of the items, including the contents of folders
+        stats_opt: Option<tab::SelectionStats>,
+    },
+    PreviewChanges {
+        operations: Vec<Operation>,
+        changes_opt: Option<Result<Vec<PlannedChange>, String>>,
     },
     RenameItem {
         from: PathBuf,
@@ -500,11 +878,29 @@ pub enum DialogPage {
         to: tab::Item,
         multiple: bool,
         apply_to_all: bool,
+        /// New name for the item being copied or moved
+        rename: String,
+        /// The item is being restored from the trash, and can be restored to another folder
+        restore: bool,
         tx: mpsc::Sender<ReplaceResult>,
     },
+    /// Confirm overwriting files before deleting them
+    SecureDelete {
+        paths: Vec<PathBuf>,
+    },
+    SelectionStats {
+        stats_opt: Option<tab::SelectionStats>,
+    },
     SetExecutableAndLaunch {
         path: PathBuf,
     },
+    VerifyChecksum {
+        path: PathBuf,
+        expected: String,
+        /// Running verification, which is cancelled when the dialog is closed
+        controller_opt: Option<Controller>,
+        result_opt: Option<Result<Option<ChecksumAlgorithm>, String>>,
+    },
     FavoritePathError {
         path: PathBuf,
         entity: Entity,
@@ -513,15 +909,23 @@ pub enum DialogPage {
 
 pub struct FavoriteIndex(usize);
 
+/// Index of a sidebar item in [`App::temporary_pins`]
+pub struct TemporaryPin(usize);
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum MimeAppMatch {
     Exact,
     Related,
+    /// Opens other types with the same top-level type, like `image`
+    Category,
     Other,
 }
 
 pub struct MounterData(MounterKey, MounterItem);
 
+/// Marks the sidebar item that opens the add network drive page
+pub struct AddNetworkDriveData;
+
 #[derive(Clone, Debug)]
 pub enum WindowKind {
     Desktop(Entity),
@@ -552,6 +956,36 @@ impl PartialEq for WatcherWrapper {
     }
 }
 
+/// What is being mounted, used to retry a failed mount
+#[derive(Clone, Debug)]
+enum MountTarget {
+    Item(MounterItem),
+    Uri(String),
+}
+
+impl MountTarget {
+    fn name(&self) -> String {
+        match self {
+            Self::Item(item) => item.name(),
+            Self::Uri(uri) => uri.clone(),
+        }
+    }
+}
+
+/// Mount shown inline above the tab while connecting, or after it failed
+struct MountProgress {
+    mounter_key: MounterKey,
+    target: MountTarget,
+    error_opt: Option<MounterError>,
+}
+
+/// Details pane resize in progress, started by pressing on the edge of the pane
+struct DetailsResize {
+    start_x: f32,
+    start_width: u16,
+    width: u16,
+}
+
 /// The [`App`] stores application-specific state.
 pub struct App {
     core: Core,
@@ -562,15 +996,37 @@ pub struct App {
     config: Config,
     mode: Mode,
     app_themes: Vec<String>,
+    compression_level_names: Vec<String>,
+    trash_max_days_names: Vec<String>,
+    trash_max_size_names: Vec<String>,
     compio_tx: mpsc::Sender<Pin<Box<dyn Future<Output = ()> + Send>>>,
     context_page: ContextPage,
+    cursor_position: Point,
+    details_resize_opt: Option<DetailsResize>,
+    /// Identifies this window in the saved session
+    session_id: u64,
+    /// Keyboard focus is in the details pane, where keys control the media preview
+    details_focused: bool,
     dialog_pages: VecDeque<DialogPage>,
     dialog_text_input: widget::Id,
+    /// Folders shown in the frequently used folders bar
+    frequent_folders: Vec<PathBuf>,
+    /// Folders opened in tabs while the bar is shown, kept in a state file
+    folder_visits: BTreeMap<PathBuf, FolderVisits>,
+    /// A [`Message::SaveFolderVisits`] is already waiting
+    folder_visits_save_pending: bool,
+    /// Paths pinned to the sidebar until the app is closed, which are not saved in the config
+    temporary_pins: Vec<PathBuf>,
+    hidden_patterns_input: String,
     key_binds: HashMap<KeyBind, Action>,
+    /// Last listing of each remote location, shown while the share is unreachable
+    listing_cache: HashMap<Location, (Option<tab::Item>, Vec<tab::Item>)>,
     margin: HashMap<window::Id, (f32, f32, f32, f32)>,
     mime_app_cache: MimeAppCache,
+    mime_apps_search: String,
     modifiers: Modifiers,
     mounter_items: HashMap<MounterKey, MounterItems>,
+    mount_progress_opt: Option<MountProgress>,
     network_drive_connecting: Option<(MounterKey, String)>,
     network_drive_input: String,
     #[cfg(feature = "notify")]
@@ -578,6 +1034,12 @@ pub struct App {
     overlap: HashMap<String, (window::Id, Rectangle)>,
     pending_operation_id: u64,
     pending_operations: BTreeMap<u64, (Operation, Controller)>,
+    /// Pending operations that open their destination when they finish
+    open_destination_ids: HashSet<u64>,
+    operation_queue: OperationQueue,
+    show_operation_queue: bool,
+    /// Running on battery or in power saver mode
+    power_saving: bool,
     progress_operations: BTreeSet<u64>,
     complete_operations: BTreeMap<u64, Operation>,
     failed_operations: BTreeMap<u64, (Operation, Controller, String)>,
@@ -597,24 +1059,227 @@ pub struct App {
     tab_drag_id: DragId,
     auto_scroll_speed: Option<i16>,
     file_dialog_opt: Option<Dialog<Message>>,
+    /// Trashed items restored into the folder chosen in the file dialog
+    restore_to_items: Vec<trash::TrashItem>,
+}
+
+/// Copy or move of the clipboard contents into `to`, or `None` if there is nothing to paste
+fn paste_operation(to: PathBuf, mut contents: ClipboardPaste) -> Option<Operation> {
+    // Items on network locations are copied by the mounter that reads them
+    contents
+        .uris
+        .retain(|uri| mounter::network_files(uri).is_some());
+    if !contents.uris.is_empty() {
+        let to = url::Url::from_file_path(&to).ok()?;
+        return network_paste_operation(to.into(), contents);
+    }
+    contents.paths.retain(|p| p != &to);
+    if contents.paths.is_empty() {
+        return None;
+    }
+    Some(match contents.kind {
+        ClipboardKind::Copy => Operation::Copy {
+            paths: contents.paths,
+            to,
+        },
+        ClipboardKind::Cut { is_dnd } => Operation::Move {
+            paths: contents.paths,
+            to,
+            cross_device_copy: is_dnd,
+        },
+    })
+}
+
+/// Copy or move of the clipboard contents into the network folder `to`, or `None` if there is
+/// nothing to paste. Local items are copied as `file://` URIs.
+fn network_paste_operation(to: String, contents: ClipboardPaste) -> Option<Operation> {
+    let mut uris = contents.uris;
+    uris.extend(
+        contents
+            .paths
+            .iter()
+            .filter_map(|path| url::Url::from_file_path(path).ok())
+            .map(String::from),
+    );
+    uris.retain(|uri| uri.trim_end_matches('/') != to.trim_end_matches('/'));
+    if uris.is_empty() {
+        return None;
+    }
+    Some(match contents.kind {
+        ClipboardKind::Copy => Operation::NetworkCopy { uris, to },
+        ClipboardKind::Cut { .. } => Operation::NetworkMove { uris, to },
+    })
+}
+
+/// Rename dialog for a single item
+fn rename_dialog(path: PathBuf) -> Option<DialogPage> {
+    let parent = path.parent()?.to_path_buf();
+    let name = tab::escaped_file_name(path.file_name()?);
+    let dir = path.is_dir();
+    Some(DialogPage::RenameItem {
+        from: path,
+        parent,
+        name,
+        dir,
+    })
+}
+
+/// Renames of the items whose names are changed in a bulk rename preview
+fn rename_operations(previews: &[RenamePreview]) -> Vec<Operation> {
+    previews
+        .iter()
+        .filter(|preview| preview.changed())
+        .map(|preview| Operation::Rename {
+            from: preview.from.clone(),
+            to: preview.to.clone(),
+        })
+        .collect()
+}
+
+/// Rename done by the rename dialog, or `None` if the name was not changed. Names are edited
+/// escaped, so the new name is unescaped and an unchanged name is kept as it is.
+fn rename_operation(from: &Path, parent: &Path, name: &str) -> Option<Operation> {
+    if from.file_name().map(tab::escaped_file_name).as_deref() == Some(name) {
+        return None;
+    }
+    let to = parent.join(tab::unescaped_file_name(name));
+    Some(Operation::Rename {
+        from: from.to_path_buf(),
+        to,
+    })
 }
 
 impl App {
-    fn open_file(&mut self, paths: &[impl AsRef<Path>]) {
+    /// Open a terminal in `path`, passing `file_opt` to the shell in the `FILE` environment
+    /// variable so it can be used in commands
+    fn open_terminal(&self, path: &Path, file_opt: Option<&Path>) {
+        let Some(terminal) = self.mime_app_cache.terminal() else {
+            log::warn!("no terminal found to open {:?}", path);
+            return;
+        };
+        if let Some(mut command) = terminal
+            .command::<&str>(&[])
+            .and_then(|v| v.into_iter().next())
+        {
+            command.current_dir(path);
+            if let Some(file) = file_opt {
+                command.env("FILE", file);
+            }
+            if let Err(err) = spawn_detached(&mut command) {
+                log::warn!(
+                    "failed to open {:?} with terminal {:?}: {}",
+                    path,
+                    terminal.id,
+                    err
+                )
+            }
+        } else {
+            log::warn!("failed to get command for {:?}", terminal.id);
+        }
+    }
+
+    /// Group paths by MIME type, then merge groups that share a default application so that each
+    /// application is launched once with all of its paths
+    fn open_groups(&self, paths: &[impl AsRef<Path>]) -> Vec<(Mime, Vec<PathBuf>)> {
         // Associate all paths to its MIME type
         // This allows handling paths as groups if possible, such as launching a single video
         // player that is passed every path.
         let mut groups: HashMap<Mime, Vec<PathBuf>> = HashMap::new();
         for (mime, path) in paths.iter().map(|path| {
             (
-                mime_icon::mime_for_path(path, None, false),
+                mime_icon::mime_for_path_sniffed(path.as_ref()),
                 path.as_ref().to_owned(),
             )
         }) {
             groups.entry(mime).or_default().push(path);
         }
 
-        'outer: for (mime, paths) in groups {
+        let mut app_groups: HashMap<String, usize> = HashMap::new();
+        let mut merged: Vec<(Mime, Vec<PathBuf>)> = Vec::with_capacity(groups.len());
+        for (mime, paths) in groups {
+            match self.mime_app_cache.get(&mime).first() {
+                Some(app) => match app_groups.get(&app.id) {
+                    Some(&i) => merged[i].1.extend(paths),
+                    None => {
+                        app_groups.insert(app.id.clone(), merged.len());
+                        merged.push((mime, paths));
+                    }
+                },
+                None => merged.push((mime, paths)),
+            }
+        }
+        merged
+    }
+
+    /// Number of applications or windows that [`Self::open_file`] would launch for `paths`
+    fn open_launches(&self, paths: &[impl AsRef<Path>]) -> usize {
+        self.open_groups(paths)
+            .into_iter()
+            .map(|(mime, paths)| {
+                if mime != "application/x-desktop"
+                    && mime != "application/x-executable"
+                    && mime != "application/vnd.appimage"
+                    && !self.mime_app_cache.get(&mime).is_empty()
+                {
+                    1
+                } else {
+                    paths.len()
+                }
+            })
+            .sum()
+    }
+
+    /// Open files with their default applications and folders in new tabs, asking first if this
+    /// would launch many applications or windows
+    fn open_all(&mut self, files: Vec<PathBuf>, folders: Vec<Location>) -> Task<Message> {
+        let launches = self.open_launches(&files) + folders.len();
+        if launches > OPEN_ALL_CONFIRM_LAUNCHES {
+            self.dialog_pages.push_back(DialogPage::OpenAll {
+                files,
+                folders,
+                launches,
+            });
+            return Task::none();
+        }
+        self.open_files_and_folders(files, folders)
+    }
+
+    fn open_files_and_folders(
+        &mut self,
+        files: Vec<PathBuf>,
+        folders: Vec<Location>,
+    ) -> Task<Message> {
+        let mut commands = vec![self.open_file(&files)];
+        for location in folders {
+            commands.push(self.open_tab(location, false, None));
+        }
+        Task::batch(commands)
+    }
+
+    fn open_file(&mut self, paths: &[impl AsRef<Path>]) -> Task<Message> {
+        // Archives of types set to extract on open are extracted next to themselves instead
+        let mut extract_groups: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+        let mut open_paths = Vec::with_capacity(paths.len());
+        for path in paths.iter().map(AsRef::as_ref) {
+            let mime = mime_icon::mime_for_path_sniffed(path);
+            match path.parent() {
+                Some(parent)
+                    if self
+                        .config
+                        .extract_on_open
+                        .iter()
+                        .any(|essence| essence == mime.essence_str()) =>
+                {
+                    extract_groups
+                        .entry(parent.to_path_buf())
+                        .or_default()
+                        .push(path.to_path_buf());
+                }
+                _ => open_paths.push(path.to_path_buf()),
+            }
+        }
+
+        'outer: for (mime, paths) in self.open_groups(&open_paths) {
             log::debug!("Attempting to launch app\n\tfor: {mime}\n\twith: {paths:?}");
 
             // First launch apps that can be launched directly
@@ -660,6 +1325,13 @@ impl App {
                 }
             }
 
+            // Ask which application to use if none is associated with the type
+            if !self.mime_app_cache.apps().is_empty() {
+                let page = self.open_with_page(paths, mime, true);
+                self.dialog_pages.push_back(page);
+                continue;
+            }
+
             // Fall back to using open crate
             for path in paths {
                 match open::that_detached(&path) {
@@ -677,6 +1349,19 @@ impl App {
                 }
             }
         }
+
+        Task::batch(
+            extract_groups
+                .into_iter()
+                .map(|(to, paths)| {
+                    self.operation(Operation::Extract {
+                        paths,
+                        to,
+                        password: None,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )
     }
 
     fn launch_desktop_entries(paths: &[impl AsRef<Path>]) {
@@ -871,6 +1556,10 @@ impl App {
         selection_paths: Option<Vec<PathBuf>>,
     ) -> (Entity, Task<Message>) {
         let mut tab = Tab::new(location.clone(), self.config.tab);
+        tab.set_hidden_patterns(&self.config.hidden_patterns);
+        tab.set_folder_columns(&self.config.folder_columns);
+        tab.set_folder_column_widths(&self.config.folder_column_widths);
+        tab.set_starred(&self.config.starred);
         tab.mode = match self.mode {
             Mode::App => tab::Mode::App,
             Mode::Desktop => {
@@ -901,6 +1590,82 @@ impl App {
         )
     }
 
+    /// Open the tabs of a saved window
+    fn restore_session_window(&mut self, window: &SessionWindow) -> Task<Message> {
+        let mut commands = Vec::with_capacity(window.tabs.len());
+        for (i, session_tab) in window.tabs.iter().enumerate() {
+            if let SessionLocation::Path(path) = &session_tab.location {
+                if !path.is_dir() {
+                    log::info!("not restoring missing folder {:?}", path);
+                    continue;
+                }
+            }
+            let (entity, command) =
+                self.open_tab_entity(session_tab.location.location(), i == window.active, None);
+            if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
+                tab.config.view = session_tab.view;
+                tab.sort_name = session_tab.sort_name;
+                tab.sort_direction = session_tab.sort_direction;
+                tab.set_scroll_restore(AbsoluteOffset {
+                    x: 0.0,
+                    y: session_tab.scroll as f32,
+                });
+            }
+            commands.push(command);
+        }
+        Task::batch(commands)
+    }
+
+    /// Saved session with the tabs of this window updated, or removed if the window is closing
+    /// while other windows are still open
+    fn session(&self, closing: bool) -> Vec<SessionWindow> {
+        let mut session = self.config.session.clone();
+        let index_opt = session
+            .iter()
+            .position(|window| window.id == self.session_id);
+        if closing
+            && session
+                .iter()
+                .any(|window| window.id != self.session_id && window.running())
+        {
+            if let Some(index) = index_opt {
+                session.remove(index);
+            }
+            return session;
+        }
+
+        let active = self.tab_model.active();
+        let mut window = SessionWindow {
+            id: self.session_id,
+            pid: process::id(),
+            tabs: Vec::new(),
+            active: 0,
+        };
+        for entity in self.tab_model.iter() {
+            let Some(tab) = self.tab_model.data::<Tab>(entity) else {
+                continue;
+            };
+            let Some(location) = SessionLocation::from_location(&tab.location) else {
+                continue;
+            };
+            if entity == active {
+                window.active = window.tabs.len();
+            }
+            window.tabs.push(SessionTab {
+                location,
+                view: tab.config.view,
+                sort_name: tab.sort_name,
+                sort_direction: tab.sort_direction,
+                scroll: tab.scroll_opt.map_or(0, |offset| offset.y.max(0.0) as u32),
+            });
+        }
+        match index_opt {
+            Some(index) => session[index] = window,
+            None => session.push(window),
+        }
+        session
+    }
+
     fn open_tab(
         &mut self,
         location: Location,
@@ -935,26 +1700,118 @@ impl App {
             }
         }
 
+        let mut commands = Vec::with_capacity(2);
         if !dialog_paths.is_empty() {
-            self.dialog_pages.push_back(DialogPage::PermanentlyDelete {
-                paths: dialog_paths,
-            });
+            commands.push(self.permanently_delete_dialog(dialog_paths));
         }
-
         if !trash_paths.is_empty() {
-            self.operation(Operation::Delete { paths: trash_paths })
-        } else {
-            Task::none()
+            commands.push(self.operation(Operation::Delete { paths: trash_paths }));
+        }
+        Task::batch(commands)
+    }
+
+    /// Ask for confirmation before deleting items without moving them to the trash, listing the
+    /// items and their total size once the selected folders have been walked
+    fn permanently_delete_dialog(&mut self, paths: Vec<PathBuf>) -> Task<Message> {
+        self.dialog_pages.push_back(DialogPage::PermanentlyDelete {
+            paths: paths.clone(),
+            stats_opt: None,
+        });
+        Task::perform(
+            async move {
+                let stats_paths = paths.clone();
+                match tokio::task::spawn_blocking(move || tab::selection_stats(&stats_paths)).await
+                {
+                    Ok(stats) => cosmic::action::app(Message::PermanentlyDeleteStats(paths, stats)),
+                    Err(err) => {
+                        log::warn!("failed to summarize items to delete: {}", err);
+                        cosmic::action::none()
+                    }
+                }
+            },
+            |x| x,
+        )
+    }
+
+    /// Check if an operation writes to a mounted drive that can be ejected, network mounts are
+    /// not removable
+    fn is_removable(&self, operation: &Operation) -> bool {
+        let to = match operation {
+            Operation::Copy { to, .. } | Operation::Move { to, .. } => to,
+            _ => return false,
+        };
+        self.mounter_items.values().any(|items| {
+            items.iter().any(|item| {
+                item.is_mounted()
+                    && item.can_eject()
+                    && item
+                        .path()
+                        .map_or(false, |mount_path| to.starts_with(mount_path))
+            })
+        })
+    }
+
+    /// Record a finished destructive operation, if the audit log is enabled
+    #[must_use]
+    fn audit_log(
+        &self,
+        operation: &Operation,
+        result: Result<&OperationSelection, String>,
+    ) -> Task<Message> {
+        if !self.config.audit_log {
+            return Task::none();
         }
+        let Some(entry) = AuditEntry::new(operation, result) else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(move || audit_log::append(&entry)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => log::warn!("failed to write audit log: {}", err),
+                    Err(err) => log::warn!("failed to write audit log: {}", err),
+                }
+                cosmic::action::none()
+            },
+            |x| x,
+        )
     }
 
     #[must_use]
     fn operation(&mut self, operation: Operation) -> Task<Message> {
+        self.operation_with_conflict_policy(operation, None)
+    }
+
+    /// Start an operation, with copies and moves handling items that already exist by
+    /// `conflict_policy_opt` instead of the policy of the destination folder or the default
+    fn operation_with_conflict_policy(
+        &mut self,
+        operation: Operation,
+        conflict_policy_opt: Option<ConflictPolicy>,
+    ) -> Task<Message> {
         let id = self.pending_operation_id;
         let controller = Controller::default();
         let compio_tx = self.compio_tx.clone();
+        let write_through = self.config.write_through_removable && self.is_removable(&operation);
+        let conflict_policy = match &operation {
+            Operation::Copy { to, .. } | Operation::Move { to, .. } => conflict_policy_opt
+                .unwrap_or_else(|| {
+                    if self.config.keep_both_folders.contains(to) {
+                        ConflictPolicy::KeepBoth
+                    } else {
+                        self.config.conflict_policy
+                    }
+                }),
+            _ => ConflictPolicy::Ask,
+        };
 
         self.pending_operation_id += 1;
+        if self.config.open_destination && operation.destination().is_some() {
+            self.open_destination_ids.insert(id);
+        }
+        if operation.queued() && !self.operation_queue.push(id) {
+            controller.queue();
+        }
         if operation.show_progress_notification() {
             self.progress_operations.insert(id);
         }
@@ -973,7 +1830,10 @@ impl App {
 
                 _ = compio_tx
                     .send(Box::pin(async move {
-                        let msg = match operation.perform(&msg_tx_clone, controller).await {
+                        let msg = match operation
+                            .perform(&msg_tx_clone, controller, write_through, conflict_policy)
+                            .await
+                        {
                             Ok(result_paths) => Message::PendingComplete(id, result_paths),
                             Err(err) => Message::PendingError(id, err),
                         };
@@ -990,28 +1850,129 @@ impl App {
         .map(cosmic::Action::App)
     }
 
-    fn remove_window(&mut self, id: &window::Id) {
-        if let Some(WindowKind::Desktop(entity)) = self.windows.remove(id) {
-            // Remove the tab from the tab model
-            self.tab_model.remove(entity);
-        }
-    }
-
-    #[must_use]
-    fn rescan_operation_selection(&mut self, op_sel: OperationSelection) -> Task<Message> {
-        log::info!("rescan_operation_selection {:?}", op_sel);
-        let entity = self.tab_model.active();
-        let Some(tab) = self.tab_model.data::<Tab>(entity) else {
+    /// Paste the clipboard into the folder of the tab, with `on_contents` giving the message
+    /// for the destination and the clipboard contents
+    fn paste(
+        &mut self,
+        entity_opt: Option<Entity>,
+        on_contents: impl Fn(PathBuf, ClipboardPaste) -> Message + Send + 'static,
+    ) -> Task<Message> {
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        let Some(to) = self
+            .tab_model
+            .data::<Tab>(entity)
+            .and_then(|tab| tab.location.path_opt())
+            .cloned()
+        else {
             return Task::none();
         };
-        let Some(items) = tab.items_opt() else {
-            return Task::none();
+        clipboard::read_data::<ClipboardPaste>().map(move |contents_opt| match contents_opt {
+            Some(contents) => cosmic::action::app(on_contents(to.clone(), contents)),
+            None => cosmic::action::none(),
+        })
+    }
+
+    /// Paste into a network folder whose files a mounter reads itself, or `None` if the tab does
+    /// not show one
+    fn paste_network(&self, entity_opt: Option<Entity>) -> Option<Task<Message>> {
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        let to = match &self.tab_model.data::<Tab>(entity)?.location {
+            Location::Network(uri, _) if mounter::network_files(uri).is_some() => uri.clone(),
+            _ => return None,
         };
-        for item in items.iter() {
-            if item.selected {
-                if let Some(path) = item.path_opt() {
-                    if op_sel.selected.contains(path) || op_sel.ignored.contains(path) {
-                        // Ignore if path in selected or ignored paths
+        Some(
+            clipboard::read_data::<ClipboardPaste>().map(move |contents_opt| match contents_opt {
+                Some(contents) => cosmic::action::app(Message::PasteNetwork(to.clone(), contents)),
+                None => cosmic::action::none(),
+            }),
+        )
+    }
+
+    /// Start the next queued operation after one finishes or is cancelled
+    fn operation_queue_finish(&mut self, id: u64) {
+        if let Some(next) = self.operation_queue.finish(id) {
+            if let Some((_, controller)) = self.pending_operations.get(&next) {
+                controller.start();
+            }
+        }
+        if self.operation_queue.is_empty() {
+            self.show_operation_queue = false;
+        }
+    }
+
+    /// Toggle for opening the destination of a pending operation when it finishes
+    fn open_destination_toggle(&self, id: u64, op: &Operation) -> Option<Element<'_, Message>> {
+        op.destination()?;
+        Some(
+            widget::checkbox(
+                fl!("open-destination"),
+                self.open_destination_ids.contains(&id),
+            )
+            .on_toggle(move |open| Message::PendingOpenDestination(id, open))
+            .into(),
+        )
+    }
+
+    /// Open a new window in another process, on the output and workspace of this window
+    fn open_window(&self, args: Vec<OsString>) -> Task<Message> {
+        // The compositor places windows activated with a token from this window next to it
+        #[cfg(feature = "wayland")]
+        if let Some(window_id) = self.window_id_opt {
+            return activation::request_token(Some(Self::APP_ID.to_string()), Some(window_id)).map(
+                move |token_opt| cosmic::action::app(Message::WindowSpawn(args.clone(), token_opt)),
+            );
+        }
+        self.spawn_window(&args, None);
+        Task::none()
+    }
+
+    /// New windows start with the size of this window
+    fn spawn_window(&self, args: &[OsString], token_opt: Option<String>) {
+        let exe = match env::current_exe() {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::error!("failed to get current executable path: {}", err);
+                return;
+            }
+        };
+        let mut command = process::Command::new(&exe);
+        command.args(args);
+        if let Some(size) = self.size {
+            command.arg(format!(
+                "--window-size={}x{}",
+                size.width as u32, size.height as u32
+            ));
+        }
+        if let Some(token) = token_opt {
+            command.env("XDG_ACTIVATION_TOKEN", token);
+        }
+        if let Err(err) = command.spawn() {
+            log::error!("failed to execute {:?}: {}", exe, err);
+        }
+    }
+
+    fn remove_window(&mut self, id: &window::Id) {
+        if let Some(WindowKind::Desktop(entity)) = self.windows.remove(id) {
+            // Remove the tab from the tab model
+            self.tab_model.remove(entity);
+        }
+    }
+
+    #[must_use]
+    fn rescan_operation_selection(&mut self, op_sel: OperationSelection) -> Task<Message> {
+        log::info!("rescan_operation_selection {:?}", op_sel);
+        let entity = self.tab_model.active();
+        let Some(tab) = self.tab_model.data::<Tab>(entity) else {
+            return Task::none();
+        };
+        let Some(items) = tab.items_opt() else {
+            return Task::none();
+        };
+        for item in items.iter() {
+            if item.selected {
+                if let Some(path) = item.path_opt() {
+                    if op_sel.selected.contains(path) || op_sel.ignored.contains(path) {
+                        // Ignore if path in selected or ignored paths
                         continue;
                     }
                 }
@@ -1023,6 +1984,21 @@ impl App {
         self.update_tab(entity, tab.location.clone(), Some(op_sel.selected))
     }
 
+    /// Rescan the active tab and select, scroll to and highlight a newly created path
+    #[must_use]
+    fn reveal_path(&mut self, path: PathBuf) -> Task<Message> {
+        let entity = self.tab_model.active();
+        let Some(tab) = self.tab_model.data_mut::<Tab>(entity) else {
+            return Task::none();
+        };
+        if tab.location.path_opt().map(PathBuf::as_path) != path.parent() {
+            return Task::none();
+        }
+        tab.reveal_path_opt = Some(path.clone());
+        let location = tab.location.clone();
+        self.update_tab(entity, location, Some(vec![path]))
+    }
+
     fn update_tab(
         &mut self,
         entity: Entity,
@@ -1044,17 +2020,42 @@ impl App {
     ) -> Task<Message> {
         log::info!("rescan_tab {entity:?} {location:?} {selection_paths:?}");
         let icon_sizes = self.config.tab.icon_sizes;
+        let starred: Vec<PathBuf> = self.config.starred.iter().cloned().collect();
         Task::perform(
             async move {
-                let location2 = location.clone();
-                match tokio::task::spawn_blocking(move || location2.scan(icon_sizes)).await {
-                    Ok((parent_item_opt, items)) => cosmic::action::app(Message::TabRescan(
-                        entity,
-                        location,
-                        parent_item_opt,
-                        items,
-                        selection_paths,
-                    )),
+                match tokio::task::spawn_blocking(move || match location.scan_remote(icon_sizes) {
+                    Some(res) => {
+                        // Browse mounted network locations through their local path when there
+                        // is one, so that all file operations are available
+                        if let (Ok(_), Location::Network(uri, _)) = (&res, &location) {
+                            if let Some(path) = tab::network_path(uri) {
+                                return Message::TabMessage(
+                                    Some(entity),
+                                    tab::Message::Location(Location::Path(path)),
+                                );
+                            }
+                        }
+                        Message::TabRescanRemote(entity, location, res, selection_paths)
+                    }
+                    None => {
+                        let (parent_item_opt, items) = match location {
+                            Location::Favorites => {
+                                (None, tab::scan_favorites(&starred, icon_sizes))
+                            }
+                            _ => location.scan(icon_sizes),
+                        };
+                        Message::TabRescan(
+                            entity,
+                            location,
+                            parent_item_opt,
+                            items,
+                            selection_paths,
+                        )
+                    }
+                })
+                .await
+                {
+                    Ok(message) => cosmic::action::app(message),
                     Err(err) => {
                         log::warn!("failed to rescan: {}", err);
                         cosmic::action::none()
@@ -1082,11 +2083,147 @@ impl App {
         Task::batch(commands)
     }
 
+    /// Rescan tabs showing any of these network locations
+    fn rescan_network(&mut self, uris: &[String]) -> Task<Message> {
+        let mut needs_reload = Vec::new();
+        for entity in self.tab_model.iter() {
+            if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+                if let Location::Network(uri, _) = &tab.location {
+                    if uris
+                        .iter()
+                        .any(|x| x.trim_end_matches('/') == uri.trim_end_matches('/'))
+                    {
+                        needs_reload.push((entity, tab.location.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut commands = Vec::with_capacity(needs_reload.len());
+        for (entity, location) in needs_reload {
+            commands.push(self.update_tab(entity, location, None));
+        }
+        Task::batch(commands)
+    }
+
+    fn trash_summary(&self) -> Task<Message> {
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(tab::trash_summary).await {
+                    Ok(trash_summary) => cosmic::action::app(Message::TrashSummary(trash_summary)),
+                    Err(err) => {
+                        log::warn!("failed to summarize trash: {}", err);
+                        cosmic::action::none()
+                    }
+                }
+            },
+            |x| x,
+        )
+    }
+
+    fn selected_trash_items(&self, entity_opt: Option<Entity>) -> Vec<trash::TrashItem> {
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        let mut trash_items = Vec::new();
+        if let Some(items) = self
+            .tab_model
+            .data::<Tab>(entity)
+            .and_then(|tab| tab.items_opt())
+        {
+            for item in items.iter() {
+                if item.selected {
+                    if let ItemMetadata::Trash { entry, .. } = &item.metadata {
+                        trash_items.push(entry.clone());
+                    }
+                }
+            }
+        }
+        trash_items
+    }
+
+    /// Restore trashed items to where they were, asking for another folder for the items whose
+    /// original folder no longer exists
+    fn restore_from_trash(&mut self, items: Vec<trash::TrashItem>) -> Task<Message> {
+        let (items, missing): (Vec<_>, Vec<_>) = items
+            .into_iter()
+            .partition(|item| item.original_parent.is_dir());
+        let mut commands = Vec::with_capacity(2);
+        if !items.is_empty() {
+            commands.push(self.operation(Operation::Restore { items }));
+        }
+        if !missing.is_empty() {
+            commands.push(self.restore_to(missing, fl!("restore-missing-title")));
+        }
+        Task::batch(commands)
+    }
+
+    /// Open a dialog for choosing the folder that trashed items are restored into
+    fn restore_to(&mut self, items: Vec<trash::TrashItem>, title: String) -> Task<Message> {
+        let folder = items
+            .iter()
+            .map(|item| item.original_parent.as_path())
+            .find(|parent| parent.is_dir())
+            .map_or_else(home_dir, Path::to_path_buf);
+        let (mut dialog, dialog_task) = Dialog::new(
+            DialogKind::OpenFolder,
+            Some(folder),
+            Message::FileDialogMessage,
+            Message::RestoreToResult,
+        );
+        let set_title_task = dialog.set_title(title);
+        dialog.set_accept_label(fl!("restore-here"));
+        dialog.set_location_check(not_writable_reason);
+        self.windows
+            .insert(dialog.window_id(), WindowKind::FileDialog(None));
+        self.file_dialog_opt = Some(dialog);
+        self.restore_to_items = items;
+        Task::batch([set_title_task, dialog_task])
+    }
+
+    /// Find trashed items past the age or size limit in the background, which are then deleted by
+    /// an operation. Only the main application purges the trash.
+    fn purge_trash(&self) -> Task<Message> {
+        let max_days = self.config.trash_max_days;
+        let max_size = self.config.trash_max_size;
+        if !matches!(self.mode, Mode::App) || (max_days == 0 && max_size == 0) {
+            return Task::none();
+        }
+        // Wait for the previous purge to finish
+        if self
+            .pending_operations
+            .values()
+            .any(|(operation, _)| matches!(operation, Operation::DeleteTrash { .. }))
+        {
+            return Task::none();
+        }
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(move || {
+                    trash_policy::expired_items(max_days, max_size)
+                })
+                .await
+                {
+                    Ok(Ok(items)) if items.is_empty() => cosmic::action::none(),
+                    Ok(Ok(items)) => cosmic::action::app(Message::PurgeTrashItems(items)),
+                    Ok(Err(err)) => {
+                        log::warn!("failed to list old items in the trash: {}", err);
+                        cosmic::action::none()
+                    }
+                    Err(err) => {
+                        log::warn!("failed to list old items in the trash: {}", err);
+                        cosmic::action::none()
+                    }
+                }
+            },
+            |x| x,
+        )
+    }
+
     fn search_get(&self) -> Option<&str> {
         let entity = self.tab_model.active();
         let tab = self.tab_model.data::<Tab>(entity)?;
         match &tab.location {
             Location::Search(_, term, ..) => Some(term),
+            Location::Trash => tab.trash_filter.search_opt.as_deref(),
             _ => None,
         }
     }
@@ -1104,6 +2241,16 @@ impl App {
     ) -> Task<Message> {
         let mut title_location_opt = None;
         if let Some(tab) = self.tab_model.data_mut::<Tab>(tab) {
+            // Trash is searched by filtering its items
+            if tab.location == Location::Trash {
+                let focus_search = term_opt.is_some();
+                tab.trash_filter.search_opt = term_opt;
+                return if focus_search {
+                    widget::text_input::focus(self.search_id.clone())
+                } else {
+                    Task::none()
+                };
+            }
             let location_opt = match term_opt {
                 Some(term) => match &tab.location {
                     Location::Path(path) | Location::Search(path, ..) => Some((
@@ -1156,6 +2303,45 @@ impl App {
         paths
     }
 
+    /// URIs of selected items on network locations whose files a mounter reads itself, as they
+    /// have no paths
+    fn selected_network_uris(&self, entity_opt: Option<Entity>) -> Vec<String> {
+        let mut uris = Vec::new();
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+            for location in tab.selected_locations() {
+                if let Location::Network(uri, _) = location {
+                    if mounter::network_files(&uri).is_some() {
+                        uris.push(uri);
+                    }
+                }
+            }
+        }
+        uris
+    }
+
+    /// Items in the Computer view are volumes, which cannot be moved, renamed, or deleted
+    fn selection_is_fixed(&self, entity_opt: Option<Entity>) -> bool {
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        self.tab_model
+            .data::<Tab>(entity)
+            .is_some_and(|tab| tab.location == Location::Computer)
+    }
+
+    /// Selected paths, or the tab's own path if nothing is selected
+    fn selected_or_location_paths(&self, entity_opt: Option<Entity>) -> Vec<PathBuf> {
+        let mut paths = self.selected_paths(entity_opt);
+        if paths.is_empty() {
+            let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+            if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+                if let Some(path) = tab.location.path_opt() {
+                    paths.push(path.to_path_buf());
+                }
+            }
+        }
+        paths
+    }
+
     fn set_cut(&mut self, entity_opt: Option<Entity>) {
         let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
         if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
@@ -1163,20 +2349,66 @@ impl App {
         }
     }
 
+    /// Change the icon size of the view of the tab, and show the new zoom level over it
+    fn zoom(
+        &mut self,
+        entity_opt: Option<Entity>,
+        change: impl FnOnce(&mut NonZeroU16),
+    ) -> Task<Message> {
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        let mut config = self.config.tab;
+        if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+            match tab.config.view {
+                tab::View::List => change(&mut config.icon_sizes.list),
+                tab::View::Grid => change(&mut config.icon_sizes.grid),
+            }
+        }
+        Task::batch([
+            self.update(Message::TabConfig(config)),
+            self.update(Message::TabMessage(
+                Some(entity),
+                tab::Message::ZoomIndicator,
+            )),
+        ])
+    }
+
     fn update_config(&mut self) -> Task<Message> {
         self.update_nav_model();
+        self.update_frequent_folders();
         // Tabs are collected first to placate the borrowck
         let tabs: Vec<_> = self.tab_model.iter().collect();
         // Update main conf and each tab with the new config
-        let commands: Vec<_> =
+        let mut commands: Vec<_> =
             std::iter::once(cosmic::command::set_theme(self.config.app_theme.theme()))
-                .chain(tabs.into_iter().map(|entity| {
+                .chain(tabs.iter().map(|&entity| {
                     self.update(Message::TabMessage(
                         Some(entity),
                         tab::Message::Config(self.config.tab),
                     ))
                 }))
                 .collect();
+        // Rescan tabs where the hidden patterns changed, and the favorites view when the starred
+        // items changed
+        for entity in tabs {
+            let location_opt = self.tab_model.data_mut::<Tab>(entity).and_then(|tab| {
+                tab.set_folder_columns(&self.config.folder_columns);
+                tab.set_folder_column_widths(&self.config.folder_column_widths);
+                let starred_changed = tab.set_starred(&self.config.starred);
+                let hidden_changed = tab.set_hidden_patterns(&self.config.hidden_patterns);
+                (hidden_changed || (starred_changed && tab.location == Location::Favorites))
+                    .then(|| tab.location.clone())
+            });
+            if let Some(location) = location_opt {
+                commands.push(self.update_tab(entity, location, None));
+            }
+        }
+        // Tab titles depend on the title setting
+        for entity in self.tab_model.iter().collect::<Vec<_>>() {
+            if let Some(title) = self.tab_model.data::<Tab>(entity).map(|tab| tab.title()) {
+                self.tab_model.text_set(entity, title);
+            }
+        }
+        commands.push(self.update_title());
         Task::batch(commands)
     }
 
@@ -1227,12 +2459,24 @@ impl App {
                 .data(Location::Recents)
         });
 
+        nav_model = nav_model.insert(|b| {
+            b.text(fl!("computer"))
+                .icon(widget::icon::from_name("computer-symbolic"))
+                .data(Location::Computer)
+        });
+
+        nav_model = nav_model.insert(|b| {
+            b.text(fl!("starred"))
+                .icon(widget::icon::from_name("starred-symbolic"))
+                .data(Location::Favorites)
+        });
+
         for (favorite_i, favorite) in self.config.favorites.iter().enumerate() {
             if let Some(path) = favorite.path_opt() {
                 let name = if matches!(favorite, Favorite::Home) {
                     fl!("home")
-                } else if let Some(file_name) = path.file_name().and_then(|x| x.to_str()) {
-                    file_name.to_string()
+                } else if let Some(file_name) = path.file_name() {
+                    tab::escaped_file_name(file_name)
                 } else {
                     fl!("filesystem")
                 };
@@ -1254,6 +2498,26 @@ impl App {
             }
         }
 
+        for (pin_i, path) in self.temporary_pins.iter().enumerate() {
+            let name = match path.file_name() {
+                Some(file_name) => tab::escaped_file_name(file_name),
+                None => fl!("filesystem"),
+            };
+            nav_model = nav_model.insert(move |b| {
+                let b = b
+                    .text(name.clone())
+                    .icon(widget::icon::from_name("view-pin-symbolic").size(16))
+                    .data(Location::Path(path.clone()))
+                    .data(TemporaryPin(pin_i))
+                    .closable();
+                if pin_i == 0 {
+                    b.divider_above()
+                } else {
+                    b
+                }
+            });
+        }
+
         nav_model = nav_model.insert(|b| {
             b.text(fl!("trash"))
                 .icon(widget::icon::icon(tab::trash_icon_symbolic(16)))
@@ -1261,6 +2525,20 @@ impl App {
                 .divider_above()
         });
 
+        for (tag_i, tag) in tag::used_tags().into_iter().enumerate() {
+            nav_model = nav_model.insert(|b| {
+                let b = b
+                    .text(tag.name())
+                    .icon(widget::icon::from_name("tag-symbolic"))
+                    .data(Location::Tag(tag));
+                if tag_i == 0 {
+                    b.divider_above()
+                } else {
+                    b
+                }
+            });
+        }
+
         if !MOUNTERS.is_empty() {
             nav_model = nav_model.insert(|b| {
                 b.text(fl!("networks"))
@@ -1275,6 +2553,15 @@ impl App {
                     ))
                     .divider_above()
             });
+            nav_model = nav_model.insert(|b| {
+                b.text(fl!("add-network-drive"))
+                    .icon(widget::icon::icon(
+                        widget::icon::from_name("list-add-symbolic")
+                            .size(16)
+                            .handle(),
+                    ))
+                    .data(AddNetworkDriveData)
+            });
         }
 
         // Collect all mounter items
@@ -1433,6 +2720,78 @@ impl App {
         .into()
     }
 
+    fn mime_apps(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let search = self.mime_apps_search.to_lowercase();
+        let mut section = widget::settings::section();
+        for mime in self.mime_app_cache.mime_types() {
+            if !mime.as_ref().contains(search.as_str()) {
+                continue;
+            }
+            let apps = self.mime_app_cache.get(mime);
+            let mime_default = mime.clone();
+            let mime_reset = mime.clone();
+            section = section.add(
+                widget::settings::item::builder(mime.to_string()).control(
+                    widget::row::with_children(vec![
+                        widget::dropdown(
+                            apps,
+                            apps.iter().position(|app| app.is_default),
+                            move |index| Message::MimeDefault(mime_default.clone(), index),
+                        )
+                        .into(),
+                        widget::tooltip(
+                            widget::button::icon(widget::icon::from_name("edit-undo-symbolic"))
+                                .on_press_maybe(
+                                    self.mime_app_cache
+                                        .has_user_default(mime)
+                                        .then(|| Message::MimeDefaultReset(mime_reset)),
+                                ),
+                            widget::text::body(fl!("file-associations-reset")),
+                            widget::tooltip::Position::Bottom,
+                        )
+                        .into(),
+                    ])
+                    .align_y(Alignment::Center)
+                    .spacing(space_xxs),
+                ),
+            );
+        }
+        section.into()
+    }
+
+    /// Default applications for whole categories of files, offering the applications of a
+    /// typical type in each category
+    fn default_apps_section(&self) -> Element<Message> {
+        let mut section = widget::settings::section().title(fl!("default-apps"));
+        for category in AppCategory::ALL {
+            let primary_mime = category.primary_mime();
+            let apps = self.mime_app_cache.get(&primary_mime);
+            let label = match category {
+                AppCategory::Images => fl!("default-apps-images"),
+                AppCategory::Videos => fl!("default-apps-videos"),
+                AppCategory::Music => fl!("default-apps-music"),
+                AppCategory::Text => fl!("default-apps-text"),
+                AppCategory::Archives => fl!("default-apps-archives"),
+            };
+            let item = widget::settings::item::builder(label);
+            section = section.add(if apps.is_empty() {
+                item.control(widget::text::body(fl!("default-apps-none")))
+            } else {
+                item.control(
+                    widget::dropdown(
+                        apps,
+                        apps.iter().position(|app| app.is_default),
+                        move |index| Message::MimeCategoryDefault(category, index),
+                    )
+                    .icons(self.mime_app_cache.icons(&primary_mime)),
+                )
+            });
+        }
+        section.into()
+    }
+
     fn network_drive(&self) -> Element<Message> {
         let cosmic_theme::Spacing {
             space_xxs, space_m, ..
@@ -1558,7 +2917,7 @@ impl App {
             let mut section = widget::settings::section().title(fl!("pending"));
             for (id, (op, controller)) in self.pending_operations.iter().rev() {
                 let progress = controller.progress();
-                section = section.add(widget::column::with_children(vec![
+                let mut column = widget::column::with_children(vec![
                     widget::row::with_children(vec![
                         widget::progress_bar(0.0..=1.0, progress)
                             .height(progress_bar_height)
@@ -1598,7 +2957,11 @@ impl App {
                     .align_y(Alignment::Center)
                     .into(),
                     widget::text::body(op.pending_text(progress, controller.state())).into(),
-                ]));
+                ]);
+                if let Some(toggle) = self.open_destination_toggle(*id, op) {
+                    column = column.push(toggle);
+                }
+                section = section.add(column);
             }
             children.push(section.into());
         }
@@ -1643,18 +3006,29 @@ impl App {
         let mut children = Vec::with_capacity(1);
         let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
         let military_time = self.config.tab.military_time;
+        let details_tab = Some(self.config.details_tab);
         match kind {
             PreviewKind::Custom(PreviewItem(item)) => {
-                children.push(item.preview_view(Some(&self.mime_app_cache), military_time));
+                children.push(item.preview_view(
+                    Some(&self.mime_app_cache),
+                    details_tab,
+                    None,
+                    military_time,
+                    None,
+                ));
             }
             PreviewKind::Location(location) => {
                 if let Some(tab) = self.tab_model.data::<Tab>(entity) {
                     if let Some(items) = tab.items_opt() {
                         for item in items.iter() {
                             if item.location_opt.as_ref() == Some(location) {
-                                children.push(
-                                    item.preview_view(Some(&self.mime_app_cache), military_time),
-                                );
+                                children.push(item.preview_view(
+                                    Some(&self.mime_app_cache),
+                                    details_tab,
+                                    Some(&tab.attributes_edit),
+                                    military_time,
+                                    None,
+                                ));
                                 // Only show one property view to avoid issues like hangs when generating
                                 // preview images on thousands of files
                                 break;
@@ -1668,9 +3042,13 @@ impl App {
                     if let Some(items) = tab.items_opt() {
                         for item in items.iter() {
                             if item.selected {
-                                children.push(
-                                    item.preview_view(Some(&self.mime_app_cache), military_time),
-                                );
+                                children.push(item.preview_view(
+                                    Some(&self.mime_app_cache),
+                                    details_tab,
+                                    Some(&tab.attributes_edit),
+                                    military_time,
+                                    tab.media_view(item),
+                                ));
                                 // Only show one property view to avoid issues like hangs when generating
                                 // preview images on thousands of files
                                 break;
@@ -1678,9 +3056,13 @@ impl App {
                         }
                         if children.is_empty() {
                             if let Some(item) = &tab.parent_item_opt {
-                                children.push(
-                                    item.preview_view(Some(&self.mime_app_cache), military_time),
-                                );
+                                children.push(item.preview_view(
+                                    Some(&self.mime_app_cache),
+                                    details_tab,
+                                    Some(&tab.attributes_edit),
+                                    military_time,
+                                    None,
+                                ));
                             }
                         }
                     }
@@ -1696,24 +3078,480 @@ impl App {
             .into()
     }
 
-    fn settings(&self) -> Element<Message> {
-        let tab_config = self.config.tab;
+    fn details_visible(&self) -> bool {
+        self.core.window.show_context && matches!(self.context_page, ContextPage::Preview(..))
+    }
 
-        // TODO: Should dialog be updated here too?
-        widget::settings::view_column(vec![
-            widget::settings::section()
-                .title(fl!("appearance"))
-                .add({
-                    let app_theme_selected = match self.config.app_theme {
-                        AppTheme::Dark => 1,
-                        AppTheme::Light => 2,
-                        AppTheme::System => 0,
-                    };
-                    widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
-                        &self.app_themes,
-                        Some(app_theme_selected),
-                        move |index| {
-                            Message::AppTheme(match index {
+    /// Details pane with a handle on its inner edge for resizing
+    fn details_pane(&self) -> Option<Element<Message>> {
+        if !self.details_visible() {
+            return None;
+        }
+        let ContextPage::Preview(entity_opt, kind) = &self.context_page else {
+            return None;
+        };
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+        let mut header = widget::row::with_capacity(4)
+            .align_y(Alignment::Center)
+            .spacing(space_xxs);
+        if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+            if let Some(item) = tab
+                .items_opt()
+                .and_then(|items| items.iter().find(|item| item.selected))
+            {
+                for element in item.preview_header() {
+                    header =
+                        header.push(element.map(move |x| Message::TabMessage(Some(entity), x)));
+                }
+            }
+        }
+        header = header.push(horizontal_space()).push(
+            widget::button::icon(widget::icon::from_name("window-close-symbolic")).on_press(
+                Message::ToggleContextPage(ContextPage::Preview(Some(entity), kind.clone())),
+            ),
+        );
+
+        let width = self
+            .details_resize_opt
+            .as_ref()
+            .map_or(self.config.details_width, |resize| resize.width)
+            .clamp(DETAILS_WIDTH_MIN, DETAILS_WIDTH_MAX);
+        let mut tabs = widget::row::with_capacity(DetailsTab::ALL.len()).spacing(space_xxs);
+        for details_tab in DetailsTab::ALL {
+            let label = match details_tab {
+                DetailsTab::Preview => fl!("details-preview"),
+                DetailsTab::Properties => fl!("details-properties"),
+                DetailsTab::Permissions => fl!("details-permissions"),
+                DetailsTab::Versions => fl!("details-versions"),
+                DetailsTab::Advanced => fl!("details-advanced"),
+            };
+            tabs = tabs.push(
+                widget::button::text(label)
+                    .selected(details_tab == self.config.details_tab)
+                    .on_press(Message::DetailsTab(details_tab)),
+            );
+        }
+
+        let pane = widget::container(
+            widget::column::with_children(vec![
+                header.into(),
+                tabs.into(),
+                widget::scrollable(
+                    self.preview(entity_opt, kind, true)
+                        .map(move |x| Message::TabMessage(Some(entity), x)),
+                )
+                .height(Length::Fill)
+                .into(),
+            ])
+            .spacing(space_s),
+        )
+        .width(Length::Fixed(width.into()))
+        .height(Length::Fill)
+        .padding(space_s)
+        .class(if self.details_focused {
+            // Outline the pane while it has keyboard focus
+            style::Container::Custom(Box::new(|theme| {
+                let cosmic = theme.cosmic();
+                widget::container::Style {
+                    text_color: Some(cosmic.background.on.into()),
+                    background: Some(iced::Color::from(cosmic.background.base).into()),
+                    border: iced::Border {
+                        color: cosmic.accent_color().into(),
+                        width: 2.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            }))
+        } else {
+            style::Container::Background
+        });
+
+        let handle = mouse_area::MouseArea::new(
+            widget::container(vertical_rule(1))
+                .width(Length::Fixed(space_xxs.into()))
+                .height(Length::Fill)
+                .align_x(Alignment::Center),
+        )
+        .on_press(|_| Message::DetailsResizeStart);
+
+        Some(
+            match self.config.details_position {
+                DetailsPosition::Left => {
+                    widget::row::with_children(vec![pane.into(), handle.into()])
+                }
+                DetailsPosition::Right => {
+                    widget::row::with_children(vec![handle.into(), pane.into()])
+                }
+            }
+            .into(),
+        )
+    }
+
+    /// Label, icon, and action of a toolbar button, which may depend on the active tab
+    fn toolbar_button(&self, button: ToolbarButton) -> (String, &'static str, Action) {
+        let tab_config = self
+            .tab_model
+            .active_data::<Tab>()
+            .map_or(self.config.tab, |tab| tab.config);
+        match button {
+            ToolbarButton::NewFolder => {
+                (fl!("new-folder"), "folder-new-symbolic", Action::NewFolder)
+            }
+            ToolbarButton::ToggleHidden => (
+                fl!("show-hidden-files"),
+                if tab_config.show_hidden {
+                    "view-conceal-symbolic"
+                } else {
+                    "view-reveal-symbolic"
+                },
+                Action::ToggleShowHidden,
+            ),
+            ToolbarButton::ViewSwitch => match tab_config.view {
+                tab::View::Grid => (fl!("list-view"), "view-list-symbolic", Action::TabViewList),
+                tab::View::List => (fl!("grid-view"), "view-grid-symbolic", Action::TabViewGrid),
+            },
+            ToolbarButton::Terminal => (
+                fl!("open-in-terminal"),
+                "utilities-terminal-symbolic",
+                Action::OpenTerminal,
+            ),
+            ToolbarButton::Up => (fl!("parent-folder"), "go-up-symbolic", Action::LocationUp),
+            ToolbarButton::Refresh => (
+                fl!("reload-folder"),
+                "view-refresh-symbolic",
+                Action::Reload,
+            ),
+        }
+    }
+
+    fn toolbar(&self) -> Option<Element<Message>> {
+        if self.config.toolbar.is_empty() {
+            return None;
+        }
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let mut row = widget::row::with_capacity(self.config.toolbar.len())
+            .align_y(Alignment::Center)
+            .spacing(space_xxs)
+            .padding([space_xxs, space_s]);
+        for &button in self.config.toolbar.iter() {
+            let (label, icon, action) = self.toolbar_button(button);
+            row = row.push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name(icon)).on_press(action.message(None)),
+                widget::text::body(label),
+                widget::tooltip::Position::Bottom,
+            ));
+        }
+        Some(row.into())
+    }
+
+    fn update_frequent_folders(&mut self) {
+        let now_secs = time::SystemTime::now()
+            .duration_since(time::UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+        self.frequent_folders = frequent_folders::frequent(
+            &self.folder_visits,
+            &self.config.frequent_pinned,
+            now_secs,
+            |path| path.is_dir(),
+        );
+    }
+
+    /// Save the folder visits after [`frequent_folders::SAVE_DELAY`], unless a save is already
+    /// waiting, so that several visits in a row are written once
+    fn save_folder_visits_later(&mut self) -> Task<Message> {
+        if self.folder_visits_save_pending {
+            return Task::none();
+        }
+        self.folder_visits_save_pending = true;
+        Task::perform(tokio::time::sleep(frequent_folders::SAVE_DELAY), |_| {
+            cosmic::Action::App(Message::SaveFolderVisits)
+        })
+    }
+
+    fn frequent_folders_bar(&self) -> Option<Element<Message>> {
+        if !self.config.show_frequent_folders || self.frequent_folders.is_empty() {
+            return None;
+        }
+
+        let cosmic_theme::Spacing {
+            space_xxxs,
+            space_xxs,
+            space_s,
+            ..
+        } = theme::active().cosmic().spacing;
+
+        let mut row = widget::row::with_capacity(self.frequent_folders.len())
+            .align_y(Alignment::Center)
+            .spacing(space_s)
+            .padding([space_xxs, space_s]);
+        for path in self.frequent_folders.iter() {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            let pinned = self.config.frequent_pinned.contains(path);
+            row = row.push(
+                widget::row::with_children(vec![
+                    widget::tooltip(
+                        widget::button::text(name).on_press(Message::TabMessage(
+                            None,
+                            tab::Message::Location(Location::Path(path.clone())),
+                        )),
+                        widget::text::body(path.display().to_string()),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .into(),
+                    widget::tooltip(
+                        widget::button::icon(widget::icon::from_name("view-pin-symbolic").size(16))
+                            .padding(space_xxxs)
+                            .selected(pinned)
+                            .on_press(Message::FrequentFolderPin(path.clone())),
+                        widget::text::body(if pinned {
+                            fl!("unpin-folder")
+                        } else {
+                            fl!("pin-folder")
+                        }),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .into(),
+                    widget::tooltip(
+                        widget::button::icon(
+                            widget::icon::from_name("window-close-symbolic").size(16),
+                        )
+                        .padding(space_xxxs)
+                        .on_press(Message::FrequentFolderRemove(path.clone())),
+                        widget::text::body(fl!("remove")),
+                        widget::tooltip::Position::Bottom,
+                    )
+                    .into(),
+                ])
+                .align_y(Alignment::Center),
+            );
+        }
+        Some(row.into())
+    }
+
+    /// Thumbnailing and folder sizes are slowed down on battery or as configured
+    fn reduce_background_work(&self) -> bool {
+        match self.config.reduce_background_work {
+            ReduceBackgroundWork::Auto => self.power_saving,
+            ReduceBackgroundWork::Always => true,
+            ReduceBackgroundWork::Never => false,
+        }
+    }
+
+    fn mount(&mut self, mounter_key: MounterKey, target: MountTarget) -> Task<Message> {
+        let Some(mounter) = MOUNTERS.get(&mounter_key) else {
+            log::warn!("no mounter found for {:?}", mounter_key);
+            return Task::none();
+        };
+        let task = match &target {
+            MountTarget::Item(item) => mounter.mount(item.clone()),
+            MountTarget::Uri(uri) => {
+                self.network_drive_connecting = Some((mounter_key, uri.clone()));
+                mounter.network_drive(uri.clone())
+            }
+        };
+        self.mount_progress_opt = Some(MountProgress {
+            mounter_key,
+            target,
+            error_opt: None,
+        });
+        task.map(|_| cosmic::action::none())
+    }
+
+    fn mount_progress_finish(
+        &mut self,
+        mounter_key: MounterKey,
+        name: &str,
+        error_opt: Option<MounterError>,
+    ) {
+        let matches = self.mount_progress_opt.as_ref().map_or(false, |progress| {
+            progress.mounter_key == mounter_key && progress.target.name() == name
+        });
+        match error_opt {
+            Some(error) => {
+                // Failures are shown even if the mount was started elsewhere, like a network scan
+                let target = match self.mount_progress_opt.take() {
+                    Some(progress) if matches => progress.target,
+                    _ => MountTarget::Uri(name.to_string()),
+                };
+                self.mount_progress_opt = Some(MountProgress {
+                    mounter_key,
+                    target,
+                    error_opt: Some(error),
+                });
+            }
+            None => {
+                if matches {
+                    self.mount_progress_opt = None;
+                }
+            }
+        }
+    }
+
+    fn mount_progress(&self) -> Option<Element<Message>> {
+        let progress = self.mount_progress_opt.as_ref()?;
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let name = progress.target.name();
+        let (icon, title, description, buttons): (_, _, _, Vec<Element<Message>>) =
+            match &progress.error_opt {
+                None => (
+                    "process-working-symbolic",
+                    fl!("mount-connecting", name = name.as_str()),
+                    None,
+                    vec![widget::button::standard(fl!("cancel"))
+                        .on_press(Message::MountCancel)
+                        .into()],
+                ),
+                Some(error) => (
+                    "dialog-error-symbolic",
+                    match error.kind {
+                        MounterErrorKind::Auth => fl!("mount-error-auth", name = name.as_str()),
+                        MounterErrorKind::Unreachable => {
+                            fl!("mount-error-unreachable", name = name.as_str())
+                        }
+                        MounterErrorKind::Timeout => {
+                            fl!("mount-error-timeout", name = name.as_str())
+                        }
+                        MounterErrorKind::Busy | MounterErrorKind::Other => match progress.target {
+                            MountTarget::Item(_) => fl!("mount-error"),
+                            MountTarget::Uri(_) => fl!("network-drive-error"),
+                        },
+                    },
+                    Some(error.message.clone()),
+                    vec![
+                        widget::button::standard(fl!("dismiss"))
+                            .on_press(Message::MountDismiss)
+                            .into(),
+                        widget::button::suggested(fl!("try-again"))
+                            .on_press(Message::MountRetry)
+                            .into(),
+                    ],
+                ),
+            };
+
+        let mut text_column = widget::column::with_capacity(2)
+            .push(widget::text::heading(title))
+            .width(Length::Fill);
+        if let Some(description) = description {
+            text_column = text_column.push(widget::text::caption(description));
+        }
+
+        let mut row = widget::row::with_capacity(2 + buttons.len())
+            .align_y(Alignment::Center)
+            .spacing(space_s)
+            .push(widget::icon::from_name(icon).size(24))
+            .push(text_column);
+        for button in buttons {
+            row = row.push(button);
+        }
+
+        Some(
+            widget::container(
+                widget::container(row)
+                    .class(theme::Container::Card)
+                    .padding(space_s)
+                    .width(Length::Fill),
+            )
+            .padding([space_xxs, space_s])
+            .into(),
+        )
+    }
+
+    fn settings(&self) -> Element<Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+        let tab_config = self.config.tab;
+
+        // Enabled toolbar buttons are listed first, in the order they are shown
+        let mut toolbar_section = widget::settings::section().title(fl!("toolbar"));
+        for &button in self.config.toolbar.iter().chain(
+            ToolbarButton::all()
+                .iter()
+                .filter(|button| !self.config.toolbar.contains(*button)),
+        ) {
+            let toolbar = &self.config.toolbar;
+            let position = toolbar.iter().position(|x| *x == button);
+            let mut control = widget::row::with_capacity(3)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs);
+            if let Some(i) = position {
+                let moved = |j: usize| {
+                    let mut toolbar = toolbar.clone();
+                    toolbar.swap(i, j);
+                    Message::SetToolbar(toolbar)
+                };
+                control = control
+                    .push(
+                        widget::button::icon(widget::icon::from_name("go-up-symbolic"))
+                            .on_press_maybe((i > 0).then(|| moved(i - 1))),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("go-down-symbolic"))
+                            .on_press_maybe((i + 1 < toolbar.len()).then(|| moved(i + 1))),
+                    );
+            }
+            let toggled = toolbar.clone();
+            control = control.push(
+                widget::toggler(position.is_some()).on_toggle(move |enabled| {
+                    let mut toolbar = toggled.clone();
+                    toolbar.retain(|x| *x != button);
+                    if enabled {
+                        toolbar.push(button);
+                    }
+                    Message::SetToolbar(toolbar)
+                }),
+            );
+            let (label, _, _) = self.toolbar_button(button);
+            toolbar_section =
+                toolbar_section.add(widget::settings::item::builder(label).control(control));
+        }
+
+        let mut extract_section = widget::settings::section().title(fl!("extract-on-open"));
+        for &essence in EXTRACT_MIMES {
+            let extract_on_open = self.config.extract_on_open.clone();
+            extract_section =
+                extract_section.add(widget::settings::item::builder(essence).toggler(
+                    extract_on_open.iter().any(|x| x == essence),
+                    move |enabled| {
+                        let mut extract_on_open = extract_on_open.clone();
+                        extract_on_open.retain(|x| x != essence);
+                        if enabled {
+                            extract_on_open.push(essence.to_string());
+                        }
+                        Message::SetExtractOnOpen(extract_on_open)
+                    },
+                ));
+        }
+
+        // TODO: Should dialog be updated here too?
+        widget::settings::view_column(vec![
+            widget::settings::section()
+                .title(fl!("appearance"))
+                .add({
+                    let app_theme_selected = match self.config.app_theme {
+                        AppTheme::Dark => 1,
+                        AppTheme::Light => 2,
+                        AppTheme::System => 0,
+                    };
+                    widget::settings::item::builder(fl!("theme")).control(widget::dropdown(
+                        &self.app_themes,
+                        Some(app_theme_selected),
+                        move |index| {
+                            Message::AppTheme(match index {
                                 1 => AppTheme::Dark,
                                 2 => AppTheme::Light,
                                 _ => AppTheme::System,
@@ -1722,6 +3560,155 @@ impl App {
                     ))
                 })
                 .into(),
+            toolbar_section.into(),
+            extract_section.into(),
+            widget::settings::section()
+                .title(fl!("details-position"))
+                .add(widget::radio(
+                    widget::text::body(fl!("details-position-left")),
+                    DetailsPosition::Left,
+                    Some(self.config.details_position),
+                    Message::SetDetailsPosition,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("details-position-right")),
+                    DetailsPosition::Right,
+                    Some(self.config.details_position),
+                    Message::SetDetailsPosition,
+                ))
+                .into(),
+            widget::settings::section()
+                .title(fl!("conflict-policy"))
+                .add(widget::radio(
+                    widget::text::body(fl!("conflict-policy-ask")),
+                    ConflictPolicy::Ask,
+                    Some(self.config.conflict_policy),
+                    Message::SetConflictPolicy,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("conflict-policy-keep-both")),
+                    ConflictPolicy::KeepBoth,
+                    Some(self.config.conflict_policy),
+                    Message::SetConflictPolicy,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("conflict-policy-replace-older")),
+                    ConflictPolicy::ReplaceOlder,
+                    Some(self.config.conflict_policy),
+                    Message::SetConflictPolicy,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("conflict-policy-skip")),
+                    ConflictPolicy::Skip,
+                    Some(self.config.conflict_policy),
+                    Message::SetConflictPolicy,
+                ))
+                .into(),
+            widget::settings::section()
+                .title(fl!("reduce-background-work"))
+                .add(widget::radio(
+                    widget::text::body(fl!("reduce-background-work-auto")),
+                    ReduceBackgroundWork::Auto,
+                    Some(self.config.reduce_background_work),
+                    Message::SetReduceBackgroundWork,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("reduce-background-work-always")),
+                    ReduceBackgroundWork::Always,
+                    Some(self.config.reduce_background_work),
+                    Message::SetReduceBackgroundWork,
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("reduce-background-work-never")),
+                    ReduceBackgroundWork::Never,
+                    Some(self.config.reduce_background_work),
+                    Message::SetReduceBackgroundWork,
+                ))
+                .into(),
+            widget::settings::section()
+                .title(fl!("trash"))
+                .add(
+                    widget::settings::item::builder(fl!("trash-max-days")).control(
+                        widget::dropdown(
+                            &self.trash_max_days_names,
+                            TRASH_MAX_DAYS
+                                .iter()
+                                .position(|&days| days == self.config.trash_max_days),
+                            |index| Message::SetTrashMaxDays(TRASH_MAX_DAYS[index]),
+                        ),
+                    ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("trash-max-size"))
+                        .description(fl!("trash-max-size-description"))
+                        .control(widget::dropdown(
+                            &self.trash_max_size_names,
+                            TRASH_MAX_SIZES
+                                .iter()
+                                .position(|&size| size == self.config.trash_max_size),
+                            |index| Message::SetTrashMaxSize(TRASH_MAX_SIZES[index]),
+                        )),
+                )
+                .into(),
+            self.default_apps_section(),
+            widget::settings::section()
+                .title(fl!("file-associations"))
+                .add(
+                    widget::settings::item::builder(fl!("file-associations-description")).control(
+                        widget::button::standard(fl!("file-associations-open"))
+                            .on_press(Message::ToggleContextPage(ContextPage::MimeApps)),
+                    ),
+                )
+                .into(),
+            widget::settings::section()
+                .title(fl!("audit-log"))
+                .add(
+                    widget::settings::item::builder(fl!("audit-log-enable"))
+                        .description(fl!("audit-log-description"))
+                        .toggler(self.config.audit_log, Message::SetAuditLog),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("audit-log-view")).control(
+                        widget::button::standard(fl!("audit-log-open")).on_press(Message::AuditLog),
+                    ),
+                )
+                .into(),
+            widget::settings::section()
+                .title(fl!("tab-title"))
+                .add(widget::radio(
+                    widget::text::body(fl!("tab-title-folder-name")),
+                    TabTitle::FolderName,
+                    Some(tab_config.title),
+                    move |title| {
+                        Message::TabConfig(TabConfig {
+                            title,
+                            ..tab_config
+                        })
+                    },
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("tab-title-shortened")),
+                    TabTitle::Shortened,
+                    Some(tab_config.title),
+                    move |title| {
+                        Message::TabConfig(TabConfig {
+                            title,
+                            ..tab_config
+                        })
+                    },
+                ))
+                .add(widget::radio(
+                    widget::text::body(fl!("tab-title-full-path")),
+                    TabTitle::FullPath,
+                    Some(tab_config.title),
+                    move |title| {
+                        Message::TabConfig(TabConfig {
+                            title,
+                            ..tab_config
+                        })
+                    },
+                ))
+                .into(),
             widget::settings::section()
                 .title(fl!("type-to-search"))
                 .add(widget::radio(
@@ -1750,11 +3737,202 @@ impl App {
                         },
                     )
                 })
+                .add({
+                    widget::settings::item::builder(fl!("animated-preview")).toggler(
+                        tab_config.animated_preview,
+                        move |animated_preview| {
+                            Message::TabConfig(TabConfig {
+                                animated_preview,
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add({
+                    widget::settings::item::builder(fl!("extension-badges")).toggler(
+                        tab_config.extension_badges,
+                        move |extension_badges| {
+                            Message::TabConfig(TabConfig {
+                                extension_badges,
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add({
+                    widget::settings::item::builder(fl!("secure-delete-setting"))
+                        .description(fl!("secure-delete-storage-warning"))
+                        .toggler(tab_config.secure_delete, move |secure_delete| {
+                            Message::TabConfig(TabConfig {
+                                secure_delete,
+                                ..tab_config
+                            })
+                        })
+                })
+                .add({
+                    widget::settings::item::builder(fl!("tree-view")).toggler(
+                        tab_config.tree_view,
+                        move |tree_view| {
+                            Message::TabConfig(TabConfig {
+                                tree_view,
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add({
+                    let grid_columns = tab_config.grid_columns.map_or(0, NonZeroU16::get);
+                    widget::settings::item::builder(fl!("grid-columns"))
+                        .description(if grid_columns == 0 {
+                            fl!("grid-columns-automatic")
+                        } else {
+                            grid_columns.to_string()
+                        })
+                        .control(widget::slider(
+                            0..=GRID_COLUMNS_MAX,
+                            grid_columns,
+                            move |grid_columns| {
+                                Message::TabConfig(TabConfig {
+                                    grid_columns: NonZeroU16::new(grid_columns),
+                                    ..tab_config
+                                })
+                            },
+                        ))
+                })
+                .add({
+                    let slideshow = tab_config.slideshow;
+                    widget::settings::item::builder(fl!("slideshow-interval"))
+                        .description(fl!("slideshow-seconds", seconds = slideshow.interval))
+                        .control(widget::slider(
+                            SLIDESHOW_INTERVAL_MIN..=SLIDESHOW_INTERVAL_MAX,
+                            slideshow.interval,
+                            move |interval| {
+                                Message::TabConfig(TabConfig {
+                                    slideshow: SlideshowConfig {
+                                        interval,
+                                        ..slideshow
+                                    },
+                                    ..tab_config
+                                })
+                            },
+                        ))
+                })
+                .add({
+                    let slideshow = tab_config.slideshow;
+                    widget::settings::item::builder(fl!("slideshow-shuffle")).toggler(
+                        slideshow.shuffle,
+                        move |shuffle| {
+                            Message::TabConfig(TabConfig {
+                                slideshow: SlideshowConfig {
+                                    shuffle,
+                                    ..slideshow
+                                },
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add({
+                    let slideshow = tab_config.slideshow;
+                    widget::settings::item::builder(fl!("slideshow-repeat")).toggler(
+                        slideshow.repeat,
+                        move |repeat| {
+                            Message::TabConfig(TabConfig {
+                                slideshow: SlideshowConfig {
+                                    repeat,
+                                    ..slideshow
+                                },
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add({
+                    widget::settings::item::builder(fl!("double-click-parent")).toggler(
+                        tab_config.double_click_parent,
+                        move |double_click_parent| {
+                            Message::TabConfig(TabConfig {
+                                double_click_parent,
+                                ..tab_config
+                            })
+                        },
+                    )
+                })
+                .add(
+                    widget::settings::item::builder(fl!("write-through-removable"))
+                        .description(fl!("write-through-removable-description"))
+                        .toggler(
+                            self.config.write_through_removable,
+                            Message::SetWriteThroughRemovable,
+                        ),
+                )
+                .add({
+                    let new_tab = self.config.new_tab;
+                    widget::settings::item::builder(fl!("new-tab-drives"))
+                        .toggler(new_tab.drives, move |drives| {
+                            Message::NewTabConfig(NewTabConfig { drives, ..new_tab })
+                        })
+                })
+                .add({
+                    let new_tab = self.config.new_tab;
+                    widget::settings::item::builder(fl!("new-tab-network"))
+                        .toggler(new_tab.network, move |network| {
+                            Message::NewTabConfig(NewTabConfig { network, ..new_tab })
+                        })
+                })
+                .add(
+                    widget::settings::item::builder(fl!("open-destination"))
+                        .description(fl!("open-destination-description"))
+                        .toggler(self.config.open_destination, Message::SetOpenDestination),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("restore-session"))
+                        .description(fl!("restore-session-description"))
+                        .toggler(self.config.restore_session, Message::SetRestoreSession),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("show-frequent-folders"))
+                        .description(fl!("show-frequent-folders-description"))
+                        .toggler(
+                            self.config.show_frequent_folders,
+                            Message::SetShowFrequentFolders,
+                        ),
+                )
+                .add(
+                    widget::settings::item::builder(fl!("hidden-patterns"))
+                        .description(fl!("hidden-patterns-description"))
+                        .control(
+                            widget::text_input(
+                                "*~, *.pyc, __pycache__",
+                                &self.hidden_patterns_input,
+                            )
+                            .on_input(Message::HiddenPatternsInput)
+                            .on_submit(|_| Message::HiddenPatternsSubmit),
+                        ),
+                )
                 .into(),
         ])
         .into()
     }
 
+    /// Open With dialog for files of one type. Without an associated application, only apps for
+    /// the same category of files are shown at first, and the choice is remembered by default.
+    fn open_with_page(&self, paths: Vec<PathBuf>, mime: Mime, fallback: bool) -> DialogPage {
+        DialogPage::OpenWith {
+            paths,
+            mime,
+            selected: 0,
+            store_opt: "x-scheme-handler/mime"
+                .parse::<mime_guess::Mime>()
+                .ok()
+                .and_then(|mime| self.mime_app_cache.get(&mime).first().cloned()),
+            remember: fallback,
+            show_all: !fallback,
+            search: String::new(),
+            custom_command: String::new(),
+        }
+    }
+
     fn get_apps_for_mime(&self, mime_type: &Mime) -> Vec<(&MimeApp, MimeAppMatch)> {
         let mut results = Vec::new();
 
@@ -1782,6 +3960,20 @@ impl App {
             }
         }
 
+        // grab matches for other types in the same category
+        for other_type in self.mime_app_cache.mime_types() {
+            if other_type.type_() != mime_type.type_() {
+                continue;
+            }
+            for mime_app in self.mime_app_cache.get(other_type) {
+                let app_id = &mime_app.id;
+                if !dedupe.contains(app_id) {
+                    results.push((mime_app, MimeAppMatch::Category));
+                    dedupe.insert(app_id);
+                }
+            }
+        }
+
         // Add other apps
         for mime_app in self.mime_app_cache.apps() {
             let app_id = &mime_app.id;
@@ -1885,6 +4077,27 @@ impl Application for App {
         }
 
         let app_themes = vec![fl!("match-desktop"), fl!("dark"), fl!("light")];
+        let compression_level_names = vec![
+            fl!("compression-fast"),
+            fl!("compression-normal"),
+            fl!("compression-best"),
+        ];
+        let trash_max_days_names = TRASH_MAX_DAYS
+            .iter()
+            .map(|&days| match days {
+                0 => fl!("trash-max-days-never"),
+                days => fl!("trash-max-days-after", days = days),
+            })
+            .collect();
+        let trash_max_size_names = TRASH_MAX_SIZES
+            .iter()
+            .map(|&size| match size {
+                0 => fl!("trash-max-size-none"),
+                size => tab::format_size(size),
+            })
+            .collect();
+
+        let hidden_patterns_input = flags.config.hidden_patterns.join(", ");
 
         let key_binds = key_binds(&match flags.mode {
             Mode::App => tab::Mode::App,
@@ -1918,15 +4131,30 @@ impl Application for App {
             config: flags.config,
             mode: flags.mode,
             app_themes,
+            compression_level_names,
+            trash_max_days_names,
+            trash_max_size_names,
             compio_tx,
             context_page: ContextPage::Preview(None, PreviewKind::Selected),
+            cursor_position: Point::ORIGIN,
+            details_resize_opt: None,
+            session_id: SessionWindow::new_id(),
+            details_focused: false,
             dialog_pages: VecDeque::new(),
             dialog_text_input: widget::Id::unique(),
+            frequent_folders: Vec::new(),
+            folder_visits: frequent_folders::load(),
+            folder_visits_save_pending: false,
+            temporary_pins: Vec::new(),
+            hidden_patterns_input,
             key_binds,
+            listing_cache: HashMap::new(),
             margin: HashMap::new(),
             mime_app_cache: MimeAppCache::new(),
+            mime_apps_search: String::new(),
             modifiers: Modifiers::empty(),
             mounter_items: HashMap::new(),
+            mount_progress_opt: None,
             network_drive_connecting: None,
             network_drive_input: String::new(),
             #[cfg(feature = "notify")]
@@ -1934,6 +4162,10 @@ impl Application for App {
             overlap: HashMap::new(),
             pending_operation_id: 0,
             pending_operations: BTreeMap::new(),
+            open_destination_ids: HashSet::new(),
+            operation_queue: OperationQueue::default(),
+            show_operation_queue: false,
+            power_saving: false,
             progress_operations: BTreeSet::new(),
             complete_operations: BTreeMap::new(),
             failed_operations: BTreeMap::new(),
@@ -1953,9 +4185,43 @@ impl Application for App {
             tab_drag_id: DragId::new(),
             auto_scroll_speed: None,
             file_dialog_opt: None,
+            restore_to_items: Vec::new(),
         };
 
-        let mut commands = vec![app.update_config()];
+        let mut commands = vec![app.update_config(), app.purge_trash()];
+
+        if matches!(app.mode, Mode::App) && app.config.restore_session {
+            let session = app.config.session.clone();
+            if let Some(id) = flags.session_window_opt {
+                if let Some(window) = session.iter().find(|window| window.id == id) {
+                    app.session_id = id;
+                    commands.push(app.restore_session_window(window));
+                }
+            } else if flags.locations.is_empty() && !session.iter().any(|window| window.running()) {
+                // Restore the first window here and start a process for each other window
+                for (i, window) in session.iter().enumerate() {
+                    if i == 0 {
+                        app.session_id = window.id;
+                        commands.push(app.restore_session_window(window));
+                        continue;
+                    }
+                    match env::current_exe() {
+                        Ok(exe) => {
+                            if let Err(err) = process::Command::new(&exe)
+                                .arg(format!("--session-window={}", window.id))
+                                .spawn()
+                            {
+                                log::error!("failed to execute {:?}: {}", exe, err);
+                            }
+                        }
+                        Err(err) => {
+                            log::error!("failed to get current executable path: {}", err);
+                        }
+                    }
+                }
+            }
+            commands.push(cosmic::task::message(Message::SaveSession(false)));
+        }
 
         for location in flags.locations {
             if let Some(path) = location.path_opt() {
@@ -2074,6 +4340,24 @@ impl Application for App {
                 NavMenuAction::RemoveFromSidebar(entity),
             ));
         }
+        if self.nav_model.data::<TemporaryPin>(entity).is_some() {
+            items.push(cosmic::widget::menu::Item::Button(
+                fl!("unpin"),
+                None,
+                NavMenuAction::Unpin(entity),
+            ));
+        }
+        if self
+            .nav_model
+            .data::<MounterData>(entity)
+            .map_or(false, |data| data.1.is_mounted())
+        {
+            items.push(cosmic::widget::menu::Item::Button(
+                fl!("unmount"),
+                None,
+                NavMenuAction::Unmount(entity),
+            ));
+        }
         if matches!(location_opt, Some(Location::Trash)) {
             if tab::trash_entries() > 0 {
                 items.push(cosmic::widget::menu::Item::Button(
@@ -2095,6 +4379,12 @@ impl Application for App {
     }
 
     fn on_nav_select(&mut self, entity: Entity) -> Task<Self::Message> {
+        if self.nav_model.data::<AddNetworkDriveData>(entity).is_some() {
+            self.context_page = ContextPage::NetworkDrive;
+            self.set_show_context(true);
+            return Task::none();
+        }
+
         self.nav_model.activate(entity);
         if let Some(location) = self.nav_model.data::<Location>(entity) {
             let should_open = match location {
@@ -2121,17 +4411,27 @@ impl Application for App {
             };
 
             if should_open {
+                let new_tab = match location {
+                    Location::Network(..) => self.config.new_tab.network,
+                    _ => {
+                        self.config.new_tab.drives
+                            && self.nav_model.data::<MounterData>(entity).is_some()
+                    }
+                };
+                let active_location = self
+                    .tab_model
+                    .data::<Tab>(self.tab_model.active())
+                    .map(|tab| &tab.location);
+                if new_tab && active_location != Some(location) {
+                    return self.open_tab(location.clone(), true, None);
+                }
                 let message = Message::TabMessage(None, tab::Message::Location(location.clone()));
                 return self.update(message);
             }
         }
 
         if let Some(data) = self.nav_model.data::<MounterData>(entity) {
-            if let Some(mounter) = MOUNTERS.get(&data.0) {
-                return mounter
-                    .mount(data.1.clone())
-                    .map(|_| cosmic::action::none());
-            }
+            return self.mount(data.0, MountTarget::Item(data.1.clone()));
         }
         Task::none()
     }
@@ -2162,10 +4462,16 @@ impl Application for App {
             return Task::none();
         }
 
+        // Move keyboard focus out of the details pane
+        if self.details_focused {
+            self.details_focused = false;
+            return Task::none();
+        }
+
         // Close gallery mode if open
         if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
             if tab.gallery {
-                tab.gallery = false;
+                tab.close_gallery();
                 return Task::none();
             }
         }
@@ -2263,6 +4569,7 @@ impl Application for App {
                             to,
                             name,
                             archive_type,
+                            level: CompressionLevel::default(),
                             password: None,
                         });
                         return widget::text_input::focus(self.dialog_text_input.clone());
@@ -2276,6 +4583,7 @@ impl Application for App {
                     let show_details = self.config.show_details;
                     self.config = config;
                     self.config.show_details = show_details;
+                    self.hidden_patterns_input = self.config.hidden_patterns.join(", ");
                     return self.update_config();
                 }
             }
@@ -2285,11 +4593,40 @@ impl Application for App {
                         tab.refresh_cut(&[]);
                     }
                 }
-                let paths = self.selected_paths(entity_opt);
-                let contents = ClipboardCopy::new(ClipboardKind::Copy, &paths);
+                let mut paths = self.selected_paths(entity_opt);
+                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+                if let Some(Location::Recents) =
+                    self.tab_model.data::<Tab>(entity).map(|tab| &tab.location)
+                {
+                    // Recents may point at files through links, copy the files themselves
+                    for path in paths.iter_mut() {
+                        if let Ok(real_path) = fs::canonicalize(&*path) {
+                            *path = real_path;
+                        }
+                    }
+                }
+                let contents = if paths.is_empty() {
+                    ClipboardCopy::from_uris(
+                        ClipboardKind::Copy,
+                        &self.selected_network_uris(entity_opt),
+                    )
+                } else {
+                    ClipboardCopy::new(ClipboardKind::Copy, &paths)
+                };
                 return clipboard::write_data(contents);
             }
             Message::CursorMoved(pos) => {
+                self.cursor_position = pos;
+                if let Some(resize) = &mut self.details_resize_opt {
+                    // The pane grows when its edge moves away from the side it is placed on
+                    let delta = match self.config.details_position {
+                        DetailsPosition::Left => pos.x - resize.start_x,
+                        DetailsPosition::Right => resize.start_x - pos.x,
+                    };
+                    resize.width = (f32::from(resize.start_width) + delta)
+                        .clamp(DETAILS_WIDTH_MIN.into(), DETAILS_WIDTH_MAX.into())
+                        as u16;
+                }
                 let entity = self.tab_model.active();
                 return self.update(Message::TabMessage(
                     Some(entity),
@@ -2297,11 +4634,153 @@ impl Application for App {
                 ));
             }
             Message::Cut(entity_opt) => {
+                if self.selection_is_fixed(entity_opt) {
+                    return Task::none();
+                }
                 self.set_cut(entity_opt);
                 let paths = self.selected_paths(entity_opt);
-                let contents = ClipboardCopy::new(ClipboardKind::Cut { is_dnd: false }, &paths);
+                let kind = ClipboardKind::Cut { is_dnd: false };
+                let contents = if paths.is_empty() {
+                    ClipboardCopy::from_uris(kind, &self.selected_network_uris(entity_opt))
+                } else {
+                    ClipboardCopy::new(kind, &paths)
+                };
                 return clipboard::write_data(contents);
             }
+            Message::AuditLog => {
+                self.dialog_pages
+                    .push_back(DialogPage::AuditLog { entries_opt: None });
+                return Task::perform(
+                    async move {
+                        match tokio::task::spawn_blocking(|| {
+                            audit_log::read(audit_log::AUDIT_LOG_VIEW_ENTRIES)
+                        })
+                        .await
+                        {
+                            Ok(Ok(entries)) => {
+                                cosmic::action::app(Message::AuditLogResult(entries))
+                            }
+                            Ok(Err(err)) => {
+                                log::warn!("failed to read audit log: {}", err);
+                                cosmic::action::app(Message::AuditLogResult(Vec::new()))
+                            }
+                            Err(err) => {
+                                log::warn!("failed to read audit log: {}", err);
+                                cosmic::action::none()
+                            }
+                        }
+                    },
+                    |x| x,
+                );
+            }
+            Message::BulkRename(new_rename) => {
+                if let Some(DialogPage::BulkRename {
+                    paths,
+                    rename,
+                    preview,
+                }) = self.dialog_pages.front_mut()
+                {
+                    *preview = new_rename.preview(paths);
+                    *rename = new_rename;
+                }
+            }
+            Message::ChangeExtension(entity_opt) => {
+                if self.selection_is_fixed(entity_opt) {
+                    return Task::none();
+                }
+                let mut paths = self.selected_paths(entity_opt);
+                paths.retain(|path| !path.is_dir());
+                if paths.is_empty() {
+                    return Task::none();
+                }
+                let change = ChangeExtension::default();
+                self.dialog_pages.push_back(DialogPage::ChangeExtension {
+                    paths: paths.clone(),
+                    preview: change.preview(&paths),
+                    change,
+                    contents_opt: None,
+                    mismatches: HashMap::new(),
+                });
+                let contents_task = Task::perform(
+                    async move {
+                        let content_paths = paths.clone();
+                        match tokio::task::spawn_blocking(move || {
+                            let mut contents = HashMap::new();
+                            for path in content_paths {
+                                match mime_icon::content_mime(&path) {
+                                    Ok(Some(mime)) => {
+                                        contents.insert(path, mime);
+                                    }
+                                    Ok(None) => {}
+                                    Err(err) => {
+                                        log::warn!(
+                                            "failed to read contents of {:?}: {}",
+                                            path,
+                                            err
+                                        );
+                                    }
+                                }
+                            }
+                            contents
+                        })
+                        .await
+                        {
+                            Ok(contents) => cosmic::action::app(Message::ChangeExtensionContents(
+                                paths, contents,
+                            )),
+                            Err(err) => {
+                                log::warn!("failed to find types of files: {}", err);
+                                cosmic::action::none()
+                            }
+                        }
+                    },
+                    |x| x,
+                );
+                return Task::batch([
+                    widget::text_input::focus(self.dialog_text_input.clone()),
+                    contents_task,
+                ]);
+            }
+            Message::ChangeExtensionInput(new_change) => {
+                if let Some(DialogPage::ChangeExtension {
+                    paths,
+                    change,
+                    preview,
+                    contents_opt,
+                    mismatches,
+                }) = self.dialog_pages.front_mut()
+                {
+                    *preview = new_change.preview(paths);
+                    if let Some(contents) = contents_opt {
+                        *mismatches = new_change.mismatches(contents, mime_icon::extension_fits);
+                    }
+                    *change = new_change;
+                }
+            }
+            Message::ChangeExtensionContents(content_paths, contents) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::ChangeExtension {
+                        paths,
+                        change,
+                        contents_opt,
+                        mismatches,
+                        ..
+                    } = dialog_page
+                    {
+                        if *paths == content_paths {
+                            *mismatches = change.mismatches(&contents, mime_icon::extension_fits);
+                            *contents_opt = Some(contents.clone());
+                        }
+                    }
+                }
+            }
+            Message::AuditLogResult(entries) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::AuditLog { entries_opt } = dialog_page {
+                        *entries_opt = Some(entries.clone());
+                    }
+                }
+            }
             Message::CloseToast(id) => {
                 self.toasts.remove(id);
             }
@@ -2341,11 +4820,18 @@ impl Application for App {
                                 }
                             }
                         }
+                        Location::Computer => {}
                         _ => {
                             let paths = self.selected_paths(entity_opt);
                             if !paths.is_empty() {
                                 return self.delete(paths);
                             }
+                            // Network locations have no trash
+                            let uris = self.selected_network_uris(entity_opt);
+                            if !uris.is_empty() {
+                                self.dialog_pages
+                                    .push_back(DialogPage::NetworkDelete { uris });
+                            }
                         }
                     }
                 }
@@ -2377,30 +4863,143 @@ impl Application for App {
                 self.windows.insert(id, WindowKind::DesktopViewOptions);
                 return command.map(|_id| cosmic::action::none());
             }
+            Message::FocusDetails => {
+                if self.details_focused {
+                    self.details_focused = false;
+                } else {
+                    self.details_focused = true;
+                    // Open the details pane to move focus into it
+                    if !self.details_visible() {
+                        self.context_page = ContextPage::Preview(None, PreviewKind::Selected);
+                        self.core.window.show_context = true;
+                        return cosmic::task::message(Message::SetShowDetails(true));
+                    }
+                }
+            }
+            Message::DetailsResizeStart => {
+                self.details_resize_opt = Some(DetailsResize {
+                    start_x: self.cursor_position.x,
+                    start_width: self.config.details_width,
+                    width: self.config.details_width,
+                });
+            }
+            Message::DetailsTab(details_tab) => {
+                config_set!(details_tab, details_tab);
+            }
             Message::DialogCancel => {
                 self.dialog_pages.pop_front();
             }
             Message::DialogComplete => {
                 if let Some(dialog_page) = self.dialog_pages.pop_front() {
                     match dialog_page {
+                        DialogPage::AuditLog { .. } => {}
+                        DialogPage::BulkRename { preview, .. } => {
+                            let Ok(preview) = preview else {
+                                return Task::none();
+                            };
+                            return Task::batch(
+                                preview
+                                    .into_iter()
+                                    .filter(|preview| preview.changed())
+                                    .map(|preview| {
+                                        self.operation(Operation::Rename {
+                                            from: preview.from,
+                                            to: preview.to,
+                                        })
+                                    })
+                                    .collect::<Vec<_>>(),
+                            );
+                        }
+                        DialogPage::ChangeExtension { preview, .. } => {
+                            return Task::batch(
+                                preview
+                                    .into_iter()
+                                    .filter(|preview| preview.changed())
+                                    .map(|preview| {
+                                        self.operation(Operation::Rename {
+                                            from: preview.from,
+                                            to: preview.to,
+                                        })
+                                    })
+                                    .collect::<Vec<_>>(),
+                            );
+                        }
                         DialogPage::Compress {
                             paths,
                             to,
                             name,
                             archive_type,
+                            level,
                             password,
                         } => {
                             let extension = archive_type.extension();
                             let name = format!("{}{}", name, extension);
                             let to = to.join(name);
+                            let password = password.filter(|_| archive_type.has_password());
                             return self.operation(Operation::Compress {
                                 paths,
                                 to,
                                 archive_type,
+                                level,
                                 password,
                             });
                         }
-                        DialogPage::EmptyTrash => {
+                        DialogPage::EditTags { path, tags } => {
+                            match tag::write_tags(&path, &tags) {
+                                Ok(()) => {
+                                    // Update items in place, there is no need to rescan
+                                    let entities: Vec<_> = self.tab_model.iter().collect();
+                                    for entity in entities {
+                                        let Some(items) = self
+                                            .tab_model
+                                            .data_mut::<Tab>(entity)
+                                            .and_then(|tab| tab.items_opt_mut())
+                                        else {
+                                            continue;
+                                        };
+                                        for item in items.iter_mut() {
+                                            if item.path_opt() == Some(&path) {
+                                                item.tags.clone_from(&tags);
+                                            }
+                                        }
+                                    }
+
+                                    // Tags may have started or stopped being used
+                                    self.update_nav_model();
+                                    let mut commands = Vec::new();
+                                    let entities: Vec<_> = self.tab_model.iter().collect();
+                                    for entity in entities {
+                                        if let Some(Location::Tag(tag)) = self
+                                            .tab_model
+                                            .data::<Tab>(entity)
+                                            .map(|tab| tab.location.clone())
+                                        {
+                                            commands.push(self.rescan_tab(
+                                                entity,
+                                                Location::Tag(tag),
+                                                None,
+                                            ));
+                                        }
+                                    }
+                                    return Task::batch(commands);
+                                }
+                                Err(err) => {
+                                    log::warn!("failed to set tags of {:?}: {}", path, err);
+                                }
+                            }
+                        }
+                        DialogPage::EjectBusy { .. } => {}
+                        DialogPage::EmptyTrash {
+                            summary_opt,
+                            older_only,
+                        } => {
+                            if older_only {
+                                if let Some(summary) = summary_opt {
+                                    return self.operation(Operation::DeleteTrash {
+                                        items: summary.old_items,
+                                    });
+                                }
+                            }
                             return self.operation(Operation::EmptyTrash);
                         }
                         DialogPage::FailedOperation(id) => {
@@ -2418,15 +5017,6 @@ impl Application for App {
                             };
                             return self.operation(new_op);
                         }
-                        DialogPage::MountError {
-                            mounter_key,
-                            item,
-                            error: _,
-                        } => {
-                            if let Some(mounter) = MOUNTERS.get(&mounter_key) {
-                                return mounter.mount(item).map(|_| cosmic::action::none());
-                            }
-                        }
                         DialogPage::NetworkAuth {
                             mounter_key: _,
                             uri: _,
@@ -2441,17 +5031,6 @@ impl Application for App {
                                 |x| x,
                             );
                         }
-                        DialogPage::NetworkError {
-                            mounter_key: _,
-                            uri,
-                            error: _,
-                        } => {
-                            //TODO: re-use mounter_key?
-                            return Task::batch([
-                                self.update(Message::NetworkDriveInput(uri)),
-                                self.update(Message::NetworkDriveSubmit),
-                            ]);
-                        }
                         DialogPage::NewItem { parent, name, dir } => {
                             let path = parent.join(name);
                             return self.operation(if dir {
@@ -2460,60 +5039,129 @@ impl Application for App {
                                 Operation::NewFile { path }
                             });
                         }
+                        DialogPage::OpenAll { files, folders, .. } => {
+                            return self.open_files_and_folders(files, folders);
+                        }
                         DialogPage::OpenWith {
-                            path,
+                            paths,
                             mime,
                             selected,
+                            remember,
+                            custom_command,
                             ..
                         } => {
-                            let available_apps = self.get_apps_for_mime(&mime);
-
-                            if let Some((app, _)) = available_apps.get(selected) {
-                                if let Some(mut command) =
-                                    app.command(&[&path]).and_then(|v| v.into_iter().next())
-                                {
-                                    match spawn_detached(&mut command) {
-                                        Ok(()) => {
+                            if !custom_command.trim().is_empty() {
+                                match mime_app::custom_command(&custom_command, &paths) {
+                                    Some(commands) => {
+                                        for mut command in commands {
+                                            if let Err(err) = spawn_detached(&mut command) {
+                                                log::warn!(
+                                                    "failed to open {:?} with {:?}: {}",
+                                                    paths,
+                                                    custom_command,
+                                                    err
+                                                )
+                                            }
+                                        }
+                                        for path in paths.iter() {
                                             let _ = recently_used_xbel::update_recently_used(
-                                                &path,
+                                                path,
                                                 App::APP_ID.to_string(),
                                                 "cosmic-files".to_string(),
                                                 None,
                                             );
                                         }
-                                        Err(err) => {
+                                    }
+                                    None => {
+                                        log::warn!(
+                                            "failed to open {:?} with {:?}: failed to parse command",
+                                            paths,
+                                            custom_command
+                                        );
+                                    }
+                                }
+                                return Task::none();
+                            }
+
+                            let available_apps = self.get_apps_for_mime(&mime);
+
+                            let mut remember_opt = None;
+                            if let Some((app, _)) = available_apps.get(selected) {
+                                if let Some(commands) = app.command(&paths) {
+                                    for mut command in commands {
+                                        if let Err(err) = spawn_detached(&mut command) {
                                             log::warn!(
                                                 "failed to open {:?} with {:?}: {}",
-                                                path,
+                                                paths,
                                                 app.id,
                                                 err
                                             )
                                         }
                                     }
+                                    for path in paths.iter() {
+                                        let _ = recently_used_xbel::update_recently_used(
+                                            path,
+                                            App::APP_ID.to_string(),
+                                            "cosmic-files".to_string(),
+                                            None,
+                                        );
+                                    }
+                                    if remember {
+                                        remember_opt = Some(app.id.clone());
+                                    }
                                 } else {
                                     log::warn!(
                                         "failed to open {:?} with {:?}: failed to get command",
-                                        path,
+                                        paths,
                                         app.id
                                     );
                                 }
                             }
+                            if let Some(id) = remember_opt {
+                                self.mime_app_cache.set_default(mime, id);
+                            }
+                        }
+                        DialogPage::NetworkDelete { uris } => {
+                            return self.operation(Operation::NetworkDelete { uris });
+                        }
+                        DialogPage::NetworkRename { from, name, .. } => {
+                            let to_opt = operation::network::parent_uri(&from)
+                                .and_then(|parent| operation::network::child_uri(&parent, &name));
+                            if let Some(to) = to_opt {
+                                if to != from {
+                                    return self.operation(Operation::NetworkRename { from, to });
+                                }
+                            }
                         }
-                        DialogPage::PermanentlyDelete { paths } => {
+                        DialogPage::PermanentlyDelete { paths, .. } => {
                             return self.operation(Operation::PermanentlyDelete { paths });
                         }
+                        DialogPage::PreviewChanges { operations, .. } => {
+                            return Task::batch(
+                                operations
+                                    .into_iter()
+                                    .map(|operation| self.operation(operation))
+                                    .collect::<Vec<_>>(),
+                            );
+                        }
                         DialogPage::RenameItem {
                             from, parent, name, ..
                         } => {
-                            let to = parent.join(name);
-                            return self.operation(Operation::Rename { from, to });
+                            if let Some(operation) = rename_operation(&from, &parent, &name) {
+                                return self.operation(operation);
+                            }
                         }
                         DialogPage::Replace { .. } => {
                             log::warn!("replace dialog should be completed with replace result");
                         }
+                        DialogPage::SecureDelete { paths } => {
+                            return self.operation(Operation::SecureDelete { paths });
+                        }
+                        DialogPage::SelectionStats { .. } => {}
                         DialogPage::SetExecutableAndLaunch { path } => {
                             return self.operation(Operation::SetExecutableAndLaunch { path });
                         }
+                        DialogPage::VerifyChecksum { .. } => {}
                         DialogPage::FavoritePathError { entity, .. } => {
                             if let Some(FavoriteIndex(favorite_i)) =
                                 self.nav_model.data::<FavoriteIndex>(entity)
@@ -2530,6 +5178,56 @@ impl Application for App {
             Message::DialogPush(dialog_page) => {
                 self.dialog_pages.push_back(dialog_page);
             }
+            Message::CompressDrop(data_opt) => {
+                if let (Some(data), Some(DialogPage::Compress { paths, to, .. })) =
+                    (data_opt, self.dialog_pages.front_mut())
+                {
+                    for path in data.paths {
+                        // The archive is created in this folder, and must not include itself
+                        if to.starts_with(&path) {
+                            continue;
+                        }
+                        // Items are stored by their names, which must be unique in the archive
+                        if paths
+                            .iter()
+                            .any(|existing| existing.file_name() == path.file_name())
+                        {
+                            continue;
+                        }
+                        paths.push(path);
+                    }
+                }
+            }
+            Message::CompressTo => {
+                if let Some(DialogPage::Compress { to, .. }) = self.dialog_pages.front() {
+                    let (mut dialog, dialog_task) = Dialog::new(
+                        DialogKind::OpenFolder,
+                        Some(to.clone()),
+                        Message::FileDialogMessage,
+                        Message::CompressToResult,
+                    );
+                    let set_title_task = dialog.set_title(fl!("compress-to-title"));
+                    dialog.set_accept_label(fl!("compress-here"));
+                    dialog.set_location_check(not_writable_reason);
+                    self.windows
+                        .insert(dialog.window_id(), WindowKind::FileDialog(None));
+                    self.file_dialog_opt = Some(dialog);
+                    return Task::batch([set_title_task, dialog_task]);
+                }
+            }
+            Message::CompressToResult(result) => {
+                if let Some(file_dialog) = self.file_dialog_opt.take() {
+                    self.windows.remove(&file_dialog.window_id());
+                }
+                if let DialogResult::Open(selected_paths) = result {
+                    if let (Some(folder), Some(DialogPage::Compress { to, .. })) = (
+                        selected_paths.into_iter().next(),
+                        self.dialog_pages.front_mut(),
+                    ) {
+                        *to = folder;
+                    }
+                }
+            }
             Message::DialogUpdate(dialog_page) => {
                 if !self.dialog_pages.is_empty() {
                     self.dialog_pages[0] = dialog_page;
@@ -2541,6 +5239,48 @@ impl Application for App {
                     self.update(Message::DialogComplete),
                 ]);
             }
+            Message::EjectBusyProcesses(name, processes) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::EjectBusy {
+                        item,
+                        processes_opt,
+                        ..
+                    } = dialog_page
+                    {
+                        if item.name() == name {
+                            *processes_opt = Some(processes.clone());
+                        }
+                    }
+                }
+            }
+            Message::EjectOpenLocation(path) => {
+                // Working folders are opened, and files are selected in their folder
+                let (location, selection_paths) = if path.is_dir() {
+                    (Location::Path(path), None)
+                } else {
+                    match path.parent() {
+                        Some(parent) => (Location::Path(parent.to_path_buf()), Some(vec![path])),
+                        None => return Task::none(),
+                    }
+                };
+                return self.open_tab(location, true, selection_paths);
+            }
+            Message::EjectRetry(force) => {
+                if let Some(DialogPage::EjectBusy {
+                    mounter_key,
+                    item,
+                    ejecting,
+                    ..
+                }) = self.dialog_pages.front_mut()
+                {
+                    if let Some(mounter) = MOUNTERS.get(mounter_key) {
+                        *ejecting = true;
+                        return mounter
+                            .unmount(item.clone(), force)
+                            .map(|_| cosmic::action::none());
+                    }
+                }
+            }
             Message::ExtractHere(entity_opt) => {
                 let paths = self.selected_paths(entity_opt);
                 if let Some(destination) = paths
@@ -2570,6 +5310,7 @@ impl Application for App {
                     );
                     let set_title_task = dialog.set_title(fl!("extract-to-title"));
                     dialog.set_accept_label(fl!("extract-here"));
+                    dialog.set_location_check(not_writable_reason);
                     self.windows
                         .insert(dialog.window_id(), WindowKind::FileDialog(Some(paths)));
                     self.file_dialog_opt = Some(dialog);
@@ -2608,6 +5349,20 @@ impl Application for App {
             }
             Message::Key(modifiers, key, text) => {
                 let entity = self.tab_model.active();
+
+                // Plain keys control the media preview while the details pane has focus
+                if self.details_focused && self.details_visible() && !modifiers.control() {
+                    let tab_message_opt = match &key {
+                        Key::Named(Named::Space) => Some(tab::Message::MediaPlayPause),
+                        Key::Named(Named::ArrowLeft) => Some(tab::Message::MediaSeek(false)),
+                        Key::Named(Named::ArrowRight) => Some(tab::Message::MediaSeek(true)),
+                        _ => None,
+                    };
+                    if let Some(tab_message) = tab_message_opt {
+                        return self.update(Message::TabMessage(Some(entity), tab_message));
+                    }
+                }
+
                 for (key_bind, action) in self.key_binds.iter() {
                     if key_bind.matches(modifiers, &key) {
                         return self.update(action.message(Some(entity)));
@@ -2727,6 +5482,9 @@ impl Application for App {
                 // Insert new items
                 self.mounter_items.insert(mounter_key, mounter_items);
 
+                // Shares may have reconnected
+                commands.push(self.update(Message::OfflineRetry));
+
                 // Update nav bar
                 //TODO: this could change favorites IDs while they are in use
                 self.update_nav_model();
@@ -2736,22 +5494,68 @@ impl Application for App {
 
                 return Task::batch(commands);
             }
-            Message::MountResult(mounter_key, item, res) => match res {
-                Ok(true) => {
-                    log::info!("connected to {:?}", item);
+            Message::MountCancel => {
+                if let Some(progress) = self.mount_progress_opt.take() {
+                    if let Some(mounter) = MOUNTERS.get(&progress.mounter_key) {
+                        return mounter.cancel().map(|_| cosmic::action::none());
+                    }
                 }
-                Ok(false) => {
-                    log::info!("cancelled connection to {:?}", item);
+            }
+            Message::MimeAppsSearch(search) => {
+                self.mime_apps_search = search;
+            }
+            Message::MimeCategoryDefault(category, index) => {
+                let primary_mime = category.primary_mime();
+                if let Some(app) = self.mime_app_cache.get(&primary_mime).get(index) {
+                    let id = app.id.clone();
+                    // Only types the application can open are changed
+                    let mut mimes = vec![primary_mime.clone()];
+                    for mime in self.mime_app_cache.mime_types() {
+                        if category.contains(mime)
+                            && *mime != primary_mime
+                            && self.mime_app_cache.get(mime).iter().any(|app| app.id == id)
+                        {
+                            mimes.push(mime.clone());
+                        }
+                    }
+                    self.mime_app_cache.set_defaults(&mimes, &id);
                 }
-                Err(error) => {
-                    log::warn!("failed to connect to {:?}: {}", item, error);
-                    self.dialog_pages.push_back(DialogPage::MountError {
-                        mounter_key,
-                        item,
-                        error,
-                    });
+            }
+            Message::MimeDefault(mime, index) => {
+                if let Some(app) = self.mime_app_cache.get(&mime).get(index) {
+                    let id = app.id.clone();
+                    //TODO: this will block for a few ms, run in background?
+                    self.mime_app_cache.set_default(mime, id);
                 }
-            },
+            }
+            Message::MimeDefaultReset(mime) => {
+                self.mime_app_cache.reset_default(mime);
+            }
+            Message::MountDismiss => {
+                self.mount_progress_opt = None;
+            }
+            Message::MountResult(mounter_key, item, res) => {
+                let name = item.name();
+                match res {
+                    Ok(true) => {
+                        log::info!("connected to {:?}", item);
+                        self.mount_progress_finish(mounter_key, &name, None);
+                    }
+                    Ok(false) => {
+                        log::info!("cancelled connection to {:?}", item);
+                        self.mount_progress_finish(mounter_key, &name, None);
+                    }
+                    Err(error) => {
+                        log::warn!("failed to connect to {:?}: {}", item, error);
+                        self.mount_progress_finish(mounter_key, &name, Some(error));
+                    }
+                }
+            }
+            Message::MountRetry => {
+                if let Some(progress) = self.mount_progress_opt.take() {
+                    return self.mount(progress.mounter_key, progress.target);
+                }
+            }
             Message::NetworkAuth(mounter_key, uri, auth, auth_tx) => {
                 self.dialog_pages.push_back(DialogPage::NetworkAuth {
                     mounter_key,
@@ -2761,17 +5565,56 @@ impl Application for App {
                 });
                 return widget::text_input::focus(self.dialog_text_input.clone());
             }
+            Message::FrequentFolderPin(path) => {
+                let mut frequent_pinned = self.config.frequent_pinned.clone();
+                if let Some(index) = frequent_pinned.iter().position(|x| *x == path) {
+                    frequent_pinned.remove(index);
+                } else {
+                    frequent_pinned.push(path);
+                }
+                config_set!(frequent_pinned, frequent_pinned);
+                self.update_frequent_folders();
+            }
+            Message::FrequentFolderRemove(path) => {
+                self.folder_visits.remove(&path);
+                let mut frequent_pinned = self.config.frequent_pinned.clone();
+                frequent_pinned.retain(|x| *x != path);
+                config_set!(frequent_pinned, frequent_pinned);
+                self.update_frequent_folders();
+                return self.save_folder_visits_later();
+            }
+            Message::HiddenPatternsInput(input) => {
+                self.hidden_patterns_input = input;
+            }
+            Message::HiddenPatternsSubmit => {
+                let hidden_patterns: Vec<String> = self
+                    .hidden_patterns_input
+                    .split(',')
+                    .map(|pattern| pattern.trim())
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(|pattern| pattern.to_string())
+                    .collect();
+                self.hidden_patterns_input = hidden_patterns.join(", ");
+                if hidden_patterns != self.config.hidden_patterns {
+                    config_set!(hidden_patterns, hidden_patterns);
+                    return self.update_config();
+                }
+            }
+            Message::NetworkChanged(uris) => {
+                return self.rescan_network(&uris);
+            }
             Message::NetworkDriveInput(input) => {
                 self.network_drive_input = input;
             }
             Message::NetworkDriveSubmit => {
-                //TODO: know which mounter to use for network drives
-                for (mounter_key, mounter) in MOUNTERS.iter() {
-                    self.network_drive_connecting =
-                        Some((*mounter_key, self.network_drive_input.clone()));
-                    return mounter
-                        .network_drive(self.network_drive_input.clone())
-                        .map(|_| cosmic::action::none());
+                // Mounters that read the files of a location themselves are preferred
+                let mounter_key_opt = mounter::network_files(&self.network_drive_input)
+                    .or_else(|| MOUNTERS.keys().next().copied());
+                if let Some(mounter_key) = mounter_key_opt {
+                    return self.mount(
+                        mounter_key,
+                        MountTarget::Uri(self.network_drive_input.clone()),
+                    );
                 }
                 log::warn!(
                     "no mounter found for connecting to {:?}",
@@ -2785,27 +5628,47 @@ impl Application for App {
                 match res {
                     Ok(true) => {
                         log::info!("connected to {:?}", uri);
+                        self.mount_progress_finish(mounter_key, &uri, None);
                         if matches!(self.context_page, ContextPage::NetworkDrive) {
                             self.set_show_context(false);
                         }
+                        // There is no mount to show, so the location is browsed directly
+                        if mounter::network_files(&uri).is_some() {
+                            if let Some(location) = tab::network_location(&uri) {
+                                return self.open_tab(location, true, None);
+                            }
+                        }
                     }
                     Ok(false) => {
                         log::info!("cancelled connection to {:?}", uri);
+                        self.mount_progress_finish(mounter_key, &uri, None);
                     }
                     Err(error) => {
                         log::warn!("failed to connect to {:?}: {}", uri, error);
-                        self.dialog_pages.push_back(DialogPage::NetworkError {
-                            mounter_key,
-                            uri,
-                            error,
-                        });
+                        self.mount_progress_finish(mounter_key, &uri, Some(error));
                     }
                 }
             }
+            Message::NewTabConfig(config) => {
+                if config != self.config.new_tab {
+                    config_set!(new_tab, config);
+                }
+            }
             Message::NewItem(entity_opt, dir) => {
                 let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                 if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
                     if let Some(path) = &tab.location.path_opt() {
+                        if dir {
+                            // Folders are created right away and then renamed when revealed
+                            let name = fl!("untitled-folder");
+                            let mut new_path = path.join(&name);
+                            let mut number = 2;
+                            while new_path.exists() {
+                                new_path = path.join(format!("{} {}", name, number));
+                                number += 1;
+                            }
+                            return self.operation(Operation::NewFolder { path: new_path });
+                        }
                         self.dialog_pages.push_back(DialogPage::NewItem {
                             parent: path.to_path_buf(),
                             name: String::new(),
@@ -2826,6 +5689,11 @@ impl Application for App {
                 let entities: Vec<_> = self.tab_model.iter().collect();
                 for entity in entities {
                     if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
+                        for event in events.iter() {
+                            for event_path in event.paths.iter() {
+                                tab.forget_dir_sizes(event_path);
+                            }
+                        }
                         if let Some(path) = &tab.location.path_opt() {
                             let mut contains_change = false;
                             for event in events.iter() {
@@ -2896,8 +5764,23 @@ impl Application for App {
                     log::warn!("message did not contain notify watcher");
                 }
             },
+            Message::OfflineRetry => {
+                let offline: Vec<_> = self
+                    .tab_model
+                    .iter()
+                    .filter_map(|entity| {
+                        let tab = self.tab_model.data::<Tab>(entity)?;
+                        tab.offline.then(|| (entity, tab.location.clone()))
+                    })
+                    .collect();
+                let commands: Vec<_> = offline
+                    .into_iter()
+                    .map(|(entity, location)| self.rescan_tab(entity, location, None))
+                    .collect();
+                return Task::batch(commands);
+            }
             Message::OpenTerminal(entity_opt) => {
-                if let Some(terminal) = self.mime_app_cache.terminal() {
+                if self.mime_app_cache.terminal().is_some() {
                     let mut paths = Vec::new();
                     let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                     if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
@@ -2917,59 +5800,61 @@ impl Application for App {
                         }
                     }
                     for path in paths {
-                        if let Some(mut command) = terminal
-                            .command::<&str>(&[])
-                            .and_then(|v| v.into_iter().next())
-                        {
-                            command.current_dir(&path);
-                            if let Err(err) = spawn_detached(&mut command) {
-                                log::warn!(
-                                    "failed to open {:?} with terminal {:?}: {}",
-                                    path,
-                                    terminal.id,
-                                    err
-                                )
-                            }
-                        } else {
-                            log::warn!("failed to get command for {:?}", terminal.id);
-                        }
+                        self.open_terminal(&path, None);
+                    }
+                }
+            }
+            Message::OpenTerminalWithFile(entity_opt) => {
+                for path in self.selected_paths(entity_opt) {
+                    if path.is_dir() {
+                        continue;
+                    }
+                    if let Some(parent) = path.parent() {
+                        self.open_terminal(parent, Some(&path));
                     }
                 }
             }
             Message::OpenInNewTab(entity_opt) => {
-                return Task::batch(self.selected_paths(entity_opt).into_iter().filter_map(
-                    |path| {
-                        if path.is_dir() {
-                            Some(self.open_tab(Location::Path(path), false, None))
-                        } else {
-                            None
-                        }
-                    },
-                ))
+                return Task::batch(
+                    self.selected_or_location_paths(entity_opt)
+                        .into_iter()
+                        .filter_map(|path| {
+                            if path.is_dir() {
+                                Some(self.open_tab(Location::Path(path), false, None))
+                            } else {
+                                None
+                            }
+                        }),
+                )
             }
-            Message::OpenInNewWindow(entity_opt) => match env::current_exe() {
-                Ok(exe) => self
-                    .selected_paths(entity_opt)
+            Message::OpenInNewWindow(entity_opt) => {
+                let commands: Vec<_> = self
+                    .selected_or_location_paths(entity_opt)
                     .into_iter()
                     .filter(|p| p.is_dir())
-                    .for_each(|path| match process::Command::new(&exe).arg(path).spawn() {
-                        Ok(_child) => {}
-                        Err(err) => {
-                            log::error!("failed to execute {:?}: {}", exe, err);
-                        }
-                    }),
-                Err(err) => {
-                    log::error!("failed to get current executable path: {}", err);
-                }
-            },
+                    .map(|path| self.open_window(vec![path.into_os_string()]))
+                    .collect();
+                return Task::batch(commands);
+            }
             Message::OpenItemLocation(entity_opt) => {
-                return Task::batch(self.selected_paths(entity_opt).into_iter().filter_map(
-                    |path| {
-                        path.parent().map(Path::to_path_buf).map(|parent| {
-                            self.open_tab(Location::Path(parent), true, Some(vec![path]))
-                        })
-                    },
-                ))
+                // Open each parent once, selecting all of its items
+                let mut parents: Vec<(PathBuf, Vec<PathBuf>)> = Vec::new();
+                for path in self.selected_paths(entity_opt) {
+                    let Some(parent) = path.parent().map(Path::to_path_buf) else {
+                        continue;
+                    };
+                    match parents.iter_mut().find(|(other, _)| *other == parent) {
+                        Some((_, paths)) => paths.push(path),
+                        None => parents.push((parent, vec![path])),
+                    }
+                }
+                // Multiple locations are opened in the background
+                let activate = parents.len() == 1;
+                let mut commands = Vec::with_capacity(parents.len());
+                for (parent, paths) in parents {
+                    commands.push(self.open_tab(Location::Path(parent), activate, Some(paths)));
+                }
+                return Task::batch(commands);
             }
             Message::OpenWithBrowse => match self.dialog_pages.pop_front() {
                 Some(DialogPage::OpenWith {
@@ -2998,6 +5883,13 @@ impl Application for App {
                 }
                 None => {}
             },
+            Message::OpenWithCustomCommand(value) => {
+                if let Some(DialogPage::OpenWith { custom_command, .. }) =
+                    self.dialog_pages.front_mut()
+                {
+                    *custom_command = value;
+                }
+            }
             Message::OpenWithDialog(entity_opt) => {
                 let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                 if let Some(tab) = self.tab_model.data::<Tab>(entity) {
@@ -3009,57 +5901,216 @@ impl Application for App {
                             let Some(path) = item.path_opt() else {
                                 continue;
                             };
-                            return self.update(Message::DialogPush(DialogPage::OpenWith {
-                                path: path.to_path_buf(),
-                                mime: item.mime.clone(),
-                                selected: 0,
-                                store_opt: "x-scheme-handler/mime"
-                                    .parse::<mime_guess::Mime>()
-                                    .ok()
-                                    .and_then(|mime| {
-                                        self.mime_app_cache.get(&mime).first().cloned()
-                                    }),
-                            }));
+                            let page = self.open_with_page(
+                                vec![path.to_path_buf()],
+                                item.mime.clone(),
+                                false,
+                            );
+                            return self.update(Message::DialogPush(page));
+                        }
+                    }
+                }
+            }
+            Message::VerifyChecksum(entity_opt) => {
+                let path_opt = self
+                    .selected_paths(entity_opt)
+                    .into_iter()
+                    .find(|path| path.is_file());
+                if let Some(path) = path_opt {
+                    self.dialog_pages.push_back(DialogPage::VerifyChecksum {
+                        path,
+                        expected: String::new(),
+                        controller_opt: None,
+                        result_opt: None,
+                    });
+                    return widget::text_input::focus(self.dialog_text_input.clone());
+                }
+            }
+            Message::VerifyChecksumFile => {
+                let parent_opt = self.dialog_pages.front().and_then(|page| match page {
+                    DialogPage::VerifyChecksum { path, .. } => path.parent().map(Path::to_path_buf),
+                    _ => None,
+                });
+                if let Some(parent) = parent_opt {
+                    let (mut dialog, dialog_task) = Dialog::new(
+                        DialogKind::OpenFile,
+                        Some(parent),
+                        Message::FileDialogMessage,
+                        Message::VerifyChecksumFileResult,
+                    );
+                    let set_title_task = dialog.set_title(fl!("load-checksum-file"));
+                    self.windows
+                        .insert(dialog.window_id(), WindowKind::FileDialog(None));
+                    self.file_dialog_opt = Some(dialog);
+                    return Task::batch([set_title_task, dialog_task]);
+                }
+            }
+            Message::VerifyChecksumFileResult(result) => {
+                if let Some(file_dialog) = self.file_dialog_opt.take() {
+                    self.windows.remove(&file_dialog.window_id());
+                }
+                if let DialogResult::Open(selected_paths) = result {
+                    if let Some(DialogPage::VerifyChecksum {
+                        path,
+                        expected,
+                        controller_opt,
+                        result_opt,
+                    }) = self.dialog_pages.front_mut()
+                    {
+                        if let Some(checksum_path) = selected_paths.into_iter().next() {
+                            let name = path
+                                .file_name()
+                                .map(|name| name.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+                            *controller_opt = None;
+                            match fs::read_to_string(&checksum_path) {
+                                Ok(contents) => {
+                                    match checksum::parse_checksum_file(&contents, &name) {
+                                        Some(checksum) => {
+                                            *expected = checksum;
+                                            return self.update(Message::VerifyChecksumStart);
+                                        }
+                                        None => {
+                                            *result_opt =
+                                                Some(Err(fl!("checksum-not-found", name = name)));
+                                        }
+                                    }
+                                }
+                                Err(err) => {
+                                    *result_opt = Some(Err(err.to_string()));
+                                }
+                            }
                         }
                     }
                 }
             }
+            Message::VerifyChecksumInput(value) => {
+                if let Some(DialogPage::VerifyChecksum {
+                    expected,
+                    controller_opt,
+                    result_opt,
+                    ..
+                }) = self.dialog_pages.front_mut()
+                {
+                    *expected = value;
+                    *controller_opt = None;
+                    *result_opt = None;
+                }
+            }
+            Message::VerifyChecksumResult(checksum, result) => {
+                if let Some(DialogPage::VerifyChecksum {
+                    expected,
+                    controller_opt,
+                    result_opt,
+                    ..
+                }) = self.dialog_pages.front_mut()
+                {
+                    // Results for a checksum that was changed since are ignored
+                    if *expected == checksum && controller_opt.is_some() {
+                        *controller_opt = None;
+                        *result_opt = Some(result);
+                    }
+                }
+            }
+            Message::VerifyChecksumStart => {
+                if let Some(DialogPage::VerifyChecksum {
+                    path,
+                    expected,
+                    controller_opt,
+                    result_opt,
+                }) = self.dialog_pages.front_mut()
+                {
+                    if controller_opt.is_some() || expected.trim().is_empty() {
+                        return Task::none();
+                    }
+                    let controller = Controller::default();
+                    let path = path.clone();
+                    let checksum = expected.clone();
+                    let task_controller = controller.clone();
+                    *controller_opt = Some(controller);
+                    *result_opt = None;
+                    return Task::perform(
+                        async move {
+                            let result = {
+                                let checksum = checksum.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    checksum::verify(&path, &checksum, &task_controller)
+                                })
+                                .await
+                                .unwrap_or_else(|err| Err(err.to_string()))
+                            };
+                            cosmic::action::app(Message::VerifyChecksumResult(checksum, result))
+                        },
+                        |x| x,
+                    );
+                }
+            }
+            Message::EditTags(entity_opt) => {
+                if let Some(path) = self.selected_paths(entity_opt).into_iter().next() {
+                    let tags = tag::read_tags(&path);
+                    self.dialog_pages
+                        .push_back(DialogPage::EditTags { path, tags });
+                }
+            }
+            Message::OpenWithRemember(value) => {
+                if let Some(DialogPage::OpenWith { remember, .. }) = self.dialog_pages.front_mut() {
+                    *remember = value;
+                }
+            }
+            Message::OpenWithSearch(value) => {
+                if let Some(DialogPage::OpenWith { search, .. }) = self.dialog_pages.front_mut() {
+                    *search = value;
+                }
+            }
             Message::OpenWithSelection(index) => {
-                if let Some(DialogPage::OpenWith { selected, .. }) = self.dialog_pages.front_mut() {
+                if let Some(DialogPage::OpenWith {
+                    selected,
+                    custom_command,
+                    ..
+                }) = self.dialog_pages.front_mut()
+                {
                     *selected = index;
+                    custom_command.clear();
+                }
+            }
+            Message::OpenWithShowAll => {
+                if let Some(DialogPage::OpenWith { show_all, .. }) = self.dialog_pages.front_mut() {
+                    *show_all = true;
                 }
             }
             Message::Paste(entity_opt) => {
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
-                if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
-                    if let Some(path) = tab.location.path_opt() {
-                        let to = path.clone();
-                        return clipboard::read_data::<ClipboardPaste>().map(move |contents_opt| {
-                            match contents_opt {
-                                Some(contents) => cosmic::action::app(Message::PasteContents(
-                                    to.clone(),
-                                    contents,
-                                )),
-                                None => cosmic::action::none(),
-                            }
-                        });
-                    }
+                if let Some(task) = self.paste_network(entity_opt) {
+                    return task;
                 }
+                return self.paste(entity_opt, |to, contents| {
+                    Message::PasteContents(to, contents, None)
+                });
             }
-            Message::PasteContents(to, mut contents) => {
-                contents.paths.retain(|p| p != &to);
-                if !contents.paths.is_empty() {
-                    return match contents.kind {
-                        ClipboardKind::Copy => self.operation(Operation::Copy {
-                            paths: contents.paths,
-                            to,
-                        }),
-                        ClipboardKind::Cut { is_dnd } => self.operation(Operation::Move {
-                            paths: contents.paths,
-                            to,
-                            cross_device_copy: is_dnd,
-                        }),
-                    };
+            Message::PastePreview(entity_opt) => {
+                return self.paste(entity_opt, Message::PastePreviewContents);
+            }
+            Message::PasteWith(entity_opt, conflict_policy) => {
+                // Pastes into network folders never replace items
+                if let Some(task) = self.paste_network(entity_opt) {
+                    return task;
+                }
+                return self.paste(entity_opt, move |to, contents| {
+                    Message::PasteContents(to, contents, Some(conflict_policy))
+                });
+            }
+            Message::PasteContents(to, contents, conflict_policy_opt) => {
+                if let Some(operation) = paste_operation(to, contents) {
+                    return self.operation_with_conflict_policy(operation, conflict_policy_opt);
+                }
+            }
+            Message::PasteNetwork(to, contents) => {
+                if let Some(operation) = network_paste_operation(to, contents) {
+                    return self.operation(operation);
+                }
+            }
+            Message::PastePreviewContents(to, contents) => {
+                if let Some(operation) = paste_operation(to, contents) {
+                    return self.update(Message::PreviewChanges(vec![operation]));
                 }
             }
             Message::PendingCancel(id) => {
@@ -3076,7 +6127,20 @@ impl Application for App {
             }
             Message::PendingComplete(id, op_sel) => {
                 let mut commands = Vec::with_capacity(4);
+                let mut reveal_path_opt = None;
+                let mut open_destination_opt = None;
+                self.operation_queue_finish(id);
                 if let Some((op, _)) = self.pending_operations.remove(&id) {
+                    if let Operation::NewFolder { ref path } = op {
+                        reveal_path_opt = Some(path.clone());
+                    }
+                    if self.open_destination_ids.remove(&id) {
+                        open_destination_opt = op.destination().map(Path::to_path_buf);
+                    }
+
+                    commands.push(self.audit_log(&op, Ok(&op_sel)));
+                    commands.push(self.rescan_network(&op.network_folders()));
+
                     // Show toast for some operations
                     if let Some(description) = op.toast() {
                         if let Operation::Delete { ref paths } = op {
@@ -3133,7 +6197,30 @@ impl Application for App {
                 // Potentially show a notification
                 commands.push(self.update_notification());
                 // Rescan and select based on operation
-                commands.push(self.rescan_operation_selection(op_sel));
+                match (reveal_path_opt, open_destination_opt) {
+                    (Some(path), _) => {
+                        // New folders have a placeholder name, so they are renamed right away
+                        let revealed = self
+                            .tab_model
+                            .data::<Tab>(self.tab_model.active())
+                            .and_then(|tab| tab.location.path_opt())
+                            .is_some_and(|location| path.parent() == Some(location.as_path()));
+                        if let Some(dialog_page) =
+                            revealed.then(|| rename_dialog(path.clone())).flatten()
+                        {
+                            self.dialog_pages.push_back(dialog_page);
+                            commands
+                                .push(widget::text_input::focus(self.dialog_text_input.clone()));
+                        }
+                        commands.push(self.reveal_path(path));
+                    }
+                    (None, Some(to)) => commands.push(self.open_tab(
+                        Location::Path(to),
+                        true,
+                        Some(op_sel.selected),
+                    )),
+                    (None, None) => commands.push(self.rescan_operation_selection(op_sel)),
+                }
                 // Manually rescan any trash tabs after any operation is completed
                 commands.push(self.rescan_trash());
                 return Task::batch(commands);
@@ -3142,6 +6229,9 @@ impl Application for App {
                 self.progress_operations.clear();
             }
             Message::PendingError(id, err) => {
+                let mut audit_task = Task::none();
+                self.operation_queue_finish(id);
+                self.open_destination_ids.remove(&id);
                 if let Some((op, controller)) = self.pending_operations.remove(&id) {
                     // Only show dialog if not cancelled
                     if !controller.is_cancelled() {
@@ -3155,6 +6245,11 @@ impl Application for App {
                     }
                     // Remove from progress
                     self.progress_operations.remove(&id);
+                    // Items may have changed before the operation failed
+                    audit_task = Task::batch([
+                        self.audit_log(&op, Err(err.to_string())),
+                        self.rescan_network(&op.network_folders()),
+                    ]);
                     self.failed_operations
                         .insert(id, (op, controller, err.to_string()));
                 }
@@ -3167,7 +6262,16 @@ impl Application for App {
                     self.progress_operations.clear();
                 }
                 // Manually rescan any trash tabs after any operation is completed
-                return self.rescan_trash();
+                return Task::batch([audit_task, self.rescan_trash()]);
+            }
+            Message::PendingOpenDestination(id, open) => {
+                if open {
+                    if self.pending_operations.contains_key(&id) {
+                        self.open_destination_ids.insert(id);
+                    }
+                } else {
+                    self.open_destination_ids.remove(&id);
+                }
             }
             Message::PendingPause(id, pause) => {
                 if let Some((_, controller)) = self.pending_operations.get(&id) {
@@ -3187,13 +6291,102 @@ impl Application for App {
                     }
                 }
             }
+            Message::PendingReorder(id, earlier) => {
+                self.operation_queue.reorder(id, earlier);
+            }
+            Message::PreviewChanges(operations) => {
+                // Replace the dialog the preview was opened from
+                if matches!(
+                    self.dialog_pages.front(),
+                    Some(
+                        DialogPage::BulkRename { .. }
+                            | DialogPage::ChangeExtension { .. }
+                            | DialogPage::PermanentlyDelete { .. }
+                            | DialogPage::RenameItem { .. }
+                            | DialogPage::SecureDelete { .. }
+                    )
+                ) {
+                    self.dialog_pages.pop_front();
+                }
+                self.dialog_pages.push_front(DialogPage::PreviewChanges {
+                    operations: operations.clone(),
+                    changes_opt: None,
+                });
+                return Task::perform(
+                    async move {
+                        match tokio::task::spawn_blocking(
+                            move || -> Result<Vec<PlannedChange>, String> {
+                                let mut changes = Vec::new();
+                                for operation in operations.iter() {
+                                    changes
+                                        .extend(operation.plan().map_err(|err| err.to_string())?);
+                                }
+                                Ok(changes)
+                            },
+                        )
+                        .await
+                        {
+                            Ok(result) => {
+                                cosmic::action::app(Message::PreviewChangesResult(result))
+                            }
+                            Err(err) => {
+                                log::warn!("failed to plan operation: {}", err);
+                                cosmic::action::none()
+                            }
+                        }
+                    },
+                    |x| x,
+                );
+            }
+            Message::PreviewChangesResult(result) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::PreviewChanges { changes_opt, .. } = dialog_page {
+                        *changes_opt = Some(result.clone());
+                    }
+                }
+            }
             Message::PermanentlyDelete(entity_opt) => {
                 let paths = self.selected_paths(entity_opt);
-                if !paths.is_empty() {
+                if !paths.is_empty() && !self.selection_is_fixed(entity_opt) {
+                    return self.permanently_delete_dialog(paths);
+                }
+                let uris = self.selected_network_uris(entity_opt);
+                if !uris.is_empty() {
+                    self.dialog_pages
+                        .push_back(DialogPage::NetworkDelete { uris });
+                }
+            }
+            Message::SecureDelete(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() && !self.selection_is_fixed(entity_opt) {
                     self.dialog_pages
-                        .push_back(DialogPage::PermanentlyDelete { paths });
+                        .push_back(DialogPage::SecureDelete { paths });
+                }
+            }
+            Message::PermanentlyDeleteStats(stats_paths, stats) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::PermanentlyDelete { paths, stats_opt } = dialog_page {
+                        if *paths == stats_paths {
+                            *stats_opt = Some(stats.clone());
+                        }
+                    }
+                }
+            }
+            Message::StripMetadata(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    return self.operation(Operation::StripMetadata { paths });
+                }
+            }
+            Message::TransformImages(entity_opt, transform) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    return self.operation(Operation::TransformImages { paths, transform });
                 }
             }
+            Message::PowerSaving(power_saving) => {
+                self.power_saving = power_saving;
+            }
             Message::Preview(entity_opt) => {
                 match self.mode {
                     Mode::App => {
@@ -3236,6 +6429,13 @@ impl Application for App {
                     }
                 }
             }
+            Message::PurgeTrash => {
+                return self.purge_trash();
+            }
+            Message::PurgeTrashItems(items) => {
+                log::info!("deleting {} old items from the trash", items.len());
+                return self.operation(Operation::DeleteTrash { items });
+            }
             Message::RescanTrash => {
                 // Update trash icon if empty/full
                 let maybe_entity = self.nav_model.iter().find(|&entity| {
@@ -3252,6 +6452,9 @@ impl Application for App {
                 return Task::batch([self.rescan_trash(), self.update_desktop()]);
             }
             Message::Rename(entity_opt) => {
+                if self.selection_is_fixed(entity_opt) {
+                    return Task::none();
+                }
                 let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                 if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
                     if let Some(items) = tab.items_opt() {
@@ -3263,29 +6466,41 @@ impl Application for App {
                                 }
                             }
                         }
-                        if !selected.is_empty() {
-                            //TODO: batch rename
-                            for path in selected {
-                                let parent = match path.parent() {
-                                    Some(some) => some.to_path_buf(),
-                                    None => continue,
-                                };
-                                let name = match path.file_name().and_then(|x| x.to_str()) {
-                                    Some(some) => some.to_string(),
-                                    None => continue,
-                                };
-                                let dir = path.is_dir();
-                                self.dialog_pages.push_back(DialogPage::RenameItem {
-                                    from: path,
-                                    parent,
-                                    name,
-                                    dir,
-                                });
-                            }
+                        if selected.len() > 1 {
+                            let rename = BulkRename::default();
+                            let preview = rename.preview(&selected);
+                            self.dialog_pages.push_back(DialogPage::BulkRename {
+                                paths: selected,
+                                rename,
+                                preview,
+                            });
+                            return widget::text_input::focus(self.dialog_text_input.clone());
+                        } else if !selected.is_empty() {
+                            self.dialog_pages
+                                .extend(selected.into_iter().filter_map(rename_dialog));
                             return widget::text_input::focus(self.dialog_text_input.clone());
                         }
                     }
                 }
+                // Items on network locations without paths are renamed one at a time
+                let uris = self.selected_network_uris(entity_opt);
+                if let [from] = uris.as_slice() {
+                    let dir = self
+                        .tab_model
+                        .data::<Tab>(entity)
+                        .and_then(|tab| tab.items_opt())
+                        .is_some_and(|items| {
+                            items
+                                .iter()
+                                .any(|item| item.selected && item.metadata.is_dir())
+                        });
+                    self.dialog_pages.push_back(DialogPage::NetworkRename {
+                        from: from.clone(),
+                        name: operation::network::uri_name(from).into_owned(),
+                        dir,
+                    });
+                    return widget::text_input::focus(self.dialog_text_input.clone());
+                }
             }
             Message::ReplaceResult(replace_result) => {
                 if let Some(dialog_page) = self.dialog_pages.pop_front() {
@@ -3306,60 +6521,239 @@ impl Application for App {
                     }
                 }
             }
+            Message::ResizeEnd => {
+                if let Some(resize) = self.details_resize_opt.take() {
+                    config_set!(details_width, resize.width);
+                }
+                let entity = self.tab_model.active();
+                return self.update(Message::TabMessage(
+                    Some(entity),
+                    tab::Message::ColumnResizeEnd,
+                ));
+            }
+            Message::RestoreElsewhere => {
+                let parent_opt = self.dialog_pages.front().and_then(|page| match page {
+                    DialogPage::Replace { to, .. } => to
+                        .path_opt()
+                        .and_then(|path| path.parent())
+                        .map(Path::to_path_buf),
+                    _ => None,
+                });
+                if let Some(parent) = parent_opt {
+                    let (mut dialog, dialog_task) = Dialog::new(
+                        DialogKind::OpenFolder,
+                        Some(parent),
+                        Message::FileDialogMessage,
+                        Message::RestoreElsewhereResult,
+                    );
+                    let set_title_task = dialog.set_title(fl!("restore-elsewhere-title"));
+                    dialog.set_accept_label(fl!("restore-here"));
+                    dialog.set_location_check(not_writable_reason);
+                    self.windows
+                        .insert(dialog.window_id(), WindowKind::FileDialog(None));
+                    self.file_dialog_opt = Some(dialog);
+                    return Task::batch([set_title_task, dialog_task]);
+                }
+            }
+            Message::RestoreElsewhereResult(result) => {
+                if let Some(file_dialog) = self.file_dialog_opt.take() {
+                    self.windows.remove(&file_dialog.window_id());
+                }
+                if let DialogResult::Open(selected_paths) = result {
+                    if let Some(parent) = selected_paths.into_iter().next() {
+                        return self
+                            .update(Message::ReplaceResult(ReplaceResult::Elsewhere(parent)));
+                    }
+                }
+            }
             Message::RestoreFromTrash(entity_opt) => {
-                let mut trash_items = Vec::new();
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
-                if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
-                    if let Some(items) = tab.items_opt() {
-                        for item in items.iter() {
-                            if item.selected {
-                                match &item.metadata {
-                                    ItemMetadata::Trash { entry, .. } => {
-                                        trash_items.push(entry.clone());
-                                    }
-                                    _ => {
-                                        //TODO: error on trying to restore non-trash file?
-                                    }
+                let trash_items = self.selected_trash_items(entity_opt);
+                if !trash_items.is_empty() {
+                    return self.restore_from_trash(trash_items);
+                }
+            }
+            Message::RestoreTo(entity_opt) => {
+                let trash_items = self.selected_trash_items(entity_opt);
+                if !trash_items.is_empty() {
+                    return self.restore_to(trash_items, fl!("restore-elsewhere-title"));
+                }
+            }
+            Message::RestoreToResult(result) => {
+                if let Some(file_dialog) = self.file_dialog_opt.take() {
+                    self.windows.remove(&file_dialog.window_id());
+                }
+                let trash_items = std::mem::take(&mut self.restore_to_items);
+                if let DialogResult::Open(selected_paths) = result {
+                    if let Some(parent) = selected_paths.into_iter().next() {
+                        let items = trash_items
+                            .into_iter()
+                            .map(|mut item| {
+                                item.original_parent = parent.clone();
+                                item
+                            })
+                            .collect();
+                        return self.operation(Operation::Restore { items });
+                    }
+                }
+            }
+            Message::ScrollTab(scroll_speed) => {
+                let entity = self.tab_model.active();
+                return self.update(Message::TabMessage(
+                    Some(entity),
+                    tab::Message::ScrollTab((scroll_speed as f32) / 10.0),
+                ));
+            }
+            Message::SelectionStats(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    self.dialog_pages
+                        .push_back(DialogPage::SelectionStats { stats_opt: None });
+                    // Folders are walked to find their size, which may take a while
+                    return Task::perform(
+                        async move {
+                            match tokio::task::spawn_blocking(move || tab::selection_stats(&paths))
+                                .await
+                            {
+                                Ok(stats) => {
+                                    cosmic::action::app(Message::SelectionStatsResult(stats))
+                                }
+                                Err(err) => {
+                                    log::warn!("failed to summarize selection: {}", err);
+                                    cosmic::action::none()
                                 }
                             }
-                        }
+                        },
+                        |x| x,
+                    );
+                }
+            }
+            Message::SelectionStatsResult(stats) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::SelectionStats { stats_opt } = dialog_page {
+                        *stats_opt = Some(stats.clone());
                     }
                 }
-                if !trash_items.is_empty() {
-                    return self.operation(Operation::Restore { items: trash_items });
+            }
+            Message::SearchActivate => {
+                return if self.search_get().is_none() {
+                    self.search_set_active(Some(String::new()))
+                } else {
+                    widget::text_input::focus(self.search_id.clone())
+                };
+            }
+            Message::SearchClear => {
+                return self.search_set_active(None);
+            }
+            Message::SearchInput(input) => {
+                return self.search_set_active(Some(input));
+            }
+            Message::SetAuditLog(audit_log) => {
+                config_set!(audit_log, audit_log);
+            }
+            Message::SetConflictPolicy(conflict_policy) => {
+                config_set!(conflict_policy, conflict_policy);
+            }
+            Message::SetDetailsPosition(details_position) => {
+                config_set!(details_position, details_position);
+            }
+            Message::SetReduceBackgroundWork(reduce_background_work) => {
+                config_set!(reduce_background_work, reduce_background_work);
+            }
+            Message::SetTrashMaxDays(trash_max_days) => {
+                config_set!(trash_max_days, trash_max_days);
+                return self.purge_trash();
+            }
+            Message::SetTrashMaxSize(trash_max_size) => {
+                config_set!(trash_max_size, trash_max_size);
+                return self.purge_trash();
+            }
+            Message::SetExtractOnOpen(extract_on_open) => {
+                config_set!(extract_on_open, extract_on_open);
+            }
+            Message::SaveFolderVisits => {
+                self.folder_visits_save_pending = false;
+                let folder_visits = self.folder_visits.clone();
+                return Task::perform(
+                    async move {
+                        match tokio::task::spawn_blocking(move || {
+                            frequent_folders::save(&folder_visits)
+                        })
+                        .await
+                        {
+                            Ok(Ok(())) => {}
+                            Ok(Err(err)) => log::warn!("failed to save folder visits: {}", err),
+                            Err(err) => log::warn!("failed to save folder visits: {}", err),
+                        }
+                        cosmic::action::none()
+                    },
+                    |x| x,
+                );
+            }
+            Message::SaveSession(closing) => {
+                if matches!(self.mode, Mode::App) && self.config.restore_session {
+                    let session = self.session(closing);
+                    config_set!(session, session);
                 }
             }
-            Message::ScrollTab(scroll_speed) => {
-                let entity = self.tab_model.active();
-                return self.update(Message::TabMessage(
-                    Some(entity),
-                    tab::Message::ScrollTab((scroll_speed as f32) / 10.0),
-                ));
+            Message::SetFolderColumns(path, columns) => {
+                let mut folder_columns = self.config.folder_columns.clone();
+                if columns == HeadingOptions::DEFAULT_COLUMNS {
+                    folder_columns.remove(&path);
+                } else {
+                    folder_columns.insert(path, columns);
+                }
+                config_set!(folder_columns, folder_columns);
+                return self.update_config();
             }
-            Message::SearchActivate => {
-                return if self.search_get().is_none() {
-                    self.search_set_active(Some(String::new()))
+            Message::SetFolderColumnWidths(path, column_widths) => {
+                let mut folder_column_widths = self.config.folder_column_widths.clone();
+                if column_widths == self.config.tab.column_widths {
+                    folder_column_widths.remove(&path);
                 } else {
-                    widget::text_input::focus(self.search_id.clone())
-                };
+                    folder_column_widths.insert(path, column_widths);
+                }
+                config_set!(folder_column_widths, folder_column_widths);
+                return self.update_config();
             }
-            Message::SearchClear => {
-                return self.search_set_active(None);
+            Message::SetOpenDestination(open_destination) => {
+                config_set!(open_destination, open_destination);
+                return self.update_config();
             }
-            Message::SearchInput(input) => {
-                return self.search_set_active(Some(input));
+            Message::SetRestoreSession(restore_session) => {
+                config_set!(restore_session, restore_session);
+                if restore_session {
+                    return self.update(Message::SaveSession(false));
+                }
+                config_set!(session, Vec::new());
             }
             Message::SetShowDetails(show_details) => {
                 config_set!(show_details, show_details);
                 return self.update_config();
             }
+            Message::SetShowFrequentFolders(show_frequent_folders) => {
+                config_set!(show_frequent_folders, show_frequent_folders);
+            }
+            Message::SetToolbar(toolbar) => {
+                config_set!(toolbar, toolbar);
+            }
             Message::SetTypeToSearch(type_to_search) => {
                 config_set!(type_to_search, type_to_search);
                 return self.update_config();
             }
+            Message::SetWriteThroughRemovable(write_through_removable) => {
+                config_set!(write_through_removable, write_through_removable);
+                return self.update_config();
+            }
             Message::SystemThemeModeChange(_theme_mode) => {
                 return self.update_config();
             }
+            Message::TrashSummary(trash_summary) => {
+                for dialog_page in self.dialog_pages.iter_mut() {
+                    if let DialogPage::EmptyTrash { summary_opt, .. } = dialog_page {
+                        *summary_opt = Some(trash_summary.clone());
+                    }
+                }
+            }
             Message::TabActivate(entity) => {
                 self.tab_model.activate(entity);
 
@@ -3439,11 +6833,75 @@ impl Application for App {
                     return self.update_config();
                 }
             }
+            Message::ToggleFolderSizes => {
+                let mut config = self.config.tab;
+                config.folder_sizes = !config.folder_sizes;
+                return self.update(Message::TabConfig(config));
+            }
+            Message::ToggleFavorite(entity_opt) => {
+                let paths = self.selected_paths(entity_opt);
+                if !paths.is_empty() {
+                    let mut starred = self.config.starred.clone();
+                    // Unstar only when everything selected is already starred
+                    if paths.iter().all(|path| starred.contains(path)) {
+                        for path in paths.iter() {
+                            starred.remove(path);
+                        }
+                    } else {
+                        starred.extend(paths);
+                    }
+                    config_set!(starred, starred);
+                    return self.update_config();
+                }
+            }
             Message::ToggleFoldersFirst => {
                 let mut config = self.config.tab;
                 config.folders_first = !config.folders_first;
                 return self.update(Message::TabConfig(config));
             }
+            Message::TogglePin(entity_opt) => {
+                let paths = self.selected_or_location_paths(entity_opt);
+                if !paths.is_empty() && paths.iter().all(|path| self.temporary_pins.contains(path))
+                {
+                    self.temporary_pins.retain(|pin| !paths.contains(pin));
+                } else {
+                    for path in paths {
+                        // Folders in the sidebar already stay there
+                        let favorite = Favorite::from_path(path.clone());
+                        if !self.temporary_pins.contains(&path)
+                            && !self.config.favorites.contains(&favorite)
+                        {
+                            self.temporary_pins.push(path);
+                        }
+                    }
+                }
+                self.update_nav_model();
+            }
+            Message::ToggleGroupByKind => {
+                let mut config = self.config.tab;
+                config.group_by_kind = !config.group_by_kind;
+                return self.update(Message::TabConfig(config));
+            }
+            Message::ToggleOperationQueue => {
+                self.show_operation_queue =
+                    !self.show_operation_queue && !self.operation_queue.is_empty();
+            }
+            Message::ToggleKeepBoth(entity_opt) => {
+                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
+                if let Some(Location::Path(path)) = self
+                    .tab_model
+                    .data::<Tab>(entity)
+                    .map(|tab| tab.location.clone())
+                {
+                    let mut keep_both_folders = self.config.keep_both_folders.clone();
+                    if keep_both_folders.contains(&path) {
+                        keep_both_folders.retain(|folder| folder != &path);
+                    } else {
+                        keep_both_folders.push(path);
+                    }
+                    config_set!(keep_both_folders, keep_both_folders);
+                }
+            }
             Message::TabMessage(entity_opt, tab_message) => {
                 let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
 
@@ -3453,11 +6911,32 @@ impl Application for App {
                     self.set_show_context(false);
                 }
 
+                let location_context_menu =
+                    matches!(tab_message, tab::Message::LocationContextMenuIndex(_));
+
                 let tab_commands = match self.tab_model.data_mut::<Tab>(entity) {
                     Some(tab) => tab.update(tab_message, self.modifiers),
                     _ => Vec::new(),
                 };
 
+                // Breadcrumbs that are mount points can be unmounted from their context menu
+                if location_context_menu {
+                    if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
+                        let path_opt = tab.location_context_menu_index.and_then(|ancestor_index| {
+                            tab.location
+                                .path_opt()
+                                .and_then(|path| path.ancestors().nth(ancestor_index))
+                                .map(Path::to_path_buf)
+                        });
+                        tab.location_context_menu_mount = path_opt.and_then(|path| {
+                            self.mounter_items.values().flatten().find_map(|item| {
+                                (item.is_mounted() && item.path().as_ref() == Some(&path))
+                                    .then(|| item.clone())
+                            })
+                        });
+                    }
+                }
+
                 let mut commands = Vec::new();
                 for tab_command in tab_commands {
                     match tab_command {
@@ -3486,9 +6965,35 @@ impl Application for App {
                                 self.auto_scroll_speed = None;
                             }
                         }
+                        tab::Command::ColumnWidths(Some(path), column_widths) => {
+                            commands.push(
+                                self.update(Message::SetFolderColumnWidths(path, column_widths)),
+                            );
+                        }
+                        tab::Command::ColumnWidths(None, column_widths) => {
+                            let mut config = self.config.tab;
+                            config.column_widths = column_widths;
+                            commands.push(self.update(Message::TabConfig(config)));
+                        }
                         tab::Command::ChangeLocation(tab_title, tab_path, selection_paths) => {
                             self.activate_nav_model_location(&tab_path);
 
+                            // Visits are only recorded while the bar is shown
+                            let visited_opt = match &tab_path {
+                                Location::Path(path) if self.config.show_frequent_folders => {
+                                    Some(path)
+                                }
+                                _ => None,
+                            };
+                            if let Some(path) = visited_opt {
+                                let now_secs = time::SystemTime::now()
+                                    .duration_since(time::UNIX_EPOCH)
+                                    .map_or(0, |x| x.as_secs());
+                                frequent_folders::visit(&mut self.folder_visits, path, now_secs);
+                                self.update_frequent_folders();
+                                commands.push(self.save_folder_visits_later());
+                            }
+
                             self.tab_model.text_set(entity, tab_title);
                             commands.push(Task::batch([
                                 self.update_title(),
@@ -3496,12 +7001,43 @@ impl Application for App {
                                 self.update_tab(entity, tab_path, selection_paths),
                             ]));
                         }
+                        tab::Command::Compress(paths, archive) => {
+                            if let Some(to) = archive.parent() {
+                                let name = paths
+                                    .first()
+                                    .and_then(|path| path.file_stem())
+                                    .and_then(|stem| stem.to_str())
+                                    .unwrap_or_default()
+                                    .to_string();
+                                self.dialog_pages.push_back(DialogPage::Compress {
+                                    paths,
+                                    to: to.to_path_buf(),
+                                    name,
+                                    archive_type: ArchiveType::from_path(&archive)
+                                        .unwrap_or_default(),
+                                    level: CompressionLevel::default(),
+                                    password: None,
+                                });
+                                commands.push(widget::text_input::focus(
+                                    self.dialog_text_input.clone(),
+                                ));
+                            }
+                        }
                         tab::Command::Delete(paths) => commands.push(self.delete(paths)),
+                        tab::Command::EditTags(path) => {
+                            let tags = tag::read_tags(&path);
+                            self.dialog_pages
+                                .push_back(DialogPage::EditTags { path, tags });
+                        }
                         tab::Command::DropFiles(to, from) => {
-                            commands.push(self.update(Message::PasteContents(to, from)));
+                            commands.push(self.update(Message::PasteContents(to, from, None)));
                         }
                         tab::Command::EmptyTrash => {
-                            self.dialog_pages.push_back(DialogPage::EmptyTrash);
+                            self.dialog_pages.push_back(DialogPage::EmptyTrash {
+                                summary_opt: None,
+                                older_only: false,
+                            });
+                            commands.push(self.trash_summary());
                         }
                         #[cfg(feature = "desktop")]
                         tab::Command::ExecEntryAction(entry, action) => {
@@ -3512,21 +7048,19 @@ impl Application for App {
                                 cosmic::action::app(Message::TabMessage(Some(entity), x))
                             }));
                         }
-                        tab::Command::OpenFile(paths) => self.open_file(&paths),
+                        tab::Command::OpenFile(paths) => commands.push(self.open_file(&paths)),
+                        tab::Command::OpenAll(files, folders) => {
+                            commands.push(self.open_all(files, folders));
+                        }
                         tab::Command::OpenInNewTab(path) => {
                             commands.push(self.open_tab(Location::Path(path.clone()), false, None));
                         }
-                        tab::Command::OpenInNewWindow(path) => match env::current_exe() {
-                            Ok(exe) => match process::Command::new(&exe).arg(path).spawn() {
-                                Ok(_child) => {}
-                                Err(err) => {
-                                    log::error!("failed to execute {:?}: {}", exe, err);
-                                }
-                            },
-                            Err(err) => {
-                                log::error!("failed to get current executable path: {}", err);
-                            }
-                        },
+                        tab::Command::OpenInNewWindow(path) => {
+                            commands.push(self.open_window(vec![path.into_os_string()]));
+                        }
+                        tab::Command::OpenInTerminal(path) => {
+                            self.open_terminal(&path, None);
+                        }
                         tab::Command::OpenTrash => {
                             //TODO: use handler for x-scheme-handler/trash and open trash:///
                             let mut command = process::Command::new("cosmic-files");
@@ -3542,13 +7076,85 @@ impl Application for App {
                             self.context_page = ContextPage::Preview(Some(entity), kind);
                             self.set_show_context(true);
                         }
+                        tab::Command::OpenUrl(url) => {
+                            commands.push(self.update(Message::LaunchUrl(url)));
+                        }
+                        tab::Command::OpenWithApp(path, mime, id) => {
+                            let app_opt = self
+                                .mime_app_cache
+                                .get(&mime)
+                                .iter()
+                                .find(|app| app.id == id);
+                            match app_opt.and_then(|app| app.command(&[&path])) {
+                                Some(app_commands) => {
+                                    for mut command in app_commands {
+                                        if let Err(err) = spawn_detached(&mut command) {
+                                            log::warn!(
+                                                "failed to open {:?} with {:?}: {}",
+                                                path,
+                                                id,
+                                                err
+                                            )
+                                        }
+                                    }
+                                    let _ = recently_used_xbel::update_recently_used(
+                                        &path,
+                                        App::APP_ID.to_string(),
+                                        "cosmic-files".to_string(),
+                                        None,
+                                    );
+                                }
+                                None => {
+                                    log::warn!(
+                                        "failed to open {:?} with {:?}: failed to get command",
+                                        path,
+                                        id
+                                    );
+                                }
+                            }
+                        }
                         tab::Command::SetOpenWith(mime, id) => {
                             //TODO: this will block for a few ms, run in background?
                             self.mime_app_cache.set_default(mime, id);
                         }
+                        tab::Command::RestoreFromTrash(items) => {
+                            commands.push(self.restore_from_trash(items));
+                        }
                         tab::Command::SetPermissions(path, mode) => {
                             commands.push(self.operation(Operation::SetPermissions { path, mode }));
                         }
+                        tab::Command::SetFolderColumns(path, columns) => {
+                            commands.push(self.update(Message::SetFolderColumns(path, columns)));
+                        }
+                        tab::Command::SetPermissionsRecursive(path, mode, uid, gid) => {
+                            commands.push(self.operation(Operation::SetPermissionsRecursive {
+                                path,
+                                mode,
+                                uid,
+                                gid,
+                            }));
+                        }
+                        tab::Command::Unmount(path) => {
+                            let mut found = false;
+                            for (key, items) in self.mounter_items.iter() {
+                                let Some(mounter) = MOUNTERS.get(key) else {
+                                    continue;
+                                };
+                                for item in items.iter() {
+                                    if item.is_mounted() && item.path().as_ref() == Some(&path) {
+                                        commands.push(
+                                            mounter
+                                                .unmount(item.clone(), false)
+                                                .map(|_| cosmic::action::none()),
+                                        );
+                                        found = true;
+                                    }
+                                }
+                            }
+                            if !found {
+                                log::warn!("no mounter item found for {:?}", path);
+                            }
+                        }
                         tab::Command::WindowDrag => {
                             if let Some(window_id) = &self.window_id_opt {
                                 commands.push(window::drag(*window_id));
@@ -3574,22 +7180,75 @@ impl Application for App {
             Message::TabRescan(entity, location, parent_item_opt, items, selection_paths) => {
                 if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
                     if location == tab.location {
+                        tab.offline = false;
                         tab.parent_item_opt = parent_item_opt;
                         tab.set_items(items);
                         if let Some(selection_paths) = selection_paths {
                             tab.select_paths(selection_paths);
                         }
-                        return clipboard::read_data::<ClipboardPaste>().map(|p| {
-                            cosmic::action::app(Message::CutPaths(match p {
-                                Some(s) => match s.kind {
-                                    ClipboardKind::Copy => Vec::new(),
-                                    ClipboardKind::Cut { .. } => s.paths,
-                                },
-                                None => Vec::new(),
-                            }))
-                        });
+                        return Task::batch([
+                            clipboard::read_data::<ClipboardPaste>().map(|p| {
+                                cosmic::action::app(Message::CutPaths(match p {
+                                    Some(s) => match s.kind {
+                                        ClipboardKind::Copy => Vec::new(),
+                                        ClipboardKind::Cut { .. } => s.paths,
+                                    },
+                                    None => Vec::new(),
+                                }))
+                            }),
+                            self.update(Message::TabMessage(
+                                Some(entity),
+                                tab::Message::ScrollRestore,
+                            )),
+                            // Reveal after the view has positioned the new items
+                            cosmic::task::message(cosmic::action::app(Message::TabMessage(
+                                Some(entity),
+                                tab::Message::ScrollAnchor,
+                            ))),
+                            cosmic::task::message(cosmic::action::app(Message::TabMessage(
+                                Some(entity),
+                                tab::Message::Reveal,
+                            ))),
+                        ]);
+                    }
+                }
+            }
+            Message::TabRescanRemote(entity, location, res, selection_paths) => {
+                let (parent_item_opt, items, offline) = match res {
+                    Ok((parent_item_opt, items)) => {
+                        self.listing_cache
+                            .insert(location.clone(), (parent_item_opt.clone(), items.clone()));
+                        (parent_item_opt, items, false)
+                    }
+                    Err(err) => {
+                        if let Some(tab) = self.tab_model.data::<Tab>(entity) {
+                            if tab.offline && location == tab.location {
+                                // Still offline, keep the cached listing as it is
+                                return Task::none();
+                            }
+                        }
+                        log::warn!("failed to scan {:?}: {}", location, err);
+                        match self.listing_cache.get(&location) {
+                            Some((parent_item_opt, items)) => {
+                                (parent_item_opt.clone(), items.clone(), true)
+                            }
+                            None => (None, Vec::new(), false),
+                        }
+                    }
+                };
+                let task = self.update(Message::TabRescan(
+                    entity,
+                    location.clone(),
+                    parent_item_opt,
+                    items,
+                    selection_paths,
+                ));
+                if let Some(tab) = self.tab_model.data_mut::<Tab>(entity) {
+                    if location == tab.location {
+                        tab.offline = offline;
                     }
                 }
+                return task;
             }
             Message::TabView(entity_opt, view) => {
                 let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
@@ -3662,9 +7321,78 @@ impl Application for App {
             Message::UndoTrashStart(items) => {
                 return self.operation(Operation::Restore { items });
             }
+            Message::UnmountResult(mounter_key, item, res) => {
+                let name = item.name();
+                let dialog_i_opt = self.dialog_pages.iter().position(|dialog_page| {
+                    matches!(dialog_page, DialogPage::EjectBusy { mounter_key: key, item, .. }
+                        if *key == mounter_key && item.name() == name)
+                });
+                match res {
+                    Ok(_) => {
+                        log::info!("unmounted {:?}", item);
+                        if let Some(dialog_i) = dialog_i_opt {
+                            self.dialog_pages.remove(dialog_i);
+                        }
+                    }
+                    Err(error) if error.kind == MounterErrorKind::Busy => {
+                        log::info!("{:?} is busy: {}", item, error);
+                        match dialog_i_opt.and_then(|dialog_i| self.dialog_pages.get_mut(dialog_i))
+                        {
+                            Some(DialogPage::EjectBusy {
+                                processes_opt,
+                                ejecting,
+                                ..
+                            }) => {
+                                *processes_opt = None;
+                                *ejecting = false;
+                            }
+                            _ => {
+                                self.dialog_pages.push_back(DialogPage::EjectBusy {
+                                    mounter_key,
+                                    item: item.clone(),
+                                    processes_opt: None,
+                                    ejecting: false,
+                                });
+                            }
+                        }
+                        let path_opt = item.path();
+                        return Task::perform(
+                            async move {
+                                let processes = match path_opt {
+                                    Some(path) => tokio::task::spawn_blocking(move || {
+                                        blocking_processes(&path)
+                                    })
+                                    .await
+                                    .unwrap_or_else(|err| {
+                                        log::warn!("failed to find blocking processes: {}", err);
+                                        Vec::new()
+                                    }),
+                                    None => Vec::new(),
+                                };
+                                cosmic::action::app(Message::EjectBusyProcesses(name, processes))
+                            },
+                            |x| x,
+                        );
+                    }
+                    Err(error) => {
+                        log::warn!("failed to unmount {:?}: {}", item, error);
+                        if let Some(dialog_i) = dialog_i_opt {
+                            self.dialog_pages.remove(dialog_i);
+                        }
+                        return self
+                            .toasts
+                            .push(widget::toaster::Toast::new(fl!(
+                                "eject-error",
+                                name = name.as_str()
+                            )))
+                            .map(cosmic::Action::App);
+                    }
+                }
+            }
             Message::WindowClose => {
                 if let Some(window_id) = self.window_id_opt.take() {
                     return Task::batch([
+                        self.update(Message::SaveSession(true)),
                         window::close(window_id),
                         Task::perform(
                             async move { cosmic::action::app(Message::MaybeExit) },
@@ -3682,30 +7410,16 @@ impl Application for App {
             Message::WindowCloseRequested(id) => {
                 self.remove_window(&id);
             }
-            Message::WindowNew => match env::current_exe() {
-                Ok(exe) => match process::Command::new(&exe).spawn() {
-                    Ok(_child) => {}
-                    Err(err) => {
-                        log::error!("failed to execute {:?}: {}", exe, err);
-                    }
-                },
-                Err(err) => {
-                    log::error!("failed to get current executable path: {}", err);
-                }
-            },
+            Message::WindowNew => {
+                return self.open_window(Vec::new());
+            }
+            Message::WindowSpawn(args, token_opt) => {
+                self.spawn_window(&args, token_opt);
+            }
             Message::ZoomDefault(entity_opt) => {
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
-                let mut config = self.config.tab;
-                if let Some(tab) = self.tab_model.data::<Tab>(entity) {
-                    match tab.config.view {
-                        tab::View::List => config.icon_sizes.list = 100.try_into().unwrap(),
-                        tab::View::Grid => config.icon_sizes.grid = 100.try_into().unwrap(),
-                    }
-                }
-                return self.update(Message::TabConfig(config));
+                return self.update(Message::ZoomSet(entity_opt, 100));
             }
             Message::ZoomIn(entity_opt) => {
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                 let zoom_in = |size: &mut NonZeroU16, min: u16, max: u16| {
                     let mut step = min;
                     while step <= max {
@@ -3719,17 +7433,9 @@ impl Application for App {
                         *size = step.try_into().unwrap();
                     }
                 };
-                let mut config = self.config.tab;
-                if let Some(tab) = self.tab_model.data::<Tab>(entity) {
-                    match tab.config.view {
-                        tab::View::List => zoom_in(&mut config.icon_sizes.list, 50, 500),
-                        tab::View::Grid => zoom_in(&mut config.icon_sizes.grid, 50, 500),
-                    }
-                }
-                return self.update(Message::TabConfig(config));
+                return self.zoom(entity_opt, |size| zoom_in(size, 50, 500));
             }
             Message::ZoomOut(entity_opt) => {
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
                 let zoom_out = |size: &mut NonZeroU16, min: u16, max: u16| {
                     let mut step = max;
                     while step >= min {
@@ -3743,14 +7449,12 @@ impl Application for App {
                         *size = step.try_into().unwrap();
                     }
                 };
-                let mut config = self.config.tab;
-                if let Some(tab) = self.tab_model.data::<Tab>(entity) {
-                    match tab.config.view {
-                        tab::View::List => zoom_out(&mut config.icon_sizes.list, 50, 500),
-                        tab::View::Grid => zoom_out(&mut config.icon_sizes.grid, 50, 500),
-                    }
+                return self.zoom(entity_opt, |size| zoom_out(size, 50, 500));
+            }
+            Message::ZoomSet(entity_opt, zoom) => {
+                if let Some(zoom) = NonZeroU16::new(zoom) {
+                    return self.zoom(entity_opt, |size| *size = zoom);
                 }
-                return self.update(Message::TabConfig(config));
             }
             Message::DndEnterNav(entity) => {
                 if let Some(location) = self.nav_model.data::<Location>(entity) {
@@ -3777,7 +7481,9 @@ impl Application for App {
                             ClipboardPaste {
                                 kind,
                                 paths: data.paths,
+                                uris: Vec::new(),
                             },
+                            None,
                         )),
                         Location::Trash if matches!(action, DndAction::Move) => {
                             self.delete(data.paths)
@@ -3837,7 +7543,9 @@ impl Application for App {
                             ClipboardPaste {
                                 kind,
                                 paths: data.paths,
+                                uris: Vec::new(),
                             },
+                            None,
                         )),
                         Location::Trash if matches!(action, DndAction::Move) => {
                             self.delete(data.paths)
@@ -3860,11 +7568,16 @@ impl Application for App {
                     return self.update(Message::TabActivate(entity));
                 }
             }
-            Message::NavBarClose(entity) => {
-                if let Some(data) = self.nav_model.data::<MounterData>(entity) {
+            Message::NavBarClose(entity)
+            | Message::NavMenuAction(NavMenuAction::Unpin(entity))
+            | Message::NavMenuAction(NavMenuAction::Unmount(entity)) => {
+                if let Some(TemporaryPin(pin_i)) = self.nav_model.data::<TemporaryPin>(entity) {
+                    self.temporary_pins.remove(*pin_i);
+                    self.update_nav_model();
+                } else if let Some(data) = self.nav_model.data::<MounterData>(entity) {
                     if let Some(mounter) = MOUNTERS.get(&data.0) {
                         return mounter
-                            .unmount(data.1.clone())
+                            .unmount(data.1.clone(), false)
                             .map(|_| cosmic::action::none());
                     }
                 }
@@ -3886,7 +7599,7 @@ impl Application for App {
                         .and_then(|x| x.path_opt())
                         .map(ToOwned::to_owned)
                     {
-                        self.open_file(&[path]);
+                        return self.open_file(&[path]);
                     }
                 }
                 NavMenuAction::OpenWith(entity) => {
@@ -3898,17 +7611,8 @@ impl Application for App {
                     {
                         match tab::item_from_path(&path, IconSizes::default()) {
                             Ok(item) => {
-                                return self.update(Message::DialogPush(DialogPage::OpenWith {
-                                    path: path.to_path_buf(),
-                                    mime: item.mime.clone(),
-                                    selected: 0,
-                                    store_opt: "x-scheme-handler/mime"
-                                        .parse::<mime_guess::Mime>()
-                                        .ok()
-                                        .and_then(|mime| {
-                                            self.mime_app_cache.get(&mime).first().cloned()
-                                        }),
-                                }));
+                                let page = self.open_with_page(vec![path], item.mime, false);
+                                return self.update(Message::DialogPush(page));
                             }
                             Err(err) => {
                                 log::warn!("failed to get item for path {:?}: {}", path, err);
@@ -3931,6 +7635,15 @@ impl Application for App {
                         Some(Location::Recents) => {
                             return self.open_tab(Location::Recents, false, None);
                         }
+                        Some(Location::Computer) => {
+                            return self.open_tab(Location::Computer, false, None);
+                        }
+                        Some(Location::Favorites) => {
+                            return self.open_tab(Location::Favorites, false, None);
+                        }
+                        Some(Location::Tag(tag)) => {
+                            return self.open_tab(Location::Tag(*tag), false, None);
+                        }
                         Some(Location::Trash) => {
                             return self.open_tab(Location::Trash, false, None);
                         }
@@ -3939,43 +7652,24 @@ impl Application for App {
                 }
 
                 // Open the selected path in a new cosmic-files window.
-                NavMenuAction::OpenInNewWindow(entity) => 'open_in_new_window: {
+                NavMenuAction::OpenInNewWindow(entity) => {
                     if let Some(location) = self.nav_model.data::<Location>(entity) {
-                        match env::current_exe() {
-                            Ok(exe) => {
-                                let mut command = process::Command::new(&exe);
-                                match location {
-                                    Location::Path(path) => {
-                                        command.arg(path);
-                                    }
-                                    Location::Trash => {
-                                        command.arg("--trash");
-                                    }
-                                    Location::Network(..) => {
-                                        command.arg("--network");
-                                    }
-                                    Location::Recents => {
-                                        command.arg("--recents");
-                                    }
-                                    _ => {
-                                        log::error!(
-                                            "unsupported location for open in new window: {:?}",
-                                            location
-                                        );
-                                        break 'open_in_new_window;
-                                    }
-                                };
-                                match command.spawn() {
-                                    Ok(_child) => {}
-                                    Err(err) => {
-                                        log::error!("failed to execute {:?}: {}", exe, err);
-                                    }
-                                };
-                            }
-                            Err(err) => {
-                                log::error!("failed to get current executable path: {}", err);
+                        let arg = match location {
+                            Location::Path(path) => path.clone().into_os_string(),
+                            Location::Trash => "--trash".into(),
+                            Location::Network(..) => "--network".into(),
+                            Location::Recents => "--recents".into(),
+                            Location::Computer => "--computer".into(),
+                            Location::Favorites => "--favorites".into(),
+                            _ => {
+                                log::error!(
+                                    "unsupported location for open in new window: {:?}",
+                                    location
+                                );
+                                return Task::none();
                             }
-                        }
+                        };
+                        return self.open_window(vec![arg]);
                     }
                 }
 
@@ -4012,12 +7706,24 @@ impl Application for App {
                 }
 
                 NavMenuAction::EmptyTrash => {
-                    self.dialog_pages.push_front(DialogPage::EmptyTrash);
+                    self.dialog_pages.push_front(DialogPage::EmptyTrash {
+                        summary_opt: None,
+                        older_only: false,
+                    });
+                    return self.trash_summary();
                 }
+                // Handled together with NavBarClose
+                NavMenuAction::Unpin(_entity) | NavMenuAction::Unmount(_entity) => {}
             },
             Message::Recents => {
                 return self.open_tab(Location::Recents, false, None);
             }
+            Message::Computer => {
+                return self.open_tab(Location::Computer, false, None);
+            }
+            Message::Favorites => {
+                return self.open_tab(Location::Favorites, false, None);
+            }
             #[cfg(feature = "wayland")]
             Message::OutputEvent(output_event, output) => {
                 match output_event {
@@ -4164,6 +7870,19 @@ impl Application for App {
                 Message::ToggleContextPage(ContextPage::EditHistory),
             )
             .title(fl!("edit-history")),
+            ContextPage::MimeApps => context_drawer::context_drawer(
+                self.mime_apps(),
+                Message::ToggleContextPage(ContextPage::MimeApps),
+            )
+            .title(fl!("file-associations"))
+            .header(
+                widget::text_input::search_input(
+                    fl!("file-associations-search"),
+                    &self.mime_apps_search,
+                )
+                .on_input(Message::MimeAppsSearch)
+                .on_clear(Message::MimeAppsSearch(String::new())),
+            ),
             ContextPage::NetworkDrive => {
                 let mut text_input =
                     widget::text_input(fl!("enter-server-address"), &self.network_drive_input);
@@ -4186,28 +7905,8 @@ impl Application for App {
                     button.into(),
                 ]))
             }
-            ContextPage::Preview(entity_opt, kind) => {
-                let mut actions = Vec::with_capacity(3);
-                let entity = entity_opt.unwrap_or_else(|| self.tab_model.active());
-                if let Some(tab) = self.tab_model.data::<Tab>(entity) {
-                    if let Some(items) = tab.items_opt() {
-                        for item in items.iter() {
-                            if item.selected {
-                                actions.extend(item.preview_header().into_iter().map(|element| {
-                                    element.map(move |x| Message::TabMessage(Some(entity), x))
-                                }));
-                                break;
-                            }
-                        }
-                    }
-                };
-                context_drawer::context_drawer(
-                    self.preview(entity_opt, kind, true)
-                        .map(move |x| Message::TabMessage(Some(entity), x)),
-                    Message::ToggleContextPage(ContextPage::Preview(Some(entity), kind.clone())),
-                )
-                .header_actions(actions)
-            }
+            // Shown as a resizable pane in the main view
+            ContextPage::Preview(..) => return None,
             ContextPage::Settings => context_drawer::context_drawer(
                 self.settings(),
                 Message::ToggleContextPage(ContextPage::Settings),
@@ -4226,27 +7925,346 @@ impl Application for App {
                         .map(move |x| Message::TabMessage(Some(entity), x)),
                 );
             }
-        }
+        }
+
+        let Some(dialog_page) = self.dialog_pages.front() else {
+            return None;
+        };
+
+        let cosmic_theme::Spacing {
+            space_xxs, space_s, ..
+        } = theme::active().cosmic().spacing;
+
+        let dialog = match dialog_page {
+            DialogPage::AuditLog { entries_opt } => {
+                let dialog = widget::dialog().title(fl!("audit-log")).primary_action(
+                    widget::button::standard(fl!("close")).on_press(Message::DialogCancel),
+                );
+                match entries_opt {
+                    Some(entries) if entries.is_empty() => dialog.body(fl!("audit-log-empty")),
+                    Some(entries) => {
+                        let military_time = self.config.tab.military_time;
+                        let mut column =
+                            widget::column::with_capacity(entries.len()).spacing(space_xxs);
+                        for entry in entries.iter() {
+                            let from = match entry.from.as_slice() {
+                                [] => String::new(),
+                                [path] => path.display().to_string(),
+                                [path, ..] => fl!(
+                                    "audit-log-paths",
+                                    path = path.display().to_string(),
+                                    items = entry.from.len() - 1
+                                ),
+                            };
+                            let mut text = fl!(
+                                "audit-log-entry",
+                                time = tab::format_system_time(entry.system_time(), military_time),
+                                operation = entry.operation.as_str(),
+                                from = from
+                            );
+                            if let Some(to) = &entry.to {
+                                text.push_str(" → ");
+                                text.push_str(&to.display().to_string());
+                            }
+                            let mut entry_column =
+                                widget::column::with_capacity(2).push(widget::text::body(text));
+                            for conflict in entry.conflicts.iter().take(AUDIT_LOG_CONFLICTS) {
+                                entry_column =
+                                    entry_column.push(widget::text::caption(match conflict {
+                                        AuditConflict::Replaced { path } => fl!(
+                                            "audit-log-replaced",
+                                            path = path.display().to_string()
+                                        ),
+                                        AuditConflict::Skipped { path } => fl!(
+                                            "audit-log-skipped",
+                                            path = path.display().to_string()
+                                        ),
+                                        AuditConflict::Renamed { path, to } => fl!(
+                                            "audit-log-renamed",
+                                            path = path.display().to_string(),
+                                            to = to.display().to_string()
+                                        ),
+                                    }));
+                            }
+                            if entry.conflicts.len() > AUDIT_LOG_CONFLICTS {
+                                entry_column = entry_column.push(widget::text::caption(fl!(
+                                    "audit-log-more-conflicts",
+                                    items = entry.conflicts.len() - AUDIT_LOG_CONFLICTS
+                                )));
+                            }
+                            if let Some(error) = &entry.error {
+                                entry_column = entry_column.push(widget::text::caption(fl!(
+                                    "audit-log-failed",
+                                    error = error.as_str()
+                                )));
+                            }
+                            column = column.push(entry_column);
+                        }
+                        dialog.control(widget::scrollable(column).height(Length::Fixed(360.0)))
+                    }
+                    None => dialog.body(fl!("calculating")),
+                }
+            }
+            DialogPage::BulkRename {
+                paths,
+                rename,
+                preview,
+            } => {
+                let mut complete_maybe = None;
+                let mut preview_maybe = None;
+                let mut preview_column =
+                    widget::column::with_capacity(paths.len()).spacing(space_xxs);
+                match preview {
+                    Ok(previews) => {
+                        let mut changes = 0;
+                        let mut conflicts = 0;
+                        for preview in previews.iter() {
+                            let from_name = preview
+                                .from
+                                .file_name()
+                                .map(tab::escaped_file_name)
+                                .unwrap_or_default();
+                            let to_name = preview
+                                .to
+                                .file_name()
+                                .map(tab::escaped_file_name)
+                                .unwrap_or_default();
+                            let mut row = widget::row::with_capacity(2).spacing(space_xxs).push(
+                                widget::text::body(if preview.changed() {
+                                    format!("{} → {}", from_name, to_name)
+                                } else {
+                                    from_name
+                                }),
+                            );
+                            if preview.changed() {
+                                changes += 1;
+                            }
+                            if let Some(conflict) = preview.conflict_opt {
+                                conflicts += 1;
+                                row = row.push(widget::text::caption(match conflict {
+                                    RenameConflict::Invalid => fl!("bulk-rename-invalid"),
+                                    RenameConflict::Duplicate => fl!("bulk-rename-duplicate"),
+                                    RenameConflict::Exists => fl!("bulk-rename-exists"),
+                                }));
+                            }
+                            preview_column = preview_column.push(row);
+                        }
+                        if changes > 0 && conflicts == 0 {
+                            complete_maybe = Some(Message::DialogComplete);
+                            preview_maybe =
+                                Some(Message::PreviewChanges(rename_operations(previews)));
+                        }
+                    }
+                    Err(err) => {
+                        preview_column = preview_column.push(widget::text::body(fl!(
+                            "bulk-rename-regex-error",
+                            error = err.as_str()
+                        )));
+                    }
+                }
 
-        let Some(dialog_page) = self.dialog_pages.front() else {
-            return None;
-        };
+                let mut case_row =
+                    widget::row::with_capacity(CaseTransform::ALL.len()).spacing(space_s);
+                for case in CaseTransform::ALL {
+                    let rename = rename.clone();
+                    case_row = case_row.push(widget::radio(
+                        widget::text::body(match case {
+                            CaseTransform::Keep => fl!("bulk-rename-case-keep"),
+                            CaseTransform::Lower => fl!("bulk-rename-case-lower"),
+                            CaseTransform::Upper => fl!("bulk-rename-case-upper"),
+                            CaseTransform::Title => fl!("bulk-rename-case-title"),
+                        }),
+                        case,
+                        Some(rename.case),
+                        move |case| {
+                            Message::BulkRename(BulkRename {
+                                case,
+                                ..rename.clone()
+                            })
+                        },
+                    ));
+                }
 
-        let cosmic_theme::Spacing {
-            space_xxs, space_s, ..
-        } = theme::active().cosmic().spacing;
+                widget::dialog()
+                    .title(fl!("bulk-rename", items = paths.len()))
+                    .primary_action(
+                        widget::button::suggested(fl!("rename"))
+                            .on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("preview-changes")).on_press_maybe(preview_maybe),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(fl!("bulk-rename-find")).into(),
+                            widget::text_input("", rename.find.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input({
+                                    let rename = rename.clone();
+                                    move |find| {
+                                        Message::BulkRename(BulkRename {
+                                            find,
+                                            ..rename.clone()
+                                        })
+                                    }
+                                })
+                                .into(),
+                            widget::text::body(fl!("bulk-rename-replace")).into(),
+                            widget::text_input(
+                                fl!("bulk-rename-replace-placeholder"),
+                                rename.replace.as_str(),
+                            )
+                            .on_input({
+                                let rename = rename.clone();
+                                move |replace| {
+                                    Message::BulkRename(BulkRename {
+                                        replace,
+                                        ..rename.clone()
+                                    })
+                                }
+                            })
+                            .on_submit_maybe(
+                                complete_maybe.clone().map(|maybe| move |_| maybe.clone()),
+                            )
+                            .into(),
+                            widget::checkbox(fl!("bulk-rename-regex"), rename.regex)
+                                .on_toggle({
+                                    let rename = rename.clone();
+                                    move |regex| {
+                                        Message::BulkRename(BulkRename {
+                                            regex,
+                                            ..rename.clone()
+                                        })
+                                    }
+                                })
+                                .into(),
+                            widget::checkbox(
+                                fl!("bulk-rename-keep-extension"),
+                                rename.keep_extension,
+                            )
+                            .on_toggle({
+                                let rename = rename.clone();
+                                move |keep_extension| {
+                                    Message::BulkRename(BulkRename {
+                                        keep_extension,
+                                        ..rename.clone()
+                                    })
+                                }
+                            })
+                            .into(),
+                            case_row.into(),
+                            widget::text::heading(fl!("bulk-rename-preview")).into(),
+                            widget::scrollable(preview_column)
+                                .height(Length::Fixed(240.0))
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+            }
+            DialogPage::ChangeExtension {
+                paths,
+                change,
+                preview,
+                contents_opt,
+                mismatches,
+            } => {
+                let mut changes = 0;
+                let mut conflicts = 0;
+                let mut preview_column =
+                    widget::column::with_capacity(preview.len()).spacing(space_xxs);
+                for preview in preview.iter() {
+                    let from_name = preview
+                        .from
+                        .file_name()
+                        .map(tab::escaped_file_name)
+                        .unwrap_or_default();
+                    let to_name = preview
+                        .to
+                        .file_name()
+                        .map(tab::escaped_file_name)
+                        .unwrap_or_default();
+                    let mut row =
+                        widget::row::with_capacity(2)
+                            .spacing(space_xxs)
+                            .push(widget::text::body(if preview.changed() {
+                                format!("{} → {}", from_name, to_name)
+                            } else {
+                                from_name
+                            }));
+                    if preview.changed() {
+                        changes += 1;
+                    }
+                    if let Some(conflict) = preview.conflict_opt {
+                        conflicts += 1;
+                        row = row.push(widget::text::caption(match conflict {
+                            RenameConflict::Invalid => fl!("bulk-rename-invalid"),
+                            RenameConflict::Duplicate => fl!("bulk-rename-duplicate"),
+                            RenameConflict::Exists => fl!("bulk-rename-exists"),
+                        }));
+                    } else if let Some(mime) = mismatches.get(&preview.from) {
+                        row = row.push(widget::text::caption(fl!(
+                            "change-extension-mismatch",
+                            mime = mime.essence_str()
+                        )));
+                    }
+                    preview_column = preview_column.push(row);
+                }
+                let complete_maybe =
+                    (changes > 0 && conflicts == 0).then_some(Message::DialogComplete);
+                let preview_maybe = complete_maybe
+                    .is_some()
+                    .then(|| Message::PreviewChanges(rename_operations(preview)));
+
+                let mut column = widget::column::with_capacity(4).spacing(space_xxs).push(
+                    widget::text_input(
+                        fl!("change-extension-placeholder"),
+                        change.extension.as_str(),
+                    )
+                    .id(self.dialog_text_input.clone())
+                    .on_input(|extension| {
+                        Message::ChangeExtensionInput(ChangeExtension { extension })
+                    })
+                    .on_submit_maybe(complete_maybe.clone().map(|maybe| move |_| maybe.clone())),
+                );
+                if contents_opt.is_none() {
+                    column = column.push(widget::text::caption(fl!("change-extension-checking")));
+                } else if !mismatches.is_empty() {
+                    column = column.push(widget::text::caption(fl!(
+                        "change-extension-mismatches",
+                        items = mismatches.len()
+                    )));
+                }
+                column = column
+                    .push(widget::text::heading(fl!("bulk-rename-preview")))
+                    .push(widget::scrollable(preview_column).height(Length::Fixed(240.0)));
 
-        let dialog = match dialog_page {
+                widget::dialog()
+                    .title(fl!("change-extension-title", items = paths.len()))
+                    .primary_action(
+                        widget::button::suggested(fl!("rename")).on_press_maybe(complete_maybe),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("preview-changes")).on_press_maybe(preview_maybe),
+                    )
+                    .control(column)
+            }
             DialogPage::Compress {
                 paths,
                 to,
                 name,
                 archive_type,
+                level,
                 password,
             } => {
                 let mut dialog = widget::dialog().title(fl!("create-archive"));
 
-                let complete_maybe = if name.is_empty() {
+                let complete_maybe = if name.is_empty() || paths.is_empty() {
                     None
                 } else if name == "." || name == ".." {
                     dialog = dialog.tertiary_action(widget::text::body(fl!(
@@ -4295,6 +8313,7 @@ impl Application for App {
                                             to: to.clone(),
                                             name: name.clone(),
                                             archive_type: *archive_type,
+                                            level: *level,
                                             password: password.clone(),
                                         })
                                     })
@@ -4313,6 +8332,7 @@ impl Application for App {
                                         to: to.clone(),
                                         name: name.clone(),
                                         archive_type: archive_types[index],
+                                        level: *level,
                                         password: password.clone(),
                                     })
                                 })
@@ -4321,11 +8341,87 @@ impl Application for App {
                             .align_y(Alignment::Center)
                             .spacing(space_xxs)
                             .into(),
+                            widget::text::body(fl!("compress-location")).into(),
+                            widget::row::with_children(vec![
+                                widget::text::body(to.display().to_string()).into(),
+                                horizontal_space().into(),
+                                widget::button::standard(fl!("compress-choose-folder"))
+                                    .on_press(Message::CompressTo)
+                                    .into(),
+                            ])
+                            .align_y(Alignment::Center)
+                            .spacing(space_xxs)
+                            .into(),
+                            widget::text::body(fl!("compression-level")).into(),
+                            Element::from(widget::dropdown(
+                                &self.compression_level_names,
+                                CompressionLevel::all().iter().position(|x| x == level),
+                                move |index| index,
+                            ))
+                            .map(|index| {
+                                Message::DialogUpdate(DialogPage::Compress {
+                                    paths: paths.clone(),
+                                    to: to.clone(),
+                                    name: name.clone(),
+                                    archive_type: *archive_type,
+                                    level: CompressionLevel::all()[index],
+                                    password: password.clone(),
+                                })
+                            })
+                            .into(),
+                        ])
+                        .spacing(space_xxs),
+                    );
+
+                // Staging list of the items to compress, which accepts more dropped items
+                let mut list = widget::column::with_capacity(paths.len());
+                for (i, path) in paths.iter().enumerate() {
+                    let item_name = path
+                        .file_name()
+                        .map(tab::escaped_file_name)
+                        .unwrap_or_default();
+                    let mut remaining = paths.clone();
+                    remaining.remove(i);
+                    list = list.push(
+                        widget::row::with_children(vec![
+                            widget::text::body(item_name).into(),
+                            horizontal_space().into(),
+                            widget::button::icon(widget::icon::from_name("list-remove-symbolic"))
+                                .on_press(Message::DialogUpdate(DialogPage::Compress {
+                                    paths: remaining,
+                                    to: to.clone(),
+                                    name: name.clone(),
+                                    archive_type: *archive_type,
+                                    level: *level,
+                                    password: password.clone(),
+                                }))
+                                .into(),
                         ])
+                        .align_y(Alignment::Center)
                         .spacing(space_xxs),
                     );
+                }
+                if paths.is_empty() {
+                    list = list.push(widget::text::caption(fl!("compress-empty")));
+                }
+                let list = widget::dnd_destination::DndDestination::for_data::<ClipboardPaste>(
+                    widget::container(widget::scrollable(list))
+                        .padding(space_xxs)
+                        .width(Length::Fill)
+                        .max_height(160.0)
+                        .class(theme::Container::Card),
+                    |data, _action| Message::CompressDrop(data),
+                );
+                dialog = dialog.control(
+                    widget::column::with_children(vec![
+                        widget::text::body(fl!("compress-contents")).into(),
+                        list.into(),
+                        widget::text::caption(fl!("compress-drop-hint")).into(),
+                    ])
+                    .spacing(space_xxs),
+                );
 
-                if *archive_type == ArchiveType::Zip {
+                if archive_type.has_password() {
                     let password_unwrapped = password.clone().unwrap_or_else(String::default);
                     dialog = dialog.control(widget::column::with_children(vec![
                         widget::text::body(fl!("password")).into(),
@@ -4337,6 +8433,7 @@ impl Application for App {
                                     to: to.clone(),
                                     name: name.clone(),
                                     archive_type: *archive_type,
+                                    level: *level,
                                     password: Some(password_unwrapped),
                                 })
                             })
@@ -4349,15 +8446,174 @@ impl Application for App {
 
                 dialog
             }
-            DialogPage::EmptyTrash => widget::dialog()
-                .title(fl!("empty-trash"))
-                .body(fl!("empty-trash-warning"))
-                .primary_action(
-                    widget::button::suggested(fl!("empty-trash")).on_press(Message::DialogComplete),
-                )
-                .secondary_action(
-                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                ),
+            DialogPage::EditTags { path, tags } => {
+                let name = path
+                    .file_name()
+                    .map(tab::escaped_file_name)
+                    .unwrap_or_default();
+                let mut column =
+                    widget::column::with_capacity(ColorTag::ALL.len()).spacing(space_xxs);
+                for tag in ColorTag::ALL {
+                    let color = tag.color();
+                    column = column.push(
+                        widget::row::with_children(vec![
+                            widget::container(widget::Space::new(
+                                Length::Fixed(12.0),
+                                Length::Fixed(12.0),
+                            ))
+                            .style(move |_theme| widget::container::Style {
+                                background: Some(color.into()),
+                                border: cosmic::iced::Border {
+                                    radius: 6.0.into(),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .into(),
+                            widget::checkbox(tag.name(), tags.contains(&tag))
+                                .on_toggle(move |checked| {
+                                    let mut tags = tags.clone();
+                                    tags.retain(|other| *other != tag);
+                                    if checked {
+                                        tags.push(tag);
+                                        tags.sort();
+                                    }
+                                    Message::DialogUpdate(DialogPage::EditTags {
+                                        path: path.clone(),
+                                        tags,
+                                    })
+                                })
+                                .into(),
+                        ])
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs),
+                    );
+                }
+                widget::dialog()
+                    .title(fl!("edit-tags-title", name = name))
+                    .primary_action(
+                        widget::button::suggested(fl!("save")).on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(column)
+            }
+            DialogPage::EjectBusy {
+                item,
+                processes_opt,
+                ejecting,
+                ..
+            } => {
+                let name = item.name();
+                let dialog = widget::dialog()
+                    .title(if *ejecting {
+                        fl!("ejecting", name = name.as_str())
+                    } else {
+                        fl!("eject-busy", name = name.as_str())
+                    })
+                    .icon(widget::icon::from_name("dialog-warning").size(64))
+                    .primary_action(
+                        widget::button::suggested(fl!("try-again"))
+                            .on_press_maybe((!ejecting).then_some(Message::EjectRetry(false))),
+                    )
+                    .secondary_action(
+                        widget::button::destructive(fl!("eject-force"))
+                            .on_press_maybe((!ejecting).then_some(Message::EjectRetry(true))),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("cancel")).on_press(Message::DialogCancel),
+                    );
+                match processes_opt {
+                    Some(processes) if processes.is_empty() => {
+                        dialog.body(fl!("eject-busy-unknown"))
+                    }
+                    Some(processes) => {
+                        let mut column =
+                            widget::column::with_capacity(processes.len()).spacing(space_xxs);
+                        for process in processes.iter() {
+                            let mut text_column =
+                                widget::column::with_capacity(1 + process.paths.len())
+                                    .push(widget::text::body(fl!(
+                                        "eject-busy-process",
+                                        name = process.name.as_str(),
+                                        pid = process.pid
+                                    )))
+                                    .width(Length::Fill);
+                            for path in process.paths.iter() {
+                                text_column = text_column
+                                    .push(widget::text::caption(path.display().to_string()));
+                            }
+                            let mut row = widget::row::with_capacity(2)
+                                .align_y(Alignment::Center)
+                                .spacing(space_s)
+                                .push(text_column);
+                            if let Some(path) = process.paths.first() {
+                                row = row.push(
+                                    widget::button::standard(fl!("open-item-location"))
+                                        .on_press(Message::EjectOpenLocation(path.clone())),
+                                );
+                            }
+                            column = column.push(row);
+                        }
+                        dialog
+                            .body(fl!("eject-busy-description"))
+                            .control(widget::scrollable(column).height(Length::Fixed(240.0)))
+                    }
+                    None => dialog.body(fl!("eject-busy-finding")),
+                }
+            }
+            DialogPage::EmptyTrash { summary_opt, .. } => {
+                let mut dialog = widget::dialog()
+                    .title(fl!("empty-trash"))
+                    .body(fl!("empty-trash-warning"))
+                    .primary_action(
+                        widget::button::suggested(fl!("empty-trash"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    );
+                match summary_opt {
+                    Some(summary) => {
+                        let mut column = widget::column::with_capacity(2 + summary.largest.len())
+                            .spacing(space_xxs);
+                        column = column.push(widget::text::body(fl!(
+                            "empty-trash-summary",
+                            items = summary.count,
+                            size = tab::format_size(summary.size)
+                        )));
+                        if !summary.largest.is_empty() {
+                            column = column.push(widget::text::heading(fl!("largest-items")));
+                            for (name, size) in summary.largest.iter() {
+                                column = column.push(widget::row::with_children(vec![
+                                    widget::text::body(name.clone()).width(Length::Fill).into(),
+                                    widget::text::body(tab::format_size(*size)).into(),
+                                ]));
+                            }
+                        }
+                        dialog = dialog.control(column);
+                        if !summary.old_items.is_empty() {
+                            dialog = dialog.tertiary_action(
+                                widget::button::text(fl!(
+                                    "empty-trash-older",
+                                    days = tab::TRASH_OLD_DAYS
+                                ))
+                                .on_press(
+                                    Message::DialogUpdateComplete(DialogPage::EmptyTrash {
+                                        summary_opt: summary_opt.clone(),
+                                        older_only: true,
+                                    }),
+                                ),
+                            );
+                        }
+                    }
+                    None => {
+                        dialog = dialog.control(widget::text::body(fl!("calculating")));
+                    }
+                }
+                dialog
+            }
             DialogPage::FailedOperation(id) => {
                 //TODO: try next dialog page (making sure index is used by Dialog messages)?
                 let (operation, _, err) = self.failed_operations.get(id)?;
@@ -4389,20 +8645,6 @@ impl Application for App {
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
             }
-            DialogPage::MountError {
-                mounter_key: _,
-                item: _,
-                error,
-            } => widget::dialog()
-                .title(fl!("mount-error"))
-                .body(error)
-                .icon(widget::icon::from_name("dialog-error").size(64))
-                .primary_action(
-                    widget::button::standard(fl!("try-again")).on_press(Message::DialogComplete),
-                )
-                .secondary_action(
-                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                ),
             DialogPage::NetworkAuth {
                 mounter_key,
                 uri,
@@ -4512,40 +8754,123 @@ impl Application for App {
                     )
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                    );
-
-                if let Some(_anonymous) = &auth.anonymous_opt {
-                    widget = widget.tertiary_action(
-                        widget::button::text(fl!("connect-anonymously")).on_press(
-                            Message::DialogUpdateComplete(DialogPage::NetworkAuth {
-                                mounter_key: *mounter_key,
-                                uri: uri.clone(),
-                                auth: MounterAuth {
-                                    anonymous_opt: Some(true),
-                                    ..auth.clone()
-                                },
-                                auth_tx: auth_tx.clone(),
-                            }),
-                        ),
-                    );
-                }
-
-                widget
+                    );
+
+                if let Some(_anonymous) = &auth.anonymous_opt {
+                    widget = widget.tertiary_action(
+                        widget::button::text(fl!("connect-anonymously")).on_press(
+                            Message::DialogUpdateComplete(DialogPage::NetworkAuth {
+                                mounter_key: *mounter_key,
+                                uri: uri.clone(),
+                                auth: MounterAuth {
+                                    anonymous_opt: Some(true),
+                                    ..auth.clone()
+                                },
+                                auth_tx: auth_tx.clone(),
+                            }),
+                        ),
+                    );
+                }
+
+                widget
+            }
+            DialogPage::NetworkDelete { uris } => {
+                let mut column =
+                    widget::column::with_capacity(PERMANENTLY_DELETE_LISTED + 1).spacing(space_xxs);
+                for uri in uris.iter().take(PERMANENTLY_DELETE_LISTED) {
+                    column = column.push(widget::text::body(
+                        percent_encoding::percent_decode_str(uri)
+                            .decode_utf8_lossy()
+                            .into_owned(),
+                    ));
+                }
+                if uris.len() > PERMANENTLY_DELETE_LISTED {
+                    column = column.push(widget::text::body(fl!(
+                        "permanently-delete-more",
+                        items = uris.len() - PERMANENTLY_DELETE_LISTED
+                    )));
+                }
+
+                let target = if uris.len() == 1 {
+                    format!("\"{}\"", operation::network::uri_name(&uris[0]))
+                } else {
+                    fl!("selected-items", items = uris.len())
+                };
+
+                widget::dialog()
+                    .title(fl!("permanently-delete-question"))
+                    .primary_action(
+                        widget::button::destructive(fl!("delete"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(widget::text(fl!(
+                        "permanently-delete-warning",
+                        target = target
+                    )))
+                    .control(column)
+            }
+            DialogPage::NetworkRename { from, name, dir } => {
+                let mut dialog = widget::dialog().title(if *dir {
+                    fl!("rename-folder")
+                } else {
+                    fl!("rename-file")
+                });
+
+                let complete_maybe = if name.is_empty() {
+                    None
+                } else if name == "." || name == ".." {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!(
+                        "name-invalid",
+                        filename = name.as_str()
+                    )));
+                    None
+                } else if name.contains('/') {
+                    dialog = dialog.tertiary_action(widget::text::body(fl!("name-no-slashes")));
+                    None
+                } else {
+                    // Existing items are not replaced, the server refuses the rename instead
+                    if name.starts_with('.') {
+                        dialog = dialog.tertiary_action(widget::text::body(fl!("name-hidden")));
+                    }
+                    Some(Message::DialogComplete)
+                };
+
+                dialog
+                    .primary_action(
+                        widget::button::suggested(fl!("rename"))
+                            .on_press_maybe(complete_maybe.clone()),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::body(if *dir {
+                                fl!("folder-name")
+                            } else {
+                                fl!("file-name")
+                            })
+                            .into(),
+                            widget::text_input("", name.as_str())
+                                .id(self.dialog_text_input.clone())
+                                .on_input(move |name| {
+                                    Message::DialogUpdate(DialogPage::NetworkRename {
+                                        from: from.clone(),
+                                        name,
+                                        dir: *dir,
+                                    })
+                                })
+                                .on_submit_maybe(
+                                    complete_maybe.clone().map(|maybe| move |_| maybe.clone()),
+                                )
+                                .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
             }
-            DialogPage::NetworkError {
-                mounter_key: _,
-                uri: _,
-                error,
-            } => widget::dialog()
-                .title(fl!("network-drive-error"))
-                .body(error)
-                .icon(widget::icon::from_name("dialog-error").size(64))
-                .primary_action(
-                    widget::button::standard(fl!("try-again")).on_press(Message::DialogComplete),
-                )
-                .secondary_action(
-                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                ),
             DialogPage::NewItem { parent, name, dir } => {
                 let mut dialog = widget::dialog().title(if *dir {
                     fl!("create-new-folder")
@@ -4616,29 +8941,79 @@ impl Application for App {
                         .spacing(space_xxs),
                     )
             }
+            DialogPage::OpenAll {
+                files,
+                folders,
+                launches,
+            } => widget::dialog()
+                .title(fl!("open-all-question", launches = launches))
+                .primary_action(
+                    widget::button::suggested(fl!("open")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                )
+                .control(widget::text(fl!(
+                    "open-all-warning",
+                    files = files.len(),
+                    folders = folders.len(),
+                    launches = launches
+                ))),
             DialogPage::OpenWith {
-                path,
+                paths,
                 mime,
                 selected,
                 store_opt,
-                ..
+                remember,
+                show_all,
+                search,
+                custom_command,
             } => {
-                let name = match path.file_name() {
+                let name = paths.first().and_then(|path| match path.file_name() {
                     Some(file_name) => file_name.to_str(),
                     None => path.as_os_str().to_str(),
-                };
+                });
 
                 let mut column = widget::list_column();
-                let available_apps = self.get_apps_for_mime(mime);
+                // Indexes are kept from the full list, which the selection refers to
+                let mut available_apps: Vec<_> = self
+                    .get_apps_for_mime(mime)
+                    .into_iter()
+                    .enumerate()
+                    .collect();
+                let search_lower = search.trim().to_lowercase();
+                // Other applications are hidden until asked for, unless there are no others or
+                // they are searched for
+                let mut hidden_apps = false;
+                if !search_lower.is_empty() {
+                    available_apps.retain(|(_, (app, _))| {
+                        app.name.to_lowercase().contains(&search_lower)
+                            || app.id.to_lowercase().contains(&search_lower)
+                    });
+                } else if !*show_all
+                    && available_apps
+                        .iter()
+                        .any(|(_, (_, kind))| *kind != MimeAppMatch::Other)
+                {
+                    let len = available_apps.len();
+                    available_apps.retain(|(_, (_, kind))| *kind != MimeAppMatch::Other);
+                    hidden_apps = available_apps.len() < len;
+                }
                 let item_height = 32.0;
                 let mut displayed_default = false;
                 let mut last_kind = MimeAppMatch::Exact;
-                for (i, (app, kind)) in available_apps.iter().enumerate() {
+                for (i, (app, kind)) in available_apps.iter() {
                     if *kind != last_kind {
                         match kind {
                             MimeAppMatch::Related => {
                                 column = column.add(widget::text::heading(fl!("related-apps")));
                             }
+                            MimeAppMatch::Category => {
+                                column = column.add(widget::text::heading(fl!(
+                                    "category-apps",
+                                    category = mime.type_().as_str()
+                                )));
+                            }
                             MimeAppMatch::Other => {
                                 column = column.add(widget::text::heading(fl!("other-apps")));
                             }
@@ -4661,7 +9036,7 @@ impl Application for App {
                                     widget::text::body(app.name.to_string()).into()
                                 },
                                 widget::horizontal_space().into(),
-                                if *selected == i {
+                                if *selected == *i && custom_command.trim().is_empty() {
                                     widget::icon::from_name("checkbox-checked-symbolic")
                                         .size(16)
                                         .into()
@@ -4675,7 +9050,13 @@ impl Application for App {
                         )
                         .width(Length::Fill)
                         .class(theme::Button::MenuItem)
-                        .on_press(Message::OpenWithSelection(i)),
+                        .on_press(Message::OpenWithSelection(*i)),
+                    );
+                }
+                if hidden_apps {
+                    column = column.add(
+                        widget::button::text(fl!("show-all-apps"))
+                            .on_press(Message::OpenWithShowAll),
                     );
                 }
 
@@ -4687,6 +9068,11 @@ impl Application for App {
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
+                    .control(
+                        widget::text_input::search_input(fl!("open-with-search"), search.as_str())
+                            .on_input(Message::OpenWithSearch)
+                            .on_clear(Message::OpenWithSearch(String::new())),
+                    )
                     .control(
                         widget::scrollable(column).height(if let Some(size) = self.size {
                             let max_size = (size.height - 256.0).min(480.0);
@@ -4704,6 +9090,29 @@ impl Application for App {
                         }),
                     );
 
+                dialog = dialog
+                    .control(
+                        widget::column::with_children(vec![
+                            widget::text::heading(fl!("custom-command")).into(),
+                            widget::text_input(
+                                fl!("custom-command-placeholder"),
+                                custom_command.as_str(),
+                            )
+                            .on_input(Message::OpenWithCustomCommand)
+                            .on_submit(|_| Message::DialogComplete)
+                            .into(),
+                        ])
+                        .spacing(space_xxs),
+                    )
+                    .control(
+                        // Custom commands have no desktop entry to make the default
+                        widget::checkbox(
+                            fl!("remember-app", mime = mime.essence_str()),
+                            *remember && custom_command.trim().is_empty(),
+                        )
+                        .on_toggle(Message::OpenWithRemember),
+                    );
+
                 if let Some(app) = store_opt {
                     dialog = dialog.tertiary_action(
                         widget::button::text(fl!("browse-store", store = app.name.as_str()))
@@ -4713,7 +9122,27 @@ impl Application for App {
 
                 dialog
             }
-            DialogPage::PermanentlyDelete { paths } => {
+            DialogPage::PermanentlyDelete { paths, stats_opt } => {
+                let mut column =
+                    widget::column::with_capacity(PERMANENTLY_DELETE_LISTED + 2).spacing(space_xxs);
+                for path in paths.iter().take(PERMANENTLY_DELETE_LISTED) {
+                    column = column.push(widget::text::body(path.display().to_string()));
+                }
+                if paths.len() > PERMANENTLY_DELETE_LISTED {
+                    column = column.push(widget::text::body(fl!(
+                        "permanently-delete-more",
+                        items = paths.len() - PERMANENTLY_DELETE_LISTED
+                    )));
+                }
+                column = column.push(widget::text::body(match stats_opt {
+                    Some(stats) => fl!(
+                        "permanently-delete-summary",
+                        items = stats.count,
+                        size = tab::format_size(stats.size)
+                    ),
+                    None => fl!("calculating"),
+                }));
+
                 let target = if paths.len() == 1 {
                     format!(
                         "\"{}\"",
@@ -4735,10 +9164,55 @@ impl Application for App {
                     .secondary_action(
                         widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                     )
+                    .tertiary_action(widget::button::text(fl!("preview-changes")).on_press(
+                        Message::PreviewChanges(vec![Operation::PermanentlyDelete {
+                            paths: paths.clone(),
+                        }]),
+                    ))
                     .control(widget::text(fl!(
                         "permanently-delete-warning",
                         target = target
                     )))
+                    .control(column)
+            }
+            DialogPage::PreviewChanges { changes_opt, .. } => {
+                let control: Element<_> = match changes_opt {
+                    Some(Ok(changes)) => {
+                        let mut column = widget::column::with_capacity(
+                            changes.len().min(PREVIEW_CHANGES_LISTED) + 2,
+                        )
+                        .spacing(space_xxs);
+                        column = column.push(widget::text::heading(fl!(
+                            "planned-changes",
+                            changes = changes.len()
+                        )));
+                        for change in changes.iter().take(PREVIEW_CHANGES_LISTED) {
+                            column = column.push(widget::text::body(change.description()));
+                        }
+                        if changes.len() > PREVIEW_CHANGES_LISTED {
+                            column = column.push(widget::text::body(fl!(
+                                "planned-changes-more",
+                                changes = changes.len() - PREVIEW_CHANGES_LISTED
+                            )));
+                        }
+                        widget::scrollable(column)
+                            .height(Length::Fixed(self.size.map_or(240.0, |size| {
+                                (size.height - 256.0).clamp(120.0, 480.0)
+                            })))
+                            .into()
+                    }
+                    Some(Err(err)) => widget::text::body(err.clone()).into(),
+                    None => widget::text::body(fl!("calculating")).into(),
+                };
+                widget::dialog()
+                    .title(fl!("preview-changes"))
+                    .primary_action(widget::button::destructive(fl!("apply")).on_press_maybe(
+                        matches!(changes_opt, Some(Ok(_))).then_some(Message::DialogComplete),
+                    ))
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .control(control)
             }
             DialogPage::RenameItem {
                 from,
@@ -4765,7 +9239,7 @@ impl Application for App {
                     dialog = dialog.tertiary_action(widget::text::body(fl!("name-no-slashes")));
                     None
                 } else {
-                    let path = parent.join(name);
+                    let path = parent.join(tab::unescaped_file_name(name));
                     if from != &path && path.exists() {
                         if path.is_dir() {
                             dialog = dialog
@@ -4778,6 +9252,11 @@ impl Application for App {
                     } else {
                         if name.starts_with('.') {
                             dialog = dialog.tertiary_action(widget::text::body(fl!("name-hidden")));
+                        } else if let Some(operation) = rename_operation(from, parent, name) {
+                            dialog = dialog.tertiary_action(
+                                widget::button::text(fl!("preview-changes"))
+                                    .on_press(Message::PreviewChanges(vec![operation])),
+                            );
                         }
                         Some(Message::DialogComplete)
                     }
@@ -4822,37 +9301,108 @@ impl Application for App {
                 to,
                 multiple,
                 apply_to_all,
+                rename,
+                restore,
                 tx,
             } => {
                 let military_time = self.config.tab.military_time;
-                let dialog = widget::dialog()
+
+                // A new name only applies to this item, and must not be taken
+                let rename_maybe = if *apply_to_all || !valid_rename(rename) {
+                    None
+                } else {
+                    to.path_opt()
+                        .and_then(|path| path.parent())
+                        .filter(|parent| parent.join(rename).symlink_metadata().is_err())
+                        .map(|_| Message::ReplaceResult(ReplaceResult::Rename(rename.clone())))
+                };
+                let rename_input = widget::text_input(fl!("replace-rename"), rename.as_str())
+                    .id(self.dialog_text_input.clone())
+                    .on_submit_maybe(rename_maybe.clone().map(|maybe| move |_| maybe.clone()));
+                let rename_input = if *apply_to_all {
+                    rename_input
+                } else {
+                    rename_input.on_input(move |rename| {
+                        Message::DialogUpdate(DialogPage::Replace {
+                            from: from.clone(),
+                            to: to.clone(),
+                            multiple: *multiple,
+                            apply_to_all: *apply_to_all,
+                            rename,
+                            restore: *restore,
+                            tx: tx.clone(),
+                        })
+                    })
+                };
+
+                let mut dialog = widget::dialog()
                     .title(fl!("replace-title", filename = to.name.as_str()))
-                    .body(fl!("replace-warning-operation"))
+                    .body(if *restore {
+                        fl!("replace-warning-restore")
+                    } else {
+                        fl!("replace-warning-operation")
+                    })
                     .control(
-                        to.replace_view(fl!("original-file"), military_time)
-                            .map(|x| Message::TabMessage(None, x)),
+                        widget::row::with_children(vec![
+                            widget::container(
+                                to.replace_view(fl!("original-file"), from, military_time)
+                                    .map(|x| Message::TabMessage(None, x)),
+                            )
+                            .width(Length::Fill)
+                            .into(),
+                            widget::container(
+                                from.replace_view(fl!("replace-with"), to, military_time)
+                                    .map(|x| Message::TabMessage(None, x)),
+                            )
+                            .width(Length::Fill)
+                            .into(),
+                        ])
+                        .spacing(space_s),
                     )
                     .control(
-                        from.replace_view(fl!("replace-with"), military_time)
-                            .map(|x| Message::TabMessage(None, x)),
+                        widget::row::with_children(vec![
+                            rename_input.into(),
+                            widget::button::standard(fl!("rename"))
+                                .on_press_maybe(rename_maybe)
+                                .into(),
+                        ])
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs),
                     )
                     .primary_action(widget::button::suggested(fl!("replace")).on_press(
                         Message::ReplaceResult(ReplaceResult::Replace(*apply_to_all)),
                     ));
+                if *restore {
+                    dialog = dialog.control(
+                        widget::button::standard(fl!("restore-elsewhere"))
+                            .on_press_maybe((!*apply_to_all).then_some(Message::RestoreElsewhere)),
+                    );
+                }
                 if *multiple {
                     dialog
                         .control(
-                            widget::checkbox(fl!("apply-to-all"), *apply_to_all).on_toggle(
-                                |apply_to_all| {
-                                    Message::DialogUpdate(DialogPage::Replace {
-                                        from: from.clone(),
-                                        to: to.clone(),
-                                        multiple: *multiple,
-                                        apply_to_all,
-                                        tx: tx.clone(),
+                            widget::row::with_children(vec![
+                                widget::checkbox(fl!("apply-to-all"), *apply_to_all)
+                                    .on_toggle(|apply_to_all| {
+                                        Message::DialogUpdate(DialogPage::Replace {
+                                            from: from.clone(),
+                                            to: to.clone(),
+                                            multiple: *multiple,
+                                            apply_to_all,
+                                            rename: rename.clone(),
+                                            restore: *restore,
+                                            tx: tx.clone(),
+                                        })
                                     })
-                                },
-                            ),
+                                    .into(),
+                                widget::horizontal_space().into(),
+                                widget::button::standard(fl!("keep-both"))
+                                    .on_press(Message::ReplaceResult(ReplaceResult::KeepBoth(
+                                        *apply_to_all,
+                                    )))
+                                    .into(),
+                            ])
+                            .align_y(Alignment::Center),
                         )
                         .secondary_action(
                             widget::button::standard(fl!("skip")).on_press(Message::ReplaceResult(
@@ -4871,10 +9421,90 @@ impl Application for App {
                         )
                         .tertiary_action(
                             widget::button::text(fl!("keep-both"))
-                                .on_press(Message::ReplaceResult(ReplaceResult::KeepBoth)),
+                                .on_press(Message::ReplaceResult(ReplaceResult::KeepBoth(false))),
                         )
                 }
             }
+            DialogPage::SecureDelete { paths } => {
+                let target = if paths.len() == 1 {
+                    format!(
+                        "\"{}\"",
+                        paths[0]
+                            .file_name()
+                            .map(std::ffi::OsStr::to_string_lossy)
+                            .unwrap_or_else(|| paths[0].to_string_lossy())
+                    )
+                } else {
+                    fl!("selected-items", items = paths.len())
+                };
+
+                widget::dialog()
+                    .title(fl!("secure-delete-question"))
+                    .primary_action(
+                        widget::button::destructive(fl!("secure-delete"))
+                            .on_press(Message::DialogComplete),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(widget::button::text(fl!("preview-changes")).on_press(
+                        Message::PreviewChanges(vec![Operation::SecureDelete {
+                            paths: paths.clone(),
+                        }]),
+                    ))
+                    .control(widget::text(fl!("secure-delete-warning", target = target)))
+                    .control(widget::text::caption(fl!("secure-delete-storage-warning")))
+            }
+            DialogPage::SelectionStats { stats_opt } => {
+                let dialog = widget::dialog()
+                    .title(fl!("selection-statistics"))
+                    .primary_action(
+                        widget::button::standard(fl!("close")).on_press(Message::DialogCancel),
+                    );
+                match stats_opt {
+                    Some(stats) => {
+                        let military_time = self.config.tab.military_time;
+                        let mut column =
+                            widget::column::with_capacity(stats.kinds.len() + 5).spacing(space_xxs);
+                        column = column.push(widget::text::body(fl!(
+                            "selection-statistics-size",
+                            items = stats.count,
+                            size = tab::format_size(stats.size),
+                            average = tab::format_size(stats.size / stats.count.max(1) as u64)
+                        )));
+                        for (kind, count) in stats.kinds.iter() {
+                            column = column.push(widget::text::body(fl!(
+                                "selection-statistics-kind",
+                                kind = kind.as_str(),
+                                items = count
+                            )));
+                        }
+                        if let Some((name, size)) = &stats.largest {
+                            column = column.push(widget::text::body(fl!(
+                                "selection-statistics-largest",
+                                name = name.as_str(),
+                                size = tab::format_size(*size)
+                            )));
+                        }
+                        if let Some((name, time)) = &stats.oldest {
+                            column = column.push(widget::text::body(fl!(
+                                "selection-statistics-oldest",
+                                name = name.as_str(),
+                                modified = tab::format_system_time(*time, military_time)
+                            )));
+                        }
+                        if let Some((name, time)) = &stats.newest {
+                            column = column.push(widget::text::body(fl!(
+                                "selection-statistics-newest",
+                                name = name.as_str(),
+                                modified = tab::format_system_time(*time, military_time)
+                            )));
+                        }
+                        dialog.control(column)
+                    }
+                    None => dialog.body(fl!("calculating")),
+                }
+            }
             DialogPage::SetExecutableAndLaunch { path } => {
                 let name = match path.file_name() {
                     Some(file_name) => file_name.to_str(),
@@ -4897,6 +9527,60 @@ impl Application for App {
                         name = name
                     )))
             }
+            DialogPage::VerifyChecksum {
+                path,
+                expected,
+                controller_opt,
+                result_opt,
+            } => {
+                let name = path
+                    .file_name()
+                    .map(tab::escaped_file_name)
+                    .unwrap_or_default();
+                let verifying = controller_opt.is_some();
+                let mut column = widget::column::with_capacity(2).spacing(space_xxs);
+                column = column.push(
+                    widget::text_input(fl!("checksum-placeholder"), expected.as_str())
+                        .id(self.dialog_text_input.clone())
+                        .on_input(Message::VerifyChecksumInput)
+                        .on_submit(|_| Message::VerifyChecksumStart),
+                );
+                if verifying {
+                    column = column.push(widget::text::body(fl!("calculating")));
+                } else {
+                    match result_opt {
+                        Some(Ok(Some(algorithm))) => {
+                            column = column.push(widget::text::body(fl!(
+                                "checksum-match",
+                                algorithm = algorithm.to_string()
+                            )));
+                        }
+                        Some(Ok(None)) => {
+                            column = column.push(widget::text::body(fl!("checksum-mismatch")));
+                        }
+                        Some(Err(err)) => {
+                            column = column.push(widget::text::body(err.clone()));
+                        }
+                        None => {}
+                    }
+                }
+                widget::dialog()
+                    .title(fl!("verify-checksum-title", name = name))
+                    .primary_action(
+                        widget::button::suggested(fl!("verify")).on_press_maybe(
+                            (!verifying && !expected.trim().is_empty())
+                                .then_some(Message::VerifyChecksumStart),
+                        ),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("close")).on_press(Message::DialogCancel),
+                    )
+                    .tertiary_action(
+                        widget::button::text(fl!("load-checksum-file"))
+                            .on_press(Message::VerifyChecksumFile),
+                    )
+                    .control(column)
+            }
             DialogPage::FavoritePathError { path, .. } => widget::dialog()
                 .title(fl!("favorite-path-error"))
                 .body(fl!(
@@ -4914,6 +9598,123 @@ impl Application for App {
         Some(dialog.into())
     }
 
+    /// Queued copies and moves with their progress and controls, shown in a popover
+    fn operation_queue_view(&self) -> Element<Message> {
+        let cosmic_theme::Spacing {
+            space_xxs,
+            space_xs,
+            space_s,
+            ..
+        } = theme::active().cosmic().spacing;
+
+        //TODO: get height from theme?
+        let progress_bar_height = Length::Fixed(4.0);
+
+        let icon_button = |name: &'static str, tooltip: String, message_opt: Option<Message>| {
+            widget::tooltip(
+                widget::button::icon(widget::icon::from_name(name))
+                    .on_press_maybe(message_opt)
+                    .padding(space_xxs),
+                widget::text::body(tooltip),
+                widget::tooltip::Position::Bottom,
+            )
+        };
+
+        let mut column = widget::column::with_capacity(self.operation_queue.len() + 1)
+            .spacing(space_s)
+            .push(widget::text::heading(fl!("operation-queue")));
+        let waiting: Vec<_> = self.operation_queue.waiting().collect();
+        let ids = self
+            .operation_queue
+            .running()
+            .into_iter()
+            .chain(waiting.iter().copied());
+        for id in ids {
+            let Some((op, controller)) = self.pending_operations.get(&id) else {
+                continue;
+            };
+            let progress = controller.progress();
+
+            let mut row = widget::row::with_capacity(4)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs)
+                .push(
+                    widget::progress_bar(0.0..=1.0, progress)
+                        .height(progress_bar_height)
+                        .width(Length::Fill),
+                );
+            let status = match waiting.iter().position(|waiting_id| *waiting_id == id) {
+                Some(position) => {
+                    row = row
+                        .push(icon_button(
+                            "go-up-symbolic",
+                            fl!("move-earlier"),
+                            (position > 0).then_some(Message::PendingReorder(id, true)),
+                        ))
+                        .push(icon_button(
+                            "go-down-symbolic",
+                            fl!("move-later"),
+                            (position + 1 < waiting.len())
+                                .then_some(Message::PendingReorder(id, false)),
+                        ));
+                    fl!("queue-position", position = position + 1)
+                }
+                None => {
+                    row = row.push(if controller.is_paused() {
+                        icon_button(
+                            "media-playback-start-symbolic",
+                            fl!("resume"),
+                            Some(Message::PendingPause(id, false)),
+                        )
+                    } else {
+                        icon_button(
+                            "media-playback-pause-symbolic",
+                            fl!("pause"),
+                            Some(Message::PendingPause(id, true)),
+                        )
+                    });
+                    match Transfer::from_controller(controller) {
+                        Some(transfer) => {
+                            let rate = fl!(
+                                "transfer-rate",
+                                rate = tab::format_size(transfer.bytes_per_second)
+                            );
+                            match transfer.remaining_opt {
+                                Some(remaining) => {
+                                    format!("{}, {}", rate, queue::format_remaining(remaining))
+                                }
+                                None => rate,
+                            }
+                        }
+                        None => fl!("calculating"),
+                    }
+                }
+            };
+            row = row.push(icon_button(
+                "window-close-symbolic",
+                fl!("cancel"),
+                Some(Message::PendingCancel(id)),
+            ));
+
+            let mut item = widget::column::with_children(vec![
+                widget::text::body(op.pending_text(progress, controller.state())).into(),
+                row.into(),
+                widget::text::caption(status).into(),
+            ])
+            .spacing(space_xxs);
+            if let Some(toggle) = self.open_destination_toggle(id, op) {
+                item = item.push(toggle);
+            }
+            column = column.push(item);
+        }
+
+        widget::layer_container(column)
+            .padding(space_xs)
+            .layer(cosmic_theme::Layer::Primary)
+            .width(Length::Fixed(360.0))
+            .into()
+    }
+
     fn footer(&self) -> Option<Element<Message>> {
         if self.progress_operations.is_empty() {
             return None;
@@ -4928,7 +9729,7 @@ impl Application for App {
         let mut count = 0;
         let mut all_paused = true;
         for (_id, (op, controller)) in self.pending_operations.iter() {
-            if !controller.is_paused() {
+            if !controller.is_paused() && !controller.is_queued() {
                 all_paused = false;
             }
             if op.show_progress_notification() {
@@ -5042,7 +9843,24 @@ impl Application for App {
     }
 
     fn header_end(&self) -> Vec<Element<Self::Message>> {
-        let mut elements = Vec::with_capacity(2);
+        let mut elements = Vec::with_capacity(3);
+
+        if !self.operation_queue.is_empty() {
+            let mut popover = widget::popover(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("emblem-synchronizing-symbolic"))
+                    .on_press(Message::ToggleOperationQueue)
+                    .padding(8)
+                    .selected(self.show_operation_queue),
+                widget::text::body(fl!("operation-queue")),
+                widget::tooltip::Position::Bottom,
+            ));
+            if self.show_operation_queue {
+                popover = popover
+                    .popup(self.operation_queue_view())
+                    .position(widget::popover::Position::Bottom);
+            }
+            elements.push(popover.into());
+        }
 
         if let Some(term) = self.search_get() {
             if self.core.is_condensed() {
@@ -5082,7 +9900,7 @@ impl Application for App {
             space_xxs, space_s, ..
         } = theme::active().cosmic().spacing;
 
-        let mut tab_column = widget::column::with_capacity(4);
+        let mut tab_column = widget::column::with_capacity(5);
 
         if self.core.is_condensed() {
             if let Some(term) = self.search_get() {
@@ -5100,8 +9918,17 @@ impl Application for App {
         }
 
         if self.tab_model.iter().count() > 1 {
+            // Show the full path of the active tab, as its title may be shortened
+            let tab_tooltip = self
+                .tab_model
+                .active_data::<Tab>()
+                .map(|tab| match tab.location.path_opt() {
+                    Some(path) => path.display().to_string(),
+                    None => tab.title(),
+                })
+                .unwrap_or_default();
             tab_column = tab_column.push(
-                widget::container(
+                widget::container(widget::tooltip(
                     widget::tab_bar::horizontal(&self.tab_model)
                         .button_height(32)
                         .button_spacing(space_xxs)
@@ -5113,13 +9940,27 @@ impl Application for App {
                             Message::DndDropTab(entity, data, action)
                         })
                         .drag_id(self.tab_drag_id),
-                )
+                    widget::text::body(tab_tooltip),
+                    widget::tooltip::Position::Bottom,
+                ))
                 .class(style::Container::Background)
                 .width(Length::Fill)
                 .padding([0, space_s]),
             );
         }
 
+        if let Some(toolbar) = self.toolbar() {
+            tab_column = tab_column.push(toolbar);
+        }
+
+        if let Some(frequent_folders_bar) = self.frequent_folders_bar() {
+            tab_column = tab_column.push(frequent_folders_bar);
+        }
+
+        if let Some(mount_progress) = self.mount_progress() {
+            tab_column = tab_column.push(mount_progress);
+        }
+
         let entity = self.tab_model.active();
         match self.tab_model.data::<Tab>(entity) {
             Some(tab) => {
@@ -5136,7 +9977,18 @@ impl Application for App {
         // The toaster is added on top of an empty element to ensure that it does not override context menus
         tab_column = tab_column.push(widget::toaster(&self.toasts, widget::horizontal_space()));
 
-        let content: Element<_> = tab_column.into();
+        let content: Element<_> = match self.details_pane() {
+            Some(details) => match self.config.details_position {
+                DetailsPosition::Left => {
+                    widget::row::with_children(vec![details, tab_column.into()])
+                }
+                DetailsPosition::Right => {
+                    widget::row::with_children(vec![tab_column.into(), details])
+                }
+            }
+            .into(),
+            None => tab_column.into(),
+        };
 
         // Uncomment to debug layout:
         //content.explain(cosmic::iced::Color::WHITE)
@@ -5252,6 +10104,9 @@ impl Application for App {
                     }
                 }
                 Event::Mouse(CursorMoved { position: pos }) => Some(Message::CursorMoved(pos)),
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::ResizeEnd)
+                }
                 _ => None,
             }),
             Config::subscription().map(|update| {
@@ -5437,6 +10292,23 @@ impl Application for App {
             ),
         ];
 
+        if self.tab_model.iter().any(|entity| {
+            self.tab_model
+                .data::<Tab>(entity)
+                .is_some_and(|tab| tab.offline)
+        }) {
+            subscriptions.push(
+                iced::time::every(time::Duration::from_secs(10)).map(|_| Message::OfflineRetry),
+            );
+        }
+
+        if matches!(self.mode, Mode::App)
+            && (self.config.trash_max_days > 0 || self.config.trash_max_size > 0)
+        {
+            subscriptions
+                .push(iced::time::every(TRASH_PURGE_INTERVAL).map(|_| Message::PurgeTrash));
+        }
+
         if let Some(scroll_speed) = self.auto_scroll_speed {
             subscriptions.push(
                 iced::time::every(time::Duration::from_millis(10))
@@ -5459,11 +10331,18 @@ impl Application for App {
                         MounterMessage::NetworkResult(uri, res) => {
                             Message::NetworkResult(key, uri, res)
                         }
+                        MounterMessage::NetworkChanged(uris) => Message::NetworkChanged(uris),
+                        MounterMessage::UnmountResult(item, res) => {
+                            Message::UnmountResult(key, item, res)
+                        }
                     },
                 ),
             );
         }
 
+        #[cfg(feature = "upower")]
+        subscriptions.push(crate::power::subscription().map(Message::PowerSaving));
+
         if !self.pending_operations.is_empty() {
             //TODO: inhibit suspend/shutdown?
 
@@ -5526,10 +10405,11 @@ impl Application for App {
                 selected_preview = Some(entity_opt.unwrap_or_else(|| self.tab_model.active()));
             }
         }
+        let reduce_background_work = self.reduce_background_work();
         for entity in self.tab_model.iter() {
             if let Some(tab) = self.tab_model.data::<Tab>(entity) {
                 subscriptions.push(
-                    tab.subscription(selected_preview == Some(entity))
+                    tab.subscription(selected_preview == Some(entity), reduce_background_work)
                         .with(entity)
                         .map(|(entity, tab_msg)| Message::TabMessage(Some(entity), tab_msg)),
                 );
@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! POSIX access control lists and extended attributes of files, shown and edited in the advanced
+//! tab of the details pane. Both need the `acl` feature and are only supported on Linux.
+
+use std::{io, path::Path};
+
+/// Prefix of the extended attributes that users can set on their files
+pub const USER_PREFIX: &str = "user.";
+
+/// Who an ACL entry applies to
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum AclTag {
+    Owner,
+    User(u32),
+    OwningGroup,
+    Group(u32),
+    Mask,
+    Other,
+}
+
+impl AclTag {
+    /// Entries for named users and groups, the only ones that can be removed
+    pub fn is_named(self) -> bool {
+        matches!(self, Self::User(_) | Self::Group(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AclEntry {
+    pub tag: AclTag,
+    /// Read, write and execute bits, like one part of the file mode
+    pub perm: u32,
+    /// Entry of the default ACL that new items in a folder inherit
+    pub default: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Xattr {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+impl Xattr {
+    pub fn editable(&self) -> bool {
+        self.name.starts_with(USER_PREFIX)
+    }
+
+    pub fn value_text(&self) -> String {
+        String::from_utf8_lossy(&self.value).into_owned()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Attributes {
+    /// Access entries followed by default entries, each sorted by tag
+    pub acl: Vec<AclEntry>,
+    /// Sorted by name, without the attributes storing the ACL
+    pub xattrs: Vec<Xattr>,
+}
+
+/// Full name of a user attribute typed without or with its prefix, `None` if it is empty or in
+/// another namespace
+pub fn user_xattr_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    if ["security.", "system.", "trusted."]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        return None;
+    }
+    let name = name.strip_prefix(USER_PREFIX).unwrap_or(name);
+    (!name.is_empty()).then(|| format!("{}{}", USER_PREFIX, name))
+}
+
+#[cfg(all(feature = "acl", target_os = "linux"))]
+mod imp {
+    use posix_acl::{PosixACL, Qualifier};
+    use std::{fs, io, os::unix::fs::MetadataExt, path::Path};
+
+    use super::{AclEntry, AclTag, Attributes, Xattr};
+
+    /// Attributes holding the ACL, shown as ACL entries instead
+    const ACL_XATTRS: [&str; 2] = ["system.posix_acl_access", "system.posix_acl_default"];
+
+    fn acl_error(err: posix_acl::ACLError) -> io::Error {
+        match err.as_io_error() {
+            Some(io_err) if io_err.raw_os_error() == Some(libc::ENOTSUP) => {
+                io::ErrorKind::Unsupported.into()
+            }
+            _ => io::Error::other(err),
+        }
+    }
+
+    fn tag(qual: Qualifier) -> Option<AclTag> {
+        match qual {
+            Qualifier::UserObj => Some(AclTag::Owner),
+            Qualifier::User(uid) => Some(AclTag::User(uid)),
+            Qualifier::GroupObj => Some(AclTag::OwningGroup),
+            Qualifier::Group(gid) => Some(AclTag::Group(gid)),
+            Qualifier::Mask => Some(AclTag::Mask),
+            Qualifier::Other => Some(AclTag::Other),
+            Qualifier::Undefined => None,
+        }
+    }
+
+    fn qualifier(tag: AclTag) -> Qualifier {
+        match tag {
+            AclTag::Owner => Qualifier::UserObj,
+            AclTag::User(uid) => Qualifier::User(uid),
+            AclTag::OwningGroup => Qualifier::GroupObj,
+            AclTag::Group(gid) => Qualifier::Group(gid),
+            AclTag::Mask => Qualifier::Mask,
+            AclTag::Other => Qualifier::Other,
+        }
+    }
+
+    fn entries(acl: &PosixACL, default: bool) -> Vec<AclEntry> {
+        let mut entries: Vec<_> = acl
+            .entries()
+            .into_iter()
+            .filter_map(|entry| {
+                Some(AclEntry {
+                    tag: tag(entry.qual)?,
+                    perm: entry.perm & 0o7,
+                    default,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.tag);
+        entries
+    }
+
+    pub fn read(path: &Path) -> io::Result<Attributes> {
+        let metadata = fs::symlink_metadata(path)?;
+        let mut attributes = Attributes::default();
+
+        // File systems without ACL support still have the permissions in the file mode
+        match PosixACL::read_acl(path).map_err(acl_error) {
+            Ok(acl) => attributes.acl = entries(&acl, false),
+            Err(err) if err.kind() == io::ErrorKind::Unsupported => {}
+            Err(err) => return Err(err),
+        }
+        if metadata.is_dir() {
+            match PosixACL::read_default_acl(path).map_err(acl_error) {
+                Ok(acl) => attributes.acl.extend(entries(&acl, true)),
+                Err(err) if err.kind() == io::ErrorKind::Unsupported => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        if xattr::SUPPORTED_PLATFORM {
+            let names = match xattr::list(path) {
+                Ok(ok) => ok,
+                Err(err) if err.raw_os_error() == Some(libc::ENOTSUP) => {
+                    return Ok(attributes);
+                }
+                Err(err) => return Err(err),
+            };
+            for name in names {
+                let name = name.to_string_lossy().into_owned();
+                if ACL_XATTRS.contains(&name.as_str()) {
+                    continue;
+                }
+                // Some namespaces can only be read with privileges
+                match xattr::get(path, &name) {
+                    Ok(Some(value)) => attributes.xattrs.push(Xattr { name, value }),
+                    Ok(None) => {}
+                    Err(err) => {
+                        log::debug!("failed to read attribute {} of {:?}: {}", name, path, err);
+                    }
+                }
+            }
+            attributes.xattrs.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(attributes)
+    }
+
+    /// ACL to change, starting a default ACL from the file mode if the folder has none
+    fn acl_to_change(path: &Path, default: bool) -> io::Result<PosixACL> {
+        if default {
+            let acl = PosixACL::read_default_acl(path).map_err(acl_error)?;
+            if !acl.entries().is_empty() {
+                return Ok(acl);
+            }
+            Ok(PosixACL::new(fs::metadata(path)?.mode()))
+        } else {
+            PosixACL::read_acl(path).map_err(acl_error)
+        }
+    }
+
+    fn write_acl(path: &Path, mut acl: PosixACL, default: bool) -> io::Result<()> {
+        acl.fix_mask();
+        if default {
+            acl.write_default_acl(path).map_err(acl_error)
+        } else {
+            acl.write_acl(path).map_err(acl_error)
+        }
+    }
+
+    pub fn set_acl_entry(path: &Path, entry: AclEntry) -> io::Result<()> {
+        let mut acl = acl_to_change(path, entry.default)?;
+        acl.set(qualifier(entry.tag), entry.perm & 0o7);
+        write_acl(path, acl, entry.default)
+    }
+
+    pub fn remove_acl_entry(path: &Path, tag: AclTag, default: bool) -> io::Result<()> {
+        let mut acl = acl_to_change(path, default)?;
+        if acl.remove(qualifier(tag)).is_none() {
+            return Ok(());
+        }
+        write_acl(path, acl, default)
+    }
+
+    pub fn set_xattr(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        xattr::set(path, name, value)
+    }
+
+    pub fn remove_xattr(path: &Path, name: &str) -> io::Result<()> {
+        xattr::remove(path, name)
+    }
+}
+
+#[cfg(not(all(feature = "acl", target_os = "linux")))]
+mod imp {
+    use std::{io, path::Path};
+
+    use super::{AclEntry, AclTag, Attributes};
+
+    pub fn read(_path: &Path) -> io::Result<Attributes> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn set_acl_entry(_path: &Path, _entry: AclEntry) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn remove_acl_entry(_path: &Path, _tag: AclTag, _default: bool) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn set_xattr(_path: &Path, _name: &str, _value: &[u8]) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub fn remove_xattr(_path: &Path, _name: &str) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// ACL entries and extended attributes of a file, not following symbolic links for attributes
+pub fn read(path: &Path) -> io::Result<Attributes> {
+    imp::read(path)
+}
+
+/// Add or change an ACL entry, updating the mask so that it still grants every entry
+pub fn set_acl_entry(path: &Path, entry: AclEntry) -> io::Result<()> {
+    imp::set_acl_entry(path, entry)
+}
+
+pub fn remove_acl_entry(path: &Path, tag: AclTag, default: bool) -> io::Result<()> {
+    imp::remove_acl_entry(path, tag, default)
+}
+
+/// Set a user attribute, other namespaces are not changed
+pub fn set_xattr(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+    if !name.starts_with(USER_PREFIX) {
+        return Err(io::ErrorKind::PermissionDenied.into());
+    }
+    imp::set_xattr(path, name, value)
+}
+
+pub fn remove_xattr(path: &Path, name: &str) -> io::Result<()> {
+    if !name.starts_with(USER_PREFIX) {
+        return Err(io::ErrorKind::PermissionDenied.into());
+    }
+    imp::remove_xattr(path, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::user_xattr_name;
+
+    #[test]
+    fn user_xattr_names() {
+        assert_eq!(user_xattr_name("comment").as_deref(), Some("user.comment"));
+        assert_eq!(
+            user_xattr_name(" user.xdg.origin.url ").as_deref(),
+            Some("user.xdg.origin.url")
+        );
+        assert_eq!(user_xattr_name("user."), None);
+        assert_eq!(user_xattr_name(""), None);
+        assert_eq!(user_xattr_name("security.selinux"), None);
+    }
+
+    #[cfg(all(feature = "acl", target_os = "linux"))]
+    #[test]
+    fn user_xattrs() -> std::io::Result<()> {
+        use super::{read, remove_xattr, set_xattr};
+        use std::{fs, io};
+        use tempfile::TempDir;
+
+        let dir = TempDir::new()?;
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, b"notes")?;
+
+        match set_xattr(&path, "user.comment", b"draft") {
+            Ok(()) => {}
+            // File system of the temporary folder does not support user attributes
+            Err(err) if err.raw_os_error() == Some(libc::ENOTSUP) => return Ok(()),
+            Err(err) => return Err(err),
+        }
+        let attributes = read(&path)?;
+        let xattr = attributes
+            .xattrs
+            .iter()
+            .find(|xattr| xattr.name == "user.comment")
+            .unwrap();
+        assert!(xattr.editable());
+        assert_eq!(xattr.value_text(), "draft");
+
+        assert_eq!(
+            set_xattr(&path, "trusted.comment", b"draft")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::PermissionDenied
+        );
+
+        remove_xattr(&path, "user.comment")?;
+        assert!(read(&path)?
+            .xattrs
+            .iter()
+            .all(|xattr| xattr.name != "user.comment"));
+
+        Ok(())
+    }
+}
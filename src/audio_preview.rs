@@ -0,0 +1,359 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Audio files in the details pane: metadata and cover art read with ffprobe and ffmpeg, and
+//! playback with ffplay so that samples can be heard without opening a media player. These are
+//! only run when the `audio-preview` feature is enabled.
+
+use mime_guess::{mime, Mime};
+use once_cell::sync::Lazy;
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// Whether ffprobe and ffplay are installed, looked up once as the details pane is redrawn often
+static PLAYER_AVAILABLE: Lazy<bool> = Lazy::new(|| {
+    let found = |program: &str| {
+        env::var_os("PATH")
+            .is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+    };
+    found("ffprobe") && found("ffplay")
+});
+
+pub fn audio_preview_supported(mime: &Mime) -> bool {
+    cfg!(feature = "audio-preview") && mime.type_() == mime::AUDIO
+}
+
+/// Whether the programs for reading and playing audio files are installed
+pub fn player_available() -> bool {
+    *PLAYER_AVAILABLE
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AudioMetadata {
+    pub codec: String,
+    /// Bits per second of the audio stream, or of the whole file if the stream does not say
+    pub bitrate_opt: Option<u64>,
+    pub sample_rate_opt: Option<u32>,
+    pub channels_opt: Option<u32>,
+    pub duration_opt: Option<Duration>,
+    /// Cover art encoded as PNG, scaled to fit the thumbnail size
+    pub cover_opt: Option<Vec<u8>>,
+}
+
+/// Metadata from the JSON written by `ffprobe -show_format -show_streams`, and whether the file
+/// has embedded cover art
+#[cfg(feature = "audio-preview")]
+fn parse_probe(json: &[u8]) -> Result<(AudioMetadata, bool), String> {
+    let json: serde_json::Value = serde_json::from_slice(json).map_err(|err| err.to_string())?;
+    let streams = json["streams"].as_array().map_or(&[][..], Vec::as_slice);
+    let audio = streams
+        .iter()
+        .find(|stream| stream["codec_type"] == "audio")
+        .ok_or_else(|| "no audio stream".to_string())?;
+    let has_cover = streams
+        .iter()
+        .any(|stream| stream["disposition"]["attached_pic"] == 1);
+
+    // Numbers are written as strings, except for the channel count
+    let number = |value: &serde_json::Value| -> Option<f64> {
+        value
+            .as_str()
+            .and_then(|text| text.parse().ok())
+            .or_else(|| value.as_f64())
+    };
+    let format = &json["format"];
+    let metadata = AudioMetadata {
+        codec: audio["codec_long_name"]
+            .as_str()
+            .or_else(|| audio["codec_name"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        bitrate_opt: number(&audio["bit_rate"])
+            .or_else(|| number(&format["bit_rate"]))
+            .map(|bitrate| bitrate as u64),
+        sample_rate_opt: number(&audio["sample_rate"]).map(|rate| rate as u32),
+        channels_opt: number(&audio["channels"]).map(|channels| channels as u32),
+        duration_opt: number(&audio["duration"])
+            .or_else(|| number(&format["duration"]))
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok()),
+        cover_opt: None,
+    };
+    Ok((metadata, has_cover))
+}
+
+#[cfg(feature = "audio-preview")]
+pub fn metadata(path: &Path, thumbnail_size: u32) -> Result<AudioMetadata, String> {
+    let output = Command::new("ffprobe")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-show_format")
+        .arg("-show_streams")
+        .arg("-of")
+        .arg("json")
+        .arg(path)
+        .output()
+        .map_err(|err| format!("failed to run ffprobe: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let (mut metadata, has_cover) = parse_probe(&output.stdout)?;
+    if has_cover {
+        // Missing cover art does not stop the rest from being shown
+        match cover(path, thumbnail_size) {
+            Ok(cover) => metadata.cover_opt = Some(cover),
+            Err(err) => log::warn!("failed to read cover art of {:?}: {}", path, err),
+        }
+    }
+    Ok(metadata)
+}
+
+#[cfg(feature = "audio-preview")]
+fn cover(path: &Path, thumbnail_size: u32) -> Result<Vec<u8>, String> {
+    let output = Command::new("ffmpeg")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(path)
+        .arg("-an")
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-vf")
+        .arg(format!(
+            "scale={size}:{size}:force_original_aspect_ratio=decrease",
+            size = thumbnail_size
+        ))
+        .arg("-c:v")
+        .arg("png")
+        .arg("-f")
+        .arg("image2pipe")
+        .arg("-")
+        .output()
+        .map_err(|err| format!("failed to run ffmpeg: {}", err))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "ffmpeg failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Duration as `m:ss`, or `h:mm:ss` from an hour on
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            (seconds / 60) % 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Playback of an audio file with ffplay. Pausing stops the process and remembers the position,
+/// which playing again starts from.
+pub struct AudioPlayer {
+    path: PathBuf,
+    duration_opt: Option<Duration>,
+    /// Position when playback last started or stopped
+    offset: Duration,
+    started_opt: Option<Instant>,
+    child_opt: Option<Child>,
+}
+
+impl AudioPlayer {
+    pub fn new(path: PathBuf, duration_opt: Option<Duration>) -> Self {
+        Self {
+            path,
+            duration_opt,
+            offset: Duration::ZERO,
+            started_opt: None,
+            child_opt: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn duration_opt(&self) -> Option<Duration> {
+        self.duration_opt
+    }
+
+    pub fn playing(&self) -> bool {
+        self.child_opt.is_some()
+    }
+
+    pub fn position(&self) -> Duration {
+        let position = match self.started_opt {
+            Some(started) => self.offset + started.elapsed(),
+            None => self.offset,
+        };
+        match self.duration_opt {
+            Some(duration) => position.min(duration),
+            None => position,
+        }
+    }
+
+    pub fn play(&mut self) -> io::Result<()> {
+        if self.playing() {
+            return Ok(());
+        }
+        // Start over when playing again at the end
+        if self
+            .duration_opt
+            .is_some_and(|duration| self.offset >= duration)
+        {
+            self.offset = Duration::ZERO;
+        }
+        let child = Command::new("ffplay")
+            .arg("-nodisp")
+            .arg("-autoexit")
+            .arg("-loglevel")
+            .arg("error")
+            .arg("-ss")
+            .arg(format!("{:.3}", self.offset.as_secs_f64()))
+            .arg(&self.path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        self.child_opt = Some(child);
+        self.started_opt = Some(Instant::now());
+        Ok(())
+    }
+
+    pub fn pause(&mut self) {
+        self.offset = self.position();
+        self.started_opt = None;
+        if let Some(mut child) = self.child_opt.take() {
+            // The process may have exited already at the end
+            let _ = child.kill();
+            if let Err(err) = child.wait() {
+                log::warn!("failed to wait for ffplay: {}", err);
+            }
+        }
+    }
+
+    pub fn seek(&mut self, position: Duration) -> io::Result<()> {
+        let playing = self.playing();
+        self.pause();
+        self.offset = match self.duration_opt {
+            Some(duration) => position.min(duration),
+            None => position,
+        };
+        if playing {
+            self.play()?;
+        }
+        Ok(())
+    }
+
+    /// Notice when playback has reached the end, returning whether it is still playing
+    pub fn poll(&mut self) -> bool {
+        let exited = match &mut self.child_opt {
+            Some(child) => !matches!(child.try_wait(), Ok(None)),
+            None => return false,
+        };
+        if exited {
+            self.child_opt = None;
+            self.started_opt = None;
+            self.offset = self.duration_opt.unwrap_or(self.offset);
+        }
+        !exited
+    }
+}
+
+impl Drop for AudioPlayer {
+    fn drop(&mut self) {
+        self.pause();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, AudioPlayer};
+    use std::{path::PathBuf, time::Duration};
+
+    #[cfg(feature = "audio-preview")]
+    #[test]
+    fn audio_metadata() {
+        use super::{parse_probe, AudioMetadata};
+
+        let json = br#"{
+            "streams": [
+                {
+                    "codec_name": "mp3",
+                    "codec_long_name": "MP3 (MPEG audio layer 3)",
+                    "codec_type": "audio",
+                    "sample_rate": "44100",
+                    "channels": 2,
+                    "bit_rate": "320000",
+                    "disposition": { "attached_pic": 0 }
+                },
+                {
+                    "codec_name": "mjpeg",
+                    "codec_type": "video",
+                    "disposition": { "attached_pic": 1 }
+                }
+            ],
+            "format": { "duration": "215.250000", "bit_rate": "325000" }
+        }"#;
+        assert_eq!(
+            parse_probe(json),
+            Ok((
+                AudioMetadata {
+                    codec: "MP3 (MPEG audio layer 3)".to_string(),
+                    bitrate_opt: Some(320_000),
+                    sample_rate_opt: Some(44_100),
+                    channels_opt: Some(2),
+                    duration_opt: Some(Duration::from_millis(215_250)),
+                    cover_opt: None,
+                },
+                true
+            ))
+        );
+
+        // Lossless streams only have the bitrate of the whole file
+        let json = br#"{
+            "streams": [{ "codec_name": "flac", "codec_type": "audio" }],
+            "format": { "bit_rate": "900000" }
+        }"#;
+        let (metadata, has_cover) = parse_probe(json).unwrap();
+        assert_eq!(metadata.codec, "flac");
+        assert_eq!(metadata.bitrate_opt, Some(900_000));
+        assert_eq!(metadata.duration_opt, None);
+        assert!(!has_cover);
+
+        assert!(parse_probe(br#"{ "streams": [], "format": {} }"#).is_err());
+    }
+
+    #[test]
+    fn audio_durations() {
+        assert_eq!(format_duration(Duration::from_secs(65)), "1:05");
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn audio_player_position() {
+        let mut player =
+            AudioPlayer::new(PathBuf::from("sample.ogg"), Some(Duration::from_secs(30)));
+        assert!(!player.playing());
+        player.seek(Duration::from_secs(10)).unwrap();
+        assert_eq!(player.position(), Duration::from_secs(10));
+        // Seeking past the end stops at the end
+        player.seek(Duration::from_secs(60)).unwrap();
+        assert_eq!(player.position(), Duration::from_secs(30));
+        assert!(!player.poll());
+    }
+}
@@ -0,0 +1,287 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Append-only log of destructive file operations, stored as JSON lines
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::operation::{Operation, OperationSelection, Resolution};
+
+/// Most recent entries shown in the viewer
+pub const AUDIT_LOG_VIEW_ENTRIES: usize = 200;
+
+/// Size at which the log is moved to a backup, replacing the previous backup
+const AUDIT_LOG_MAX_SIZE: u64 = 1024 * 1024;
+
+/// Item that already existed at the destination of an operation
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "resolution")]
+pub enum AuditConflict {
+    Replaced {
+        path: PathBuf,
+    },
+    Skipped {
+        path: PathBuf,
+    },
+    /// The existing item was kept and the new one written to `to`
+    Renamed {
+        path: PathBuf,
+        to: PathBuf,
+    },
+}
+
+impl From<&(PathBuf, Resolution)> for AuditConflict {
+    fn from((path, resolution): &(PathBuf, Resolution)) -> Self {
+        let path = path.clone();
+        match resolution {
+            Resolution::Replaced => Self::Replaced { path },
+            Resolution::Skipped => Self::Skipped { path },
+            Resolution::Renamed(to) => Self::Renamed {
+                path,
+                to: to.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    /// Kind of operation, like `delete` or `move`
+    pub operation: String,
+    /// Seconds since the Unix epoch
+    pub time: u64,
+    pub from: Vec<PathBuf>,
+    pub to: Option<PathBuf>,
+    /// Error message, or `None` if the operation succeeded
+    pub error: Option<String>,
+    /// Items that already existed at the destination, and how they were handled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<AuditConflict>,
+}
+
+impl AuditEntry {
+    /// Entry for a destructive operation that finished with `result`, or `None` if the
+    /// operation is not logged
+    pub fn new(operation: &Operation, result: Result<&OperationSelection, String>) -> Option<Self> {
+        let (conflicts, error) = match result {
+            Ok(op_sel) => (
+                op_sel.conflicts.iter().map(AuditConflict::from).collect(),
+                None,
+            ),
+            Err(err) => (Vec::new(), Some(err)),
+        };
+        let trash_paths = |items: &[trash::TrashItem]| -> Vec<PathBuf> {
+            items
+                .iter()
+                .map(|item| item.original_parent.join(&item.name))
+                .collect()
+        };
+        let uri_paths =
+            |uris: &[String]| -> Vec<PathBuf> { uris.iter().map(PathBuf::from).collect() };
+        let (kind, from, to) = match operation {
+            // Copying is only destructive if it replaced items, which a failed copy may have done
+            Operation::Copy { paths, to }
+                if error.is_some()
+                    || conflicts
+                        .iter()
+                        .any(|conflict| matches!(conflict, AuditConflict::Replaced { .. })) =>
+            {
+                ("copy", paths.clone(), Some(to.clone()))
+            }
+            Operation::Delete { paths } => ("delete", paths.clone(), None),
+            Operation::DeleteTrash { items } => ("delete-trash", trash_paths(items), None),
+            Operation::EmptyTrash => ("empty-trash", Vec::new(), None),
+            Operation::Move { paths, to, .. } => ("move", paths.clone(), Some(to.clone())),
+            // Network items are logged by their URIs
+            Operation::NetworkDelete { uris } => ("permanently-delete", uri_paths(uris), None),
+            Operation::NetworkMove { uris, to } => {
+                ("move", uri_paths(uris), Some(PathBuf::from(to)))
+            }
+            Operation::NetworkRename { from, to } => {
+                ("rename", vec![PathBuf::from(from)], Some(PathBuf::from(to)))
+            }
+            Operation::PermanentlyDelete { paths } => ("permanently-delete", paths.clone(), None),
+            Operation::Rename { from, to } => ("rename", vec![from.clone()], Some(to.clone())),
+            Operation::Restore { items } => ("restore", trash_paths(items), None),
+            Operation::SecureDelete { paths } => ("secure-delete", paths.clone(), None),
+            Operation::SetPermissionsRecursive { path, .. } => {
+                ("set-permissions-recursive", vec![path.clone()], None)
+            }
+            Operation::StripMetadata { paths } => ("strip-metadata", paths.clone(), None),
+            Operation::TransformImages { paths, .. } => ("transform-images", paths.clone(), None),
+            _ => return None,
+        };
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Some(Self {
+            operation: kind.to_string(),
+            time,
+            from,
+            to,
+            error,
+            conflicts,
+        })
+    }
+
+    pub fn system_time(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.time)
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    let dir = dirs::state_dir().or_else(dirs::data_local_dir)?;
+    Some(dir.join("cosmic-files").join("operations.jsonl"))
+}
+
+/// Older entries, kept until the log fills up again
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_os_string();
+    backup.push(".1");
+    PathBuf::from(backup)
+}
+
+pub fn append(entry: &AuditEntry) -> io::Result<()> {
+    let path = path().ok_or_else(|| io::Error::other("no state directory"))?;
+    append_to(&path, entry, AUDIT_LOG_MAX_SIZE)
+}
+
+fn append_to(path: &Path, entry: &AuditEntry, max_size: u64) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= max_size) {
+        fs::rename(path, backup_path(path))?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+/// Read the most recent entries, newest first
+pub fn read(limit: usize) -> io::Result<Vec<AuditEntry>> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+    read_from(&path, limit)
+}
+
+fn read_from(path: &Path, limit: usize) -> io::Result<Vec<AuditEntry>> {
+    let mut entries = read_file(path)?;
+    if entries.len() < limit {
+        let mut older = read_file(&backup_path(path))?;
+        older.append(&mut entries);
+        entries = older;
+    }
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Entries of one file, which is never much larger than `AUDIT_LOG_MAX_SIZE`, oldest first
+fn read_file(path: &Path) -> io::Result<Vec<AuditEntry>> {
+    let file = match fs::File::open(path) {
+        Ok(ok) => ok,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut entries = Vec::new();
+    for line_res in io::BufReader::new(file).lines() {
+        let line = line_res?;
+        match serde_json::from_str::<AuditEntry>(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => log::warn!("invalid audit log entry {:?}: {}", line, err),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_to, read_from, AuditConflict, AuditEntry};
+    use crate::operation::{Operation, OperationSelection, Resolution};
+    use std::{io, path::PathBuf};
+    use tempfile::TempDir;
+
+    #[test]
+    fn audit_copy_conflicts() {
+        let copy = Operation::Copy {
+            paths: vec![PathBuf::from("/a/x.txt"), PathBuf::from("/a/y.txt")],
+            to: PathBuf::from("/b"),
+        };
+        let mut op_sel = OperationSelection::default();
+        op_sel
+            .conflicts
+            .push((PathBuf::from("/b/x.txt"), Resolution::Skipped));
+        assert!(AuditEntry::new(&copy, Ok(&op_sel)).is_none());
+
+        op_sel.conflicts.push((
+            PathBuf::from("/b/y.txt"),
+            Resolution::Renamed(PathBuf::from("/b/y (copy 1).txt")),
+        ));
+        op_sel
+            .conflicts
+            .push((PathBuf::from("/b/z.txt"), Resolution::Replaced));
+        let entry = AuditEntry::new(&copy, Ok(&op_sel)).expect("replacing should be logged");
+        let line = serde_json::to_string(&entry).unwrap();
+        let parsed: AuditEntry = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            parsed.conflicts,
+            vec![
+                AuditConflict::Skipped {
+                    path: PathBuf::from("/b/x.txt")
+                },
+                AuditConflict::Renamed {
+                    path: PathBuf::from("/b/y.txt"),
+                    to: PathBuf::from("/b/y (copy 1).txt")
+                },
+                AuditConflict::Replaced {
+                    path: PathBuf::from("/b/z.txt")
+                },
+            ]
+        );
+
+        // Entries written before conflicts were recorded
+        let parsed: AuditEntry = serde_json::from_str(
+            r#"{"operation":"move","time":1,"from":["/a"],"to":"/b","error":null}"#,
+        )
+        .unwrap();
+        assert!(parsed.conflicts.is_empty());
+    }
+
+    #[test]
+    fn audit_log_rotation() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("operations.jsonl");
+        for i in 0..5 {
+            let delete = Operation::Delete {
+                paths: vec![PathBuf::from(format!("/{i}"))],
+            };
+            let entry = AuditEntry::new(&delete, Ok(&OperationSelection::default())).unwrap();
+            append_to(&path, &entry, 100)?;
+        }
+
+        let from = |entries: Vec<AuditEntry>| -> Vec<PathBuf> {
+            entries.into_iter().flat_map(|entry| entry.from).collect()
+        };
+        // Only the log and its backup are kept
+        let entries = read_from(&path, 10)?;
+        assert!(entries.len() < 5);
+        assert_eq!(from(entries)[0], PathBuf::from("/4"));
+        assert_eq!(
+            from(read_from(&path, 2)?),
+            vec![PathBuf::from("/4"), PathBuf::from("/3")]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Checksums of files, and verifying them against a pasted value or a checksum file
+
+use sha2::Digest;
+use std::{
+    ffi::OsStr,
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::{fl, operation::Controller};
+
+/// Size of the reads used to hash a file
+const CHECKSUM_BUFFER_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChecksumAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: [Self; 4] = [Self::Md5, Self::Sha1, Self::Sha256, Self::Blake3];
+
+    /// Algorithms whose checksums have this many hex digits
+    pub fn for_len(len: usize) -> Vec<Self> {
+        match len {
+            32 => vec![Self::Md5],
+            40 => vec![Self::Sha1],
+            64 => vec![Self::Sha256, Self::Blake3],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Md5 => write!(f, "MD5"),
+            Self::Sha1 => write!(f, "SHA-1"),
+            Self::Sha256 => write!(f, "SHA-256"),
+            Self::Blake3 => write!(f, "BLAKE3"),
+        }
+    }
+}
+
+enum Hasher {
+    Md5(md5::Md5),
+    Sha1(sha1::Sha1),
+    Sha256(sha2::Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Md5 => Self::Md5(md5::Md5::new()),
+            ChecksumAlgorithm::Sha1 => Self::Sha1(sha1::Sha1::new()),
+            ChecksumAlgorithm::Sha256 => Self::Sha256(sha2::Sha256::new()),
+            ChecksumAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(hasher) => hasher.update(data),
+            Self::Sha1(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        let bytes = match self {
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+            Self::Sha1(hasher) => hasher.finalize().to_vec(),
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::Blake3(hasher) => hasher.finalize().as_bytes().to_vec(),
+        };
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+/// Checksums of a file with each of the algorithms, reading it only once
+pub fn compute(
+    path: &Path,
+    algorithms: &[ChecksumAlgorithm],
+    controller: &Controller,
+) -> Result<Vec<(ChecksumAlgorithm, String)>, String> {
+    let mut file = File::open(path).map_err(|err| err.to_string())?;
+    let mut hashers: Vec<_> = algorithms
+        .iter()
+        .map(|algorithm| (*algorithm, Hasher::new(*algorithm)))
+        .collect();
+    let mut buffer = vec![0; CHECKSUM_BUFFER_SIZE];
+    loop {
+        if controller.is_cancelled() {
+            return Err(fl!("cancelled"));
+        }
+        let count = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(count) => count,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.to_string()),
+        };
+        for (_, hasher) in hashers.iter_mut() {
+            hasher.update(&buffer[..count]);
+        }
+    }
+    Ok(hashers
+        .into_iter()
+        .map(|(algorithm, hasher)| (algorithm, hasher.finalize()))
+        .collect())
+}
+
+/// Lowercase checksum without surrounding whitespace, or `None` if it is not hex
+fn normalize(checksum: &str) -> Option<String> {
+    let checksum = checksum.trim().to_lowercase();
+    (!checksum.is_empty() && checksum.chars().all(|c| c.is_ascii_hexdigit())).then_some(checksum)
+}
+
+/// Checksum of the file named `name` in a checksum file, in the format written by `sha256sum` and
+/// similar tools or their BSD style tagged format. A file with only one checksum matches any name.
+pub fn parse_checksum_file(contents: &str, name: &str) -> Option<String> {
+    let mut checksums = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // BSD style, like `SHA256 (name) = checksum`
+        if let Some((tag, checksum)) = line.rsplit_once(") = ") {
+            if let Some((_, file_name)) = tag.split_once(" (") {
+                checksums.push((Some(file_name), checksum));
+                continue;
+            }
+        }
+        // GNU style, like `checksum  name` or `checksum *name` for binary mode
+        match line.split_once(char::is_whitespace) {
+            Some((checksum, file_name)) => {
+                let file_name = file_name.trim_start();
+                let file_name = file_name.strip_prefix('*').unwrap_or(file_name);
+                checksums.push((Some(file_name), checksum));
+            }
+            None => checksums.push((None, line)),
+        }
+    }
+    let matching = checksums.iter().find(|(file_name, _)| {
+        file_name.is_some_and(|file_name| {
+            // Names may include a relative path
+            file_name == name || Path::new(file_name).file_name() == Some(OsStr::new(name))
+        })
+    });
+    let (_, checksum) = match matching {
+        Some(some) => some,
+        None if checksums.len() == 1 => &checksums[0],
+        None => return None,
+    };
+    normalize(checksum)
+}
+
+/// Compare a file against an expected checksum, trying each algorithm with checksums of the same
+/// length. Returns the algorithm that matched, or `None` if the file does not match.
+pub fn verify(
+    path: &Path,
+    expected: &str,
+    controller: &Controller,
+) -> Result<Option<ChecksumAlgorithm>, String> {
+    let expected = normalize(expected).ok_or_else(|| fl!("checksum-invalid"))?;
+    let algorithms = ChecksumAlgorithm::for_len(expected.len());
+    if algorithms.is_empty() {
+        return Err(fl!("checksum-invalid"));
+    }
+    Ok(compute(path, &algorithms, controller)?
+        .into_iter()
+        .find(|(_, checksum)| *checksum == expected)
+        .map(|(algorithm, _)| algorithm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute, parse_checksum_file, verify, ChecksumAlgorithm};
+    use crate::operation::Controller;
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn checksums_of_file() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("abc.txt");
+        fs::write(&path, b"abc")?;
+
+        let controller = Controller::default();
+        let checksums =
+            compute(&path, &ChecksumAlgorithm::ALL, &controller).map_err(io::Error::other)?;
+        assert_eq!(
+            checksums,
+            [
+                (
+                    ChecksumAlgorithm::Md5,
+                    "900150983cd24fb0d6963f7d28e17f72".to_string()
+                ),
+                (
+                    ChecksumAlgorithm::Sha1,
+                    "a9993e364706816aba3e25717850c26c9cd0d89d".to_string()
+                ),
+                (
+                    ChecksumAlgorithm::Sha256,
+                    "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string()
+                ),
+                (
+                    ChecksumAlgorithm::Blake3,
+                    "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85".to_string()
+                ),
+            ]
+        );
+
+        // Checksums of the same length are told apart by trying each algorithm
+        assert_eq!(
+            verify(
+                &path,
+                " 6437B3AC38465133FFB63B75273A8DB548C558465D79DB03FD359C6CD5BD9D85\n",
+                &controller
+            ),
+            Ok(Some(ChecksumAlgorithm::Blake3))
+        );
+        assert_eq!(
+            verify(&path, "00000000000000000000000000000000", &controller),
+            Ok(None)
+        );
+        assert!(verify(&path, "not a checksum", &controller).is_err());
+
+        controller.cancel();
+        assert!(compute(&path, &ChecksumAlgorithm::ALL, &controller).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn checksum_files() {
+        let gnu = "d41d8cd98f00b204e9800998ecf8427e  other.iso\n\
+                   900150983cd24fb0d6963f7d28e17f72 *dir/abc.txt\n";
+        assert_eq!(
+            parse_checksum_file(gnu, "abc.txt").as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert_eq!(parse_checksum_file(gnu, "missing.txt"), None);
+
+        let bsd = "MD5 (abc.txt) = 900150983CD24FB0D6963F7D28E17F72";
+        assert_eq!(
+            parse_checksum_file(bsd, "abc.txt").as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+
+        // A single checksum applies to any file
+        assert_eq!(
+            parse_checksum_file("900150983cd24fb0d6963f7d28e17f72\n", "renamed.txt").as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+    }
+}
@@ -26,33 +26,14 @@ pub struct ClipboardCopy {
 
 impl ClipboardCopy {
     pub fn new<P: AsRef<Path>>(kind: ClipboardKind, paths: &[P]) -> Self {
-        let available = vec![
-            "text/plain".to_string(),
-            "text/plain;charset=utf-8".to_string(),
-            "UTF8_STRING".to_string(),
-            "text/uri-list".to_string(),
-            "x-special/gnome-copied-files".to_string(),
-        ];
-        let mut text_plain = String::new();
-        let mut text_uri_list = String::new();
-        let mut x_special_gnome_copied_files = match kind {
-            ClipboardKind::Copy => "copy",
-            ClipboardKind::Cut { .. } => "cut",
-        }
-        .to_string();
-        //TODO: do we have to use \r\n?
-        let cr_nl = "\r\n";
+        let mut text_plain = Vec::new();
+        let mut urls = Vec::new();
         for path in paths.iter() {
             let path = path.as_ref();
 
             match path.to_str() {
-                Some(path_str) => {
-                    if !text_plain.is_empty() {
-                        text_plain.push_str(cr_nl);
-                    }
-                    //TOOD: what if the path contains CR or NL?
-                    text_plain.push_str(path_str);
-                }
+                //TOOD: what if the path contains CR or NL?
+                Some(path_str) => text_plain.push(path_str.to_string()),
                 None => {
                     //TODO: allow non-UTF-8?
                     log::warn!(
@@ -63,15 +44,7 @@ impl ClipboardCopy {
             }
 
             match Url::from_file_path(path) {
-                Ok(url) => {
-                    let url_str = url.as_ref();
-
-                    text_uri_list.push_str(url_str);
-                    text_uri_list.push_str(cr_nl);
-
-                    x_special_gnome_copied_files.push('\n');
-                    x_special_gnome_copied_files.push_str(url_str);
-                }
+                Ok(url) => urls.push(String::from(url)),
                 Err(()) => {
                     log::warn!(
                         "{:?} cannot be turned into a URL, not adding to text/uri-list clipboard",
@@ -80,6 +53,38 @@ impl ClipboardCopy {
                 }
             }
         }
+        Self::from_lines(kind, &text_plain, &urls)
+    }
+
+    /// Items on network locations without local paths, given by their URIs
+    pub fn from_uris(kind: ClipboardKind, uris: &[String]) -> Self {
+        Self::from_lines(kind, uris, uris)
+    }
+
+    fn from_lines(kind: ClipboardKind, text_plain: &[String], urls: &[String]) -> Self {
+        let available = vec![
+            "text/plain".to_string(),
+            "text/plain;charset=utf-8".to_string(),
+            "UTF8_STRING".to_string(),
+            "text/uri-list".to_string(),
+            "x-special/gnome-copied-files".to_string(),
+        ];
+        //TODO: do we have to use \r\n?
+        let cr_nl = "\r\n";
+        let text_plain = text_plain.join(cr_nl);
+        let mut text_uri_list = String::new();
+        let mut x_special_gnome_copied_files = match kind {
+            ClipboardKind::Copy => "copy",
+            ClipboardKind::Cut { .. } => "cut",
+        }
+        .to_string();
+        for url in urls.iter() {
+            text_uri_list.push_str(url);
+            text_uri_list.push_str(cr_nl);
+
+            x_special_gnome_copied_files.push('\n');
+            x_special_gnome_copied_files.push_str(url);
+        }
         Self {
             available: Cow::from(available),
             text_plain: Cow::from(text_plain.into_bytes()),
@@ -110,6 +115,8 @@ impl AsMimeTypes for ClipboardCopy {
 pub struct ClipboardPaste {
     pub kind: ClipboardKind,
     pub paths: Vec<PathBuf>,
+    /// Items on network locations without local paths
+    pub uris: Vec<String>,
 }
 
 impl AllowedMimeTypes for ClipboardPaste {
@@ -128,15 +135,23 @@ impl TryFrom<(Vec<u8>, String)> for ClipboardPaste {
         // Assume the kind is Copy if not provided by the mime type
         let mut kind = ClipboardKind::Copy;
         let mut paths = Vec::new();
+        let mut uris = Vec::new();
+        let mut push_url = |url: Url| -> Result<(), Box<dyn Error>> {
+            if url.scheme() != "file" {
+                uris.push(url.into());
+                return Ok(());
+            }
+            match url.to_file_path() {
+                Ok(path) => paths.push(path),
+                Err(()) => Err(format!("invalid file URL {:?}", url))?,
+            }
+            Ok(())
+        };
         match mime.as_str() {
             "text/uri-list" => {
                 let text = str::from_utf8(&data)?;
                 for line in text.lines() {
-                    let url = Url::parse(line)?;
-                    match url.to_file_path() {
-                        Ok(path) => paths.push(path),
-                        Err(()) => Err(format!("invalid file URL {:?}", url))?,
-                    }
+                    push_url(Url::parse(line)?)?;
                 }
             }
             "x-special/gnome-copied-files" => {
@@ -149,16 +164,12 @@ impl TryFrom<(Vec<u8>, String)> for ClipboardPaste {
                             _ => Err(format!("unsupported clipboard operation {:?}", line))?,
                         };
                     } else {
-                        let url = Url::parse(line)?;
-                        match url.to_file_path() {
-                            Ok(path) => paths.push(path),
-                            Err(()) => Err(format!("invalid file URL {:?}", url))?,
-                        }
+                        push_url(Url::parse(line)?)?;
                     }
                 }
             }
             _ => Err(format!("unsupported mime type {:?}", mime))?,
         }
-        Ok(Self { kind, paths })
+        Ok(Self { kind, paths, uris })
     }
 }
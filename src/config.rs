@@ -1,6 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
-use std::{any::TypeId, num::NonZeroU16, path::PathBuf};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroU16,
+    path::PathBuf,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
@@ -9,7 +16,11 @@ use cosmic::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{app::App, tab::View};
+use crate::{
+    app::App,
+    tab::{HeadingOptions, Location, View},
+    tag::ColorTag,
+};
 
 pub const CONFIG_VERSION: u64 = 1;
 
@@ -19,6 +30,21 @@ pub const ICON_SIZE_LIST_CONDENSED: u16 = 48;
 pub const ICON_SIZE_GRID: u16 = 64;
 // TODO: 5 is an arbitrary number. Maybe there's a better icon size max
 pub const ICON_SCALE_MAX: u16 = 5;
+// Icon sizes offered in the View menu, in percent
+pub const ZOOM_PRESETS: [u16; 8] = [50, 75, 100, 125, 150, 200, 300, 500];
+
+// Details pane width bounds
+pub const DETAILS_WIDTH_DEFAULT: u16 = 360;
+pub const DETAILS_WIDTH_MIN: u16 = 240;
+pub const DETAILS_WIDTH_MAX: u16 = 720;
+
+// List view column width bounds
+pub const COLUMN_WIDTH_MIN: u16 = 60;
+pub const COLUMN_WIDTH_MAX: u16 = 600;
+
+// Gallery slideshow interval bounds, in seconds
+pub const SLIDESHOW_INTERVAL_MIN: u16 = 1;
+pub const SLIDESHOW_INTERVAL_MAX: u16 = 60;
 
 macro_rules! percent {
     ($perc:expr, $pixel:ident) => {
@@ -101,15 +127,219 @@ pub enum TypeToSearch {
     EnterPath,
 }
 
+/// Optional buttons for the toolbar below the tab bar
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ToolbarButton {
+    NewFolder,
+    ToggleHidden,
+    ViewSwitch,
+    Terminal,
+    Up,
+    Refresh,
+}
+
+impl ToolbarButton {
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::NewFolder,
+            Self::ToggleHidden,
+            Self::ViewSwitch,
+            Self::Terminal,
+            Self::Up,
+            Self::Refresh,
+        ]
+    }
+}
+
+/// What copies and moves do with items that already exist at the destination
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ConflictPolicy {
+    Ask,
+    KeepBoth,
+    /// Replace existing items that were modified before the new ones, and skip the rest
+    ReplaceOlder,
+    Skip,
+}
+
+/// When thumbnailing and folder size calculation are slowed down to save power
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ReduceBackgroundWork {
+    /// On battery or in power saver mode
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DetailsPosition {
+    Left,
+    Right,
+}
+
+/// Tab of the details pane, remembered when switching between items
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum DetailsTab {
+    Preview,
+    Properties,
+    Permissions,
+    Versions,
+    /// Access control list and extended attributes
+    Advanced,
+}
+
+impl DetailsTab {
+    pub const ALL: [Self; 5] = [
+        Self::Preview,
+        Self::Properties,
+        Self::Permissions,
+        Self::Versions,
+        Self::Advanced,
+    ];
+}
+
+/// Location of a tab in a saved session
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SessionLocation {
+    Computer,
+    Favorites,
+    Network(String, String),
+    Path(PathBuf),
+    Recents,
+    Tag(ColorTag),
+    Trash,
+}
+
+impl SessionLocation {
+    /// Searches are saved as the folder that was searched, and the desktop is not saved
+    pub fn from_location(location: &Location) -> Option<Self> {
+        match location {
+            Location::Computer => Some(Self::Computer),
+            Location::Dates(path, _) => Some(Self::Path(path.clone())),
+            Location::Favorites => Some(Self::Favorites),
+            Location::Desktop(..) => None,
+            Location::Network(uri, name) => Some(Self::Network(uri.clone(), name.clone())),
+            Location::Path(path) | Location::Search(path, ..) => Some(Self::Path(path.clone())),
+            Location::Recents => Some(Self::Recents),
+            Location::Tag(tag) => Some(Self::Tag(*tag)),
+            Location::Trash => Some(Self::Trash),
+        }
+    }
+
+    pub fn location(&self) -> Location {
+        match self {
+            Self::Computer => Location::Computer,
+            Self::Favorites => Location::Favorites,
+            Self::Network(uri, name) => Location::Network(uri.clone(), name.clone()),
+            Self::Path(path) => Location::Path(path.clone()),
+            Self::Recents => Location::Recents,
+            Self::Tag(tag) => Location::Tag(*tag),
+            Self::Trash => Location::Trash,
+        }
+    }
+}
+
+/// Tab in a saved session
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SessionTab {
+    pub location: SessionLocation,
+    pub view: View,
+    pub sort_name: HeadingOptions,
+    pub sort_direction: bool,
+    /// Vertical scroll offset in pixels
+    pub scroll: u32,
+}
+
+/// Window in a saved session, where every window is a separate process
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SessionWindow {
+    /// Identifies the window when it is restored by a new process
+    pub id: u64,
+    /// Process that last showed the window
+    pub pid: u32,
+    pub tabs: Vec<SessionTab>,
+    /// Index of the active tab
+    pub active: usize,
+}
+
+impl SessionWindow {
+    pub fn new_id() -> u64 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+        nanos ^ (u64::from(process::id()) << 32)
+    }
+
+    /// Returns true if the process that last showed the window is still running
+    pub fn running(&self) -> bool {
+        #[cfg(unix)]
+        {
+            // Signal 0 only checks that the process exists
+            unsafe { libc::kill(self.pid as libc::pid_t, 0) == 0 }
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+}
+
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(default)]
 pub struct Config {
     pub app_theme: AppTheme,
+    /// Append destructive operations to the audit log
+    pub audit_log: bool,
+    /// Default for items that already exist when copying or moving, see also
+    /// [`Config::keep_both_folders`]
+    pub conflict_policy: ConflictPolicy,
     pub desktop: DesktopConfig,
+    /// Side of the window the details pane is placed on
+    pub details_position: DetailsPosition,
+    /// Tab last shown in the details pane
+    pub details_tab: DetailsTab,
+    /// Width of the details pane, between [`DETAILS_WIDTH_MIN`] and [`DETAILS_WIDTH_MAX`]
+    pub details_width: u16,
+    /// Archive MIME types that are extracted next to the archive when opened
+    pub extract_on_open: Vec<String>,
     pub favorites: Vec<Favorite>,
+    /// List view columns chosen for each folder, folders without an entry use
+    /// [`HeadingOptions::DEFAULT_COLUMNS`]
+    pub folder_columns: BTreeMap<PathBuf, Vec<HeadingOptions>>,
+    /// List view column widths chosen for each folder, folders without an entry use
+    /// [`TabConfig::column_widths`]
+    pub folder_column_widths: BTreeMap<PathBuf, ColumnWidths>,
+    /// Folders always shown first in the frequently used folders bar
+    pub frequent_pinned: Vec<PathBuf>,
+    /// Glob patterns for file names that are hidden, in addition to dot-files
+    pub hidden_patterns: Vec<String>,
+    /// Folders where pasted and dropped items are renamed instead of replacing existing items
+    pub keep_both_folders: Vec<PathBuf>,
+    pub new_tab: NewTabConfig,
+    /// Open the destination of copies and extractions in a new tab when they finish
+    pub open_destination: bool,
+    pub reduce_background_work: ReduceBackgroundWork,
+    /// Reopen the windows and tabs of the previous session on startup
+    pub restore_session: bool,
+    /// Windows saved for [`Config::restore_session`]
+    pub session: Vec<SessionWindow>,
     pub show_details: bool,
+    /// Show a bar of frequently used folders above the view
+    pub show_frequent_folders: bool,
+    /// Files and folders starred with [`Action::ToggleFavorite`](crate::app::Action), shown
+    /// together in the favorites view. These are separate from the sidebar favorites.
+    pub starred: BTreeSet<PathBuf>,
     pub tab: TabConfig,
+    /// Toolbar buttons in display order, the toolbar is hidden when empty
+    pub toolbar: Vec<ToolbarButton>,
+    /// Trashed items are permanently deleted this many days after they were trashed, never if 0
+    pub trash_max_days: u32,
+    /// The oldest trashed items are permanently deleted while the trash is larger than this many
+    /// bytes, no limit if 0
+    pub trash_max_size: u64,
     pub type_to_search: TypeToSearch,
+    /// Flush writes to removable drives as they happen, so that progress is accurate and
+    /// ejecting is quick
+    pub write_through_removable: bool,
 }
 
 impl Config {
@@ -146,7 +376,13 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             app_theme: AppTheme::System,
+            audit_log: false,
+            conflict_policy: ConflictPolicy::Ask,
             desktop: DesktopConfig::default(),
+            details_position: DetailsPosition::Right,
+            details_tab: DetailsTab::Preview,
+            details_width: DETAILS_WIDTH_DEFAULT,
+            extract_on_open: Vec::new(),
             favorites: vec![
                 Favorite::Home,
                 Favorite::Documents,
@@ -155,9 +391,25 @@ impl Default for Config {
                 Favorite::Pictures,
                 Favorite::Videos,
             ],
+            folder_columns: BTreeMap::new(),
+            folder_column_widths: BTreeMap::new(),
+            frequent_pinned: Vec::new(),
+            hidden_patterns: Vec::new(),
+            keep_both_folders: Vec::new(),
+            new_tab: NewTabConfig::default(),
+            open_destination: false,
+            reduce_background_work: ReduceBackgroundWork::Auto,
+            restore_session: false,
+            session: Vec::new(),
             show_details: false,
+            show_frequent_folders: false,
+            starred: BTreeSet::new(),
             tab: TabConfig::default(),
+            toolbar: Vec::new(),
+            trash_max_days: 0,
+            trash_max_size: 0,
             type_to_search: TypeToSearch::Recursive,
+            write_through_removable: false,
         }
     }
 }
@@ -190,6 +442,17 @@ impl DesktopConfig {
     }
 }
 
+/// Location types that open in a new tab when selected in the sidebar, instead of replacing the
+/// current tab
+#[derive(
+    Clone, Copy, Debug, Default, Eq, Hash, PartialEq, CosmicConfigEntry, Deserialize, Serialize,
+)]
+#[serde(default)]
+pub struct NewTabConfig {
+    pub drives: bool,
+    pub network: bool,
+}
+
 /// Global and local [`crate::tab::Tab`] config.
 ///
 /// [`TabConfig`] contains options that are passed to each instance of [`crate::tab::Tab`].
@@ -211,6 +474,29 @@ pub struct TabConfig {
     pub military_time: bool,
     /// Single click to open
     pub single_click: bool,
+    /// Double click on empty space to go to the parent folder
+    pub double_click_parent: bool,
+    /// Play animations and videos in grid view while hovered
+    pub animated_preview: bool,
+    /// How folder paths are shown in tab and window titles
+    pub title: TabTitle,
+    /// Widths of the list view columns next to the name in folders without their own widths, and
+    /// in other locations
+    pub column_widths: ColumnWidths,
+    /// Expand folders inline in list view
+    pub tree_view: bool,
+    /// Most columns in grid view, or `None` to fill the width of the window
+    pub grid_columns: Option<NonZeroU16>,
+    /// Split grid view into groups of items of the same kind
+    pub group_by_kind: bool,
+    /// Calculate the total size of folders for the size column in list view
+    pub folder_sizes: bool,
+    /// Show the extension of files without a thumbnail on their icon in grid view
+    pub extension_badges: bool,
+    /// Offer overwriting files before deleting them in the context menu
+    pub secure_delete: bool,
+    /// Slideshow in the gallery
+    pub slideshow: SlideshowConfig,
 }
 
 impl Default for TabConfig {
@@ -222,10 +508,81 @@ impl Default for TabConfig {
             icon_sizes: IconSizes::default(),
             military_time: false,
             single_click: false,
+            double_click_parent: false,
+            animated_preview: false,
+            title: TabTitle::Shortened,
+            column_widths: ColumnWidths::default(),
+            tree_view: false,
+            grid_columns: None,
+            group_by_kind: false,
+            folder_sizes: false,
+            extension_badges: false,
+            secure_delete: false,
+            slideshow: SlideshowConfig::default(),
         }
     }
 }
 
+/// Widths of list view columns, between [`COLUMN_WIDTH_MIN`] and [`COLUMN_WIDTH_MAX`]. The name
+/// column fills the remaining space.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColumnWidths {
+    pub modified: u16,
+    pub size: u16,
+}
+
+impl Default for ColumnWidths {
+    fn default() -> Self {
+        Self {
+            modified: 200,
+            size: 100,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SlideshowConfig {
+    /// Seconds each item is shown, between [`SLIDESHOW_INTERVAL_MIN`] and
+    /// [`SLIDESHOW_INTERVAL_MAX`]
+    pub interval: u16,
+    /// Show the items in a random order
+    pub shuffle: bool,
+    /// Start over after the last item
+    pub repeat: bool,
+}
+
+impl Default for SlideshowConfig {
+    fn default() -> Self {
+        Self {
+            interval: 5,
+            shuffle: false,
+            repeat: false,
+        }
+    }
+}
+
+impl SlideshowConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(
+            self.interval
+                .clamp(SLIDESHOW_INTERVAL_MIN, SLIDESHOW_INTERVAL_MAX)
+                .into(),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TabTitle {
+    /// Only the folder name
+    FolderName,
+    /// The last two folders of the path, such as `…/Projects/cosmic-files`
+    Shortened,
+    /// The full path
+    FullPath,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, CosmicConfigEntry, Deserialize, Serialize)]
 #[serde(default)]
 pub struct IconSizes {
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Virtual folders that group the files of a folder by the date they were modified, for folders
+//! like downloads that are never sorted by hand. Pictures and videos are grouped by year and
+//! month, other folders by how recent the files are.
+
+use std::{collections::BTreeMap, path::Path};
+
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::fl;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Period {
+    Today,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    Older,
+}
+
+impl Period {
+    /// The first period that contains the date, with future dates counted as today
+    pub fn from_date(date: NaiveDate, today: NaiveDate) -> Self {
+        let week_start = today - Days::new(today.weekday().num_days_from_monday().into());
+        let month_start = today.with_day(1).unwrap_or(today);
+        if date >= today {
+            Self::Today
+        } else if date >= week_start {
+            Self::ThisWeek
+        } else if date >= week_start - Days::new(7) {
+            Self::LastWeek
+        } else if date >= month_start {
+            Self::ThisMonth
+        } else if date >= month_start - Months::new(1) {
+            Self::LastMonth
+        } else {
+            Self::Older
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Today => fl!("today"),
+            Self::ThisWeek => fl!("earlier-this-week"),
+            Self::LastWeek => fl!("last-week"),
+            Self::ThisMonth => fl!("earlier-this-month"),
+            Self::LastMonth => fl!("last-month"),
+            Self::Older => fl!("older"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DateFolder {
+    Period(Period),
+    Year(i32),
+    Month(i32, u32),
+}
+
+impl DateFolder {
+    /// Folder one level up, `None` for the folders shown first
+    pub fn parent(self) -> Option<Self> {
+        match self {
+            Self::Month(year, _) => Some(Self::Year(year)),
+            _ => None,
+        }
+    }
+
+    /// Whether the folder lists files instead of other date folders
+    pub fn has_files(self) -> bool {
+        !matches!(self, Self::Year(_))
+    }
+
+    pub fn contains(self, date: NaiveDate, today: NaiveDate) -> bool {
+        match self {
+            Self::Period(period) => Period::from_date(date, today) == period,
+            Self::Year(year) => date.year() == year,
+            Self::Month(year, month) => date.year() == year && date.month() == month,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Period(period) => period.name(),
+            Self::Year(year) => year.to_string(),
+            Self::Month(year, month) => fl!("date-month", year = year, month = month),
+        }
+    }
+}
+
+/// Pictures and videos are grouped by year and month, other folders by recent periods
+pub fn by_calendar(path: &Path) -> bool {
+    [dirs::picture_dir(), dirs::video_dir()]
+        .into_iter()
+        .flatten()
+        .any(|dir| path.starts_with(dir))
+}
+
+/// Date folders inside a date folder, or inside the top of the view with `None`, with the number
+/// of files in each. Folders are in the order they are shown, the most recent first.
+pub fn subfolders(
+    dates: &[NaiveDate],
+    today: NaiveDate,
+    by_calendar: bool,
+    parent_opt: Option<DateFolder>,
+) -> Vec<(DateFolder, u64)> {
+    let mut counts = BTreeMap::new();
+    for &date in dates {
+        let folder_opt = match parent_opt {
+            None if by_calendar => Some(DateFolder::Year(date.year())),
+            None => Some(DateFolder::Period(Period::from_date(date, today))),
+            Some(DateFolder::Year(year)) if date.year() == year => {
+                Some(DateFolder::Month(year, date.month()))
+            }
+            _ => None,
+        };
+        if let Some(folder) = folder_opt {
+            *counts.entry(folder).or_insert(0) += 1;
+        }
+    }
+    let mut folders: Vec<_> = counts.into_iter().collect();
+    // Periods are declared from the most recent, years and months count up
+    if by_calendar {
+        folders.reverse();
+    }
+    folders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{subfolders, DateFolder, Period};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn date_folders() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        // A Thursday
+        let today = date(2024, 3, 14);
+
+        assert_eq!(Period::from_date(date(2024, 3, 15), today), Period::Today);
+        assert_eq!(Period::from_date(today, today), Period::Today);
+        assert_eq!(
+            Period::from_date(date(2024, 3, 11), today),
+            Period::ThisWeek
+        );
+        assert_eq!(Period::from_date(date(2024, 3, 4), today), Period::LastWeek);
+        assert_eq!(
+            Period::from_date(date(2024, 3, 1), today),
+            Period::ThisMonth
+        );
+        assert_eq!(
+            Period::from_date(date(2024, 2, 1), today),
+            Period::LastMonth
+        );
+        assert_eq!(Period::from_date(date(2024, 1, 31), today), Period::Older);
+
+        let dates = [
+            today,
+            date(2024, 3, 12),
+            date(2024, 2, 20),
+            date(2023, 12, 24),
+            date(2023, 12, 25),
+            date(2023, 6, 1),
+        ];
+        assert_eq!(
+            subfolders(&dates, today, false, None),
+            vec![
+                (DateFolder::Period(Period::Today), 1),
+                (DateFolder::Period(Period::ThisWeek), 1),
+                (DateFolder::Period(Period::LastMonth), 1),
+                (DateFolder::Period(Period::Older), 3),
+            ]
+        );
+        assert_eq!(
+            subfolders(&dates, today, true, None),
+            vec![(DateFolder::Year(2024), 3), (DateFolder::Year(2023), 3)]
+        );
+        assert_eq!(
+            subfolders(&dates, today, true, Some(DateFolder::Year(2023))),
+            vec![
+                (DateFolder::Month(2023, 12), 2),
+                (DateFolder::Month(2023, 6), 1)
+            ]
+        );
+        assert!(DateFolder::Month(2023, 12).contains(date(2023, 12, 24), today));
+        assert!(!DateFolder::Month(2023, 12).contains(date(2024, 12, 24), today));
+        assert_eq!(
+            DateFolder::Month(2023, 12).parent(),
+            Some(DateFolder::Year(2023))
+        );
+    }
+}
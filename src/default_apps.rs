@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Default applications chosen by the user, written to the `[Default Applications]` section of
+//! their `mimeapps.list` while keeping the other lines and sections as they are.
+
+use mime_guess::{mime, Mime};
+
+/// Group of MIME types whose default application is chosen at once in the settings
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AppCategory {
+    Images,
+    Videos,
+    Music,
+    Text,
+    Archives,
+}
+
+impl AppCategory {
+    pub const ALL: [Self; 5] = [
+        Self::Images,
+        Self::Videos,
+        Self::Music,
+        Self::Text,
+        Self::Archives,
+    ];
+
+    /// Type whose applications are offered for the whole category
+    pub fn primary_mime(self) -> Mime {
+        match self {
+            Self::Images => mime::IMAGE_PNG,
+            Self::Videos => "video/mp4".parse().unwrap(),
+            Self::Music => "audio/mpeg".parse().unwrap(),
+            Self::Text => mime::TEXT_PLAIN,
+            Self::Archives => "application/zip".parse().unwrap(),
+        }
+    }
+
+    pub fn contains(self, mime: &Mime) -> bool {
+        match self {
+            Self::Images => mime.type_() == mime::IMAGE,
+            Self::Videos => mime.type_() == mime::VIDEO,
+            Self::Music => mime.type_() == mime::AUDIO,
+            Self::Text => mime.type_() == mime::TEXT,
+            Self::Archives => matches!(
+                mime.essence_str(),
+                "application/gzip"
+                    | "application/vnd.rar"
+                    | "application/x-7z-compressed"
+                    | "application/x-bzip"
+                    | "application/x-bzip-compressed-tar"
+                    | "application/x-bzip2"
+                    | "application/x-bzip2-compressed-tar"
+                    | "application/x-compressed-tar"
+                    | "application/x-rar"
+                    | "application/x-tar"
+                    | "application/x-xz"
+                    | "application/x-xz-compressed-tar"
+                    | "application/x-zstd-compressed-tar"
+                    | "application/zip"
+                    | "application/zstd"
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "desktop", test))]
+const DEFAULT_APPLICATIONS: &str = "[Default Applications]";
+
+#[cfg(any(feature = "desktop", test))]
+fn desktop_file_id(id: &str) -> String {
+    if id.ends_with(".desktop") {
+        id.to_string()
+    } else {
+        format!("{}.desktop", id)
+    }
+}
+
+/// Set the default application of the MIME types in the contents of a mimeapps.list file,
+/// replacing the entries they have and adding the section if it is missing
+#[cfg(any(feature = "desktop", test))]
+pub fn set_default_apps(contents: &str, mimes: &[&str], id: &str) -> String {
+    let desktop_file_id = desktop_file_id(id);
+    let entry = |mime: &str| format!("{}={};", mime, desktop_file_id);
+
+    let mut section = "";
+    let mut found_section = false;
+    let mut lines = Vec::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            // New entries go at the end of the section, before the blank lines separating it
+            if section == DEFAULT_APPLICATIONS {
+                let blank = lines
+                    .iter()
+                    .rev()
+                    .take_while(|line: &&String| line.trim().is_empty())
+                    .count();
+                let at = lines.len() - blank;
+                lines.splice(at..at, mimes.iter().map(|mime| entry(mime)));
+            }
+            section = trimmed;
+            found_section |= section == DEFAULT_APPLICATIONS;
+        } else if section == DEFAULT_APPLICATIONS {
+            if let Some((key, _)) = trimmed.split_once('=') {
+                if mimes.contains(&key.trim()) {
+                    continue;
+                }
+            }
+        }
+        lines.push(line.to_string());
+    }
+    if section == DEFAULT_APPLICATIONS {
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.extend(mimes.iter().map(|mime| entry(mime)));
+    } else if !found_section {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(DEFAULT_APPLICATIONS.to_string());
+        lines.extend(mimes.iter().map(|mime| entry(mime)));
+    }
+
+    let mut string = lines.join("\n");
+    string.push('\n');
+    string
+}
+
+/// Remove the default application of a MIME type from the contents of a mimeapps.list file,
+/// returning `None` if the file does not set one
+#[cfg(any(feature = "desktop", test))]
+pub fn remove_default_app(contents: &str, mime: &str) -> Option<String> {
+    let mut section = "";
+    let mut removed = false;
+    let mut lines = Vec::new();
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed;
+        } else if section == DEFAULT_APPLICATIONS {
+            if let Some((key, _)) = trimmed.split_once('=') {
+                if key.trim() == mime {
+                    removed = true;
+                    continue;
+                }
+            }
+        }
+        lines.push(line);
+    }
+    removed.then(|| {
+        let mut string = lines.join("\n");
+        string.push('\n');
+        string
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{remove_default_app, set_default_apps, AppCategory};
+
+    #[test]
+    fn remove_default_app_entry() {
+        let contents = "[Added Associations]\ntext/plain=gedit.desktop;\n\n[Default Applications]\ntext/plain=com.system76.CosmicEdit.desktop\nimage/png=org.gnome.eog.desktop\n";
+        assert_eq!(
+            remove_default_app(contents, "text/plain").as_deref(),
+            Some("[Added Associations]\ntext/plain=gedit.desktop;\n\n[Default Applications]\nimage/png=org.gnome.eog.desktop\n")
+        );
+        assert_eq!(remove_default_app(contents, "video/mp4"), None);
+    }
+
+    #[test]
+    fn set_default_app_entries() {
+        // Replaced in place of the old entry, with other sections and comments kept
+        let contents = "# Edited by hand\n[Default Applications]\nimage/png=org.gnome.eog.desktop\ntext/plain=gedit.desktop\n\n[Added Associations]\nimage/png=gimp.desktop;\n";
+        assert_eq!(
+            set_default_apps(contents, &["image/png", "image/jpeg"], "org.gnome.Loupe"),
+            "# Edited by hand\n[Default Applications]\ntext/plain=gedit.desktop\nimage/png=org.gnome.Loupe.desktop;\nimage/jpeg=org.gnome.Loupe.desktop;\n\n[Added Associations]\nimage/png=gimp.desktop;\n"
+        );
+
+        // The section is added when missing
+        assert_eq!(
+            set_default_apps(
+                "[Added Associations]\ntext/plain=gedit.desktop;\n",
+                &["text/plain"],
+                "gedit.desktop"
+            ),
+            "[Added Associations]\ntext/plain=gedit.desktop;\n\n[Default Applications]\ntext/plain=gedit.desktop;\n"
+        );
+        assert_eq!(
+            set_default_apps("", &["video/mp4"], "mpv"),
+            "[Default Applications]\nvideo/mp4=mpv.desktop;\n"
+        );
+
+        assert!(AppCategory::Images.contains(&AppCategory::Images.primary_mime()));
+        assert!(AppCategory::Archives.contains(&"application/x-7z-compressed".parse().unwrap()));
+        assert!(!AppCategory::Text.contains(&"application/zip".parse().unwrap()));
+    }
+}
@@ -29,7 +29,7 @@ use std::{
     collections::{HashMap, HashSet, VecDeque},
     env, fmt, fs,
     num::NonZeroU16,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     time::{self, Instant},
 };
@@ -45,9 +45,25 @@ use crate::{
     tab::{self, ItemMetadata, Location, Tab},
 };
 
+pub mod bulk_rename;
+
 #[derive(Clone, Debug)]
 pub struct DialogMessage(cosmic::Action<Message>);
 
+/// Reason why files cannot be created in a folder, `None` if they can
+pub fn not_writable_reason(path: &Path) -> Option<String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    if unsafe { libc::access(c_path.as_ptr(), libc::W_OK) } == 0 {
+        return None;
+    }
+    Some(match std::io::Error::last_os_error().raw_os_error() {
+        Some(libc::EROFS) => fl!("read-only-location"),
+        _ => fl!("location-not-writable"),
+    })
+}
+
 #[derive(Clone, Debug)]
 pub enum DialogResult {
     Cancel,
@@ -287,6 +303,12 @@ impl<M: Send + 'static> Dialog<M> {
         self.cosmic.app.accept_label = DialogLabel::from(accept_label);
     }
 
+    /// Reject locations for which `check` returns a reason, which is shown on the disabled accept
+    /// button. Open dialogs check the chosen items, save dialogs the folder to save in.
+    pub fn set_location_check(&mut self, check: impl Fn(&Path) -> Option<String> + 'static) {
+        self.cosmic.app.location_check_opt = Some(Box::new(check));
+    }
+
     pub fn choices(&self) -> &[DialogChoice] {
         &self.cosmic.app.choices
     }
@@ -330,6 +352,7 @@ impl<M: Send + 'static> Dialog<M> {
             .map(DialogMessage)
             .map(move |message| cosmic::action::app(mapper(message)));
         if let Some(result) = self.cosmic.app.result_opt.take() {
+            self.cosmic.app.remove_unused_folders(&result);
             let on_result_message = (self.on_result)(result);
             Task::batch([
                 command,
@@ -354,8 +377,14 @@ impl<M: Send + 'static> Dialog<M> {
 
 #[derive(Clone, Debug)]
 enum DialogPage {
-    NewFolder { parent: PathBuf, name: String },
-    Replace { filename: String },
+    /// Create the missing folders of a path typed in the filename box, shallowest first
+    CreateFolders {
+        path: PathBuf,
+        folders: Vec<PathBuf>,
+    },
+    Replace {
+        filename: String,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -378,13 +407,16 @@ enum Message {
     CursorMoved(Point),
     DialogCancel,
     DialogComplete,
-    DialogUpdate(DialogPage),
     Filename(String),
     Filter(usize),
     Key(Modifiers, Key),
     ModifiersChanged(Modifiers),
     MounterItems(MounterKey, MounterItems),
+    /// Show the folder name box in the footer
     NewFolder,
+    NewFolderCancel,
+    NewFolderInput(String),
+    NewFolderSubmit,
     NotifyEvents(Vec<DebouncedEvent>),
     NotifyWatcher(WatcherWrapper),
     Open,
@@ -400,10 +432,13 @@ enum Message {
     TabRescan(Location, Option<tab::Item>, Vec<tab::Item>),
     TabView(tab::View),
     TimeConfigChange(TimeConfig),
+    ToggleFolderSizes,
     ToggleFoldersFirst,
+    ToggleGroupByKind,
     ZoomDefault,
     ZoomIn,
     ZoomOut,
+    ZoomSet(u16),
 }
 
 impl From<AppMessage> for Message {
@@ -415,10 +450,13 @@ impl From<AppMessage> for Message {
             AppMessage::ScrollTab(scroll_speed) => Message::ScrollTab(scroll_speed),
             AppMessage::TabMessage(_entity_opt, tab_message) => Message::TabMessage(tab_message),
             AppMessage::TabView(_entity_opt, view) => Message::TabView(view),
+            AppMessage::ToggleFolderSizes => Message::ToggleFolderSizes,
             AppMessage::ToggleFoldersFirst => Message::ToggleFoldersFirst,
+            AppMessage::ToggleGroupByKind => Message::ToggleGroupByKind,
             AppMessage::ZoomDefault(_entity_opt) => Message::ZoomDefault,
             AppMessage::ZoomIn(_entity_opt) => Message::ZoomIn,
             AppMessage::ZoomOut(_entity_opt) => Message::ZoomOut,
+            AppMessage::ZoomSet(_entity_opt, zoom) => Message::ZoomSet(zoom),
             AppMessage::NewItem(_entity_opt, true) => Message::NewFolder,
             unsupported => {
                 log::warn!("{unsupported:?} not supported in dialog mode");
@@ -461,7 +499,6 @@ struct App {
     choices: Vec<DialogChoice>,
     context_page: ContextPage,
     dialog_pages: VecDeque<DialogPage>,
-    dialog_text_input: widget::Id,
     filters: Vec<DialogFilter>,
     filter_selected: Option<usize>,
     filename_id: widget::Id,
@@ -469,6 +506,12 @@ struct App {
     mounter_items: HashMap<MounterKey, MounterItems>,
     nav_model: segmented_button::SingleSelectModel,
     result_opt: Option<DialogResult>,
+    /// Folders created for a path typed in the filename box, removed again if not saved to
+    created_folders: Vec<PathBuf>,
+    /// Name typed for a new folder in the footer
+    new_folder_opt: Option<String>,
+    new_folder_id: widget::Id,
+    location_check_opt: Option<Box<dyn Fn(&Path) -> Option<String>>>,
     search_id: widget::Id,
     tab: Tab,
     key_binds: HashMap<KeyBind, Action>,
@@ -476,7 +519,91 @@ struct App {
     auto_scroll_speed: Option<i16>,
 }
 
+/// Folders above a path that do not exist yet, shallowest first
+fn missing_folders(path: &Path) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = path
+        .ancestors()
+        .skip(1)
+        .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    folders.reverse();
+    folders
+}
+
+/// Whether a folder with the name typed in the footer can be created, with a message about the
+/// name if there is something to say about it
+fn new_folder_status(parent: &Path, name: &str) -> (bool, Option<String>) {
+    if name.is_empty() {
+        (false, None)
+    } else if name == "." || name == ".." {
+        (false, Some(fl!("name-invalid", filename = name)))
+    } else if name.contains('/') {
+        (false, Some(fl!("name-no-slashes")))
+    } else {
+        let path = parent.join(name);
+        if path.is_dir() {
+            (false, Some(fl!("folder-already-exists")))
+        } else if path.exists() {
+            (false, Some(fl!("file-already-exists")))
+        } else if name.starts_with('.') {
+            (true, Some(fl!("name-hidden")))
+        } else {
+            (true, None)
+        }
+    }
+}
+
 impl App {
+    fn check_location(&self, path: &Path) -> Option<String> {
+        self.location_check_opt
+            .as_ref()
+            .and_then(|check| check(path))
+    }
+
+    /// Reason why the accept button is disabled, if the folder to save in or an item that would
+    /// be opened is not allowed
+    fn accept_blocked(&self) -> Option<String> {
+        if self.flags.kind.save() {
+            let path = self.tab.location.path_opt()?;
+            return not_writable_reason(path).or_else(|| self.check_location(path));
+        }
+
+        let mut any_selected = false;
+        for item in self.tab.items_opt()?.iter().filter(|item| item.selected) {
+            any_selected = true;
+            // Folders are entered when choosing files
+            if item.metadata.is_dir() != self.flags.kind.is_dir() {
+                continue;
+            }
+            if let Some(reason) = item.path_opt().and_then(|path| self.check_location(path)) {
+                return Some(reason);
+            }
+        }
+        match &self.tab.location {
+            Location::Path(path) if !any_selected && self.flags.kind.is_dir() => {
+                self.check_location(path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Remove created folders that the file was not saved to, deepest first so they are empty
+    fn remove_unused_folders(&mut self, result: &DialogResult) {
+        let used = |folder: &Path| match result {
+            DialogResult::Open(paths) => paths.iter().any(|path| path.starts_with(folder)),
+            DialogResult::Cancel => false,
+        };
+        for folder in self.created_folders.drain(..).rev() {
+            if used(&folder) {
+                continue;
+            }
+            if let Err(err) = fs::remove_dir(&folder) {
+                log::warn!("failed to remove {:?}: {}", folder, err);
+            }
+        }
+    }
+
     fn button_view(&self) -> Element<Message> {
         let cosmic_theme::Spacing {
             space_xxxs,
@@ -487,7 +614,30 @@ impl App {
             ..
         } = theme::active().cosmic().spacing;
 
-        let mut col = widget::column::with_capacity(2).spacing(space_xxs);
+        let mut col = widget::column::with_capacity(3).spacing(space_xxs);
+        if let (Some(name), Some(parent)) = (&self.new_folder_opt, self.tab.location.path_opt()) {
+            let (valid, message_opt) = new_folder_status(parent, name);
+            let mut row = widget::row::with_capacity(5)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs)
+                .push(widget::icon::from_name("folder-new-symbolic").size(16))
+                .push(
+                    widget::text_input(fl!("folder-name"), name.as_str())
+                        .id(self.new_folder_id.clone())
+                        .on_input(Message::NewFolderInput)
+                        .on_submit_maybe(valid.then_some(|_| Message::NewFolderSubmit)),
+                );
+            if let Some(message) = message_opt {
+                row = row.push(widget::text::caption(message));
+            }
+            row = row
+                .push(widget::button::standard(fl!("cancel")).on_press(Message::NewFolderCancel))
+                .push(
+                    widget::button::standard(fl!("create"))
+                        .on_press_maybe(valid.then_some(Message::NewFolderSubmit)),
+                );
+            col = col.push(row);
+        }
         if let DialogKind::SaveFile { filename } = &self.flags.kind {
             col = col.push(
                 widget::text_input("", filename)
@@ -541,27 +691,36 @@ impl App {
                 }
             }
         }
-        row = row.push(
-            //TODO: easier way to create buttons with rich text
-            widget::button::custom(
-                widget::row::with_children(vec![Element::from(&self.accept_label)])
-                    .padding([0, space_s])
-                    .width(Length::Shrink)
-                    .height(space_l)
-                    .spacing(space_xxxs)
-                    .align_y(Alignment::Center)
-            )
-            .padding(0)
-            .on_press_maybe(if self.flags.kind.save() {
-                Some(Message::Save(false))
-            } else if has_selected || self.flags.kind.is_dir() {
-                Some(Message::Open)
-            } else {
-                None
-            })
-            .class(widget::button::ButtonClass::Suggested)
-            /*TODO: a11y feature: .label(&self.accept_label.text)*/
-        );
+        let blocked_opt = self.accept_blocked();
+        //TODO: easier way to create buttons with rich text
+        let accept_button = widget::button::custom(
+            widget::row::with_children(vec![Element::from(&self.accept_label)])
+                .padding([0, space_s])
+                .width(Length::Shrink)
+                .height(space_l)
+                .spacing(space_xxxs)
+                .align_y(Alignment::Center),
+        )
+        .padding(0)
+        .on_press_maybe(if blocked_opt.is_some() {
+            None
+        } else if self.flags.kind.save() {
+            Some(Message::Save(false))
+        } else if has_selected || self.flags.kind.is_dir() {
+            Some(Message::Open)
+        } else {
+            None
+        })
+        .class(widget::button::ButtonClass::Suggested);
+        /*TODO: a11y feature: .label(&self.accept_label.text)*/
+        row = row.push(match blocked_opt {
+            Some(reason) => Element::from(widget::tooltip(
+                accept_button,
+                widget::text::body(reason),
+                widget::tooltip::Position::Top,
+            )),
+            None => accept_button.into(),
+        });
 
         col = col.push(row);
 
@@ -576,13 +735,13 @@ impl App {
         let mut children = Vec::with_capacity(1);
         match kind {
             PreviewKind::Custom(PreviewItem(item)) => {
-                children.push(item.preview_view(None, military_time));
+                children.push(item.preview_view(None, None, None, military_time, None));
             }
             PreviewKind::Location(location) => {
                 if let Some(items) = self.tab.items_opt() {
                     for item in items.iter() {
                         if item.location_opt.as_ref() == Some(location) {
-                            children.push(item.preview_view(None, military_time));
+                            children.push(item.preview_view(None, None, None, military_time, None));
                             // Only show one property view to avoid issues like hangs when generating
                             // preview images on thousands of files
                             break;
@@ -594,7 +753,7 @@ impl App {
                 if let Some(items) = self.tab.items_opt() {
                     for item in items.iter() {
                         if item.selected {
-                            children.push(item.preview_view(None, military_time));
+                            children.push(item.preview_view(None, None, None, military_time, None));
                             // Only show one property view to avoid issues like hangs when generating
                             // preview images on thousands of files
                             break;
@@ -602,7 +761,7 @@ impl App {
                     }
                     if children.is_empty() {
                         if let Some(item) = &self.tab.parent_item_opt {
-                            children.push(item.preview_view(None, military_time));
+                            children.push(item.preview_view(None, None, None, military_time, None));
                         }
                     }
                 }
@@ -611,6 +770,15 @@ impl App {
         widget::column::with_children(children).into()
     }
 
+    /// Change the icon size of the view, and show the new zoom level over it
+    fn zoom(&mut self, change: impl FnOnce(&mut NonZeroU16)) -> Task<Message> {
+        match self.tab.config.view {
+            tab::View::List => change(&mut self.tab.config.icon_sizes.list),
+            tab::View::Grid => change(&mut self.tab.config.icon_sizes.grid),
+        }
+        self.update(Message::TabMessage(tab::Message::ZoomIndicator))
+    }
+
     fn rescan_tab(&self) -> Task<Message> {
         let location = self.tab.location.clone();
         let icon_sizes = self.tab.config.icon_sizes;
@@ -676,9 +844,17 @@ impl App {
     fn update_config(&mut self) -> Task<Message> {
         self.update_nav_model();
 
-        self.update(Message::TabMessage(tab::Message::Config(
+        let mut commands = vec![self.update(Message::TabMessage(tab::Message::Config(
             self.flags.config.tab,
-        )))
+        )))];
+        if self
+            .tab
+            .set_hidden_patterns(&self.flags.config.hidden_patterns)
+            && self.tab.items_opt().is_some()
+        {
+            commands.push(self.rescan_tab());
+        }
+        Task::batch(commands)
     }
 
     fn activate_nav_model_location(&mut self, location: &Location) {
@@ -880,7 +1056,6 @@ impl Application for App {
             choices: Vec::new(),
             context_page: ContextPage::Preview(None, PreviewKind::Selected),
             dialog_pages: VecDeque::new(),
-            dialog_text_input: widget::Id::unique(),
             filters: Vec::new(),
             filter_selected: None,
             filename_id: widget::Id::unique(),
@@ -888,6 +1063,10 @@ impl Application for App {
             mounter_items: HashMap::new(),
             nav_model: segmented_button::ModelBuilder::default().build(),
             result_opt: None,
+            created_folders: Vec::new(),
+            new_folder_opt: None,
+            new_folder_id: widget::Id::unique(),
+            location_check_opt: None,
             search_id: widget::Id::unique(),
             tab,
             key_binds,
@@ -961,66 +1140,19 @@ impl Application for App {
         };
 
         let dialog = match dialog_page {
-            DialogPage::NewFolder { parent, name } => {
-                let mut dialog = widget::dialog().title(fl!("create-new-folder"));
-
-                let complete_maybe = if name.is_empty() {
-                    None
-                } else if name == "." || name == ".." {
-                    dialog = dialog.tertiary_action(widget::text::body(fl!(
-                        "name-invalid",
-                        filename = name.as_str()
-                    )));
-                    None
-                } else if name.contains('/') {
-                    dialog = dialog.tertiary_action(widget::text::body(fl!("name-no-slashes")));
-                    None
-                } else {
-                    let path = parent.join(name);
-                    if path.exists() {
-                        if path.is_dir() {
-                            dialog = dialog
-                                .tertiary_action(widget::text::body(fl!("folder-already-exists")));
-                        } else {
-                            dialog = dialog
-                                .tertiary_action(widget::text::body(fl!("file-already-exists")));
-                        }
-                        None
-                    } else {
-                        if name.starts_with('.') {
-                            dialog = dialog.tertiary_action(widget::text::body(fl!("name-hidden")));
-                        }
-                        Some(Message::DialogComplete)
-                    }
-                };
-
-                dialog
-                    .primary_action(
-                        widget::button::suggested(fl!("save"))
-                            .on_press_maybe(complete_maybe.clone()),
-                    )
-                    .secondary_action(
-                        widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
-                    )
-                    .control(
-                        widget::column::with_children(vec![
-                            widget::text::body(fl!("folder-name")).into(),
-                            widget::text_input("", name.as_str())
-                                .id(self.dialog_text_input.clone())
-                                .on_input(move |name| {
-                                    Message::DialogUpdate(DialogPage::NewFolder {
-                                        parent: parent.clone(),
-                                        name,
-                                    })
-                                })
-                                .on_submit_maybe(
-                                    complete_maybe.clone().map(|maybe| move |_| maybe.clone()),
-                                )
-                                .into(),
-                        ])
-                        .spacing(space_xxs),
-                    )
-            }
+            DialogPage::CreateFolders { path, folders } => widget::dialog()
+                .title(fl!("create-folders-title", folders = folders.len()))
+                .icon(widget::icon::from_name("folder-new").size(64))
+                .body(fl!(
+                    "create-folders-warning",
+                    path = path.parent().unwrap_or(path).display().to_string()
+                ))
+                .primary_action(
+                    widget::button::suggested(fl!("create")).on_press(Message::DialogComplete),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                ),
             DialogPage::Replace { filename } => widget::dialog()
                 .title(fl!("replace-title", filename = filename.as_str()))
                 .icon(widget::icon::from_name("dialog-question").size(64))
@@ -1073,12 +1205,23 @@ impl Application for App {
         }
 
         if self.flags.kind.save() {
-            elements.push(
-                widget::button::icon(widget::icon::from_name("folder-new-symbolic"))
-                    .on_press(Message::NewFolder)
-                    .padding(8)
-                    .into(),
-            );
+            let not_writable_opt = self
+                .tab
+                .location
+                .path_opt()
+                .and_then(|path| not_writable_reason(path));
+            let button = widget::button::icon(widget::icon::from_name("folder-new-symbolic"))
+                .on_press_maybe(not_writable_opt.is_none().then_some(Message::NewFolder))
+                .padding(8);
+            elements.push(match not_writable_opt {
+                Some(reason) => widget::tooltip(
+                    button,
+                    widget::text::body(reason),
+                    widget::tooltip::Position::Bottom,
+                )
+                .into(),
+                None => button.into(),
+            });
         }
 
         let show_details = match self.context_page {
@@ -1148,7 +1291,7 @@ impl Application for App {
     fn on_escape(&mut self) -> Task<Message> {
         if self.tab.gallery {
             // Close gallery if open
-            self.tab.gallery = false;
+            self.tab.close_gallery();
             return Task::none();
         }
 
@@ -1220,20 +1363,26 @@ impl Application for App {
             Message::DialogComplete => {
                 if let Some(dialog_page) = self.dialog_pages.pop_front() {
                     match dialog_page {
-                        DialogPage::NewFolder { parent, name } => {
-                            let path = parent.join(name);
-                            match fs::create_dir(&path) {
-                                Ok(()) => {
-                                    // cd to directory
-                                    let message = Message::TabMessage(tab::Message::Location(
-                                        Location::Path(path.clone()),
-                                    ));
-                                    return self.update(message);
-                                }
-                                Err(err) => {
-                                    log::warn!("failed to create {:?}: {}", path, err);
+                        DialogPage::CreateFolders { path, folders } => {
+                            for folder in folders {
+                                if let Err(err) = fs::create_dir(&folder) {
+                                    log::warn!("failed to create {:?}: {}", folder, err);
+                                    return Task::none();
                                 }
+                                self.created_folders.push(folder);
                             }
+                            // Show the new folder, so the file is saved with the next confirm
+                            let (Some(parent), Some(name)) = (path.parent(), path.file_name())
+                            else {
+                                return Task::none();
+                            };
+                            if let DialogKind::SaveFile { filename } = &mut self.flags.kind {
+                                *filename = name.to_string_lossy().into_owned();
+                            }
+                            let message = Message::TabMessage(tab::Message::Location(
+                                Location::Path(parent.to_path_buf()),
+                            ));
+                            return self.update(message);
                         }
                         DialogPage::Replace { .. } => {
                             return self.update(Message::Save(true));
@@ -1241,11 +1390,6 @@ impl Application for App {
                     }
                 }
             }
-            Message::DialogUpdate(dialog_page) => {
-                if !self.dialog_pages.is_empty() {
-                    self.dialog_pages[0] = dialog_page;
-                }
-            }
             Message::Filename(new_filename) => {
                 // Select based on filename
                 self.tab.select_name(&new_filename);
@@ -1329,12 +1473,38 @@ impl Application for App {
                 return Task::batch(commands);
             }
             Message::NewFolder => {
-                if let Some(path) = self.tab.location.path_opt() {
-                    self.dialog_pages.push_back(DialogPage::NewFolder {
-                        parent: path.to_path_buf(),
-                        name: String::new(),
-                    });
-                    return widget::text_input::focus(self.dialog_text_input.clone());
+                if self.tab.location.path_opt().is_some() {
+                    self.new_folder_opt = Some(String::new());
+                    return widget::text_input::focus(self.new_folder_id.clone());
+                }
+            }
+            Message::NewFolderCancel => {
+                self.new_folder_opt = None;
+            }
+            Message::NewFolderInput(name) => {
+                self.new_folder_opt = Some(name);
+            }
+            Message::NewFolderSubmit => {
+                let (Some(parent), Some(name)) =
+                    (self.tab.location.path_opt(), self.new_folder_opt.as_ref())
+                else {
+                    return Task::none();
+                };
+                if !new_folder_status(parent, name).0 {
+                    return Task::none();
+                }
+                let path = parent.join(name);
+                match fs::create_dir(&path) {
+                    Ok(()) => {
+                        self.new_folder_opt = None;
+                        // Enter the folder, so the file is saved in it
+                        let message =
+                            Message::TabMessage(tab::Message::Location(Location::Path(path)));
+                        return self.update(message);
+                    }
+                    Err(err) => {
+                        log::warn!("failed to create {:?}: {}", path, err);
+                    }
                 }
             }
             Message::NotifyEvents(events) => {
@@ -1402,6 +1572,9 @@ impl Application for App {
                 }
             },
             Message::Open => {
+                if self.accept_blocked().is_some() {
+                    return Task::none();
+                }
                 let mut paths = Vec::new();
                 if let Some(items) = self.tab.items_opt() {
                     for item in items.iter() {
@@ -1461,11 +1634,18 @@ impl Application for App {
                 }
             },
             Message::Save(replace) => {
+                if self.accept_blocked().is_some() {
+                    return Task::none();
+                }
                 if let DialogKind::SaveFile { filename } = &self.flags.kind {
                     if !filename.is_empty() {
                         if let Some(tab_path) = self.tab.location.path_opt() {
                             let path = tab_path.join(filename);
-                            if path.is_dir() {
+                            let folders = missing_folders(&path);
+                            if !folders.is_empty() {
+                                self.dialog_pages
+                                    .push_back(DialogPage::CreateFolders { path, folders });
+                            } else if path.is_dir() {
                                 // cd to directory
                                 let message = Message::TabMessage(tab::Message::Location(
                                     Location::Path(path.clone()),
@@ -1531,6 +1711,9 @@ impl Application for App {
                         tab::Command::ChangeLocation(_tab_title, _tab_path, _selection_paths) => {
                             commands.push(Task::batch([self.update_watcher(), self.rescan_tab()]));
                         }
+                        tab::Command::ColumnWidths(_path_opt, _column_widths) => {
+                            // Column widths are only saved from the main window
+                        }
                         tab::Command::Iced(iced_command) => {
                             commands.push(iced_command.0.map(|tab_message| {
                                 cosmic::action::app(Message::TabMessage(tab_message))
@@ -1657,13 +1840,18 @@ impl Application for App {
                 self.flags.config.tab.military_time = time_config.military_time;
                 return self.update_config();
             }
+            Message::ToggleFolderSizes => {
+                self.tab.config.folder_sizes = !self.tab.config.folder_sizes;
+            }
             Message::ToggleFoldersFirst => {
                 self.tab.config.folders_first = !self.tab.config.folders_first;
             }
-            Message::ZoomDefault => match self.tab.config.view {
-                tab::View::List => self.tab.config.icon_sizes.list = 100.try_into().unwrap(),
-                tab::View::Grid => self.tab.config.icon_sizes.grid = 100.try_into().unwrap(),
-            },
+            Message::ToggleGroupByKind => {
+                self.tab.config.group_by_kind = !self.tab.config.group_by_kind;
+            }
+            Message::ZoomDefault => {
+                return self.update(Message::ZoomSet(100));
+            }
             Message::ZoomIn => {
                 let zoom_in = |size: &mut NonZeroU16, min: u16, max: u16| {
                     let mut step = min;
@@ -1678,10 +1866,7 @@ impl Application for App {
                         *size = step.try_into().unwrap();
                     }
                 };
-                match self.tab.config.view {
-                    tab::View::List => zoom_in(&mut self.tab.config.icon_sizes.list, 50, 500),
-                    tab::View::Grid => zoom_in(&mut self.tab.config.icon_sizes.grid, 50, 500),
-                }
+                return self.zoom(|size| zoom_in(size, 50, 500));
             }
             Message::ZoomOut => {
                 let zoom_out = |size: &mut NonZeroU16, min: u16, max: u16| {
@@ -1697,9 +1882,11 @@ impl Application for App {
                         *size = step.try_into().unwrap();
                     }
                 };
-                match self.tab.config.view {
-                    tab::View::List => zoom_out(&mut self.tab.config.icon_sizes.list, 50, 500),
-                    tab::View::Grid => zoom_out(&mut self.tab.config.icon_sizes.grid, 50, 500),
+                return self.zoom(|size| zoom_out(size, 50, 500));
+            }
+            Message::ZoomSet(zoom) => {
+                if let Some(zoom) = NonZeroU16::new(zoom) {
+                    return self.zoom(|size| *size = zoom);
                 }
             }
             Message::Surface(a) => {
@@ -1753,6 +1940,9 @@ impl Application for App {
                 Event::Mouse(mouse::Event::CursorMoved { position: pos }) => {
                     Some(Message::CursorMoved(pos))
                 }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::TabMessage(tab::Message::ColumnResizeEnd))
+                }
                 _ => None,
             }),
             Config::subscription().map(|update| {
@@ -1861,6 +2051,7 @@ impl Application for App {
                             self.context_page,
                             ContextPage::Preview(_, PreviewKind::Selected)
                         ),
+                    false,
                 )
                 .map(Message::TabMessage),
         ];
@@ -1890,3 +2081,22 @@ impl Application for App {
         Subscription::batch(subscriptions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::missing_folders;
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn missing_save_folders() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        fs::create_dir(dir.path().join("a"))?;
+        assert_eq!(
+            missing_folders(&dir.path().join("a/b/c/file.txt")),
+            [dir.path().join("a/b"), dir.path().join("a/b/c")]
+        );
+        assert!(missing_folders(&dir.path().join("a/file.txt")).is_empty());
+        Ok(())
+    }
+}
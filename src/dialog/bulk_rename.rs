@@ -0,0 +1,554 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Renaming many items at once with find and replace, numbering, and case transforms, or by
+//! changing their extension
+
+use mime_guess::Mime;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use crate::tab;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CaseTransform {
+    #[default]
+    Keep,
+    Lower,
+    Upper,
+    Title,
+}
+
+impl CaseTransform {
+    pub const ALL: [Self; 4] = [Self::Keep, Self::Lower, Self::Upper, Self::Title];
+
+    /// Change the case of a name escaped by [`tab::escaped_file_name`], keeping the escapes
+    fn apply(self, name: &str) -> String {
+        if self == Self::Keep {
+            return name.to_string();
+        }
+        let mut transformed = String::with_capacity(name.len());
+        let mut word_start = true;
+        let mut rest = name;
+        while let Some(c) = rest.chars().next() {
+            if let Some(escape) = escape_prefix(rest) {
+                transformed.push_str(escape);
+                // Bytes that are not valid UTF-8 are usually letters in another encoding
+                word_start = escape == "\\\\";
+                rest = &rest[escape.len()..];
+                continue;
+            }
+            match self {
+                Self::Keep => transformed.push(c),
+                Self::Lower => transformed.extend(c.to_lowercase()),
+                Self::Upper => transformed.extend(c.to_uppercase()),
+                Self::Title => {
+                    if c.is_alphanumeric() {
+                        if word_start {
+                            transformed.extend(c.to_uppercase());
+                        } else {
+                            transformed.extend(c.to_lowercase());
+                        }
+                        word_start = false;
+                    } else {
+                        transformed.push(c);
+                        word_start = true;
+                    }
+                }
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        transformed
+    }
+}
+
+/// Escape made by [`tab::escaped_file_name`] at the start of the text
+fn escape_prefix(text: &str) -> Option<&str> {
+    if text.starts_with("\\\\") {
+        return Some(&text[..2]);
+    }
+    let hex = text.strip_prefix("\\x")?.get(..2)?;
+    hex.bytes()
+        .all(|byte| byte.is_ascii_hexdigit())
+        .then(|| &text[..4])
+}
+
+/// Why a new name cannot be used
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RenameConflict {
+    /// The name is empty, `.`, `..`, or contains a slash
+    Invalid,
+    /// Another item in the batch gets the same name
+    Duplicate,
+    /// An item with the name already exists
+    Exists,
+}
+
+#[derive(Clone, Debug)]
+pub struct RenamePreview {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub conflict_opt: Option<RenameConflict>,
+}
+
+impl RenamePreview {
+    pub fn changed(&self) -> bool {
+        self.from != self.to
+    }
+}
+
+/// Options of the bulk rename dialog
+#[derive(Clone, Debug)]
+pub struct BulkRename {
+    pub find: String,
+    /// Replacement text, where `{n}` is the item number and `{n:3}` is zero padded to three digits.
+    /// In regex mode, `$1` inserts the first capture group.
+    pub replace: String,
+    pub regex: bool,
+    /// Only change the part of the name before the extension
+    pub keep_extension: bool,
+    pub case: CaseTransform,
+}
+
+impl Default for BulkRename {
+    fn default() -> Self {
+        Self {
+            find: String::new(),
+            replace: String::new(),
+            regex: false,
+            keep_extension: true,
+            case: CaseTransform::Keep,
+        }
+    }
+}
+
+/// Options of the change extension dialog
+#[derive(Clone, Debug, Default)]
+pub struct ChangeExtension {
+    /// New extension, typed with or without its dot. An empty extension removes it.
+    pub extension: String,
+}
+
+impl ChangeExtension {
+    /// The new extension without its dot
+    pub fn extension(&self) -> &str {
+        self.extension.trim().trim_start_matches('.')
+    }
+
+    /// New names for the paths that are renamed, in the same order. Only the extension is
+    /// changed, the bytes of the rest of the name are kept even if they are not valid UTF-8.
+    pub fn preview(&self, paths: &[PathBuf]) -> Vec<RenamePreview> {
+        let extension = self.extension();
+        let mut previews: Vec<_> = paths
+            .iter()
+            .map(|from| {
+                let name = from.file_name().unwrap_or_default();
+                let mut new_name = file_stem(name).to_os_string();
+                if !extension.is_empty() {
+                    new_name.push(".");
+                    new_name.push(extension);
+                }
+                rename_preview(from, &new_name)
+            })
+            .collect();
+        check_conflicts(&mut previews);
+        previews.retain(RenamePreview::changed);
+        previews
+    }
+
+    /// Paths whose contents have a type that the new extension does not fit, with that type
+    pub fn mismatches(
+        &self,
+        contents: &HashMap<PathBuf, Mime>,
+        fits: impl Fn(&str, &Mime) -> bool,
+    ) -> HashMap<PathBuf, Mime> {
+        let extension = self.extension();
+        if extension.is_empty() {
+            return HashMap::new();
+        }
+        contents
+            .iter()
+            .filter(|(_, mime)| !fits(extension, mime))
+            .map(|(path, mime)| (path.clone(), mime.clone()))
+            .collect()
+    }
+}
+
+/// Replace `{n}` and `{n:WIDTH}` with the item number
+fn expand_numbering(replace: &str, number: usize) -> String {
+    let mut expanded = String::with_capacity(replace.len());
+    let mut rest = replace;
+    while let Some(start) = rest.find("{n") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            expanded.push_str(&rest[start..]);
+            return expanded;
+        };
+        let spec = &after[..end];
+        let width = if spec.is_empty() {
+            Some(0)
+        } else {
+            spec.strip_prefix(':').and_then(|width| width.parse().ok())
+        };
+        match width {
+            Some(width) => expanded.push_str(&format!("{:0width$}", number, width = width)),
+            // Not a numbering token, keep it as typed
+            None => expanded.push_str(&rest[start..start + 2 + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Name without its extension, split on the raw bytes of the name. Names like .bashrc have no
+/// extension.
+#[cfg(unix)]
+fn file_stem(name: &OsStr) -> &OsStr {
+    use std::os::unix::ffi::OsStrExt;
+
+    let bytes = name.as_bytes();
+    match bytes
+        .iter()
+        .rposition(|byte| *byte == b'.')
+        .filter(|dot| *dot > 0)
+    {
+        Some(dot) => OsStr::from_bytes(&bytes[..dot]),
+        None => name,
+    }
+}
+
+#[cfg(not(unix))]
+fn file_stem(name: &OsStr) -> &OsStr {
+    match name.to_str() {
+        Some(name) => OsStr::new(split_extension(name, true).0),
+        None => name,
+    }
+}
+
+/// Split a name into the part that is renamed and the extension that is kept, with its dot
+fn split_extension(name: &str, keep_extension: bool) -> (&str, &str) {
+    if keep_extension {
+        // Names like .bashrc have no extension
+        if let Some(dot) = name.rfind('.').filter(|dot| *dot > 0) {
+            return name.split_at(dot);
+        }
+    }
+    (name, "")
+}
+
+impl BulkRename {
+    /// New names for the paths that are renamed, in the same order. Names are edited escaped by
+    /// [`tab::escaped_file_name`], so bytes that are not valid UTF-8 are kept. Returns an error if
+    /// the regex is invalid.
+    pub fn preview(&self, paths: &[PathBuf]) -> Result<Vec<RenamePreview>, String> {
+        let regex_opt = if self.regex && !self.find.is_empty() {
+            Some(regex::Regex::new(&self.find).map_err(|err| err.to_string())?)
+        } else {
+            None
+        };
+
+        let mut previews = Vec::with_capacity(paths.len());
+        for (i, from) in paths.iter().enumerate() {
+            let name = from
+                .file_name()
+                .map(tab::escaped_file_name)
+                .unwrap_or_default();
+            let (stem, extension) = split_extension(&name, self.keep_extension);
+            let replace = expand_numbering(&self.replace, i + 1);
+            let stem = match &regex_opt {
+                Some(regex) => regex.replace_all(stem, replace.as_str()).into_owned(),
+                None if !self.find.is_empty() => stem.replace(&self.find, &replace),
+                None => stem.to_string(),
+            };
+            let new_name = format!("{}{}", self.case.apply(&stem), extension);
+            previews.push(rename_preview(from, &tab::unescaped_file_name(&new_name)));
+        }
+        check_conflicts(&mut previews);
+        previews.retain(RenamePreview::changed);
+        Ok(previews)
+    }
+}
+
+/// Rename of a path to a new name in the same folder, checking that the name is valid
+fn rename_preview(from: &Path, new_name: &OsStr) -> RenamePreview {
+    let to = match from.parent() {
+        Some(parent) => parent.join(new_name),
+        None => PathBuf::from(new_name),
+    };
+    RenamePreview {
+        from: from.to_path_buf(),
+        to,
+        conflict_opt: if new_name.is_empty()
+            || new_name == "."
+            || new_name == ".."
+            || new_name.to_string_lossy().contains('/')
+        {
+            Some(RenameConflict::Invalid)
+        } else {
+            None
+        },
+    }
+}
+
+/// Check for names used twice, or by items outside of the batch. Unchanged items are checked too,
+/// as other items cannot take their names.
+fn check_conflicts(previews: &mut [RenamePreview]) {
+    let mut seen = HashSet::with_capacity(previews.len());
+    for preview in previews.iter_mut() {
+        if preview.conflict_opt.is_some() {
+            continue;
+        }
+        if !seen.insert(preview.to.clone()) {
+            preview.conflict_opt = Some(RenameConflict::Duplicate);
+        } else if preview.changed() && exists(&preview.to) {
+            preview.conflict_opt = Some(RenameConflict::Exists);
+        }
+    }
+}
+
+fn exists(path: &Path) -> bool {
+    path.symlink_metadata().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BulkRename, CaseTransform, ChangeExtension, RenameConflict};
+    use mime_guess::mime;
+    use std::{collections::HashMap, fs, io, path::PathBuf};
+    use tempfile::TempDir;
+
+    fn names(rename: &BulkRename, paths: &[PathBuf]) -> Vec<String> {
+        rename
+            .preview(paths)
+            .unwrap()
+            .into_iter()
+            .map(|preview| {
+                preview
+                    .to
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bulk_rename_patterns() {
+        let paths = vec![
+            PathBuf::from("/photos/IMG_0001.JPG"),
+            PathBuf::from("/photos/IMG_0002.JPG"),
+        ];
+
+        let rename = BulkRename {
+            find: "IMG_".to_string(),
+            replace: "holiday-{n:3}-".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&rename, &paths),
+            ["holiday-001-0001.JPG", "holiday-002-0002.JPG"]
+        );
+
+        let rename = BulkRename {
+            find: r"IMG_(\d+)".to_string(),
+            replace: "photo $1 {n}".to_string(),
+            regex: true,
+            case: CaseTransform::Title,
+            ..Default::default()
+        };
+        assert_eq!(
+            names(&rename, &paths),
+            ["Photo 0001 1.JPG", "Photo 0002 2.JPG"]
+        );
+
+        let rename = BulkRename {
+            keep_extension: false,
+            case: CaseTransform::Lower,
+            ..Default::default()
+        };
+        assert_eq!(names(&rename, &paths), ["img_0001.jpg", "img_0002.jpg"]);
+
+        let rename = BulkRename {
+            find: "(".to_string(),
+            regex: true,
+            ..Default::default()
+        };
+        assert!(rename.preview(&paths).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bulk_rename_raw_names() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+        let paths = vec![
+            Path::new("/music").join(OsStr::from_bytes(b"caf\xE9 track.mp3")),
+            PathBuf::from("/music/other song.mp3"),
+        ];
+
+        // Names that are not matched are left out instead of being renamed
+        let rename = BulkRename {
+            find: "song".to_string(),
+            replace: "tune".to_string(),
+            ..Default::default()
+        };
+        let previews = rename.preview(&paths).unwrap();
+        assert_eq!(previews.len(), 1);
+        assert_eq!(previews[0].to, PathBuf::from("/music/other tune.mp3"));
+
+        // Bytes that are not valid UTF-8 are kept by renames and case changes
+        let rename = BulkRename {
+            find: "track".to_string(),
+            replace: "song".to_string(),
+            case: CaseTransform::Title,
+            ..Default::default()
+        };
+        let previews = rename.preview(&paths).unwrap();
+        assert_eq!(
+            previews[0].to,
+            Path::new("/music").join(OsStr::from_bytes(b"Caf\xE9 Song.mp3"))
+        );
+        assert_eq!(previews[1].to, PathBuf::from("/music/Other Song.mp3"));
+
+        let change = ChangeExtension {
+            extension: "ogg".to_string(),
+        };
+        let previews = change.preview(&paths);
+        assert_eq!(
+            previews[0].to,
+            Path::new("/music").join(OsStr::from_bytes(b"caf\xE9 track.ogg"))
+        );
+
+        let rename = BulkRename {
+            case: CaseTransform::Upper,
+            ..Default::default()
+        };
+        let previews = rename.preview(&paths).unwrap();
+        assert_eq!(
+            previews[0].to,
+            Path::new("/music").join(OsStr::from_bytes(b"CAF\xE9 TRACK.mp3"))
+        );
+    }
+
+    #[test]
+    fn bulk_rename_conflicts() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let paths: Vec<_> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        for path in paths.iter() {
+            fs::write(path, b"")?;
+        }
+        fs::write(dir.path().join("existing.txt"), b"")?;
+
+        let rename = BulkRename {
+            find: "^.*$".to_string(),
+            replace: "same".to_string(),
+            regex: true,
+            ..Default::default()
+        };
+        let conflicts: Vec<_> = rename
+            .preview(&paths)
+            .map_err(io::Error::other)?
+            .into_iter()
+            .map(|preview| preview.conflict_opt)
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                None,
+                Some(RenameConflict::Duplicate),
+                Some(RenameConflict::Duplicate)
+            ]
+        );
+
+        let rename = BulkRename {
+            find: "a".to_string(),
+            replace: "existing".to_string(),
+            ..Default::default()
+        };
+        let preview = rename.preview(&paths[..1]).map_err(io::Error::other)?;
+        assert_eq!(preview[0].conflict_opt, Some(RenameConflict::Exists));
+
+        let rename = BulkRename {
+            find: "a.txt".to_string(),
+            replace: String::new(),
+            keep_extension: false,
+            ..Default::default()
+        };
+        let preview = rename.preview(&paths[..1]).map_err(io::Error::other)?;
+        assert_eq!(preview[0].conflict_opt, Some(RenameConflict::Invalid));
+
+        Ok(())
+    }
+
+    #[test]
+    fn change_extensions() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let paths: Vec<_> = ["photo.jpeg", "scan.JPG", "notes", ".hidden"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        fs::write(dir.path().join("notes.jpg"), b"")?;
+
+        let change = ChangeExtension {
+            extension: " .jpg".to_string(),
+        };
+        let previews = change.preview(&paths);
+        let names: Vec<_> = previews
+            .iter()
+            .map(|preview| {
+                preview
+                    .to
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["photo.jpg", "scan.jpg", "notes.jpg", ".hidden.jpg"]);
+        assert_eq!(previews[2].conflict_opt, Some(RenameConflict::Exists));
+        assert_eq!(previews[0].conflict_opt, None);
+
+        // An empty extension removes it
+        let change = ChangeExtension::default();
+        let previews = change.preview(&paths[..2]);
+        assert_eq!(previews[0].to, dir.path().join("photo"));
+        assert_eq!(previews[1].to, dir.path().join("scan"));
+
+        let change = ChangeExtension {
+            extension: "tar/gz".to_string(),
+        };
+        assert_eq!(
+            change.preview(&paths[..1])[0].conflict_opt,
+            Some(RenameConflict::Invalid)
+        );
+
+        // Files whose contents are not of a type used with the extension are warned about
+        let contents = HashMap::from([
+            (paths[0].clone(), mime::IMAGE_JPEG),
+            (paths[1].clone(), mime::IMAGE_PNG),
+        ]);
+        let fits = |extension: &str, mime: &mime::Mime| {
+            mime_guess::from_ext(extension).iter().any(|x| &x == mime)
+        };
+        let change = ChangeExtension {
+            extension: "jpg".to_string(),
+        };
+        assert_eq!(
+            change.mismatches(&contents, fits),
+            HashMap::from([(paths[1].clone(), mime::IMAGE_PNG)])
+        );
+        assert!(ChangeExtension::default()
+            .mismatches(&contents, fits)
+            .is_empty());
+
+        Ok(())
+    }
+}
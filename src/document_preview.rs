@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Documents in the details pane: the start of text files with syntax highlighting, and pages of
+//! PDFs rendered with poppler when the `pdf-preview` feature is enabled.
+
+use cosmic::iced::Color;
+use mime_guess::{mime, Mime};
+use once_cell::sync::Lazy;
+use std::{fs::File, io::Read, path::Path};
+use syntect::{
+    easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet, util::LinesWithEndings,
+};
+
+/// How much of a text file is read for the preview
+const TEXT_PREVIEW_BYTES: u64 = 16 * 1024;
+/// Lines of a text file shown in the preview
+const TEXT_PREVIEW_LINES: usize = 40;
+
+static SYNTAXES: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEMES: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// Spans of a line with their colors
+pub type HighlightedLine = Vec<(Color, String)>;
+
+/// Text files, and other formats that are usually read as text
+pub fn text_preview_supported(mime: &Mime) -> bool {
+    mime.type_() == mime::TEXT
+        || (mime.type_() == mime::APPLICATION
+            && matches!(
+                mime.subtype().as_str(),
+                "json" | "xml" | "javascript" | "toml" | "x-shellscript" | "x-yaml"
+            ))
+}
+
+pub fn pdf_preview_supported(mime: &Mime) -> bool {
+    cfg!(feature = "pdf-preview") && mime.essence_str() == "application/pdf"
+}
+
+/// First lines of a text file, highlighted with the syntax found from its extension or first
+/// line, in colors for a dark or light background
+pub fn highlight_text(path: &Path, dark: bool) -> Result<Vec<HighlightedLine>, String> {
+    let mut data = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(TEXT_PREVIEW_BYTES).read_to_end(&mut data))
+        .map_err(|err| err.to_string())?;
+    if data.contains(&0) {
+        return Err("binary data".to_string());
+    }
+    let mut text = String::from_utf8_lossy(&data).into_owned();
+    // Drop a line cut off by the size limit
+    if data.len() as u64 == TEXT_PREVIEW_BYTES {
+        if let Some(end) = text.rfind('\n') {
+            text.truncate(end + 1);
+        }
+    }
+
+    let syntax = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| SYNTAXES.find_syntax_by_extension(extension))
+        .or_else(|| SYNTAXES.find_syntax_by_first_line(&text))
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+    let theme = &THEMES.themes[if dark {
+        "base16-ocean.dark"
+    } else {
+        "InspiredGitHub"
+    }];
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let mut lines = Vec::new();
+    for line in LinesWithEndings::from(&text).take(TEXT_PREVIEW_LINES) {
+        let ranges = highlighter
+            .highlight_line(line, &SYNTAXES)
+            .map_err(|err| err.to_string())?;
+        lines.push(
+            ranges
+                .into_iter()
+                .map(|(style, span)| {
+                    let color = style.foreground;
+                    (
+                        Color::from_rgba8(color.r, color.g, color.b, f32::from(color.a) / 255.0),
+                        span.trim_end_matches(['\r', '\n']).to_string(),
+                    )
+                })
+                .filter(|(_, span)| !span.is_empty())
+                .collect(),
+        );
+    }
+    Ok(lines)
+}
+
+#[cfg(feature = "pdf-preview")]
+pub fn pdf_page_count(path: &Path) -> Result<usize, String> {
+    let output = std::process::Command::new("pdfinfo")
+        .arg(path)
+        .output()
+        .map_err(|err| format!("failed to run pdfinfo: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pdfinfo failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("Pages:"))
+        .and_then(|pages| pages.trim().parse().ok())
+        .ok_or_else(|| "missing page count".to_string())
+}
+
+/// Page of a PDF, numbered from 1, encoded as PNG and scaled to fit the thumbnail size
+#[cfg(feature = "pdf-preview")]
+pub fn pdf_page(path: &Path, page: usize, thumbnail_size: u32) -> Result<Vec<u8>, String> {
+    let output = std::process::Command::new("pdftoppm")
+        .arg("-f")
+        .arg(page.to_string())
+        .arg("-l")
+        .arg(page.to_string())
+        .arg("-scale-to")
+        .arg(thumbnail_size.to_string())
+        .arg("-png")
+        .arg("-singlefile")
+        .arg(path)
+        // Written to stdout
+        .arg("-")
+        .output()
+        .map_err(|err| format!("failed to run pdftoppm: {}", err))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "pdftoppm failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{highlight_text, text_preview_supported, TEXT_PREVIEW_LINES};
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn highlighted_text() -> io::Result<()> {
+        assert!(text_preview_supported(&"text/markdown".parse().unwrap()));
+        assert!(text_preview_supported(&"application/json".parse().unwrap()));
+        assert!(!text_preview_supported(&"application/pdf".parse().unwrap()));
+
+        let dir = TempDir::new()?;
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "fn main() {\n    println!(\"hello\");\n}\n")?;
+        let lines = highlight_text(&path, true).map_err(io::Error::other)?;
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.iter().map(|(_, span)| span.as_str()).collect())
+            .collect();
+        assert_eq!(text, ["fn main() {", "    println!(\"hello\");", "}"]);
+        // Keywords are colored differently from names
+        assert!(lines[0].len() > 1);
+
+        let path = dir.path().join("long.txt");
+        fs::write(&path, "line\n".repeat(TEXT_PREVIEW_LINES * 2))?;
+        let lines = highlight_text(&path, false).map_err(io::Error::other)?;
+        assert_eq!(lines.len(), TEXT_PREVIEW_LINES);
+
+        let path = dir.path().join("binary.txt");
+        fs::write(&path, b"text\0more")?;
+        assert!(highlight_text(&path, false).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Emblems drawn over item icons, like the arrow on symbolic links. Other subsystems, like sync
+//! providers, can contribute their own with [`Item::add_emblem`](crate::tab::Item::add_emblem).
+
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
+use std::{ffi::CString, fs, os::unix::ffi::OsStrExt, path::Path};
+
+/// Files that sync clients keep in the root of a synced folder
+const SYNC_ROOT_MARKERS: [&str; 3] = [
+    // Dropbox
+    ".dropbox",
+    // Nextcloud and ownCloud
+    ".owncloudsync.log",
+    // Syncthing
+    ".stfolder",
+];
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Emblem {
+    Symlink,
+    Unreadable,
+    Synced,
+    /// Ignored by the git repository containing the item
+    Ignored,
+}
+
+impl Emblem {
+    /// Icon of the badge, or `None` for emblems that dim the item instead
+    pub fn icon_name(self) -> Option<&'static str> {
+        match self {
+            Self::Symlink => Some("emblem-symbolic-link"),
+            Self::Unreadable => Some("changes-prevent-symbolic"),
+            Self::Synced => Some("emblem-synchronizing-symbolic"),
+            Self::Ignored => None,
+        }
+    }
+}
+
+/// Emblems found from the item itself
+pub fn path_emblems(path: &Path) -> Vec<Emblem> {
+    let mut emblems = Vec::new();
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        emblems.push(Emblem::Symlink);
+    }
+    if !readable(path) {
+        emblems.push(Emblem::Unreadable);
+    }
+    emblems
+}
+
+fn readable(path: &Path) -> bool {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return true;
+    };
+    // Unlike the permission bits, this takes groups and ACLs into account
+    unsafe { libc::access(c_path.as_ptr(), libc::R_OK) == 0 }
+}
+
+/// Emblems that depend on the folder containing the items, found once per scan
+pub struct FolderEmblems {
+    synced: bool,
+    /// Ignore files from the folder up to the repository root, closest first
+    gitignores: Vec<Gitignore>,
+}
+
+impl FolderEmblems {
+    pub fn new(dir: &Path) -> Self {
+        let synced = dir.ancestors().any(|ancestor| {
+            SYNC_ROOT_MARKERS
+                .iter()
+                .any(|marker| ancestor.join(marker).exists())
+        });
+        Self {
+            synced,
+            gitignores: gitignores(dir),
+        }
+    }
+
+    pub fn emblems(&self, path: &Path, is_dir: bool) -> Vec<Emblem> {
+        let mut emblems = Vec::new();
+        if self.synced {
+            emblems.push(Emblem::Synced);
+        }
+        // The closest ignore file with a matching rule decides, like in git
+        for gitignore in self.gitignores.iter() {
+            match gitignore.matched_path_or_any_parents(path, is_dir) {
+                Match::None => continue,
+                Match::Ignore(_) => emblems.push(Emblem::Ignored),
+                Match::Whitelist(_) => {}
+            }
+            break;
+        }
+        emblems
+    }
+}
+
+/// Ignore rules of the git repository containing the folder. Each ignore file has its own root,
+/// as its rules are relative to the folder it is in.
+fn gitignores(dir: &Path) -> Vec<Gitignore> {
+    let Some(repo) = dir
+        .ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+    else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for ancestor in dir.ancestors() {
+        files.push((ancestor, ancestor.join(".gitignore")));
+        if ancestor == repo {
+            break;
+        }
+    }
+    files.push((repo, repo.join(".git").join("info").join("exclude")));
+
+    let mut gitignores = Vec::new();
+    for (root, file) in files {
+        if !file.is_file() {
+            continue;
+        }
+        let mut builder = GitignoreBuilder::new(root);
+        if let Some(err) = builder.add(&file) {
+            log::warn!("failed to read {:?}: {}", file, err);
+        }
+        match builder.build() {
+            Ok(gitignore) => gitignores.push(gitignore),
+            Err(err) => log::warn!("failed to parse {:?}: {}", file, err),
+        }
+    }
+    gitignores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{path_emblems, Emblem, FolderEmblems};
+    use std::{fs, io, os::unix::fs::symlink};
+    use tempfile::TempDir;
+
+    #[test]
+    fn item_emblems() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let file = dir.path().join("file.txt");
+        fs::write(&file, b"")?;
+        let link = dir.path().join("link.txt");
+        symlink(&file, &link)?;
+        assert_eq!(path_emblems(&file), []);
+        assert_eq!(path_emblems(&link), [Emblem::Symlink]);
+
+        fs::create_dir(dir.path().join(".git"))?;
+        fs::write(dir.path().join(".gitignore"), b"*.log\n/build\n")?;
+        let sub = dir.path().join("sub");
+        fs::create_dir(&sub)?;
+        fs::write(sub.join(".gitignore"), b"!keep.log\n")?;
+
+        let folder = FolderEmblems::new(dir.path());
+        assert_eq!(
+            folder.emblems(&dir.path().join("build"), true),
+            [Emblem::Ignored]
+        );
+        assert_eq!(folder.emblems(&file, false), []);
+
+        // Rules in a subfolder are relative to it and take precedence
+        let folder = FolderEmblems::new(&sub);
+        assert_eq!(folder.emblems(&sub.join("build"), true), []);
+        assert_eq!(folder.emblems(&sub.join("keep.log"), false), []);
+        assert_eq!(
+            folder.emblems(&sub.join("other.log"), false),
+            [Emblem::Ignored]
+        );
+
+        fs::create_dir(dir.path().join(".stfolder"))?;
+        let folder = FolderEmblems::new(&sub);
+        assert_eq!(
+            folder.emblems(&sub.join("other.log"), false),
+            [Emblem::Synced, Emblem::Ignored]
+        );
+
+        Ok(())
+    }
+}
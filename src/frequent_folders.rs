@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Frequently used folders, learned from the folders opened in tabs. Every visit adds to the score
+//! of a folder and scores are halved every week, so folders that are no longer used drop out of
+//! the bar shown above the view. Visits are only recorded while the bar is shown, and they are
+//! kept in a state file that is written a while after the last visit, not in the config.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Score of one visit, scores are kept in thousandths of a visit
+const VISIT_SCORE: u64 = 1000;
+/// Seconds after which a score is halved
+const HALF_LIFE_SECS: u64 = 7 * 24 * 60 * 60;
+/// Folders whose visits are remembered, the one with the lowest score is forgotten first
+const MAX_FOLDERS: usize = 100;
+/// Folders shown in the bar, unless more are pinned
+pub const SHOWN: usize = 8;
+/// How long after a visit the visits are saved, so that browsing does not write on every click
+pub const SAVE_DELAY: Duration = Duration::from_secs(10);
+
+/// Visits of a folder, for the frequently used folders bar
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FolderVisits {
+    /// Thousandths of a visit, decayed up to [`FolderVisits::time`]
+    pub score: u64,
+    /// Last visit in seconds since the epoch
+    pub time: u64,
+}
+
+fn path() -> Option<PathBuf> {
+    let dir = dirs::state_dir().or_else(dirs::data_local_dir)?;
+    Some(dir.join("cosmic-files").join("folder-visits.json"))
+}
+
+pub fn load() -> BTreeMap<PathBuf, FolderVisits> {
+    let Some(path) = path() else {
+        return BTreeMap::new();
+    };
+    match fs::read(&path) {
+        Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|err| {
+            log::warn!("invalid folder visits in {:?}: {}", path, err);
+            BTreeMap::new()
+        }),
+        Err(err) => {
+            if err.kind() != io::ErrorKind::NotFound {
+                log::warn!("failed to read folder visits from {:?}: {}", path, err);
+            }
+            BTreeMap::new()
+        }
+    }
+}
+
+pub fn save(folder_visits: &BTreeMap<PathBuf, FolderVisits>) -> io::Result<()> {
+    let path = path().ok_or_else(|| io::Error::other("no state directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write a new file and replace the old one, so that it is never left half written
+    // Keys have to be strings, so folders with names that are not UTF-8 are not saved
+    let folder_visits: BTreeMap<&str, &FolderVisits> = folder_visits
+        .iter()
+        .filter_map(|(path, visits)| Some((path.to_str()?, visits)))
+        .collect();
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec(&folder_visits)?)?;
+    fs::rename(&tmp_path, &path)
+}
+
+/// Score of the folder at the given time in seconds since the epoch
+pub fn score(visits: &FolderVisits, now_secs: u64) -> u64 {
+    let elapsed = now_secs.saturating_sub(visits.time);
+    (visits.score as f64 * 0.5f64.powf(elapsed as f64 / HALF_LIFE_SECS as f64)) as u64
+}
+
+pub fn visit(folder_visits: &mut BTreeMap<PathBuf, FolderVisits>, path: &Path, now_secs: u64) {
+    let visits = folder_visits.entry(path.to_path_buf()).or_default();
+    *visits = FolderVisits {
+        score: score(visits, now_secs) + VISIT_SCORE,
+        time: now_secs,
+    };
+    if folder_visits.len() > MAX_FOLDERS {
+        let forgotten_opt = folder_visits
+            .iter()
+            .filter(|(forgotten, _)| *forgotten != path)
+            .min_by_key(|(_, visits)| score(visits, now_secs))
+            .map(|(forgotten, _)| forgotten.clone());
+        if let Some(forgotten) = forgotten_opt {
+            folder_visits.remove(&forgotten);
+        }
+    }
+}
+
+/// Folders shown in the bar, the pinned folders first in the order they were pinned and then the
+/// folders with the highest scores that still exist
+pub fn frequent(
+    folder_visits: &BTreeMap<PathBuf, FolderVisits>,
+    pinned: &[PathBuf],
+    now_secs: u64,
+    exists: impl Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    let mut scored: Vec<_> = folder_visits
+        .iter()
+        .filter(|(path, _)| !pinned.contains(path))
+        .map(|(path, visits)| (score(visits, now_secs), path))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    let mut folders = pinned.to_vec();
+    folders.extend(
+        scored
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| exists(path))
+            .take(SHOWN.saturating_sub(pinned.len()))
+            .cloned(),
+    );
+    folders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{frequent, score, visit, HALF_LIFE_SECS, MAX_FOLDERS, SHOWN, VISIT_SCORE};
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    #[test]
+    fn frequent_folders() {
+        let now = 100 * HALF_LIFE_SECS;
+        let mut folder_visits = BTreeMap::new();
+        let docs = PathBuf::from("/home/user/Documents");
+        let music = PathBuf::from("/home/user/Music");
+        let work = PathBuf::from("/home/user/Work");
+
+        // Three visits two weeks ago count less than two visits now
+        for _ in 0..3 {
+            visit(&mut folder_visits, &docs, now - 2 * HALF_LIFE_SECS);
+        }
+        visit(&mut folder_visits, &music, now);
+        visit(&mut folder_visits, &music, now);
+        visit(&mut folder_visits, &work, now);
+        assert_eq!(score(&folder_visits[&docs], now), 3 * VISIT_SCORE / 4);
+        assert_eq!(score(&folder_visits[&music], now), 2 * VISIT_SCORE);
+        assert_eq!(
+            frequent(&folder_visits, &[], now, |_| true),
+            vec![music.clone(), work.clone(), docs.clone()]
+        );
+
+        // Pinned folders come first, and missing folders are left out
+        assert_eq!(
+            frequent(&folder_visits, &[docs.clone()], now, |path| path != work),
+            vec![docs.clone(), music.clone()]
+        );
+
+        for i in 0..MAX_FOLDERS {
+            visit(&mut folder_visits, &PathBuf::from(format!("/{}", i)), now);
+        }
+        assert_eq!(folder_visits.len(), MAX_FOLDERS);
+        assert!(!folder_visits.contains_key(&docs));
+        assert!(folder_visits.contains_key(&music));
+        assert_eq!(frequent(&folder_visits, &[], now, |_| true).len(), SHOWN);
+    }
+}
@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Camera metadata of photos in the details pane, read from their EXIF data, and removing that
+//! metadata from JPEG and PNG files before sharing them.
+
+use exif::{In, Reader, Tag, Value};
+use mime_guess::Mime;
+use std::{
+    fs,
+    io::{self, BufReader, Write},
+    path::Path,
+};
+
+const JPEG_SIGNATURE: [u8; 2] = [0xFF, 0xD8];
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+/// Keyword of the PNG text chunk holding XMP, which often repeats the EXIF data
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp\0";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageMetadata {
+    pub camera_opt: Option<String>,
+    pub lens_opt: Option<String>,
+    /// Exposure time in seconds
+    pub exposure_time_opt: Option<f64>,
+    pub f_number_opt: Option<f64>,
+    /// Focal length in millimeters
+    pub focal_length_opt: Option<f64>,
+    pub iso_opt: Option<u32>,
+    /// Latitude and longitude in degrees, negative to the south and west
+    pub location_opt: Option<(f64, f64)>,
+    pub dimensions_opt: Option<(u32, u32)>,
+}
+
+/// Formats that can have EXIF data
+pub fn metadata_supported(mime: &Mime) -> bool {
+    matches!(
+        mime.essence_str(),
+        "image/jpeg" | "image/png" | "image/tiff" | "image/webp" | "image/heif" | "image/heic"
+    )
+}
+
+/// Metadata of the image, or `None` if it has no EXIF data
+pub fn metadata(path: &Path) -> Result<Option<ImageMetadata>, String> {
+    let file = fs::File::open(path).map_err(|err| err.to_string())?;
+    let exif = match Reader::new().read_from_container(&mut BufReader::new(file)) {
+        Ok(ok) => ok,
+        Err(exif::Error::NotFound(_)) => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    };
+
+    let field = |tag| exif.get_field(tag, In::PRIMARY).map(|field| &field.value);
+    let text = |tag| match field(tag)? {
+        Value::Ascii(values) => {
+            let text = String::from_utf8_lossy(values.first()?);
+            let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+            (!text.is_empty()).then(|| text.to_string())
+        }
+        _ => None,
+    };
+    let rational = |tag, index| match field(tag)? {
+        Value::Rational(values) => values
+            .get(index)
+            .filter(|value| value.denom != 0)
+            .map(|value| value.to_f64()),
+        _ => None,
+    };
+    let uint = |tag| field(tag)?.get_uint(0);
+    let coordinate = |tag, reference_tag, negative: &str| {
+        let degrees = rational(tag, 0)? + rational(tag, 1)? / 60.0 + rational(tag, 2)? / 3600.0;
+        Some(if text(reference_tag).as_deref() == Some(negative) {
+            -degrees
+        } else {
+            degrees
+        })
+    };
+
+    // Models usually repeat the make, which is left out then
+    let camera_opt = match (text(Tag::Make), text(Tag::Model)) {
+        (Some(make), Some(model)) if !model.starts_with(&make) => {
+            Some(format!("{} {}", make, model))
+        }
+        (make_opt, model_opt) => model_opt.or(make_opt),
+    };
+    Ok(Some(ImageMetadata {
+        camera_opt,
+        lens_opt: text(Tag::LensModel),
+        exposure_time_opt: rational(Tag::ExposureTime, 0),
+        f_number_opt: rational(Tag::FNumber, 0),
+        focal_length_opt: rational(Tag::FocalLength, 0),
+        iso_opt: uint(Tag::PhotographicSensitivity),
+        location_opt: coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S").zip(coordinate(
+            Tag::GPSLongitude,
+            Tag::GPSLongitudeRef,
+            "W",
+        )),
+        dimensions_opt: uint(Tag::PixelXDimension).zip(uint(Tag::PixelYDimension)),
+    }))
+}
+
+/// Exposure time as a fraction of a second like cameras show it, such as `1/250`
+pub fn format_exposure_time(seconds: f64) -> String {
+    if seconds > 0.0 && seconds < 1.0 {
+        format!("1/{}", (1.0 / seconds).round())
+    } else {
+        format!("{}", (seconds * 10.0).round() / 10.0)
+    }
+}
+
+/// Location on OpenStreetMap, which opens in the browser
+pub fn map_url((latitude, longitude): (f64, f64)) -> String {
+    format!(
+        "https://www.openstreetmap.org/?mlat={latitude:.6}&mlon={longitude:.6}#map=15/{latitude:.6}/{longitude:.6}"
+    )
+}
+
+/// Formats that metadata can be removed from
+pub fn strip_supported(mime: &Mime) -> bool {
+    matches!(mime.essence_str(), "image/jpeg" | "image/png")
+}
+
+/// Remove the EXIF and XMP metadata of a JPEG or PNG file, keeping the image as it is
+pub fn strip(path: &Path) -> io::Result<()> {
+    let data = fs::read(path)?;
+    let stripped = if data.starts_with(&JPEG_SIGNATURE) {
+        strip_jpeg(&data)?
+    } else if data.starts_with(&PNG_SIGNATURE) {
+        strip_png(&data)?
+    } else {
+        return Err(io::Error::other("unsupported image format"));
+    };
+    if stripped.len() == data.len() {
+        return Ok(());
+    }
+
+    // Written to a temporary file first, so that the image is never left partially written
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other(format!("{:?} has no parent directory", path)))?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(&stripped)?;
+    file.as_file()
+        .set_permissions(fs::metadata(path)?.permissions())?;
+    file.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+fn invalid(format: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid {} data", format),
+    )
+}
+
+/// Segments before the image data are copied, except for the APP1 segments with EXIF or XMP
+fn strip_jpeg(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(&JPEG_SIGNATURE);
+    let mut pos = JPEG_SIGNATURE.len();
+    loop {
+        if data.get(pos) != Some(&0xFF) {
+            return Err(invalid("JPEG"));
+        }
+        // Markers may be padded with fill bytes
+        while data.get(pos + 1) == Some(&0xFF) {
+            pos += 1;
+        }
+        let marker = *data.get(pos + 1).ok_or_else(|| invalid("JPEG"))?;
+        // The start of scan and end of image markers are followed by image data only
+        if marker == 0xDA || marker == 0xD9 {
+            stripped.extend_from_slice(&data[pos..]);
+            return Ok(stripped);
+        }
+        let len = data
+            .get(pos + 2..pos + 4)
+            .map(|len| usize::from(u16::from_be_bytes([len[0], len[1]])))
+            .ok_or_else(|| invalid("JPEG"))?;
+        let end = pos + 2 + len;
+        let payload = data.get(pos + 4..end).ok_or_else(|| invalid("JPEG"))?;
+        let metadata = marker == 0xE1
+            && (payload.starts_with(b"Exif\0")
+                || payload.starts_with(b"http://ns.adobe.com/xap/1.0/\0"));
+        if !metadata {
+            stripped.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+}
+
+/// Chunks are copied, except for the EXIF chunk and the text chunk with XMP
+fn strip_png(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(&PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < data.len() {
+        let len = data
+            .get(pos..pos + 4)
+            .map(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize)
+            .ok_or_else(|| invalid("PNG"))?;
+        // Length, type, data and CRC
+        let end = pos + 12 + len;
+        let chunk = data.get(pos..end).ok_or_else(|| invalid("PNG"))?;
+        let kind = &chunk[4..8];
+        let metadata =
+            kind == b"eXIf" || (kind == b"iTXt" && chunk[8..].starts_with(PNG_XMP_KEYWORD));
+        if !metadata {
+            stripped.extend_from_slice(chunk);
+        }
+        pos = end;
+    }
+    Ok(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_exposure_time, map_url, metadata, strip, strip_png, ImageMetadata, PNG_SIGNATURE,
+    };
+    use exif::{experimental::Writer, Field, In, Rational, Tag, Value};
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    fn field(tag: Tag, value: Value) -> Field {
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value,
+        }
+    }
+
+    fn rational(num: u32, denom: u32) -> Rational {
+        Rational { num, denom }
+    }
+
+    /// JPEG file with only the segments needed to hold EXIF data
+    fn jpeg_with_exif(fields: &[Field]) -> Vec<u8> {
+        let mut writer = Writer::new();
+        for field in fields {
+            writer.push_field(field);
+        }
+        let mut tiff = io::Cursor::new(Vec::new());
+        writer.write(&mut tiff, false).unwrap();
+        let tiff = tiff.into_inner();
+
+        let mut data = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        data.extend_from_slice(&(tiff.len() as u16 + 8).to_be_bytes());
+        data.extend_from_slice(b"Exif\0\0");
+        data.extend_from_slice(&tiff);
+        // Comment segment, which is kept
+        data.extend_from_slice(&[0xFF, 0xFE, 0x00, 0x04, b'h', b'i']);
+        data.extend_from_slice(&[0xFF, 0xDA, 0x01, 0x02, 0xFF, 0xD9]);
+        data
+    }
+
+    #[test]
+    fn image_metadata() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("photo.jpg");
+        fs::write(
+            &path,
+            jpeg_with_exif(&[
+                field(Tag::Make, Value::Ascii(vec![b"Camera Co".to_vec()])),
+                field(Tag::Model, Value::Ascii(vec![b"Camera Co X100".to_vec()])),
+                field(Tag::ExposureTime, Value::Rational(vec![rational(1, 250)])),
+                field(Tag::FNumber, Value::Rational(vec![rational(28, 10)])),
+                field(Tag::PhotographicSensitivity, Value::Short(vec![400])),
+                field(Tag::GPSLatitudeRef, Value::Ascii(vec![b"S".to_vec()])),
+                field(
+                    Tag::GPSLatitude,
+                    Value::Rational(vec![rational(33, 1), rational(30, 1), rational(0, 1)]),
+                ),
+                field(Tag::GPSLongitudeRef, Value::Ascii(vec![b"E".to_vec()])),
+                field(
+                    Tag::GPSLongitude,
+                    Value::Rational(vec![rational(151, 1), rational(15, 1), rational(0, 1)]),
+                ),
+            ]),
+        )?;
+        assert_eq!(
+            metadata(&path),
+            Ok(Some(ImageMetadata {
+                camera_opt: Some("Camera Co X100".to_string()),
+                exposure_time_opt: Some(0.004),
+                f_number_opt: Some(2.8),
+                iso_opt: Some(400),
+                location_opt: Some((-33.5, 151.25)),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(format_exposure_time(0.004), "1/250");
+        assert_eq!(format_exposure_time(2.5), "2.5");
+        assert_eq!(
+            map_url((-33.5, 151.25)),
+            "https://www.openstreetmap.org/?mlat=-33.500000&mlon=151.250000#map=15/-33.500000/151.250000"
+        );
+
+        // The rest of the file is kept
+        strip(&path)?;
+        assert_eq!(metadata(&path), Ok(None));
+        assert_eq!(
+            fs::read(&path)?,
+            [0xFF, 0xD8, 0xFF, 0xFE, 0x00, 0x04, b'h', b'i', 0xFF, 0xDA, 0x01, 0x02, 0xFF, 0xD9]
+        );
+
+        let path = dir.path().join("broken.jpg");
+        fs::write(&path, [0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x40])?;
+        assert!(strip(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn strip_png_chunks() -> io::Result<()> {
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+            chunk.extend_from_slice(kind);
+            chunk.extend_from_slice(data);
+            // The CRC is not checked
+            chunk.extend_from_slice(&[0; 4]);
+            chunk
+        };
+        let header = chunk(b"IHDR", &[0; 13]);
+        let text = chunk(b"tEXt", b"Title\0Photo");
+        let end = chunk(b"IEND", &[]);
+        let data = [
+            &PNG_SIGNATURE[..],
+            &header,
+            &chunk(b"eXIf", b"MM\0*"),
+            &text,
+            &chunk(b"iTXt", b"XML:com.adobe.xmp\0\0\0\0\0<x:xmpmeta/>"),
+            &end,
+        ]
+        .concat();
+        assert_eq!(
+            strip_png(&data)?,
+            [&PNG_SIGNATURE[..], &header, &text, &end].concat()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Rotating and flipping images from the context menu. JPEG files are transformed losslessly with
+//! jpegtran when their size allows it, other images are decoded and encoded again.
+
+use exif::{In, Reader, Tag};
+use image::{codecs::jpeg::JpegEncoder, DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use mime_guess::Mime;
+use std::{
+    fs,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+    process::Command,
+};
+
+use crate::thumbnail_cache;
+
+/// Quality of JPEG files that have to be encoded again
+const JPEG_QUALITY: u8 = 90;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ImageTransform {
+    RotateLeft,
+    RotateRight,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl ImageTransform {
+    /// Transform of the stored pixels that has this effect on the image as shown, which differs
+    /// when the EXIF orientation turns or mirrors the stored pixels
+    fn stored(self, orientation: u32) -> Self {
+        let turned = matches!(orientation, 5..=8);
+        let mirrored = matches!(orientation, 2 | 4 | 5 | 7);
+        match self {
+            Self::RotateLeft if mirrored => Self::RotateRight,
+            Self::RotateRight if mirrored => Self::RotateLeft,
+            Self::FlipHorizontal if turned => Self::FlipVertical,
+            Self::FlipVertical if turned => Self::FlipHorizontal,
+            _ => self,
+        }
+    }
+
+    fn jpegtran_args(self) -> [&'static str; 2] {
+        match self {
+            Self::RotateLeft => ["-rotate", "270"],
+            Self::RotateRight => ["-rotate", "90"],
+            Self::FlipHorizontal => ["-flip", "horizontal"],
+            Self::FlipVertical => ["-flip", "vertical"],
+        }
+    }
+
+    fn apply(self, image: DynamicImage) -> DynamicImage {
+        match self {
+            Self::RotateLeft => image.rotate270(),
+            Self::RotateRight => image.rotate90(),
+            Self::FlipHorizontal => image.fliph(),
+            Self::FlipVertical => image.flipv(),
+        }
+    }
+}
+
+/// Formats that can be written again without losing animation or more than JPEG quality
+pub fn transform_supported(mime: &Mime) -> bool {
+    matches!(
+        mime.essence_str(),
+        "image/jpeg" | "image/png" | "image/bmp" | "image/tiff"
+    )
+}
+
+/// EXIF orientation of the image, 1 if it has none
+fn orientation(path: &Path) -> u32 {
+    fs::File::open(path)
+        .ok()
+        .and_then(|file| {
+            Reader::new()
+                .read_from_container(&mut BufReader::new(file))
+                .ok()
+        })
+        .and_then(|exif| {
+            exif.get_field(Tag::Orientation, In::PRIMARY)?
+                .value
+                .get_uint(0)
+        })
+        .unwrap_or(1)
+}
+
+/// Lossless transform of a JPEG file, which fails if the image size is not a multiple of the
+/// JPEG block size
+fn jpegtran(from: &Path, to: &Path, transform: ImageTransform) -> Result<(), String> {
+    let output = Command::new("jpegtran")
+        .arg("-copy")
+        .arg("all")
+        .arg("-perfect")
+        .args(transform.stored(orientation(from)).jpegtran_args())
+        .arg("-outfile")
+        .arg(to)
+        .arg(from)
+        .output()
+        .map_err(|err| format!("failed to run jpegtran: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "jpegtran failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Transform the image as it is shown, replacing the file
+pub fn transform(path: &Path, transform: ImageTransform) -> io::Result<()> {
+    let reader = ImageReader::open(path)?.with_guessed_format()?;
+    let format = reader
+        .format()
+        .ok_or_else(|| io::Error::other("unknown image format"))?;
+
+    // Written to a temporary file first, so that the image is never left partially written
+    let dir = path
+        .parent()
+        .ok_or_else(|| io::Error::other(format!("{:?} has no parent directory", path)))?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    let lossless = format == ImageFormat::Jpeg
+        && match jpegtran(path, file.path(), transform) {
+            Ok(()) => true,
+            Err(err) => {
+                log::info!("encoding {:?} again: {}", path, err);
+                false
+            }
+        };
+    if !lossless {
+        // Metadata is not written again, so the orientation is applied to the pixels
+        let mut decoder = reader.into_decoder().map_err(io::Error::other)?;
+        let orientation = decoder.orientation().map_err(io::Error::other)?;
+        let mut image = DynamicImage::from_decoder(decoder).map_err(io::Error::other)?;
+        image.apply_orientation(orientation);
+        let image = transform.apply(image);
+
+        let mut writer = BufWriter::new(file.as_file_mut());
+        match format {
+            ImageFormat::Jpeg => {
+                image.write_with_encoder(JpegEncoder::new_with_quality(&mut writer, JPEG_QUALITY))
+            }
+            _ => image.write_to(&mut writer, format),
+        }
+        .map_err(io::Error::other)?;
+        writer.into_inner().map_err(|err| err.into_error())?;
+    }
+    file.as_file()
+        .set_permissions(fs::metadata(path)?.permissions())?;
+    file.persist(path).map_err(|err| err.error)?;
+
+    // The modification time may be in the same second as the cached thumbnails
+    if let Err(err) = thumbnail_cache::remove(path) {
+        log::warn!("failed to remove thumbnails of {:?}: {}", path, err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{transform, ImageTransform};
+    use image::{Rgb, RgbImage};
+    use std::io;
+    use tempfile::TempDir;
+
+    #[test]
+    fn image_transform() -> io::Result<()> {
+        // Turned to the right and mirrored, so rotations are reversed and flips are swapped
+        assert_eq!(
+            ImageTransform::RotateLeft.stored(7),
+            ImageTransform::RotateRight
+        );
+        assert_eq!(
+            ImageTransform::FlipHorizontal.stored(6),
+            ImageTransform::FlipVertical
+        );
+        assert_eq!(
+            ImageTransform::FlipHorizontal.stored(1),
+            ImageTransform::FlipHorizontal
+        );
+
+        let dir = TempDir::new()?;
+        let path = dir.path().join("image.png");
+        let red = Rgb([255, 0, 0]);
+        let blue = Rgb([0, 0, 255]);
+        RgbImage::from_fn(2, 1, |x, _| if x == 0 { red } else { blue })
+            .save(&path)
+            .map_err(io::Error::other)?;
+        let read = || -> io::Result<RgbImage> {
+            Ok(image::open(&path).map_err(io::Error::other)?.into_rgb8())
+        };
+
+        transform(&path, ImageTransform::RotateRight)?;
+        let image = read()?;
+        assert_eq!(image.dimensions(), (1, 2));
+        assert_eq!(image.get_pixel(0, 0), &red);
+
+        transform(&path, ImageTransform::FlipVertical)?;
+        let image = read()?;
+        assert_eq!(image.get_pixel(0, 0), &blue);
+        assert_eq!(image.get_pixel(0, 1), &red);
+
+        Ok(())
+    }
+}
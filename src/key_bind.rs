@@ -43,6 +43,11 @@ pub fn key_binds(mode: &tab::Mode) -> HashMap<KeyBind, Action> {
     bind!([Ctrl], Key::Named(Named::Space), Preview);
     bind!([Ctrl], Key::Character("h".into()), ToggleShowHidden);
     bind!([Ctrl], Key::Character("a".into()), SelectAll);
+    bind!(
+        [Ctrl, Shift],
+        Key::Character("a".into()),
+        SelectAllIncludingHidden
+    );
     bind!([Ctrl], Key::Character("=".into()), ZoomIn);
     bind!([Ctrl], Key::Character("+".into()), ZoomIn);
     bind!([Ctrl], Key::Character("0".into()), ZoomDefault);
@@ -54,6 +59,8 @@ pub fn key_binds(mode: &tab::Mode) -> HashMap<KeyBind, Action> {
     // App-only keys
     if matches!(mode, tab::Mode::App) {
         bind!([Ctrl], Key::Character("d".into()), AddToSidebar);
+        bind!([Ctrl, Shift], Key::Character("d".into()), TogglePin);
+        bind!([], Key::Named(Named::F6), FocusDetails);
         bind!([Ctrl], Key::Named(Named::Enter), OpenInNewTab);
         bind!([Ctrl], Key::Character(",".into()), Settings);
         bind!([Ctrl], Key::Character("w".into()), TabClose);
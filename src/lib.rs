@@ -1,27 +1,54 @@
 // Copyright 2023 System76 <info@system76.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use cosmic::{app::Settings, iced::Limits};
+use cosmic::{
+    app::Settings,
+    iced::{Limits, Size},
+};
 use std::{env, fs, path::PathBuf, process};
 
 use app::{App, Flags};
 pub mod app;
+mod attributes;
+mod audio_preview;
+mod audit_log;
+mod checksum;
 pub mod clipboard;
 use config::Config;
 pub mod config;
+mod date_view;
+mod default_apps;
 pub mod dialog;
+mod document_preview;
+pub mod emblem;
+mod frequent_folders;
+mod image_metadata;
+mod image_transform;
 mod key_bind;
 mod localize;
 mod menu;
 mod mime_app;
 pub mod mime_icon;
+#[cfg(feature = "model-thumbnails")]
+mod model_thumbnailer;
 mod mounter;
 mod mouse_area;
 pub mod operation;
+#[cfg(feature = "upower")]
+mod power;
+mod prefetch;
 mod spawn_detached;
+pub mod tag;
 use tab::Location;
 pub mod tab;
+mod thumbnail_cache;
 mod thumbnailer;
+mod trash_policy;
+mod versions;
+#[cfg(feature = "video-thumbnails")]
+mod video_player;
+#[cfg(feature = "video-thumbnails")]
+mod video_thumbnailer;
 
 pub(crate) fn err_str<T: ToString>(err: T) -> String {
     err.to_string()
@@ -74,6 +101,7 @@ pub fn desktop() -> Result<(), Box<dyn std::error::Error>> {
         config,
         mode: app::Mode::Desktop,
         locations,
+        session_window_opt: None,
     };
     cosmic::app::run::<App>(settings, flags)?;
 
@@ -91,18 +119,45 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut daemonize = true;
     let mut locations = Vec::new();
+    let mut session_window_opt = None;
+    let mut window_size_opt = None;
     for arg in env::args().skip(1) {
         let location = if &arg == "--no-daemon" {
             daemonize = false;
             continue;
+        } else if let Some(size) = arg.strip_prefix("--window-size=") {
+            // Used by new windows to start with the size of the window that opened them
+            match size
+                .split_once('x')
+                .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            {
+                Some((width, height)) => window_size_opt = Some(Size::new(width, height)),
+                None => log::warn!("invalid window size {:?}", size),
+            }
+            continue;
+        } else if let Some(id) = arg.strip_prefix("--session-window=") {
+            // Used when restoring a session to open each saved window in its own process
+            match id.parse() {
+                Ok(id) => session_window_opt = Some(id),
+                Err(err) => log::warn!("invalid session window {:?}: {}", id, err),
+            }
+            continue;
         } else if &arg == "--trash" {
             Location::Trash
         } else if &arg == "--recents" {
             Location::Recents
+        } else if &arg == "--computer" {
+            Location::Computer
+        } else if &arg == "--favorites" {
+            Location::Favorites
         } else if &arg == "--network" {
             Location::Network("network:///".to_string(), fl!("networks"))
         } else {
-            //TODO: support more URLs
+            // Remote URIs like sftp://host/path are opened as network locations
+            if let Some(location) = tab::network_location(&arg) {
+                locations.push(location);
+                continue;
+            }
             let path = match url::Url::parse(&arg) {
                 Ok(url) => match url.to_file_path() {
                     Ok(path) => path,
@@ -114,7 +169,15 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Err(_) => PathBuf::from(arg),
             };
             match fs::canonicalize(&path) {
-                Ok(absolute) => Location::Path(absolute),
+                Ok(absolute) => {
+                    // Archives are opened like folders, other files in the folder containing them
+                    #[cfg(feature = "gvfs")]
+                    if let Some(location) = tab::archive_location(&absolute) {
+                        locations.push(location);
+                        continue;
+                    }
+                    Location::Path(absolute)
+                }
                 Err(err) => {
                     log::warn!("failed to canonicalize {:?}: {}", path, err);
                     continue;
@@ -139,6 +202,9 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut settings = Settings::default();
     settings = settings.theme(config.app_theme.theme());
     settings = settings.size_limits(Limits::NONE.min_width(360.0).min_height(180.0));
+    if let Some(size) = window_size_opt {
+        settings = settings.size(Size::new(size.width.max(360.0), size.height.max(180.0)));
+    }
     settings = settings.exit_on_close(false);
 
     #[cfg(feature = "jemalloc")]
@@ -151,6 +217,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
         config,
         mode: app::Mode::App,
         locations,
+        session_window_opt,
     };
     cosmic::app::run::<App>(settings, flags)?;
 
@@ -17,8 +17,10 @@ use std::{collections::HashMap, sync::LazyLock};
 
 use crate::{
     app::{Action, Message},
-    config::Config,
-    fl,
+    config::{Config, ConflictPolicy, ZOOM_PRESETS},
+    fl, image_metadata,
+    image_transform::{self, ImageTransform},
+    mounter::{self, MounterItem},
     tab::{self, HeadingOptions, Location, LocationMenuAction, Tab},
 };
 
@@ -89,8 +91,10 @@ pub fn context_menu<'a>(
         .into()
     };
 
+    let read_only = tab.capabilities.read_only;
     let mut selected_dir = 0;
     let mut selected = 0;
+    let mut selected_starred = 0;
     let mut selected_trash_only = false;
     let mut selected_desktop_entry = None;
     let mut selected_types: Vec<Mime> = vec![];
@@ -101,6 +105,9 @@ pub fn context_menu<'a>(
                 if item.metadata.is_dir() {
                     selected_dir += 1;
                 }
+                if tab.is_starred(item) {
+                    selected_starred += 1;
+                }
                 match &item.location_opt {
                     Some(Location::Trash) => selected_trash_only = true,
                     Some(Location::Path(path)) => {
@@ -118,6 +125,12 @@ pub fn context_menu<'a>(
     };
     selected_types.sort_unstable();
     selected_types.dedup();
+    let selected_photos =
+        !selected_types.is_empty() && selected_types.iter().all(image_metadata::strip_supported);
+    let selected_transformable = !selected_types.is_empty()
+        && selected_types
+            .iter()
+            .all(image_transform::transform_supported);
     selected_trash_only = selected_trash_only && selected == 1;
     // Parse the desktop entry if it is the only selection
     #[cfg(feature = "desktop")]
@@ -136,7 +149,13 @@ pub fn context_menu<'a>(
     match (&tab.mode, &tab.location) {
         (
             tab::Mode::App | tab::Mode::Desktop,
-            Location::Desktop(..) | Location::Path(..) | Location::Search(..) | Location::Recents,
+            Location::Desktop(..)
+            | Location::Path(..)
+            | Location::Search(..)
+            | Location::Recents
+            | Location::Tag(..)
+            | Location::Favorites
+            | Location::Dates(..),
         ) => {
             if selected_trash_only {
                 children.push(menu_item(fl!("open"), Action::Open).into());
@@ -163,12 +182,35 @@ pub fn context_menu<'a>(
                 }
                 if selected == 1 {
                     children.push(menu_item(fl!("menu-open-with"), Action::OpenWith).into());
+                    if matches!(tab.mode, tab::Mode::App) {
+                        children.push(menu_item(fl!("edit-tags"), Action::EditTags).into());
+                        if selected_dir == 0 {
+                            children.push(
+                                menu_item(fl!("verify-checksum"), Action::VerifyChecksum).into(),
+                            );
+                        }
+                    }
                     if selected_dir == 1 {
                         children
                             .push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
+                    } else {
+                        children.push(
+                            menu_item(
+                                fl!("open-in-terminal-with-file"),
+                                Action::OpenTerminalWithFile,
+                            )
+                            .into(),
+                        );
                     }
                 }
-                if matches!(tab.location, Location::Search(..) | Location::Recents) {
+                if matches!(
+                    tab.location,
+                    Location::Search(..)
+                        | Location::Recents
+                        | Location::Tag(..)
+                        | Location::Favorites
+                        | Location::Dates(..)
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
@@ -179,9 +221,24 @@ pub fn context_menu<'a>(
                     children
                         .push(menu_item(fl!("open-in-new-window"), Action::OpenInNewWindow).into());
                 }
+                if matches!(tab.mode, tab::Mode::App) {
+                    let label = if selected_starred == selected {
+                        fl!("unstar")
+                    } else {
+                        fl!("star")
+                    };
+                    children.push(menu_item(label, Action::ToggleFavorite).into());
+                }
                 children.push(divider::horizontal::light().into());
-                children.push(menu_item(fl!("rename"), Action::Rename).into());
-                children.push(menu_item(fl!("cut"), Action::Cut).into());
+                if !read_only {
+                    children.push(menu_item(fl!("rename"), Action::Rename).into());
+                    if selected_dir == 0 && matches!(tab.mode, tab::Mode::App) {
+                        children.push(
+                            menu_item(fl!("change-extension"), Action::ChangeExtension).into(),
+                        );
+                    }
+                    children.push(menu_item(fl!("cut"), Action::Cut).into());
+                }
                 children.push(menu_item(fl!("copy"), Action::Copy).into());
 
                 children.push(divider::horizontal::light().into());
@@ -208,10 +265,29 @@ pub fn context_menu<'a>(
                 .collect::<Vec<_>>();
                 selected_types.retain(|t| !supported_archive_types.contains(t));
                 if selected_types.is_empty() {
-                    children.push(menu_item(fl!("extract-here"), Action::ExtractHere).into());
+                    if !read_only {
+                        children.push(menu_item(fl!("extract-here"), Action::ExtractHere).into());
+                    }
                     children.push(menu_item(fl!("extract-to"), Action::ExtractTo).into());
                 }
-                children.push(menu_item(fl!("compress"), Action::Compress).into());
+                if !read_only {
+                    children.push(menu_item(fl!("compress"), Action::Compress).into());
+                }
+                if !read_only && selected_transformable && matches!(tab.mode, tab::Mode::App) {
+                    for (label, transform) in [
+                        (fl!("rotate-left"), ImageTransform::RotateLeft),
+                        (fl!("rotate-right"), ImageTransform::RotateRight),
+                        (fl!("flip-horizontally"), ImageTransform::FlipHorizontal),
+                        (fl!("flip-vertically"), ImageTransform::FlipVertical),
+                    ] {
+                        children.push(menu_item(label, Action::TransformImages(transform)).into());
+                    }
+                }
+                if !read_only && selected_photos && matches!(tab.mode, tab::Mode::App) {
+                    children.push(
+                        menu_item(fl!("remove-photo-metadata"), Action::StripMetadata).into(),
+                    );
+                }
                 children.push(divider::horizontal::light().into());
 
                 //TODO: Print?
@@ -219,26 +295,47 @@ pub fn context_menu<'a>(
                 if matches!(tab.mode, tab::Mode::App) {
                     children.push(divider::horizontal::light().into());
                     children.push(menu_item(fl!("add-to-sidebar"), Action::AddToSidebar).into());
+                    children.push(menu_item(fl!("pin-for-session"), Action::TogglePin).into());
                 }
-                children.push(divider::horizontal::light().into());
-                if modifiers.shift() && !modifiers.control() {
+                if read_only {
+                    // Items on a read-only file system cannot be deleted
+                } else if (modifiers.shift() && !modifiers.control()) || tab.capabilities.remote {
+                    // Network shares have no trash, so items can only be deleted permanently
+                    children.push(divider::horizontal::light().into());
                     children.push(
                         menu_item(fl!("delete-permanently"), Action::PermanentlyDelete).into(),
                     );
                 } else {
+                    children.push(divider::horizontal::light().into());
                     children.push(menu_item(fl!("move-to-trash"), Action::Delete).into());
                 }
+                if !read_only && tab.config.secure_delete && matches!(tab.mode, tab::Mode::App) {
+                    children.push(menu_item(fl!("secure-delete"), Action::SecureDelete).into());
+                }
             } else {
                 //TODO: need better designs for menu with no selection
                 //TODO: have things like properties but they apply to the folder?
-                children.push(menu_item(fl!("new-folder"), Action::NewFolder).into());
-                children.push(menu_item(fl!("new-file"), Action::NewFile).into());
+                if !read_only {
+                    children.push(menu_item(fl!("new-folder"), Action::NewFolder).into());
+                    children.push(menu_item(fl!("new-file"), Action::NewFile).into());
+                }
                 children.push(menu_item(fl!("open-in-terminal"), Action::OpenTerminal).into());
+                if matches!(tab.mode, tab::Mode::App) {
+                    children.push(
+                        menu_item(fl!("open-folder-in-new-tab"), Action::OpenInNewTab).into(),
+                    );
+                    children.push(
+                        menu_item(fl!("open-folder-in-new-window"), Action::OpenInNewWindow).into(),
+                    );
+                }
+                children.push(menu_item(fl!("reload-folder"), Action::Reload).into());
                 children.push(divider::horizontal::light().into());
                 if tab.mode.multiple() {
                     children.push(menu_item(fl!("select-all"), Action::SelectAll).into());
                 }
-                children.push(menu_item(fl!("paste"), Action::Paste).into());
+                if !read_only {
+                    children.push(menu_item(fl!("paste"), Action::Paste).into());
+                }
 
                 //TODO: only show if cosmic-settings is found?
                 if matches!(tab.mode, tab::Mode::Desktop) {
@@ -260,6 +357,7 @@ pub fn context_menu<'a>(
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions::Name));
                 children.push(sort_item(fl!("sort-by-modified"), HeadingOptions::Modified));
                 children.push(sort_item(fl!("sort-by-size"), HeadingOptions::Size));
+                children.push(sort_item(fl!("sort-by-type"), HeadingOptions::Kind));
                 if matches!(tab.location, Location::Desktop(..)) {
                     children.push(divider::horizontal::light().into());
                     children.push(
@@ -270,13 +368,26 @@ pub fn context_menu<'a>(
         }
         (
             tab::Mode::Dialog(dialog_kind),
-            Location::Desktop(..) | Location::Path(..) | Location::Search(..) | Location::Recents,
+            Location::Desktop(..)
+            | Location::Path(..)
+            | Location::Search(..)
+            | Location::Recents
+            | Location::Tag(..)
+            | Location::Favorites
+            | Location::Dates(..),
         ) => {
             if selected > 0 {
                 if selected_dir == 1 && selected == 1 || selected_dir == 0 {
                     children.push(menu_item(fl!("open"), Action::Open).into());
                 }
-                if matches!(tab.location, Location::Search(..) | Location::Recents) {
+                if matches!(
+                    tab.location,
+                    Location::Search(..)
+                        | Location::Recents
+                        | Location::Tag(..)
+                        | Location::Favorites
+                        | Location::Dates(..)
+                ) {
                     children.push(
                         menu_item(fl!("open-item-location"), Action::OpenItemLocation).into(),
                     );
@@ -296,9 +407,38 @@ pub fn context_menu<'a>(
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions::Name));
                 children.push(sort_item(fl!("sort-by-modified"), HeadingOptions::Modified));
                 children.push(sort_item(fl!("sort-by-size"), HeadingOptions::Size));
+                children.push(sort_item(fl!("sort-by-type"), HeadingOptions::Kind));
             }
         }
-        (_, Location::Network(..)) => {
+        // Items of network locations that a mounter reads itself can be copied, renamed, and
+        // deleted, but not opened with other applications
+        (tab::Mode::App, Location::Network(uri, _)) if mounter::network_files(uri).is_some() => {
+            if selected > 0 {
+                if selected_dir == 1 && selected == 1 {
+                    children.push(menu_item(fl!("open"), Action::Open).into());
+                    children.push(divider::horizontal::light().into());
+                }
+                if selected == 1 {
+                    children.push(menu_item(fl!("rename"), Action::Rename).into());
+                }
+                children.push(menu_item(fl!("cut"), Action::Cut).into());
+                children.push(menu_item(fl!("copy"), Action::Copy).into());
+                children.push(divider::horizontal::light().into());
+                children
+                    .push(menu_item(fl!("delete-permanently"), Action::PermanentlyDelete).into());
+            } else {
+                children.push(menu_item(fl!("reload-folder"), Action::Reload).into());
+                children.push(divider::horizontal::light().into());
+                children.push(menu_item(fl!("select-all"), Action::SelectAll).into());
+                children.push(menu_item(fl!("paste"), Action::Paste).into());
+                children.push(divider::horizontal::light().into());
+                children.push(sort_item(fl!("sort-by-name"), HeadingOptions::Name));
+                children.push(sort_item(fl!("sort-by-modified"), HeadingOptions::Modified));
+                children.push(sort_item(fl!("sort-by-size"), HeadingOptions::Size));
+                children.push(sort_item(fl!("sort-by-type"), HeadingOptions::Kind));
+            }
+        }
+        (_, Location::Computer | Location::Network(..)) => {
             if selected > 0 {
                 if selected_dir == 1 && selected == 1 || selected_dir == 0 {
                     children.push(menu_item(fl!("open"), Action::Open).into());
@@ -313,6 +453,7 @@ pub fn context_menu<'a>(
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions::Name));
                 children.push(sort_item(fl!("sort-by-modified"), HeadingOptions::Modified));
                 children.push(sort_item(fl!("sort-by-size"), HeadingOptions::Size));
+                children.push(sort_item(fl!("sort-by-type"), HeadingOptions::Kind));
             }
         }
         (_, Location::Trash) => {
@@ -327,6 +468,7 @@ pub fn context_menu<'a>(
                 children.push(divider::horizontal::light().into());
                 children
                     .push(menu_item(fl!("restore-from-trash"), Action::RestoreFromTrash).into());
+                children.push(menu_item(fl!("restore-to"), Action::RestoreTo).into());
                 children.push(divider::horizontal::light().into());
                 children.push(menu_item(fl!("delete-permanently"), Action::Delete).into());
             } else {
@@ -334,6 +476,10 @@ pub fn context_menu<'a>(
                 children.push(sort_item(fl!("sort-by-name"), HeadingOptions::Name));
                 children.push(sort_item(fl!("sort-by-trashed"), HeadingOptions::TrashedOn));
                 children.push(sort_item(fl!("sort-by-size"), HeadingOptions::Size));
+                children.push(sort_item(
+                    fl!("sort-by-original-location"),
+                    HeadingOptions::OriginalLocation,
+                ));
             }
         }
     }
@@ -454,7 +600,7 @@ pub fn dialog_menu(
                         tab::HeadingOptions::Size,
                         false,
                     ),
-                    //TODO: sort by type
+                    sort_item(fl!("sort-by-type"), tab::HeadingOptions::Kind, true),
                 ],
             ),
         ),
@@ -482,6 +628,18 @@ pub fn dialog_menu(
                         tab.config.folders_first,
                         Action::ToggleFoldersFirst,
                     ),
+                    menu::Item::CheckBox(
+                        fl!("group-by-type"),
+                        None,
+                        tab.config.group_by_kind,
+                        Action::ToggleGroupByKind,
+                    ),
+                    menu::Item::CheckBox(
+                        fl!("folder-sizes"),
+                        None,
+                        tab.config.folder_sizes,
+                        Action::ToggleFolderSizes,
+                    ),
                     menu::Item::CheckBox(fl!("show-details"), None, show_details, Action::Preview),
                     menu::Item::Divider,
                     menu_button_optional(
@@ -518,6 +676,16 @@ pub fn menu_bar<'a>(
         )
     };
     let in_trash = tab_opt.map_or(false, |tab| tab.location == Location::Trash);
+    let read_only = tab_opt.map_or(false, |tab| tab.capabilities.read_only);
+    let sort_secondary_opt = tab_opt.and_then(|tab| tab.sort_secondary());
+    let sort_secondary_item = |label, sort| {
+        menu::Item::CheckBox(
+            label,
+            None,
+            sort_secondary_opt.map_or(false, |(sort_secondary, _)| sort_secondary == sort),
+            Action::ToggleSortSecondary(sort),
+        )
+    };
 
     let mut selected_dir = 0;
     let mut selected = 0;
@@ -536,8 +704,11 @@ pub fn menu_bar<'a>(
         }
     };
 
-    let (delete_item, delete_item_action) = if in_trash || modifiers.shift() {
+    let no_trash = tab_opt.map_or(false, |tab| tab.capabilities.remote);
+    let (delete_item, delete_item_action) = if in_trash {
         (fl!("delete-permanently"), Action::Delete)
+    } else if modifiers.shift() || no_trash {
+        (fl!("delete-permanently"), Action::PermanentlyDelete)
     } else {
         (fl!("move-to-trash"), Action::Delete)
     };
@@ -557,8 +728,8 @@ pub fn menu_bar<'a>(
                     vec![
                         menu::Item::Button(fl!("new-tab"), None, Action::TabNew),
                         menu::Item::Button(fl!("new-window"), None, Action::WindowNew),
-                        menu::Item::Button(fl!("new-folder"), None, Action::NewFolder),
-                        menu::Item::Button(fl!("new-file"), None, Action::NewFile),
+                        menu_button_optional(fl!("new-folder"), Action::NewFolder, !read_only),
+                        menu_button_optional(fl!("new-file"), Action::NewFile, !read_only),
                         menu_button_optional(
                             fl!("open"),
                             Action::Open,
@@ -571,7 +742,16 @@ pub fn menu_bar<'a>(
                             selected == 1,
                         ),
                         menu::Item::Divider,
-                        menu_button_optional(fl!("rename"), Action::Rename, selected > 0),
+                        menu_button_optional(
+                            fl!("rename"),
+                            Action::Rename,
+                            selected > 0 && !read_only,
+                        ),
+                        menu_button_optional(
+                            fl!("change-extension"),
+                            Action::ChangeExtension,
+                            selected > 0 && selected_dir == 0 && !read_only,
+                        ),
                         menu::Item::Divider,
                         menu::Item::Button(fl!("reload-folder"), None, Action::Reload),
                         menu::Item::Divider,
@@ -580,13 +760,23 @@ pub fn menu_bar<'a>(
                             Action::AddToSidebar,
                             selected > 0,
                         ),
+                        menu::Item::Button(fl!("pin-for-session"), None, Action::TogglePin),
                         menu::Item::Divider,
                         menu_button_optional(
                             fl!("restore-from-trash"),
                             Action::RestoreFromTrash,
                             selected > 0 && in_trash,
                         ),
-                        menu_button_optional(delete_item, delete_item_action, selected > 0),
+                        menu_button_optional(
+                            fl!("restore-to"),
+                            Action::RestoreTo,
+                            selected > 0 && in_trash,
+                        ),
+                        menu_button_optional(
+                            delete_item,
+                            delete_item_action,
+                            selected > 0 && !read_only,
+                        ),
                         menu::Item::Divider,
                         menu::Item::Button(fl!("close-tab"), None, Action::TabClose),
                         menu::Item::Button(fl!("quit"), None, Action::WindowClose),
@@ -595,10 +785,55 @@ pub fn menu_bar<'a>(
                 (
                     (fl!("edit")),
                     vec![
-                        menu_button_optional(fl!("cut"), Action::Cut, selected > 0),
+                        menu_button_optional(fl!("cut"), Action::Cut, selected > 0 && !read_only),
                         menu_button_optional(fl!("copy"), Action::Copy, selected > 0),
-                        menu_button_optional(fl!("paste"), Action::Paste, selected > 0),
+                        menu_button_optional(
+                            fl!("paste"),
+                            Action::Paste,
+                            selected > 0 && !read_only,
+                        ),
+                        menu::Item::Folder(
+                            fl!("paste-with"),
+                            [
+                                (fl!("paste-keep-both"), ConflictPolicy::KeepBoth),
+                                (fl!("paste-replace-older"), ConflictPolicy::ReplaceOlder),
+                                (fl!("paste-skip"), ConflictPolicy::Skip),
+                            ]
+                            .into_iter()
+                            .map(|(label, conflict_policy)| {
+                                menu_button_optional(
+                                    label,
+                                    Action::PasteWith(conflict_policy),
+                                    selected > 0 && !read_only,
+                                )
+                            })
+                            .collect(),
+                        ),
+                        menu_button_optional(
+                            fl!("paste-preview"),
+                            Action::PastePreview,
+                            selected > 0 && !read_only,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("keep-both-here"),
+                            None,
+                            tab_opt.map_or(false, |tab| match &tab.location {
+                                Location::Path(path) => config.keep_both_folders.contains(path),
+                                _ => false,
+                            }),
+                            Action::ToggleKeepBoth,
+                        ),
                         menu::Item::Button(fl!("select-all"), None, Action::SelectAll),
+                        menu::Item::Button(
+                            fl!("select-all-including-hidden"),
+                            None,
+                            Action::SelectAllIncludingHidden,
+                        ),
+                        menu_button_optional(
+                            fl!("selection-statistics"),
+                            Action::SelectionStats,
+                            selected > 0,
+                        ),
                         menu::Item::Divider,
                         menu::Item::Button(fl!("history"), None, Action::EditHistory),
                     ],
@@ -609,6 +844,20 @@ pub fn menu_bar<'a>(
                         menu::Item::Button(fl!("zoom-in"), None, Action::ZoomIn),
                         menu::Item::Button(fl!("default-size"), None, Action::ZoomDefault),
                         menu::Item::Button(fl!("zoom-out"), None, Action::ZoomOut),
+                        menu::Item::Folder(
+                            fl!("zoom"),
+                            ZOOM_PRESETS
+                                .into_iter()
+                                .map(|zoom| {
+                                    menu::Item::CheckBox(
+                                        fl!("zoom-level", zoom = zoom),
+                                        None,
+                                        tab_opt.is_some_and(|tab| tab.zoom() == zoom),
+                                        Action::ZoomSet(zoom),
+                                    )
+                                })
+                                .collect(),
+                        ),
                         menu::Item::Divider,
                         menu::Item::CheckBox(
                             fl!("grid-view"),
@@ -635,6 +884,24 @@ pub fn menu_bar<'a>(
                             tab_opt.map_or(false, |tab| tab.config.folders_first),
                             Action::ToggleFoldersFirst,
                         ),
+                        menu::Item::CheckBox(
+                            fl!("group-by-type"),
+                            None,
+                            tab_opt.map_or(false, |tab| tab.config.group_by_kind),
+                            Action::ToggleGroupByKind,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("folder-sizes"),
+                            None,
+                            tab_opt.map_or(false, |tab| tab.config.folder_sizes),
+                            Action::ToggleFolderSizes,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("browse-by-date"),
+                            None,
+                            tab_opt.is_some_and(|tab| matches!(tab.location, Location::Dates(..))),
+                            Action::BrowseByDate,
+                        ),
                         menu::Item::CheckBox(
                             fl!("show-details"),
                             None,
@@ -653,6 +920,17 @@ pub fn menu_bar<'a>(
                         menu::Item::Button(fl!("menu-about"), None, Action::About),
                     ],
                 ),
+                (
+                    (fl!("go")),
+                    vec![
+                        menu::Item::Button(fl!("back"), None, Action::HistoryPrevious),
+                        menu::Item::Button(fl!("forward"), None, Action::HistoryNext),
+                        menu::Item::Button(fl!("parent-folder"), None, Action::LocationUp),
+                        menu::Item::Divider,
+                        menu::Item::Button(fl!("recents"), None, Action::Recents),
+                        menu::Item::Button(fl!("computer"), None, Action::Computer),
+                    ],
+                ),
                 (
                     (fl!("sort")),
                     vec![
@@ -686,16 +964,30 @@ pub fn menu_bar<'a>(
                             tab::HeadingOptions::Size,
                             false,
                         ),
-                        //TODO: sort by type
+                        sort_item(fl!("sort-by-type"), tab::HeadingOptions::Kind, true),
+                        menu::Item::Divider,
+                        sort_secondary_item(fl!("then-by-name"), tab::HeadingOptions::Name),
+                        sort_secondary_item(
+                            fl!("then-by-modified"),
+                            if in_trash {
+                                tab::HeadingOptions::TrashedOn
+                            } else {
+                                tab::HeadingOptions::Modified
+                            },
+                        ),
+                        sort_secondary_item(fl!("then-by-size"), tab::HeadingOptions::Size),
                     ],
                 ),
             ],
         )
 }
 
-pub fn location_context_menu<'a>(ancestor_index: usize) -> Element<'a, tab::Message> {
+pub fn location_context_menu<'a>(
+    ancestor_index: usize,
+    mount_opt: Option<&MounterItem>,
+) -> Element<'a, tab::Message> {
     //TODO: only add some of these when in App mode
-    let children = vec![
+    let mut children = vec![
         menu_button!(text::body(fl!("open-in-new-tab")))
             .on_press(tab::Message::LocationMenuAction(
                 LocationMenuAction::OpenInNewTab(ancestor_index),
@@ -706,7 +998,17 @@ pub fn location_context_menu<'a>(ancestor_index: usize) -> Element<'a, tab::Mess
                 LocationMenuAction::OpenInNewWindow(ancestor_index),
             ))
             .into(),
+        menu_button!(text::body(fl!("open-in-terminal")))
+            .on_press(tab::Message::LocationMenuAction(
+                LocationMenuAction::OpenInTerminal(ancestor_index),
+            ))
+            .into(),
         divider::horizontal::light().into(),
+        menu_button!(text::body(fl!("copy-path")))
+            .on_press(tab::Message::LocationMenuAction(
+                LocationMenuAction::CopyPath(ancestor_index),
+            ))
+            .into(),
         menu_button!(text::body(fl!("show-details")))
             .on_press(tab::Message::LocationMenuAction(
                 LocationMenuAction::Preview(ancestor_index),
@@ -719,7 +1021,45 @@ pub fn location_context_menu<'a>(ancestor_index: usize) -> Element<'a, tab::Mess
             ))
             .into(),
     ];
+    if let Some(mount) = mount_opt {
+        children.push(divider::horizontal::light().into());
+        children.push(
+            menu_button!(text::body(if mount.can_eject() {
+                fl!("eject")
+            } else {
+                fl!("unmount")
+            }))
+            .on_press(tab::Message::LocationMenuAction(
+                LocationMenuAction::Unmount(ancestor_index),
+            ))
+            .into(),
+        );
+    }
+
+    popup_container(children)
+}
+
+/// Columns shown in list view, opened by right clicking the column headings
+pub fn column_menu<'a>(columns: &[HeadingOptions]) -> Element<'a, tab::Message> {
+    let children = HeadingOptions::COLUMNS
+        .iter()
+        .map(|heading| {
+            let check: Element<_> = if columns.contains(heading) {
+                widget::icon::from_name("object-select-symbolic")
+                    .size(16)
+                    .into()
+            } else {
+                widget::Space::with_width(Length::Fixed(16.0)).into()
+            };
+            menu_button!(text::body(heading.to_string()), horizontal_space(), check)
+                .on_press(tab::Message::ToggleColumn(*heading))
+                .into()
+        })
+        .collect();
+    popup_container(children)
+}
 
+fn popup_container<'a>(children: Vec<Element<'a, tab::Message>>) -> Element<'a, tab::Message> {
     container(column::with_children(children))
         .padding(1)
         .style(|theme| {
@@ -1,13 +1,15 @@
 // Copyright 2023 System76 <info@system76.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+#[cfg(feature = "desktop")]
+use crate::default_apps;
 #[cfg(feature = "desktop")]
 use cosmic::desktop;
 use cosmic::widget;
 pub use mime_guess::Mime;
 use std::{
     cmp::Ordering,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env,
     ffi::OsStr,
     fs, io,
@@ -161,6 +163,23 @@ pub fn exec_to_command(
     Some(processes)
 }
 
+/// Commands for a command line typed in the Open With dialog. The paths are added at the end
+/// unless the command line has a field code for them.
+pub fn custom_command(
+    command: &str,
+    path_opt: &[impl AsRef<OsStr>],
+) -> Option<Vec<process::Command>> {
+    let args = shlex::split(command)?;
+    if args.is_empty() {
+        return None;
+    }
+    if args.iter().any(|arg| EXEC_HANDLERS.contains(&arg.as_str())) {
+        exec_to_command(command, path_opt)
+    } else {
+        exec_to_command(&format!("{} %F", command.trim_end()), path_opt)
+    }
+}
+
 fn from_file_or_dir(path: impl AsRef<Path>) -> Option<url::Url> {
     url::Url::from_file_path(&path)
         .ok()
@@ -224,6 +243,8 @@ pub struct MimeAppCache {
     cache: HashMap<Mime, Vec<MimeApp>>,
     icons: HashMap<Mime, Vec<widget::icon::Handle>>,
     terminals: Vec<MimeApp>,
+    /// MIME types with a default application chosen by the user
+    user_defaults: HashSet<Mime>,
 }
 
 impl MimeAppCache {
@@ -233,6 +254,7 @@ impl MimeAppCache {
             cache: HashMap::new(),
             icons: HashMap::new(),
             terminals: Vec::new(),
+            user_defaults: HashSet::new(),
         };
         mime_app_cache.reload();
         mime_app_cache
@@ -252,6 +274,7 @@ impl MimeAppCache {
         self.cache.clear();
         self.icons.clear();
         self.terminals.clear();
+        self.user_defaults.clear();
 
         //TODO: get proper locale?
         let locale = &[];
@@ -318,6 +341,7 @@ impl MimeAppCache {
         }
 
         //TODO: handle directory specific behavior
+        let local_path_opt = cosmic_mime_apps::local_list_path();
         for path in mimeapps_paths {
             let entry = match freedesktop_entry_parser::parse_entry(&path) {
                 Ok(ok) => ok,
@@ -366,8 +390,12 @@ impl MimeAppCache {
                 }
             }
 
+            let local = local_path_opt.as_ref() == Some(&path);
             for attr in entry.section("Default Applications").attrs() {
                 if let Ok(mime) = attr.name.parse::<Mime>() {
+                    if local {
+                        self.user_defaults.insert(mime.clone());
+                    }
                     if let Some(filenames) = attr.value {
                         for filename in filenames.split_terminator(';') {
                             log::trace!("default {}={}", mime, filename);
@@ -430,6 +458,23 @@ impl MimeAppCache {
         self.cache.get(key).unwrap_or(&EMPTY)
     }
 
+    /// MIME types that can be opened by at least one application, sorted by name
+    pub fn mime_types(&self) -> Vec<&Mime> {
+        let mut mime_types: Vec<_> = self
+            .cache
+            .iter()
+            .filter(|(_, apps)| !apps.is_empty())
+            .map(|(mime, _)| mime)
+            .collect();
+        mime_types.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        mime_types
+    }
+
+    /// The default application of the MIME type was chosen by the user, and can be reset
+    pub fn has_user_default(&self, key: &Mime) -> bool {
+        self.user_defaults.contains(key)
+    }
+
     pub fn icons(&self, key: &Mime) -> &[widget::icon::Handle] {
         static EMPTY: Vec<widget::icon::Handle> = Vec::new();
         self.icons.get(key).unwrap_or(&EMPTY)
@@ -475,41 +520,74 @@ impl MimeAppCache {
         self.terminals.first()
     }
 
-    #[cfg(not(feature = "desktop"))]
     pub fn set_default(&mut self, mime: Mime, id: String) {
+        self.set_defaults(&[mime], &id);
+    }
+
+    #[cfg(not(feature = "desktop"))]
+    pub fn set_defaults(&mut self, mimes: &[Mime], id: &str) {
         log::warn!(
-            "failed to set default handler for {mime:?} to {id:?}: desktop feature not enabled"
+            "failed to set default handler for {mimes:?} to {id:?}: desktop feature not enabled"
         );
     }
 
+    /// Make the application the default for all of the MIME types, reloading once afterwards
     #[cfg(feature = "desktop")]
-    pub fn set_default(&mut self, mime: Mime, mut id: String) {
+    pub fn set_defaults(&mut self, mimes: &[Mime], id: &str) {
         let Some(path) = cosmic_mime_apps::local_list_path() else {
             log::warn!("failed to find mimeapps.list path");
             return;
         };
 
-        let mut list = cosmic_mime_apps::List::default();
-        match fs::read_to_string(&path) {
-            Ok(string) => {
-                list.load_from(&string);
+        let string = match fs::read_to_string(&path) {
+            Ok(ok) => ok,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                log::warn!("failed to read {path:?}: {err}");
+                return;
+            }
+        };
+        let mimes: Vec<&str> = mimes.iter().map(|mime| mime.as_ref()).collect();
+        let string = default_apps::set_default_apps(&string, &mimes, id);
+        if let Some(parent) = path.parent() {
+            if let Err(err) = fs::create_dir_all(parent) {
+                log::warn!("failed to create {parent:?}: {err}");
+                return;
+            }
+        }
+        match fs::write(&path, string) {
+            Ok(()) => {
+                self.reload();
             }
             Err(err) => {
-                if err.kind() != io::ErrorKind::NotFound {
-                    log::warn!("failed to read {path:?}: {err}");
-                    return;
-                }
+                log::warn!("failed to write {path:?}: {err}");
             }
         }
+    }
 
-        let suffix = ".desktop";
-        if !id.ends_with(suffix) {
-            id.push_str(suffix);
-        }
-        list.set_default_app(mime, id);
+    #[cfg(not(feature = "desktop"))]
+    pub fn reset_default(&mut self, mime: Mime) {
+        log::warn!("failed to reset default handler for {mime:?}: desktop feature not enabled");
+    }
+
+    /// Remove the default application chosen by the user, so that the system default is used
+    #[cfg(feature = "desktop")]
+    pub fn reset_default(&mut self, mime: Mime) {
+        let Some(path) = cosmic_mime_apps::local_list_path() else {
+            log::warn!("failed to find mimeapps.list path");
+            return;
+        };
 
-        let mut string = list.to_string();
-        string.push('\n');
+        let string = match fs::read_to_string(&path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to read {path:?}: {err}");
+                return;
+            }
+        };
+        let Some(string) = default_apps::remove_default_app(&string, mime.as_ref()) else {
+            return;
+        };
         match fs::write(&path, string) {
             Ok(()) => {
                 self.reload();
@@ -529,7 +607,22 @@ impl Default for MimeAppCache {
 
 #[cfg(test)]
 mod tests {
-    use super::exec_to_command;
+    use super::{custom_command, exec_to_command};
+
+    #[test]
+    fn custom_command_paths() {
+        let paths = ["one.png", "two.png"];
+        let commands = custom_command("gimp --new-instance", &paths).expect("Should parse");
+        assert_eq!(1, commands.len());
+        let args: Vec<_> = commands[0].get_args().collect();
+        assert_eq!(args, ["--new-instance", "one.png", "two.png"]);
+
+        let commands = custom_command("viewer %f", &paths).expect("Should parse");
+        assert_eq!(2, commands.len());
+
+        assert!(custom_command("", &paths).is_none());
+        assert!(custom_command("viewer 'unclosed", &paths).is_none());
+    }
 
     #[test]
     fn one_path_f_field_code() {
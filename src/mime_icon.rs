@@ -3,10 +3,22 @@
 use cosmic::widget::icon;
 use mime_guess::Mime;
 use once_cell::sync::Lazy;
-use std::{collections::HashMap, fs, path::Path, sync::Mutex};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
 
 pub const FALLBACK_MIME_ICON: &str = "text-x-generic";
 
+/// Most bytes read from the start of a file to find its type by its contents
+const SNIFF_MAX_BYTES: u64 = 4096;
+/// Sniffed types are forgotten once this many files have been sniffed
+const SNIFF_CACHE_MAX_ENTRIES: usize = 10_000;
+
 #[derive(Debug, Eq, Hash, PartialEq)]
 struct MimeIconKey {
     mime: Mime,
@@ -78,6 +90,87 @@ pub fn mime_for_path<P: AsRef<Path>>(
     }
 }
 
+static SNIFF_CACHE: Lazy<Mutex<HashMap<(PathBuf, SystemTime), Mime>>> = Lazy::new(Default::default);
+
+/// Type of a file from its name and the magic bytes at its start, which finds the type of files
+/// without an extension or with a misleading one. Results are cached by path and modification time.
+pub fn sniff_mime(path: &Path, metadata: &fs::Metadata) -> io::Result<Mime> {
+    let key = (path.to_path_buf(), metadata.modified()?);
+    if let Some(mime) = SNIFF_CACHE.lock().unwrap().get(&key) {
+        return Ok(mime.clone());
+    }
+
+    let mut data = Vec::new();
+    fs::File::open(path)?
+        .take(SNIFF_MAX_BYTES)
+        .read_to_end(&mut data)?;
+    let mime = {
+        let mime_icon_cache = MIME_ICON_CACHE.lock().unwrap();
+        let mut gb = mime_icon_cache.shared_mime_info.guess_mime_type();
+        if let Some(file_name) = path.file_name().and_then(|x| x.to_str()) {
+            gb.file_name(file_name);
+        }
+        gb.data(&data);
+        gb.metadata(metadata.clone());
+        let guess = gb.guess();
+        if guess.uncertain() {
+            mime_guess::from_path(path).first_or_octet_stream()
+        } else {
+            guess.mime_type().clone()
+        }
+    };
+
+    let mut cache = SNIFF_CACHE.lock().unwrap();
+    if cache.len() >= SNIFF_CACHE_MAX_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(key, mime.clone());
+    Ok(mime)
+}
+
+/// Type of a file from the magic bytes at its start only, `None` if they do not tell, like for
+/// most text files
+pub fn content_mime(path: &Path) -> io::Result<Option<Mime>> {
+    let mut data = Vec::new();
+    fs::File::open(path)?
+        .take(SNIFF_MAX_BYTES)
+        .read_to_end(&mut data)?;
+    let mime_icon_cache = MIME_ICON_CACHE.lock().unwrap();
+    Ok(mime_icon_cache
+        .shared_mime_info
+        .get_mime_type_for_data(&data)
+        .map(|(mime, _)| mime))
+}
+
+/// Whether files with the extension can have a type found from their contents. Types that are a
+/// kind of each other fit, like office documents and the ZIP archives they are stored in.
+pub fn extension_fits(extension: &str, content: &Mime) -> bool {
+    let mime_icon_cache = MIME_ICON_CACHE.lock().unwrap();
+    let shared_mime_info = &mime_icon_cache.shared_mime_info;
+    let mut mimes = shared_mime_info.get_mime_types_from_file_name(&format!("file.{}", extension));
+    mimes.extend(mime_guess::from_ext(extension).iter());
+    mimes.iter().any(|mime| {
+        mime == content
+            || shared_mime_info.mime_type_subclass(mime, content)
+            || shared_mime_info.mime_type_subclass(content, mime)
+    })
+}
+
+/// Type of a file, using the type found by [`sniff_mime`] if the file has not changed since
+pub fn mime_for_path_sniffed(path: &Path) -> Mime {
+    let sniffed_opt = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| {
+            SNIFF_CACHE
+                .lock()
+                .unwrap()
+                .get(&(path.to_path_buf(), modified))
+                .cloned()
+        });
+    sniffed_opt.unwrap_or_else(|| mime_for_path(path, None, false))
+}
+
 pub fn mime_icon(mime: Mime, size: u16) -> icon::Handle {
     let mut mime_icon_cache = MIME_ICON_CACHE.lock().unwrap();
     match mime_icon_cache.get(MimeIconKey { mime, size }) {
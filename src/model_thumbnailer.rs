@@ -0,0 +1,538 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Thumbnails for 3D files: the preview Blender embeds in .blend files, and a shaded render of
+//! the geometry in .stl, .obj, and .glb files.
+
+use image::{Rgba, RgbaImage};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+};
+
+/// Limit on the number of triangles rendered, to bound the time spent on huge models
+const MAX_TRIANGLES: usize = 1_000_000;
+/// Largest .blend preview read, Blender stores them at 256x256 or smaller
+const MAX_BLEND_THUMBNAIL: u64 = 4 * 1024 * 1024;
+
+type Triangle = [[f32; 3]; 3];
+
+pub fn is_supported(path: &Path) -> bool {
+    extension(path).is_some_and(|ext| matches!(ext.as_str(), "blend" | "glb" | "obj" | "stl"))
+}
+
+pub fn thumbnail(path: &Path, thumbnail_size: u32) -> Result<Option<RgbaImage>, String> {
+    match extension(path).as_deref() {
+        Some("blend") => blend_thumbnail(path),
+        Some("glb") => Ok(render(
+            &glb_triangles(&fs::read(path).map_err(err_str)?)?,
+            false,
+            thumbnail_size,
+        )),
+        Some("obj") => Ok(render(&obj_triangles(path)?, false, thumbnail_size)),
+        Some("stl") => Ok(render(
+            &stl_triangles(&fs::read(path).map_err(err_str)?)?,
+            true,
+            thumbnail_size,
+        )),
+        _ => Ok(None),
+    }
+}
+
+fn err_str<T: ToString>(err: T) -> String {
+    err.to_string()
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+}
+
+fn skip<R: Read>(reader: &mut R, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.by_ref().take(len), &mut io::sink())?;
+    if skipped == len {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+/// Read the preview stored in the TEST block of a .blend file
+fn blend_thumbnail(path: &Path) -> Result<Option<RgbaImage>, String> {
+    let mut file = BufReader::new(fs::File::open(path).map_err(err_str)?);
+    let magic = file.fill_buf().map_err(err_str)?;
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        // Compressed with gzip by Blender 2.x
+        blend_thumbnail_from(flate2::read::GzDecoder::new(file))
+    } else if magic.starts_with(b"BLENDER") {
+        blend_thumbnail_from(file)
+    } else {
+        // Files compressed with zstd are left to external thumbnailers
+        Ok(None)
+    }
+}
+
+fn blend_thumbnail_from<R: Read>(mut reader: R) -> Result<Option<RgbaImage>, String> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header).map_err(err_str)?;
+    if &header[..7] != b"BLENDER" {
+        return Err("invalid .blend header".to_string());
+    }
+
+    // Block headers are either the legacy layout with pointer sized addresses, or the large
+    // layout introduced with Blender 5.0, which has a header like BLENDER17-01v0500
+    let (large, pointer_size, little_endian) = match header[7] {
+        b'_' => (false, 4, header[8] == b'v'),
+        b'-' => (false, 8, header[8] == b'v'),
+        b'0'..=b'9' => {
+            let mut rest = [0; 5];
+            reader.read_exact(&mut rest).map_err(err_str)?;
+            // Header size and format version are followed by the endianness and version
+            (true, 8, rest[0] == b'v')
+        }
+        _ => return Err("invalid .blend pointer size".to_string()),
+    };
+    let read_i32 = |bytes: &[u8]| {
+        let bytes: [u8; 4] = bytes[..4].try_into().unwrap();
+        if little_endian {
+            i32::from_le_bytes(bytes)
+        } else {
+            i32::from_be_bytes(bytes)
+        }
+    };
+    let read_i64 = |bytes: &[u8]| {
+        let bytes: [u8; 8] = bytes[..8].try_into().unwrap();
+        if little_endian {
+            i64::from_le_bytes(bytes)
+        } else {
+            i64::from_be_bytes(bytes)
+        }
+    };
+
+    let block_header_size = if large { 32 } else { 16 + pointer_size };
+    let mut block_header = vec![0; block_header_size];
+    loop {
+        reader.read_exact(&mut block_header).map_err(err_str)?;
+        let code = &block_header[..4];
+        let len = if large {
+            read_i64(&block_header[16..])
+        } else {
+            read_i32(&block_header[4..]) as i64
+        };
+        let len = u64::try_from(len).map_err(|_| "invalid .blend block length".to_string())?;
+        match code {
+            b"TEST" => {
+                // The length comes from the file, so it is checked before allocating
+                if len > MAX_BLEND_THUMBNAIL {
+                    return Err("invalid .blend thumbnail length".to_string());
+                }
+                let mut data = vec![0; usize::try_from(len).map_err(err_str)?];
+                reader.read_exact(&mut data).map_err(err_str)?;
+                if data.len() < 8 {
+                    return Err("invalid .blend thumbnail".to_string());
+                }
+                let width = u32::try_from(read_i32(&data[0..])).map_err(err_str)?;
+                let height = u32::try_from(read_i32(&data[4..])).map_err(err_str)?;
+                let pixels = data.split_off(8);
+                let Some(mut image) = RgbaImage::from_raw(width, height, pixels) else {
+                    return Err("invalid .blend thumbnail size".to_string());
+                };
+                // Rows are stored bottom to top
+                image::imageops::flip_vertical_in_place(&mut image);
+                return Ok(Some(image));
+            }
+            // The thumbnail is always written before the scene data
+            b"ENDB" | b"DNA1" => return Ok(None),
+            _ => skip(&mut reader, len).map_err(err_str)?,
+        }
+    }
+}
+
+fn stl_triangles(data: &[u8]) -> Result<Vec<Triangle>, String> {
+    // Binary files have an 80 byte header, a triangle count, and 50 bytes per triangle
+    if data.len() >= 84 {
+        let count = u32::from_le_bytes(data[80..84].try_into().unwrap()) as usize;
+        if data.len() == 84 + count * 50 {
+            let read_vertex = |bytes: &[u8]| {
+                let mut vertex = [0.0; 3];
+                for (i, value) in vertex.iter_mut().enumerate() {
+                    *value = f32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+                }
+                vertex
+            };
+            return Ok(data[84..]
+                .chunks_exact(50)
+                .take(MAX_TRIANGLES)
+                .map(|chunk| {
+                    // Skip the normal, vertices follow
+                    [
+                        read_vertex(&chunk[12..]),
+                        read_vertex(&chunk[24..]),
+                        read_vertex(&chunk[36..]),
+                    ]
+                })
+                .collect());
+        }
+    }
+
+    let text = std::str::from_utf8(data).map_err(|_| "invalid STL file".to_string())?;
+    let mut vertices = Vec::new();
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        if parts.next() == Some("vertex") {
+            vertices.push(parse_vertex(parts)?);
+        }
+    }
+    Ok(vertices
+        .chunks_exact(3)
+        .take(MAX_TRIANGLES)
+        .map(|chunk| [chunk[0], chunk[1], chunk[2]])
+        .collect())
+}
+
+fn parse_vertex<'a>(mut parts: impl Iterator<Item = &'a str>) -> Result<[f32; 3], String> {
+    let mut vertex = [0.0; 3];
+    for value in vertex.iter_mut() {
+        *value = parts
+            .next()
+            .and_then(|part| part.parse().ok())
+            .ok_or_else(|| "invalid vertex".to_string())?;
+    }
+    Ok(vertex)
+}
+
+fn obj_triangles(path: &Path) -> Result<Vec<Triangle>, String> {
+    let file = BufReader::new(fs::File::open(path).map_err(err_str)?);
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+    for line_res in file.lines() {
+        let line = line_res.map_err(err_str)?;
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("v") => vertices.push(parse_vertex(parts)?),
+            Some("f") => {
+                // Indices start at one and may be negative to count from the end, and may be
+                // followed by texture and normal indices
+                let indices = parts
+                    .map(|part| {
+                        let index: isize = part
+                            .split('/')
+                            .next()
+                            .and_then(|index| index.parse().ok())
+                            .ok_or_else(|| format!("invalid face {:?}", line))?;
+                        let index = if index < 0 {
+                            vertices.len() as isize + index
+                        } else {
+                            index - 1
+                        };
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|index| vertices.get(index).copied())
+                            .ok_or_else(|| format!("invalid face {:?}", line))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Faces are convex polygons, so they are split into a fan
+                for i in 2..indices.len() {
+                    triangles.push([indices[0], indices[i - 1], indices[i]]);
+                }
+                if triangles.len() >= MAX_TRIANGLES {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(triangles)
+}
+
+fn glb_triangles(data: &[u8]) -> Result<Vec<Triangle>, String> {
+    const JSON_CHUNK: u32 = 0x4E4F534A;
+    const BIN_CHUNK: u32 = 0x004E4942;
+
+    let read_u32 = |offset: usize| -> Result<u32, String> {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or_else(|| "truncated glTF file".to_string())
+    };
+    if !data.starts_with(b"glTF") {
+        return Err("invalid glTF header".to_string());
+    }
+
+    let mut json_opt = None;
+    let mut bin: &[u8] = &[];
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let len = read_u32(offset)? as usize;
+        let kind = read_u32(offset + 4)?;
+        let chunk = data
+            .get(offset + 8..offset + 8 + len)
+            .ok_or_else(|| "truncated glTF chunk".to_string())?;
+        match kind {
+            JSON_CHUNK => json_opt = Some(chunk),
+            BIN_CHUNK => bin = chunk,
+            _ => {}
+        }
+        offset += 8 + len;
+    }
+    let json: serde_json::Value =
+        serde_json::from_slice(json_opt.ok_or_else(|| "missing glTF JSON chunk".to_string())?)
+            .map_err(err_str)?;
+
+    // Only data in the binary chunk is supported, external buffers are ignored
+    let accessor_bytes = |index: &serde_json::Value| -> Option<(&[u8], usize, u64, usize)> {
+        let accessor = &json["accessors"][index.as_u64()? as usize];
+        let view = &json["bufferViews"][accessor["bufferView"].as_u64()? as usize];
+        if view["buffer"].as_u64()? != 0 {
+            return None;
+        }
+        let start = view["byteOffset"].as_u64().unwrap_or(0) as usize
+            + accessor["byteOffset"].as_u64().unwrap_or(0) as usize;
+        let end = view["byteOffset"].as_u64().unwrap_or(0) as usize
+            + view["byteLength"].as_u64()? as usize;
+        let stride = view["byteStride"].as_u64().unwrap_or(0) as usize;
+        Some((
+            bin.get(start..end)?,
+            accessor["count"].as_u64()? as usize,
+            accessor["componentType"].as_u64()?,
+            stride,
+        ))
+    };
+
+    let mut triangles = Vec::new();
+    let meshes = json["meshes"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+    for mesh in meshes {
+        let primitives = mesh["primitives"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        for primitive in primitives {
+            // Only triangle lists are rendered
+            if primitive["mode"].as_u64().unwrap_or(4) != 4 {
+                continue;
+            }
+            //TODO: apply node transforms
+            let Some((bytes, count, 5126, stride)) =
+                accessor_bytes(&primitive["attributes"]["POSITION"])
+            else {
+                continue;
+            };
+            let stride = if stride == 0 { 12 } else { stride };
+            let vertices: Vec<[f32; 3]> = (0..count)
+                .filter_map(|i| {
+                    let bytes = bytes.get(i * stride..i * stride + 12)?;
+                    let mut vertex = [0.0; 3];
+                    for (j, value) in vertex.iter_mut().enumerate() {
+                        *value = f32::from_le_bytes(bytes[j * 4..j * 4 + 4].try_into().unwrap());
+                    }
+                    Some(vertex)
+                })
+                .collect();
+
+            let indices: Vec<usize> = match accessor_bytes(&primitive["indices"]) {
+                Some((bytes, count, component_type, _)) => {
+                    let size = match component_type {
+                        5121 => 1,
+                        5123 => 2,
+                        5125 => 4,
+                        _ => continue,
+                    };
+                    bytes
+                        .chunks_exact(size)
+                        .take(count)
+                        .map(|chunk| match chunk {
+                            [a] => *a as usize,
+                            [a, b] => u16::from_le_bytes([*a, *b]) as usize,
+                            _ => u32::from_le_bytes(chunk.try_into().unwrap()) as usize,
+                        })
+                        .collect()
+                }
+                None => (0..vertices.len()).collect(),
+            };
+            for chunk in indices.chunks_exact(3) {
+                if let (Some(a), Some(b), Some(c)) = (
+                    vertices.get(chunk[0]),
+                    vertices.get(chunk[1]),
+                    vertices.get(chunk[2]),
+                ) {
+                    triangles.push([*a, *b, *c]);
+                }
+            }
+            if triangles.len() >= MAX_TRIANGLES {
+                return Ok(triangles);
+            }
+        }
+    }
+    Ok(triangles)
+}
+
+/// Render triangles with flat shading from a fixed angle above and to the front right
+fn render(triangles: &[Triangle], z_up: bool, size: u32) -> Option<RgbaImage> {
+    if triangles.is_empty() || size == 0 {
+        return None;
+    }
+
+    let (yaw_sin, yaw_cos) = (-35.0f32).to_radians().sin_cos();
+    let (pitch_sin, pitch_cos) = 25.0f32.to_radians().sin_cos();
+    let transform = |[x, y, z]: [f32; 3]| {
+        // Convert to Y up
+        let [x, y, z] = if z_up { [x, z, -y] } else { [x, y, z] };
+        let (x, z) = (x * yaw_cos + z * yaw_sin, z * yaw_cos - x * yaw_sin);
+        let (y, z) = (y * pitch_cos - z * pitch_sin, y * pitch_sin + z * pitch_cos);
+        // Screen Y points down, and Z towards the viewer
+        [x, -y, z]
+    };
+    let projected: Vec<Triangle> = triangles
+        .iter()
+        .map(|triangle| triangle.map(transform))
+        .filter(|triangle| triangle.iter().flatten().all(|value| value.is_finite()))
+        .collect();
+
+    let mut min = [f32::MAX; 2];
+    let mut max = [f32::MIN; 2];
+    for vertex in projected.iter().flatten() {
+        min = [min[0].min(vertex[0]), min[1].min(vertex[1])];
+        max = [max[0].max(vertex[0]), max[1].max(vertex[1])];
+    }
+    let extent = (max[0] - min[0]).max(max[1] - min[1]);
+    if !extent.is_finite() || extent <= 0.0 {
+        return None;
+    }
+    let margin = size as f32 * 0.05;
+    let scale = (size as f32 - 2.0 * margin) / extent;
+    let offset = [
+        (size as f32 - (max[0] - min[0]) * scale) / 2.0,
+        (size as f32 - (max[1] - min[1]) * scale) / 2.0,
+    ];
+
+    let mut image = RgbaImage::new(size, size);
+    let mut depth = vec![f32::MIN; (size * size) as usize];
+    for triangle in projected.iter() {
+        let [a, b, c] = triangle.map(|[x, y, z]| {
+            [
+                (x - min[0]) * scale + offset[0],
+                (y - min[1]) * scale + offset[1],
+                z,
+            ]
+        });
+
+        // Shade by the angle between the face normal and the viewer, for both sides
+        let u = [b[0] - a[0], b[1] - a[1], (b[2] - a[2]) * scale];
+        let v = [c[0] - a[0], c[1] - a[1], (c[2] - a[2]) * scale];
+        let normal = [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+        if length == 0.0 || area == 0.0 {
+            continue;
+        }
+        let light = 0.3 + 0.7 * (normal[2] / length).abs();
+        let color = Rgba([
+            (160.0 * light) as u8,
+            (175.0 * light) as u8,
+            (190.0 * light) as u8,
+            255,
+        ]);
+
+        let x_min = a[0].min(b[0]).min(c[0]).floor().max(0.0) as u32;
+        let x_max = (a[0].max(b[0]).max(c[0]).ceil() as u32).min(size - 1);
+        let y_min = a[1].min(b[1]).min(c[1]).floor().max(0.0) as u32;
+        let y_max = (a[1].max(b[1]).max(c[1]).ceil() as u32).min(size - 1);
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                // Barycentric coordinates of the pixel center
+                let w0 = ((b[0] - px) * (c[1] - py) - (b[1] - py) * (c[0] - px)) / area;
+                let w1 = ((c[0] - px) * (a[1] - py) - (c[1] - py) * (a[0] - px)) / area;
+                let w2 = 1.0 - w0 - w1;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+                let z = w0 * a[2] + w1 * b[2] + w2 * c[2];
+                let i = (y * size + x) as usize;
+                if z > depth[i] {
+                    depth[i] = z;
+                    image.put_pixel(x, y, color);
+                }
+            }
+        }
+    }
+    Some(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blend_thumbnail_from, obj_triangles, render, stl_triangles};
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn blend_thumbnail_reads_test_block() {
+        let mut data = b"BLENDER-v300".to_vec();
+        // A block before the thumbnail is skipped
+        data.extend_from_slice(b"REND");
+        data.extend_from_slice(&4i32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        // A 1x2 thumbnail, stored bottom row first
+        data.extend_from_slice(b"TEST");
+        data.extend_from_slice(&16i32.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&1i32.to_le_bytes());
+        data.extend_from_slice(&2i32.to_le_bytes());
+        data.extend_from_slice(&[1, 1, 1, 255, 2, 2, 2, 255]);
+
+        let image = blend_thumbnail_from(data.as_slice()).unwrap().unwrap();
+        assert_eq!(image.dimensions(), (1, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [2, 2, 2, 255]);
+    }
+
+    #[test]
+    fn stl_triangles_reads_ascii_and_binary() {
+        let ascii = b"solid test\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\nendloop\nendfacet\nendsolid test\n";
+        assert_eq!(
+            stl_triangles(ascii).unwrap(),
+            [[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]]
+        );
+
+        let mut binary = vec![0; 80];
+        binary.extend_from_slice(&1u32.to_le_bytes());
+        for value in [
+            0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+        ] {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+        binary.extend_from_slice(&[0; 2]);
+        assert_eq!(
+            stl_triangles(&binary).unwrap(),
+            [[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]]
+        );
+    }
+
+    #[test]
+    fn obj_triangles_splits_polygons() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("quad.obj");
+        fs::write(
+            &path,
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1/1/1 2/2/1 3/3/1 -1/4/1\n",
+        )?;
+
+        let triangles = obj_triangles(&path).unwrap();
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[1][2], [0.0, 1.0, 0.0]);
+
+        let image = render(&triangles, false, 32).unwrap();
+        assert_eq!(image.dimensions(), (32, 32));
+        assert!(image.pixels().any(|pixel| pixel.0[3] == 255));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Processes keeping a mount busy, found like `lsof` does by looking at the working folder,
+//! executable and open files of every process that can be inspected.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockingProcess {
+    pub pid: i32,
+    pub name: String,
+    /// Files and folders on the mount used by the process, the working folder first
+    pub paths: Vec<PathBuf>,
+}
+
+/// Processes using files on the mount, sorted by name
+#[cfg(target_os = "linux")]
+pub fn blocking_processes(mount_point: &Path) -> Vec<BlockingProcess> {
+    let processes = match procfs::process::all_processes() {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to list processes: {}", err);
+            return Vec::new();
+        }
+    };
+    let mut blocking: Vec<_> = processes
+        .flatten()
+        .filter_map(|process| blocking_process(&process, mount_point))
+        .collect();
+    blocking.sort_by(|a, b| a.name.cmp(&b.name).then(a.pid.cmp(&b.pid)));
+    blocking
+}
+
+#[cfg(target_os = "linux")]
+fn blocking_process(
+    process: &procfs::process::Process,
+    mount_point: &Path,
+) -> Option<BlockingProcess> {
+    // Processes of other users cannot be inspected without privileges, so errors are skipped
+    let mut paths = Vec::new();
+    let mut push = |path: PathBuf| {
+        if path.starts_with(mount_point) && !paths.contains(&path) {
+            paths.push(path);
+        }
+    };
+    for path in [process.cwd(), process.root(), process.exe()]
+        .into_iter()
+        .flatten()
+    {
+        push(path);
+    }
+    if let Ok(fds) = process.fd() {
+        for fd in fds.flatten() {
+            if let procfs::process::FDTarget::Path(path) = fd.target {
+                push(path);
+            }
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+
+    let name = process
+        .stat()
+        .map_or_else(|_| process.pid.to_string(), |stat| stat.comm);
+    Some(BlockingProcess {
+        pid: process.pid,
+        name,
+        paths,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn blocking_processes(_mount_point: &Path) -> Vec<BlockingProcess> {
+    Vec::new()
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::blocking_processes;
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn blocking_processes_found() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("open.txt");
+        fs::write(&path, b"open")?;
+
+        let pid = std::process::id() as i32;
+        let is_blocking = || {
+            blocking_processes(dir.path())
+                .iter()
+                .any(|process| process.pid == pid && process.paths.contains(&path))
+        };
+        assert!(!is_blocking());
+        let file = fs::File::open(&path)?;
+        assert!(is_blocking());
+        drop(file);
+        assert!(!is_blocking());
+
+        Ok(())
+    }
+}
@@ -6,11 +6,16 @@ use gio::{glib, prelude::*};
 use std::{any::TypeId, cell::Cell, future::pending, path::PathBuf, sync::Arc};
 use tokio::sync::{mpsc, Mutex};
 
-use super::{Mounter, MounterAuth, MounterItem, MounterItems, MounterMessage};
+use super::{
+    Mounter, MounterAuth, MounterError, MounterErrorKind, MounterItem, MounterItems, MounterMessage,
+};
 use crate::{
     config::IconSizes,
     err_str,
-    tab::{self, DirSize, ItemMetadata, ItemThumbnail, Location},
+    tab::{
+        self, ArchiveEntries, AudioInfo, Checksums, DirSize, DocumentPreview, ImageInfo,
+        ItemMetadata, ItemThumbnail, Location,
+    },
 };
 
 fn gio_icon_to_path(icon: &gio::Icon, size: u16) -> Option<PathBuf> {
@@ -34,6 +39,7 @@ fn items(monitor: &gio::VolumeMonitor, sizes: IconSizes) -> MounterItems {
             index: i,
             name: MountExt::name(&mount).to_string(),
             is_mounted: true,
+            can_eject: MountExt::can_eject(&mount),
             icon_opt: gio_icon_to_path(&MountExt::icon(&mount), sizes.grid()),
             icon_symbolic_opt: gio_icon_to_path(&MountExt::symbolic_icon(&mount), 16),
             path_opt: MountExt::root(&mount).path(),
@@ -49,6 +55,7 @@ fn items(monitor: &gio::VolumeMonitor, sizes: IconSizes) -> MounterItems {
             index: i,
             name: VolumeExt::name(&volume).to_string(),
             is_mounted: false,
+            can_eject: VolumeExt::can_eject(&volume),
             icon_opt: gio_icon_to_path(&VolumeExt::icon(&volume), sizes.grid()),
             icon_symbolic_opt: gio_icon_to_path(&VolumeExt::symbolic_icon(&volume), 16),
             path_opt: None,
@@ -68,6 +75,18 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab::Item>, String> {
         let name = info.name().to_string_lossy().to_string();
         let display_name = info.display_name().to_string();
 
+        // Items on mounted shares like sftp are reachable through the gvfs FUSE daemon, which
+        // allows them to be opened, copied, renamed, and deleted like local items
+        if let Some(path) = file.child(info.name()).path().filter(|path| path.exists()) {
+            match tab::item_from_path(path, sizes) {
+                Ok(item) => {
+                    items.push(item);
+                    continue;
+                }
+                Err(err) => log::warn!("{}", err),
+            }
+        }
+
         //TODO: what is the best way to resolve shortcuts?
         let location = Location::Network(
             if let Some(target_uri) = info.attribute_string(gio::FILE_ATTRIBUTE_STANDARD_TARGET_URI)
@@ -79,8 +98,13 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab::Item>, String> {
             display_name.clone(),
         );
 
-        //TODO: support dir or file
-        let metadata = ItemMetadata::SimpleDir { entries: 0 };
+        let metadata = if info.file_type() == gio::FileType::Regular {
+            ItemMetadata::SimpleFile {
+                size: info.size() as u64,
+            }
+        } else {
+            ItemMetadata::SimpleDir { entries: 0 }
+        };
 
         let (mime, icon_handle_grid, icon_handle_list, icon_handle_list_condensed) = {
             let file_icon = |size| {
@@ -99,8 +123,14 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab::Item>, String> {
                     )
             };
             (
-                //TODO: get mime from content_type?
-                "inode/directory".parse().unwrap(),
+                if metadata.is_dir() {
+                    "inode/directory".parse().unwrap()
+                } else {
+                    info.content_type()
+                        .and_then(|content_type| gio::content_type_get_mime_type(&content_type))
+                        .and_then(|mime| mime.parse().ok())
+                        .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM)
+                },
                 file_icon(sizes.grid()),
                 file_icon(sizes.list()),
                 file_icon(sizes.list_condensed()),
@@ -126,17 +156,63 @@ fn network_scan(uri: &str, sizes: IconSizes) -> Result<Vec<tab::Item>, String> {
             overlaps_drag_rect: false,
             //TODO: scan directory size on gvfs mounts?
             dir_size: DirSize::NotDirectory,
+            archive_entries: ArchiveEntries::NotArchive,
+            audio_info: AudioInfo::NotAudio,
+            image_info: ImageInfo::NoMetadata,
+            document_preview: DocumentPreview::NotDocument,
+            fs_info_opt: None,
+            versions_opt: Some(Vec::new()),
+            attributes_opt: Some(Ok(Default::default())),
+            checksums: Checksums::NotCalculated,
             cut: false,
+            tags: Vec::new(),
+            emblems: Vec::new(),
+            depth: 0,
+            expanded: false,
         });
     }
     Ok(items)
 }
 
+fn reply(mount_op: &gio::MountOperation, auth: &MounterAuth) {
+    if auth.anonymous_opt == Some(true) {
+        mount_op.set_anonymous(true);
+    } else {
+        mount_op.set_username(auth.username_opt.as_deref());
+        mount_op.set_domain(auth.domain_opt.as_deref());
+        mount_op.set_password(auth.password_opt.as_deref());
+        if auth.remember_opt == Some(true) {
+            mount_op.set_password_save(gio::PasswordSave::Permanently);
+        }
+    }
+    mount_op.reply(gio::MountOperationResult::Handled);
+}
+
 fn mount_op(uri: String, event_tx: mpsc::UnboundedSender<Event>) -> gio::MountOperation {
+    // Passwords of SMB servers are remembered in the Secret Service keyring
+    #[cfg(feature = "smb")]
+    let smb_server_opt = super::smb::server(&uri);
+    // Set when remembered credentials were replied, which are forgotten if they are asked for again
+    #[cfg(feature = "smb")]
+    let replied_remembered = Cell::new(false);
+
     let mount_op = gio::MountOperation::new();
     mount_op.connect_ask_password(
         move |mount_op, message, default_user, default_domain, flags| {
-            let auth = MounterAuth {
+            #[cfg(feature = "smb")]
+            if let Some(server) = &smb_server_opt {
+                if replied_remembered.replace(false) {
+                    log::info!("remembered password for {} was refused", server);
+                    super::secrets::forget("smb", server);
+                } else if let Some(auth) = super::secrets::load("smb", server) {
+                    replied_remembered.set(true);
+                    reply(mount_op, &auth);
+                    return;
+                }
+            }
+
+            #[allow(unused_mut)]
+            let mut auth = MounterAuth {
                 message: message.to_string(),
                 username_opt: if flags.contains(gio::AskPasswordFlags::NEED_USERNAME) {
                     Some(default_user.to_string())
@@ -164,23 +240,35 @@ fn mount_op(uri: String, event_tx: mpsc::UnboundedSender<Event>) -> gio::MountOp
                     None
                 },
             };
+            #[cfg(feature = "smb")]
+            if smb_server_opt.is_some() && auth.password_opt.is_some() {
+                // Remembered here even if gvfs cannot save it
+                auth.remember_opt.get_or_insert(false);
+            }
+
             let (auth_tx, mut auth_rx) = mpsc::channel(1);
             event_tx
                 .send(Event::NetworkAuth(uri.clone(), auth, auth_tx))
                 .unwrap();
             //TODO: async recv?
             if let Some(auth) = auth_rx.blocking_recv() {
-                if auth.anonymous_opt == Some(true) {
-                    mount_op.set_anonymous(true);
-                } else {
-                    mount_op.set_username(auth.username_opt.as_deref());
-                    mount_op.set_domain(auth.domain_opt.as_deref());
-                    mount_op.set_password(auth.password_opt.as_deref());
-                    if auth.remember_opt == Some(true) {
-                        mount_op.set_password_save(gio::PasswordSave::Permanently);
+                #[cfg(feature = "smb")]
+                if let Some(server) = &smb_server_opt {
+                    if auth.anonymous_opt != Some(true) && auth.remember_opt == Some(true) {
+                        super::secrets::store("smb", server, &auth);
+                        replied_remembered.set(true);
+                        // Not saved a second time by gvfs
+                        reply(
+                            mount_op,
+                            &MounterAuth {
+                                remember_opt: Some(false),
+                                ..auth
+                            },
+                        );
+                        return;
                     }
                 }
-                mount_op.reply(gio::MountOperationResult::Handled);
+                reply(mount_op, &auth);
             } else {
                 mount_op.reply(gio::MountOperationResult::Aborted);
             }
@@ -189,7 +277,33 @@ fn mount_op(uri: String, event_tx: mpsc::UnboundedSender<Event>) -> gio::MountOp
     mount_op
 }
 
+fn mount_result(res: Result<(), glib::Error>) -> Result<bool, MounterError> {
+    let err = match res {
+        Ok(()) => return Ok(true),
+        Err(err) => err,
+    };
+    let kind = match err.kind::<gio::IOErrorEnum>() {
+        Some(gio::IOErrorEnum::FailedHandled | gio::IOErrorEnum::Cancelled) => return Ok(false),
+        Some(gio::IOErrorEnum::PermissionDenied) => MounterErrorKind::Auth,
+        Some(
+            gio::IOErrorEnum::HostNotFound
+            | gio::IOErrorEnum::HostUnreachable
+            | gio::IOErrorEnum::NetworkUnreachable
+            | gio::IOErrorEnum::ConnectionRefused
+            | gio::IOErrorEnum::ConnectionClosed,
+        ) => MounterErrorKind::Unreachable,
+        Some(gio::IOErrorEnum::TimedOut) => MounterErrorKind::Timeout,
+        Some(gio::IOErrorEnum::Busy) => MounterErrorKind::Busy,
+        _ => MounterErrorKind::Other,
+    };
+    Err(MounterError {
+        kind,
+        message: format!("{}", err),
+    })
+}
+
 enum Cmd {
+    Cancel,
     Items(IconSizes, mpsc::Sender<MounterItems>),
     Rescan,
     Mount(MounterItem),
@@ -199,15 +313,17 @@ enum Cmd {
         IconSizes,
         mpsc::Sender<Result<Vec<tab::Item>, String>>,
     ),
-    Unmount(MounterItem),
+    NetworkPath(String, mpsc::Sender<Option<PathBuf>>),
+    Unmount(MounterItem, bool),
 }
 
 enum Event {
     Changed,
     Items(MounterItems),
-    MountResult(MounterItem, Result<bool, String>),
+    MountResult(MounterItem, Result<bool, MounterError>),
     NetworkAuth(String, MounterAuth, mpsc::Sender<MounterAuth>),
-    NetworkResult(String, Result<bool, String>),
+    NetworkResult(String, Result<bool, MounterError>),
+    UnmountResult(MounterItem, Result<bool, MounterError>),
 }
 
 #[derive(Clone, Debug)]
@@ -223,6 +339,7 @@ pub struct Item {
     index: usize,
     name: String,
     is_mounted: bool,
+    can_eject: bool,
     icon_opt: Option<PathBuf>,
     icon_symbolic_opt: Option<PathBuf>,
     path_opt: Option<PathBuf>,
@@ -237,6 +354,10 @@ impl Item {
         self.is_mounted
     }
 
+    pub fn can_eject(&self) -> bool {
+        self.can_eject
+    }
+
     pub fn icon(&self, symbolic: bool) -> Option<widget::icon::Handle> {
         if symbolic {
             self.icon_symbolic_opt.as_ref()
@@ -309,8 +430,14 @@ impl Gvfs {
                     });
                 }
 
+                // Shared by all mounts in progress, replaced after being cancelled
+                let mut cancellable = gio::Cancellable::new();
                 while let Some(command) = command_rx.recv().await {
                     match command {
+                        Cmd::Cancel => {
+                            cancellable.cancel();
+                            cancellable = gio::Cancellable::new();
+                        }
                         Cmd::Items(sizes, items_tx) => {
                             items_tx.send(items(&monitor, sizes)).await.unwrap();
                         }
@@ -340,16 +467,10 @@ impl Gvfs {
                                     &volume,
                                     gio::MountMountFlags::NONE,
                                     Some(&mount_op),
-                                    gio::Cancellable::NONE,
+                                    Some(&cancellable),
                                     move |res| {
                                         log::info!("mount {}: result {:?}", name, res);
-                                        event_tx.send(Event::MountResult(mounter_item, match res {
-                                            Ok(()) => Ok(true),
-                                            Err(err) => match err.kind::<gio::IOErrorEnum>() {
-                                                Some(gio::IOErrorEnum::FailedHandled) => Ok(false),
-                                                _ => Err(format!("{}", err))
-                                            }
-                                        })).unwrap();
+                                        event_tx.send(Event::MountResult(mounter_item, mount_result(res))).unwrap();
                                     },
                                 );
                             }
@@ -361,16 +482,10 @@ impl Gvfs {
                             file.mount_enclosing_volume(
                                 gio::MountMountFlags::NONE,
                                 Some(&mount_op),
-                                gio::Cancellable::NONE,
+                                Some(&cancellable),
                                 move |res| {
                                     log::info!("network drive {}: result {:?}", uri, res);
-                                    event_tx.send(Event::NetworkResult(uri, match res {
-                                        Ok(()) => Ok(true),
-                                        Err(err) => match err.kind::<gio::IOErrorEnum>() {
-                                            Some(gio::IOErrorEnum::FailedHandled) => Ok(false),
-                                            _ => Err(format!("{}", err))
-                                        }
-                                    })).unwrap();
+                                    event_tx.send(Event::NetworkResult(uri, mount_result(res))).unwrap();
                                 }
                             );
                         }
@@ -386,27 +501,23 @@ impl Gvfs {
                                 file.mount_enclosing_volume(
                                     gio::MountMountFlags::empty(),
                                     Some(&mount_op),
-                                    gio::Cancellable::NONE,
+                                    Some(&cancellable),
                                     move |res| {
                                         log::info!("network scan mounted {}: result {:?}", uri, res);
                                         items_tx.blocking_send(network_scan(&uri, sizes)).unwrap();
-                                        event_tx.send(Event::NetworkResult(uri, match res {
-                                            Ok(()) => {
-                                                Ok(true)
-                                            },
-                                            Err(err) => match err.kind::<gio::IOErrorEnum>() {
-                                                Some(gio::IOErrorEnum::FailedHandled) => Ok(false),
-                                                _ => Err(format!("{}", err))
-                                            }
-                                        })).unwrap();
+                                        event_tx.send(Event::NetworkResult(uri, mount_result(res))).unwrap();
                                     }
                                 );
                             } else {
                                 items_tx.send(network_scan(&uri, sizes)).await.unwrap();
                             }
                         }
-                        Cmd::Unmount(mounter_item) => {
-                            let MounterItem::Gvfs(item) = mounter_item else { continue };
+                        Cmd::NetworkPath(uri, path_tx) => {
+                            let path_opt = gio::File::for_uri(&uri).path().filter(|path| path.exists());
+                            path_tx.send(path_opt).await.unwrap();
+                        }
+                        Cmd::Unmount(mounter_item, force) => {
+                            let MounterItem::Gvfs(ref item) = mounter_item else { continue };
                             let ItemKind::Mount = item.kind else { continue };
                             for (i, mount) in monitor.mounts().into_iter().enumerate() {
                                 if i != item.index {
@@ -419,26 +530,35 @@ impl Gvfs {
                                     continue;
                                 }
 
+                                let flags = if force {
+                                    gio::MountUnmountFlags::FORCE
+                                } else {
+                                    gio::MountUnmountFlags::NONE
+                                };
+                                let event_tx = event_tx.clone();
+                                let mounter_item = mounter_item.clone();
                                 if MountExt::can_eject(&mount) {
                                     log::info!("eject {}", name);
                                     MountExt::eject_with_operation(
                                         &mount,
-                                        gio::MountUnmountFlags::NONE,
+                                        flags,
                                         gio::MountOperation::NONE,
                                         gio::Cancellable::NONE,
                                         move |result| {
                                             log::info!("eject {}: result {:?}", name, result);
+                                            event_tx.send(Event::UnmountResult(mounter_item, mount_result(result))).unwrap();
                                         },
                                     );
                                 } else {
                                     log::info!("unmount {}", name);
                                     MountExt::unmount_with_operation(
                                         &mount,
-                                        gio::MountUnmountFlags::NONE,
+                                        flags,
                                         gio::MountOperation::NONE,
                                         gio::Cancellable::NONE,
                                         move |result| {
                                             log::info!("unmount {}: result {:?}", name, result);
+                                            event_tx.send(Event::UnmountResult(mounter_item, mount_result(result))).unwrap();
                                         },
                                     );
                                 }
@@ -474,6 +594,10 @@ impl Mounter for Gvfs {
     }
 
     fn network_drive(&self, uri: String) -> Task<()> {
+        #[cfg(feature = "sftp")]
+        if super::sftp::handles(&uri) {
+            return Task::none();
+        }
         let command_tx = self.command_tx.clone();
         Task::perform(
             async move {
@@ -484,6 +608,11 @@ impl Mounter for Gvfs {
     }
 
     fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab::Item>, String>> {
+        // Browsed with the native client instead of a gvfs mount
+        #[cfg(feature = "sftp")]
+        if super::sftp::handles(uri) {
+            return None;
+        }
         let (items_tx, mut items_rx) = mpsc::channel(1);
         self.command_tx
             .send(Cmd::NetworkScan(uri.to_string(), sizes, items_tx))
@@ -491,11 +620,33 @@ impl Mounter for Gvfs {
         items_rx.blocking_recv()
     }
 
-    fn unmount(&self, item: MounterItem) -> Task<()> {
+    fn network_path(&self, uri: &str) -> Option<PathBuf> {
+        #[cfg(feature = "sftp")]
+        if super::sftp::handles(uri) {
+            return None;
+        }
+        let (path_tx, mut path_rx) = mpsc::channel(1);
+        self.command_tx
+            .send(Cmd::NetworkPath(uri.to_string(), path_tx))
+            .unwrap();
+        path_rx.blocking_recv().flatten()
+    }
+
+    fn unmount(&self, item: MounterItem, force: bool) -> Task<()> {
+        let command_tx = self.command_tx.clone();
+        Task::perform(
+            async move {
+                command_tx.send(Cmd::Unmount(item, force)).unwrap();
+            },
+            |x| x,
+        )
+    }
+
+    fn cancel(&self) -> Task<()> {
         let command_tx = self.command_tx.clone();
         Task::perform(
             async move {
-                command_tx.send(Cmd::Unmount(item)).unwrap();
+                command_tx.send(Cmd::Cancel).unwrap();
             },
             |x| x,
         )
@@ -526,6 +677,10 @@ impl Mounter for Gvfs {
                             .send(MounterMessage::NetworkResult(uri, res))
                             .await
                             .unwrap(),
+                        Event::UnmountResult(item, res) => output
+                            .send(MounterMessage::UnmountResult(item, res))
+                            .await
+                            .unwrap(),
                     }
                 }
                 pending().await
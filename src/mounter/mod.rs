@@ -1,12 +1,27 @@
 use cosmic::{iced::Subscription, widget, Task};
 use once_cell::sync::Lazy;
-use std::{collections::BTreeMap, fmt, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Read, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 use tokio::sync::mpsc;
 
 use crate::{config::IconSizes, tab};
 
+mod busy;
 #[cfg(feature = "gvfs")]
 mod gvfs;
+#[cfg(any(feature = "smb", feature = "sftp"))]
+mod secrets;
+#[cfg(feature = "sftp")]
+mod sftp;
+#[cfg(feature = "smb")]
+mod smb;
+
+pub use busy::{blocking_processes, BlockingProcess};
 
 #[derive(Clone)]
 pub struct MounterAuth {
@@ -63,6 +78,15 @@ impl MounterItem {
         }
     }
 
+    /// Whether the drive can be ejected, unmounting it also ejects it
+    pub fn can_eject(&self) -> bool {
+        match self {
+            #[cfg(feature = "gvfs")]
+            Self::Gvfs(item) => item.can_eject(),
+            Self::None => unreachable!(),
+        }
+    }
+
     pub fn icon(&self, symbolic: bool) -> Option<widget::icon::Handle> {
         match self {
             #[cfg(feature = "gvfs")]
@@ -82,12 +106,46 @@ impl MounterItem {
 
 pub type MounterItems = Vec<MounterItem>;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MounterErrorKind {
+    Auth,
+    Unreachable,
+    Timeout,
+    /// Files on the mount are still open
+    Busy,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct MounterError {
+    pub kind: MounterErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for MounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum MounterMessage {
     Items(MounterItems),
-    MountResult(MounterItem, Result<bool, String>),
+    MountResult(MounterItem, Result<bool, MounterError>),
     NetworkAuth(String, MounterAuth, mpsc::Sender<MounterAuth>),
-    NetworkResult(String, Result<bool, String>),
+    NetworkResult(String, Result<bool, MounterError>),
+    /// Listings of these network locations changed, like the servers found on the network
+    NetworkChanged(Vec<String>),
+    UnmountResult(MounterItem, Result<bool, MounterError>),
+}
+
+/// Item in a network folder whose files a mounter reads and changes itself
+#[derive(Clone, Debug)]
+pub struct NetworkEntry {
+    pub name: String,
+    pub uri: String,
+    pub is_dir: bool,
+    pub size: u64,
 }
 
 pub trait Mounter: Send + Sync {
@@ -96,8 +154,42 @@ pub trait Mounter: Send + Sync {
     fn mount(&self, item: MounterItem) -> Task<()>;
     fn network_drive(&self, uri: String) -> Task<()>;
     fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab::Item>, String>>;
-    fn unmount(&self, item: MounterItem) -> Task<()>;
+    /// Local path of a mounted network location, if the mounter exposes one
+    fn network_path(&self, uri: &str) -> Option<PathBuf>;
+    /// Forcing unmounts even if files on the mount are still open
+    fn unmount(&self, item: MounterItem, force: bool) -> Task<()>;
+    /// Cancel any mounts that are still in progress
+    fn cancel(&self) -> Task<()>;
     fn subscription(&self) -> Subscription<MounterMessage>;
+
+    /// Whether the mounter reads and changes the files of a network location itself with the
+    /// `network_*` file methods, as they have no local path
+    fn network_files(&self, _uri: &str) -> bool {
+        false
+    }
+    fn network_metadata(&self, _uri: &str) -> Option<io::Result<NetworkEntry>> {
+        None
+    }
+    fn network_read_dir(&self, _uri: &str) -> Option<io::Result<Vec<NetworkEntry>>> {
+        None
+    }
+    fn network_open(&self, _uri: &str) -> Option<io::Result<Box<dyn Read + Send>>> {
+        None
+    }
+    /// Create a file, replacing one that exists
+    fn network_create(&self, _uri: &str) -> Option<io::Result<Box<dyn Write + Send>>> {
+        None
+    }
+    fn network_create_dir(&self, _uri: &str) -> Option<io::Result<()>> {
+        None
+    }
+    fn network_rename(&self, _from: &str, _to: &str) -> Option<io::Result<()>> {
+        None
+    }
+    /// Remove a file or an empty folder
+    fn network_remove(&self, _uri: &str) -> Option<io::Result<()>> {
+        None
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -114,7 +206,64 @@ pub fn mounters() -> Mounters {
         mounters.insert(MounterKey("gvfs"), Box::new(gvfs::Gvfs::new()));
     }
 
+    #[cfg(feature = "sftp")]
+    {
+        mounters.insert(MounterKey("sftp"), Box::new(sftp::Sftp::new()));
+    }
+
+    #[cfg(feature = "smb")]
+    {
+        mounters.insert(MounterKey("smb"), Box::new(smb::Smb::new()));
+    }
+
     Mounters::new(mounters)
 }
 
 pub static MOUNTERS: Lazy<Mounters> = Lazy::new(mounters);
+
+/// Mounter that reads and changes the files of a network location itself
+pub fn network_files(uri: &str) -> Option<MounterKey> {
+    MOUNTERS
+        .iter()
+        .find_map(|(key, mounter)| mounter.network_files(uri).then_some(*key))
+}
+
+fn network_file<T>(uri: &str, f: impl Fn(&dyn Mounter) -> Option<io::Result<T>>) -> io::Result<T> {
+    MOUNTERS
+        .values()
+        .find_map(|mounter| f(mounter.as_ref()))
+        .unwrap_or_else(|| {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("no mounter can access {}", uri),
+            ))
+        })
+}
+
+pub fn network_metadata(uri: &str) -> io::Result<NetworkEntry> {
+    network_file(uri, |mounter| mounter.network_metadata(uri))
+}
+
+pub fn network_read_dir(uri: &str) -> io::Result<Vec<NetworkEntry>> {
+    network_file(uri, |mounter| mounter.network_read_dir(uri))
+}
+
+pub fn network_open(uri: &str) -> io::Result<Box<dyn Read + Send>> {
+    network_file(uri, |mounter| mounter.network_open(uri))
+}
+
+pub fn network_create(uri: &str) -> io::Result<Box<dyn Write + Send>> {
+    network_file(uri, |mounter| mounter.network_create(uri))
+}
+
+pub fn network_create_dir(uri: &str) -> io::Result<()> {
+    network_file(uri, |mounter| mounter.network_create_dir(uri))
+}
+
+pub fn network_rename(from: &str, to: &str) -> io::Result<()> {
+    network_file(from, |mounter| mounter.network_rename(from, to))
+}
+
+pub fn network_remove(uri: &str) -> io::Result<()> {
+    network_file(uri, |mounter| mounter.network_remove(uri))
+}
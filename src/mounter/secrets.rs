@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Passwords of network servers kept in the Secret Service keyring. They use the network password
+//! schema of other file managers, so that passwords they saved are found too.
+
+use secret_service::{blocking::SecretService, EncryptionType};
+use std::collections::HashMap;
+
+use super::MounterAuth;
+use crate::err_str;
+
+const SCHEMA: &str = "org.gnome.keyring.NetworkPassword";
+
+fn attributes<'a>(protocol: &'a str, server: &'a str) -> HashMap<&'a str, &'a str> {
+    HashMap::from([
+        ("xdg:schema", SCHEMA),
+        ("protocol", protocol),
+        ("server", server),
+    ])
+}
+
+/// Credentials remembered for a server, if the keyring has any
+pub fn load(protocol: &str, server: &str) -> Option<MounterAuth> {
+    match try_load(protocol, server) {
+        Ok(auth_opt) => auth_opt,
+        Err(err) => {
+            log::warn!(
+                "failed to load password for {}://{}: {}",
+                protocol,
+                server,
+                err
+            );
+            None
+        }
+    }
+}
+
+fn try_load(protocol: &str, server: &str) -> Result<Option<MounterAuth>, String> {
+    let service = SecretService::connect(EncryptionType::Dh).map_err(err_str)?;
+    let results = service
+        .search_items(attributes(protocol, server))
+        .map_err(err_str)?;
+    let Some(item) = results.unlocked.first().or(results.locked.first()) else {
+        return Ok(None);
+    };
+    item.ensure_unlocked().map_err(err_str)?;
+    let item_attributes = item.get_attributes().map_err(err_str)?;
+    let password = String::from_utf8(item.get_secret().map_err(err_str)?).map_err(err_str)?;
+    Ok(Some(MounterAuth {
+        message: String::new(),
+        username_opt: item_attributes.get("user").cloned(),
+        domain_opt: item_attributes.get("domain").cloned(),
+        password_opt: Some(password),
+        // Already remembered
+        remember_opt: None,
+        anonymous_opt: None,
+    }))
+}
+
+/// Remember the credentials for a server, replacing those remembered before
+pub fn store(protocol: &str, server: &str, auth: &MounterAuth) {
+    if let Err(err) = try_store(protocol, server, auth) {
+        log::warn!(
+            "failed to store password for {}://{}: {}",
+            protocol,
+            server,
+            err
+        );
+    }
+}
+
+fn try_store(protocol: &str, server: &str, auth: &MounterAuth) -> Result<(), String> {
+    try_forget(protocol, server)?;
+
+    let service = SecretService::connect(EncryptionType::Dh).map_err(err_str)?;
+    let collection = service.get_default_collection().map_err(err_str)?;
+    collection.ensure_unlocked().map_err(err_str)?;
+
+    let mut item_attributes = attributes(protocol, server);
+    let username_opt = auth.username_opt.as_deref().filter(|x| !x.is_empty());
+    if let Some(username) = username_opt {
+        item_attributes.insert("user", username);
+    }
+    if let Some(domain) = auth.domain_opt.as_deref().filter(|x| !x.is_empty()) {
+        item_attributes.insert("domain", domain);
+    }
+    let label = match username_opt {
+        Some(username) => format!("{}://{}@{}", protocol, username, server),
+        None => format!("{}://{}", protocol, server),
+    };
+    collection
+        .create_item(
+            &label,
+            item_attributes,
+            auth.password_opt.as_deref().unwrap_or_default().as_bytes(),
+            true,
+            "text/plain",
+        )
+        .map_err(err_str)?;
+    Ok(())
+}
+
+/// Forget the credentials of a server, after they were refused
+pub fn forget(protocol: &str, server: &str) {
+    if let Err(err) = try_forget(protocol, server) {
+        log::warn!(
+            "failed to forget password for {}://{}: {}",
+            protocol,
+            server,
+            err
+        );
+    }
+}
+
+fn try_forget(protocol: &str, server: &str) -> Result<(), String> {
+    let service = SecretService::connect(EncryptionType::Dh).map_err(err_str)?;
+    let results = service
+        .search_items(attributes(protocol, server))
+        .map_err(err_str)?;
+    for item in results.unlocked.iter().chain(results.locked.iter()) {
+        item.delete().map_err(err_str)?;
+    }
+    Ok(())
+}
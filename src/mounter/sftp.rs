@@ -0,0 +1,669 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Native sftp client on libssh2, which browses `sftp://` and `ssh://` locations and reads and
+//! changes their files without a gvfs mount. Servers are authenticated with the ssh agent, the
+//! default keys in `~/.ssh`, or a password that can be remembered in the Secret Service keyring.
+//! Unknown host keys are confirmed before they are added to `~/.ssh/known_hosts`.
+
+use base64::Engine;
+use cosmic::{
+    iced::{futures::SinkExt, stream, Subscription},
+    Task,
+};
+use ssh2::{CheckResult, ErrorCode, HashType, KnownHostFileKind, OpenFlags, OpenType, Session};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    env, fmt,
+    fs::{self, OpenOptions},
+    future::pending,
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{mpsc, Mutex as TokioMutex};
+use url::{Host, Url};
+
+use super::{
+    secrets, Mounter, MounterAuth, MounterError, MounterErrorKind, MounterItem, MounterItems,
+    MounterMessage, NetworkEntry,
+};
+use crate::{
+    config::IconSizes,
+    fl,
+    tab::{self, ItemMetadata, Location},
+};
+
+const SCHEMES: [&str; 2] = ["sftp", "ssh"];
+/// Protocol that passwords are remembered for in the keyring, like gvfs does for both schemes
+const PROTOCOL: &str = "sftp";
+const DEFAULT_PORT: u16 = 22;
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// Seconds between keepalives, which also find connections that the server closed
+const KEEPALIVE_INTERVAL: u32 = 15;
+/// Keys tried after those of the ssh agent, in the order ssh tries them
+const KEY_FILES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+/// Passwords asked for before giving up, like ssh
+const PASSWORD_ATTEMPTS: usize = 3;
+/// `LIBSSH2_ERROR_AUTHENTICATION_FAILED`, when a password or key was refused
+const AUTHENTICATION_FAILED: ErrorCode = ErrorCode::Session(-18);
+
+/// Whether a URI is browsed with this client instead of gvfs
+pub fn handles(uri: &str) -> bool {
+    Url::parse(uri).is_ok_and(|url| SCHEMES.contains(&url.scheme()))
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct Server {
+    user: String,
+    host: String,
+    port: u16,
+}
+
+impl Server {
+    fn from_url(url: &Url) -> Option<Self> {
+        if !SCHEMES.contains(&url.scheme()) {
+            return None;
+        }
+        let host = match url.host()? {
+            Host::Domain(domain) if !domain.is_empty() => domain.to_lowercase(),
+            Host::Domain(_) => return None,
+            Host::Ipv4(addr) => addr.to_string(),
+            Host::Ipv6(addr) => addr.to_string(),
+        };
+        // Like ssh, the local user name is used if the URI has none
+        let user = if url.username().is_empty() {
+            env::var("USER").ok()?
+        } else {
+            percent_encoding::percent_decode_str(url.username())
+                .decode_utf8_lossy()
+                .into_owned()
+        };
+        Some(Self {
+            user,
+            host,
+            port: url.port().unwrap_or(DEFAULT_PORT),
+        })
+    }
+
+    /// Host as it is written in `known_hosts` and the keyring
+    fn host_port(&self) -> String {
+        if self.port == DEFAULT_PORT {
+            self.host.clone()
+        } else {
+            format!("[{}]:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.user, self.host_port())
+    }
+}
+
+struct Connection {
+    session: Session,
+    sftp: ssh2::Sftp,
+}
+
+type Connections = Arc<Mutex<HashMap<Server, Arc<Connection>>>>;
+
+fn error(kind: MounterErrorKind, err: impl fmt::Display) -> MounterError {
+    MounterError {
+        kind,
+        message: err.to_string(),
+    }
+}
+
+fn io_error(err: MounterError) -> io::Error {
+    io::Error::other(err.message)
+}
+
+fn cancelled() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, fl!("cancelled"))
+}
+
+/// Path on the server of a URI, which is absolute like those of gvfs
+fn remote_path(url: &Url) -> PathBuf {
+    let path = percent_encoding::percent_decode_str(url.path()).decode_utf8_lossy();
+    if path.is_empty() {
+        PathBuf::from("/")
+    } else {
+        PathBuf::from(path.as_ref())
+    }
+}
+
+fn child_uri(url: &Url, name: &str) -> String {
+    let mut child = url.clone();
+    if let Ok(mut segments) = child.path_segments_mut() {
+        segments.pop_if_empty().push(name);
+    }
+    child.to_string()
+}
+
+/// Ask with the network authentication dialog, returning `None` if it was cancelled
+fn ask(
+    message_tx: &mpsc::UnboundedSender<MounterMessage>,
+    uri: &str,
+    auth: MounterAuth,
+) -> Option<MounterAuth> {
+    let (auth_tx, mut auth_rx) = mpsc::channel(1);
+    message_tx
+        .send(MounterMessage::NetworkAuth(uri.to_string(), auth, auth_tx))
+        .ok()?;
+    auth_rx.blocking_recv()
+}
+
+/// Check the host key against `~/.ssh/known_hosts`, asking to trust keys that are not known yet.
+/// Returns false if that was cancelled.
+fn verify_host(
+    session: &Session,
+    server: &Server,
+    uri: &str,
+    message_tx: &mpsc::UnboundedSender<MounterMessage>,
+) -> Result<bool, MounterError> {
+    let Some((key, key_type)) = session.host_key() else {
+        return Err(error(MounterErrorKind::Other, "server sent no host key"));
+    };
+    let mut known_hosts = session
+        .known_hosts()
+        .map_err(|err| error(MounterErrorKind::Other, err))?;
+    let path_opt = dirs::home_dir().map(|home| home.join(".ssh").join("known_hosts"));
+    if let Some(path) = path_opt.as_ref().filter(|path| path.exists()) {
+        // Lines that libssh2 cannot parse are skipped by ssh as well
+        if let Err(err) = known_hosts.read_file(path, KnownHostFileKind::OpenSSH) {
+            log::warn!("failed to read {:?}: {}", path, err);
+        }
+    }
+    match known_hosts.check_port(&server.host, server.port, key) {
+        CheckResult::Match => return Ok(true),
+        CheckResult::Mismatch => {
+            return Err(error(
+                MounterErrorKind::Other,
+                fl!("sftp-host-key-changed", server = server.host_port()),
+            ));
+        }
+        CheckResult::NotFound | CheckResult::Failure => {}
+    }
+
+    let fingerprint = session
+        .host_key_hash(HashType::Sha256)
+        .map(|hash| base64::engine::general_purpose::STANDARD_NO_PAD.encode(hash))
+        .unwrap_or_default();
+    let auth = MounterAuth {
+        message: format!(
+            "{}\n{}",
+            fl!("sftp-host-key-unknown", server = server.host_port()),
+            fl!(
+                "sftp-host-key-description",
+                fingerprint = format!("SHA256:{}", fingerprint)
+            )
+        ),
+        username_opt: None,
+        domain_opt: None,
+        password_opt: None,
+        remember_opt: None,
+        anonymous_opt: None,
+    };
+    if ask(message_tx, uri, auth).is_none() {
+        return Ok(false);
+    }
+
+    // Trusted keys are appended like ssh does, leaving the other lines as they are
+    if let Some(path) = path_opt {
+        let res = known_hosts
+            .add(&server.host_port(), key, "", key_type.into())
+            .and_then(|()| known_hosts.hosts())
+            .map_err(io::Error::from)
+            .and_then(|hosts| {
+                let host_port = server.host_port();
+                let Some(host) = hosts
+                    .iter()
+                    .find(|host| host.name() == Some(host_port.as_str()))
+                else {
+                    return Err(io::Error::other("added host key not found"));
+                };
+                let line = known_hosts.write_string(host, KnownHostFileKind::OpenSSH)?;
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+                writeln!(file, "{}", line.trim_end())
+            });
+        if let Err(err) = res {
+            log::warn!(
+                "failed to add host key of {} to {:?}: {}",
+                server,
+                path,
+                err
+            );
+        }
+    }
+    Ok(true)
+}
+
+/// Authenticate with the ssh agent, the default keys, or a password. Returns false if entering
+/// the password was cancelled.
+fn authenticate(
+    session: &Session,
+    server: &Server,
+    uri: &str,
+    message_tx: &mpsc::UnboundedSender<MounterMessage>,
+) -> Result<bool, MounterError> {
+    if let Err(err) = session.userauth_agent(&server.user) {
+        log::info!("ssh agent did not authenticate {}: {}", server, err);
+    }
+    if !session.authenticated() {
+        if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+            for name in KEY_FILES {
+                let path = ssh_dir.join(name);
+                if !path.exists() {
+                    continue;
+                }
+                match session.userauth_pubkey_file(&server.user, None, &path, None) {
+                    Ok(()) => break,
+                    Err(err) => log::info!("{:?} did not authenticate {}: {}", path, server, err),
+                }
+            }
+        }
+    }
+    if session.authenticated() {
+        return Ok(true);
+    }
+
+    let keyring_server = server.host_port();
+    if let Some(auth) = secrets::load(PROTOCOL, &keyring_server)
+        .filter(|auth| auth.username_opt.as_deref() == Some(server.user.as_str()))
+    {
+        match session.userauth_password(
+            &server.user,
+            auth.password_opt.as_deref().unwrap_or_default(),
+        ) {
+            Ok(()) => return Ok(true),
+            Err(err) => {
+                log::info!("remembered password for {} was refused: {}", server, err);
+                secrets::forget(PROTOCOL, &keyring_server);
+            }
+        }
+    }
+
+    let title = fl!("sftp-password", server = server.to_string());
+    let mut message = title.clone();
+    for _ in 0..PASSWORD_ATTEMPTS {
+        let auth = MounterAuth {
+            message: message.clone(),
+            username_opt: None,
+            domain_opt: None,
+            password_opt: Some(String::new()),
+            remember_opt: Some(false),
+            anonymous_opt: None,
+        };
+        let Some(auth) = ask(message_tx, uri, auth) else {
+            return Ok(false);
+        };
+        match session.userauth_password(
+            &server.user,
+            auth.password_opt.as_deref().unwrap_or_default(),
+        ) {
+            Ok(()) => {
+                if auth.remember_opt == Some(true) {
+                    secrets::store(
+                        PROTOCOL,
+                        &keyring_server,
+                        &MounterAuth {
+                            username_opt: Some(server.user.clone()),
+                            ..auth
+                        },
+                    );
+                }
+                return Ok(true);
+            }
+            Err(err) if err.code() == AUTHENTICATION_FAILED => {
+                log::info!("password for {} was refused: {}", server, err);
+                message = format!("{}\n{}", title, fl!("sftp-password-refused"));
+            }
+            Err(err) => return Err(error(MounterErrorKind::Other, err)),
+        }
+    }
+    Err(error(MounterErrorKind::Auth, fl!("sftp-password-refused")))
+}
+
+fn connect(
+    server: &Server,
+    uri: &str,
+    message_tx: &mpsc::UnboundedSender<MounterMessage>,
+) -> Result<Option<Connection>, MounterError> {
+    let unreachable = |err: io::Error| {
+        let kind = match err.kind() {
+            io::ErrorKind::TimedOut => MounterErrorKind::Timeout,
+            _ => MounterErrorKind::Unreachable,
+        };
+        error(kind, err)
+    };
+    let mut tcp_res = Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no address found for {}", server.host),
+    ));
+    for addr in (server.host.as_str(), server.port)
+        .to_socket_addrs()
+        .map_err(unreachable)?
+    {
+        tcp_res = TcpStream::connect_timeout(&addr, TIMEOUT);
+        if tcp_res.is_ok() {
+            break;
+        }
+    }
+    let tcp = tcp_res.map_err(unreachable)?;
+
+    let mut session = Session::new().map_err(|err| error(MounterErrorKind::Other, err))?;
+    session.set_timeout(TIMEOUT.as_millis() as u32);
+    session.set_tcp_stream(tcp);
+    session
+        .handshake()
+        .map_err(|err| error(MounterErrorKind::Unreachable, err))?;
+    session.set_keepalive(false, KEEPALIVE_INTERVAL);
+    if !verify_host(&session, server, uri, message_tx)?
+        || !authenticate(&session, server, uri, message_tx)?
+    {
+        return Ok(None);
+    }
+    let sftp = session
+        .sftp()
+        .map_err(|err| error(MounterErrorKind::Other, err))?;
+    log::info!("connected to {}", server);
+    Ok(Some(Connection { session, sftp }))
+}
+
+/// Connection to the server of a URI, connecting if there is none yet. Returns `None` if
+/// connecting was cancelled.
+fn connection(
+    connections: &Connections,
+    message_tx: &mpsc::UnboundedSender<MounterMessage>,
+    url: &Url,
+) -> Result<Option<Arc<Connection>>, MounterError> {
+    let Some(server) = Server::from_url(url) else {
+        return Err(error(
+            MounterErrorKind::Other,
+            format!("invalid sftp location {}", url),
+        ));
+    };
+    // Held while connecting, so that a password is asked for once
+    let mut connections = connections.lock().unwrap();
+    if let Some(connection) = connections.get(&server) {
+        if connection.session.keepalive_send().is_ok() {
+            return Ok(Some(connection.clone()));
+        }
+        log::info!("connection to {} was closed", server);
+        connections.remove(&server);
+    }
+    let Some(connection) = connect(&server, url.as_str(), message_tx)? else {
+        return Ok(None);
+    };
+    let connection = Arc::new(connection);
+    connections.insert(server, connection.clone());
+    Ok(Some(connection))
+}
+
+fn entry(url: &Url, uri: String, stat: &ssh2::FileStat) -> NetworkEntry {
+    let path = remote_path(url);
+    NetworkEntry {
+        name: path.file_name().map_or_else(
+            || "/".to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ),
+        uri,
+        is_dir: stat.is_dir(),
+        size: stat.size.unwrap_or(0),
+    }
+}
+
+pub struct Sftp {
+    connections: Connections,
+    message_tx: mpsc::UnboundedSender<MounterMessage>,
+    message_rx: Arc<TokioMutex<mpsc::UnboundedReceiver<MounterMessage>>>,
+}
+
+impl Sftp {
+    pub fn new() -> Self {
+        let (message_tx, message_rx) = mpsc::unbounded_channel();
+        Self {
+            connections: Connections::default(),
+            message_tx,
+            message_rx: Arc::new(TokioMutex::new(message_rx)),
+        }
+    }
+
+    /// Run `f` with the sftp session for the path of a URI, or return `None` for other URIs
+    fn with_sftp<T>(
+        &self,
+        uri: &str,
+        f: impl FnOnce(&ssh2::Sftp, &Url) -> Result<T, ssh2::Error>,
+    ) -> Option<io::Result<T>> {
+        let url = Url::parse(uri)
+            .ok()
+            .filter(|url| SCHEMES.contains(&url.scheme()))?;
+        Some(
+            match connection(&self.connections, &self.message_tx, &url) {
+                Ok(Some(connection)) => f(&connection.sftp, &url).map_err(io::Error::from),
+                Ok(None) => Err(cancelled()),
+                Err(err) => Err(io_error(err)),
+            },
+        )
+    }
+}
+
+impl Mounter for Sftp {
+    fn items(&self, _sizes: IconSizes) -> Option<MounterItems> {
+        None
+    }
+
+    fn mount(&self, _item: MounterItem) -> Task<()> {
+        Task::none()
+    }
+
+    fn network_drive(&self, uri: String) -> Task<()> {
+        let connections = self.connections.clone();
+        let message_tx = self.message_tx.clone();
+        Task::perform(
+            async move {
+                let res = tokio::task::spawn_blocking(move || {
+                    let res = match Url::parse(&uri) {
+                        Ok(url) => connection(&connections, &message_tx, &url)
+                            .map(|connection_opt| connection_opt.is_some()),
+                        Err(err) => Err(error(MounterErrorKind::Other, err)),
+                    };
+                    // The subscription may have ended with the application
+                    let _ = message_tx.send(MounterMessage::NetworkResult(uri, res));
+                })
+                .await;
+                if let Err(err) = res {
+                    log::warn!("failed to connect: {}", err);
+                }
+            },
+            |x| x,
+        )
+    }
+
+    fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab::Item>, String>> {
+        let res = self.with_sftp(uri, |sftp, url| {
+            let mut items = Vec::new();
+            for (path, mut stat) in sftp.readdir(remote_path(url))? {
+                let Some(name) = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                else {
+                    continue;
+                };
+                // Links are shown as what they point to
+                if stat.file_type().is_symlink() {
+                    match sftp.stat(&path) {
+                        Ok(target_stat) => stat = target_stat,
+                        Err(err) => log::info!("failed to follow link {:?}: {}", path, err),
+                    }
+                }
+                let child_uri = child_uri(url, &name);
+                let location = tab::network_location(&child_uri)
+                    .unwrap_or_else(|| Location::Network(child_uri, name.clone()));
+                let metadata = if stat.is_dir() {
+                    ItemMetadata::SimpleDir { entries: 0 }
+                } else {
+                    ItemMetadata::SimpleFile {
+                        size: stat.size.unwrap_or(0),
+                    }
+                };
+                items.push(tab::remote_item(name, location, metadata, sizes));
+            }
+            Ok(items)
+        })?;
+        Some(res.map_err(|err| err.to_string()))
+    }
+
+    fn network_path(&self, _uri: &str) -> Option<PathBuf> {
+        None
+    }
+
+    fn unmount(&self, _item: MounterItem, _force: bool) -> Task<()> {
+        Task::none()
+    }
+
+    fn cancel(&self) -> Task<()> {
+        Task::none()
+    }
+
+    fn subscription(&self) -> Subscription<MounterMessage> {
+        let message_rx = self.message_rx.clone();
+        Subscription::run_with_id(
+            TypeId::of::<Self>(),
+            stream::channel(1, |mut output| async move {
+                while let Some(message) = message_rx.lock().await.recv().await {
+                    output.send(message).await.unwrap();
+                }
+                pending().await
+            }),
+        )
+    }
+
+    fn network_files(&self, uri: &str) -> bool {
+        handles(uri)
+    }
+
+    fn network_metadata(&self, uri: &str) -> Option<io::Result<NetworkEntry>> {
+        // Links are not followed, so that removing a folder does not remove what they point to
+        self.with_sftp(uri, |sftp, url| {
+            let stat = sftp.lstat(&remote_path(url))?;
+            Ok(entry(url, uri.to_string(), &stat))
+        })
+    }
+
+    fn network_read_dir(&self, uri: &str) -> Option<io::Result<Vec<NetworkEntry>>> {
+        self.with_sftp(uri, |sftp, url| {
+            let mut entries = Vec::new();
+            for (path, stat) in sftp.readdir(remote_path(url))? {
+                if let Some(name) = path.file_name() {
+                    let child_uri = child_uri(url, &name.to_string_lossy());
+                    if let Ok(child_url) = Url::parse(&child_uri) {
+                        entries.push(entry(&child_url, child_uri, &stat));
+                    }
+                }
+            }
+            Ok(entries)
+        })
+    }
+
+    fn network_open(&self, uri: &str) -> Option<io::Result<Box<dyn Read + Send>>> {
+        self.with_sftp(uri, |sftp, url| {
+            let file = sftp.open(remote_path(url))?;
+            Ok(Box::new(file) as Box<dyn Read + Send>)
+        })
+    }
+
+    fn network_create(&self, uri: &str) -> Option<io::Result<Box<dyn Write + Send>>> {
+        self.with_sftp(uri, |sftp, url| {
+            let file = sftp.open_mode(
+                remote_path(url),
+                OpenFlags::WRITE | OpenFlags::CREATE | OpenFlags::TRUNCATE,
+                0o644,
+                OpenType::File,
+            )?;
+            Ok(Box::new(file) as Box<dyn Write + Send>)
+        })
+    }
+
+    fn network_create_dir(&self, uri: &str) -> Option<io::Result<()>> {
+        self.with_sftp(uri, |sftp, url| sftp.mkdir(&remote_path(url), 0o755))
+    }
+
+    fn network_rename(&self, from: &str, to: &str) -> Option<io::Result<()>> {
+        if !handles(from) {
+            return None;
+        }
+        let Some(to_url) = Url::parse(to).ok().filter(|to_url| {
+            Url::parse(from).is_ok_and(|url| Server::from_url(&url) == Server::from_url(to_url))
+        }) else {
+            return Some(Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("cannot rename {} to another server", from),
+            )));
+        };
+        self.with_sftp(from, |sftp, url| {
+            // Without the overwrite flag, existing items are not replaced
+            sftp.rename(
+                &remote_path(url),
+                &remote_path(&to_url),
+                Some(ssh2::RenameFlags::ATOMIC | ssh2::RenameFlags::NATIVE),
+            )
+        })
+    }
+
+    fn network_remove(&self, uri: &str) -> Option<io::Result<()>> {
+        self.with_sftp(uri, |sftp, url| {
+            let path = remote_path(url);
+            if sftp.lstat(&path)?.is_dir() {
+                sftp.rmdir(&path)
+            } else {
+                sftp.unlink(&path)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{child_uri, handles, remote_path, Server};
+    use std::path::PathBuf;
+    use url::Url;
+
+    #[test]
+    fn sftp_server() {
+        let url = Url::parse("sftp://alice@Example.com:2222/home/alice/My%20Files").unwrap();
+        assert_eq!(
+            Server::from_url(&url),
+            Some(Server {
+                user: "alice".to_string(),
+                host: "example.com".to_string(),
+                port: 2222,
+            })
+        );
+        assert_eq!(
+            Server::from_url(&url).unwrap().to_string(),
+            "alice@[example.com]:2222"
+        );
+        assert_eq!(remote_path(&url), PathBuf::from("/home/alice/My Files"));
+        assert_eq!(
+            child_uri(&url, "a b#c"),
+            "sftp://alice@Example.com:2222/home/alice/My%20Files/a%20b%23c"
+        );
+
+        let root = Url::parse("ssh://bob@[::1]/").unwrap();
+        assert_eq!(Server::from_url(&root).unwrap().host, "::1");
+        assert_eq!(remote_path(&root), PathBuf::from("/"));
+        assert_eq!(child_uri(&root, "srv"), "ssh://bob@[::1]/srv");
+
+        assert!(handles("ssh://host/"));
+        assert!(!handles("smb://host/share"));
+        assert!(!handles("file:///home"));
+    }
+}
@@ -0,0 +1,456 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! SMB servers on the local network, found with mDNS for Samba and macOS servers and with
+//! WS-Discovery for Windows computers. Their shares are browsed through gvfs, and the passwords
+//! entered for them are remembered in the Secret Service keyring.
+
+use cosmic::{
+    iced::{futures::SinkExt, stream, Subscription},
+    widget, Task,
+};
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    future::pending,
+    io::{self, Read, Write},
+    net::{TcpStream, ToSocketAddrs, UdpSocket},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, Mutex};
+
+use super::{Mounter, MounterItem, MounterItems, MounterMessage};
+use crate::{
+    config::IconSizes,
+    err_str,
+    tab::{self, Location},
+};
+
+const MDNS_SERVICE: &str = "_smb._tcp.local.";
+const WSD_ADDRESS: &str = "239.255.255.250:3702";
+/// How long to wait for computers to answer a probe or a request for their name
+const WSD_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often computers are probed for again, to find those that joined or left the network
+const WSD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Listings that show the servers found
+const SERVER_LISTINGS: [&str; 2] = ["network:///", "smb:///"];
+
+static CHILD_TEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<(?:[\w.-]+:)?(\w+)(?:\s[^>]*)?>([^<]*)</(?:[\w.-]+:)?(\w+)>").unwrap()
+});
+
+/// Host of an `smb://` URI, which its credentials are remembered for
+pub fn server(uri: &str) -> Option<String> {
+    let url = url::Url::parse(uri).ok()?;
+    if url.scheme() != "smb" {
+        return None;
+    }
+    url.host_str()
+        .filter(|host| !host.is_empty())
+        .map(|host| host.to_lowercase())
+}
+
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum ServerKey {
+    /// Full name of the mDNS service
+    Mdns(String),
+    /// Endpoint address of the WS-Discovery device
+    Wsd(String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Server {
+    name: String,
+    host: String,
+}
+
+type Servers = Arc<std::sync::Mutex<BTreeMap<ServerKey, Server>>>;
+
+/// Name of an mDNS service instance, like `nas` for `nas._smb._tcp.local.`
+fn instance_name(fullname: &str) -> &str {
+    fullname
+        .strip_suffix(MDNS_SERVICE)
+        .map_or(fullname, |name| name.trim_end_matches('.'))
+}
+
+fn browse_mdns(servers: &Servers, changed_tx: &mpsc::UnboundedSender<()>) -> Result<(), String> {
+    let daemon = ServiceDaemon::new().map_err(err_str)?;
+    let receiver = daemon.browse(MDNS_SERVICE).map_err(err_str)?;
+    while let Ok(event) = receiver.recv() {
+        let changed = match event {
+            ServiceEvent::ServiceResolved(info) => {
+                let server = Server {
+                    name: instance_name(info.get_fullname()).to_string(),
+                    host: info.get_hostname().trim_end_matches('.').to_lowercase(),
+                };
+                let key = ServerKey::Mdns(info.get_fullname().to_string());
+                servers.lock().unwrap().insert(key, server.clone()) != Some(server)
+            }
+            ServiceEvent::ServiceRemoved(_, fullname) => servers
+                .lock()
+                .unwrap()
+                .remove(&ServerKey::Mdns(fullname))
+                .is_some(),
+            _ => false,
+        };
+        if changed {
+            changed_tx.send(()).unwrap();
+        }
+    }
+    Ok(())
+}
+
+/// Random message ID, which devices use to match answers and drop repeated messages
+fn message_id() -> String {
+    let hex: String = (0..32).map(|_| fastrand::digit(16)).collect();
+    format!(
+        "urn:uuid:{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn probe_message() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope" xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing" xmlns:wsd="http://schemas.xmlsoap.org/ws/2005/04/discovery" xmlns:wsdp="http://schemas.xmlsoap.org/ws/2006/02/devprof">
+<soap:Header>
+<wsa:To>urn:schemas-xmlsoap-org:ws:2005:04:discovery</wsa:To>
+<wsa:Action>http://schemas.xmlsoap.org/ws/2005/04/discovery/Probe</wsa:Action>
+<wsa:MessageID>{}</wsa:MessageID>
+</soap:Header>
+<soap:Body><wsd:Probe><wsd:Types>wsdp:Device</wsd:Types></wsd:Probe></soap:Body>
+</soap:Envelope>"#,
+        message_id()
+    )
+}
+
+fn get_message(endpoint: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope" xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing">
+<soap:Header>
+<wsa:To>{}</wsa:To>
+<wsa:Action>http://schemas.xmlsoap.org/ws/2004/09/transfer/Get</wsa:Action>
+<wsa:MessageID>{}</wsa:MessageID>
+<wsa:ReplyTo><wsa:Address>http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</wsa:Address></wsa:ReplyTo>
+</soap:Header>
+<soap:Body/>
+</soap:Envelope>"#,
+        endpoint,
+        message_id()
+    )
+}
+
+/// Text of the first element with this name and only text inside, ignoring its namespace prefix
+fn element_text<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    CHILD_TEXT.captures_iter(xml).find_map(|captures| {
+        (&captures[1] == name && &captures[3] == name)
+            .then(|| captures.get(2).unwrap().as_str().trim())
+    })
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ProbeMatch {
+    /// Endpoint address of the device, which requests for its metadata are sent to
+    endpoint: String,
+    /// URL that the device answers those requests at
+    xaddr: String,
+}
+
+/// Computer that answered a probe, which is the only kind of device to have shares
+fn parse_probe_match(xml: &str) -> Option<ProbeMatch> {
+    let types = element_text(xml, "Types")?;
+    if !types.split_whitespace().any(|x| x.ends_with(":Computer")) {
+        return None;
+    }
+    let endpoint = element_text(xml, "Address")?.to_string();
+    // The same URL may be given for IPv6 and IPv4, which is easier to reach
+    let xaddrs: Vec<_> = element_text(xml, "XAddrs")?.split_whitespace().collect();
+    let xaddr = xaddrs
+        .iter()
+        .find(|xaddr| {
+            url::Url::parse(xaddr).is_ok_and(|url| matches!(url.host(), Some(url::Host::Ipv4(_))))
+        })
+        .or(xaddrs.first())?
+        .to_string();
+    Some(ProbeMatch { endpoint, xaddr })
+}
+
+/// Name of a computer from its metadata, like `DESKTOP` for `DESKTOP/Workgroup:WORKGROUP`
+fn parse_computer_name(xml: &str) -> Option<String> {
+    let computer = element_text(xml, "Computer")?;
+    let name = computer.split('/').next().unwrap_or(computer);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn computer_name(probe_match: &ProbeMatch) -> Result<String, String> {
+    let url = url::Url::parse(&probe_match.xaddr).map_err(err_str)?;
+    let host = url.host_str().ok_or("no host")?;
+    let port = url.port_or_known_default().ok_or("no port")?;
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(err_str)?
+        .next()
+        .ok_or("no address")?;
+
+    let body = get_message(&probe_match.endpoint);
+    let mut stream = TcpStream::connect_timeout(&addr, WSD_TIMEOUT).map_err(err_str)?;
+    stream
+        .set_read_timeout(Some(WSD_TIMEOUT))
+        .map_err(err_str)?;
+    stream
+        .set_write_timeout(Some(WSD_TIMEOUT))
+        .map_err(err_str)?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/soap+xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        url.path(),
+        host,
+        port,
+        body.len(),
+        body
+    )
+    .map_err(err_str)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(err_str)?;
+    parse_computer_name(&String::from_utf8_lossy(&response)).ok_or("no computer name".to_string())
+}
+
+/// Computers that answer a WS-Discovery probe, named by their metadata or else by their address
+fn probe_wsd() -> Result<BTreeMap<ServerKey, Server>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(err_str)?;
+    socket
+        .send_to(probe_message().as_bytes(), WSD_ADDRESS)
+        .map_err(err_str)?;
+
+    let mut probe_matches = Vec::new();
+    let mut buf = vec![0; 65536];
+    let deadline = Instant::now() + WSD_TIMEOUT;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket.set_read_timeout(Some(remaining)).map_err(err_str)?;
+        match socket.recv_from(&mut buf) {
+            Ok((len, _addr)) => {
+                if let Some(probe_match) = parse_probe_match(&String::from_utf8_lossy(&buf[..len]))
+                {
+                    if !probe_matches.contains(&probe_match) {
+                        probe_matches.push(probe_match);
+                    }
+                }
+            }
+            // The read timed out, with no more answers
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) if err.kind() == io::ErrorKind::TimedOut => break,
+            Err(err) => return Err(err_str(err)),
+        }
+    }
+
+    let mut servers = BTreeMap::new();
+    for probe_match in probe_matches {
+        let Some(host) = url::Url::parse(&probe_match.xaddr)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+        else {
+            continue;
+        };
+        let name = match computer_name(&probe_match) {
+            Ok(name) => name,
+            Err(err) => {
+                log::info!("failed to get name of {:?}: {}", probe_match.xaddr, err);
+                host.clone()
+            }
+        };
+        servers.insert(ServerKey::Wsd(probe_match.endpoint), Server { name, host });
+    }
+    Ok(servers)
+}
+
+fn update_wsd(servers: &Servers, changed_tx: &mpsc::UnboundedSender<()>) {
+    let found = match probe_wsd() {
+        Ok(found) => found,
+        Err(err) => {
+            log::warn!("failed to probe for SMB servers with WS-Discovery: {}", err);
+            return;
+        }
+    };
+    let mut servers = servers.lock().unwrap();
+    let old: BTreeMap<_, _> = servers
+        .iter()
+        .filter(|(key, _)| matches!(key, ServerKey::Wsd(_)))
+        .map(|(key, server)| (key.clone(), server.clone()))
+        .collect();
+    if old != found {
+        servers.retain(|key, _| !matches!(key, ServerKey::Wsd(_)));
+        servers.extend(found);
+        changed_tx.send(()).unwrap();
+    }
+}
+
+fn server_icon(size: u16) -> widget::icon::Handle {
+    widget::icon::from_name("network-server")
+        .size(size)
+        .handle()
+}
+
+pub struct Smb {
+    servers: Servers,
+    changed_rx: Arc<Mutex<mpsc::UnboundedReceiver<()>>>,
+}
+
+impl Smb {
+    pub fn new() -> Self {
+        let servers = Servers::default();
+        let (changed_tx, changed_rx) = mpsc::unbounded_channel();
+        {
+            let servers = servers.clone();
+            let changed_tx = changed_tx.clone();
+            std::thread::spawn(move || {
+                if let Err(err) = browse_mdns(&servers, &changed_tx) {
+                    log::warn!("failed to browse for SMB servers with mDNS: {}", err);
+                }
+            });
+        }
+        {
+            let servers = servers.clone();
+            std::thread::spawn(move || loop {
+                update_wsd(&servers, &changed_tx);
+                std::thread::sleep(WSD_INTERVAL);
+            });
+        }
+        Self {
+            servers,
+            changed_rx: Arc::new(Mutex::new(changed_rx)),
+        }
+    }
+}
+
+impl Mounter for Smb {
+    fn items(&self, _sizes: IconSizes) -> Option<MounterItems> {
+        None
+    }
+
+    fn mount(&self, _item: MounterItem) -> Task<()> {
+        Task::none()
+    }
+
+    fn network_drive(&self, _uri: String) -> Task<()> {
+        Task::none()
+    }
+
+    fn network_scan(&self, uri: &str, sizes: IconSizes) -> Option<Result<Vec<tab::Item>, String>> {
+        if !SERVER_LISTINGS
+            .iter()
+            .any(|listing| listing.trim_end_matches('/') == uri.trim_end_matches('/'))
+        {
+            return None;
+        }
+        let mut listed = Vec::<&Server>::new();
+        let mut items = Vec::new();
+        let servers = self.servers.lock().unwrap();
+        for server in servers.values() {
+            // Servers announced both ways are listed once
+            if listed.iter().any(|other| {
+                other.host == server.host || other.name.eq_ignore_ascii_case(&server.name)
+            }) {
+                continue;
+            }
+            listed.push(server);
+            items.push(tab::shortcut_item(
+                server.name.clone(),
+                Location::Network(format!("smb://{}/", server.host), server.name.clone()),
+                0,
+                server_icon,
+                sizes,
+            ));
+        }
+        Some(Ok(items))
+    }
+
+    fn network_path(&self, _uri: &str) -> Option<PathBuf> {
+        None
+    }
+
+    fn unmount(&self, _item: MounterItem, _force: bool) -> Task<()> {
+        Task::none()
+    }
+
+    fn cancel(&self) -> Task<()> {
+        Task::none()
+    }
+
+    fn subscription(&self) -> Subscription<MounterMessage> {
+        let changed_rx = self.changed_rx.clone();
+        Subscription::run_with_id(
+            TypeId::of::<Self>(),
+            stream::channel(1, |mut output| async move {
+                while let Some(()) = changed_rx.lock().await.recv().await {
+                    output
+                        .send(MounterMessage::NetworkChanged(
+                            SERVER_LISTINGS.iter().map(|x| x.to_string()).collect(),
+                        ))
+                        .await
+                        .unwrap();
+                }
+                pending().await
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{instance_name, parse_computer_name, parse_probe_match, server, ProbeMatch};
+
+    #[test]
+    fn smb_server() {
+        assert_eq!(
+            server("smb://NAS.local/music/"),
+            Some("nas.local".to_string())
+        );
+        assert_eq!(server("smb:///"), None);
+        assert_eq!(server("sftp://nas.local/"), None);
+        assert_eq!(instance_name("Living Room._smb._tcp.local."), "Living Room");
+    }
+
+    #[test]
+    fn wsd_probe_match() {
+        let xml = r#"<soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope" xmlns:wsa="http://schemas.xmlsoap.org/ws/2004/08/addressing" xmlns:wsd="http://schemas.xmlsoap.org/ws/2005/04/discovery">
+<soap:Header><wsa:Action>http://schemas.xmlsoap.org/ws/2005/04/discovery/ProbeMatches</wsa:Action></soap:Header>
+<soap:Body><wsd:ProbeMatches><wsd:ProbeMatch>
+<wsa:EndpointReference><wsa:Address>urn:uuid:0b3a2c6e-3f1d-4c7a-9d55-7d1c0e6f2a10</wsa:Address></wsa:EndpointReference>
+<wsd:Types>wsdp:Device pub:Computer</wsd:Types>
+<wsd:XAddrs>http://[fe80::1%25eth0]:5357/0b3a2c6e http://192.168.1.20:5357/0b3a2c6e</wsd:XAddrs>
+<wsd:MetadataVersion>2</wsd:MetadataVersion>
+</wsd:ProbeMatch></wsd:ProbeMatches></soap:Body></soap:Envelope>"#;
+        assert_eq!(
+            parse_probe_match(xml),
+            Some(ProbeMatch {
+                endpoint: "urn:uuid:0b3a2c6e-3f1d-4c7a-9d55-7d1c0e6f2a10".to_string(),
+                xaddr: "http://192.168.1.20:5357/0b3a2c6e".to_string(),
+            })
+        );
+        // Printers have no shares
+        assert_eq!(
+            parse_probe_match(&xml.replace("pub:Computer", "print:PrintDeviceType")),
+            None
+        );
+
+        let metadata = "<wsx:MetadataSection><pub:Computer>DESKTOP-42/Workgroup:WORKGROUP</pub:Computer></wsx:MetadataSection>";
+        assert_eq!(
+            parse_computer_name(metadata),
+            Some("DESKTOP-42".to_string())
+        );
+    }
+}
@@ -42,6 +42,7 @@ pub struct MouseArea<'a, Message> {
     on_scroll: Option<Box<dyn OnScroll<'a, Message>>>,
     on_enter: Option<Box<dyn OnEnterExit<'a, Message>>>,
     on_exit: Option<Box<dyn OnEnterExit<'a, Message>>>,
+    on_move: Option<Box<dyn OnMove<'a, Message>>>,
     show_drag_rect: bool,
 }
 
@@ -172,6 +173,14 @@ impl<'a, Message> MouseArea<'a, Message> {
         self
     }
 
+    /// The message to emit when a mouse moves inside the area, with its position relative to the
+    /// area and the size of the area.
+    #[must_use]
+    pub fn on_move(mut self, message: impl OnMove<'a, Message>) -> Self {
+        self.on_move = Some(Box::new(message));
+        self
+    }
+
     #[must_use]
     pub fn show_drag_rect(mut self, show_drag_rect: bool) -> Self {
         self.show_drag_rect = show_drag_rect;
@@ -204,6 +213,9 @@ impl<'a, Message, F> OnScroll<'a, Message> for F where
 pub trait OnEnterExit<'a, Message>: Fn() -> Message + 'a {}
 impl<'a, Message, F> OnEnterExit<'a, Message> for F where F: Fn() -> Message + 'a {}
 
+pub trait OnMove<'a, Message>: Fn(Point, Size) -> Message + 'a {}
+impl<'a, Message, F> OnMove<'a, Message> for F where F: Fn(Point, Size) -> Message + 'a {}
+
 /// Local state of the [`MouseArea`].
 #[derive(Default)]
 struct State {
@@ -283,6 +295,7 @@ impl<'a, Message> MouseArea<'a, Message> {
             on_forward_release: None,
             on_enter: None,
             on_exit: None,
+            on_move: None,
             on_scroll: None,
             show_drag_rect: false,
         }
@@ -518,6 +531,10 @@ fn update<Message: Clone>(
         }
         state.last_position = position_in;
 
+        if let (Some(position_in), Some(message)) = (position_in, widget.on_move.as_ref()) {
+            shell.publish(message(position_in, layout_bounds.size()));
+        }
+
         state.last_virtual_position = Some(Point::new(
             viewport.x - layout_bounds.x + position.x,
             viewport.y - layout_bounds.y + position.y,
@@ -1,12 +1,17 @@
 use crate::fl;
 
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use tokio::sync::Notify;
 
 #[derive(Clone, Copy, Debug)]
 pub enum ControllerState {
     Cancelled,
     Paused,
+    /// Waiting for earlier operations in the queue to finish
+    Queued,
     Running,
 }
 
@@ -14,6 +19,10 @@ pub enum ControllerState {
 struct ControllerInner {
     state: Mutex<ControllerState>,
     progress: Mutex<f32>,
+    /// Bytes written and bytes to write
+    bytes: Mutex<(u64, u64)>,
+    /// Time spent running before the last pause, and when it last started running
+    running_time: Mutex<(Duration, Option<Instant>)>,
     notify: Notify,
 }
 
@@ -30,6 +39,8 @@ impl Default for Controller {
             inner: Arc::new(ControllerInner {
                 state: Mutex::new(ControllerState::Running),
                 progress: Mutex::new(0.0),
+                bytes: Mutex::new((0, 0)),
+                running_time: Mutex::new((Duration::ZERO, Some(Instant::now()))),
                 notify: Notify::new(),
             }),
         }
@@ -41,7 +52,7 @@ impl Controller {
         loop {
             match self.state() {
                 ControllerState::Cancelled => return Err(fl!("cancelled")),
-                ControllerState::Paused => (),
+                ControllerState::Paused | ControllerState::Queued => (),
                 ControllerState::Running => return Ok(()),
             }
 
@@ -57,11 +68,39 @@ impl Controller {
         *self.inner.progress.lock().unwrap() = progress;
     }
 
+    pub fn add_bytes(&self, bytes: u64) {
+        self.inner.bytes.lock().unwrap().0 += bytes;
+    }
+
+    pub fn set_total_bytes(&self, total: u64) {
+        self.inner.bytes.lock().unwrap().1 = total;
+    }
+
+    /// Bytes written and bytes to write, for operations that copy data
+    pub fn bytes(&self) -> (u64, u64) {
+        *self.inner.bytes.lock().unwrap()
+    }
+
+    /// Time spent running, without time spent paused or queued
+    pub fn running_time(&self) -> Duration {
+        let (time, start_opt) = *self.inner.running_time.lock().unwrap();
+        time + start_opt.map_or(Duration::ZERO, |start| start.elapsed())
+    }
+
     pub fn state(&self) -> ControllerState {
         *self.inner.state.lock().unwrap()
     }
 
     pub fn set_state(&self, state: ControllerState) {
+        {
+            let mut running_time = self.inner.running_time.lock().unwrap();
+            if let Some(start) = running_time.1.take() {
+                running_time.0 += start.elapsed();
+            }
+            if matches!(state, ControllerState::Running) {
+                running_time.1 = Some(Instant::now());
+            }
+        }
         *self.inner.state.lock().unwrap() = state;
         self.inner.notify.notify_waiters();
     }
@@ -78,12 +117,31 @@ impl Controller {
         matches!(self.state(), ControllerState::Paused)
     }
 
+    pub fn is_queued(&self) -> bool {
+        matches!(self.state(), ControllerState::Queued)
+    }
+
+    /// Queued operations are not paused, so that resuming does not skip the queue
     pub fn pause(&self) {
-        self.set_state(ControllerState::Paused);
+        if matches!(self.state(), ControllerState::Running) {
+            self.set_state(ControllerState::Paused);
+        }
     }
 
     pub fn unpause(&self) {
-        if !self.is_cancelled() {
+        if self.is_paused() {
+            self.set_state(ControllerState::Running);
+        }
+    }
+
+    /// Wait for earlier operations in the queue
+    pub fn queue(&self) {
+        self.set_state(ControllerState::Queued);
+    }
+
+    /// Start a queued operation
+    pub fn start(&self) {
+        if self.is_queued() {
             self.set_state(ControllerState::Running);
         }
     }
@@ -1,10 +1,11 @@
 use crate::{
-    app::{ArchiveType, DialogPage, Message},
-    config::IconSizes,
-    fl,
+    app::{ArchiveType, CompressionLevel, DialogPage, Message},
+    config::{ConflictPolicy, IconSizes},
+    fl, image_metadata,
+    image_transform::{self, ImageTransform},
     mime_icon::mime_for_path,
     spawn_detached::spawn_detached,
-    tab,
+    tab, tag, trash_policy,
 };
 use cosmic::iced::futures::{channel::mpsc::Sender, SinkExt};
 use std::collections::VecDeque;
@@ -24,19 +25,29 @@ use zip::AesMode::Aes256;
 pub use self::controller::{Controller, ControllerState};
 pub mod controller;
 
+pub mod network;
+
+#[cfg(unix)]
+pub mod permissions;
+
+pub mod queue;
+
 use self::reader::OpReader;
 pub mod reader;
 
 use self::recursive::{Context, Method};
 pub mod recursive;
 
+pub mod shred;
+
 async fn handle_replace(
     msg_tx: Arc<TokioMutex<Sender<Message>>>,
     file_from: PathBuf,
     file_to: PathBuf,
     multiple: bool,
+    restore: bool,
 ) -> ReplaceResult {
-    let item_from = match tab::item_from_path(file_from, IconSizes::default()) {
+    let item_from = match tab::item_from_path(file_from.clone(), IconSizes::default()) {
         Ok(ok) => ok,
         Err(err) => {
             log::warn!("{}", err);
@@ -44,7 +55,7 @@ async fn handle_replace(
         }
     };
 
-    let item_to = match tab::item_from_path(file_to, IconSizes::default()) {
+    let item_to = match tab::item_from_path(file_to.clone(), IconSizes::default()) {
         Ok(ok) => ok,
         Err(err) => {
             log::warn!("{}", err);
@@ -52,6 +63,15 @@ async fn handle_replace(
         }
     };
 
+    // Suggest the name that keep both would use
+    let rename = match file_to.parent() {
+        Some(to_parent) => copy_unique_path(&file_to, to_parent)
+            .file_name()
+            .map(tab::escaped_file_name)
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+
     let (tx, mut rx) = mpsc::channel(1);
     let _ = msg_tx
         .lock()
@@ -61,6 +81,8 @@ async fn handle_replace(
             to: item_to,
             multiple,
             apply_to_all: false,
+            rename,
+            restore,
             tx,
         }))
         .await;
@@ -256,20 +278,63 @@ fn zip_extract<R: io::Read + io::Seek, P: AsRef<Path>>(
     Ok(())
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// Choice for an item that already exists at the destination. The `bool` applies it to all
+/// following conflicts of the operation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum ReplaceResult {
     Replace(bool),
-    KeepBoth,
+    /// Number the new item, like "name (copy 1).ext"
+    KeepBoth(bool),
+    /// Use a new name for this item only
+    Rename(String),
+    /// Restore this item into another folder, only offered when restoring from the trash
+    Elsewhere(PathBuf),
     Skip(bool),
     Cancel,
 }
 
+/// Whether a new name chosen for an item stays in the same folder
+pub fn valid_rename(name: &str) -> bool {
+    !(name.is_empty() || name == "." || name == ".." || name.contains('/'))
+}
+
+/// Write the entries to a tar archive, returning the writer so that its compression can be
+/// finished
+fn tar_compress<W: Write>(
+    writer: W,
+    entries: &[(PathBuf, PathBuf)],
+    controller: &Controller,
+) -> Result<W, OperationError> {
+    let mut archive = tar::Builder::new(writer);
+    let total_paths = entries.len();
+    for (i, (path, relative_root)) in entries.iter().enumerate() {
+        futures::executor::block_on(async {
+            controller.check().await.map_err(OperationError::from_str)
+        })?;
+
+        controller.set_progress((i as f32) / total_paths as f32);
+
+        if let Some(relative_path) = path
+            .strip_prefix(relative_root)
+            .map_err(OperationError::from_str)?
+            .to_str()
+        {
+            archive
+                .append_path_with_name(path, relative_path)
+                .map_err(OperationError::from_str)?;
+        }
+    }
+    archive.into_inner().map_err(OperationError::from_str)
+}
+
 async fn copy_or_move(
     paths: Vec<PathBuf>,
     to: PathBuf,
     method: Method,
     msg_tx: &Arc<TokioMutex<Sender<Message>>>,
     controller: Controller,
+    write_through: bool,
+    conflict_policy: ConflictPolicy,
 ) -> Result<OperationSelection, OperationError> {
     let msg_tx = msg_tx.clone();
 
@@ -284,36 +349,27 @@ async fn copy_or_move(
             to
         );
 
-        // Handle duplicate file names by renaming paths
-        let mut from_to_pairs: Vec<(PathBuf, PathBuf)> = paths
-            .into_iter()
-            .zip(std::iter::repeat(to.as_path()))
-            .filter_map(|(from, to)| {
-                if matches!(from.parent(), Some(parent) if parent == to)
-                    && matches!(method, Method::Copy)
-                {
-                    // `from`'s parent is equal to `to` which means we're copying to the same
-                    // directory (duplicating files)
-                    let to = copy_unique_path(&from, to);
-                    Some((from, to))
-                } else if let Some(name) = from.file_name() {
-                    let to = to.join(name);
-                    Some((from, to))
-                } else {
-                    //TODO: how to handle from missing file name?
-                    None
-                }
-            })
-            .collect();
+        let mut from_to_pairs = from_to_pairs(paths, &to, &method);
+
+        // Wait while the operation is queued or paused, renames happen right away
+        controller
+            .check()
+            .await
+            .map_err(OperationError::from_str)?;
 
         // Attempt quick and simple renames
         //TODO: allow rename to be used for directories in recursive context?
         if matches!(method, Method::Move { .. }) {
             from_to_pairs.retain(|(from, to)| {
+                // Renaming replaces an existing item, so conflicts are left to the recursive move
+                if fs::symlink_metadata(to).is_ok() {
+                    return true;
+                }
                 //TODO: use compio::fs::rename?
                 match fs::rename(from, to) {
                     Ok(()) => {
                         log::info!("renamed {from:?} to {to:?}");
+                        tag::moved(from, to, true);
                         false
                     },
                     Err(err) => {
@@ -324,7 +380,9 @@ async fn copy_or_move(
             });
         }
 
-        let mut context = Context::new(controller.clone());
+        let mut context = Context::new(controller.clone())
+            .write_through(write_through)
+            .conflict_policy(conflict_policy);
 
         {
             context = context.on_progress(move |_op, progress| {
@@ -348,7 +406,13 @@ async fn copy_or_move(
             let msg_tx = msg_tx.clone();
             context = context.on_replace(move |op| {
                 let msg_tx = msg_tx.clone();
-                Box::pin(handle_replace(msg_tx, op.from.clone(), op.to.clone(), true))
+                Box::pin(handle_replace(
+                    msg_tx,
+                    op.from.clone(),
+                    op.to.clone(),
+                    true,
+                    false,
+                ))
             });
         }
 
@@ -364,6 +428,30 @@ async fn copy_or_move(
     .map_err(OperationError::from_str)
 }
 
+/// Pair each path with its destination inside of `to`
+fn from_to_pairs(paths: Vec<PathBuf>, to: &Path, method: &Method) -> Vec<(PathBuf, PathBuf)> {
+    // Handle duplicate file names by renaming paths
+    paths
+        .into_iter()
+        .filter_map(|from| {
+            if matches!(from.parent(), Some(parent) if parent == to)
+                && matches!(method, Method::Copy)
+            {
+                // `from`'s parent is equal to `to` which means we're copying to the same
+                // directory (duplicating files)
+                let to = copy_unique_path(&from, to);
+                Some((from, to))
+            } else if let Some(name) = from.file_name() {
+                let to = to.join(name);
+                Some((from, to))
+            } else {
+                //TODO: how to handle from missing file name?
+                None
+            }
+        })
+        .collect()
+}
+
 fn copy_unique_path(from: &Path, to: &Path) -> PathBuf {
     // List of compound extensions to check
     const COMPOUND_EXTENSIONS: &[&str] = &[
@@ -464,12 +552,118 @@ fn paths_parent_name(paths: &[PathBuf]) -> Cow<'_, str> {
     file_name(parent)
 }
 
+/// A single change that an operation would make, see [`Operation::plan`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlannedChange {
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    CreateFolder(PathBuf),
+    Delete(PathBuf),
+    Move {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    /// An existing file will be replaced, unless skipped when asked
+    Replace {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+impl PlannedChange {
+    pub fn description(&self) -> String {
+        match self {
+            Self::Copy { from, to } => fl!(
+                "planned-copy",
+                from = from.display().to_string(),
+                to = to.display().to_string()
+            ),
+            Self::CreateFolder(path) => {
+                fl!("planned-create-folder", path = path.display().to_string())
+            }
+            Self::Delete(path) => fl!("planned-delete", path = path.display().to_string()),
+            Self::Move { from, to } => fl!(
+                "planned-move",
+                from = from.display().to_string(),
+                to = to.display().to_string()
+            ),
+            Self::Rename { from, to } => fl!(
+                "planned-rename",
+                from = from.display().to_string(),
+                to = to.display().to_string()
+            ),
+            Self::Replace { from, to } => fl!(
+                "planned-replace",
+                from = from.display().to_string(),
+                to = to.display().to_string()
+            ),
+        }
+    }
+}
+
+/// Walk paths the same way as a recursive copy or move, listing what would change
+fn plan_copy_or_move(
+    paths: &[PathBuf],
+    to: &Path,
+    method: Method,
+) -> Result<Vec<PlannedChange>, OperationError> {
+    let mut changes = Vec::new();
+    for (from_parent, to_parent) in from_to_pairs(paths.to_vec(), to, &method) {
+        if from_parent == to_parent {
+            continue;
+        }
+        for entry in WalkDir::new(&from_parent) {
+            let entry = entry.map_err(OperationError::from_str)?;
+            let from = entry.path().to_path_buf();
+            let to = match from.strip_prefix(&from_parent) {
+                Ok(relative) if !relative.as_os_str().is_empty() => to_parent.join(relative),
+                _ => to_parent.clone(),
+            };
+            changes.push(if entry.file_type().is_dir() {
+                if to.is_dir() {
+                    // Existing folders are merged
+                    continue;
+                }
+                PlannedChange::CreateFolder(to)
+            } else if to.exists() {
+                PlannedChange::Replace { from, to }
+            } else {
+                match method {
+                    Method::Copy => PlannedChange::Copy { from, to },
+                    Method::Move { .. } => PlannedChange::Move { from, to },
+                }
+            });
+        }
+        if matches!(method, Method::Move { .. }) && from_parent.is_dir() {
+            changes.push(PlannedChange::Delete(from_parent));
+        }
+    }
+    Ok(changes)
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct OperationSelection {
     // Paths to ignore if they are already selected
     pub ignored: Vec<PathBuf>,
     // Paths to select
     pub selected: Vec<PathBuf>,
+    // Items that already existed at the destination, and how they were handled
+    pub conflicts: Vec<(PathBuf, Resolution)>,
+}
+
+/// How an item that already existed at the destination was handled
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Resolution {
+    Replaced,
+    Skipped,
+    /// Written to another path, after keeping both or choosing a new name or folder
+    Renamed(PathBuf),
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -479,6 +673,7 @@ pub enum Operation {
         paths: Vec<PathBuf>,
         to: PathBuf,
         archive_type: ArchiveType,
+        level: CompressionLevel,
         password: Option<String>,
     },
     /// Copy items
@@ -508,6 +703,24 @@ pub enum Operation {
         to: PathBuf,
         cross_device_copy: bool,
     },
+    /// Copy items to or from a network location that a mounter reads itself
+    NetworkCopy {
+        uris: Vec<String>,
+        to: String,
+    },
+    /// Permanently delete items on a network location that a mounter reads itself
+    NetworkDelete {
+        uris: Vec<String>,
+    },
+    /// Move items to or from a network location that a mounter reads itself
+    NetworkMove {
+        uris: Vec<String>,
+        to: String,
+    },
+    NetworkRename {
+        from: String,
+        to: String,
+    },
     NewFile {
         path: PathBuf,
     },
@@ -526,6 +739,10 @@ pub enum Operation {
     Restore {
         items: Vec<trash::TrashItem>,
     },
+    /// Overwrite the contents of files before deleting them
+    SecureDelete {
+        paths: Vec<PathBuf>,
+    },
     /// Set executable and launch
     SetExecutableAndLaunch {
         path: PathBuf,
@@ -535,6 +752,22 @@ pub enum Operation {
         path: PathBuf,
         mode: u32,
     },
+    /// Apply the permissions and ownership of a folder to everything inside it
+    SetPermissionsRecursive {
+        path: PathBuf,
+        mode: u32,
+        uid: u32,
+        gid: u32,
+    },
+    /// Remove the camera metadata of photos
+    StripMetadata {
+        paths: Vec<PathBuf>,
+    },
+    /// Rotate or flip images
+    TransformImages {
+        paths: Vec<PathBuf>,
+        transform: ImageTransform,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -570,6 +803,7 @@ impl Operation {
         let progress = || match state {
             ControllerState::Running => fl!("progress", percent = percent),
             ControllerState::Paused => fl!("progress-paused", percent = percent),
+            ControllerState::Queued => fl!("progress-queued", percent = percent),
             ControllerState::Cancelled => fl!("progress-cancelled", percent = percent),
         };
         match self {
@@ -616,6 +850,26 @@ impl Operation {
                 to = file_name(to),
                 progress = progress()
             ),
+            Self::NetworkCopy { uris, to } => fl!(
+                "copying",
+                items = uris.len(),
+                from = network::uris_parent_name(uris),
+                to = network::uri_name(to),
+                progress = progress()
+            ),
+            Self::NetworkDelete { uris } => fl!("permanently-deleting", items = uris.len()),
+            Self::NetworkMove { uris, to } => fl!(
+                "moving",
+                items = uris.len(),
+                from = network::uris_parent_name(uris),
+                to = network::uri_name(to),
+                progress = progress()
+            ),
+            Self::NetworkRename { from, to } => fl!(
+                "renaming",
+                from = network::uri_name(from),
+                to = network::uri_name(to)
+            ),
             Self::NewFile { path } => fl!(
                 "creating",
                 name = file_name(path),
@@ -631,6 +885,11 @@ impl Operation {
                 fl!("renaming", from = file_name(from), to = file_name(to))
             }
             Self::Restore { items } => fl!("restoring", items = items.len(), progress = progress()),
+            Self::SecureDelete { paths } => fl!(
+                "securely-deleting",
+                items = paths.len(),
+                progress = progress()
+            ),
             Self::SetExecutableAndLaunch { path } => {
                 fl!("setting-executable-and-launching", name = file_name(path))
             }
@@ -641,6 +900,31 @@ impl Operation {
                     mode = format!("{:#03o}", mode)
                 )
             }
+            Self::SetPermissionsRecursive { path, mode, .. } => {
+                fl!(
+                    "setting-permissions-recursive",
+                    name = file_name(path),
+                    mode = format!("{:#03o}", mode),
+                    progress = progress()
+                )
+            }
+            Self::StripMetadata { paths } => fl!(
+                "removing-photo-metadata",
+                items = paths.len(),
+                progress = progress()
+            ),
+            Self::TransformImages { paths, transform } => match transform {
+                ImageTransform::RotateLeft | ImageTransform::RotateRight => fl!(
+                    "rotating-images",
+                    items = paths.len(),
+                    progress = progress()
+                ),
+                ImageTransform::FlipHorizontal | ImageTransform::FlipVertical => fl!(
+                    "flipping-images",
+                    items = paths.len(),
+                    progress = progress()
+                ),
+            },
         }
     }
 
@@ -682,6 +966,24 @@ impl Operation {
                 from = paths_parent_name(paths),
                 to = file_name(to)
             ),
+            Self::NetworkCopy { uris, to } => fl!(
+                "copied",
+                items = uris.len(),
+                from = network::uris_parent_name(uris),
+                to = network::uri_name(to)
+            ),
+            Self::NetworkDelete { uris } => fl!("permanently-deleted", items = uris.len()),
+            Self::NetworkMove { uris, to } => fl!(
+                "moved",
+                items = uris.len(),
+                from = network::uris_parent_name(uris),
+                to = network::uri_name(to)
+            ),
+            Self::NetworkRename { from, to } => fl!(
+                "renamed",
+                from = network::uri_name(from),
+                to = network::uri_name(to)
+            ),
             Self::NewFile { path } => fl!(
                 "created",
                 name = file_name(path),
@@ -695,6 +997,7 @@ impl Operation {
             Self::PermanentlyDelete { paths } => fl!("permanently-deleted", items = paths.len()),
             Self::Rename { from, to } => fl!("renamed", from = file_name(from), to = file_name(to)),
             Self::Restore { items } => fl!("restored", items = items.len()),
+            Self::SecureDelete { paths } => fl!("securely-deleted", items = paths.len()),
             Self::SetExecutableAndLaunch { path } => {
                 fl!("set-executable-and-launched", name = file_name(path))
             }
@@ -705,9 +1008,64 @@ impl Operation {
                     mode = format!("{:#03o}", mode)
                 )
             }
+            Self::SetPermissionsRecursive { path, mode, .. } => {
+                fl!(
+                    "set-permissions-recursive",
+                    name = file_name(path),
+                    mode = format!("{:#03o}", mode)
+                )
+            }
+            Self::StripMetadata { paths } => {
+                fl!("removed-photo-metadata", items = paths.len())
+            }
+            Self::TransformImages { paths, transform } => match transform {
+                ImageTransform::RotateLeft | ImageTransform::RotateRight => {
+                    fl!("rotated-images", items = paths.len())
+                }
+                ImageTransform::FlipHorizontal | ImageTransform::FlipVertical => {
+                    fl!("flipped-images", items = paths.len())
+                }
+            },
+        }
+    }
+
+    /// Folder that receives new items, which can be opened when the operation finishes
+    pub fn destination(&self) -> Option<&Path> {
+        match self {
+            Self::Copy { to, .. } | Self::Extract { to, .. } => Some(to),
+            _ => None,
+        }
+    }
+
+    /// Network folders whose items change, which are scanned again when the operation finishes
+    pub fn network_folders(&self) -> Vec<String> {
+        match self {
+            Self::NetworkCopy { to, .. } => vec![to.clone()],
+            Self::NetworkMove { uris, to } => uris
+                .iter()
+                .filter_map(|uri| network::parent_uri(uri))
+                .chain(std::iter::once(to.clone()))
+                .collect(),
+            Self::NetworkDelete { uris } => uris
+                .iter()
+                .filter_map(|uri| network::parent_uri(uri))
+                .collect(),
+            Self::NetworkRename { from, .. } => network::parent_uri(from).into_iter().collect(),
+            _ => Vec::new(),
         }
     }
 
+    /// Copies and moves wait for each other, as they are limited by drive speed
+    pub fn queued(&self) -> bool {
+        matches!(
+            self,
+            Self::Copy { .. }
+                | Self::Move { .. }
+                | Self::NetworkCopy { .. }
+                | Self::NetworkMove { .. }
+        )
+    }
+
     pub fn show_progress_notification(&self) -> bool {
         // Long running operations show a progress notification
         match self {
@@ -718,9 +1076,17 @@ impl Operation {
             | Self::EmptyTrash
             | Self::Extract { .. }
             | Self::Move { .. }
+            | Self::NetworkCopy { .. }
+            | Self::NetworkDelete { .. }
+            | Self::NetworkMove { .. }
             | Self::PermanentlyDelete { .. }
-            | Self::Restore { .. } => true,
-            Self::NewFile { .. }
+            | Self::Restore { .. }
+            | Self::SecureDelete { .. }
+            | Self::SetPermissionsRecursive { .. }
+            | Self::StripMetadata { .. }
+            | Self::TransformImages { .. } => true,
+            Self::NetworkRename { .. }
+            | Self::NewFile { .. }
             | Self::NewFolder { .. }
             | Self::Rename { .. }
             | Self::SetExecutableAndLaunch { .. }
@@ -738,11 +1104,49 @@ impl Operation {
         }
     }
 
-    /// Perform the operation
+    /// Plan the changes of the operation without performing it. This is blocking, as it walks
+    /// the file system. Operations without a plan return no changes.
+    pub fn plan(&self) -> Result<Vec<PlannedChange>, OperationError> {
+        match self {
+            Self::Copy { paths, to } => plan_copy_or_move(paths, to, Method::Copy),
+            Self::Move {
+                paths,
+                to,
+                cross_device_copy,
+            } => plan_copy_or_move(
+                paths,
+                to,
+                Method::Move {
+                    cross_device_copy: *cross_device_copy,
+                },
+            ),
+            Self::PermanentlyDelete { paths } | Self::SecureDelete { paths } => {
+                let mut changes = Vec::new();
+                for path in paths.iter() {
+                    // List contents before their folders, in the order they are removed
+                    for entry in WalkDir::new(path).contents_first(true) {
+                        let entry = entry.map_err(OperationError::from_str)?;
+                        changes.push(PlannedChange::Delete(entry.into_path()));
+                    }
+                }
+                Ok(changes)
+            }
+            Self::Rename { from, to } => Ok(vec![PlannedChange::Rename {
+                from: from.clone(),
+                to: to.clone(),
+            }]),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Perform the operation, syncing copied data as it is written if `write_through` is set,
+    /// and handling items that already exist by `conflict_policy` when copying or moving
     pub async fn perform(
         self,
         msg_tx: &Arc<TokioMutex<Sender<Message>>>,
         controller: Controller,
+        write_through: bool,
+        conflict_policy: ConflictPolicy,
     ) -> Result<OperationSelection, OperationError> {
         let controller_clone = controller.clone();
 
@@ -752,48 +1156,56 @@ impl Operation {
                 paths,
                 to,
                 archive_type,
+                level,
                 password,
             } => {
                 compio::runtime::spawn_blocking(
                     move || -> Result<OperationSelection, OperationError> {
-                        let Some(relative_root) = to.parent() else {
-                            return Err(OperationError::from_str(format!(
-                                "path {:?} has no parent directory",
-                                to
-                            )));
-                        };
-
                         let op_sel = OperationSelection {
                             ignored: paths.clone(),
                             selected: vec![to.clone()],
+                            conflicts: Vec::new(),
                         };
 
-                        let mut paths = paths;
-                        for path in paths.clone().iter() {
+                        // Items are stored relative to the folder containing them, so items
+                        // added from other folders are also at the top of the archive
+                        let mut entries = Vec::with_capacity(paths.len());
+                        for path in paths {
+                            let Some(relative_root) = path.parent().map(Path::to_path_buf) else {
+                                return Err(OperationError::from_str(format!(
+                                    "path {:?} has no parent directory",
+                                    path
+                                )));
+                            };
+                            entries.push((path.clone(), relative_root.clone()));
                             if path.is_dir() {
-                                let new_paths_it = WalkDir::new(path).into_iter();
+                                let new_paths_it = WalkDir::new(&path).into_iter();
                                 for entry in new_paths_it.skip(1) {
                                     let entry = entry.map_err(OperationError::from_str)?;
-                                    paths.push(entry.into_path());
+                                    entries.push((entry.into_path(), relative_root.clone()));
                                 }
                             }
                         }
 
                         match archive_type {
-                            ArchiveType::Tgz => {
-                                let mut archive = fs::File::create(&to)
-                                    .map(io::BufWriter::new)
-                                    .map(|w| {
-                                        flate2::write::GzEncoder::new(
-                                            w,
-                                            flate2::Compression::default(),
-                                        )
-                                    })
-                                    .map(tar::Builder::new)
+                            ArchiveType::SevenZ => {
+                                let mut archive = sevenz_rust::SevenZWriter::create(&to)
                                     .map_err(OperationError::from_str)?;
-
-                                let total_paths = paths.len();
-                                for (i, path) in paths.iter().enumerate() {
+                                // Encryption is applied to the compressed data
+                                let mut methods = Vec::with_capacity(2);
+                                if let Some(password) = password.as_deref() {
+                                    methods.push(
+                                        sevenz_rust::AesEncoderOptions::new(password.into()).into(),
+                                    );
+                                }
+                                methods.push(
+                                    sevenz_rust::lzma::LZMA2Options::with_preset(level.level())
+                                        .into(),
+                                );
+                                archive.set_content_methods(methods);
+
+                                let total_paths = entries.len();
+                                for (i, (path, relative_root)) in entries.iter().enumerate() {
                                     futures::executor::block_on(async {
                                         controller.check().await.map_err(OperationError::from_str)
                                     })?;
@@ -805,30 +1217,78 @@ impl Operation {
                                         .map_err(OperationError::from_str)?
                                         .to_str()
                                     {
+                                        let entry = sevenz_rust::SevenZArchiveEntry::from_path(
+                                            path,
+                                            relative_path.to_string(),
+                                        );
+                                        let reader = if path.is_file() {
+                                            Some(
+                                                fs::File::open(path)
+                                                    .map_err(OperationError::from_str)?,
+                                            )
+                                        } else {
+                                            None
+                                        };
                                         archive
-                                            .append_path_with_name(path, relative_path)
+                                            .push_archive_entry(entry, reader)
                                             .map_err(OperationError::from_str)?;
                                     }
                                 }
-
                                 archive.finish().map_err(OperationError::from_str)?;
                             }
+                            ArchiveType::Tgz => {
+                                let writer = fs::File::create(&to)
+                                    .map(io::BufWriter::new)
+                                    .map(|w| {
+                                        flate2::write::GzEncoder::new(
+                                            w,
+                                            flate2::Compression::new(level.level()),
+                                        )
+                                    })
+                                    .map_err(OperationError::from_str)?;
+                                tar_compress(writer, &entries, &controller)?
+                                    .finish()
+                                    .and_then(|mut w| w.flush())
+                                    .map_err(OperationError::from_str)?;
+                            }
+                            #[cfg(feature = "xz2")]
+                            ArchiveType::TarXz => {
+                                let writer = fs::File::create(&to)
+                                    .map(io::BufWriter::new)
+                                    .map(|w| xz2::write::XzEncoder::new(w, level.level()))
+                                    .map_err(OperationError::from_str)?;
+                                tar_compress(writer, &entries, &controller)?
+                                    .finish()
+                                    .and_then(|mut w| w.flush())
+                                    .map_err(OperationError::from_str)?;
+                            }
+                            ArchiveType::TarZst => {
+                                let writer = fs::File::create(&to)
+                                    .map(io::BufWriter::new)
+                                    .and_then(|w| zstd::Encoder::new(w, level.zstd_level()))
+                                    .map_err(OperationError::from_str)?;
+                                tar_compress(writer, &entries, &controller)?
+                                    .finish()
+                                    .and_then(|mut w| w.flush())
+                                    .map_err(OperationError::from_str)?;
+                            }
                             ArchiveType::Zip => {
                                 let mut archive = fs::File::create(&to)
                                     .map(io::BufWriter::new)
                                     .map(zip::ZipWriter::new)
                                     .map_err(OperationError::from_str)?;
 
-                                let total_paths = paths.len();
+                                let total_paths = entries.len();
                                 let mut buffer = vec![0; 4 * 1024 * 1024];
-                                for (i, path) in paths.iter().enumerate() {
+                                for (i, (path, relative_root)) in entries.iter().enumerate() {
                                     futures::executor::block_on(async {
                                         controller.check().await.map_err(OperationError::from_str)
                                     })?;
 
                                     controller.set_progress((i as f32) / total_paths as f32);
 
-                                    let mut zip_options = zip::write::SimpleFileOptions::default();
+                                    let mut zip_options = zip::write::SimpleFileOptions::default()
+                                        .compression_level(Some(level.level().into()));
                                     if password.is_some() {
                                         zip_options = zip_options.with_aes_encryption(
                                             Aes256,
@@ -905,7 +1365,16 @@ impl Operation {
                 .map_err(OperationError::from_str)
             }
             Self::Copy { paths, to } => {
-                copy_or_move(paths, to, Method::Copy, msg_tx, controller).await
+                copy_or_move(
+                    paths,
+                    to,
+                    Method::Copy,
+                    msg_tx,
+                    controller,
+                    write_through,
+                    conflict_policy,
+                )
+                .await
             }
             Self::Delete { paths } => {
                 let total = paths.len();
@@ -916,11 +1385,15 @@ impl Operation {
 
                     controller.set_progress((i as f32) / (total as f32));
 
-                    let _items_opt = compio::runtime::spawn_blocking(|| trash::delete(path))
-                        .await
-                        .map_err(wrap_compio_spawn_error)?
-                        .map_err(OperationError::from_str)?;
+                    let _items_opt = compio::runtime::spawn_blocking({
+                        let path = path.clone();
+                        move || trash::delete(path)
+                    })
+                    .await
+                    .map_err(wrap_compio_spawn_error)?
+                    .map_err(OperationError::from_str)?;
                     //TODO: items_opt allows for easy restore
+                    tag::removed(&path);
                 }
                 Ok(OperationSelection::default())
             }
@@ -1094,9 +1567,37 @@ impl Operation {
                     Method::Move { cross_device_copy },
                     msg_tx,
                     controller,
+                    write_through,
+                    conflict_policy,
                 )
                 .await
             }
+            Self::NetworkCopy { uris, to } => compio::runtime::spawn_blocking(move || {
+                network::transfer(&uris, &to, false, &controller)
+            })
+            .await
+            .map_err(wrap_compio_spawn_error)?,
+            Self::NetworkDelete { uris } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    network::delete(&uris, &controller)?;
+                    Ok(OperationSelection::default())
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
+            Self::NetworkMove { uris, to } => compio::runtime::spawn_blocking(move || {
+                network::transfer(&uris, &to, true, &controller)
+            })
+            .await
+            .map_err(wrap_compio_spawn_error)?,
+            Self::NetworkRename { from, to } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    network::rename(&from, &to)?;
+                    Ok(OperationSelection::default())
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
             Self::NewFolder { path } => compio::runtime::spawn(async move {
                 controller.check().await.map_err(OperationError::from_str)?;
                 compio::fs::create_dir(&path)
@@ -1105,6 +1606,7 @@ impl Operation {
                 Result::<_, OperationError>::Ok(OperationSelection {
                     ignored: Vec::new(),
                     selected: vec![path],
+                    conflicts: Vec::new(),
                 })
             })
             .await
@@ -1118,6 +1620,7 @@ impl Operation {
                 Result::<_, OperationError>::Ok(OperationSelection {
                     ignored: Vec::new(),
                     selected: vec![path],
+                    conflicts: Vec::new(),
                 })
             })
             .await
@@ -1130,21 +1633,25 @@ impl Operation {
 
                     controller.set_progress((idx as f32) / (total as f32));
 
-                    tokio::task::spawn_blocking(|| {
-                        if path.is_symlink() || path.is_file() {
-                            fs::remove_file(path)
-                        } else if path.is_dir() {
-                            fs::remove_dir_all(path)
-                        } else {
-                            Err(std::io::Error::new(
-                                std::io::ErrorKind::InvalidData,
-                                "File to delete is not symlink, file or directory",
-                            ))
+                    tokio::task::spawn_blocking({
+                        let path = path.clone();
+                        move || {
+                            if path.is_symlink() || path.is_file() {
+                                fs::remove_file(path)
+                            } else if path.is_dir() {
+                                fs::remove_dir_all(path)
+                            } else {
+                                Err(std::io::Error::new(
+                                    std::io::ErrorKind::InvalidData,
+                                    "File to delete is not symlink, file or directory",
+                                ))
+                            }
                         }
                     })
                     .await
                     .map_err(OperationError::from_str)?
                     .map_err(OperationError::from_str)?;
+                    tag::removed(&path);
                 }
 
                 Ok(OperationSelection::default())
@@ -1154,14 +1661,53 @@ impl Operation {
                 compio::fs::rename(&from, &to)
                     .await
                     .map_err(OperationError::from_str)?;
+                tag::moved(&from, &to, true);
                 Result::<_, OperationError>::Ok(OperationSelection {
                     ignored: vec![from],
                     selected: vec![to],
+                    conflicts: Vec::new(),
                 })
             })
             .await
             .map_err(wrap_compio_spawn_error)?
             .map_err(OperationError::from_str),
+            Self::SecureDelete { paths } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    let plan = shred::ShredPlan::new(&paths).map_err(OperationError::from_str)?;
+                    for path in plan.shared.iter() {
+                        log::warn!(
+                            "not overwriting {:?} as it has hard links that are not deleted",
+                            path
+                        );
+                    }
+                    let total = plan.total_size().max(1);
+                    let mut written = 0;
+                    for (path, size) in plan.files.iter() {
+                        shred::overwrite(path, *size, |len| {
+                            futures::executor::block_on(controller.check())
+                                .map_err(std::io::Error::other)?;
+                            written += len;
+                            controller.set_progress(written as f32 / total as f32);
+                            Ok(())
+                        })
+                        .and_then(|()| fs::remove_file(path))
+                        .map_err(|err| {
+                            OperationError::from_str(format!("{}: {}", path.display(), err))
+                        })?;
+                    }
+                    for path in plan.removed.iter() {
+                        shred::remove(path).map_err(|err| {
+                            OperationError::from_str(format!("{}: {}", path.display(), err))
+                        })?;
+                    }
+                    for path in paths.iter() {
+                        tag::removed(path);
+                    }
+                    Ok(OperationSelection::default())
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
             #[cfg(target_os = "macos")]
             Self::Restore { .. } => {
                 // TODO: add support for macos
@@ -1171,11 +1717,95 @@ impl Operation {
             Self::Restore { items } => {
                 let total = items.len();
                 let mut paths = Vec::with_capacity(total);
-                for (i, item) in items.into_iter().enumerate() {
+                let mut conflicts = Vec::new();
+                let mut replace_result_opt = None;
+                for (i, mut item) in items.into_iter().enumerate() {
                     controller.check().await.map_err(OperationError::from_str)?;
 
                     controller.set_progress((i as f32) / (total as f32));
 
+                    // Restoring fails if another item was added at the original path
+                    let original_path = item.original_path();
+                    if original_path.symlink_metadata().is_ok() {
+                        let replace_result = match &replace_result_opt {
+                            Some(result) => ReplaceResult::clone(result),
+                            None => {
+                                handle_replace(
+                                    msg_tx.clone(),
+                                    trash_policy::trash_file_path(&item).unwrap_or_default(),
+                                    original_path.clone(),
+                                    total > 1,
+                                    true,
+                                )
+                                .await
+                            }
+                        };
+                        // The name is changed by picking an unused one in the folder
+                        let unique_name = |path: &Path, parent: &Path| {
+                            copy_unique_path(path, parent)
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_os_string()
+                        };
+                        let replaced = match replace_result {
+                            ReplaceResult::Replace(apply_to_all) => {
+                                if apply_to_all {
+                                    replace_result_opt = Some(ReplaceResult::Replace(true));
+                                }
+                                // The replaced item is trashed, so that it can be restored too
+                                let path = original_path.clone();
+                                compio::runtime::spawn_blocking(move || trash::delete(path))
+                                    .await
+                                    .map_err(wrap_compio_spawn_error)?
+                                    .map_err(OperationError::from_str)?;
+                                true
+                            }
+                            ReplaceResult::KeepBoth(apply_to_all) => {
+                                if apply_to_all {
+                                    replace_result_opt = Some(ReplaceResult::KeepBoth(true));
+                                }
+                                item.name = unique_name(&original_path, &item.original_parent);
+                                false
+                            }
+                            ReplaceResult::Rename(name) => {
+                                if !valid_rename(&name) {
+                                    return Err(OperationError::from_str(format!(
+                                        "invalid name {:?}",
+                                        name
+                                    )));
+                                }
+                                let to = item.original_parent.join(&name);
+                                item.name = if to.symlink_metadata().is_ok() {
+                                    unique_name(&to, &item.original_parent)
+                                } else {
+                                    name.into()
+                                };
+                                false
+                            }
+                            ReplaceResult::Elsewhere(parent) => {
+                                let to = parent.join(&item.name);
+                                if to.symlink_metadata().is_ok() {
+                                    item.name = unique_name(&to, &parent);
+                                }
+                                item.original_parent = parent;
+                                false
+                            }
+                            ReplaceResult::Skip(apply_to_all) => {
+                                if apply_to_all {
+                                    replace_result_opt = Some(ReplaceResult::Skip(true));
+                                }
+                                conflicts.push((original_path, Resolution::Skipped));
+                                continue;
+                            }
+                            ReplaceResult::Cancel => break,
+                        };
+                        conflicts.push(if replaced {
+                            (original_path, Resolution::Replaced)
+                        } else {
+                            (original_path, Resolution::Renamed(item.original_path()))
+                        });
+                    }
+
                     paths.push(item.original_path());
 
                     compio::runtime::spawn_blocking(|| trash::os_limited::restore_all([item]))
@@ -1186,6 +1816,7 @@ impl Operation {
                 Ok(OperationSelection {
                     ignored: Vec::new(),
                     selected: paths,
+                    conflicts,
                 })
             }
             Self::SetExecutableAndLaunch { path } => {
@@ -1235,6 +1866,86 @@ impl Operation {
                 .map_err(OperationError::from_str)?;
                 Ok(OperationSelection::default())
             }
+            #[cfg(unix)]
+            Self::SetPermissionsRecursive {
+                path,
+                mode,
+                uid,
+                gid,
+            } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    // Items that fail, like those on read-only mounts, do not stop the others
+                    let (paths, mut errors) = permissions::enclosed_paths(&path);
+                    let total = paths.len();
+                    for (i, path) in paths.iter().enumerate() {
+                        futures::executor::block_on(async {
+                            controller.check().await.map_err(OperationError::from_str)
+                        })?;
+
+                        controller.set_progress((i as f32) / (total as f32));
+
+                        if let Err(err) = permissions::apply(path, mode, uid, gid) {
+                            errors.push((path.clone(), err.to_string()));
+                        }
+                    }
+                    if errors.is_empty() {
+                        Ok(OperationSelection::default())
+                    } else {
+                        Err(OperationError::from_str(permissions::error_summary(
+                            &errors,
+                        )))
+                    }
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
+            #[cfg(not(unix))]
+            Self::SetPermissionsRecursive { .. } => Err(OperationError::from_str(
+                "setting permissions is not supported on this platform",
+            )),
+            Self::StripMetadata { paths } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    let total = paths.len();
+                    for (i, path) in paths.iter().enumerate() {
+                        futures::executor::block_on(async {
+                            controller.check().await.map_err(OperationError::from_str)
+                        })?;
+
+                        controller.set_progress((i as f32) / (total as f32));
+
+                        image_metadata::strip(path).map_err(|err| {
+                            OperationError::from_str(format!("{}: {}", path.display(), err))
+                        })?;
+                    }
+                    Ok(OperationSelection::default())
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
+            Self::TransformImages { paths, transform } => compio::runtime::spawn_blocking(
+                move || -> Result<OperationSelection, OperationError> {
+                    let total = paths.len();
+                    for (i, path) in paths.iter().enumerate() {
+                        futures::executor::block_on(async {
+                            controller.check().await.map_err(OperationError::from_str)
+                        })?;
+
+                        controller.set_progress((i as f32) / (total as f32));
+
+                        image_transform::transform(path, transform).map_err(|err| {
+                            OperationError::from_str(format!("{}: {}", path.display(), err))
+                        })?;
+                    }
+                    // Keep the images selected for transforming them again
+                    Ok(OperationSelection {
+                        ignored: Vec::new(),
+                        selected: paths,
+                        conflicts: Vec::new(),
+                    })
+                },
+            )
+            .await
+            .map_err(wrap_compio_spawn_error)?,
         };
 
         controller_clone.set_progress(1.0);
@@ -1259,6 +1970,7 @@ mod tests {
         fs::{self, File},
         io,
         path::PathBuf,
+        time::{Duration, SystemTime},
     };
 
     use cosmic::iced::futures::{channel::mpsc, StreamExt};
@@ -1273,8 +1985,9 @@ mod tests {
                 empty_fs, filter_dirs, filter_files, simple_fs, NAME_LEN, NUM_DIRS, NUM_FILES,
                 NUM_HIDDEN, NUM_NESTED,
             },
-            DialogPage, Message,
+            ArchiveType, CompressionLevel, DialogPage, Message,
         },
+        config::ConflictPolicy,
         fl,
     };
 
@@ -1282,6 +1995,15 @@ mod tests {
     pub async fn operation_copy(
         paths: Vec<PathBuf>,
         to: PathBuf,
+    ) -> Result<OperationSelection, OperationError> {
+        operation_copy_replacing(paths, to, ReplaceResult::Cancel).await
+    }
+
+    /// `[Operation::Copy]` that answers every replace request with `replace_result`
+    pub async fn operation_copy_replacing(
+        paths: Vec<PathBuf>,
+        to: PathBuf,
+        replace_result: ReplaceResult,
     ) -> Result<OperationSelection, OperationError> {
         let id = fastrand::u64(0..u64::MAX);
         let (tx, mut rx) = mpsc::channel(1);
@@ -1294,7 +2016,12 @@ mod tests {
                 paths: paths_clone,
                 to: to_clone,
             }
-            .perform(&sync::Mutex::new(tx).into(), Controller::default())
+            .perform(
+                &sync::Mutex::new(tx).into(),
+                Controller::default(),
+                false,
+                ConflictPolicy::Ask,
+            )
             .await
         };
 
@@ -1304,7 +2031,7 @@ mod tests {
                 match msg {
                     Message::DialogPush(DialogPage::Replace { tx, .. }) => {
                         debug!("[{id}] Replace request");
-                        tx.send(ReplaceResult::Cancel).await.expect("Sending a response to a replace request should succeed")
+                        tx.send(replace_result.clone()).await.expect("Sending a response to a replace request should succeed")
 
                     }
                     _ => unreachable!("Only [ `Message::PendingProgress`, `Message::DialogPush(DialogPage::Replace)` ] are sent from operation"),
@@ -1315,6 +2042,43 @@ mod tests {
         futures::future::join(handle_messages, handle_copy).await.1
     }
 
+    /// `[Operation::Copy]` that handles existing items by `conflict_policy` without asking
+    pub async fn operation_copy_with_policy(
+        paths: Vec<PathBuf>,
+        to: PathBuf,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<OperationSelection, OperationError> {
+        let (tx, _rx) = mpsc::channel(1);
+        Operation::Copy { paths, to }
+            .perform(
+                &sync::Mutex::new(tx).into(),
+                Controller::default(),
+                false,
+                conflict_policy,
+            )
+            .await
+    }
+
+    pub async fn operation_move_with_policy(
+        paths: Vec<PathBuf>,
+        to: PathBuf,
+        conflict_policy: ConflictPolicy,
+    ) -> Result<OperationSelection, OperationError> {
+        let (tx, _rx) = mpsc::channel(1);
+        Operation::Move {
+            paths,
+            to,
+            cross_device_copy: false,
+        }
+        .perform(
+            &sync::Mutex::new(tx).into(),
+            Controller::default(),
+            false,
+            conflict_policy,
+        )
+        .await
+    }
+
     #[test(compio::test)]
     async fn copy_file_to_same_location() -> io::Result<()> {
         let fs = simple_fs(NUM_FILES, 0, 1, 0, NAME_LEN)?;
@@ -1485,4 +2249,292 @@ mod tests {
 
         Ok(())
     }
+
+    #[test(compio::test)]
+    async fn copy_conflict_choices() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        let dir_path = path.join("cosmic");
+        fs::create_dir(&dir_path)?;
+        let file_path = path.join("ferris.txt");
+        fs::write(&file_path, b"new")?;
+        let existing = dir_path.join("ferris.txt");
+        fs::write(&existing, b"old")?;
+
+        operation_copy_replacing(
+            vec![file_path.clone()],
+            dir_path.clone(),
+            ReplaceResult::Rename("crab.txt".to_string()),
+        )
+        .await
+        .expect("Copy operation should have succeeded");
+        assert_eq!(fs::read(&existing)?, b"old", "Existing file should be kept");
+        assert_eq!(fs::read(dir_path.join("crab.txt"))?, b"new");
+
+        for name in ["", "..", "../crab.txt"] {
+            operation_copy_replacing(
+                vec![file_path.clone()],
+                dir_path.clone(),
+                ReplaceResult::Rename(name.to_string()),
+            )
+            .await
+            .expect_err("Copy operation should have rejected the name");
+        }
+        assert!(
+            !path.join("crab.txt").exists(),
+            "Name should not leave the folder"
+        );
+
+        operation_copy_replacing(
+            vec![file_path.clone()],
+            dir_path.clone(),
+            ReplaceResult::Skip(false),
+        )
+        .await
+        .expect("Copy operation should have succeeded");
+        assert_eq!(fs::read(&existing)?, b"old", "Existing file should be kept");
+
+        operation_copy_replacing(
+            vec![file_path.clone()],
+            dir_path.clone(),
+            ReplaceResult::KeepBoth(false),
+        )
+        .await
+        .expect("Copy operation should have succeeded");
+        assert_eq!(fs::read(&existing)?, b"old", "Existing file should be kept");
+        let kept = dir_path.join(format!("ferris ({} 1).txt", fl!("copy_noun")));
+        assert_eq!(fs::read(kept)?, b"new");
+
+        operation_copy_replacing(
+            vec![file_path.clone()],
+            dir_path.clone(),
+            ReplaceResult::Replace(false),
+        )
+        .await
+        .expect("Copy operation should have succeeded");
+        assert_eq!(
+            fs::read(&existing)?,
+            b"new",
+            "Existing file should be replaced"
+        );
+
+        Ok(())
+    }
+
+    #[test(compio::test)]
+    async fn copy_conflict_policies() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        let from_dir = path.join("from");
+        let to_dir = path.join("to");
+        fs::create_dir(&from_dir)?;
+        fs::create_dir(&to_dir)?;
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let mut paths = Vec::new();
+        for (name, new_modified, existing_modified) in [
+            ("newer.txt", now, now - hour),
+            ("older.txt", now - hour, now),
+        ] {
+            let new = from_dir.join(name);
+            fs::write(&new, b"new")?;
+            File::options()
+                .write(true)
+                .open(&new)?
+                .set_modified(new_modified)?;
+            let existing = to_dir.join(name);
+            fs::write(&existing, b"old")?;
+            File::options()
+                .write(true)
+                .open(&existing)?
+                .set_modified(existing_modified)?;
+            paths.push(new);
+        }
+
+        operation_copy_with_policy(paths.clone(), to_dir.clone(), ConflictPolicy::Skip)
+            .await
+            .expect("Copy operation should have succeeded");
+        assert_eq!(fs::read(to_dir.join("newer.txt"))?, b"old");
+        assert_eq!(fs::read(to_dir.join("older.txt"))?, b"old");
+
+        operation_copy_with_policy(paths.clone(), to_dir.clone(), ConflictPolicy::ReplaceOlder)
+            .await
+            .expect("Copy operation should have succeeded");
+        assert_eq!(
+            fs::read(to_dir.join("newer.txt"))?,
+            b"new",
+            "Older existing file should be replaced"
+        );
+        assert_eq!(
+            fs::read(to_dir.join("older.txt"))?,
+            b"old",
+            "Newer existing file should be kept"
+        );
+
+        operation_copy_with_policy(paths, to_dir.clone(), ConflictPolicy::KeepBoth)
+            .await
+            .expect("Copy operation should have succeeded");
+        assert_eq!(fs::read(to_dir.join("older.txt"))?, b"old");
+        let kept = to_dir.join(format!("older ({} 1).txt", fl!("copy_noun")));
+        assert_eq!(fs::read(kept)?, b"new");
+
+        Ok(())
+    }
+
+    #[test(compio::test)]
+    async fn move_conflict_policies() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+
+        // Moves on the same file system must not replace existing items by renaming
+        let from_dir = path.join("from");
+        let to_dir = path.join("to");
+        fs::create_dir(&from_dir)?;
+        fs::create_dir(&to_dir)?;
+        let new = from_dir.join("a.txt");
+        fs::write(&new, b"new")?;
+        fs::write(to_dir.join("a.txt"), b"old")?;
+        fs::write(from_dir.join("b.txt"), b"moved")?;
+
+        operation_move_with_policy(
+            vec![new.clone(), from_dir.join("b.txt")],
+            to_dir.clone(),
+            ConflictPolicy::Skip,
+        )
+        .await
+        .expect("Move operation should have succeeded");
+        assert_eq!(fs::read(to_dir.join("a.txt"))?, b"old");
+        assert_eq!(fs::read(&new)?, b"new", "Skipped item should not be moved");
+        assert_eq!(fs::read(to_dir.join("b.txt"))?, b"moved");
+
+        operation_move_with_policy(vec![new.clone()], to_dir.clone(), ConflictPolicy::KeepBoth)
+            .await
+            .expect("Move operation should have succeeded");
+        assert_eq!(fs::read(to_dir.join("a.txt"))?, b"old");
+        let kept = to_dir.join(format!("a ({} 1).txt", fl!("copy_noun")));
+        assert_eq!(fs::read(kept)?, b"new");
+        assert!(!new.exists());
+
+        Ok(())
+    }
+
+    #[test(compio::test)]
+    async fn compress_items_from_different_folders() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+        let first = path.join("first");
+        let nested = path.join("second").join("nested");
+        fs::create_dir(&first)?;
+        fs::create_dir_all(&nested)?;
+        fs::write(first.join("a.txt"), b"a")?;
+        fs::write(nested.join("b.txt"), b"b")?;
+
+        // Items added from another folder are stored at the top of the archive too
+        let to = first.join("archive.zip");
+        let (tx, _rx) = mpsc::channel(1);
+        Operation::Compress {
+            paths: vec![first.join("a.txt"), nested],
+            to: to.clone(),
+            archive_type: ArchiveType::Zip,
+            level: CompressionLevel::default(),
+            password: None,
+        }
+        .perform(
+            &sync::Mutex::new(tx).into(),
+            Controller::default(),
+            false,
+            ConflictPolicy::Ask,
+        )
+        .await
+        .expect("Compress operation should have succeeded");
+
+        let archive = zip::ZipArchive::new(File::open(&to)?).expect("Archive should be readable");
+        let mut names: Vec<_> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, ["a.txt", "nested/", "nested/b.txt"]);
+
+        Ok(())
+    }
+
+    #[test(compio::test)]
+    async fn compress_tar_zst() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+        let folder = path.join("folder");
+        fs::create_dir(&folder)?;
+        fs::write(folder.join("a.txt"), b"a")?;
+
+        let to = path.join("archive.tar.zst");
+        assert_eq!(ArchiveType::from_path(&to), Some(ArchiveType::TarZst));
+        let (tx, _rx) = mpsc::channel(1);
+        Operation::Compress {
+            paths: vec![folder],
+            to: to.clone(),
+            archive_type: ArchiveType::TarZst,
+            level: CompressionLevel::Best,
+            password: None,
+        }
+        .perform(
+            &sync::Mutex::new(tx).into(),
+            Controller::default(),
+            false,
+            ConflictPolicy::Ask,
+        )
+        .await
+        .expect("Compress operation should have succeeded");
+
+        // The compression must be finished for the archive to be read to the end
+        let mut archive = tar::Archive::new(zstd::Decoder::new(File::open(&to)?)?);
+        let mut names = Vec::new();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                names.push(entry.path()?.to_string_lossy().into_owned());
+            }
+        }
+        assert_eq!(names, ["folder/a.txt"]);
+
+        Ok(())
+    }
+
+    #[test(compio::test)]
+    async fn compress_7z_with_password() -> io::Result<()> {
+        let fs = empty_fs()?;
+        let path = fs.path();
+        let folder = path.join("folder");
+        fs::create_dir(&folder)?;
+        fs::write(folder.join("a.txt"), b"a")?;
+
+        let to = path.join("archive.7z");
+        assert_eq!(ArchiveType::from_path(&to), Some(ArchiveType::SevenZ));
+        let (tx, _rx) = mpsc::channel(1);
+        Operation::Compress {
+            paths: vec![folder],
+            to: to.clone(),
+            archive_type: ArchiveType::SevenZ,
+            level: CompressionLevel::Fast,
+            password: Some("secret".to_string()),
+        }
+        .perform(
+            &sync::Mutex::new(tx).into(),
+            Controller::default(),
+            false,
+            ConflictPolicy::Ask,
+        )
+        .await
+        .expect("Compress operation should have succeeded");
+
+        // The archive can only be read with the password
+        let extracted = path.join("extracted");
+        assert!(
+            sevenz_rust::decompress_file_with_password(&to, &extracted, "wrong".into()).is_err()
+        );
+        sevenz_rust::decompress_file_with_password(&to, &extracted, "secret".into())
+            .map_err(io::Error::other)?;
+        assert_eq!(fs::read(extracted.join("folder").join("a.txt"))?, b"a");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,329 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copies, moves, renames and deletes of items on network locations whose files a mounter reads
+//! and changes itself, like sftp servers browsed without a gvfs mount. Items are given as URIs,
+//! with local items as `file://` URIs, so that they can be copied in both directions.
+
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+use super::{Controller, OperationError, OperationSelection};
+use crate::{
+    fl,
+    mounter::{self, NetworkEntry},
+};
+
+/// Bytes copied at once, progress is reported after each chunk
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+fn local_path(uri: &str) -> Option<PathBuf> {
+    let url = Url::parse(uri).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+    url.to_file_path().ok()
+}
+
+/// Name of the item of a URI, decoded
+pub fn uri_name(uri: &str) -> Cow<'_, str> {
+    let name = uri
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    if name.is_empty() {
+        return fl!("unknown-folder").into();
+    }
+    percent_encoding::percent_decode_str(name).decode_utf8_lossy()
+}
+
+/// URI of the folder containing the item of a URI
+pub fn parent_uri(uri: &str) -> Option<String> {
+    let mut url = Url::parse(uri).ok()?;
+    if url.path().trim_matches('/').is_empty() {
+        return None;
+    }
+    url.path_segments_mut().ok()?.pop_if_empty().pop().push("");
+    Some(url.into())
+}
+
+pub fn child_uri(uri: &str, name: &str) -> Option<String> {
+    let mut url = Url::parse(uri).ok()?;
+    url.path_segments_mut().ok()?.pop_if_empty().push(name);
+    Some(url.into())
+}
+
+/// Name of the folder containing the first item, for progress messages
+pub fn uris_parent_name(uris: &[String]) -> Cow<'_, str> {
+    match uris.first().and_then(|uri| parent_uri(uri)) {
+        Some(parent) => uri_name(&parent).into_owned().into(),
+        None => fl!("unknown-folder").into(),
+    }
+}
+
+fn error(uri: &str, err: impl ToString) -> OperationError {
+    OperationError::from_str(format!("{}: {}", uri_name(uri), err.to_string()))
+}
+
+fn check(controller: &Controller) -> Result<(), OperationError> {
+    futures::executor::block_on(controller.check()).map_err(OperationError::from_str)
+}
+
+/// Symbolic links are not followed, so that folders they point to are not copied or removed
+fn metadata(uri: &str) -> io::Result<NetworkEntry> {
+    let Some(path) = local_path(uri) else {
+        return mounter::network_metadata(uri);
+    };
+    let metadata = fs::symlink_metadata(&path)?;
+    Ok(NetworkEntry {
+        name: uri_name(uri).into_owned(),
+        uri: uri.to_string(),
+        is_dir: metadata.is_dir(),
+        size: metadata.len(),
+    })
+}
+
+fn read_dir(uri: &str) -> io::Result<Vec<NetworkEntry>> {
+    let Some(path) = local_path(uri) else {
+        return mounter::network_read_dir(uri);
+    };
+    let mut entries = Vec::new();
+    for entry_res in fs::read_dir(path)? {
+        let entry = entry_res?;
+        let metadata = entry.metadata()?;
+        let Ok(url) = Url::from_file_path(entry.path()) else {
+            continue;
+        };
+        entries.push(NetworkEntry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            uri: url.into(),
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+        });
+    }
+    Ok(entries)
+}
+
+fn open(uri: &str) -> io::Result<Box<dyn Read + Send>> {
+    match local_path(uri) {
+        Some(path) => Ok(Box::new(fs::File::open(path)?)),
+        None => mounter::network_open(uri),
+    }
+}
+
+fn create(uri: &str) -> io::Result<Box<dyn Write + Send>> {
+    match local_path(uri) {
+        Some(path) => Ok(Box::new(fs::File::create(path)?)),
+        None => mounter::network_create(uri),
+    }
+}
+
+fn create_dir(uri: &str) -> io::Result<()> {
+    match local_path(uri) {
+        Some(path) => fs::create_dir(path),
+        None => mounter::network_create_dir(uri),
+    }
+}
+
+fn remove(entry: &NetworkEntry) -> io::Result<()> {
+    match local_path(&entry.uri) {
+        Some(path) if entry.is_dir => fs::remove_dir(path),
+        Some(path) => fs::remove_file(path),
+        None => mounter::network_remove(&entry.uri),
+    }
+}
+
+/// Name for a copy that is not taken in the destination folder, like copies in the same folder
+fn unique_name(entry: &NetworkEntry, taken: &[String]) -> String {
+    let path = Path::new(&entry.name);
+    let (stem, ext_opt) = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) if !entry.is_dir => {
+            (stem.to_string_lossy(), Some(ext.to_string_lossy()))
+        }
+        _ => (Cow::from(entry.name.as_str()), None),
+    };
+    let mut name = entry.name.clone();
+    let mut n = 1;
+    while taken.contains(&name) {
+        name = match &ext_opt {
+            Some(ext) => format!("{} ({} {}).{}", stem, fl!("copy_noun"), n, ext),
+            None => format!("{} ({} {})", stem, fl!("copy_noun"), n),
+        };
+        n += 1;
+    }
+    name
+}
+
+/// Items to copy with their destinations, folders before their contents
+fn plan(
+    entry: NetworkEntry,
+    to: String,
+    items: &mut Vec<(NetworkEntry, String)>,
+) -> Result<(), OperationError> {
+    if entry.is_dir {
+        let children = read_dir(&entry.uri).map_err(|err| error(&entry.uri, err))?;
+        items.push((entry, to.clone()));
+        for child in children {
+            let child_to =
+                child_uri(&to, &child.name).ok_or_else(|| error(&to, "cannot be a folder"))?;
+            plan(child, child_to, items)?;
+        }
+    } else {
+        items.push((entry, to));
+    }
+    Ok(())
+}
+
+/// Copy items into the folder `to`, removing them afterwards when moving. Items with names that
+/// are taken in the folder are copied with a new name, so nothing is replaced.
+pub fn transfer(
+    uris: &[String],
+    to: &str,
+    remove_source: bool,
+    controller: &Controller,
+) -> Result<OperationSelection, OperationError> {
+    let mut taken: Vec<String> = read_dir(to)
+        .map_err(|err| error(to, err))?
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect();
+    let mut items = Vec::new();
+    let mut selected = Vec::new();
+    for uri in uris {
+        check(controller)?;
+        let entry = metadata(uri).map_err(|err| error(uri, err))?;
+        let name = unique_name(&entry, &taken);
+        let item_to = child_uri(to, &name).ok_or_else(|| error(to, "cannot be a folder"))?;
+        if let Some(path) = local_path(&item_to) {
+            selected.push(path);
+        }
+        taken.push(name);
+        plan(entry, item_to, &mut items)?;
+    }
+
+    let total = items
+        .iter()
+        .map(|(entry, _)| if entry.is_dir { 0 } else { entry.size })
+        .sum::<u64>()
+        .max(1);
+    let mut copied = 0;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    for (entry, item_to) in items.iter() {
+        check(controller)?;
+        if entry.is_dir {
+            create_dir(item_to).map_err(|err| error(item_to, err))?;
+            continue;
+        }
+        let mut reader = open(&entry.uri).map_err(|err| error(&entry.uri, err))?;
+        let mut writer = create(item_to).map_err(|err| error(item_to, err))?;
+        loop {
+            check(controller)?;
+            let count = reader
+                .read(&mut buffer)
+                .map_err(|err| error(&entry.uri, err))?;
+            if count == 0 {
+                break;
+            }
+            writer
+                .write_all(&buffer[..count])
+                .map_err(|err| error(item_to, err))?;
+            copied += count as u64;
+            controller.set_progress(copied as f32 / total as f32);
+        }
+        writer.flush().map_err(|err| error(item_to, err))?;
+    }
+
+    // Sources are only removed once everything was copied, contents before their folders
+    if remove_source {
+        for (entry, _) in items.iter().rev() {
+            remove(entry).map_err(|err| error(&entry.uri, err))?;
+        }
+    }
+
+    Ok(OperationSelection {
+        ignored: Vec::new(),
+        selected,
+        conflicts: Vec::new(),
+    })
+}
+
+fn remove_all(entry: &NetworkEntry, controller: &Controller) -> Result<(), OperationError> {
+    check(controller)?;
+    if entry.is_dir {
+        for child in read_dir(&entry.uri).map_err(|err| error(&entry.uri, err))? {
+            remove_all(&child, controller)?;
+        }
+    }
+    remove(entry).map_err(|err| error(&entry.uri, err))
+}
+
+/// Permanently delete items and the contents of folders
+pub fn delete(uris: &[String], controller: &Controller) -> Result<(), OperationError> {
+    for (i, uri) in uris.iter().enumerate() {
+        controller.set_progress(i as f32 / uris.len() as f32);
+        let entry = metadata(uri).map_err(|err| error(uri, err))?;
+        remove_all(&entry, controller)?;
+    }
+    Ok(())
+}
+
+pub fn rename(from: &str, to: &str) -> Result<(), OperationError> {
+    mounter::network_rename(from, to).map_err(|err| error(from, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{child_uri, parent_uri, unique_name, uri_name, uris_parent_name};
+    use crate::{fl, mounter::NetworkEntry};
+
+    #[test]
+    fn network_uris() {
+        let uri = "sftp://host/home/alice/My%20Files";
+        assert_eq!(uri_name(uri), "My Files");
+        assert_eq!(parent_uri(uri).as_deref(), Some("sftp://host/home/alice/"));
+        assert_eq!(parent_uri("sftp://host/"), None);
+        assert_eq!(
+            child_uri("sftp://host/home/", "a b").as_deref(),
+            Some("sftp://host/home/a%20b")
+        );
+        assert_eq!(
+            uris_parent_name(&[uri.to_string(), "sftp://host/tmp".to_string()]),
+            "alice"
+        );
+    }
+
+    #[test]
+    fn network_unique_name() {
+        let file = NetworkEntry {
+            name: "notes.txt".to_string(),
+            uri: "sftp://host/notes.txt".to_string(),
+            is_dir: false,
+            size: 3,
+        };
+        assert_eq!(unique_name(&file, &[]), "notes.txt");
+        assert_eq!(
+            unique_name(&file, &["notes.txt".to_string()]),
+            format!("notes ({} 1).txt", fl!("copy_noun"))
+        );
+
+        let dir = NetworkEntry {
+            name: "photos.old".to_string(),
+            uri: "sftp://host/photos.old".to_string(),
+            is_dir: true,
+            size: 0,
+        };
+        let taken = [
+            "photos.old".to_string(),
+            format!("photos.old ({} 1)", fl!("copy_noun")),
+        ];
+        assert_eq!(
+            unique_name(&dir, &taken),
+            format!("photos.old ({} 2)", fl!("copy_noun"))
+        );
+    }
+}
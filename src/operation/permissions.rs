@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Applying the permissions and ownership of a folder to everything inside it
+
+use std::{
+    fs, io,
+    os::unix::fs::{lchown, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+use crate::fl;
+
+/// Most failed items listed in the error summary
+const ERROR_SUMMARY_ITEMS: usize = 10;
+
+/// Mode for an item inside the folder. Folders get the folder mode. Files get its read and write
+/// bits, and keep their own execute bits where the new mode allows reading.
+pub fn enclosed_mode(mode: u32, current: u32, is_dir: bool) -> u32 {
+    if is_dir {
+        mode & 0o7777
+    } else {
+        let execute = current & 0o111 & ((mode & 0o444) >> 2);
+        (mode & 0o666) | execute | (current & 0o7000)
+    }
+}
+
+/// The folder and everything inside it, children before their parents so that a folder is not
+/// made unreadable before its contents are changed. Items that cannot be read are returned as
+/// errors.
+pub fn enclosed_paths(path: &Path) -> (Vec<PathBuf>, Vec<(PathBuf, String)>) {
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+    for entry_res in WalkDir::new(path).contents_first(true) {
+        match entry_res {
+            Ok(entry) => paths.push(entry.into_path()),
+            Err(err) => {
+                let err_path = err.path().unwrap_or(path).to_path_buf();
+                errors.push((err_path, err.to_string()));
+            }
+        }
+    }
+    (paths, errors)
+}
+
+/// Apply to a single item, only changing what differs
+pub fn apply(path: &Path, mode: u32, uid: u32, gid: u32) -> io::Result<()> {
+    let mut metadata = fs::symlink_metadata(path)?;
+    // Changing ownership clears the setuid and setgid bits, so it is done first and the mode is
+    // read again, so that the bits are not set again for the new owner
+    if metadata.uid() != uid || metadata.gid() != gid {
+        lchown(
+            path,
+            (metadata.uid() != uid).then_some(uid),
+            (metadata.gid() != gid).then_some(gid),
+        )?;
+        metadata = fs::symlink_metadata(path)?;
+    }
+    // Symlinks do not have permissions of their own
+    if !metadata.file_type().is_symlink() {
+        let new_mode = enclosed_mode(mode, metadata.mode(), metadata.is_dir());
+        if metadata.mode() & 0o7777 != new_mode {
+            fs::set_permissions(path, fs::Permissions::from_mode(new_mode))?;
+        }
+    }
+    Ok(())
+}
+
+/// Error shown when some items could not be changed, listing the first ones
+pub fn error_summary(errors: &[(PathBuf, String)]) -> String {
+    let mut summary = fl!("permission-errors", items = errors.len());
+    for (path, err) in errors.iter().take(ERROR_SUMMARY_ITEMS) {
+        summary.push_str(&format!("\n{}: {}", path.display(), err));
+    }
+    if errors.len() > ERROR_SUMMARY_ITEMS {
+        summary.push('\n');
+        summary.push_str(&fl!(
+            "permission-errors-more",
+            items = errors.len() - ERROR_SUMMARY_ITEMS
+        ));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, enclosed_mode, enclosed_paths};
+    use std::{
+        fs, io,
+        os::unix::fs::{MetadataExt, PermissionsExt},
+    };
+    use tempfile::TempDir;
+
+    #[test]
+    fn enclosed_permissions() -> io::Result<()> {
+        assert_eq!(enclosed_mode(0o750, 0o644, true), 0o750);
+        assert_eq!(enclosed_mode(0o750, 0o755, false), 0o750);
+        assert_eq!(enclosed_mode(0o750, 0o644, false), 0o640);
+        assert_eq!(enclosed_mode(0o700, 0o4755, false), 0o4700);
+
+        let dir = TempDir::new()?;
+        let sub = dir.path().join("sub");
+        fs::create_dir(&sub)?;
+        let script = sub.join("script.sh");
+        fs::write(&script, b"")?;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+        let text = sub.join("text.txt");
+        fs::write(&text, b"")?;
+        fs::set_permissions(&text, fs::Permissions::from_mode(0o666))?;
+
+        let (paths, errors) = enclosed_paths(dir.path());
+        assert!(errors.is_empty());
+        assert_eq!(paths.len(), 4);
+        assert_eq!(paths.last().map(|path| path.as_path()), Some(dir.path()));
+
+        let metadata = fs::metadata(dir.path())?;
+        for path in paths.iter() {
+            apply(path, 0o750, metadata.uid(), metadata.gid())?;
+        }
+        let mode = |path| fs::metadata(path).map(|metadata| metadata.mode() & 0o7777);
+        assert_eq!(mode(&sub)?, 0o750);
+        assert_eq!(mode(&script)?, 0o750);
+        assert_eq!(mode(&text)?, 0o640);
+
+        let (paths, errors) = enclosed_paths(&dir.path().join("missing"));
+        assert!(paths.is_empty());
+        assert_eq!(errors.len(), 1);
+
+        Ok(())
+    }
+}
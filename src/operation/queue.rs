@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Copies and moves that run one at a time, so that long transfers do not compete for a drive
+
+use std::{collections::VecDeque, time::Duration};
+
+use super::Controller;
+use crate::fl;
+
+/// Order of queued operations, by operation id
+#[derive(Debug, Default)]
+pub struct OperationQueue {
+    running_opt: Option<u64>,
+    waiting: VecDeque<u64>,
+}
+
+impl OperationQueue {
+    /// Add an operation, returning true if it can start now
+    pub fn push(&mut self, id: u64) -> bool {
+        if self.running_opt.is_none() {
+            self.running_opt = Some(id);
+            true
+        } else {
+            self.waiting.push_back(id);
+            false
+        }
+    }
+
+    /// Remove a finished or cancelled operation, returning the operation that starts next
+    pub fn finish(&mut self, id: u64) -> Option<u64> {
+        if self.running_opt == Some(id) {
+            self.running_opt = self.waiting.pop_front();
+            self.running_opt
+        } else {
+            self.waiting.retain(|waiting| *waiting != id);
+            None
+        }
+    }
+
+    pub fn running(&self) -> Option<u64> {
+        self.running_opt
+    }
+
+    /// Waiting operations, in the order they start
+    pub fn waiting(&self) -> impl Iterator<Item = u64> + '_ {
+        self.waiting.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.waiting.len() + usize::from(self.running_opt.is_some())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running_opt.is_none() && self.waiting.is_empty()
+    }
+
+    /// Move a waiting operation one place earlier or later, returning true if it moved
+    pub fn reorder(&mut self, id: u64, earlier: bool) -> bool {
+        let Some(index) = self.waiting.iter().position(|waiting| *waiting == id) else {
+            return false;
+        };
+        let other = if earlier {
+            match index.checked_sub(1) {
+                Some(some) => some,
+                None => return false,
+            }
+        } else {
+            index + 1
+        };
+        if other >= self.waiting.len() {
+            return false;
+        }
+        self.waiting.swap(index, other);
+        true
+    }
+}
+
+/// Transfer rate and remaining time of an operation that copies data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transfer {
+    pub bytes_per_second: u64,
+    pub remaining_opt: Option<Duration>,
+}
+
+impl Transfer {
+    /// Average rate while running, or `None` until there is enough data for an estimate
+    pub fn new(done: u64, total: u64, running_time: Duration) -> Option<Self> {
+        let seconds = running_time.as_secs_f64();
+        if done == 0 || seconds < 1.0 {
+            return None;
+        }
+        let rate = done as f64 / seconds;
+        Some(Self {
+            bytes_per_second: rate as u64,
+            remaining_opt: (total > done)
+                .then(|| Duration::from_secs_f64((total - done) as f64 / rate)),
+        })
+    }
+
+    pub fn from_controller(controller: &Controller) -> Option<Self> {
+        let (done, total) = controller.bytes();
+        Self::new(done, total, controller.running_time())
+    }
+}
+
+/// Rounded remaining time, like "3 minutes left"
+pub fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.as_secs();
+    if seconds < 60 {
+        fl!("seconds-left", seconds = seconds.max(1))
+    } else if seconds < 60 * 60 {
+        fl!("minutes-left", minutes = seconds.div_ceil(60))
+    } else {
+        fl!("hours-left", hours = seconds.div_ceil(60 * 60))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OperationQueue, Transfer};
+    use std::time::Duration;
+
+    #[test]
+    fn operation_queue_order() {
+        let mut queue = OperationQueue::default();
+        assert!(queue.push(1));
+        assert!(!queue.push(2));
+        assert!(!queue.push(3));
+        assert!(!queue.push(4));
+        assert_eq!(queue.len(), 4);
+
+        // Reordering only applies to waiting operations
+        assert!(!queue.reorder(1, false));
+        assert!(queue.reorder(4, true));
+        assert!(!queue.reorder(2, true));
+        assert_eq!(queue.waiting().collect::<Vec<_>>(), [2, 4, 3]);
+
+        // Cancelling a waiting operation does not start another one
+        assert_eq!(queue.finish(4), None);
+        assert_eq!(queue.running(), Some(1));
+        assert_eq!(queue.finish(1), Some(2));
+        assert_eq!(queue.finish(2), Some(3));
+        assert_eq!(queue.finish(3), None);
+        assert!(queue.is_empty());
+        assert!(queue.push(5));
+    }
+
+    #[test]
+    fn transfer_estimate() {
+        assert_eq!(Transfer::new(0, 100, Duration::from_secs(2)), None);
+        assert_eq!(Transfer::new(50, 100, Duration::from_millis(500)), None);
+        assert_eq!(
+            Transfer::new(100, 300, Duration::from_secs(2)),
+            Some(Transfer {
+                bytes_per_second: 50,
+                remaining_opt: Some(Duration::from_secs(4)),
+            })
+        );
+        assert_eq!(
+            Transfer::new(300, 300, Duration::from_secs(3)).and_then(|x| x.remaining_opt),
+            None
+        );
+    }
+}
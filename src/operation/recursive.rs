@@ -4,10 +4,26 @@ use compio::BufResult;
 use std::future::Future;
 use std::pin::Pin;
 use std::time::Instant;
-use std::{cell::Cell, error::Error, fs, ops::ControlFlow, path::PathBuf, rc::Rc};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    error::Error,
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 use walkdir::WalkDir;
 
-use super::{copy_unique_path, Controller, OperationSelection, ReplaceResult};
+use super::{
+    copy_unique_path, valid_rename, Controller, OperationSelection, ReplaceResult, Resolution,
+};
+use crate::{
+    config::ConflictPolicy,
+    fl,
+    tab::{format_size, Quota},
+    tag,
+};
 
 pub enum Method {
     Copy,
@@ -16,11 +32,15 @@ pub enum Method {
 
 pub struct Context {
     buf: Vec<u8>,
+    conflict_policy: ConflictPolicy,
     controller: Controller,
     on_progress: Box<dyn OnProgress>,
     on_replace: Pin<Box<dyn OnReplace>>,
     pub(crate) op_sel: OperationSelection,
+    /// Quota of each destination device, read once and then charged with the items written
+    quotas: HashMap<u64, Option<Quota>>,
     replace_result_opt: Option<ReplaceResult>,
+    write_through: bool,
 }
 
 pub trait OnProgress: Fn(&Op, &Progress) + 'static {}
@@ -40,14 +60,29 @@ impl Context {
         Self {
             // 128K is the optimal upper size of a buffer.
             buf: vec![0u8; 128 * 1024],
+            conflict_policy: ConflictPolicy::Ask,
             controller,
             on_progress: Box::new(|_op, _progress| {}),
             on_replace: Box::pin(|_op| Box::pin(async { ReplaceResult::Cancel })),
             op_sel: OperationSelection::default(),
+            quotas: HashMap::new(),
             replace_result_opt: None,
+            write_through: false,
         }
     }
 
+    /// Sync written data to disk before each progress update
+    pub fn write_through(mut self, write_through: bool) -> Self {
+        self.write_through = write_through;
+        self
+    }
+
+    /// Handle items that already exist without asking, unless the policy is to ask
+    pub fn conflict_policy(mut self, conflict_policy: ConflictPolicy) -> Self {
+        self.conflict_policy = conflict_policy;
+        self
+    }
+
     pub async fn recursive_copy_or_move(
         &mut self,
         from_to_pairs: Vec<(PathBuf, PathBuf)>,
@@ -125,10 +160,37 @@ impl Context {
             ops.push(cleanup_op);
         }
 
+        // Fail early if a destination device does not have enough free space
+        let mut required_by_device = Vec::<(Option<u64>, PathBuf, u64)>::new();
+        for op in ops.iter() {
+            let required = self.required_space(op);
+            if required == 0 {
+                continue;
+            }
+            // The destination may not exist yet, so use the closest existing parent
+            let Some(path) = op.to.ancestors().find(|ancestor| ancestor.exists()) else {
+                continue;
+            };
+            let device = device(path);
+            match required_by_device.iter_mut().find(|(id, ..)| *id == device) {
+                Some((_, _, total)) => *total += required,
+                None => required_by_device.push((device, path.to_path_buf(), required)),
+            }
+        }
+        for (_, path, required) in required_by_device {
+            self.check_free_space(&path, required)?;
+        }
+        self.controller
+            .set_total_bytes(ops.iter().map(Op::required_space).sum());
+
         let total_ops = ops.len();
         for (current_ops, mut op) in ops.into_iter().enumerate() {
             self.controller.check().await?;
 
+            // Free space may have changed since the operation started
+            let required = self.required_space(&op);
+            self.check_free_space(&op.to, required)?;
+
             let progress = Progress {
                 current_ops,
                 total_ops,
@@ -142,6 +204,17 @@ impl Context {
                     op.kind, op.from, op.to, err
                 )
             })? {
+                if let Some(quota) = self.quota(&op.to) {
+                    quota.used = quota.used.saturating_add(required);
+                }
+                // Items inside of folders have their own ops, so only this path is moved
+                if matches!(method, Method::Move { .. })
+                    && !op.is_cleanup
+                    && !op.skipped.normal.get()
+                    && !op.skipped.cleanup.get()
+                {
+                    tag::moved(&op.from, &op.to, false);
+                }
                 // The from path is ignored in the operation selection if it is a top level item
                 if self.op_sel.ignored.contains(&op.from) {
                     // So add the to path to the selection
@@ -156,6 +229,81 @@ impl Context {
         Ok(true)
     }
 
+    /// Bytes that `op` adds to its destination, less the size of an existing file that it
+    /// replaces
+    fn required_space(&self, op: &Op) -> u64 {
+        let required = op.required_space();
+        let existing = match fs::symlink_metadata(&op.to) {
+            Ok(metadata) if required > 0 && metadata.is_file() => metadata.len(),
+            _ => return required,
+        };
+        let replace_result = match &self.replace_result_opt {
+            Some(result) => result.clone(),
+            None => match self.conflict_policy {
+                // The user may replace the file, other choices fail when writing if there is
+                // not enough space
+                ConflictPolicy::Ask => ReplaceResult::Replace(false),
+                ConflictPolicy::KeepBoth => ReplaceResult::KeepBoth(false),
+                ConflictPolicy::ReplaceOlder => {
+                    if modified_after(&op.from, &op.to) {
+                        ReplaceResult::Replace(false)
+                    } else {
+                        ReplaceResult::Skip(false)
+                    }
+                }
+                ConflictPolicy::Skip => ReplaceResult::Skip(false),
+            },
+        };
+        match replace_result {
+            ReplaceResult::Replace(_) => required.saturating_sub(existing),
+            ReplaceResult::Skip(_) => 0,
+            _ => required,
+        }
+    }
+
+    /// Return an error describing the shortfall if `path` does not have `required` bytes free,
+    /// or if writing them would exceed the user's disk quota
+    fn check_free_space(&mut self, path: &Path, required: u64) -> Result<(), String> {
+        if required == 0 {
+            return Ok(());
+        }
+        if let Some(available) = free_space(path) {
+            if available < required {
+                return Err(fl!(
+                    "not-enough-space",
+                    path = path.display().to_string(),
+                    shortfall = format_size(required - available)
+                ));
+            }
+        }
+        if let Some(quota) = self.quota(path) {
+            if quota.remaining() < required {
+                return Err(fl!(
+                    "quota-exceeded",
+                    path = path.display().to_string(),
+                    shortfall = format_size(required - quota.remaining()),
+                    limit = format_size(quota.limit)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Quota of the device containing `path`. Reading it parses the mount table and may ask a
+    /// server, so it is only done once per device for the whole operation.
+    fn quota(&mut self, path: &Path) -> Option<&mut Quota> {
+        // The destination may not exist yet, so use the closest existing parent
+        let path = path.ancestors().find(|ancestor| ancestor.exists())?;
+        self.quotas
+            .entry(device(path)?)
+            .or_insert_with(|| {
+                crate::tab::fs_info(path)
+                    .ok()
+                    .and_then(|fs_info| fs_info.quota_opt)
+            })
+            .as_mut()
+    }
+
     pub fn on_progress<F: OnProgress>(mut self, f: F) -> Self {
         self.on_progress = Box::new(f);
         self
@@ -167,32 +315,87 @@ impl Context {
     }
 
     async fn replace(&mut self, op: &Op) -> Result<ControlFlow<bool, PathBuf>, Box<dyn Error>> {
-        let replace_result = match self.replace_result_opt {
-            Some(result) => result,
-            None => (self.on_replace)(op).await,
+        let replace_result = match &self.replace_result_opt {
+            Some(result) => result.clone(),
+            None => match self.conflict_policy {
+                ConflictPolicy::Ask => (self.on_replace)(op).await,
+                ConflictPolicy::KeepBoth => ReplaceResult::KeepBoth(false),
+                ConflictPolicy::ReplaceOlder => {
+                    if modified_after(&op.from, &op.to) {
+                        ReplaceResult::Replace(false)
+                    } else {
+                        ReplaceResult::Skip(false)
+                    }
+                }
+                ConflictPolicy::Skip => ReplaceResult::Skip(false),
+            },
         };
 
-        match replace_result {
+        let flow: Result<_, Box<dyn Error>> = match replace_result {
             ReplaceResult::Replace(apply_to_all) => {
                 if apply_to_all {
-                    self.replace_result_opt = Some(replace_result);
+                    self.replace_result_opt = Some(ReplaceResult::Replace(true));
                 }
                 compio::fs::remove_file(&op.to).await?;
                 Ok(ControlFlow::Continue(op.to.clone()))
             }
-            ReplaceResult::KeepBoth => match op.to.parent() {
-                Some(to_parent) => Ok(ControlFlow::Continue(copy_unique_path(&op.from, to_parent))),
+            ReplaceResult::KeepBoth(apply_to_all) => {
+                if apply_to_all {
+                    self.replace_result_opt = Some(ReplaceResult::KeepBoth(true));
+                }
+                match op.to.parent() {
+                    Some(to_parent) => {
+                        Ok(ControlFlow::Continue(copy_unique_path(&op.from, to_parent)))
+                    }
+                    None => Err(format!("failed to get parent of {:?}", op.to).into()),
+                }
+            }
+            ReplaceResult::Rename(name) if !valid_rename(&name) => {
+                Err(format!("invalid name {:?}", name).into())
+            }
+            ReplaceResult::Rename(name) => match op.to.parent() {
+                Some(to_parent) => {
+                    let to = to_parent.join(name);
+                    // The new name may have been taken since it was chosen
+                    if to.symlink_metadata().is_ok() {
+                        Ok(ControlFlow::Continue(copy_unique_path(&to, to_parent)))
+                    } else {
+                        Ok(ControlFlow::Continue(to))
+                    }
+                }
                 None => Err(format!("failed to get parent of {:?}", op.to).into()),
             },
             ReplaceResult::Skip(apply_to_all) => {
                 if apply_to_all {
-                    self.replace_result_opt = Some(replace_result);
+                    self.replace_result_opt = Some(ReplaceResult::Skip(true));
                 }
                 op.skipped.normal.set(true);
                 Ok(ControlFlow::Break(true))
             }
-            ReplaceResult::Cancel => Ok(ControlFlow::Break(false)),
-        }
+            // Other folders are only offered when restoring from the trash
+            ReplaceResult::Elsewhere(_) | ReplaceResult::Cancel => Ok(ControlFlow::Break(false)),
+        };
+
+        // Only a replaced item keeps the path, and a skipped one stops with `true`
+        let flow = flow?;
+        let resolution = match &flow {
+            ControlFlow::Continue(to) if *to == op.to => Resolution::Replaced,
+            ControlFlow::Continue(to) => Resolution::Renamed(to.clone()),
+            ControlFlow::Break(true) => Resolution::Skipped,
+            ControlFlow::Break(false) => return Ok(flow),
+        };
+        self.op_sel.conflicts.push((op.to.clone(), resolution));
+        Ok(flow)
+    }
+}
+
+/// Whether `from` was modified after `to`, which is false if either time is unknown
+fn modified_after(from: &Path, to: &Path) -> bool {
+    let modified =
+        |path: &Path| fs::symlink_metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(from), modified(to)) {
+        (Ok(from), Ok(to)) => from > to,
+        _ => false,
     }
 }
 
@@ -232,6 +435,21 @@ pub struct Op {
 }
 
 impl Op {
+    /// Bytes that will be written to the destination by this op
+    fn required_space(&self) -> u64 {
+        let writes_data = match self.kind {
+            OpKind::Copy => true,
+            // Moves on the same device are done with a hard link
+            OpKind::Move { .. } => !self.is_cleanup && !same_device(&self.from, &self.to),
+            _ => false,
+        };
+        if writes_data {
+            fs::metadata(&self.from).map_or(0, |metadata| metadata.len())
+        } else {
+            0
+        }
+    }
+
     fn move_cleanup_op(&self) -> Option<Self> {
         let kind = match self.kind {
             OpKind::Copy | OpKind::Move { .. } | OpKind::Symlink { .. } => OpKind::Remove,
@@ -328,10 +546,19 @@ impl Op {
 
                     progress.current_bytes += count as u64;
                     pos += count as u64;
+                    ctx.controller.add_bytes(count as u64);
 
                     // Avoid spamming progress messages too early.
                     let current = Instant::now();
                     if current.duration_since(last_progress_update).as_millis() > 49 {
+                        // Make sure progress reflects what actually reached the disk
+                        if ctx.write_through {
+                            if let Err(why) = to_file.sync_data().await {
+                                ctx.buf = buf_out;
+                                return Err(why.into());
+                            }
+                        }
+
                         last_progress_update = current;
                         (ctx.on_progress)(self, &progress);
 
@@ -427,3 +654,61 @@ impl Op {
         Ok(true)
     }
 }
+
+/// Free space available to the user on the file system containing `path`
+#[cfg(unix)]
+// statvfs field types vary between platforms
+#[allow(clippy::unnecessary_cast)]
+fn free_space(path: &Path) -> Option<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    // The destination may not exist yet, so use the closest existing parent
+    let path = path.ancestors().find(|ancestor| ancestor.exists())?;
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        log::warn!(
+            "failed to get free space of {:?}: {}",
+            path,
+            std::io::Error::last_os_error()
+        );
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_space(_path: &Path) -> Option<u64> {
+    //TODO: free space on other platforms
+    None
+}
+
+#[cfg(unix)]
+fn device(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(unix)]
+fn same_device(from: &Path, to: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(to) = to.ancestors().find(|ancestor| ancestor.exists()) else {
+        return false;
+    };
+    match (fs::metadata(from), fs::metadata(to)) {
+        (Ok(from), Ok(to)) => from.dev() == to.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_device(_from: &Path, _to: &Path) -> bool {
+    false
+}
@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Securely deleting files by overwriting their contents before removing them. File systems that
+//! copy on write, like btrfs, and SSDs write the new data elsewhere, so the old contents may still
+//! be recovered there.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Bytes written at once, progress is reported after each chunk
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Default)]
+pub struct ShredPlan {
+    /// Regular files with their sizes, which are overwritten before they are removed
+    pub files: Vec<(PathBuf, u64)>,
+    /// Symbolic links and other items that are removed without overwriting what they point to,
+    /// and folders after their contents
+    pub removed: Vec<PathBuf>,
+    /// Files with hard links that are not being deleted, which are removed without overwriting
+    /// them as that would also overwrite the other links. They are in `removed` too.
+    pub shared: Vec<PathBuf>,
+}
+
+impl ShredPlan {
+    pub fn new(paths: &[PathBuf]) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for path in paths {
+            // A selected symbolic link is removed, and the folder it points to is left alone
+            for entry in WalkDir::new(path)
+                .follow_root_links(false)
+                .contents_first(true)
+            {
+                let entry = entry.map_err(io::Error::other)?;
+                let metadata = entry.metadata().map_err(io::Error::other)?;
+                entries.push((entry.into_path(), metadata));
+            }
+        }
+
+        // Files with more than one link are only overwritten if all of their links are deleted
+        let mut links = HashMap::<(u64, u64), u64>::new();
+        for (_, metadata) in entries.iter() {
+            if let Some((id, _)) = hard_links(metadata) {
+                *links.entry(id).or_default() += 1;
+            }
+        }
+
+        let mut plan = Self::default();
+        for (path, metadata) in entries {
+            if !metadata.is_file() {
+                plan.removed.push(path);
+                continue;
+            }
+            match hard_links(&metadata) {
+                Some((id, nlink)) => match links.get_mut(&id) {
+                    Some(found) if *found >= nlink => {
+                        // The first link is overwritten, the rest only removed
+                        *found = 0;
+                        plan.files.push((path, metadata.len()));
+                    }
+                    Some(0) => plan.removed.push(path),
+                    _ => {
+                        plan.shared.push(path.clone());
+                        plan.removed.push(path);
+                    }
+                },
+                None => plan.files.push((path, metadata.len())),
+            }
+        }
+        Ok(plan)
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Identifier and number of links of a file with more than one hard link
+#[cfg(unix)]
+fn hard_links(metadata: &fs::Metadata) -> Option<((u64, u64), u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1).then(|| ((metadata.dev(), metadata.ino()), metadata.nlink()))
+}
+
+#[cfg(not(unix))]
+fn hard_links(_metadata: &fs::Metadata) -> Option<((u64, u64), u64)> {
+    None
+}
+
+/// Overwrite the contents of a file with zeros and flush them to the disk. `progress` is called
+/// with the bytes written after each chunk, and stops the overwrite if it returns an error.
+pub fn overwrite(
+    path: &Path,
+    size: u64,
+    mut progress: impl FnMut(u64) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = vec![0; CHUNK_SIZE];
+    let mut remaining = size;
+    while remaining > 0 {
+        let len = remaining.min(CHUNK_SIZE as u64);
+        file.write_all(&zeros[..len as usize])?;
+        remaining -= len;
+        progress(len)?;
+    }
+    file.sync_all()?;
+    // Remove the size of the file along with its contents
+    file.set_len(0)?;
+    file.sync_all()
+}
+
+/// Remove an item of the plan that is not overwritten
+pub fn remove(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{overwrite, remove, ShredPlan};
+    use std::{fs, io};
+    use tempfile::TempDir;
+
+    #[test]
+    fn shred_folder() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let kept = dir.path().join("kept.txt");
+        fs::write(&kept, b"kept")?;
+        let secret = dir.path().join("secret");
+        fs::create_dir_all(secret.join("nested"))?;
+        fs::write(secret.join("a.txt"), b"password")?;
+        fs::write(
+            secret.join("nested").join("b.txt"),
+            vec![1; 3 * 1024 * 1024],
+        )?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&kept, secret.join("link"))?;
+
+        let plan = ShredPlan::new(std::slice::from_ref(&secret))?;
+        assert_eq!(plan.files.len(), 2);
+        assert_eq!(plan.total_size(), 8 + 3 * 1024 * 1024);
+        assert_eq!(plan.removed.last(), Some(&secret));
+
+        // Contents are overwritten before the file is emptied
+        let (path, size) = &plan.files[0];
+        let mut written = Vec::new();
+        let file = fs::File::open(path)?;
+        overwrite(path, *size, |len| {
+            written.push(len);
+            Ok(())
+        })?;
+        assert_eq!(written.iter().sum::<u64>(), *size);
+        assert_eq!(file.metadata()?.len(), 0);
+
+        for (path, size) in plan.files.iter().skip(1) {
+            overwrite(path, *size, |_| Ok(()))?;
+            fs::remove_file(path)?;
+        }
+        fs::remove_file(path)?;
+        for path in plan.removed.iter() {
+            remove(path)?;
+        }
+        assert!(!secret.exists());
+        assert_eq!(fs::read(&kept)?, b"kept");
+
+        // Stopping the overwrite stops after the current chunk
+        let large = dir.path().join("large.bin");
+        fs::write(&large, vec![1; 3 * 1024 * 1024])?;
+        let mut chunks = 0;
+        let res = overwrite(&large, 3 * 1024 * 1024, |_| {
+            chunks += 1;
+            Err(io::Error::other("cancelled"))
+        });
+        assert!(res.is_err());
+        assert_eq!(chunks, 1);
+        let contents = fs::read(&large)?;
+        assert!(contents[..1024 * 1024].iter().all(|byte| *byte == 0));
+        assert!(contents[1024 * 1024..].iter().all(|byte| *byte == 1));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shred_hard_links() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let secret = dir.path().join("secret");
+        fs::create_dir(&secret)?;
+        fs::write(secret.join("a.txt"), b"password")?;
+        fs::hard_link(secret.join("a.txt"), secret.join("b.txt"))?;
+        let shared = dir.path().join("shared.txt");
+        fs::write(&shared, b"kept")?;
+        fs::hard_link(&shared, secret.join("c.txt"))?;
+
+        let plan = ShredPlan::new(std::slice::from_ref(&secret))?;
+        // Both links of a.txt are deleted, so it is overwritten once
+        assert_eq!(plan.files.len(), 1);
+        assert_eq!(plan.shared, vec![secret.join("c.txt")]);
+        assert!(plan.removed.contains(&secret.join("c.txt")));
+
+        for (path, size) in plan.files.iter() {
+            overwrite(path, *size, |_| Ok(()))?;
+            fs::remove_file(path)?;
+        }
+        for path in plan.removed.iter() {
+            remove(path)?;
+        }
+        assert!(!secret.exists());
+        assert_eq!(fs::read(&shared)?, b"kept");
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shred_folder_link() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let target = dir.path().join("target");
+        fs::create_dir(&target)?;
+        fs::write(target.join("a.txt"), b"kept")?;
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link)?;
+
+        let plan = ShredPlan::new(std::slice::from_ref(&link))?;
+        assert!(plan.files.is_empty());
+        assert_eq!(plan.removed, vec![link.clone()]);
+        for path in plan.removed.iter() {
+            remove(path)?;
+        }
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert_eq!(fs::read(target.join("a.txt"))?, b"kept");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Power state from upower and power-profiles-daemon, used to reduce background work
+
+use cosmic::iced::{
+    futures::{SinkExt, StreamExt},
+    stream, Subscription,
+};
+use std::{any::TypeId, future::pending};
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[zbus(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
+
+#[zbus::proxy(
+    interface = "org.freedesktop.UPower.PowerProfiles",
+    default_service = "org.freedesktop.UPower.PowerProfiles",
+    default_path = "/org/freedesktop/UPower/PowerProfiles"
+)]
+trait PowerProfiles {
+    #[zbus(property)]
+    fn active_profile(&self) -> zbus::Result<String>;
+}
+
+/// Emits true when running on battery or in power saver mode, and false otherwise
+pub fn subscription() -> Subscription<bool> {
+    struct PowerSubscription;
+    Subscription::run_with_id(
+        TypeId::of::<PowerSubscription>(),
+        stream::channel(1, |mut output| async move {
+            if let Err(err) = watch(&mut output).await {
+                log::info!("power state not available: {}", err);
+            }
+            pending().await
+        }),
+    )
+}
+
+enum Change {
+    OnBattery(bool),
+    PowerSaver(bool),
+}
+
+async fn watch(
+    output: &mut cosmic::iced::futures::channel::mpsc::Sender<bool>,
+) -> zbus::Result<()> {
+    let connection = zbus::Connection::system().await?;
+    let upower = UPowerProxy::new(&connection).await?;
+    // Power profiles are optional
+    let profiles_opt = PowerProfilesProxy::new(&connection).await.ok();
+
+    let mut on_battery = upower.on_battery().await.unwrap_or(false);
+    let mut power_saver = match &profiles_opt {
+        Some(profiles) => profiles
+            .active_profile()
+            .await
+            .map_or(false, |profile| profile == "power-saver"),
+        None => false,
+    };
+    let mut last = on_battery || power_saver;
+    let _ = output.send(last).await;
+
+    let on_battery_changes = upower
+        .receive_on_battery_changed()
+        .await
+        .then(|change| async move { Change::OnBattery(change.get().await.unwrap_or(false)) })
+        .boxed();
+    let profile_changes = match &profiles_opt {
+        Some(profiles) => profiles
+            .receive_active_profile_changed()
+            .await
+            .then(|change| async move {
+                Change::PowerSaver(
+                    change
+                        .get()
+                        .await
+                        .map_or(false, |profile| profile == "power-saver"),
+                )
+            })
+            .boxed(),
+        None => futures::stream::pending().boxed(),
+    };
+    let mut changes = futures::stream::select(on_battery_changes, profile_changes);
+    while let Some(change) = changes.next().await {
+        match change {
+            Change::OnBattery(value) => on_battery = value,
+            Change::PowerSaver(value) => power_saver = value,
+        }
+        let saving = on_battery || power_saver;
+        if saving != last {
+            log::info!("power saving {}", saving);
+            last = saving;
+            if output.send(saving).await.is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
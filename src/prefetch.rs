@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Listings of the folders visible in a tab, read ahead so that opening one of them shows its
+//! items right away while it is scanned again. Only local folders are read ahead, and the listings
+//! kept are limited by the number of folders and of items in them.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+/// Folders read ahead at once
+pub const JOBS: usize = 2;
+/// Listings kept, the oldest is dropped first
+const MAX_FOLDERS: usize = 32;
+/// Items kept in all listings together, larger folders are not kept at all
+const MAX_ITEMS: usize = 10_000;
+
+#[derive(Debug)]
+pub struct Prefetch<T> {
+    /// Path of each listing with its number of items, the oldest first
+    listings: VecDeque<(PathBuf, usize, T)>,
+    items: usize,
+    /// Folders read since the tab changed location, which are not read again
+    done: HashSet<PathBuf>,
+}
+
+impl<T> Default for Prefetch<T> {
+    fn default() -> Self {
+        Self {
+            listings: VecDeque::new(),
+            items: 0,
+            done: HashSet::new(),
+        }
+    }
+}
+
+impl<T> Prefetch<T> {
+    /// Whether the folder still has to be read ahead
+    pub fn wanted(&self, path: &Path) -> bool {
+        !self.done.contains(path)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, len: usize, listing: T) {
+        self.remove(&path);
+        self.done.insert(path.clone());
+        if len > MAX_ITEMS {
+            return;
+        }
+        self.listings.push_back((path, len, listing));
+        self.items += len;
+        while self.listings.len() > MAX_FOLDERS || self.items > MAX_ITEMS {
+            let Some((_, len, _)) = self.listings.pop_front() else {
+                break;
+            };
+            self.items -= len;
+        }
+    }
+
+    fn remove(&mut self, path: &Path) -> Option<T> {
+        let i = self.listings.iter().position(|(x, ..)| x == path)?;
+        let (_, len, listing) = self.listings.remove(i)?;
+        self.items -= len;
+        Some(listing)
+    }
+
+    /// Start reading ahead the folders of a new location, returning the listing of the location
+    /// if it was read ahead. Listings are only used once, as the location is scanned again.
+    pub fn change_location(&mut self, path_opt: Option<&Path>) -> Option<T> {
+        self.done.clear();
+        self.remove(path_opt?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Prefetch, MAX_FOLDERS, MAX_ITEMS};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn prefetch_budget() {
+        let mut prefetch = Prefetch::default();
+        let a = PathBuf::from("/home/user/a");
+        let b = PathBuf::from("/home/user/b");
+        assert!(prefetch.wanted(&a));
+        prefetch.insert(a.clone(), 10, "a");
+        prefetch.insert(b.clone(), MAX_ITEMS - 10, "b");
+        assert!(!prefetch.wanted(&a));
+
+        // Leaving the folder lets its subfolders be read again, and listings are used once
+        assert_eq!(prefetch.change_location(Some(&a)), Some("a"));
+        assert!(prefetch.wanted(&a));
+        assert_eq!(prefetch.change_location(Some(&a)), None);
+
+        // The oldest listings are dropped when there are too many items
+        prefetch.insert(a.clone(), 20, "a");
+        assert_eq!(prefetch.change_location(Some(&b)), None);
+        assert_eq!(prefetch.change_location(Some(&a)), Some("a"));
+
+        // Folders too large to keep are still not read again
+        prefetch.insert(b.clone(), MAX_ITEMS + 1, "b");
+        assert!(!prefetch.wanted(&b));
+        assert_eq!(prefetch.change_location(Some(&b)), None);
+
+        for i in 0..=MAX_FOLDERS {
+            prefetch.insert(PathBuf::from(format!("/{}", i)), 1, "folder");
+        }
+        assert_eq!(prefetch.change_location(Some(Path::new("/0"))), None);
+        assert_eq!(
+            prefetch.change_location(Some(Path::new("/1"))),
+            Some("folder")
+        );
+    }
+}
@@ -16,6 +16,7 @@ use cosmic::{
         widget::{
             horizontal_rule, rule,
             scrollable::{self, AbsoluteOffset, Viewport},
+            vertical_rule,
         },
         Alignment,
         Border,
@@ -49,11 +50,12 @@ use serde::{Deserialize, Serialize};
 use std::{
     cell::Cell,
     cmp::Ordering,
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error,
+    ffi::{OsStr, OsString},
     fmt::{self, Display},
     fs::{self, File, Metadata},
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
     os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
     sync::{atomic, Arc, Mutex, RwLock},
@@ -63,23 +65,64 @@ use tokio::sync::mpsc;
 use walkdir::WalkDir;
 
 use crate::{
-    app::{Action, PreviewItem, PreviewKind},
+    app::{Action, ArchiveType, PreviewItem, PreviewKind},
+    attributes::{self, AclEntry, AclTag, Attributes},
+    audio_preview::{self, format_duration, AudioMetadata, AudioPlayer},
+    checksum::{self, ChecksumAlgorithm},
     clipboard::{ClipboardCopy, ClipboardKind, ClipboardPaste},
-    config::{DesktopConfig, IconSizes, TabConfig, ICON_SCALE_MAX, ICON_SIZE_GRID},
+    config::{
+        ColumnWidths, DesktopConfig, DetailsTab, IconSizes, SlideshowConfig, TabConfig, TabTitle,
+        COLUMN_WIDTH_MAX, COLUMN_WIDTH_MIN, ICON_SCALE_MAX, ICON_SIZE_GRID,
+    },
+    date_view::{self, DateFolder},
     dialog::DialogKind,
+    document_preview::{self, HighlightedLine},
+    emblem::{path_emblems, Emblem, FolderEmblems},
     fl,
+    image_metadata::{self, ImageMetadata},
     localize::{LANGUAGE_SORTER, LOCALE},
     menu, mime_app,
-    mime_icon::{mime_for_path, mime_icon},
-    mounter::MOUNTERS,
+    mime_icon::{mime_for_path, mime_icon, sniff_mime},
+    mounter::{MounterItem, MOUNTERS},
     mouse_area,
     operation::Controller,
+    prefetch::{self, Prefetch},
+    tag::{read_tags, tagged_paths, ColorTag},
+    thumbnail_cache,
     thumbnailer::thumbnailer,
+    trash_policy,
+    versions::{self, FileVersion},
 };
-use uzers::{get_group_by_gid, get_user_by_uid};
+use uzers::{get_group_by_gid, get_group_by_name, get_user_by_name, get_user_by_uid};
 
 pub const DOUBLE_CLICK_DURATION: Duration = Duration::from_millis(500);
 pub const HOVER_DURATION: Duration = Duration::from_millis(1600);
+const REVEAL_DURATION: Duration = Duration::from_millis(1000);
+/// How long the zoom level is shown over the view after it changes
+const ZOOM_INDICATOR_DURATION: Duration = Duration::from_millis(1500);
+/// How long animated previews play for on hover, to limit CPU use
+const ANIMATION_DURATION: Duration = Duration::from_secs(3);
+const ANIMATION_FRAME_DELAY: Duration = Duration::from_millis(100);
+/// How much of an animation or video can be played in the details pane
+const MEDIA_PREVIEW_DURATION: Duration = Duration::from_secs(10);
+/// Size of the frames of animations played in the gallery, which are kept in memory
+const GALLERY_MEDIA_SIZE: u32 = 640;
+/// Size of the frames of videos streamed in the gallery, of which only the latest is kept
+#[cfg(feature = "video-thumbnails")]
+const GALLERY_VIDEO_SIZE: u32 = 960;
+/// How far the seek buttons and arrow keys move in the details pane media preview
+const MEDIA_SEEK_STEP: Duration = Duration::from_secs(1);
+/// How far the seek buttons and arrow keys move in audio files and in videos in the gallery,
+/// which tend to be longer
+const AUDIO_SEEK_STEP: Duration = Duration::from_secs(5);
+/// How often the position of playing audio is updated
+const AUDIO_TICK: Duration = Duration::from_millis(250);
+/// Applications offered for opening the selected item in the details pane
+const OPEN_WITH_PREVIEW_APPS: usize = 3;
+/// Times within this many days are also shown as how long ago they were in the details pane
+const RELATIVE_TIME_DAYS: u64 = 7;
+/// Archive entries shown in the details pane, to keep large archives responsive
+const ARCHIVE_PREVIEW_ENTRIES: usize = 50;
 //TODO: best limit for search items
 const MAX_SEARCH_LATENCY: Duration = Duration::from_millis(20);
 const MAX_SEARCH_RESULTS: usize = 200;
@@ -88,6 +131,15 @@ const THUMBNAIL_SIZE: u32 = (ICON_SIZE_GRID as u32) * (ICON_SCALE_MAX as u32);
 
 const DRAG_SCROLL_DISTANCE: f32 = 15.0;
 
+/// Indent of each level, and size of the disclosure button, in the tree view
+const TREE_INDENT: u16 = 16;
+
+/// Height of the headings above groups of items in grid view
+const GROUP_HEADING_HEIGHT: usize = 32;
+
+/// Longest extension shown on icons in grid view, longer ones would cover the icon
+const EXTENSION_BADGE_MAX_CHARS: usize = 5;
+
 static MODE_NAMES: Lazy<Vec<String>> = Lazy::new(|| {
     vec![
         // Mode 0
@@ -109,6 +161,20 @@ static MODE_NAMES: Lazy<Vec<String>> = Lazy::new(|| {
     ]
 });
 
+/// Whether an ACL entry is added for a user or a group
+static ACL_KIND_NAMES: Lazy<Vec<String>> = Lazy::new(|| vec![fl!("acl-user"), fl!("acl-group")]);
+
+static TRASH_DATE_NAMES: Lazy<Vec<String>> =
+    Lazy::new(|| TRASH_DATE_FILTERS.iter().map(|date| date.name()).collect());
+
+static TRASH_GROUP_NAMES: Lazy<Vec<String>> = Lazy::new(|| {
+    vec![
+        fl!("no-grouping"),
+        fl!("group-by-date"),
+        fl!("group-by-folder"),
+    ]
+});
+
 static SPECIAL_DIRS: Lazy<HashMap<PathBuf, &'static str>> = Lazy::new(|| {
     let mut special_dirs = HashMap::new();
     if let Some(dir) = dirs::document_dir() {
@@ -203,6 +269,80 @@ fn button_appearance(
     appearance
 }
 
+/// Icon with the badges of its emblems on the bottom left corner
+fn emblem_icon<'a>(
+    icon: widget::icon::Icon,
+    emblems: &[Emblem],
+    badge_size: u16,
+) -> Element<'a, Message> {
+    let mut badges = widget::row::with_capacity(emblems.len()).spacing(1);
+    let mut has_badges = false;
+    for icon_name in emblems.iter().filter_map(|emblem| emblem.icon_name()) {
+        badges = badges.push(widget::icon::from_name(icon_name).size(badge_size));
+        has_badges = true;
+    }
+    if !has_badges {
+        return icon.into();
+    }
+    cosmic::iced::widget::Stack::with_children(vec![
+        icon.into(),
+        widget::container(badges)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Left)
+            .align_y(Vertical::Bottom)
+            .into(),
+    ])
+    .into()
+}
+
+fn extension_badge<'a>(extension: String) -> Element<'a, Message> {
+    widget::container(widget::text::caption(extension))
+        .padding([0, 4])
+        .style(|theme: &theme::Theme| {
+            let cosmic = theme.cosmic();
+            widget::container::Style {
+                background: Some(Color::from(cosmic.accent_color()).into()),
+                text_color: Some(Color::from(cosmic.on_accent_color())),
+                border: Border {
+                    radius: cosmic.radius_xs().into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+/// Compact strip of color tag dots after a star for starred items, which opens the tag editor
+/// when pressed
+fn tag_strip<'a>(tags: &[ColorTag], starred: bool, i: usize) -> Element<'a, Message> {
+    let mut row = widget::row::with_capacity(tags.len() + 1)
+        .spacing(2)
+        .align_y(Alignment::Center);
+    if starred {
+        row = row.push(widget::icon::from_name("starred-symbolic").size(12));
+    }
+    for tag in tags {
+        let color = tag.color();
+        row = row.push(
+            widget::container(Space::new(Length::Fixed(8.0), Length::Fixed(8.0))).style(
+                move |_theme| widget::container::Style {
+                    background: Some(color.into()),
+                    border: Border {
+                        radius: 4.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ),
+        );
+    }
+    mouse_area::MouseArea::new(row)
+        .on_press(move |_| Message::EditTags(i))
+        .into()
+}
+
 fn button_style(
     selected: bool,
     highlighted: bool,
@@ -329,6 +469,152 @@ pub fn trash_entries() -> usize {
     }
 }
 
+/// Items deleted longer ago than this are offered for deletion when emptying the trash
+pub const TRASH_OLD_DAYS: u64 = 30;
+const TRASH_LARGEST_ITEMS: usize = 5;
+
+#[derive(Clone, Debug, Default)]
+pub struct TrashSummary {
+    pub count: usize,
+    pub size: u64,
+    /// Names and sizes of the largest items, largest first
+    pub largest: Vec<(String, u64)>,
+    /// Items deleted more than TRASH_OLD_DAYS days ago
+    pub old_items: Vec<trash::TrashItem>,
+}
+
+// This config statement is from trash::os_limited, inverted
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn trash_summary() -> TrashSummary {
+    TrashSummary::default()
+}
+
+// This config statement is from trash::os_limited
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn trash_summary() -> TrashSummary {
+    let mut summary = TrashSummary::default();
+    let entries = match trash::os_limited::list() {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::warn!("failed to read trash items: {}", err);
+            return summary;
+        }
+    };
+    let now_secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+    let old_secs = now_secs.saturating_sub(TRASH_OLD_DAYS * 24 * 60 * 60);
+    let mut sizes = Vec::with_capacity(entries.len());
+    for entry in entries {
+        summary.count += 1;
+        if let Ok(metadata) = trash::os_limited::metadata(&entry) {
+            let size = trash_policy::item_size(&entry, &metadata.size);
+            summary.size += size;
+            sizes.push((entry.name.to_string_lossy().to_string(), size));
+        }
+        if u64::try_from(entry.time_deleted).map_or(false, |secs| secs < old_secs) {
+            summary.old_items.push(entry);
+        }
+    }
+    sizes.sort_by(|a, b| b.1.cmp(&a.1));
+    sizes.truncate(TRASH_LARGEST_ITEMS);
+    summary.largest = sizes;
+    summary
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SelectionStats {
+    /// Number of items of each kind, either "folder" or the top-level MIME type, most common first
+    pub kinds: Vec<(String, usize)>,
+    pub count: usize,
+    /// Size of all files, including those inside selected folders
+    pub size: u64,
+    pub oldest: Option<(String, SystemTime)>,
+    pub newest: Option<(String, SystemTime)>,
+    pub largest: Option<(String, u64)>,
+}
+
+/// Summarize selected items, walking selected folders to include their contents in the size
+pub fn selection_stats(paths: &[PathBuf]) -> SelectionStats {
+    let mut stats = SelectionStats::default();
+    let mut kinds: HashMap<String, usize> = HashMap::new();
+    for path in paths {
+        let metadata = match fs::metadata(path) {
+            Ok(ok) => ok,
+            Err(err) => {
+                log::warn!("failed to read metadata of {:?}: {}", path, err);
+                continue;
+            }
+        };
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |x| x.to_string_lossy().into_owned(),
+        );
+        stats.count += 1;
+
+        let (kind, size) = if metadata.is_dir() {
+            let size = WalkDir::new(path)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum();
+            ("folder".to_string(), size)
+        } else {
+            let mime = mime_for_path(path, Some(&metadata), false);
+            (mime.type_().as_str().to_string(), metadata.len())
+        };
+        *kinds.entry(kind).or_default() += 1;
+        stats.size += size;
+        if stats
+            .largest
+            .as_ref()
+            .map_or(true, |(_, largest)| size > *largest)
+        {
+            stats.largest = Some((name.clone(), size));
+        }
+
+        if let Ok(modified) = metadata.modified() {
+            if stats
+                .oldest
+                .as_ref()
+                .map_or(true, |(_, oldest)| modified < *oldest)
+            {
+                stats.oldest = Some((name.clone(), modified));
+            }
+            if stats
+                .newest
+                .as_ref()
+                .map_or(true, |(_, newest)| modified > *newest)
+            {
+                stats.newest = Some((name, modified));
+            }
+        }
+    }
+    stats.kinds = kinds.into_iter().collect();
+    stats
+        .kinds
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats
+}
+
 pub fn trash_icon(icon_size: u16) -> widget::icon::Handle {
     widget::icon::from_name(if !trash::os_limited::is_empty().unwrap_or(true) {
         "user-trash-full"
@@ -349,8 +635,34 @@ pub fn trash_icon_symbolic(icon_size: u16) -> widget::icon::Handle {
     .handle()
 }
 
+fn text_width<'a>(content: &'a str, font: font::Font, font_size: f32, line_height: f32) -> f32 {
+    let text: text::Text<&'a str, font::Font> = text::Text {
+        content,
+        bounds: Size::INFINITY,
+        size: font_size.into(),
+        line_height: text::LineHeight::Absolute(line_height.into()),
+        font,
+        horizontal_alignment: Horizontal::Left,
+        vertical_alignment: Vertical::Top,
+        shaping: text::Shaping::default(),
+        wrapping: text::Wrapping::None,
+    };
+    graphics::text::Paragraph::with_text(text)
+        .min_bounds()
+        .width
+}
+
+fn text_width_body(content: &str) -> f32 {
+    //TODO: should libcosmic set the font when using widget::text::body?
+    text_width(content, font::default(), 14.0, 20.0)
+}
+
+fn text_width_heading(content: &str) -> f32 {
+    text_width(content, font::semibold(), 14.0, 20.0)
+}
+
 //TODO: translate, add more levels?
-fn format_size(size: u64) -> String {
+pub fn format_size(size: u64) -> String {
     const KB: u64 = 1000;
     const MB: u64 = 1000 * KB;
     const GB: u64 = 1000 * MB;
@@ -491,6 +803,54 @@ const fn format_time<'a>(
     }
 }
 
+/// Format a time the same way as the modified column
+pub fn format_system_time(time: SystemTime, military_time: bool) -> String {
+    format_time(
+        time,
+        &date_time_formatter(military_time),
+        &time_formatter(military_time),
+    )
+    .to_string()
+}
+
+/// How long ago a recent time was
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RelativeTime {
+    JustNow,
+    Minutes(u64),
+    Hours(u64),
+    Days(u64),
+}
+
+impl RelativeTime {
+    /// Older times, and times in the future, are only shown as dates
+    fn new(time: SystemTime, now: SystemTime) -> Option<Self> {
+        let secs = now.duration_since(time).ok()?.as_secs();
+        Some(if secs < 60 {
+            Self::JustNow
+        } else if secs < 60 * 60 {
+            Self::Minutes(secs / 60)
+        } else if secs < 24 * 60 * 60 {
+            Self::Hours(secs / (60 * 60))
+        } else if secs < RELATIVE_TIME_DAYS * 24 * 60 * 60 {
+            Self::Days(secs / (24 * 60 * 60))
+        } else {
+            return None;
+        })
+    }
+}
+
+impl Display for RelativeTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::JustNow => write!(f, "{}", fl!("just-now")),
+            Self::Minutes(minutes) => write!(f, "{}", fl!("minutes-ago", minutes = minutes)),
+            Self::Hours(hours) => write!(f, "{}", fl!("hours-ago", hours = hours)),
+            Self::Days(days) => write!(f, "{}", fl!("days-ago", days = days)),
+        }
+    }
+}
+
 #[cfg(not(target_os = "windows"))]
 fn hidden_attribute(_metadata: &Metadata) -> bool {
     false
@@ -511,6 +871,15 @@ pub enum FsKind {
     Gvfs,
 }
 
+fn fs_type_kind(fs_type: &str) -> FsKind {
+    //TODO: make sure this list is exhaustive
+    match fs_type {
+        "cifs" | "fuse.rclone" | "fuse.sshfs" | "nfs" | "nfs4" | "smb" | "smb2" => FsKind::Remote,
+        "fuse.gvfsd-fuse" => FsKind::Gvfs,
+        _ => FsKind::Local,
+    }
+}
+
 #[cfg(target_os = "linux")]
 pub fn fs_kind(metadata: &Metadata) -> FsKind {
     //TODO: method to reload remote filesystems dynamically
@@ -535,14 +904,7 @@ pub fn fs_kind(metadata: &Metadata) -> FsKind {
                             continue;
                         };
                         let dev = libc::makedev(major, minor);
-                        //TODO: make sure this list is exhaustive
-                        let kind = match mount_info.fs_type.as_str() {
-                            "cifs" | "fuse.rclone" | "fuse.sshfs" | "nfs" | "nfs4" | "smb"
-                            | "smb2" => FsKind::Remote,
-                            "fuse.gvfsd-fuse" => FsKind::Gvfs,
-                            _ => FsKind::Local,
-                        };
-                        devices.insert(dev, kind);
+                        devices.insert(dev, fs_type_kind(&mount_info.fs_type));
                     }
                 }
                 Err(err) => {
@@ -564,6 +926,199 @@ pub fn fs_kind(_metadata: &Metadata) -> FsKind {
     FsKind::Local
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FsInfo {
+    pub fs_type: String,
+    pub mount_point: PathBuf,
+    pub free: u64,
+    pub total: u64,
+    /// Disk quota of the current user, if the file system has one
+    pub quota_opt: Option<Quota>,
+    /// Mounted read-only, so nothing on it can be changed
+    pub read_only: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Quota {
+    /// Bytes used by the current user
+    pub used: u64,
+    /// Bytes the current user may use
+    pub limit: u64,
+}
+
+impl Quota {
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+/// Disk quota of the current user on the file system mounted from `source` at `mount_point`.
+/// NFS quotas are asked from the server's rquotad with the quota tool. SMB servers report the
+/// caller's quota as the size of the share, so the space of the share is used as the quota.
+#[cfg(target_os = "linux")]
+fn user_quota(
+    fs_type: &str,
+    source: &str,
+    mount_point: &Path,
+    stat: &libc::statvfs,
+) -> Option<Quota> {
+    match fs_type {
+        "nfs" | "nfs4" => nfs_quota(source, mount_point),
+        "cifs" | "smb3" => {
+            // statvfs field types vary between platforms
+            #[allow(clippy::unnecessary_cast)]
+            let (total, free) = (
+                stat.f_blocks as u64 * stat.f_frsize as u64,
+                stat.f_bavail as u64 * stat.f_frsize as u64,
+            );
+            if total == 0 {
+                return None;
+            }
+            Some(Quota {
+                used: total.saturating_sub(free),
+                limit: total,
+            })
+        }
+        _ => block_device_quota(source),
+    }
+}
+
+/// Disk quota of the current user on an NFS mount, read with the quota tool from quota-tools
+#[cfg(target_os = "linux")]
+fn nfs_quota(source: &str, mount_point: &Path) -> Option<Quota> {
+    let output = std::process::Command::new("quota")
+        .arg("--no-wrap")
+        .arg("--raw-grace")
+        .arg("--filesystem-list")
+        .arg(mount_point)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    parse_quota_output(&String::from_utf8_lossy(&output.stdout), source)
+}
+
+/// Parse the line for `source` in the output of the quota tool, where sizes are in KiB and
+/// a used size above the soft limit ends with `*`
+#[cfg(target_os = "linux")]
+fn parse_quota_output(output: &str, source: &str) -> Option<Quota> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != source {
+            return None;
+        }
+        let mut kib = || -> Option<u64> { fields.next()?.trim_end_matches('*').parse().ok() };
+        let used = kib()?;
+        let soft = kib()?;
+        let hard = kib()?;
+        let limit = if hard != 0 { hard } else { soft };
+        if limit == 0 {
+            return None;
+        }
+        Some(Quota {
+            used: used.saturating_mul(1024),
+            limit: limit.saturating_mul(1024),
+        })
+    })
+}
+
+/// Disk quota of the current user on a block device, read with quotactl
+#[cfg(target_os = "linux")]
+fn block_device_quota(device: &str) -> Option<Quota> {
+    use std::ffi::CString;
+
+    // From linux/quota.h, which libc does not expose on all targets
+    const Q_GETQUOTA: libc::c_int = 0x800007;
+    const USRQUOTA: libc::c_int = 0;
+    const QIF_DQBLKSIZE: u64 = 1024;
+    #[repr(C)]
+    #[derive(Default)]
+    struct IfDqblk {
+        dqb_bhardlimit: u64,
+        dqb_bsoftlimit: u64,
+        dqb_curspace: u64,
+        dqb_ihardlimit: u64,
+        dqb_isoftlimit: u64,
+        dqb_curinodes: u64,
+        dqb_btime: u64,
+        dqb_itime: u64,
+        dqb_valid: u32,
+    }
+
+    if !device.starts_with('/') {
+        return None;
+    }
+    let c_device = CString::new(device).ok()?;
+    let mut dqblk = IfDqblk::default();
+    let cmd = (Q_GETQUOTA << 8) | (USRQUOTA & 0xff);
+    let uid = unsafe { libc::getuid() };
+    if unsafe {
+        libc::quotactl(
+            cmd,
+            c_device.as_ptr(),
+            uid as libc::c_int,
+            &mut dqblk as *mut IfDqblk as *mut libc::c_char,
+        )
+    } != 0
+    {
+        // Quotas are not enabled on most file systems
+        return None;
+    }
+    let limit_blocks = if dqblk.dqb_bhardlimit != 0 {
+        dqblk.dqb_bhardlimit
+    } else {
+        dqblk.dqb_bsoftlimit
+    };
+    if limit_blocks == 0 {
+        return None;
+    }
+    Some(Quota {
+        used: dqblk.dqb_curspace,
+        limit: limit_blocks.saturating_mul(QIF_DQBLKSIZE),
+    })
+}
+
+/// File system type, mount point, and space of the file system containing `path`
+#[cfg(target_os = "linux")]
+// statvfs field types vary between platforms
+#[allow(clippy::unnecessary_cast)]
+pub fn fs_info(path: &Path) -> Result<FsInfo, String> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|err| err.to_string())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+
+    // The deepest mount point containing the path is the one it is on
+    let mount_infos = procfs::process::Process::myself()
+        .and_then(|process| process.mountinfo())
+        .map_err(|err| err.to_string())?;
+    let mount_info = mount_infos
+        .iter()
+        .filter(|mount_info| path.starts_with(&mount_info.mount_point))
+        .max_by_key(|mount_info| mount_info.mount_point.components().count())
+        .ok_or_else(|| format!("no mount point found for {:?}", path))?;
+
+    Ok(FsInfo {
+        fs_type: mount_info.fs_type.clone(),
+        mount_point: mount_info.mount_point.clone(),
+        free: stat.f_bavail as u64 * stat.f_frsize as u64,
+        total: stat.f_blocks as u64 * stat.f_frsize as u64,
+        quota_opt: mount_info.mount_source.as_deref().and_then(|source| {
+            user_quota(&mount_info.fs_type, source, &mount_info.mount_point, &stat)
+        }),
+        read_only: stat.f_flag & libc::ST_RDONLY != 0,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn fs_info(_path: &Path) -> Result<FsInfo, String> {
+    //TODO: support BSD, macOS, Windows?
+    Err("file system info is not supported on this platform".to_string())
+}
+
 pub fn parse_desktop_file(path: &Path) -> (Option<String>, Option<String>) {
     let entry = match freedesktop_entry_parser::parse_entry(path) {
         Ok(ok) => ok,
@@ -695,6 +1250,44 @@ pub fn item_from_entry(
         }
     }
 
+    let archive_entries = if !remote && archive_entries_supported(&mime) {
+        ArchiveEntries::Loading
+    } else {
+        ArchiveEntries::NotArchive
+    };
+
+    let audio_info = if !remote && audio_preview::audio_preview_supported(&mime) {
+        if audio_preview::player_available() {
+            AudioInfo::Loading
+        } else {
+            AudioInfo::Unavailable
+        }
+    } else {
+        AudioInfo::NotAudio
+    };
+
+    let image_info = if !remote && image_metadata::metadata_supported(&mime) {
+        ImageInfo::Loading
+    } else {
+        ImageInfo::NoMetadata
+    };
+
+    let document_preview = if !remote
+        && (document_preview::text_preview_supported(&mime)
+            || document_preview::pdf_preview_supported(&mime))
+    {
+        DocumentPreview::Loading
+    } else {
+        DocumentPreview::NotDocument
+    };
+
+    let tags = if remote { Vec::new() } else { read_tags(&path) };
+    let emblems = if remote {
+        Vec::new()
+    } else {
+        path_emblems(&path)
+    };
+
     Item {
         name,
         display_name,
@@ -720,22 +1313,97 @@ pub fn item_from_entry(
         highlighted: false,
         overlaps_drag_rect: false,
         dir_size,
+        archive_entries,
+        audio_info,
+        image_info,
+        document_preview,
+        fs_info_opt: None,
+        // Snapshots are only looked for on local file systems
+        versions_opt: if remote { Some(Vec::new()) } else { None },
+        attributes_opt: if remote {
+            Some(Ok(Attributes::default()))
+        } else {
+            None
+        },
+        checksums: Checksums::NotCalculated,
         cut: false,
+        tags,
+        emblems,
+        depth: 0,
+        expanded: false,
+    }
+}
+
+/// Convert a file name to a string for display, escaping any bytes that are not valid UTF-8 as
+/// `\xHH` and backslashes as `\\`, so [`unescaped_file_name`] gives back the same name. Items keep
+/// the raw name in their path, so they can still be operated on.
+#[cfg(unix)]
+pub fn escaped_file_name(name_os: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut name = String::with_capacity(name_os.len());
+    for chunk in name_os.as_bytes().utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c == '\\' {
+                name.push_str("\\\\");
+            } else {
+                name.push(c);
+            }
+        }
+        for byte in chunk.invalid() {
+            name.push_str(&format!("\\x{:02X}", byte));
+        }
+    }
+    name
+}
+
+#[cfg(not(unix))]
+pub fn escaped_file_name(name_os: &OsStr) -> String {
+    name_os.to_string_lossy().to_string()
+}
+
+/// Turn the escapes made by [`escaped_file_name`] back into bytes, for names edited in the rename
+/// dialog. Backslashes that do not start an escape are kept.
+#[cfg(unix)]
+pub fn unescaped_file_name(name: &str) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+
+    let bytes = name.as_bytes();
+    let mut name_os = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"\\\\") {
+            name_os.push(b'\\');
+            i += 2;
+            continue;
+        }
+        if bytes[i..].starts_with(b"\\x") {
+            if let Some(hex) = bytes.get(i + 2..i + 4) {
+                if hex.iter().all(u8::is_ascii_hexdigit) {
+                    let hex = std::str::from_utf8(hex).unwrap_or_default();
+                    if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                        name_os.push(byte);
+                        i += 4;
+                        continue;
+                    }
+                }
+            }
+        }
+        name_os.push(bytes[i]);
+        i += 1;
     }
+    OsString::from_vec(name_os)
+}
+
+#[cfg(not(unix))]
+pub fn unescaped_file_name(name: &str) -> OsString {
+    name.into()
 }
 
 pub fn item_from_path<P: Into<PathBuf>>(path: P, sizes: IconSizes) -> Result<Item, String> {
     let path = path.into();
     let name = match path.file_name() {
-        Some(name_os) => name_os
-            .to_str()
-            .ok_or_else(|| {
-                format!(
-                    "failed to parse file name for {:?}: {:?} is not valid UTF-8",
-                    path, name_os
-                )
-            })?
-            .to_string(),
+        Some(name_os) => escaped_file_name(name_os),
         None => fl!("filesystem"),
     };
     let metadata = fs::metadata(&path)
@@ -759,17 +1427,7 @@ pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
 
                 let path = entry.path();
 
-                let name = match entry.file_name().into_string() {
-                    Ok(ok) => ok,
-                    Err(name_os) => {
-                        log::warn!(
-                            "failed to parse entry at {:?}: {:?} is not valid UTF-8",
-                            path,
-                            name_os,
-                        );
-                        continue;
-                    }
-                };
+                let name = escaped_file_name(&entry.file_name());
 
                 if name == ".hidden" && path.is_file() {
                     hidden_files = parse_hidden_file(&path);
@@ -790,6 +1448,19 @@ pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
             log::warn!("failed to read directory {:?}: {}", tab_path, err);
         }
     }
+    // Looking for sync folders and git repositories is too slow on remote file systems
+    let local =
+        fs::metadata(tab_path).is_ok_and(|metadata| matches!(fs_kind(&metadata), FsKind::Local));
+    if local && !items.is_empty() {
+        let folder_emblems = FolderEmblems::new(tab_path);
+        for item in items.iter_mut() {
+            if let Some(path) = item.path_opt() {
+                for emblem in folder_emblems.emblems(path, item.metadata.is_dir()) {
+                    item.add_emblem(emblem);
+                }
+            }
+        }
+    }
     items.sort_by(|a, b| match (a.metadata.is_dir(), b.metadata.is_dir()) {
         (true, false) => Ordering::Less,
         (false, true) => Ordering::Greater,
@@ -803,26 +1474,118 @@ pub fn scan_path(tab_path: &PathBuf, sizes: IconSizes) -> Vec<Item> {
     items
 }
 
+/// Largest file that is read when searching file contents
+const CONTENT_SEARCH_MAX_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Search term, with an optional prefix choosing what is matched
+#[derive(Clone, Debug)]
+pub enum SearchQuery {
+    /// File names containing the term
+    Name(regex::Regex),
+    /// File names matching a glob pattern, like `filter:*.png`
+    Filter(glob::Pattern),
+    /// Text files containing the term, like `content:TODO`
+    Content(String),
+    /// Files with a color tag, like `tag:red`
+    Tag(Option<ColorTag>),
+}
+
+impl SearchQuery {
+    pub const PREFIXES: [&'static str; 3] = ["filter:", "content:", "tag:"];
+
+    /// Returns true if the term starts with one of the search prefixes
+    pub fn has_prefix(term: &str) -> bool {
+        Self::PREFIXES
+            .iter()
+            .any(|prefix| term.starts_with(prefix) && term.len() > prefix.len())
+    }
+
+    pub fn parse(term: &str) -> Option<Self> {
+        if let Some(pattern) = term.strip_prefix("filter:") {
+            match glob::Pattern::new(pattern.trim()) {
+                Ok(ok) => Some(Self::Filter(ok)),
+                Err(err) => {
+                    log::warn!("failed to parse glob {:?}: {}", pattern, err);
+                    None
+                }
+            }
+        } else if let Some(content) = term.strip_prefix("content:") {
+            let content = content.trim();
+            if content.is_empty() {
+                None
+            } else {
+                Some(Self::Content(content.to_lowercase()))
+            }
+        } else if let Some(name) = term.strip_prefix("tag:") {
+            // Unknown tags are still a tag search, which finds nothing
+            Some(Self::Tag(ColorTag::from_name(name.trim())))
+        } else {
+            let pattern = regex::escape(term);
+            match regex::RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(ok) => Some(Self::Name(ok)),
+                Err(err) => {
+                    log::warn!("failed to parse regex {:?}: {}", pattern, err);
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn matches(&self, path: &Path, file_name: &str, is_file: bool) -> bool {
+        match self {
+            Self::Name(regex) => regex.is_match(file_name),
+            Self::Filter(pattern) => pattern.matches_with(
+                file_name,
+                glob::MatchOptions {
+                    case_sensitive: false,
+                    ..Default::default()
+                },
+            ),
+            Self::Content(content) => is_file && file_contains(path, content),
+            Self::Tag(tag_opt) => match tag_opt {
+                Some(tag) => read_tags(path).contains(tag),
+                None => false,
+            },
+        }
+    }
+}
+
+/// Returns true if a text file contains the lowercase term, ignoring case
+fn file_contains(path: &Path, term: &str) -> bool {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.len() <= CONTENT_SEARCH_MAX_SIZE => {}
+        _ => return false,
+    }
+    let data = match fs::read(path) {
+        Ok(ok) => ok,
+        Err(err) => {
+            log::debug!("failed to read {:?} for content search: {}", path, err);
+            return false;
+        }
+    };
+    // Skip binary files
+    if data.iter().take(8192).any(|byte| *byte == 0) {
+        return false;
+    }
+    String::from_utf8_lossy(&data).to_lowercase().contains(term)
+}
+
 pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
     tab_path: &PathBuf,
     term: &str,
     show_hidden: bool,
+    stop: &atomic::AtomicBool,
     callback: F,
 ) {
     if term.is_empty() {
         return;
     }
 
-    let pattern = regex::escape(term);
-    let regex = match regex::RegexBuilder::new(&pattern)
-        .case_insensitive(true)
-        .build()
-    {
-        Ok(ok) => ok,
-        Err(err) => {
-            log::warn!("failed to parse regex {:?}: {}", pattern, err);
-            return;
-        }
+    let Some(query) = SearchQuery::parse(term) else {
+        return;
     };
 
     ignore::WalkBuilder::new(tab_path)
@@ -833,17 +1596,20 @@ pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
         .build_parallel()
         .run(|| {
             Box::new(|entry_res| {
+                // Checked for every entry, as matches may be rare
+                if stop.load(atomic::Ordering::Relaxed) {
+                    return ignore::WalkState::Quit;
+                }
+
                 let Ok(entry) = entry_res else {
                     // Skip invalid entries
                     return ignore::WalkState::Skip;
                 };
 
-                let Some(file_name) = entry.file_name().to_str() else {
-                    // Skip anything with an invalid name
-                    return ignore::WalkState::Skip;
-                };
+                let file_name = escaped_file_name(entry.file_name());
+                let is_file = entry.file_type().map_or(false, |kind| kind.is_file());
 
-                if regex.is_match(file_name) {
+                if query.matches(entry.path(), &file_name, is_file) {
                     let path = entry.path();
 
                     let metadata = match entry.metadata() {
@@ -855,7 +1621,7 @@ pub fn scan_search<F: Fn(&Path, &str, Metadata) -> bool + Sync>(
                     };
 
                     //TODO: use entry.into_path?
-                    if !callback(path, file_name, metadata) {
+                    if !callback(path, &file_name, metadata) {
                         return ignore::WalkState::Quit;
                     }
                 }
@@ -946,7 +1712,19 @@ pub fn scan_trash(sizes: IconSizes) -> Vec<Item> {
                     highlighted: false,
                     overlaps_drag_rect: false,
                     dir_size: DirSize::NotDirectory,
+                    archive_entries: ArchiveEntries::NotArchive,
+                    audio_info: AudioInfo::NotAudio,
+                    image_info: ImageInfo::NoMetadata,
+                    document_preview: DocumentPreview::NotDocument,
+                    fs_info_opt: None,
+                    versions_opt: None,
+                    attributes_opt: None,
+                    checksums: Checksums::NotCalculated,
                     cut: false,
+                    tags: Vec::new(),
+                    emblems: Vec::new(),
+                    depth: 0,
+                    expanded: false,
                 });
             }
         }
@@ -1031,17 +1809,138 @@ pub fn scan_recents(sizes: IconSizes) -> Vec<Item> {
     recents.into_iter().take(50).map(|(item, _)| item).collect()
 }
 
+/// Starred items, which may be anywhere on the file system
+pub fn scan_favorites(paths: &[PathBuf], sizes: IconSizes) -> Vec<Item> {
+    let mut items = Vec::new();
+    for path in paths {
+        match item_from_path(path, sizes) {
+            Ok(item) => items.push(item),
+            // Starred items may have been moved or deleted since
+            Err(err) => log::debug!("failed to get starred item: {}", err),
+        }
+    }
+    items
+}
+
+/// Items with a color tag, found through the tag index
+pub fn scan_tag(tag: ColorTag, sizes: IconSizes) -> Vec<Item> {
+    let mut items = Vec::new();
+    for path in tagged_paths(tag) {
+        // Items removed by other programs are dropped from the index
+        if fs::symlink_metadata(&path).is_err_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+        {
+            crate::tag::removed(&path);
+            continue;
+        }
+        // The index may be out of date if the item was moved or retagged elsewhere
+        if !read_tags(&path).contains(&tag) {
+            continue;
+        }
+        match item_from_path(&path, sizes) {
+            Ok(item) => items.push(item),
+            Err(err) => log::warn!("failed to get item for tagged path {:?}: {}", path, err),
+        }
+    }
+    items
+}
+
 pub fn scan_network(uri: &str, sizes: IconSizes) -> Vec<Item> {
+    match try_scan_network(uri, sizes) {
+        Ok(items) => items,
+        Err(err) => {
+            log::warn!("failed to scan {:?}: {}", uri, err);
+            Vec::new()
+        }
+    }
+}
+
+/// Local path of a mounted network location, like the FUSE path of an sftp share
+pub fn network_path(uri: &str) -> Option<PathBuf> {
+    MOUNTERS
+        .values()
+        .find_map(|mounter| mounter.network_path(uri))
+}
+
+/// Network location for a remote URI like `sftp://user@host/path`, or `None` for local paths
+pub fn network_location(uri: &str) -> Option<Location> {
+    let url = url::Url::parse(uri).ok()?;
+    if url.scheme() == "file" || !uri.contains("://") {
+        return None;
+    }
+    let display_name = match url.host_str() {
+        Some(host) => format!(
+            "{}{}",
+            host,
+            percent_encoding::percent_decode_str(url.path().trim_end_matches('/'))
+                .decode_utf8_lossy()
+        ),
+        None => uri.to_string(),
+    };
+    Some(Location::Network(uri.to_string(), display_name))
+}
+
+/// Network location of the folder containing a remote URI, or `None` at the top of the server
+pub fn network_parent(uri: &str) -> Option<Location> {
+    let mut url = url::Url::parse(uri).ok()?;
+    if url.path().trim_matches('/').is_empty() {
+        return None;
+    }
+    url.path_segments_mut().ok()?.pop_if_empty().pop();
+    // Folders of the server have a trailing slash like its top
+    url.path_segments_mut().ok()?.push("");
+    network_location(url.as_str())
+}
+
+/// Archive browsed like a remote folder through the gvfs archive backend, which mounts it when
+/// the location is scanned
+#[cfg(feature = "gvfs")]
+pub fn archive_location(path: &Path) -> Option<Location> {
+    let mime = mime_for_path(path, None, false);
+    if !path.is_file() || !crate::default_apps::AppCategory::Archives.contains(&mime) {
+        return None;
+    }
+    let file_uri = url::Url::from_file_path(path).ok()?;
+    // The backend takes the whole archive URI escaped as its host
+    let mut uri = "archive://".to_string();
+    for byte in file_uri.as_str().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri.push('/');
+    let display_name = path.file_name()?.to_string_lossy().into_owned();
+    Some(Location::Network(uri, display_name))
+}
+
+/// Scan a network location, returning an error if no mounter could reach it. Listings from
+/// several mounters, like the servers that each finds on the network, are combined.
+pub fn try_scan_network(uri: &str, sizes: IconSizes) -> Result<Vec<Item>, String> {
+    let mut items_opt: Option<Vec<Item>> = None;
+    let mut err_opt = None;
     for (_key, mounter) in MOUNTERS.iter() {
         match mounter.network_scan(uri, sizes) {
-            Some(Ok(items)) => return items,
-            Some(Err(err)) => {
-                log::warn!("failed to scan {:?}: {}", uri, err);
+            Some(Ok(new_items)) => {
+                let items = items_opt.get_or_insert_with(Vec::new);
+                for item in new_items {
+                    if !items.iter().any(|other| {
+                        other.location_opt == item.location_opt
+                            || other.name.eq_ignore_ascii_case(&item.name)
+                    }) {
+                        items.push(item);
+                    }
+                }
             }
+            Some(Err(err)) => err_opt = Some(err),
             None => {}
         }
     }
-    Vec::new()
+    match (items_opt, err_opt) {
+        (Some(items), _) => Ok(items),
+        (None, Some(err)) => Err(err),
+        (None, None) => Ok(Vec::new()),
+    }
 }
 
 //TODO: organize desktop items based on display
@@ -1060,76 +1959,228 @@ pub fn scan_desktop(
     }
 
     if desktop_config.show_mounted_drives {
-        for (_mounter_key, mounter) in MOUNTERS.iter() {
-            for mounter_item in mounter.items(sizes).unwrap_or_default() {
-                let Some(path) = mounter_item.path() else {
-                    continue;
-                };
+        items.extend(scan_mounted(sizes));
+    }
 
-                // Get most item data from path
-                let mut item = match item_from_path(&path, sizes) {
-                    Ok(item) => item,
-                    Err(err) => {
-                        log::warn!("failed to get item from mounter item {:?}: {}", path, err);
-                        continue;
-                    }
-                };
+    if desktop_config.show_trash {
+        items.push(shortcut_item(
+            fl!("trash"),
+            Location::Trash,
+            trash_entries() as u64,
+            trash_icon,
+            sizes,
+        ));
+    }
+
+    items
+}
 
-                //Override some data with mounter information
-                item.name = mounter_item.name();
-                item.display_name = Item::display_name(&item.name);
+/// Mount points of the items provided by mounters, named as the mounter names them
+fn scan_mounted(sizes: IconSizes) -> Vec<Item> {
+    let mut items = Vec::new();
+    for (_mounter_key, mounter) in MOUNTERS.iter() {
+        for mounter_item in mounter.items(sizes).unwrap_or_default() {
+            let Some(path) = mounter_item.path() else {
+                continue;
+            };
 
-                //TODO: use icon size for mounter item icon
-                if let Some(icon) = mounter_item.icon(false) {
-                    item.icon_handle_grid = icon.clone();
-                    item.icon_handle_list = icon.clone();
-                    item.icon_handle_list_condensed = icon;
+            // Get most item data from path
+            let mut item = match item_from_path(&path, sizes) {
+                Ok(item) => item,
+                Err(err) => {
+                    log::warn!("failed to get item from mounter item {:?}: {}", path, err);
+                    continue;
                 }
+            };
+
+            //Override some data with mounter information
+            item.name = mounter_item.name();
+            item.display_name = Item::display_name(&item.name);
 
-                items.push(item);
+            //TODO: use icon size for mounter item icon
+            if let Some(icon) = mounter_item.icon(false) {
+                item.icon_handle_grid = icon.clone();
+                item.icon_handle_list = icon.clone();
+                item.icon_handle_list_condensed = icon;
             }
+
+            items.push(item);
         }
     }
+    items
+}
 
-    if desktop_config.show_trash {
-        let name = fl!("trash");
-        let display_name = Item::display_name(&name);
+/// Files of a folder grouped into date folders, which list the files of one period or month
+pub fn scan_dates(
+    tab_path: &PathBuf,
+    folder_opt: Option<DateFolder>,
+    sizes: IconSizes,
+) -> Vec<Item> {
+    let today = chrono::Local::now().date_naive();
+    let by_calendar = date_view::by_calendar(tab_path);
+    let files: Vec<(Item, chrono::NaiveDate)> = scan_path(tab_path, sizes)
+        .into_iter()
+        .filter(|item| !item.metadata.is_dir())
+        .filter_map(|item| {
+            let modified = item.metadata.modified()?;
+            let date = chrono::DateTime::<chrono::Local>::from(modified).date_naive();
+            Some((item, date))
+        })
+        .collect();
+    match folder_opt {
+        Some(folder) if folder.has_files() => files
+            .into_iter()
+            .filter(|(_, date)| folder.contains(*date, today))
+            .map(|(item, _)| item)
+            .collect(),
+        _ => {
+            let dates: Vec<_> = files.iter().map(|(_, date)| *date).collect();
+            date_view::subfolders(&dates, today, by_calendar, folder_opt)
+                .into_iter()
+                .map(|(folder, entries)| {
+                    shortcut_item(
+                        folder.name(),
+                        Location::Dates(tab_path.clone(), Some(folder)),
+                        entries,
+                        date_folder_icon,
+                        sizes,
+                    )
+                })
+                .collect()
+        }
+    }
+}
 
-        let metadata = ItemMetadata::SimpleDir {
-            entries: trash_entries() as u64,
-        };
+fn date_folder_icon(icon_size: u16) -> widget::icon::Handle {
+    widget::icon::from_name("folder").size(icon_size).handle()
+}
 
-        let (mime, icon_handle_grid, icon_handle_list, icon_handle_list_condensed) = {
-            (
-                "inode/directory".parse().unwrap(),
-                trash_icon(sizes.grid()),
-                trash_icon(sizes.list()),
-                trash_icon(sizes.list_condensed()),
-            )
-        };
+/// Folder-like item that opens another location, like the trash
+pub fn shortcut_item(
+    name: String,
+    location: Location,
+    entries: u64,
+    icon: fn(u16) -> widget::icon::Handle,
+    sizes: IconSizes,
+) -> Item {
+    Item {
+        display_name: Item::display_name(&name),
+        name,
+        metadata: ItemMetadata::SimpleDir { entries },
+        hidden: false,
+        location_opt: Some(location),
+        //TODO: make this a static
+        mime: "inode/directory".parse().unwrap(),
+        icon_handle_grid: icon(sizes.grid()),
+        icon_handle_list: icon(sizes.list()),
+        icon_handle_list_condensed: icon(sizes.list_condensed()),
+        thumbnail_opt: Some(ItemThumbnail::NotImage),
+        button_id: widget::Id::unique(),
+        pos_opt: Cell::new(None),
+        rect_opt: Cell::new(None),
+        selected: false,
+        highlighted: false,
+        overlaps_drag_rect: false,
+        dir_size: DirSize::NotDirectory,
+        archive_entries: ArchiveEntries::NotArchive,
+        audio_info: AudioInfo::NotAudio,
+        image_info: ImageInfo::NoMetadata,
+        document_preview: DocumentPreview::NotDocument,
+        fs_info_opt: None,
+        versions_opt: None,
+        attributes_opt: None,
+        checksums: Checksums::NotCalculated,
+        cut: false,
+        tags: Vec::new(),
+        emblems: Vec::new(),
+        depth: 0,
+        expanded: false,
+    }
+}
 
-        items.push(Item {
-            name,
-            display_name,
-            metadata,
-            hidden: false,
-            location_opt: Some(Location::Trash),
-            mime,
-            icon_handle_grid,
-            icon_handle_list,
-            icon_handle_list_condensed,
-            thumbnail_opt: Some(ItemThumbnail::NotImage),
-            button_id: widget::Id::unique(),
-            pos_opt: Cell::new(None),
-            rect_opt: Cell::new(None),
-            selected: false,
-            highlighted: false,
-            overlaps_drag_rect: false,
-            dir_size: DirSize::NotDirectory,
-            cut: false,
-        })
+/// Item on a network location without a local path, with the icon of its type
+pub fn remote_item(
+    name: String,
+    location: Location,
+    metadata: ItemMetadata,
+    sizes: IconSizes,
+) -> Item {
+    let mime = if metadata.is_dir() {
+        "inode/directory".parse().unwrap()
+    } else {
+        mime_guess::from_path(&name).first_or_octet_stream()
+    };
+    Item {
+        display_name: Item::display_name(&name),
+        hidden: name.starts_with('.'),
+        name,
+        metadata,
+        location_opt: Some(location),
+        icon_handle_grid: mime_icon(mime.clone(), sizes.grid()),
+        icon_handle_list: mime_icon(mime.clone(), sizes.list()),
+        icon_handle_list_condensed: mime_icon(mime.clone(), sizes.list_condensed()),
+        mime,
+        thumbnail_opt: Some(ItemThumbnail::NotImage),
+        button_id: widget::Id::unique(),
+        pos_opt: Cell::new(None),
+        rect_opt: Cell::new(None),
+        selected: false,
+        highlighted: false,
+        overlaps_drag_rect: false,
+        dir_size: DirSize::NotDirectory,
+        archive_entries: ArchiveEntries::NotArchive,
+        audio_info: AudioInfo::NotAudio,
+        image_info: ImageInfo::NoMetadata,
+        document_preview: DocumentPreview::NotDocument,
+        fs_info_opt: None,
+        versions_opt: Some(Vec::new()),
+        attributes_opt: Some(Ok(Default::default())),
+        checksums: Checksums::NotCalculated,
+        cut: false,
+        tags: Vec::new(),
+        emblems: Vec::new(),
+        depth: 0,
+        expanded: false,
+    }
+}
+
+fn network_icon(icon_size: u16) -> widget::icon::Handle {
+    widget::icon::from_name("network-workgroup")
+        .size(icon_size)
+        .handle()
+}
+
+/// The file system root and mounted volumes with their capacity, followed by the network and
+/// trash, including mount points that are not in the sidebar
+pub fn scan_computer(sizes: IconSizes) -> Vec<Item> {
+    let mut items = Vec::new();
+    match item_from_path("/", sizes) {
+        Ok(item) => items.push(item),
+        Err(err) => log::warn!("failed to get item for file system root: {}", err),
+    }
+    items.extend(scan_mounted(sizes));
+    for item in items.iter_mut() {
+        if let Some(path) = item.path_opt() {
+            item.fs_info_opt = fs_info(path).ok();
+        }
     }
 
+    if !MOUNTERS.is_empty() {
+        items.push(shortcut_item(
+            fl!("networks"),
+            Location::Network("network:///".to_string(), fl!("networks")),
+            0,
+            network_icon,
+            sizes,
+        ));
+    }
+    items.push(shortcut_item(
+        fl!("trash"),
+        Location::Trash,
+        trash_entries() as u64,
+        trash_icon,
+        sizes,
+    ));
     items
 }
 
@@ -1150,6 +2201,27 @@ impl EditLocation {
         Some(self.location.with_path(completion.1.clone()))
     }
 
+    /// Network location if a remote URI like `sftp://host/path` was typed instead of a path
+    pub fn network_location(&self) -> Option<Location> {
+        if self.selected.is_some() {
+            return None;
+        }
+        network_location(self.location.path_opt()?.to_str()?)
+    }
+
+    /// Search term if a search prefix like `filter:` was typed instead of a path
+    pub fn search_term(&self) -> Option<String> {
+        if self.selected.is_some() {
+            return None;
+        }
+        let term = self.location.path_opt()?.to_str()?;
+        if SearchQuery::has_prefix(term) {
+            Some(term.to_string())
+        } else {
+            None
+        }
+    }
+
     pub fn select(&mut self, forwards: bool) {
         if let Some(completions) = &self.completions {
             if completions.is_empty() {
@@ -1183,19 +2255,49 @@ impl From<Location> for EditLocation {
     }
 }
 
+/// Limits of a location that disable or slow down some actions, shown in the breadcrumbs
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LocationCapabilities {
+    /// Items cannot be created, changed or deleted
+    pub read_only: bool,
+    /// Items are on a network share
+    pub remote: bool,
+    /// Where the file system of the location starts, folders above it are not limited
+    pub mount_point_opt: Option<PathBuf>,
+}
+
+impl LocationCapabilities {
+    /// Returns true if the limits apply to a folder of the location
+    pub fn applies_to(&self, path: &Path) -> bool {
+        self.mount_point_opt
+            .as_ref()
+            .map_or(true, |mount_point| path.starts_with(mount_point))
+    }
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Location {
+    Computer,
+    /// Files of a folder in virtual folders by date, starting with the folders shown first
+    Dates(PathBuf, Option<DateFolder>),
     Desktop(PathBuf, String, DesktopConfig),
+    Favorites,
     Network(String, String),
     Path(PathBuf),
     Recents,
     Search(PathBuf, String, bool, Instant),
+    Tag(ColorTag),
     Trash,
 }
 
 impl std::fmt::Display for Location {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::Computer => write!(f, "computer"),
+            Self::Dates(path, folder_opt) => {
+                write!(f, "{} by date {:?}", path.display(), folder_opt)
+            }
+            Self::Favorites => write!(f, "favorites"),
             Self::Desktop(path, display, ..) => {
                 write!(f, "{} on display {display}", path.display())
             }
@@ -1203,6 +2305,7 @@ impl std::fmt::Display for Location {
             Self::Path(path) => write!(f, "{}", path.display()),
             Self::Recents => write!(f, "recents"),
             Self::Search(path, term, ..) => write!(f, "search {} for {}", path.display(), term),
+            Self::Tag(tag) => write!(f, "tag {:?}", tag),
             Self::Trash => write!(f, "trash"),
         }
     }
@@ -1261,13 +2364,20 @@ impl Location {
                 scan_desktop(path, display, *desktop_config, sizes)
             }
             Self::Path(path) => scan_path(path, sizes),
+            Self::Dates(path, folder_opt) => scan_dates(path, *folder_opt, sizes),
             Self::Search(..) => {
                 // Search is done incrementally
                 Vec::new()
             }
+            Self::Favorites => {
+                // Starred paths are in the app config, see scan_favorites
+                Vec::new()
+            }
             Self::Trash => scan_trash(sizes),
             Self::Recents => scan_recents(sizes),
             Self::Network(uri, _) => scan_network(uri, sizes),
+            Self::Computer => scan_computer(sizes),
+            Self::Tag(tag) => scan_tag(*tag, sizes),
         };
         let parent_item_opt = match self.path_opt() {
             Some(path) => match item_from_path(path, sizes) {
@@ -1283,6 +2393,34 @@ impl Location {
         (parent_item_opt, items)
     }
 
+    /// Scan a location that lives on a network share. Returns `None` for local locations, and an
+    /// error if the share could not be reached.
+    pub fn scan_remote(
+        &self,
+        sizes: IconSizes,
+    ) -> Option<Result<(Option<Item>, Vec<Item>), String>> {
+        match self {
+            Self::Path(path) => {
+                let metadata = match fs::metadata(path) {
+                    Ok(ok) => ok,
+                    // A missing folder is not an unreachable share
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+                    Err(err) => return Some(Err(err.to_string())),
+                };
+                if matches!(fs_kind(&metadata), FsKind::Local) {
+                    return None;
+                }
+                // scan_path hides read errors, so check that the share answers first
+                if let Err(err) = fs::read_dir(path) {
+                    return Some(Err(err.to_string()));
+                }
+                Some(Ok(self.scan(sizes)))
+            }
+            Self::Network(uri, _) => Some(try_scan_network(uri, sizes).map(|items| (None, items))),
+            _ => None,
+        }
+    }
+
     pub fn title(&self) -> String {
         match self {
             Self::Desktop(path, _, _) => {
@@ -1304,7 +2442,56 @@ impl Location {
             Self::Recents => {
                 fl!("recents")
             }
+            Self::Computer => {
+                fl!("computer")
+            }
+            Self::Favorites => {
+                fl!("starred")
+            }
+            Self::Tag(tag) => tag.name(),
             Self::Network(_uri, display_name) => display_name.clone(),
+            Self::Dates(path, folder_opt) => match folder_opt {
+                Some(folder) => folder.name(),
+                None => {
+                    let (name, _) = folder_name(path);
+                    fl!("by-date", name = name)
+                }
+            },
+        }
+    }
+
+    /// Read-only and network file systems of the location. This reads the file system, which may
+    /// be slow on network shares, so it is loaded in the background.
+    pub fn capabilities(&self) -> LocationCapabilities {
+        match self {
+            Self::Network(..) => LocationCapabilities {
+                remote: true,
+                ..Default::default()
+            },
+            _ => match self.path_opt().map(|path| fs_info(path)) {
+                Some(Ok(fs_info)) => LocationCapabilities {
+                    read_only: fs_info.read_only,
+                    remote: !matches!(fs_type_kind(&fs_info.fs_type), FsKind::Local),
+                    mount_point_opt: Some(fs_info.mount_point),
+                },
+                Some(Err(err)) => {
+                    log::warn!("failed to get capabilities of {}: {}", self, err);
+                    LocationCapabilities::default()
+                }
+                None => LocationCapabilities::default(),
+            },
+        }
+    }
+
+    /// Title for tabs and windows, with folder paths shown as configured
+    pub fn tab_title(&self, tab_title: TabTitle) -> String {
+        match self {
+            Self::Path(path) => path_title(path, tab_title),
+            Self::Search(path, term, ..) => {
+                //TODO: translate
+                format!("Search \"{}\": {}", term, path_title(path, tab_title))
+            }
+            _ => self.title(),
         }
     }
 }
@@ -1330,19 +2517,37 @@ pub enum Command {
     AddToSidebar(PathBuf),
     AutoScroll(Option<f32>),
     ChangeLocation(String, Location, Option<Vec<PathBuf>>),
+    /// List view columns were resized in a folder, or in another location if `None`, and the
+    /// widths should be saved
+    ColumnWidths(Option<PathBuf>, ColumnWidths),
+    /// Create a new archive from paths dropped on an existing archive
+    Compress(Vec<PathBuf>, PathBuf),
     Delete(Vec<PathBuf>),
     DropFiles(PathBuf, ClipboardPaste),
+    EditTags(PathBuf),
     EmptyTrash,
     #[cfg(feature = "desktop")]
     ExecEntryAction(cosmic::desktop::DesktopEntryData, usize),
     Iced(TaskWrapper),
+    /// Open files with their default applications and folders in new tabs
+    OpenAll(Vec<PathBuf>, Vec<Location>),
     OpenFile(Vec<PathBuf>),
     OpenInNewTab(PathBuf),
     OpenInNewWindow(PathBuf),
+    OpenInTerminal(PathBuf),
     OpenTrash,
+    OpenWithApp(PathBuf, Mime, String),
+    /// Open a web page in the browser
+    OpenUrl(String),
     Preview(PreviewKind),
+    RestoreFromTrash(Vec<trash::TrashItem>),
+    /// List view columns were chosen for a folder, and should be saved
+    SetFolderColumns(PathBuf, Vec<HeadingOptions>),
     SetOpenWith(Mime, String),
     SetPermissions(PathBuf, u32),
+    /// Apply the mode, owner and group of a folder to everything inside it
+    SetPermissionsRecursive(PathBuf, u32, u32, u32),
+    Unmount(PathBuf),
     WindowDrag,
     WindowToggleMaximize,
 }
@@ -1350,9 +2555,24 @@ pub enum Command {
 #[derive(Clone, Debug)]
 pub enum Message {
     AddNetworkDrive,
+    /// Switch between a folder and its files grouped by date
+    BrowseByDate,
+    AnimationFrames(PathBuf, Vec<AnimationFrame>),
+    AnimationTick,
+    AudioInfo(PathBuf, AudioInfo),
+    /// Move the audio player of the selected item to this many seconds
+    AudioSeek(f32),
+    AudioTick,
+    /// The cursor moved across a grid item, to this fraction of its width
+    HoverScrub(usize, f32),
+    ImageInfo(PathBuf, ImageInfo),
     Click(Option<usize>),
     DoubleClick(Option<usize>),
     ClickRelease(Option<usize>),
+    ColumnAutoFit(Column),
+    ColumnMenu(bool),
+    ColumnResizeEnd,
+    ColumnResizeStart(Column),
     CursorMoved(Point),
     DragEnd(Option<usize>),
     Config(TabConfig),
@@ -1365,15 +2585,25 @@ pub enum Message {
     EditLocation(Option<EditLocation>),
     EditLocationComplete(usize),
     EditLocationEnable,
+    EditTags(usize),
     EditLocationSubmit,
     OpenInNewTab(PathBuf),
     EmptyTrash,
     #[cfg(feature = "desktop")]
     ExecEntryAction(Option<PathBuf>, usize),
+    /// Expand or collapse a folder in the tree view
+    ExpandFolder(usize),
+    ExpandItems(PathBuf, Vec<Item>),
     Gallery(bool),
     GalleryPrevious,
     GalleryNext,
     GalleryToggle,
+    /// Progress of the video streamed in the gallery, for a generation of its player
+    #[cfg(feature = "video-thumbnails")]
+    GalleryVideo(PathBuf, u64, VideoEvent),
+    /// Move the video in the gallery to this many seconds
+    #[cfg(feature = "video-thumbnails")]
+    GalleryVideoSeek(f32),
     GoNext,
     GoPrevious,
     ItemDown,
@@ -1381,47 +2611,112 @@ pub enum Message {
     ItemRight,
     ItemUp,
     Location(Location),
+    /// Open the parent of a path with that path selected
+    LocationSelect(PathBuf),
     LocationUp,
+    MediaFrames(PathBuf, Vec<AnimationFrame>),
+    /// Play or pause the media preview of the selected item
+    MediaPlayPause,
+    /// Seek the media preview of the selected item forward or backward
+    MediaSeek(bool),
+    MediaTick,
     ModifiersChanged(Modifiers),
     Open(Option<PathBuf>),
+    /// Open a path with the application that has this ID
+    OpenWithApp(PathBuf, Mime, String),
+    OpenUrl(String),
     Reload,
+    RestoreFromTrash(trash::TrashItem),
+    Reveal,
+    /// Listing of a visible folder, read ahead in case it is opened next
+    Prefetched(PathBuf, Option<Item>, Vec<Item>),
     RightClick(Option<usize>),
     MiddleClick(usize),
     Scroll(Viewport),
+    ScrollRestore,
+    /// Keep the item that was at the top of the view in place after refreshing
+    ScrollAnchor,
     ScrollTab(f32),
     ScrollToFocus,
     SearchContext(Location, SearchContextWrapper),
     SearchReady(bool),
+    SearchStop,
+    SearchTick,
     SelectAll,
+    SelectAllIncludingHidden,
     SelectFirst,
     SelectLast,
     SetOpenWith(Mime, String),
     SetPermissions(PathBuf, u32),
+    SetPermissionsRecursive(PathBuf, u32, u32, u32),
     SetSort(HeadingOptions, bool),
+    /// Start or stop the slideshow in the gallery
+    SlideshowToggle,
+    SlideshowTick,
     TabComplete(PathBuf, Vec<(String, PathBuf)>),
     Thumbnail(PathBuf, ItemThumbnail),
+    MimeSniffed(PathBuf, Mime),
     ToggleShowHidden,
+    TrashFilter(TrashFilter),
+    TrashSizes(HashMap<OsString, u64>),
     View(View),
+    ToggleColumn(HeadingOptions),
     ToggleSort(HeadingOptions),
+    ToggleSortSecondary(HeadingOptions),
     Drop(Option<(Location, ClipboardPaste)>),
     DndHover(Location),
     DndEnter(Location),
     DndLeave(Location),
     WindowDrag,
     WindowToggleMaximize,
+    ZoomDefault,
     ZoomIn,
     ZoomOut,
+    /// Show the zoom level over the view for a moment
+    ZoomIndicator,
+    ZoomIndicatorTimeout,
     HighlightDeactivate(usize),
     HighlightActivate(usize),
     DirectorySize(PathBuf, DirSize),
+    /// Redraw with the size counted so far, which is kept in the controller
+    DirectorySizeProgress(PathBuf),
+    DirectorySizeCancel(PathBuf),
+    DirectorySizeCalculate(PathBuf),
+    ArchiveEntries(PathBuf, ArchiveEntries),
+    DocumentPreview(PathBuf, DocumentPreview),
+    /// Show another page of a document in the details pane
+    DocumentPage(PathBuf, usize),
+    FsInfo(PathBuf, FsInfo),
+    Versions(PathBuf, Vec<FileVersion>),
+    Attributes(PathBuf, Result<Attributes, String>),
+    /// Attributes read again after changing them, or the error changing them
+    AttributesChanged(PathBuf, Result<Attributes, String>),
+    AttributesEdit(AttributesEdit),
+    /// Add the ACL entry typed in the advanced tab of the details pane
+    AclAdd(PathBuf),
+    AclRemove(PathBuf, AclTag, bool),
+    AclSet(PathBuf, AclEntry),
+    /// Add the extended attribute typed in the advanced tab of the details pane
+    XattrAdd(PathBuf),
+    XattrRemove(PathBuf, String),
+    /// Save the value typed for an extended attribute
+    XattrSet(PathBuf, String),
+    Capabilities(Location, LocationCapabilities),
+    Checksums(PathBuf, Checksums),
+    ChecksumsCalculate(PathBuf),
+    ChecksumsCancel(PathBuf),
+    ChecksumCopy(String),
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum LocationMenuAction {
     OpenInNewTab(usize),
     OpenInNewWindow(usize),
+    OpenInTerminal(usize),
+    CopyPath(usize),
     Preview(usize),
     AddToSidebar(usize),
+    Unmount(usize),
 }
 
 impl MenuAction for LocationMenuAction {
@@ -1440,6 +2735,83 @@ pub enum DirSize {
     Error(String),
 }
 
+/// Top-level entry of an archive, with the sizes of nested entries included in its folder
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub dir: bool,
+    pub size: u64,
+    /// Tar archives are compressed as a whole, so their entries have no compressed size
+    pub compressed_size: Option<u64>,
+}
+
+/// Text typed in the advanced tab of the details pane, kept until the change is made
+#[derive(Clone, Debug, Default)]
+pub struct AttributesEdit {
+    /// Item being edited, the text is not shown for other items
+    pub path_opt: Option<PathBuf>,
+    /// Adding an entry for a group instead of a user
+    pub acl_group: bool,
+    pub acl_name: String,
+    pub acl_default: bool,
+    pub xattr_name: String,
+    pub xattr_value: String,
+    /// Values typed for existing attributes, by name
+    pub xattr_values: HashMap<String, String>,
+    pub error_opt: Option<String>,
+}
+
+/// Checksums of a file, calculated when asked for in the details pane
+#[derive(Clone, Debug)]
+pub enum Checksums {
+    NotCalculated,
+    Calculating(Controller),
+    Calculated(Vec<(ChecksumAlgorithm, String)>),
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum ArchiveEntries {
+    Loading,
+    Loaded(Vec<ArchiveEntry>),
+    NotArchive,
+    Error(String),
+}
+
+/// Metadata and cover art of an audio file, loaded when it is shown in the details pane
+#[derive(Clone, Debug)]
+pub enum AudioInfo {
+    Loading,
+    Loaded(Box<AudioMetadata>, Option<widget::image::Handle>),
+    NotAudio,
+    /// ffprobe or ffplay is not installed
+    Unavailable,
+    Error(String),
+}
+
+/// Camera metadata of a photo, loaded when it is shown in the details pane
+#[derive(Clone, Debug)]
+pub enum ImageInfo {
+    Loading,
+    Loaded(Box<ImageMetadata>),
+    /// The item is not a photo, or has no camera metadata
+    NoMetadata,
+    Error(String),
+}
+
+/// Start of a text file or a page of a PDF, loaded when it is shown in the details pane
+#[derive(Clone, Debug)]
+pub enum DocumentPreview {
+    NotDocument,
+    Loading,
+    Text(Vec<HighlightedLine>),
+    /// Page numbered from 1, the page count, and the rendered page
+    Page(usize, usize, widget::image::Handle),
+    /// Another page was chosen and is being rendered
+    LoadingPage(usize, usize),
+    Error(String),
+}
+
 #[derive(Clone, Debug)]
 pub enum ItemMetadata {
     Path {
@@ -1487,6 +2859,133 @@ pub enum ItemThumbnail {
     Text(widget::text_editor::Content),
 }
 
+/// OpenDocument and Office Open XML files, which are zip packages that may embed a preview
+fn is_office_document(mime: &mime::Mime) -> bool {
+    mime.type_() == mime::APPLICATION && {
+        let subtype = mime.subtype();
+        subtype.as_str().starts_with("vnd.oasis.opendocument.")
+            || subtype
+                .as_str()
+                .starts_with("vnd.openxmlformats-officedocument.")
+    }
+}
+
+/// Read the first page preview that office suites store inside the document package
+fn office_embedded_thumbnail(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    const THUMBNAIL_NAMES: &[&str] = &[
+        // OpenDocument
+        "Thumbnails/thumbnail.png",
+        // Office Open XML
+        "docProps/thumbnail.jpeg",
+        "docProps/thumbnail.jpg",
+        "docProps/thumbnail.png",
+    ];
+    /// Largest preview read, the size in the package cannot be trusted
+    const MAX_THUMBNAIL_SIZE: u64 = 16 * 1024 * 1024;
+
+    let file = fs::File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|err| err.to_string())?;
+    for name in THUMBNAIL_NAMES {
+        let mut entry = match archive.by_name(name) {
+            Ok(ok) => ok,
+            Err(zip::result::ZipError::FileNotFound) => continue,
+            Err(err) => return Err(err.to_string()),
+        };
+        if entry.size() > MAX_THUMBNAIL_SIZE {
+            return Err(format!("{} is too large", name));
+        }
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry
+            .take(MAX_THUMBNAIL_SIZE)
+            .read_to_end(&mut data)
+            .map_err(|err| err.to_string())?;
+        return Ok(Some(data));
+    }
+    Ok(None)
+}
+
+fn archive_entries_supported(mime: &Mime) -> bool {
+    matches!(
+        mime.essence_str(),
+        "application/gzip"
+            | "application/x-compressed-tar"
+            | "application/x-tar"
+            | "application/zip"
+    )
+}
+
+/// List the top-level entries of an archive without extracting it
+fn archive_entries(path: &Path, mime: &Mime) -> Result<Vec<ArchiveEntry>, String> {
+    let mut entries: BTreeMap<String, ArchiveEntry> = BTreeMap::new();
+    let mut add_entry = |entry_path: &Path, dir: bool, size: u64, compressed_size: Option<u64>| {
+        let mut components = entry_path
+            .components()
+            .filter_map(|component| match component {
+                std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            });
+        let Some(name) = components.next() else {
+            return;
+        };
+        // Anything below the top level is part of a folder
+        let dir = dir || components.next().is_some();
+        let entry = entries.entry(name.clone()).or_insert_with(|| ArchiveEntry {
+            name,
+            dir,
+            size: 0,
+            compressed_size: compressed_size.map(|_| 0),
+        });
+        entry.dir |= dir;
+        entry.size += size;
+        entry.compressed_size = entry
+            .compressed_size
+            .zip(compressed_size)
+            .map(|(total, size)| total + size);
+    };
+
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    match mime.essence_str() {
+        "application/zip" => {
+            let mut archive =
+                zip::ZipArchive::new(BufReader::new(file)).map_err(|err| err.to_string())?;
+            for i in 0..archive.len() {
+                // Raw access reads sizes without needing the password of encrypted archives
+                let entry = archive.by_index_raw(i).map_err(|err| err.to_string())?;
+                add_entry(
+                    Path::new(entry.name()),
+                    entry.is_dir(),
+                    entry.size(),
+                    Some(entry.compressed_size()),
+                );
+            }
+        }
+        "application/gzip" | "application/x-compressed-tar" | "application/x-tar" => {
+            let reader: Box<dyn Read> = if mime.essence_str() == "application/x-tar" {
+                Box::new(BufReader::new(file))
+            } else {
+                Box::new(flate2::read::GzDecoder::new(BufReader::new(file)))
+            };
+            let mut archive = tar::Archive::new(reader);
+            for entry in archive.entries().map_err(|err| err.to_string())? {
+                let entry = entry.map_err(|err| err.to_string())?;
+                let entry_path = entry.path().map_err(|err| err.to_string())?;
+                add_entry(
+                    &entry_path,
+                    entry.header().entry_type().is_dir(),
+                    entry.size(),
+                    None,
+                );
+            }
+        }
+        _ => return Err(format!("unsupported mime type {:?}", mime)),
+    }
+
+    let mut entries: Vec<_> = entries.into_values().collect();
+    // Folders are listed first, like in the folder view
+    entries.sort_by_key(|entry| !entry.dir);
+    Ok(entries)
+}
+
 impl Clone for ItemThumbnail {
     fn clone(&self) -> Self {
         match self {
@@ -1501,15 +3000,472 @@ impl Clone for ItemThumbnail {
     }
 }
 
-impl ItemThumbnail {
-    pub fn new(path: &Path, metadata: fs::Metadata, mime: mime::Mime, thumbnail_size: u32) -> Self {
-        let size = metadata.len();
-        let check_size = |thumbnailer: &str, max_size| {
-            if size <= max_size {
-                true
+pub type AnimationFrame = (widget::icon::Handle, Duration);
+
+/// Files that can play an animated preview
+fn is_animated_mime(mime: &Mime) -> bool {
+    // Videos are decoded with ffmpeg, otherwise they keep their static thumbnail
+    (cfg!(feature = "video-thumbnails") && mime.type_() == mime::VIDEO)
+        || (mime.type_() == mime::IMAGE && matches!(mime.subtype().as_str(), "gif" | "webp"))
+}
+
+/// Decode up to `duration` of an animation or video, scaled to the thumbnail size
+pub fn animation_frames(
+    path: &Path,
+    mime: &Mime,
+    thumbnail_size: u32,
+    duration: Duration,
+) -> Result<Vec<AnimationFrame>, String> {
+    use image::AnimationDecoder;
+
+    let frame_handle = |image: image::DynamicImage| {
+        let thumbnail = image.thumbnail(thumbnail_size, thumbnail_size).into_rgba8();
+        widget::icon::Handle {
+            symbolic: false,
+            data: widget::icon::Data::Image(widget::image::Handle::from_rgba(
+                thumbnail.width(),
+                thumbnail.height(),
+                thumbnail.into_raw(),
+            )),
+        }
+    };
+    let collect_frames = |frames: image::Frames| -> Result<Vec<AnimationFrame>, String> {
+        let mut total = Duration::ZERO;
+        let mut result = Vec::new();
+        for frame_res in frames {
+            let frame = frame_res.map_err(|err| err.to_string())?;
+            let delay = Duration::from(frame.delay());
+            // Some files use no delay, which browsers treat as a default delay
+            let delay = if delay.is_zero() {
+                ANIMATION_FRAME_DELAY
             } else {
-                log::warn!(
-                    "skipping internal {} thumbnailer for {:?}: file size {} is larger than {}",
+                delay
+            };
+            result.push((frame_handle(frame.into_buffer().into()), delay));
+            total += delay;
+            if total >= duration {
+                break;
+            }
+        }
+        Ok(result)
+    };
+
+    if mime.type_() == mime::VIDEO {
+        return video_frames(path, thumbnail_size, duration);
+    }
+
+    let reader = BufReader::new(File::open(path).map_err(|err| err.to_string())?);
+    match mime.subtype().as_str() {
+        "gif" => collect_frames(
+            image::codecs::gif::GifDecoder::new(reader)
+                .map_err(|err| err.to_string())?
+                .into_frames(),
+        ),
+        "webp" => {
+            let decoder =
+                image::codecs::webp::WebPDecoder::new(reader).map_err(|err| err.to_string())?;
+            if decoder.has_animation() {
+                collect_frames(decoder.into_frames())
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Frames of the start of a video
+#[cfg(feature = "video-thumbnails")]
+fn video_frames(
+    path: &Path,
+    thumbnail_size: u32,
+    duration: Duration,
+) -> Result<Vec<AnimationFrame>, String> {
+    let fps = 1000 / ANIMATION_FRAME_DELAY.as_millis();
+    Ok(
+        crate::video_thumbnailer::frames(path, thumbnail_size, duration, fps)?
+            .into_iter()
+            .map(|image| {
+                (
+                    widget::icon::Handle {
+                        symbolic: false,
+                        data: widget::icon::Data::Image(widget::image::Handle::from_rgba(
+                            image.width(),
+                            image.height(),
+                            image.into_raw(),
+                        )),
+                    },
+                    ANIMATION_FRAME_DELAY,
+                )
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(feature = "video-thumbnails"))]
+fn video_frames(
+    _path: &Path,
+    _thumbnail_size: u32,
+    _duration: Duration,
+) -> Result<Vec<AnimationFrame>, String> {
+    Ok(Vec::new())
+}
+
+/// Frames spread over a video, chosen by the cursor position while hovering in grid view
+#[cfg(feature = "video-thumbnails")]
+fn scrub_frames(path: &Path, thumbnail_size: u32) -> Result<Vec<AnimationFrame>, String> {
+    Ok(
+        crate::video_thumbnailer::scrub_frames(path, thumbnail_size)?
+            .into_iter()
+            .map(|image| {
+                (
+                    widget::icon::Handle {
+                        symbolic: false,
+                        data: widget::icon::Data::Image(widget::image::Handle::from_rgba(
+                            image.width(),
+                            image.height(),
+                            image.into_raw(),
+                        )),
+                    },
+                    ANIMATION_FRAME_DELAY,
+                )
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(feature = "video-thumbnails"))]
+fn scrub_frames(_path: &Path, _thumbnail_size: u32) -> Result<Vec<AnimationFrame>, String> {
+    Ok(Vec::new())
+}
+
+/// Animated preview of a hovered grid item, which stops after [`ANIMATION_DURATION`]
+struct Animation {
+    index: usize,
+    path: PathBuf,
+    mime: Mime,
+    frames_opt: Option<Vec<AnimationFrame>>,
+    frame: usize,
+    start: Instant,
+    /// Position of the cursor across the item, for videos that are scrubbed instead of played
+    scrub_opt: Option<f32>,
+}
+
+impl Animation {
+    fn scrub(&mut self, fraction: f32) {
+        self.scrub_opt = Some(fraction);
+        if let Some(frames) = &self.frames_opt {
+            let last = frames.len().saturating_sub(1);
+            self.frame = ((fraction.clamp(0.0, 1.0) * frames.len() as f32) as usize).min(last);
+        }
+    }
+}
+
+/// Seek and play buttons of the media preview of the selected item
+fn media_controls<'a>(playing: bool) -> Element<'a, Message> {
+    let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+    widget::row::with_children(vec![
+        widget::button::icon(widget::icon::from_name("media-seek-backward-symbolic"))
+            .on_press(Message::MediaSeek(false))
+            .into(),
+        widget::button::icon(widget::icon::from_name(if playing {
+            "media-playback-pause-symbolic"
+        } else {
+            "media-playback-start-symbolic"
+        }))
+        .on_press(Message::MediaPlayPause)
+        .into(),
+        widget::button::icon(widget::icon::from_name("media-seek-forward-symbolic"))
+            .on_press(Message::MediaSeek(true))
+            .into(),
+    ])
+    .align_y(Alignment::Center)
+    .spacing(space_xxs)
+    .into()
+}
+
+/// Highlighted text, or a page of a document with buttons to move between pages
+fn document_view<'a>(item: &'a Item, path: &Path) -> Element<'a, Message> {
+    let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+    let (page, pages, page_opt) = match &item.document_preview {
+        DocumentPreview::Text(lines) => {
+            let mut column = widget::column::with_capacity(lines.len());
+            for line in lines.iter() {
+                let mut spans = Vec::with_capacity(line.len().max(1));
+                for (color, text) in line.iter() {
+                    spans.push(
+                        cosmic::iced::widget::span(text.as_str())
+                            .color(*color)
+                            .font(font::mono()),
+                    );
+                }
+                // Keep the height of empty lines
+                if spans.is_empty() {
+                    spans.push(cosmic::iced::widget::span(" ").font(font::mono()));
+                }
+                column = column.push(cosmic::iced::widget::rich_text(spans).size(12));
+            }
+            return widget::container(column)
+                .padding(space_xxs)
+                .width(Length::Fill)
+                .class(theme::Container::Card)
+                .into();
+        }
+        DocumentPreview::Page(page, pages, handle) => (*page, *pages, Some(handle)),
+        DocumentPreview::LoadingPage(page, pages) => (*page, *pages, None),
+        _ => return item.preview(),
+    };
+
+    let page_view: Element<_> = match page_opt {
+        Some(handle) => widget::image(handle.clone())
+            .content_fit(ContentFit::Contain)
+            .into(),
+        None => item.preview(),
+    };
+    let mut column = widget::column::with_capacity(2).spacing(space_xxs).push(
+        widget::container(page_view)
+            .center_x(Length::Fill)
+            .max_height(THUMBNAIL_SIZE as f32),
+    );
+    if pages > 1 {
+        column = column.push(
+            widget::container(
+                widget::row::with_children(vec![
+                    widget::button::icon(widget::icon::from_name("go-previous-symbolic"))
+                        .on_press_maybe(
+                            (page > 1).then(|| Message::DocumentPage(path.to_path_buf(), page - 1)),
+                        )
+                        .into(),
+                    widget::text::caption(fl!("page-of", page = page, pages = pages)).into(),
+                    widget::button::icon(widget::icon::from_name("go-next-symbolic"))
+                        .on_press_maybe(
+                            (page < pages)
+                                .then(|| Message::DocumentPage(path.to_path_buf(), page + 1)),
+                        )
+                        .into(),
+                ])
+                .align_y(Alignment::Center)
+                .spacing(space_xxs),
+            )
+            .center_x(Length::Fill),
+        );
+    }
+    column.into()
+}
+
+/// First page of a PDF with the page count, or the start of a text file
+fn load_document_preview(path: &Path, mime: &Mime, dark: bool) -> Result<DocumentPreview, String> {
+    #[cfg(feature = "pdf-preview")]
+    if document_preview::pdf_preview_supported(mime) {
+        let pages = document_preview::pdf_page_count(path)?;
+        let page = document_preview::pdf_page(path, 1, THUMBNAIL_SIZE)?;
+        return Ok(DocumentPreview::Page(
+            1,
+            pages,
+            widget::image::Handle::from_bytes(page),
+        ));
+    }
+    let _ = mime;
+    document_preview::highlight_text(path, dark).map(DocumentPreview::Text)
+}
+
+/// Slideshow in the gallery, which shows the selected items, or all items if only one is selected
+struct Slideshow {
+    /// Items in the order they are shown
+    paths: Vec<PathBuf>,
+    position: usize,
+    /// Whether only the selected items are shown, which keeps them selected
+    selected_only: bool,
+}
+
+impl Slideshow {
+    /// Path after the one that is shown, or `None` at the end unless it repeats
+    fn advance(&mut self, current_opt: Option<&Path>, config: SlideshowConfig) -> Option<&Path> {
+        // Items may have been shown with the gallery buttons in the meantime
+        if let Some(position) =
+            current_opt.and_then(|current| self.paths.iter().position(|path| path == current))
+        {
+            self.position = position;
+        }
+        self.position += 1;
+        if self.position >= self.paths.len() {
+            if !config.repeat {
+                return None;
+            }
+            if config.shuffle {
+                fastrand::shuffle(&mut self.paths);
+            }
+            self.position = 0;
+        }
+        self.paths.get(self.position).map(PathBuf::as_path)
+    }
+}
+
+/// Video played in the gallery, with the frame last streamed
+#[cfg(feature = "video-thumbnails")]
+struct GalleryVideo {
+    player: crate::video_player::VideoPlayer,
+    frame_opt: Option<widget::image::Handle>,
+    /// Generation of the player that the frame belongs to, so that seeking while paused loads
+    /// the frame at the new position
+    frame_generation_opt: Option<u64>,
+}
+
+#[cfg(feature = "video-thumbnails")]
+#[derive(Clone, Debug)]
+pub enum VideoEvent {
+    Duration(Duration),
+    Frame(widget::image::Handle),
+    End,
+}
+
+/// Send the frames of the video in the gallery from `position` at the pace of the video, or only
+/// the frame there if it is paused
+#[cfg(feature = "video-thumbnails")]
+fn gallery_video_frames(
+    path: PathBuf,
+    generation: u64,
+    position: Duration,
+    probe: bool,
+    playing: bool,
+    mut output: futures::channel::mpsc::Sender<Message>,
+) {
+    let mut send = |event| {
+        futures::executor::block_on(output.send(Message::GalleryVideo(
+            path.clone(),
+            generation,
+            event,
+        )))
+        .is_ok()
+    };
+    let handle = |image: image::RgbaImage| {
+        widget::image::Handle::from_rgba(image.width(), image.height(), image.into_raw())
+    };
+    if probe {
+        match crate::video_thumbnailer::duration(&path) {
+            Ok(duration) => {
+                send(VideoEvent::Duration(duration));
+            }
+            Err(err) => log::warn!("failed to get duration of {:?}: {}", path, err),
+        }
+    }
+    if playing {
+        match crate::video_player::stream(&path, position, GALLERY_VIDEO_SIZE, |image| {
+            send(VideoEvent::Frame(handle(image)))
+        }) {
+            Ok(()) => {
+                send(VideoEvent::End);
+            }
+            Err(err) => log::warn!("failed to play {:?}: {}", path, err),
+        }
+    } else {
+        match crate::video_thumbnailer::frame_at(&path, position, GALLERY_VIDEO_SIZE) {
+            Ok(image) => {
+                send(VideoEvent::Frame(handle(image)));
+            }
+            Err(err) => log::warn!("failed to get frame of {:?}: {}", path, err),
+        }
+    }
+}
+
+/// Seek and play buttons, and the position, of the video in the gallery
+#[cfg(feature = "video-thumbnails")]
+fn gallery_video_controls<'a>(player: &crate::video_player::VideoPlayer) -> Element<'a, Message> {
+    let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+    let position = player.position();
+    let controls = widget::row::with_capacity(5)
+        .align_y(Alignment::Center)
+        .spacing(space_xxs)
+        .push(media_controls(player.playing()));
+    match player.duration_opt() {
+        Some(duration) => controls
+            .push(
+                widget::slider(
+                    0.0..=duration.as_secs_f32(),
+                    position.as_secs_f32(),
+                    Message::GalleryVideoSeek,
+                )
+                .width(Length::Fixed(360.0)),
+            )
+            .push(widget::text::caption(format!(
+                "{} / {}",
+                format_duration(position),
+                format_duration(duration)
+            )))
+            .into(),
+        None => controls
+            .push(widget::text::caption(format_duration(position)))
+            .into(),
+    }
+}
+
+/// Media preview of the selected item in the details pane, controlled with buttons or keys
+struct MediaPreview {
+    path: PathBuf,
+    mime: Mime,
+    /// Size of the frames, larger in the gallery
+    size: u32,
+    frames_opt: Option<Vec<AnimationFrame>>,
+    frame: usize,
+    playing: bool,
+}
+
+impl MediaPreview {
+    fn seek(&mut self, forward: bool) {
+        let Some(frames) = &self.frames_opt else {
+            return;
+        };
+        let mut elapsed = Duration::ZERO;
+        while elapsed < MEDIA_SEEK_STEP {
+            let next = if forward {
+                self.frame + 1
+            } else {
+                match self.frame.checked_sub(1) {
+                    Some(some) => some,
+                    None => break,
+                }
+            };
+            let Some((_, delay)) = frames.get(next) else {
+                break;
+            };
+            elapsed += *delay;
+            self.frame = next;
+        }
+    }
+}
+
+impl ItemThumbnail {
+    pub fn new(path: &Path, metadata: fs::Metadata, mime: mime::Mime, thumbnail_size: u32) -> Self {
+        // Thumbnails made before, by this or other apps, are used until the file changes
+        if let Some((image, original_size_opt)) =
+            thumbnail_cache::load(path, &metadata, thumbnail_size)
+        {
+            return ItemThumbnail::Image(
+                widget::image::Handle::from_rgba(image.width(), image.height(), image.into_raw()),
+                original_size_opt,
+            );
+        }
+        // Image thumbnails are saved to the cache for next time
+        let cached = |image: image::RgbaImage, original_size_opt| {
+            if let Err(err) =
+                thumbnail_cache::save(path, &metadata, thumbnail_size, &image, original_size_opt)
+            {
+                log::warn!("failed to save thumbnail of {:?}: {}", path, err);
+            }
+            ItemThumbnail::Image(
+                widget::image::Handle::from_rgba(image.width(), image.height(), image.into_raw()),
+                original_size_opt,
+            )
+        };
+
+        let size = metadata.len();
+        let check_size = |thumbnailer: &str, max_size| {
+            if size <= max_size {
+                true
+            } else {
+                log::warn!(
+                    "skipping internal {} thumbnailer for {:?}: file size {} is larger than {}",
                     thumbnailer,
                     path,
                     format_size(size),
@@ -1540,14 +3496,7 @@ impl ItemThumbnail {
                     Ok(image) => {
                         let thumbnail =
                             image.thumbnail(thumbnail_size, thumbnail_size).into_rgba8();
-                        return ItemThumbnail::Image(
-                            widget::image::Handle::from_rgba(
-                                thumbnail.width(),
-                                thumbnail.height(),
-                                thumbnail.into_raw(),
-                            ),
-                            Some((image.width(), image.height())),
-                        );
+                        return cached(thumbnail, Some((image.width(), image.height())));
                     }
                     Err(err) => {
                         log::warn!("failed to decode {:?}: {}", path, err);
@@ -1557,6 +3506,24 @@ impl ItemThumbnail {
                     log::warn!("failed to read {:?}: {}", path, err);
                 }
             }
+        } else if is_office_document(&mime) && check_size("office", 64 * 1000 * 1000) {
+            // Try preview image embedded in the document package
+            match office_embedded_thumbnail(path) {
+                Ok(Some(data)) => match image::load_from_memory(&data) {
+                    Ok(image) => {
+                        let thumbnail =
+                            image.thumbnail(thumbnail_size, thumbnail_size).into_rgba8();
+                        return cached(thumbnail, None);
+                    }
+                    Err(err) => {
+                        log::warn!("failed to decode embedded thumbnail of {:?}: {}", path, err);
+                    }
+                },
+                Ok(None) => {}
+                Err(err) => {
+                    log::warn!("failed to read {:?}: {}", path, err);
+                }
+            }
         } else if mime.type_() == mime::TEXT && check_size("text", 8 * 1000 * 1000) {
             /*TODO: fix performance issues, widget::text_editr::Content::with_text forces all text to shape, which blocks rendering
             match fs::read_to_string(&path) {
@@ -1570,6 +3537,31 @@ impl ItemThumbnail {
             */
         }
 
+        #[cfg(feature = "video-thumbnails")]
+        if mime.type_() == mime::VIDEO {
+            // Try built-in video thumbnailer
+            match crate::video_thumbnailer::poster_frame(path, thumbnail_size) {
+                Ok(image) => return cached(image, None),
+                Err(err) => {
+                    log::warn!("failed to get poster frame of {:?}: {}", path, err);
+                }
+            }
+        }
+
+        #[cfg(feature = "model-thumbnails")]
+        if crate::model_thumbnailer::is_supported(path) && check_size("model", 64 * 1000 * 1000) {
+            // Try built-in 3D model thumbnailer
+            match crate::model_thumbnailer::thumbnail(path, thumbnail_size) {
+                Ok(Some(image)) => {
+                    return cached(image, None);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    log::warn!("failed to render {:?}: {}", path, err);
+                }
+            }
+        }
+
         // Try external thumbnailers
         for thumbnailer in thumbnailer(&mime) {
             let prefix = if thumbnailer.exec.starts_with("evince-thumbnailer ") {
@@ -1601,14 +3593,7 @@ impl ItemThumbnail {
                         {
                             Ok(reader) => match reader.decode().map(|image| image.into_rgba8()) {
                                 Ok(image) => {
-                                    return ItemThumbnail::Image(
-                                        widget::image::Handle::from_rgba(
-                                            image.width(),
-                                            image.height(),
-                                            image.into_raw(),
-                                        ),
-                                        None,
-                                    );
+                                    return cached(image, None);
                                 }
                                 Err(err) => {
                                     log::warn!("failed to decode {:?}: {}", path, err);
@@ -1652,6 +3637,26 @@ pub struct Item {
     pub cut: bool,
     pub overlaps_drag_rect: bool,
     pub dir_size: DirSize,
+    pub archive_entries: ArchiveEntries,
+    pub audio_info: AudioInfo,
+    pub image_info: ImageInfo,
+    pub document_preview: DocumentPreview,
+    /// File system containing a folder, loaded when the folder is shown in the details pane
+    pub fs_info_opt: Option<FsInfo>,
+    /// Earlier versions in file system snapshots, loaded when the item is shown in the details
+    /// pane
+    pub versions_opt: Option<Vec<FileVersion>>,
+    /// ACL entries and extended attributes, or the error reading them, loaded when the item is
+    /// shown in the details pane
+    pub attributes_opt: Option<Result<Attributes, String>>,
+    pub checksums: Checksums,
+    pub tags: Vec<ColorTag>,
+    /// Sorted, see [`Item::add_emblem`]
+    pub emblems: Vec<Emblem>,
+    /// Nesting below the tab location, for the contents of expanded folders in the tree view
+    pub depth: u16,
+    /// Contents are shown below the folder in the tree view
+    pub expanded: bool,
 }
 
 impl Item {
@@ -1660,12 +3665,83 @@ impl Item {
         name.replace(".", ".\u{200B}").replace("_", "_\u{200B}")
     }
 
+    /// Whether both items are the same file, unchanged since the older one was read
+    fn unchanged(&self, old: &Self) -> bool {
+        match (&self.metadata, &old.metadata) {
+            (
+                ItemMetadata::Path { metadata, .. },
+                ItemMetadata::Path {
+                    metadata: old_metadata,
+                    ..
+                },
+            ) => {
+                self.location_opt == old.location_opt
+                    && metadata.is_dir() == old_metadata.is_dir()
+                    && metadata.len() == old_metadata.len()
+                    && metadata.modified().ok() == old_metadata.modified().ok()
+            }
+            _ => false,
+        }
+    }
+
+    /// Keep the thumbnail and details loaded for an older item of the same unchanged file, so
+    /// that they are not loaded again
+    fn take_loaded(&mut self, old: &mut Self) {
+        if self.thumbnail_opt.is_none() {
+            self.thumbnail_opt = old.thumbnail_opt.take();
+        }
+        std::mem::swap(&mut self.archive_entries, &mut old.archive_entries);
+        std::mem::swap(&mut self.audio_info, &mut old.audio_info);
+        std::mem::swap(&mut self.image_info, &mut old.image_info);
+        std::mem::swap(&mut self.document_preview, &mut old.document_preview);
+        std::mem::swap(&mut self.checksums, &mut old.checksums);
+        self.fs_info_opt = old.fs_info_opt.take();
+        self.versions_opt = old.versions_opt.take();
+    }
+
     pub fn path_opt(&self) -> Option<&PathBuf> {
         self.location_opt.as_ref()?.path_opt()
     }
 
+    /// Add an emblem to the icon, for subsystems like sync providers that know more about the item
+    pub fn add_emblem(&mut self, emblem: Emblem) {
+        if let Err(index) = self.emblems.binary_search(&emblem) {
+            self.emblems.insert(index, emblem);
+        }
+    }
+
+    pub fn remove_emblem(&mut self, emblem: Emblem) {
+        self.emblems.retain(|x| *x != emblem);
+    }
+
+    pub fn has_emblem(&self, emblem: Emblem) -> bool {
+        self.emblems.contains(&emblem)
+    }
+
+    /// Extension shown on the icon in grid view, for files without a thumbnail
+    fn extension_badge_text(&self) -> Option<String> {
+        if self.metadata.is_dir()
+            || matches!(
+                self.thumbnail_opt,
+                Some(ItemThumbnail::Image(..) | ItemThumbnail::Svg(_))
+            )
+        {
+            return None;
+        }
+        let extension = Path::new(&self.name).extension()?.to_str()?;
+        (!extension.is_empty() && extension.chars().count() <= EXTENSION_BADGE_MAX_CHARS)
+            .then(|| extension.to_uppercase())
+    }
+
+    /// Cut and git ignored items are drawn dimmed
+    fn dimmed(&self) -> bool {
+        self.cut || self.has_emblem(Emblem::Ignored)
+    }
+
     pub fn can_gallery(&self) -> bool {
-        self.mime.type_() == mime::IMAGE || self.mime.type_() == mime::TEXT
+        self.mime.type_() == mime::IMAGE
+            || self.mime.type_() == mime::TEXT
+            || is_animated_mime(&self.mime)
     }
 
     fn preview(&self) -> Element<'_, Message> {
@@ -1725,34 +3801,84 @@ impl Item {
         row
     }
 
+    /// Show the item details, with `media_opt` replacing the thumbnail if the item can be played.
+    /// Only the part for `details_tab_opt` is shown if set, otherwise all but the versions and
+    /// attributes. Attributes can be changed if `attributes_edit_opt` is set.
     pub fn preview_view<'a>(
         &'a self,
         mime_app_cache_opt: Option<&'a mime_app::MimeAppCache>,
+        details_tab_opt: Option<DetailsTab>,
+        attributes_edit_opt: Option<&AttributesEdit>,
         military_time: bool,
+        media_opt: Option<Element<'a, Message>>,
     ) -> Element<'a, Message> {
         let cosmic_theme::Spacing {
             space_xxxs,
+            space_s,
             space_m,
             ..
         } = theme::active().cosmic().spacing;
 
+        let show = |tab: DetailsTab| details_tab_opt.map_or(true, |details_tab| details_tab == tab);
         let mut column = widget::column().spacing(space_m);
 
-        column = column.push(
-            widget::container(self.preview())
-                .center_x(Length::Fill)
-                .max_height(THUMBNAIL_SIZE as f32),
-        );
+        if show(DetailsTab::Preview) {
+            column = match media_opt {
+                Some(media) => column.push(media),
+                None => column.push(
+                    widget::container(self.preview())
+                        .center_x(Length::Fill)
+                        .max_height(THUMBNAIL_SIZE as f32),
+                ),
+            };
+        }
 
-        let mut details = widget::column().spacing(space_xxxs);
-        details = details.push(widget::text::heading(self.name.clone()));
-        details = details.push(widget::text::body(fl!(
+        // The name and type are shown on every tab
+        let mut title = widget::column().spacing(space_xxxs);
+        title = title.push(widget::text::heading(self.name.clone()));
+        title = title.push(widget::text::body(fl!(
             "type",
             mime = self.mime.to_string()
         )));
+        column = column.push(title);
+
+        let mut details = widget::column().spacing(space_xxxs);
         let mut settings = Vec::new();
-        if let Some(mime_app_cache) = mime_app_cache_opt {
+        let mut permissions = Vec::new();
+        let mut apps_opt = None;
+        // Trashed items cannot be opened, so there is no need to choose an app
+        if let Some(mime_app_cache) =
+            mime_app_cache_opt.filter(|_| !matches!(self.metadata, ItemMetadata::Trash { .. }))
+        {
             let mime_apps = mime_app_cache.get(&self.mime);
+            if let Some(path) = self.path_opt().filter(|_| !mime_apps.is_empty()) {
+                let mut section = widget::settings::section().title(fl!("related-apps"));
+                for app in mime_apps.iter().take(OPEN_WITH_PREVIEW_APPS) {
+                    section = section.add(
+                        widget::button::custom(
+                            widget::row::with_children(vec![
+                                widget::icon(app.icon.clone()).size(24).into(),
+                                widget::text::body(if app.is_default {
+                                    fl!("default-app", name = Some(app.name.as_str()))
+                                } else {
+                                    app.name.clone()
+                                })
+                                .into(),
+                            ])
+                            .spacing(space_s)
+                            .align_y(Alignment::Center),
+                        )
+                        .width(Length::Fill)
+                        .class(theme::Button::MenuItem)
+                        .on_press(Message::OpenWithApp(
+                            path.clone(),
+                            self.mime.clone(),
+                            app.id.clone(),
+                        )),
+                    );
+                }
+                apps_opt = Some(section);
+            }
             if !mime_apps.is_empty() {
                 settings.push(
                     widget::settings::item::builder(fl!("open-with")).control(
@@ -1772,6 +3898,7 @@ impl Item {
                 );
             }
         }
+        let mut restore_opt = None;
         match &self.metadata {
             ItemMetadata::Path {
                 metadata,
@@ -1781,14 +3908,67 @@ impl Item {
                     if let Some(children) = children_opt {
                         details = details.push(widget::text::body(fl!("items", items = children)));
                     }
-                    let size = match &self.dir_size {
-                        DirSize::Calculating(_) => fl!("calculating"),
-                        DirSize::Directory(size) => format_size(*size),
-                        DirSize::NotDirectory => String::new(),
-                        DirSize::Error(err) => err.clone(),
+                    // The size so far is shown while calculating, which can be cancelled and
+                    // started again
+                    let size_row = |text: String, label: String, message_opt: Option<Message>| {
+                        widget::row::with_children(vec![
+                            widget::text::body(text).into(),
+                            match message_opt {
+                                Some(message) => widget::button::link(label)
+                                    .on_press(message)
+                                    .padding(0)
+                                    .into(),
+                                None => Space::with_width(Length::Shrink).into(),
+                            },
+                        ])
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxxs)
                     };
-                    if !size.is_empty() {
-                        details = details.push(widget::text::body(fl!("item-size", size = size)));
+                    let path_opt = self.path_opt().cloned();
+                    match &self.dir_size {
+                        DirSize::Calculating(controller) => {
+                            details = details.push(size_row(
+                                fl!(
+                                    "item-size-calculating",
+                                    size = format_size(controller.bytes().0)
+                                ),
+                                fl!("cancel"),
+                                path_opt.map(Message::DirectorySizeCancel),
+                            ));
+                        }
+                        DirSize::Directory(size) => {
+                            details = details.push(widget::text::body(fl!(
+                                "item-size",
+                                size = format_size(*size)
+                            )));
+                        }
+                        DirSize::NotDirectory => {}
+                        DirSize::Error(err) => {
+                            details = details.push(size_row(
+                                fl!("item-size", size = err.clone()),
+                                fl!("calculate-size"),
+                                path_opt.map(Message::DirectorySizeCalculate),
+                            ));
+                        }
+                    }
+                    if let Some(fs_info) = &self.fs_info_opt {
+                        details = details.push(widget::text::body(fl!(
+                            "item-filesystem",
+                            fs_type = fs_info.fs_type.clone(),
+                            mount_point = fs_info.mount_point.display().to_string()
+                        )));
+                        details = details.push(widget::text::body(fl!(
+                            "item-free-space",
+                            free = format_size(fs_info.free),
+                            total = format_size(fs_info.total)
+                        )));
+                        if let Some(quota) = &fs_info.quota_opt {
+                            details = details.push(widget::text::body(fl!(
+                                "item-quota",
+                                used = format_size(quota.used),
+                                limit = format_size(quota.limit)
+                            )));
+                        }
                     }
                 } else {
                     details = details.push(widget::text::body(fl!(
@@ -1799,6 +3979,20 @@ impl Item {
 
                 let date_time_formatter = date_time_formatter(military_time);
                 let time_formatter = time_formatter(military_time);
+                // Recent activity is easier to read as how long ago it was
+                let now = SystemTime::now();
+                let recent_time = |time: SystemTime| {
+                    let formatted =
+                        format_time(time, &date_time_formatter, &time_formatter).to_string();
+                    match RelativeTime::new(time, now) {
+                        Some(relative) => fl!(
+                            "time-with-relative",
+                            time = formatted,
+                            relative = relative.to_string()
+                        ),
+                        None => formatted,
+                    }
+                };
 
                 if let Ok(time) = metadata.created() {
                     details = details.push(widget::text::body(fl!(
@@ -1811,16 +4005,14 @@ impl Item {
                 if let Ok(time) = metadata.modified() {
                     details = details.push(widget::text::body(fl!(
                         "item-modified",
-                        modified =
-                            format_time(time, &date_time_formatter, &time_formatter).to_string()
+                        modified = recent_time(time)
                     )));
                 }
 
                 if let Ok(time) = metadata.accessed() {
                     details = details.push(widget::text::body(fl!(
                         "item-accessed",
-                        accessed =
-                            format_time(time, &date_time_formatter, &time_formatter).to_string()
+                        accessed = recent_time(time)
                     )));
                 }
 
@@ -1834,7 +4026,7 @@ impl Item {
                         .and_then(|user| user.name().to_str().map(ToOwned::to_owned))
                         .unwrap_or_default();
                     let user_path = path.clone();
-                    settings.push(
+                    permissions.push(
                         widget::settings::item::builder(user_name)
                             .description(fl!("owner"))
                             .control(widget::dropdown(
@@ -1857,7 +4049,7 @@ impl Item {
                         .and_then(|group| group.name().to_str().map(ToOwned::to_owned))
                         .unwrap_or_default();
                     let group_path = path.clone();
-                    settings.push(
+                    permissions.push(
                         widget::settings::item::builder(group_name)
                             .description(fl!("group"))
                             .control(widget::dropdown(
@@ -1877,7 +4069,7 @@ impl Item {
                     );
 
                     let other_path = path.clone();
-                    settings.push(widget::settings::item::builder(fl!("other")).control(
+                    permissions.push(widget::settings::item::builder(fl!("other")).control(
                         widget::dropdown(
                             &MODE_NAMES,
                             Some(get_mode_part(mode, MODE_SHIFT_OTHER).try_into().unwrap()),
@@ -1893,40 +4085,584 @@ impl Item {
                             },
                         ),
                     ));
+
+                    if metadata.is_dir() {
+                        permissions.push(
+                            widget::settings::item::builder(fl!("apply-to-enclosed"))
+                                .description(fl!("apply-to-enclosed-description"))
+                                .control(widget::button::standard(fl!("apply")).on_press(
+                                    Message::SetPermissionsRecursive(
+                                        path.clone(),
+                                        mode,
+                                        metadata.uid(),
+                                        metadata.gid(),
+                                    ),
+                                )),
+                        );
+                    }
+                }
+            }
+            ItemMetadata::Trash { metadata, entry } => {
+                let size = match metadata.size {
+                    trash::TrashItemSize::Entries(entries) => fl!("items", items = entries),
+                    trash::TrashItemSize::Bytes(bytes) => {
+                        fl!("item-size", size = format_size(bytes))
+                    }
+                };
+                details = details.push(widget::text::body(size));
+
+                details = details.push(widget::text::body(fl!(
+                    "item-original-location",
+                    path = entry.original_parent.display().to_string()
+                )));
+
+                let date_time_formatter = date_time_formatter(military_time);
+                let time_formatter = time_formatter(military_time);
+                if let Some(time) =
+                    FormatTime::from_secs(entry.time_deleted, &date_time_formatter, &time_formatter)
+                {
+                    details = details.push(widget::text::body(fl!(
+                        "item-deleted",
+                        deleted = time.to_string()
+                    )));
                 }
+
+                // Permissions cannot be changed while in the trash, only restoring is possible
+                restore_opt = Some(entry.clone());
             }
             _ => {
                 //TODO: other metadata types
             }
         }
-        if let ItemThumbnail::Image(_, Some((width, height))) = self
+        let dimensions_opt = match self
             .thumbnail_opt
             .as_ref()
             .unwrap_or(&ItemThumbnail::NotImage)
         {
+            ItemThumbnail::Image(_, Some(dimensions)) => Some(*dimensions),
+            // Photos that are not thumbnailed yet have their size in their metadata
+            _ => match &self.image_info {
+                ImageInfo::Loaded(metadata) => metadata.dimensions_opt,
+                _ => None,
+            },
+        };
+        if let Some((width, height)) = dimensions_opt {
             details = details.push(widget::text::body(format!("{}x{}", width, height)));
         }
-        column = column.push(details);
-
-        if let Some(path) = self.path_opt() {
-            column = column.push(
-                widget::button::standard(fl!("open"))
-                    .on_press(Message::Open(Some(path.to_path_buf()))),
-            );
-        }
-
-        if !settings.is_empty() {
-            let mut section = widget::settings::section();
-            for setting in settings {
-                section = section.add(setting);
+        if let ImageInfo::Loaded(metadata) = &self.image_info {
+            if let Some(camera) = &metadata.camera_opt {
+                details = details.push(widget::text::body(fl!(
+                    "photo-camera",
+                    camera = camera.clone()
+                )));
             }
-            column = column.push(section);
-        }
-
-        column.into()
+            if let Some(lens) = &metadata.lens_opt {
+                details = details.push(widget::text::body(fl!("photo-lens", lens = lens.clone())));
+            }
+            // Exposure settings are shown together, like cameras do
+            let mut exposure = Vec::new();
+            if let Some(exposure_time) = metadata.exposure_time_opt {
+                exposure.push(fl!(
+                    "photo-exposure-time",
+                    time = image_metadata::format_exposure_time(exposure_time)
+                ));
+            }
+            if let Some(f_number) = metadata.f_number_opt {
+                exposure.push(format!("f/{}", (f_number * 10.0).round() / 10.0));
+            }
+            if let Some(focal_length) = metadata.focal_length_opt {
+                exposure.push(fl!(
+                    "photo-focal-length",
+                    length = focal_length.round() as u32
+                ));
+            }
+            if let Some(iso) = metadata.iso_opt {
+                exposure.push(fl!("photo-iso", iso = iso));
+            }
+            if !exposure.is_empty() {
+                details = details.push(widget::text::body(fl!(
+                    "photo-exposure",
+                    exposure = exposure.join(", ")
+                )));
+            }
+            if let Some(location) = metadata.location_opt {
+                details = details.push(
+                    widget::row::with_children(vec![
+                        widget::text::body(fl!(
+                            "photo-location",
+                            latitude = format!("{:.5}", location.0),
+                            longitude = format!("{:.5}", location.1)
+                        ))
+                        .into(),
+                        widget::button::link(fl!("photo-open-map"))
+                            .on_press(Message::OpenUrl(image_metadata::map_url(location)))
+                            .padding(0)
+                            .into(),
+                    ])
+                    .align_y(Alignment::Center)
+                    .spacing(space_xxxs),
+                );
+            }
+        }
+        if let AudioInfo::Loaded(metadata, _) = &self.audio_info {
+            details = details.push(widget::text::body(fl!(
+                "audio-codec",
+                codec = metadata.codec.clone()
+            )));
+            if let Some(duration) = metadata.duration_opt {
+                details = details.push(widget::text::body(fl!(
+                    "audio-duration",
+                    duration = format_duration(duration)
+                )));
+            }
+            if let Some(bitrate) = metadata.bitrate_opt {
+                details = details.push(widget::text::body(fl!(
+                    "audio-bitrate",
+                    bitrate = bitrate / 1000
+                )));
+            }
+            if let Some(sample_rate) = metadata.sample_rate_opt {
+                details = details.push(widget::text::body(fl!(
+                    "audio-sample-rate",
+                    sample_rate = sample_rate
+                )));
+            }
+            if let Some(channels) = metadata.channels_opt {
+                details = details.push(widget::text::body(fl!(
+                    "audio-channels",
+                    channels = channels
+                )));
+            }
+        }
+        if show(DetailsTab::Properties) {
+            column = column.push(details);
+        }
+
+        match &self.archive_entries {
+            _ if !show(DetailsTab::Preview) => {}
+            ArchiveEntries::Loading => {
+                column = column.push(widget::text::body(fl!("archive-loading")));
+            }
+            ArchiveEntries::Loaded(entries) => {
+                let mut section = widget::settings::section().title(fl!("archive-contents"));
+                for entry in entries.iter().take(ARCHIVE_PREVIEW_ENTRIES) {
+                    let size = match entry.compressed_size {
+                        Some(compressed_size) => fl!(
+                            "archive-entry-size",
+                            size = format_size(entry.size),
+                            compressed = format_size(compressed_size)
+                        ),
+                        None => format_size(entry.size),
+                    };
+                    section = section.add(
+                        widget::settings::item::builder(entry.name.clone())
+                            .icon(widget::icon::from_name(if entry.dir {
+                                "folder-symbolic"
+                            } else {
+                                "text-x-generic-symbolic"
+                            }))
+                            .control(widget::text::caption(size)),
+                    );
+                }
+                if entries.len() > ARCHIVE_PREVIEW_ENTRIES {
+                    section = section.add(widget::text::caption(fl!(
+                        "archive-more-entries",
+                        items = entries.len() - ARCHIVE_PREVIEW_ENTRIES
+                    )));
+                }
+                column = column.push(section);
+            }
+            ArchiveEntries::Error(err) => {
+                column = column.push(widget::text::body(err.clone()));
+            }
+            ArchiveEntries::NotArchive => {}
+        }
+
+        if let (Some(path), ItemMetadata::Path { metadata, .. }) = (self.path_opt(), &self.metadata)
+        {
+            if metadata.is_file() && show(DetailsTab::Properties) {
+                let mut section = widget::settings::section().title(fl!("checksums"));
+                match &self.checksums {
+                    Checksums::NotCalculated => {
+                        section = section.add(
+                            widget::button::standard(fl!("calculate-checksums"))
+                                .on_press(Message::ChecksumsCalculate(path.clone())),
+                        );
+                    }
+                    Checksums::Calculating(_) => {
+                        section = section.add(
+                            widget::settings::item::builder(fl!("calculating")).control(
+                                widget::button::standard(fl!("cancel"))
+                                    .on_press(Message::ChecksumsCancel(path.clone())),
+                            ),
+                        );
+                    }
+                    Checksums::Calculated(checksums) => {
+                        for (algorithm, checksum) in checksums.iter() {
+                            section = section.add(
+                                widget::settings::item::builder(algorithm.to_string())
+                                    .description(checksum.clone())
+                                    .control(widget::tooltip(
+                                        widget::button::icon(widget::icon::from_name(
+                                            "edit-copy-symbolic",
+                                        ))
+                                        .on_press(Message::ChecksumCopy(checksum.clone())),
+                                        widget::text::body(fl!("copy-checksum")),
+                                        widget::tooltip::Position::Top,
+                                    )),
+                            );
+                        }
+                    }
+                    Checksums::Error(err) => {
+                        section = section.add(
+                            widget::settings::item::builder(err.clone()).control(
+                                widget::button::standard(fl!("calculate-checksums"))
+                                    .on_press(Message::ChecksumsCalculate(path.clone())),
+                            ),
+                        );
+                    }
+                }
+                column = column.push(section);
+            }
+        }
+
+        if show(DetailsTab::Preview) {
+            if let Some(path) = self.path_opt() {
+                let label = match self.archive_entries {
+                    ArchiveEntries::NotArchive => fl!("open"),
+                    _ => fl!("open-archive"),
+                };
+                column = column.push(
+                    widget::button::standard(label)
+                        .on_press(Message::Open(Some(path.to_path_buf()))),
+                );
+            }
+
+            if let Some(apps) = apps_opt {
+                column = column.push(apps);
+            }
+
+            if let Some(entry) = restore_opt {
+                column = column.push(
+                    widget::button::standard(fl!("restore-from-trash"))
+                        .on_press(Message::RestoreFromTrash(entry)),
+                );
+            }
+        }
+
+        if !settings.is_empty() && show(DetailsTab::Properties) {
+            let mut section = widget::settings::section();
+            for setting in settings {
+                section = section.add(setting);
+            }
+            column = column.push(section);
+        }
+
+        if show(DetailsTab::Permissions) {
+            if !permissions.is_empty() {
+                let mut section = widget::settings::section();
+                for permission in permissions {
+                    section = section.add(permission);
+                }
+                column = column.push(section);
+            } else if details_tab_opt.is_some() {
+                column = column.push(widget::text::body(fl!("no-permissions")));
+            }
+        }
+
+        // Versions are only shown on their own tab, so that the pane is unchanged without tabs
+        if details_tab_opt == Some(DetailsTab::Versions) {
+            match &self.versions_opt {
+                None => {
+                    column = column.push(widget::text::body(fl!("versions-loading")));
+                }
+                Some(versions) if versions.is_empty() => {
+                    column = column.push(widget::text::body(fl!("no-versions")));
+                }
+                Some(versions) => {
+                    let date_time_formatter = date_time_formatter(military_time);
+                    let time_formatter = time_formatter(military_time);
+                    let mut section = widget::settings::section().title(fl!("versions"));
+                    for version in versions {
+                        let mut description = format_size(version.size);
+                        if let Some(time) = version.modified_opt {
+                            description = fl!(
+                                "version-description",
+                                modified = format_time(time, &date_time_formatter, &time_formatter)
+                                    .to_string(),
+                                size = description
+                            );
+                        }
+                        section = section.add(
+                            widget::settings::item::builder(fl!(
+                                "version-snapshot",
+                                snapshot = version.snapshot.as_str()
+                            ))
+                            .description(description)
+                            .control(
+                                widget::button::standard(fl!("open"))
+                                    .on_press(Message::Open(Some(version.path.clone()))),
+                            ),
+                        );
+                    }
+                    column = column.push(section);
+                }
+            }
+        }
+
+        if details_tab_opt == Some(DetailsTab::Advanced) {
+            column = column.push(self.attributes_view(attributes_edit_opt));
+        }
+
+        column.into()
     }
 
-    pub fn replace_view(&self, heading: String, military_time: bool) -> Element<'_, Message> {
+    /// ACL entries and extended attributes, with controls to change them if `edit_opt` is set
+    fn attributes_view<'a>(&'a self, edit_opt: Option<&AttributesEdit>) -> Element<'a, Message> {
+        let cosmic_theme::Spacing {
+            space_xxs, space_m, ..
+        } = theme::active().cosmic().spacing;
+
+        let attributes = match &self.attributes_opt {
+            None => return widget::text::body(fl!("attributes-loading")).into(),
+            Some(Err(err)) => {
+                return widget::text::body(fl!("attributes-error", error = err.as_str())).into()
+            }
+            Some(Ok(attributes)) => attributes,
+        };
+        let Some(path) = self
+            .path_opt()
+            .filter(|_| !attributes.acl.is_empty() || !attributes.xattrs.is_empty())
+        else {
+            return widget::text::body(fl!("no-attributes")).into();
+        };
+        // Text typed for another item is not shown
+        let edit_opt = edit_opt.map(|edit| {
+            if edit.path_opt.as_ref() == Some(path) {
+                edit.clone()
+            } else {
+                AttributesEdit {
+                    path_opt: Some(path.clone()),
+                    ..Default::default()
+                }
+            }
+        });
+
+        let mut column = widget::column().spacing(space_m);
+        if let Some(error) = edit_opt.as_ref().and_then(|edit| edit.error_opt.as_ref()) {
+            column = column.push(widget::text::body(fl!(
+                "attributes-change-error",
+                error = error.as_str()
+            )));
+        }
+
+        for default in [false, true] {
+            let mut entries = attributes
+                .acl
+                .iter()
+                .filter(|entry| entry.default == default)
+                .peekable();
+            if entries.peek().is_none() {
+                continue;
+            }
+            let mut section = widget::settings::section().title(if default {
+                fl!("default-acl")
+            } else {
+                fl!("acl")
+            });
+            for entry in entries {
+                let entry = *entry;
+                let (name, description_opt) = match entry.tag {
+                    AclTag::Owner => (fl!("owner"), None),
+                    AclTag::User(uid) => (user_name(uid), Some(fl!("acl-user"))),
+                    AclTag::OwningGroup => (fl!("group"), None),
+                    AclTag::Group(gid) => (group_name(gid), Some(fl!("acl-group"))),
+                    AclTag::Mask => (fl!("acl-mask"), Some(fl!("acl-mask-description"))),
+                    AclTag::Other => (fl!("other"), None),
+                };
+                let mut item = widget::settings::item::builder(name);
+                if let Some(description) = description_opt {
+                    item = item.description(description);
+                }
+                let perm = entry.perm as usize;
+                section = section.add(if edit_opt.is_some() {
+                    let mut row = widget::row::with_capacity(2)
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs);
+                    let set_path = path.clone();
+                    row = row.push(widget::dropdown(&MODE_NAMES, Some(perm), move |selected| {
+                        Message::AclSet(
+                            set_path.clone(),
+                            AclEntry {
+                                perm: selected as u32,
+                                ..entry
+                            },
+                        )
+                    }));
+                    if entry.tag.is_named() {
+                        row = row.push(
+                            widget::button::icon(widget::icon::from_name("list-remove-symbolic"))
+                                .on_press(Message::AclRemove(path.clone(), entry.tag, default)),
+                        );
+                    }
+                    item.control(row)
+                } else {
+                    item.control(widget::text::body(MODE_NAMES[perm].as_str()))
+                });
+            }
+            column = column.push(section);
+        }
+
+        if let Some(edit) = &edit_opt {
+            let mut row = widget::row::with_capacity(4)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs);
+            row = row.push(widget::dropdown(
+                &ACL_KIND_NAMES,
+                Some(edit.acl_group.into()),
+                {
+                    let edit = edit.clone();
+                    move |selected| {
+                        Message::AttributesEdit(AttributesEdit {
+                            acl_group: selected == 1,
+                            ..edit.clone()
+                        })
+                    }
+                },
+            ));
+            row = row.push(
+                widget::text_input(fl!("acl-name-placeholder"), edit.acl_name.as_str())
+                    .on_input({
+                        let edit = edit.clone();
+                        move |acl_name| {
+                            Message::AttributesEdit(AttributesEdit {
+                                acl_name,
+                                ..edit.clone()
+                            })
+                        }
+                    })
+                    .on_submit({
+                        let path = path.clone();
+                        move |_| Message::AclAdd(path.clone())
+                    }),
+            );
+            row = row.push(widget::button::standard(fl!("add")).on_press_maybe(
+                (!edit.acl_name.trim().is_empty()).then(|| Message::AclAdd(path.clone())),
+            ));
+            let mut add = widget::column::with_capacity(2)
+                .spacing(space_xxs)
+                .push(row);
+            if self.metadata.is_dir() {
+                let edit = edit.clone();
+                add = add.push(
+                    widget::checkbox(fl!("acl-default"), edit.acl_default).on_toggle(
+                        move |acl_default| {
+                            Message::AttributesEdit(AttributesEdit {
+                                acl_default,
+                                ..edit.clone()
+                            })
+                        },
+                    ),
+                );
+            }
+            column = column.push(add);
+        }
+
+        if !attributes.xattrs.is_empty() || edit_opt.is_some() {
+            let mut section = widget::settings::section().title(fl!("xattrs"));
+            for xattr in attributes.xattrs.iter() {
+                let item = widget::settings::item::builder(xattr.name.as_str());
+                section = section.add(match &edit_opt {
+                    Some(edit) if xattr.editable() => {
+                        let value = edit
+                            .xattr_values
+                            .get(&xattr.name)
+                            .cloned()
+                            .unwrap_or_else(|| xattr.value_text());
+                        let name = xattr.name.clone();
+                        let edit = edit.clone();
+                        item.control(
+                            widget::row::with_capacity(2)
+                                .align_y(Alignment::Center)
+                                .spacing(space_xxs)
+                                .push(
+                                    widget::text_input("", value)
+                                        .on_input({
+                                            let name = name.clone();
+                                            move |value| {
+                                                let mut edit = edit.clone();
+                                                edit.xattr_values.insert(name.clone(), value);
+                                                Message::AttributesEdit(edit)
+                                            }
+                                        })
+                                        .on_submit({
+                                            let path = path.clone();
+                                            let name = name.clone();
+                                            move |_| Message::XattrSet(path.clone(), name.clone())
+                                        }),
+                                )
+                                .push(
+                                    widget::button::icon(widget::icon::from_name(
+                                        "list-remove-symbolic",
+                                    ))
+                                    .on_press(Message::XattrRemove(path.clone(), name)),
+                                ),
+                        )
+                    }
+                    _ => item.control(widget::text::body(xattr.value_text())),
+                });
+            }
+            column = column.push(section);
+        }
+
+        if let Some(edit) = &edit_opt {
+            let mut row = widget::row::with_capacity(3)
+                .align_y(Alignment::Center)
+                .spacing(space_xxs);
+            row = row.push(
+                widget::text_input(fl!("xattr-name-placeholder"), edit.xattr_name.as_str())
+                    .on_input({
+                        let edit = edit.clone();
+                        move |xattr_name| {
+                            Message::AttributesEdit(AttributesEdit {
+                                xattr_name,
+                                ..edit.clone()
+                            })
+                        }
+                    }),
+            );
+            row = row.push(
+                widget::text_input(fl!("xattr-value-placeholder"), edit.xattr_value.as_str())
+                    .on_input({
+                        let edit = edit.clone();
+                        move |xattr_value| {
+                            Message::AttributesEdit(AttributesEdit {
+                                xattr_value,
+                                ..edit.clone()
+                            })
+                        }
+                    })
+                    .on_submit({
+                        let path = path.clone();
+                        move |_| Message::XattrAdd(path.clone())
+                    }),
+            );
+            row = row.push(widget::button::standard(fl!("add")).on_press_maybe(
+                (!edit.xattr_name.trim().is_empty()).then(|| Message::XattrAdd(path.clone())),
+            ));
+            column = column.push(row);
+        }
+
+        column.into()
+    }
+
+    /// Size and modification time, marking which ones differ from the `other` item
+    pub fn replace_view(
+        &self,
+        heading: String,
+        other: &Self,
+        military_time: bool,
+    ) -> Element<'_, Message> {
         let cosmic_theme::Spacing { space_xxxs, .. } = theme::active().cosmic().spacing;
 
         let mut row = widget::row().spacing(space_xxxs);
@@ -1935,7 +4671,26 @@ impl Item {
         let mut column = widget::column().spacing(space_xxxs);
         column = column.push(widget::text::heading(heading));
 
-        //TODO: translate!
+        let other_metadata_opt = match &other.metadata {
+            ItemMetadata::Path { metadata, .. } => Some(metadata),
+            _ => None,
+        };
+        let compared = |text: String, comparison_opt: Option<String>| {
+            let mut row = widget::row::with_capacity(2)
+                .spacing(space_xxxs)
+                .align_y(Alignment::Center)
+                .push(widget::text::body(text));
+            if let Some(comparison) = comparison_opt {
+                row = row.push(widget::text::caption(comparison));
+            }
+            row
+        };
+        // Copies often differ by a fraction of a second, which is not shown
+        let seconds = |time: SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs())
+        };
+
         //TODO: correct display of folder size?
         match &self.metadata {
             ItemMetadata::Path {
@@ -1944,22 +4699,40 @@ impl Item {
             } => {
                 if metadata.is_dir() {
                     if let Some(children) = children_opt {
-                        column = column.push(widget::text::body(format!("Items: {}", children)));
+                        column = column.push(widget::text::body(fl!("items", items = children)));
                     }
                 } else {
-                    column = column.push(widget::text::body(format!(
-                        "Size: {}",
-                        format_size(metadata.len())
-                    )));
+                    let comparison_opt = other_metadata_opt
+                        .filter(|other| !other.is_dir())
+                        .and_then(|other| match metadata.len().cmp(&other.len()) {
+                            Ordering::Greater => Some(fl!("replace-larger")),
+                            Ordering::Less => Some(fl!("replace-smaller")),
+                            Ordering::Equal => None,
+                        });
+                    column = column.push(compared(
+                        fl!("item-size", size = format_size(metadata.len())),
+                        comparison_opt,
+                    ));
                 }
                 if let Ok(time) = metadata.modified() {
                     let date_time_formatter = date_time_formatter(military_time);
                     let time_formatter = time_formatter(military_time);
 
-                    column = column.push(widget::text::body(format!(
-                        "Last modified: {}",
-                        format_time(time, &date_time_formatter, &time_formatter)
-                    )));
+                    let comparison_opt = other_metadata_opt
+                        .and_then(|other| other.modified().ok())
+                        .and_then(|other| match seconds(time).cmp(&seconds(other)) {
+                            Ordering::Greater => Some(fl!("replace-newer")),
+                            Ordering::Less => Some(fl!("replace-older")),
+                            Ordering::Equal => None,
+                        });
+                    column = column.push(compared(
+                        fl!(
+                            "item-modified",
+                            modified = format_time(time, &date_time_formatter, &time_formatter)
+                                .to_string()
+                        ),
+                        comparison_opt,
+                    ));
                 }
             }
             _ => {
@@ -1972,17 +4745,208 @@ impl Item {
     }
 }
 
+const TRASH_DATE_FILTERS: [TrashDate; 4] = [
+    TrashDate::All,
+    TrashDate::Today,
+    TrashDate::Yesterday,
+    TrashDate::ThisWeek,
+];
+
+const TRASH_GROUPS: [TrashGroup; 3] = [TrashGroup::None, TrashGroup::Date, TrashGroup::Folder];
+
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum TrashDate {
+    #[default]
+    All,
+    Today,
+    Yesterday,
+    ThisWeek,
+    Older,
+}
+
+impl TrashDate {
+    /// Classify a deletion time, in seconds since the epoch
+    pub fn from_secs(secs: i64) -> Self {
+        let Some(time) = DateTime::<Utc>::from_timestamp(secs, 0) else {
+            return Self::Older;
+        };
+        let date = time.with_timezone(&chrono::Local).date_naive();
+        let today = chrono::Local::now().date_naive();
+        let week_start = today - chrono::Days::new(today.weekday().num_days_from_monday().into());
+        if date >= today {
+            Self::Today
+        } else if Some(date) == today.pred_opt() {
+            Self::Yesterday
+        } else if date >= week_start {
+            Self::ThisWeek
+        } else {
+            Self::Older
+        }
+    }
+
+    /// Whether a classified date passes this date used as a filter
+    pub fn contains(self, date: Self) -> bool {
+        match self {
+            Self::All => true,
+            // This week includes today and yesterday
+            Self::ThisWeek => date != Self::Older,
+            _ => self == date,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::All => fl!("all-dates"),
+            Self::Today => fl!("today"),
+            Self::Yesterday => fl!("yesterday"),
+            Self::ThisWeek => fl!("this-week"),
+            Self::Older => fl!("older"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TrashGroup {
+    #[default]
+    None,
+    Date,
+    Folder,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TrashFilter {
+    pub date: TrashDate,
+    pub folder_opt: Option<PathBuf>,
+    pub group: TrashGroup,
+    pub search_opt: Option<String>,
+}
+
+impl TrashFilter {
+    pub fn is_active(&self) -> bool {
+        self.date != TrashDate::All
+            || self.folder_opt.is_some()
+            || self
+                .search_opt
+                .as_ref()
+                .is_some_and(|term| !term.is_empty())
+    }
+
+    pub fn matches(&self, item: &Item) -> bool {
+        let ItemMetadata::Trash { entry, .. } = &item.metadata else {
+            return true;
+        };
+        if !self.date.contains(TrashDate::from_secs(entry.time_deleted)) {
+            return false;
+        }
+        if let Some(folder) = &self.folder_opt {
+            if &entry.original_parent != folder {
+                return false;
+            }
+        }
+        if let Some(term) = &self.search_opt {
+            if !item.name.to_lowercase().contains(&term.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Heading of the group this item is shown under, if grouping is enabled
+    pub fn group_name(&self, item: &Item) -> Option<String> {
+        let ItemMetadata::Trash { entry, .. } = &item.metadata else {
+            return None;
+        };
+        match self.group {
+            TrashGroup::None => None,
+            TrashGroup::Date => Some(match TrashDate::from_secs(entry.time_deleted) {
+                TrashDate::ThisWeek => fl!("earlier-this-week"),
+                date => date.name(),
+            }),
+            TrashGroup::Folder => Some(entry.original_parent.display().to_string()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum View {
     Grid,
     List,
 }
+/// List view column that can be resized
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Column {
+    Modified,
+    Size,
+}
+
+/// Column resize in progress, started by pressing on the divider left of the column
+#[derive(Clone, Copy, Debug)]
+struct ColumnResize {
+    column: Column,
+    start_x: f32,
+    start_width: u16,
+}
+
+fn size_text(item: &Item) -> String {
+    match &item.metadata {
+        ItemMetadata::Path {
+            metadata,
+            children_opt,
+        } => {
+            if metadata.is_dir() {
+                //TODO: translate
+                if let Some(children) = children_opt {
+                    if *children == 1 {
+                        format!("{} item", children)
+                    } else {
+                        format!("{} items", children)
+                    }
+                } else {
+                    String::new()
+                }
+            } else {
+                format_size(metadata.len())
+            }
+        }
+        ItemMetadata::Trash { metadata, .. } => match metadata.size {
+            trash::TrashItemSize::Entries(entries) => {
+                //TODO: translate
+                if entries == 1 {
+                    format!("{} item", entries)
+                } else {
+                    format!("{} items", entries)
+                }
+            }
+            trash::TrashItemSize::Bytes(bytes) => format_size(bytes),
+        },
+        ItemMetadata::SimpleDir { entries } => {
+            //TODO: translate
+            if *entries == 1 {
+                format!("{} item", entries)
+            } else {
+                format!("{} items", entries)
+            }
+        }
+        ItemMetadata::SimpleFile { size } => format_size(*size),
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd, Ord, Eq, Deserialize, Serialize)]
 pub enum HeadingOptions {
     Name = 0,
     Modified,
     Size,
     TrashedOn,
+    /// Kind of item, sorted by MIME type and then by name
+    Kind,
+    Permissions,
+    Owner,
+    Group,
+    Created,
+    Accessed,
+    MimeType,
+    /// Folder a trashed item was deleted from
+    OriginalLocation,
 }
 
 impl fmt::Display for HeadingOptions {
@@ -1992,11 +4956,35 @@ impl fmt::Display for HeadingOptions {
             HeadingOptions::Modified => write!(f, "{}", fl!("modified")),
             HeadingOptions::Size => write!(f, "{}", fl!("size")),
             HeadingOptions::TrashedOn => write!(f, "{}", fl!("trashed-on")),
+            HeadingOptions::Kind => write!(f, "{}", fl!("column-type")),
+            HeadingOptions::Permissions => write!(f, "{}", fl!("column-permissions")),
+            HeadingOptions::Owner => write!(f, "{}", fl!("owner")),
+            HeadingOptions::Group => write!(f, "{}", fl!("group")),
+            HeadingOptions::Created => write!(f, "{}", fl!("column-created")),
+            HeadingOptions::Accessed => write!(f, "{}", fl!("column-accessed")),
+            HeadingOptions::MimeType => write!(f, "{}", fl!("column-mime-type")),
+            HeadingOptions::OriginalLocation => write!(f, "{}", fl!("original-location")),
         }
     }
 }
 
 impl HeadingOptions {
+    /// Columns that can be shown next to the name in list view, in display order
+    pub const COLUMNS: [Self; 9] = [
+        Self::Kind,
+        Self::MimeType,
+        Self::Permissions,
+        Self::Owner,
+        Self::Group,
+        Self::Created,
+        Self::Accessed,
+        Self::Modified,
+        Self::Size,
+    ];
+
+    /// Columns shown in folders where they were not chosen
+    pub const DEFAULT_COLUMNS: [Self; 2] = [Self::Modified, Self::Size];
+
     pub fn names() -> Vec<String> {
         vec![
             HeadingOptions::Name.to_string(),
@@ -2005,6 +4993,156 @@ impl HeadingOptions {
             HeadingOptions::TrashedOn.to_string(),
         ]
     }
+
+    /// Column that is resized by dragging the divider left of this heading
+    fn resize_column(self) -> Option<Column> {
+        match self {
+            Self::Modified | Self::TrashedOn => Some(Column::Modified),
+            Self::Size => Some(Column::Size),
+            _ => None,
+        }
+    }
+}
+
+/// Width of list view columns that cannot be resized
+fn fixed_column_width(heading: HeadingOptions) -> u16 {
+    match heading {
+        HeadingOptions::MimeType => 200,
+        HeadingOptions::OriginalLocation => 240,
+        HeadingOptions::Created | HeadingOptions::Accessed => 200,
+        _ => 120,
+    }
+}
+
+/// Metadata of items that are files or folders on disk
+fn path_metadata(item: &Item) -> Option<&Metadata> {
+    match &item.metadata {
+        ItemMetadata::Path { metadata, .. } => Some(metadata),
+        _ => None,
+    }
+}
+
+/// Translated kind of an item, like folder or image
+fn kind_text(item: &Item) -> String {
+    let kind = if item.metadata.is_dir() {
+        "folder"
+    } else {
+        item.mime.type_().as_str()
+    };
+    fl!("column-kind", kind = kind)
+}
+
+/// Permissions like `drwxr-xr-x`
+pub fn permissions_text(mode: u32, is_dir: bool) -> String {
+    let mut text = String::with_capacity(10);
+    text.push(if is_dir { 'd' } else { '-' });
+    for shift in [MODE_SHIFT_USER, MODE_SHIFT_GROUP, MODE_SHIFT_OTHER] {
+        let part = (mode >> shift) & 0o7;
+        text.push(if part & 0o4 != 0 { 'r' } else { '-' });
+        text.push(if part & 0o2 != 0 { 'w' } else { '-' });
+        text.push(if part & 0o1 != 0 { 'x' } else { '-' });
+    }
+    text
+}
+
+/// User names by id, cached because list view looks them up for every row
+fn user_name(uid: u32) -> String {
+    static USER_NAMES: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(Default::default);
+    let mut names = USER_NAMES.lock().unwrap();
+    names
+        .entry(uid)
+        .or_insert_with(|| {
+            get_user_by_uid(uid)
+                .and_then(|user| user.name().to_str().map(ToOwned::to_owned))
+                .unwrap_or_else(|| uid.to_string())
+        })
+        .clone()
+}
+
+/// Order of two items by one of the columns that can be chosen in list view
+fn column_ordering(heading: HeadingOptions, a: &Item, b: &Item) -> Ordering {
+    let metadata_key =
+        |item: &Item, key: fn(&Metadata) -> Option<SystemTime>| path_metadata(item).and_then(key);
+    match heading {
+        HeadingOptions::Kind => a
+            .mime
+            .essence_str()
+            .cmp(b.mime.essence_str())
+            .then_with(|| LANGUAGE_SORTER.compare(&a.display_name, &b.display_name)),
+        HeadingOptions::MimeType => a.mime.as_ref().cmp(b.mime.as_ref()),
+        HeadingOptions::Permissions => {
+            let mode = |item: &Item| path_metadata(item).map(|metadata| metadata.mode() & 0o7777);
+            mode(a).cmp(&mode(b))
+        }
+        HeadingOptions::Owner => {
+            let owner = |item: &Item| path_metadata(item).map(|metadata| user_name(metadata.uid()));
+            owner(a).cmp(&owner(b))
+        }
+        HeadingOptions::Group => {
+            let group =
+                |item: &Item| path_metadata(item).map(|metadata| group_name(metadata.gid()));
+            group(a).cmp(&group(b))
+        }
+        HeadingOptions::Created => {
+            let created = |metadata: &Metadata| metadata.created().ok();
+            metadata_key(a, created).cmp(&metadata_key(b, created))
+        }
+        HeadingOptions::Accessed => {
+            let accessed = |metadata: &Metadata| metadata.accessed().ok();
+            metadata_key(a, accessed).cmp(&metadata_key(b, accessed))
+        }
+        HeadingOptions::OriginalLocation => {
+            let original_parent = |item: &Item| match &item.metadata {
+                ItemMetadata::Trash { entry, .. } => Some(&entry.original_parent),
+                _ => None,
+            };
+            original_parent(a).cmp(&original_parent(b))
+        }
+        _ => Ordering::Equal,
+    }
+}
+
+/// Group names by id, cached like [`user_name`]
+fn group_name(gid: u32) -> String {
+    static GROUP_NAMES: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(Default::default);
+    let mut names = GROUP_NAMES.lock().unwrap();
+    names
+        .entry(gid)
+        .or_insert_with(|| {
+            get_group_by_gid(gid)
+                .and_then(|group| group.name().to_str().map(ToOwned::to_owned))
+                .unwrap_or_else(|| gid.to_string())
+        })
+        .clone()
+}
+
+/// Change the attributes of a file in the background and read them again
+fn change_attributes<F>(path: PathBuf, change: F) -> Command
+where
+    F: FnOnce(&Path) -> std::io::Result<()> + Send + 'static,
+{
+    Command::Iced(
+        cosmic::Task::perform(
+            async move {
+                let result = {
+                    let path = path.clone();
+                    tokio::task::spawn_blocking(move || {
+                        change(&path)?;
+                        attributes::read(&path)
+                    })
+                    .await
+                };
+                let result = match result {
+                    Ok(Ok(attributes)) => Ok(attributes),
+                    Ok(Err(err)) => Err(err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                Message::AttributesChanged(path, result)
+            },
+            |x| x,
+        )
+        .into(),
+    )
 }
 
 #[derive(Clone, Debug)]
@@ -2027,6 +5165,7 @@ impl Mode {
 struct SearchContext {
     results_rx: mpsc::Receiver<(PathBuf, String, Metadata)>,
     ready: Arc<atomic::AtomicBool>,
+    stop: Arc<atomic::AtomicBool>,
     last_modified_opt: Arc<RwLock<Option<SystemTime>>>,
 }
 
@@ -2044,6 +5183,24 @@ impl fmt::Debug for SearchContextWrapper {
     }
 }
 
+/// A visited location, with the view state to restore when returning to it
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    pub location: Location,
+    pub scroll_opt: Option<AbsoluteOffset>,
+    pub selected_paths: Vec<PathBuf>,
+}
+
+impl HistoryEntry {
+    pub fn new(location: Location) -> Self {
+        Self {
+            location,
+            scroll_opt: None,
+            selected_paths: Vec::new(),
+        }
+    }
+}
+
 // TODO when creating items, pass <Arc<SelectedItems>> to each item
 // as a drag data, so that when dnd is initiated, they are all included
 pub struct Tab {
@@ -2053,6 +5210,10 @@ pub struct Tab {
     pub location_title: String,
     pub location_context_menu_point: Option<Point>,
     pub location_context_menu_index: Option<usize>,
+    /// Mount point right clicked in the breadcrumbs, set from the mounter items of the app
+    pub location_context_menu_mount: Option<MounterItem>,
+    /// Column chooser opened from the list view headings
+    column_menu: bool,
     pub context_menu: Option<Point>,
     pub mode: Mode,
     pub scroll_opt: Option<AbsoluteOffset>,
@@ -2061,15 +5222,51 @@ pub struct Tab {
     pub edit_location: Option<EditLocation>,
     pub edit_location_id: widget::Id,
     pub history_i: usize,
-    pub history: Vec<Location>,
+    pub history: Vec<HistoryEntry>,
     pub config: TabConfig,
     pub sort_name: HeadingOptions,
     pub sort_direction: bool,
+    pub sort_secondary_opt: Option<(HeadingOptions, bool)>,
     pub gallery: bool,
     pub(crate) parent_item_opt: Option<Item>,
     pub(crate) items_opt: Option<Vec<Item>>,
+    /// Items are a cached listing because the share could not be reached
+    pub(crate) offline: bool,
+    /// Path to scroll to and highlight once it has been scanned
+    pub(crate) reveal_path_opt: Option<PathBuf>,
     pub dnd_hovered: Option<(Location, Instant)>,
+    pub trash_filter: TrashFilter,
+    trash_folders: Vec<PathBuf>,
+    trash_folder_names: Vec<String>,
+    /// Sizes of the trashed items by ID, including the files inside trashed folders
+    trash_sizes_opt: Option<HashMap<OsString, u64>>,
+    animation_opt: Option<Animation>,
+    media_preview_opt: Option<MediaPreview>,
+    #[cfg(feature = "video-thumbnails")]
+    gallery_video_opt: Option<GalleryVideo>,
+    slideshow_opt: Option<Slideshow>,
+    /// When the zoom level was last changed, while it is shown
+    zoom_indicator_opt: Option<Instant>,
+    /// Playback of the selected audio file in the details pane
+    audio_player_opt: Option<AudioPlayer>,
+    /// Extra file name patterns that are treated like dot-files
+    hidden_patterns: Vec<glob::Pattern>,
+    /// List view columns chosen for each folder
+    folder_columns: BTreeMap<PathBuf, Vec<HeadingOptions>>,
+    /// List view column widths chosen for each folder
+    folder_column_widths: BTreeMap<PathBuf, ColumnWidths>,
+    /// Paths shown with a star, see [`Config::starred`](crate::config::Config)
+    starred: BTreeSet<PathBuf>,
+    /// Calculated folder sizes, kept until something inside the folder changes
+    dir_sizes: HashMap<PathBuf, u64>,
+    /// Listings of visible folders, see [`prefetch`]
+    prefetch: Prefetch<(Option<Item>, Vec<Item>)>,
+    pub capabilities: LocationCapabilities,
     scrollable_id: widget::Id,
+    scroll_restore_opt: Option<AbsoluteOffset>,
+    /// Item at the top of the view before the items were refreshed, and its distance from the
+    /// top, which is kept by [`Message::ScrollAnchor`]
+    scroll_anchor_opt: Option<(Location, f32)>,
     select_focus: Option<usize>,
     select_range: Option<(usize, usize)>,
     clicked: Option<usize>,
@@ -2077,7 +5274,11 @@ pub struct Tab {
     modifiers: Modifiers,
     last_right_click: Option<usize>,
     search_context: Option<SearchContext>,
+    /// Time the search took, and whether it was stopped before searching everything
+    search_finished_opt: Option<(Duration, bool)>,
+    pub attributes_edit: AttributesEdit,
     global_cursor_position: Option<Point>,
+    column_resize_opt: Option<ColumnResize>,
     current_drag_rect: Option<Rectangle>,
     virtual_cursor_offset: Option<Point>,
     last_scroll_position: Option<Point>,
@@ -2087,136 +5288,693 @@ pub struct Tab {
     time_formatter: DateTimeFormatter,
 }
 
-async fn calculate_dir_size(path: &Path, controller: Controller) -> Result<u64, String> {
+/// Entries counted between pauses when reducing background work
+const DIR_SIZE_THROTTLE_ENTRIES: usize = 64;
+
+/// Time between updates of the size counted so far
+const DIR_SIZE_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+async fn calculate_dir_size(
+    path: &Path,
+    controller: Controller,
+    reduce_background_work: bool,
+    output: &mut futures::channel::mpsc::Sender<Message>,
+) -> Result<u64, String> {
     let mut total = 0;
-    for entry_res in WalkDir::new(path) {
+    let mut last_progress = Instant::now();
+    for (i, entry_res) in WalkDir::new(path).into_iter().enumerate() {
         controller.check().await?;
 
+        if reduce_background_work && i % DIR_SIZE_THROTTLE_ENTRIES == DIR_SIZE_THROTTLE_ENTRIES - 1
+        {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
         //TODO: report more errors?
         if let Ok(entry) = entry_res {
             if let Ok(metadata) = entry.metadata() {
                 if metadata.is_file() {
                     total += metadata.len();
+                    controller.add_bytes(metadata.len());
+                }
+            }
+        }
+
+        if last_progress.elapsed() >= DIR_SIZE_PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let _ = output
+                .send(Message::DirectorySizeProgress(path.to_path_buf()))
+                .await;
+        }
+
+        // Yield in case this process takes a while.
+        tokio::task::yield_now().await;
+    }
+    Ok(total)
+}
+
+fn folder_name<P: AsRef<Path>>(path: P) -> (String, bool) {
+    let path = path.as_ref();
+    let mut found_home = false;
+    let name = match path.file_name() {
+        Some(name) => {
+            if path == crate::home_dir() {
+                found_home = true;
+                fl!("home")
+            } else {
+                // This is not optimized but it helps ensure the same display names
+                match item_from_path(path, IconSizes::default()) {
+                    Ok(item) => item.display_name,
+                    Err(_err) => name.to_string_lossy().to_string(),
+                }
+            }
+        }
+        None => {
+            fl!("filesystem")
+        }
+    };
+    (name, found_home)
+}
+
+fn path_title(path: &Path, tab_title: TabTitle) -> String {
+    match tab_title {
+        TabTitle::FolderName => folder_name(path).0,
+        TabTitle::Shortened => shorten_path(path),
+        TabTitle::FullPath => path.display().to_string(),
+    }
+}
+
+/// Shorten a path to its last two folders, such as `…/Projects/cosmic-files`, using `~` for the
+/// home folder
+fn shorten_path(path: &Path) -> String {
+    let home = crate::home_dir();
+    let (prefix, rest) = match path.strip_prefix(&home) {
+        Ok(rest) => ("~", rest),
+        Err(_) => ("", path),
+    };
+    let names: Vec<_> = rest
+        .components()
+        .filter_map(|component| match component {
+            std::path::Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect();
+    match names.as_slice() {
+        // Home and the root folder use their display names
+        [] => folder_name(path).0,
+        [_, .., parent, name] => format!("…/{}/{}", parent, name),
+        _ => format!("{}/{}", prefix, names.join("/")),
+    }
+}
+
+// parse .hidden file and return files path
+fn parse_hidden_file(path: &PathBuf) -> Vec<String> {
+    let file = match File::open(path) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .flat_map(|line| {
+            let line = line.trim();
+            (!line.is_empty()).then_some(line.to_owned())
+        })
+        .collect()
+}
+
+impl Tab {
+    pub fn new(location: Location, config: TabConfig) -> Self {
+        let location = location.normalize();
+        let location_ancestors = location.ancestors();
+        let location_title = location.tab_title(config.title);
+        let history = vec![HistoryEntry::new(location.clone())];
+        Self {
+            location,
+            location_ancestors,
+            location_title,
+            context_menu: None,
+            location_context_menu_point: None,
+            location_context_menu_index: None,
+            location_context_menu_mount: None,
+            column_menu: false,
+            mode: Mode::App,
+            scroll_opt: None,
+            size_opt: Cell::new(None),
+            item_view_size_opt: Cell::new(None),
+            edit_location: None,
+            edit_location_id: widget::Id::unique(),
+            history_i: 0,
+            history,
+            config,
+            sort_name: HeadingOptions::Name,
+            sort_direction: true,
+            sort_secondary_opt: None,
+            gallery: false,
+            parent_item_opt: None,
+            items_opt: None,
+            offline: false,
+            reveal_path_opt: None,
+            trash_filter: TrashFilter::default(),
+            trash_folders: Vec::new(),
+            trash_folder_names: Vec::new(),
+            trash_sizes_opt: None,
+            animation_opt: None,
+            media_preview_opt: None,
+            #[cfg(feature = "video-thumbnails")]
+            gallery_video_opt: None,
+            slideshow_opt: None,
+            zoom_indicator_opt: None,
+            audio_player_opt: None,
+            hidden_patterns: Vec::new(),
+            folder_columns: BTreeMap::new(),
+            folder_column_widths: BTreeMap::new(),
+            starred: BTreeSet::new(),
+            dir_sizes: HashMap::new(),
+            prefetch: Prefetch::default(),
+            capabilities: LocationCapabilities::default(),
+            scrollable_id: widget::Id::unique(),
+            scroll_restore_opt: None,
+            scroll_anchor_opt: None,
+            select_focus: None,
+            select_range: None,
+            clicked: None,
+            dnd_hovered: None,
+            selected_clicked: false,
+            modifiers: Modifiers::default(),
+            last_right_click: None,
+            search_context: None,
+            search_finished_opt: None,
+            attributes_edit: AttributesEdit::default(),
+            global_cursor_position: None,
+            column_resize_opt: None,
+            current_drag_rect: None,
+            virtual_cursor_offset: None,
+            last_scroll_position: None,
+            last_scroll_offset: None,
+            scroll_bounds_opt: None,
+            date_time_formatter: date_time_formatter(config.military_time),
+            time_formatter: time_formatter(config.military_time),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        //TODO: is it possible to return a &str?
+        self.location_title.clone()
+    }
+
+    pub fn items_opt(&self) -> Option<&Vec<Item>> {
+        self.items_opt.as_ref()
+    }
+
+    pub fn items_opt_mut(&mut self) -> Option<&mut Vec<Item>> {
+        self.items_opt.as_mut()
+    }
+
+    /// Path and mime type of the first selected item, if it can play in the details pane
+    fn media_item(&self) -> Option<(PathBuf, Mime)> {
+        let item = self.items_opt.as_ref()?.iter().find(|item| item.selected)?;
+        if !is_animated_mime(&item.mime) {
+            return None;
+        }
+        Some((item.path_opt()?.clone(), item.mime.clone()))
+    }
+
+    /// Player of the first selected item if it is an audio file, created when first used
+    fn audio_player(&mut self) -> Option<&mut AudioPlayer> {
+        let item = self.items_opt.as_ref()?.iter().find(|item| item.selected)?;
+        if let AudioInfo::NotAudio | AudioInfo::Unavailable = item.audio_info {
+            return None;
+        }
+        let path = item.path_opt()?;
+        if self
+            .audio_player_opt
+            .as_ref()
+            .map_or(true, |player| player.path() != path)
+        {
+            let duration_opt = match &item.audio_info {
+                AudioInfo::Loaded(metadata, _) => metadata.duration_opt,
+                _ => None,
+            };
+            self.audio_player_opt = Some(AudioPlayer::new(path.clone(), duration_opt));
+        }
+        self.audio_player_opt.as_mut()
+    }
+
+    fn audio_view<'a>(&'a self, item: &'a Item, path: &Path) -> Element<'a, Message> {
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let cover: Element<_> = match &item.audio_info {
+            AudioInfo::Loaded(_, Some(handle)) => widget::image(handle.clone())
+                .content_fit(ContentFit::Contain)
+                .into(),
+            _ => item.preview(),
+        };
+        let cover = widget::container(cover)
+            .center_x(Length::Fill)
+            .max_height(THUMBNAIL_SIZE as f32);
+
+        if let AudioInfo::Unavailable = &item.audio_info {
+            return widget::column::with_children(vec![
+                cover.into(),
+                widget::text::caption(fl!("audio-player-unavailable")).into(),
+            ])
+            .spacing(space_xxs)
+            .into();
+        }
+
+        let player_opt = self
+            .audio_player_opt
+            .as_ref()
+            .filter(|player| player.path() == path);
+        let playing = player_opt.is_some_and(|player| player.playing());
+        let position = player_opt.map_or(Duration::ZERO, |player| player.position());
+        let duration_opt = match &item.audio_info {
+            AudioInfo::Loaded(metadata, _) => metadata.duration_opt,
+            _ => player_opt.and_then(|player| player.duration_opt()),
+        };
+
+        let mut controls = widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(space_xxs)
+            .push(
+                widget::button::icon(widget::icon::from_name(if playing {
+                    "media-playback-pause-symbolic"
+                } else {
+                    "media-playback-start-symbolic"
+                }))
+                .on_press(Message::MediaPlayPause),
+            );
+        match duration_opt {
+            Some(duration) => {
+                controls = controls
+                    .push(
+                        widget::slider(
+                            0.0..=duration.as_secs_f32(),
+                            position.as_secs_f32(),
+                            Message::AudioSeek,
+                        )
+                        .width(Length::Fill),
+                    )
+                    .push(widget::text::caption(format!(
+                        "{} / {}",
+                        format_duration(position),
+                        format_duration(duration)
+                    )));
+            }
+            // Streams without a duration can only be played from the start
+            None => {
+                controls = controls
+                    .push(Space::with_width(Length::Fill))
+                    .push(widget::text::caption(format_duration(position)));
+            }
+        }
+
+        widget::column::with_children(vec![cover.into(), controls.into()])
+            .spacing(space_xxs)
+            .into()
+    }
+
+    /// Media preview with playback controls for an item in the details pane, if it can be played
+    pub fn media_view<'a>(&'a self, item: &'a Item) -> Option<Element<'a, Message>> {
+        let path = item.path_opt()?;
+        if !matches!(item.audio_info, AudioInfo::NotAudio) {
+            return Some(self.audio_view(item, path));
+        }
+        if !matches!(
+            item.document_preview,
+            DocumentPreview::NotDocument | DocumentPreview::Error(_)
+        ) {
+            return Some(document_view(item, path));
+        }
+        if !is_animated_mime(&item.mime) {
+            return None;
+        }
+        let cosmic_theme::Spacing { space_xxs, .. } = theme::active().cosmic().spacing;
+
+        let media_opt = self
+            .media_preview_opt
+            .as_ref()
+            .filter(|media| &media.path == path);
+        let frame: Element<_> =
+            match media_opt.and_then(|media| media.frames_opt.as_ref()?.get(media.frame)) {
+                Some((handle, _)) => widget::icon::icon(handle.clone())
+                    .content_fit(ContentFit::Contain)
+                    .size(THUMBNAIL_SIZE as u16)
+                    .into(),
+                None => item.preview(),
+            };
+        let playing = media_opt.is_some_and(|media| media.playing);
+
+        Some(
+            widget::column::with_children(vec![
+                widget::container(frame)
+                    .center_x(Length::Fill)
+                    .max_height(THUMBNAIL_SIZE as f32)
+                    .into(),
+                widget::container(media_controls(playing))
+                    .center_x(Length::Fill)
+                    .into(),
+            ])
+            .spacing(space_xxs)
+            .into(),
+        )
+    }
+
+    /// Set the glob patterns for names that are hidden, returning true if they changed and the
+    /// items need to be rescanned
+    pub fn set_hidden_patterns(&mut self, patterns: &[String]) -> bool {
+        let hidden_patterns: Vec<_> = patterns
+            .iter()
+            .filter_map(|pattern| match glob::Pattern::new(pattern) {
+                Ok(ok) => Some(ok),
+                Err(err) => {
+                    log::warn!("failed to parse hidden pattern {:?}: {}", pattern, err);
+                    None
+                }
+            })
+            .collect();
+        if hidden_patterns == self.hidden_patterns {
+            return false;
+        }
+        self.hidden_patterns = hidden_patterns;
+        true
+    }
+
+    pub fn set_folder_columns(&mut self, folder_columns: &BTreeMap<PathBuf, Vec<HeadingOptions>>) {
+        self.folder_columns.clone_from(folder_columns);
+    }
+
+    pub fn set_folder_column_widths(
+        &mut self,
+        folder_column_widths: &BTreeMap<PathBuf, ColumnWidths>,
+    ) {
+        self.folder_column_widths.clone_from(folder_column_widths);
+    }
+
+    /// Returns true if the starred paths changed
+    pub fn set_starred(&mut self, starred: &BTreeSet<PathBuf>) -> bool {
+        if *starred == self.starred {
+            return false;
+        }
+        self.starred.clone_from(starred);
+        true
+    }
+
+    pub fn is_starred(&self, item: &Item) -> bool {
+        item.path_opt()
+            .is_some_and(|path| self.starred.contains(path))
+    }
+
+    /// The size column shows the total size of folders instead of their number of items
+    fn folder_sizes(&self) -> bool {
+        // Sizes of whole volumes would take too long to calculate
+        self.config.folder_sizes
+            && self.config.view == View::List
+            && self.location != Location::Computer
+    }
+
+    /// Forget the calculated sizes of the folders containing a changed path
+    pub fn forget_dir_sizes(&mut self, path: &Path) {
+        self.dir_sizes.retain(|dir, _| !path.starts_with(dir));
+    }
+
+    fn set_attributes(&mut self, path: PathBuf, attributes: Result<Attributes, String>) {
+        let location = Location::Path(path);
+        if let Some(ref mut item) = self.parent_item_opt {
+            if item.location_opt.as_ref() == Some(&location) {
+                item.attributes_opt = Some(attributes.clone());
+            }
+        }
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                if item.location_opt.as_ref() == Some(&location) {
+                    item.attributes_opt = Some(attributes);
+                    break;
                 }
             }
         }
-
-        // Yield in case this process takes a while.
-        tokio::task::yield_now().await;
     }
-    Ok(total)
-}
 
-fn folder_name<P: AsRef<Path>>(path: P) -> (String, bool) {
-    let path = path.as_ref();
-    let mut found_home = false;
-    let name = match path.file_name() {
-        Some(name) => {
-            if path == crate::home_dir() {
-                found_home = true;
-                fl!("home")
-            } else {
-                // This is not optimized but it helps ensure the same display names
-                match item_from_path(path, IconSizes::default()) {
-                    Ok(item) => item.display_name,
-                    Err(_err) => name.to_string_lossy().to_string(),
+    fn set_checksums(&mut self, path: PathBuf, checksums: Checksums) {
+        let location = Location::Path(path);
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                if item.location_opt.as_ref() == Some(&location) {
+                    item.checksums = checksums;
+                    break;
                 }
             }
         }
-        None => {
-            fl!("filesystem")
+    }
+
+    fn set_dir_size(&mut self, path: PathBuf, dir_size: DirSize) {
+        match &dir_size {
+            DirSize::Directory(size) => {
+                self.dir_sizes.insert(path.clone(), *size);
+            }
+            _ => {
+                self.dir_sizes.remove(&path);
+            }
         }
-    };
-    (name, found_home)
-}
+        let location = Location::Path(path);
+        if let Some(ref mut item) = self.parent_item_opt {
+            if item.location_opt.as_ref() == Some(&location) {
+                item.dir_size = dir_size.clone();
+            }
+        }
+        if let Some(ref mut items) = self.items_opt {
+            for item in items.iter_mut() {
+                if item.location_opt.as_ref() == Some(&location) {
+                    item.dir_size = dir_size;
+                    break;
+                }
+            }
+        }
+    }
 
-// parse .hidden file and return files path
-fn parse_hidden_file(path: &PathBuf) -> Vec<String> {
-    let file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
-    };
+    /// Grid view is split into groups of items of the same kind
+    fn group_by_kind(&self) -> bool {
+        self.config.group_by_kind
+            && self.config.view == View::Grid
+            && !matches!(self.location, Location::Desktop(..))
+    }
 
-    BufReader::new(file)
-        .lines()
-        .map_while(Result::ok)
-        .flat_map(|line| {
-            let line = line.trim();
-            (!line.is_empty()).then_some(line.to_owned())
-        })
-        .collect()
-}
+    /// Columns shown right of the name in list view
+    pub fn columns(&self) -> Vec<HeadingOptions> {
+        match &self.location {
+            Location::Trash => vec![
+                HeadingOptions::OriginalLocation,
+                HeadingOptions::TrashedOn,
+                HeadingOptions::Size,
+            ],
+            Location::Computer => vec![HeadingOptions::Kind, HeadingOptions::Size],
+            Location::Path(path) => match self.folder_columns.get(path) {
+                Some(columns) => columns.clone(),
+                None => HeadingOptions::DEFAULT_COLUMNS.to_vec(),
+            },
+            _ => HeadingOptions::DEFAULT_COLUMNS.to_vec(),
+        }
+    }
 
-impl Tab {
-    pub fn new(location: Location, config: TabConfig) -> Self {
-        let location = location.normalize();
-        let location_ancestors = location.ancestors();
-        let location_title = location.title();
-        let history = vec![location.clone()];
-        Self {
-            location,
-            location_ancestors,
-            location_title,
-            context_menu: None,
-            location_context_menu_point: None,
-            location_context_menu_index: None,
-            mode: Mode::App,
-            scroll_opt: None,
-            size_opt: Cell::new(None),
-            item_view_size_opt: Cell::new(None),
-            edit_location: None,
-            edit_location_id: widget::Id::unique(),
-            history_i: 0,
-            history,
-            config,
-            sort_name: HeadingOptions::Name,
-            sort_direction: true,
-            gallery: false,
-            parent_item_opt: None,
-            items_opt: None,
-            scrollable_id: widget::Id::unique(),
-            select_focus: None,
-            select_range: None,
-            clicked: None,
-            dnd_hovered: None,
-            selected_clicked: false,
-            modifiers: Modifiers::default(),
-            last_right_click: None,
-            search_context: None,
-            global_cursor_position: None,
-            current_drag_rect: None,
-            virtual_cursor_offset: None,
-            last_scroll_position: None,
-            last_scroll_offset: None,
-            scroll_bounds_opt: None,
-            date_time_formatter: date_time_formatter(config.military_time),
-            time_formatter: time_formatter(config.military_time),
+    /// Column widths of the folder, or the default widths
+    fn column_widths(&self) -> ColumnWidths {
+        match &self.location {
+            Location::Path(path) => self
+                .folder_column_widths
+                .get(path)
+                .copied()
+                .unwrap_or(self.config.column_widths),
+            _ => self.config.column_widths,
         }
     }
 
-    pub fn title(&self) -> String {
-        //TODO: is it possible to return a &str?
-        self.location_title.clone()
+    fn column_width(&self, heading: HeadingOptions) -> f32 {
+        let column_widths = self.column_widths();
+        f32::from(match heading.resize_column() {
+            Some(Column::Modified) => column_widths.modified,
+            Some(Column::Size) => column_widths.size,
+            None => fixed_column_width(heading),
+        })
     }
 
-    pub fn items_opt(&self) -> Option<&Vec<Item>> {
-        self.items_opt.as_ref()
+    fn column_text(&self, item: &Item, heading: HeadingOptions) -> String {
+        let format_time = |time_res: std::io::Result<SystemTime>| {
+            time_res
+                .map(|time| self.format_time(time).to_string())
+                .unwrap_or_default()
+        };
+        match heading {
+            HeadingOptions::Name => item.display_name.clone(),
+            HeadingOptions::Modified | HeadingOptions::TrashedOn => self.modified_text(item),
+            HeadingOptions::Size => match (&item.dir_size, &item.fs_info_opt) {
+                (DirSize::Directory(size), _) if self.folder_sizes() => format_size(*size),
+                // Volumes show their capacity instead of the number of items
+                (_, Some(fs_info)) if self.location == Location::Computer => fl!(
+                    "item-free-of-total",
+                    free = format_size(fs_info.free),
+                    total = format_size(fs_info.total)
+                ),
+                _ => size_text(item),
+            },
+            HeadingOptions::Kind => kind_text(item),
+            HeadingOptions::MimeType => item.mime.to_string(),
+            HeadingOptions::OriginalLocation => match &item.metadata {
+                ItemMetadata::Trash { entry, .. } => entry.original_parent.display().to_string(),
+                _ => String::new(),
+            },
+            HeadingOptions::Permissions => path_metadata(item)
+                .map(|metadata| permissions_text(metadata.mode(), metadata.is_dir()))
+                .unwrap_or_default(),
+            HeadingOptions::Owner => path_metadata(item)
+                .map(|metadata| user_name(metadata.uid()))
+                .unwrap_or_default(),
+            HeadingOptions::Group => path_metadata(item)
+                .map(|metadata| group_name(metadata.gid()))
+                .unwrap_or_default(),
+            HeadingOptions::Created => path_metadata(item)
+                .map(|metadata| format_time(metadata.created()))
+                .unwrap_or_default(),
+            HeadingOptions::Accessed => path_metadata(item)
+                .map(|metadata| format_time(metadata.accessed()))
+                .unwrap_or_default(),
+        }
     }
 
-    pub fn items_opt_mut(&mut self) -> Option<&mut Vec<Item>> {
-        self.items_opt.as_mut()
+    /// Remove the contents of expanded folders in the tree view
+    pub fn collapse_all(&mut self) {
+        if let Some(items) = self.items_opt.as_mut() {
+            items.retain(|item| item.depth == 0);
+            for item in items.iter_mut() {
+                item.expanded = false;
+            }
+        }
     }
 
     pub fn set_items(&mut self, mut items: Vec<Item>) {
         let selected = self.selected_locations();
         for item in items.iter_mut() {
+            if self
+                .hidden_patterns
+                .iter()
+                .any(|pattern| pattern.matches(&item.name))
+            {
+                item.hidden = true;
+            }
             item.selected = false;
             if let Some(location) = &item.location_opt {
                 if selected.contains(location) {
                     item.selected = true;
                 }
             }
+            if let Some(size) = item.path_opt().and_then(|path| self.dir_sizes.get(path)) {
+                item.dir_size = DirSize::Directory(*size);
+            }
+        }
+        if self.location == Location::Trash {
+            let mut folders: Vec<PathBuf> = items
+                .iter()
+                .filter_map(|item| match &item.metadata {
+                    ItemMetadata::Trash { entry, .. } => Some(entry.original_parent.clone()),
+                    _ => None,
+                })
+                .collect();
+            folders.sort();
+            folders.dedup();
+            self.trash_folder_names = std::iter::once(fl!("all-folders"))
+                .chain(folders.iter().map(|folder| folder.display().to_string()))
+                .collect();
+            self.trash_folders = folders;
+            self.trash_sizes_opt = None;
+        }
+        let mut old_items = self.items_opt.take().unwrap_or_default();
+
+        // Items of unchanged files are updated in place, keeping what was loaded for them
+        let mut old_indexes: HashMap<&Location, usize> = HashMap::new();
+        for (i, item) in old_items.iter().enumerate() {
+            if let Some(location) = &item.location_opt {
+                old_indexes.insert(location, i);
+            }
+        }
+        let old_indexes: Vec<Option<usize>> = items
+            .iter()
+            .map(|item| {
+                item.location_opt
+                    .as_ref()
+                    .and_then(|location| old_indexes.get(location).copied())
+            })
+            .collect();
+        for (item, old_i_opt) in items.iter_mut().zip(old_indexes) {
+            if let Some(old_item) = old_i_opt.and_then(|old_i| old_items.get_mut(old_i)) {
+                if item.unchanged(old_item) {
+                    item.take_loaded(old_item);
+                }
+            }
         }
+
+        // Focus, ranges and clicks refer to items by index, so they are found again by location
+        let old_location = |index_opt: Option<usize>| -> Option<Location> {
+            old_items.get(index_opt?)?.location_opt.clone()
+        };
+        let focus_opt = old_location(self.select_focus);
+        let range_opt = self
+            .select_range
+            .map(|(start, end)| (old_location(Some(start)), old_location(Some(end))));
+        let clicked_opt = old_location(self.clicked);
+        let right_click_opt = old_location(self.last_right_click);
+
+        // The item at the top of the view stays in place when items are added or removed above
+        self.scroll_anchor_opt = None;
+        if let Some(scroll_y) = self.scroll_opt.map(|offset| offset.y).filter(|y| *y > 0.0) {
+            self.scroll_anchor_opt = old_items
+                .iter()
+                .filter_map(|item| Some((item.location_opt.as_ref()?, item.rect_opt.get()?)))
+                .filter(|(_, rect)| rect.y + rect.height > scroll_y)
+                .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                .map(|(location, rect)| (location.clone(), rect.y - scroll_y));
+        }
+
+        // Expanded folders that still exist keep their contents in the tree view
+        let expanded: HashSet<PathBuf> = old_items
+            .iter()
+            .filter(|item| item.expanded)
+            .filter_map(|item| item.path_opt().cloned())
+            .collect();
+        if !expanded.is_empty() {
+            for item in items.iter_mut() {
+                item.expanded = item.path_opt().is_some_and(|path| expanded.contains(path));
+            }
+            let roots: HashSet<PathBuf> = items
+                .iter()
+                .filter(|item| item.expanded)
+                .filter_map(|item| item.path_opt().cloned())
+                .collect();
+            items.extend(old_items.into_iter().filter(|item| {
+                item.depth > 0
+                    && item.path_opt().is_some_and(|path| {
+                        path.parent()
+                            .is_some_and(|parent| expanded.contains(parent))
+                            && path
+                                .ancestors()
+                                .nth(usize::from(item.depth))
+                                .is_some_and(|root| roots.contains(root))
+                    })
+            }));
+        }
+
+        let new_index = |location_opt: Option<Location>| -> Option<usize> {
+            let location = location_opt?;
+            items
+                .iter()
+                .position(|item| item.location_opt.as_ref() == Some(&location))
+        };
+        self.select_focus = new_index(focus_opt);
+        self.select_range =
+            range_opt.and_then(|(start, end)| Some((new_index(start)?, new_index(end)?)));
+        self.clicked = new_index(clicked_opt);
+        self.last_right_click = new_index(right_click_opt);
+
         self.items_opt = Some(items);
     }
 
@@ -2258,13 +6016,27 @@ impl Tab {
         locations
     }
 
+    /// Select the items that are shown, leaving out hidden items, items that do not match the
+    /// trash filter, and the contents of collapsed folders
     pub fn select_all(&mut self) {
+        let shown: HashSet<usize> = self
+            .column_sort()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, item)| self.config.show_hidden || !item.hidden)
+            .map(|(i, _)| i)
+            .collect();
+        if let Some(ref mut items) = self.items_opt {
+            for (i, item) in items.iter_mut().enumerate() {
+                item.selected = shown.contains(&i);
+            }
+        }
+    }
+
+    /// Select every item in the location, including ones that are not shown
+    pub fn select_all_including_hidden(&mut self) {
         if let Some(ref mut items) = self.items_opt {
             for item in items.iter_mut() {
-                if !self.config.show_hidden && item.hidden {
-                    item.selected = false;
-                    continue;
-                }
                 item.selected = true;
             }
         }
@@ -2296,6 +6068,11 @@ impl Tab {
         }
     }
 
+    /// Scroll to the offset once the items are loaded
+    pub fn set_scroll_restore(&mut self, offset: AbsoluteOffset) {
+        self.scroll_restore_opt = Some(offset);
+    }
+
     pub fn select_paths(&mut self, paths: Vec<PathBuf>) {
         self.select_focus = None;
         if let Some(ref mut items) = self.items_opt {
@@ -2488,20 +6265,59 @@ impl Tab {
     }
 
     pub fn change_location(&mut self, location: &Location, history_i_opt: Option<usize>) {
+        // Remember scroll position and selection of the location being left
+        let selected_paths = self
+            .selected_locations()
+            .into_iter()
+            .filter_map(|location| location.path_opt().map(Path::to_path_buf))
+            .collect();
+        if let Some(entry) = self.history.get_mut(self.history_i) {
+            if entry.location == self.location {
+                entry.scroll_opt = self.scroll_opt;
+                entry.selected_paths = selected_paths;
+            }
+        }
+
+        if self.location != location.normalize() {
+            self.trash_filter = TrashFilter::default();
+        }
+        if self.location != location.normalize() {
+            self.capabilities = LocationCapabilities::default();
+        }
         self.location = location.normalize();
+        let prefetched_opt = self.prefetch.change_location(match &self.location {
+            Location::Path(path) => Some(path),
+            _ => None,
+        });
         self.location_ancestors = self.location.ancestors();
-        self.location_title = self.location.title();
+        self.location_title = self.location.tab_title(self.config.title);
         self.context_menu = None;
         self.edit_location = None;
         self.items_opt = None;
-        //TODO: remember scroll by location?
+        self.offline = false;
+        self.reveal_path_opt = None;
+        self.animation_opt = None;
+        self.media_preview_opt = None;
         self.scroll_opt = None;
+        self.scroll_restore_opt = None;
+        self.scroll_anchor_opt = None;
         self.scroll_bounds_opt = None;
         self.select_focus = None;
         self.search_context = None;
+        self.search_finished_opt = None;
+        self.attributes_edit = AttributesEdit::default();
+        // Show the listing read ahead until the location is scanned again
+        if let Some((parent_item_opt, items)) = prefetched_opt {
+            self.parent_item_opt = parent_item_opt;
+            self.set_items(items);
+        }
         if let Some(history_i) = history_i_opt {
             // Navigating in history
             self.history_i = history_i;
+            self.scroll_restore_opt = self
+                .history
+                .get(history_i)
+                .and_then(|entry| entry.scroll_opt);
         } else {
             // Truncate history to remove next entries
             self.history.truncate(self.history_i + 1);
@@ -2509,8 +6325,8 @@ impl Tab {
             // Compact consecutive matching paths
             {
                 let mut remove = false;
-                if let Some(last_location) = self.history.last() {
-                    if let Some(last_path) = last_location.path_opt() {
+                if let Some(last_entry) = self.history.last() {
+                    if let Some(last_path) = last_entry.location.path_opt() {
                         if let Some(path) = location.path_opt() {
                             remove = last_path == path;
                         }
@@ -2523,13 +6339,14 @@ impl Tab {
 
             // Push to the front of history
             self.history_i = self.history.len();
-            self.history.push(location.clone());
+            self.history.push(HistoryEntry::new(location.clone()));
         }
     }
 
     pub fn update(&mut self, message: Message, modifiers: Modifiers) -> Vec<Command> {
         let mut commands = Vec::new();
         let mut cd = None;
+        let mut cd_selected_paths = None;
         let mut history_i_opt = None;
         let mod_ctrl = modifiers.contains(Modifiers::CTRL) && self.mode.multiple();
         let mod_shift = modifiers.contains(Modifiers::SHIFT) && self.mode.multiple();
@@ -2543,6 +6360,7 @@ impl Tab {
                 }
                 self.context_menu = None;
                 self.location_context_menu_index = None;
+                self.column_menu = false;
                 if let Some(ref mut items) = self.items_opt {
                     for (i, item) in items.iter_mut().enumerate() {
                         if mod_ctrl {
@@ -2556,8 +6374,66 @@ impl Tab {
                     }
                 }
             }
+            Message::ColumnAutoFit(column) => {
+                self.column_resize_opt = None;
+                let space_xxs = theme::active().cosmic().spacing.space_xxs;
+                let (heading, texts): (String, Vec<String>) = match column {
+                    Column::Modified => (
+                        if self.location == Location::Trash {
+                            fl!("trashed-on")
+                        } else {
+                            fl!("modified")
+                        },
+                        self.items_opt
+                            .iter()
+                            .flatten()
+                            .map(|item| self.modified_text(item))
+                            .collect(),
+                    ),
+                    Column::Size => (
+                        fl!("size"),
+                        self.items_opt.iter().flatten().map(size_text).collect(),
+                    ),
+                };
+                // Leave room for the sort indicator next to the heading
+                let width = texts
+                    .iter()
+                    .map(|text| text_width_body(text))
+                    .fold(text_width_heading(&heading) + 16.0, f32::max)
+                    + f32::from(2 * space_xxs);
+                self.set_column_width(column, width);
+                commands.push(self.column_widths_command());
+            }
+            Message::ColumnMenu(column_menu) => {
+                self.column_menu = column_menu && matches!(self.location, Location::Path(_));
+                if self.column_menu {
+                    self.location_context_menu_index = None;
+                }
+            }
+            Message::ColumnResizeEnd => {
+                if self.column_resize_opt.take().is_some() {
+                    commands.push(self.column_widths_command());
+                }
+            }
+            Message::ColumnResizeStart(column) => {
+                if let Some(pos) = self.global_cursor_position {
+                    self.column_resize_opt = Some(ColumnResize {
+                        column,
+                        start_x: pos.x,
+                        start_width: match column {
+                            Column::Modified => self.column_widths().modified,
+                            Column::Size => self.column_widths().size,
+                        },
+                    });
+                }
+            }
             Message::CursorMoved(pos) => {
                 self.global_cursor_position = Some(pos);
+                if let Some(resize) = &self.column_resize_opt {
+                    // Columns are right of the name, so they grow when their left edge moves left
+                    let width = f32::from(resize.start_width) + resize.start_x - pos.x;
+                    self.set_column_width(resize.column, width);
+                }
 
                 // we're currently dragging
                 if self.current_drag_rect.is_some() {
@@ -2648,6 +6524,15 @@ impl Tab {
                     } else {
                         log::warn!("no location for item {:?}", clicked_item);
                     }
+                } else if click_i_opt.is_none() {
+                    // Double click on empty space
+                    if self.config.double_click_parent && !matches!(self.mode, Mode::Desktop) {
+                        if let Location::Path(ref path) = self.location {
+                            if let Some(parent) = path.parent() {
+                                cd = Some(Location::Path(parent.to_owned()));
+                            }
+                        }
+                    }
                 } else {
                     log::warn!("no item for click index {:?}", click_i_opt);
                 }
@@ -2657,6 +6542,7 @@ impl Tab {
                 self.context_menu = None;
                 self.edit_location = None;
                 self.location_context_menu_index = None;
+                self.column_menu = false;
                 if click_i_opt.is_none() {
                     self.clicked = click_i_opt;
                 }
@@ -2800,13 +6686,20 @@ impl Tab {
                 let view = self.config.view;
                 let show_hidden = self.config.show_hidden;
                 let military_time_changed = self.config.military_time != config.military_time;
+                let title_changed = self.config.title != config.title;
                 self.config = config;
                 self.config.view = view;
                 self.config.show_hidden = show_hidden;
+                if !self.config.tree_view {
+                    self.collapse_all();
+                }
                 if military_time_changed {
                     self.date_time_formatter = date_time_formatter(self.config.military_time);
                     self.time_formatter = time_formatter(self.config.military_time);
                 }
+                if title_changed {
+                    self.location_title = self.location.tab_title(self.config.title);
+                }
             }
             Message::ContextAction(action) => {
                 // Close context menu
@@ -2833,6 +6726,8 @@ impl Tab {
             }
             Message::LocationContextMenuIndex(index_opt) => {
                 self.location_context_menu_index = index_opt;
+                self.column_menu = false;
+                self.location_context_menu_mount = None;
             }
             Message::LocationMenuAction(action) => {
                 self.location_context_menu_index = None;
@@ -2853,6 +6748,19 @@ impl Tab {
                             commands.push(Command::OpenInNewWindow(path));
                         }
                     }
+                    LocationMenuAction::OpenInTerminal(ancestor_index) => {
+                        if let Some(path) = path_for_index(ancestor_index) {
+                            commands.push(Command::OpenInTerminal(path));
+                        }
+                    }
+                    LocationMenuAction::CopyPath(ancestor_index) => {
+                        if let Some(path) = path_for_index(ancestor_index) {
+                            commands.push(Command::Iced(
+                                cosmic::iced::clipboard::write(path.to_string_lossy().to_string())
+                                    .into(),
+                            ));
+                        }
+                    }
                     LocationMenuAction::Preview(ancestor_index) => {
                         if let Some(path) = path_for_index(ancestor_index) {
                             //TODO: blocking code, run in command
@@ -2878,6 +6786,11 @@ impl Tab {
                             );
                         }
                     }
+                    LocationMenuAction::Unmount(ancestor_index) => {
+                        if let Some(path) = path_for_index(ancestor_index) {
+                            commands.push(Command::Unmount(path));
+                        }
+                    }
                 }
             }
             Message::Drag(rect_opt) => {
@@ -2887,6 +6800,7 @@ impl Tab {
                 if let Some(rect) = rect_opt {
                     self.context_menu = None;
                     self.location_context_menu_index = None;
+                    self.column_menu = false;
                     if self.mode.multiple() {
                         self.select_rect(rect, mod_ctrl, mod_shift);
                     }
@@ -2920,7 +6834,29 @@ impl Tab {
             }
             Message::EditLocationSubmit => {
                 if let Some(edit_location) = self.edit_location.take() {
-                    cd = edit_location.resolve();
+                    // Search prefixes start a search in the current folder
+                    let search_opt = edit_location.search_term().and_then(|term| {
+                        let path = self.location.path_opt()?;
+                        Some(Location::Search(
+                            path.clone(),
+                            term,
+                            self.config.show_hidden,
+                            Instant::now(),
+                        ))
+                    });
+                    cd = search_opt
+                        .or_else(|| edit_location.network_location())
+                        .or_else(|| edit_location.resolve());
+                }
+            }
+            Message::EditTags(i) => {
+                if let Some(path) = self
+                    .items_opt
+                    .as_ref()
+                    .and_then(|items| items.get(i))
+                    .and_then(|item| item.path_opt())
+                {
+                    commands.push(Command::EditTags(path.clone()));
                 }
             }
             Message::OpenInNewTab(path) => {
@@ -2929,6 +6865,79 @@ impl Tab {
             Message::EmptyTrash => {
                 commands.push(Command::EmptyTrash);
             }
+            Message::ExpandFolder(index) => {
+                if let Some(items) = self.items_opt.as_mut() {
+                    let path_opt = items
+                        .get(index)
+                        .filter(|item| item.metadata.is_dir())
+                        .and_then(|item| item.path_opt().cloned());
+                    if let Some(path) = path_opt {
+                        if items[index].expanded {
+                            items[index].expanded = false;
+                            // Contents of expanded subfolders are removed too
+                            items.retain(|item| {
+                                item.depth == 0
+                                    || !item.path_opt().is_some_and(|child| {
+                                        child != &path && child.starts_with(&path)
+                                    })
+                            });
+                        } else {
+                            items[index].expanded = true;
+                            let sizes = self.config.icon_sizes;
+                            commands.push(Command::Iced(
+                                cosmic::Task::perform(
+                                    async move {
+                                        let folder = path.clone();
+                                        match tokio::task::spawn_blocking(move || {
+                                            scan_path(&folder, sizes)
+                                        })
+                                        .await
+                                        {
+                                            Ok(items) => Message::ExpandItems(path, items),
+                                            Err(err) => {
+                                                log::warn!(
+                                                    "failed to scan {:?} for tree view: {}",
+                                                    path,
+                                                    err
+                                                );
+                                                Message::ExpandItems(path, Vec::new())
+                                            }
+                                        }
+                                    },
+                                    |x| x,
+                                )
+                                .into(),
+                            ));
+                        }
+                    }
+                }
+            }
+            Message::ExpandItems(path, mut children) => {
+                if let Some(items) = self.items_opt.as_mut() {
+                    // The folder may have been collapsed while it was scanned
+                    let depth_opt = items
+                        .iter()
+                        .find(|item| item.expanded && item.path_opt() == Some(&path))
+                        .map(|item| item.depth + 1);
+                    if let Some(depth) = depth_opt {
+                        items.retain(|item| {
+                            item.depth == 0
+                                || item.path_opt().and_then(|x| x.parent()) != Some(path.as_path())
+                        });
+                        for child in children.iter_mut() {
+                            child.depth = depth;
+                            if self
+                                .hidden_patterns
+                                .iter()
+                                .any(|pattern| pattern.matches(&child.name))
+                            {
+                                child.hidden = true;
+                            }
+                        }
+                        items.extend(children);
+                    }
+                }
+            }
             #[cfg(feature = "desktop")]
             Message::ExecEntryAction(path, action) => {
                 let lang_id = crate::localize::LANGUAGE_LOADER.current_language();
@@ -2950,6 +6959,10 @@ impl Tab {
             }
             Message::Gallery(gallery) => {
                 self.gallery = gallery;
+                if !self.gallery {
+                    self.slideshow_opt = None;
+                }
+                self.update_gallery_media();
             }
             Message::GalleryPrevious | Message::GalleryNext => {
                 let mut pos_opt = None;
@@ -2986,6 +6999,7 @@ impl Tab {
                 if let Some(id) = self.select_focus_id() {
                     commands.push(Command::Iced(widget::button::focus(id).into()));
                 }
+                self.update_gallery_media();
             }
             Message::GalleryToggle => {
                 if let Some(indices) = self.column_sort() {
@@ -2996,19 +7010,68 @@ impl Tab {
                         }
                     }
                 }
+                if !self.gallery {
+                    self.slideshow_opt = None;
+                }
+                self.update_gallery_media();
+            }
+            Message::SlideshowToggle => {
+                if self.slideshow_opt.take().is_none() {
+                    self.slideshow_opt = self.slideshow();
+                }
+            }
+            Message::SlideshowTick => {
+                let current_opt = self
+                    .select_focus
+                    .and_then(|index| self.items_opt.as_ref()?.get(index)?.path_opt().cloned());
+                let config = self.config.slideshow;
+                let next_opt = self.slideshow_opt.as_mut().and_then(|slideshow| {
+                    let path = slideshow.advance(current_opt.as_deref(), config)?;
+                    Some((path.to_path_buf(), slideshow.selected_only))
+                });
+                match next_opt {
+                    Some((path, selected_only)) => {
+                        let index_opt = self.items_opt.as_ref().and_then(|items| {
+                            items.iter().position(|item| item.path_opt() == Some(&path))
+                        });
+                        match index_opt {
+                            Some(index) if selected_only => self.select_focus = Some(index),
+                            Some(_) => self.select_paths(vec![path]),
+                            None => {
+                                // Removed items are skipped
+                                if let Some(slideshow) = &mut self.slideshow_opt {
+                                    slideshow.paths.retain(|other| other != &path);
+                                }
+                            }
+                        }
+                        if let Some(offset) = self.select_focus_scroll() {
+                            commands.push(Command::Iced(
+                                scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
+                            ));
+                        }
+                    }
+                    None => self.slideshow_opt = None,
+                }
+                self.update_gallery_media();
             }
             Message::GoNext => {
                 if let Some(history_i) = self.history_i.checked_add(1) {
-                    if let Some(location) = self.history.get(history_i) {
-                        cd = Some(location.clone());
+                    if let Some(entry) = self.history.get(history_i) {
+                        cd = Some(entry.location.clone());
+                        if !entry.selected_paths.is_empty() {
+                            cd_selected_paths = Some(entry.selected_paths.clone());
+                        }
                         history_i_opt = Some(history_i);
                     }
                 }
             }
             Message::GoPrevious => {
                 if let Some(history_i) = self.history_i.checked_sub(1) {
-                    if let Some(location) = self.history.get(history_i) {
-                        cd = Some(location.clone());
+                    if let Some(entry) = self.history.get(history_i) {
+                        cd = Some(entry.location.clone());
+                        if !entry.selected_paths.is_empty() {
+                            cd_selected_paths = Some(entry.selected_paths.clone());
+                        }
                         history_i_opt = Some(history_i);
                     }
                 }
@@ -3194,15 +7257,40 @@ impl Tab {
                     }
                 }
             }
+            Message::LocationSelect(path) => {
+                if let Some(parent) = path.parent() {
+                    cd = Some(Location::Path(parent.to_path_buf()));
+                    cd_selected_paths = Some(vec![path]);
+                }
+            }
             Message::LocationUp => {
                 // Sets location to the path's parent
                 // Does nothing if path is root or location is Trash
-                if let Location::Path(ref path) = self.location {
-                    if let Some(parent) = path.parent() {
-                        cd = Some(Location::Path(parent.to_owned()));
+                match &self.location {
+                    Location::Path(path) => {
+                        if let Some(parent) = path.parent() {
+                            cd = Some(Location::Path(parent.to_owned()));
+                        }
+                    }
+                    Location::Dates(path, Some(folder)) => {
+                        cd = Some(Location::Dates(path.clone(), folder.parent()));
+                    }
+                    Location::Dates(path, None) => {
+                        cd = Some(Location::Path(path.clone()));
+                    }
+                    Location::Network(uri, _) => {
+                        if let Some(parent) = network_parent(uri) {
+                            cd = Some(parent);
+                        }
                     }
+                    _ => {}
                 }
             }
+            Message::BrowseByDate => match &self.location {
+                Location::Path(path) => cd = Some(Location::Dates(path.clone(), None)),
+                Location::Dates(path, _) => cd = Some(Location::Path(path.clone())),
+                _ => {}
+            },
             Message::ModifiersChanged(modifiers) => {
                 self.modifiers = modifiers;
             }
@@ -3218,12 +7306,12 @@ impl Tab {
                     None => {
                         if let Some(ref mut items) = self.items_opt {
                             let mut open_files = Vec::new();
+                            let mut open_folders = Vec::new();
                             for item in items.iter() {
                                 if item.selected {
                                     if let Some(location) = &item.location_opt {
                                         if item.metadata.is_dir() {
-                                            //TODO: allow opening multiple tabs?
-                                            cd = Some(location.clone());
+                                            open_folders.push(location.clone());
                                         } else if let Some(path) = location.path_opt() {
                                             open_files.push(path.to_path_buf());
                                         }
@@ -3233,7 +7321,13 @@ impl Tab {
                                 }
                             }
 
-                            commands.push(Command::OpenFile(open_files));
+                            if open_files.is_empty() && open_folders.len() == 1 {
+                                cd = open_folders.pop();
+                            } else if open_folders.is_empty() {
+                                commands.push(Command::OpenFile(open_files));
+                            } else {
+                                commands.push(Command::OpenAll(open_files, open_folders));
+                            }
                         }
                     }
                 }
@@ -3254,6 +7348,36 @@ impl Tab {
                     Some(selected_paths),
                 ));
             }
+            Message::Reveal => {
+                if let Some(path) = self.reveal_path_opt.take() {
+                    let index_opt = self.items_opt.as_mut().and_then(|items| {
+                        let (i, item) = items
+                            .iter_mut()
+                            .enumerate()
+                            .find(|(_, item)| item.path_opt() == Some(&path))?;
+                        item.highlighted = true;
+                        Some(i)
+                    });
+                    if let Some(i) = index_opt {
+                        self.select_focus = Some(i);
+                        if let Some(offset) = self.select_focus_scroll() {
+                            commands.push(Command::Iced(
+                                scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
+                            ));
+                        }
+                        commands.push(Command::Iced(
+                            cosmic::Task::perform(
+                                async move {
+                                    tokio::time::sleep(REVEAL_DURATION).await;
+                                    Message::HighlightDeactivate(i)
+                                },
+                                |x| x,
+                            )
+                            .into(),
+                        ));
+                    }
+                }
+            }
             Message::RightClick(click_i_opt) => {
                 if mod_ctrl || mod_shift {
                     self.update(Message::Click(click_i_opt), modifiers);
@@ -3272,7 +7396,12 @@ impl Tab {
                 self.last_right_click = click_i_opt;
             }
             Message::MiddleClick(click_i) => {
-                if mod_ctrl || mod_shift {
+                let clicked_dir = self
+                    .items_opt
+                    .as_ref()
+                    .and_then(|items| items.get(click_i))
+                    .is_some_and(|item| item.metadata.is_dir());
+                if mod_shift || (mod_ctrl && !clicked_dir) {
                     self.update(Message::Click(Some(click_i)), modifiers);
                 } else {
                     if let Some(ref mut items) = self.items_opt {
@@ -3286,8 +7415,11 @@ impl Tab {
                     {
                         if let Some(path) = clicked_item.path_opt() {
                             if clicked_item.metadata.is_dir() {
-                                //cd = Some(Location::Path(path.clone()));
-                                commands.push(Command::OpenInNewTab(path.clone()))
+                                if mod_ctrl {
+                                    commands.push(Command::OpenInNewWindow(path.clone()))
+                                } else {
+                                    commands.push(Command::OpenInNewTab(path.clone()))
+                                }
                             } else {
                                 commands.push(Command::OpenFile(vec![path.clone()]));
                             }
@@ -3303,10 +7435,193 @@ impl Tab {
                 if let Some(item) = self.items_opt.as_mut().and_then(|f| f.get_mut(i)) {
                     item.highlighted = false;
                 }
+                if self
+                    .animation_opt
+                    .as_ref()
+                    .is_some_and(|animation| animation.index == i)
+                {
+                    self.animation_opt = None;
+                }
             }
             Message::HighlightActivate(i) => {
                 if let Some(item) = self.items_opt.as_mut().and_then(|f| f.get_mut(i)) {
                     item.highlighted = true;
+                    // Only items that already have an image thumbnail are animated
+                    if self.config.animated_preview
+                        && self.config.view == View::Grid
+                        && is_animated_mime(&item.mime)
+                        && matches!(item.thumbnail_opt, Some(ItemThumbnail::Image(..)))
+                    {
+                        if let Some(path) = item.path_opt() {
+                            let scrub = cfg!(feature = "video-thumbnails")
+                                && item.mime.type_() == mime::VIDEO;
+                            self.animation_opt = Some(Animation {
+                                index: i,
+                                path: path.clone(),
+                                mime: item.mime.clone(),
+                                frames_opt: None,
+                                frame: 0,
+                                start: Instant::now(),
+                                scrub_opt: scrub.then_some(0.0),
+                            });
+                        }
+                    }
+                }
+            }
+            Message::AnimationFrames(path, frames) => {
+                if let Some(animation) = &mut self.animation_opt {
+                    if animation.path == path {
+                        animation.frames_opt = Some(frames);
+                        animation.frame = 0;
+                        animation.start = Instant::now();
+                        if let Some(fraction) = animation.scrub_opt {
+                            animation.scrub(fraction);
+                        }
+                    }
+                }
+            }
+            Message::HoverScrub(i, fraction) => {
+                if let Some(animation) = &mut self.animation_opt {
+                    if animation.index == i && animation.scrub_opt.is_some() {
+                        animation.scrub(fraction);
+                    }
+                }
+            }
+            Message::AnimationTick => {
+                if let Some(animation) = &mut self.animation_opt {
+                    if let Some(frames) = &animation.frames_opt {
+                        if animation.start.elapsed() >= ANIMATION_DURATION {
+                            // Stop on the static thumbnail
+                            animation.frames_opt = Some(Vec::new());
+                        } else if !frames.is_empty() {
+                            animation.frame = (animation.frame + 1) % frames.len();
+                        }
+                    }
+                }
+            }
+            Message::MediaFrames(path, frames) => {
+                if let Some(media) = &mut self.media_preview_opt {
+                    if media.path == path {
+                        media.frames_opt = Some(frames);
+                        media.frame = 0;
+                    }
+                }
+            }
+            Message::MediaPlayPause => {
+                #[cfg(feature = "video-thumbnails")]
+                if let Some(video) = &mut self.gallery_video_opt {
+                    if video.player.playing() {
+                        video.player.pause();
+                        // The frame shown is where it stopped
+                        video.frame_generation_opt = Some(video.player.generation());
+                    } else {
+                        video.player.play();
+                    }
+                    return commands;
+                }
+                if let Some(player) = self.audio_player() {
+                    if player.playing() {
+                        player.pause();
+                    } else if let Err(err) = player.play() {
+                        log::warn!("failed to play {:?}: {}", player.path(), err);
+                    }
+                } else if let Some((path, mime)) = self.media_item() {
+                    match &mut self.media_preview_opt {
+                        Some(media) if media.path == path => {
+                            media.playing = !media.playing;
+                            // Start over when playing again at the end
+                            let len = media.frames_opt.as_ref().map_or(0, |frames| frames.len());
+                            if media.playing && media.frame + 1 >= len {
+                                media.frame = 0;
+                            }
+                        }
+                        _ => {
+                            self.media_preview_opt = Some(MediaPreview {
+                                path,
+                                mime,
+                                size: THUMBNAIL_SIZE,
+                                frames_opt: None,
+                                frame: 0,
+                                playing: true,
+                            });
+                        }
+                    }
+                }
+            }
+            Message::MediaSeek(forward) => {
+                #[cfg(feature = "video-thumbnails")]
+                if let Some(video) = &mut self.gallery_video_opt {
+                    let position = if forward {
+                        video.player.position() + AUDIO_SEEK_STEP
+                    } else {
+                        video.player.position().saturating_sub(AUDIO_SEEK_STEP)
+                    };
+                    video.player.seek(position);
+                    return commands;
+                }
+                if let Some(player) = self.audio_player() {
+                    let position = if forward {
+                        player.position() + AUDIO_SEEK_STEP
+                    } else {
+                        player.position().saturating_sub(AUDIO_SEEK_STEP)
+                    };
+                    if let Err(err) = player.seek(position) {
+                        log::warn!("failed to seek in {:?}: {}", player.path(), err);
+                    }
+                } else if let Some((path, mime)) = self.media_item() {
+                    match &mut self.media_preview_opt {
+                        Some(media) if media.path == path => media.seek(forward),
+                        _ => {
+                            // Load the frames so the next seek has something to move through
+                            self.media_preview_opt = Some(MediaPreview {
+                                path,
+                                mime,
+                                size: THUMBNAIL_SIZE,
+                                frames_opt: None,
+                                frame: 0,
+                                playing: false,
+                            });
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "video-thumbnails")]
+            Message::GalleryVideo(path, generation, event) => {
+                if let Some(video) = self
+                    .gallery_video_opt
+                    .as_mut()
+                    .filter(|video| video.player.path() == path)
+                {
+                    match event {
+                        VideoEvent::Duration(duration) => video.player.set_duration(duration),
+                        // Frames of a stream from before seeking are dropped
+                        VideoEvent::Frame(handle) if generation == video.player.generation() => {
+                            video.frame_opt = Some(handle);
+                            video.frame_generation_opt = Some(generation);
+                        }
+                        VideoEvent::End if generation == video.player.generation() => {
+                            video.player.finish();
+                            video.frame_generation_opt = Some(video.player.generation());
+                        }
+                        VideoEvent::Frame(_) | VideoEvent::End => {}
+                    }
+                }
+            }
+            #[cfg(feature = "video-thumbnails")]
+            Message::GalleryVideoSeek(seconds) => {
+                if let Some(video) = &mut self.gallery_video_opt {
+                    video.player.seek(Duration::from_secs_f32(seconds.max(0.0)));
+                }
+            }
+            Message::MediaTick => {
+                if let Some(media) = &mut self.media_preview_opt {
+                    if let Some(frames) = &media.frames_opt {
+                        if media.frame + 1 < frames.len() {
+                            media.frame += 1;
+                        } else {
+                            media.playing = false;
+                        }
+                    }
                 }
             }
 
@@ -3347,6 +7662,38 @@ impl Tab {
                     .into(),
                 ));
             }
+            Message::ScrollRestore => {
+                if let Some(offset) = self.scroll_restore_opt.take() {
+                    self.scroll_opt = Some(offset);
+                    commands.push(Command::Iced(
+                        scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
+                    ));
+                }
+            }
+            Message::ScrollAnchor => {
+                if let Some((location, offset)) = self.scroll_anchor_opt.take() {
+                    let rect_opt = self.items_opt.as_ref().and_then(|items| {
+                        items
+                            .iter()
+                            .find(|item| item.location_opt.as_ref() == Some(&location))?
+                            .rect_opt
+                            .get()
+                    });
+                    if let Some(rect) = rect_opt {
+                        let y = (rect.y - offset).max(0.0);
+                        if self
+                            .scroll_opt
+                            .map_or(true, |scroll| (scroll.y - y).abs() >= 1.0)
+                        {
+                            let offset = AbsoluteOffset { x: 0.0, y };
+                            self.scroll_opt = Some(offset);
+                            commands.push(Command::Iced(
+                                scrollable::scroll_to(self.scrollable_id.clone(), offset).into(),
+                            ));
+                        }
+                    }
+                }
+            }
             Message::ScrollToFocus => {
                 if let Some(offset) = self.select_focus_scroll() {
                     commands.push(Command::Iced(
@@ -3357,6 +7704,7 @@ impl Tab {
             Message::SearchContext(location, context) => {
                 if location == self.location {
                     self.search_context = context.0;
+                    self.search_finished_opt = None;
                 } else {
                     log::warn!(
                         "search context provided for {:?} instead of {:?}",
@@ -3381,10 +7729,16 @@ impl Tab {
                                 };
                                 if index < MAX_SEARCH_RESULTS {
                                     //TODO: use correct IconSizes
-                                    items.insert(
-                                        index,
-                                        item_from_entry(path, name, metadata, IconSizes::default()),
-                                    );
+                                    let mut item =
+                                        item_from_entry(path, name, metadata, IconSizes::default());
+                                    if self
+                                        .hidden_patterns
+                                        .iter()
+                                        .any(|pattern| pattern.matches(&item.name))
+                                    {
+                                        item.hidden = true;
+                                    }
+                                    items.insert(index, item);
                                 }
                                 // Ensure that updates make it to the GUI in a timely manner
                                 if !finished && duration.elapsed() >= MAX_SEARCH_LATENCY {
@@ -3404,12 +7758,30 @@ impl Tab {
                         log::warn!("search ready but items array is empty");
                     }
                 }
-                if finished {
-                    self.search_context = None;
+                if finished && self.search_context.take().is_some() {
+                    if let Location::Search(.., start) = &self.location {
+                        self.search_finished_opt = Some((start.elapsed(), false));
+                    }
+                }
+            }
+            Message::SearchStop => {
+                // Results found so far are kept
+                if let Some(context) = self.search_context.take() {
+                    context.stop.store(true, atomic::Ordering::Relaxed);
+                    if let Location::Search(.., start) = &self.location {
+                        self.search_finished_opt = Some((start.elapsed(), true));
+                    }
                 }
             }
-            Message::SelectAll => {
-                self.select_all();
+            Message::SearchTick => {
+                // Redraws the elapsed time of the search
+            }
+            Message::SelectAll | Message::SelectAllIncludingHidden => {
+                if let Message::SelectAll = message {
+                    self.select_all();
+                } else {
+                    self.select_all_including_hidden();
+                }
                 if self.select_focus.take().is_some() {
                     // Unfocus currently focused button
                     commands.push(Command::Iced(
@@ -3447,12 +7819,24 @@ impl Tab {
                     }
                 }
             }
+            Message::OpenWithApp(path, mime, id) => {
+                commands.push(Command::OpenWithApp(path, mime, id));
+            }
+            Message::OpenUrl(url) => {
+                commands.push(Command::OpenUrl(url));
+            }
             Message::SetOpenWith(mime, id) => {
                 commands.push(Command::SetOpenWith(mime, id));
             }
+            Message::RestoreFromTrash(entry) => {
+                commands.push(Command::RestoreFromTrash(vec![entry]));
+            }
             Message::SetPermissions(path, mode) => {
                 commands.push(Command::SetPermissions(path, mode));
             }
+            Message::SetPermissionsRecursive(path, mode, uid, gid) => {
+                commands.push(Command::SetPermissionsRecursive(path, mode, uid, gid));
+            }
             Message::SetSort(heading_option, dir) => {
                 if !matches!(self.location, Location::Search(..)) {
                     self.sort_name = heading_option;
@@ -3469,6 +7853,22 @@ impl Tab {
                     }
                 }
             }
+            Message::MimeSniffed(path, mime) => {
+                if let Some(ref mut items) = self.items_opt {
+                    let location = Location::Path(path);
+                    let sizes = self.config.icon_sizes;
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.icon_handle_grid = mime_icon(mime.clone(), sizes.grid());
+                            item.icon_handle_list = mime_icon(mime.clone(), sizes.list());
+                            item.icon_handle_list_condensed =
+                                mime_icon(mime.clone(), sizes.list_condensed());
+                            item.mime = mime;
+                            break;
+                        }
+                    }
+                }
+            }
             Message::Thumbnail(path, thumbnail) => {
                 if let Some(ref mut items) = self.items_opt {
                     let location = Location::Path(path);
@@ -3509,100 +7909,393 @@ impl Tab {
                     ));
                 }
             }
+            Message::TrashFilter(trash_filter) => {
+                self.trash_filter = trash_filter;
+            }
+            Message::TrashSizes(trash_sizes) => {
+                self.trash_sizes_opt = Some(trash_sizes);
+            }
+            Message::Prefetched(path, parent_item_opt, items) => {
+                self.prefetch
+                    .insert(path, items.len(), (parent_item_opt, items));
+            }
             Message::View(view) => {
                 self.config.view = view;
+                if view != View::List {
+                    self.collapse_all();
+                }
+            }
+            Message::ToggleColumn(heading) => {
+                if let Location::Path(path) = &self.location {
+                    let columns = self.columns();
+                    let shown = columns.contains(&heading);
+                    // Columns are kept in display order
+                    let columns: Vec<_> = HeadingOptions::COLUMNS
+                        .into_iter()
+                        .filter(|column| {
+                            if *column == heading {
+                                !shown
+                            } else {
+                                columns.contains(column)
+                            }
+                        })
+                        .collect();
+                    self.folder_columns.insert(path.clone(), columns.clone());
+                    commands.push(Command::SetFolderColumns(path.clone(), columns));
+                }
             }
             Message::ToggleSort(heading_option) => {
-                if !matches!(self.location, Location::Search(..)) {
+                if modifiers.shift() && heading_option != self.sort_name {
+                    // Shift-click chooses the secondary sort key
+                    self.toggle_sort_secondary(heading_option);
+                } else if !matches!(self.location, Location::Search(..)) {
                     let heading_sort = if self.sort_name == heading_option {
                         !self.sort_direction
                     } else {
                         // Default modified to descending, and others to ascending.
                         heading_option != HeadingOptions::Modified
                     };
-                    self.sort_direction = heading_sort;
-                    self.sort_name = heading_option;
+                    self.sort_direction = heading_sort;
+                    self.sort_name = heading_option;
+                }
+            }
+            Message::ToggleSortSecondary(heading_option) => {
+                self.toggle_sort_secondary(heading_option);
+            }
+            Message::Drop(Some((to, mut from))) => {
+                self.dnd_hovered = None;
+                match to {
+                    Location::Desktop(to, ..) | Location::Path(to) if to.is_file() => {
+                        if ArchiveType::from_path(&to).is_some() {
+                            commands.push(Command::Compress(from.paths, to));
+                        }
+                    }
+                    Location::Desktop(to, ..) | Location::Path(to) => {
+                        if let Ok(entries) = fs::read_dir(&to) {
+                            for i in entries.into_iter().filter_map(|e| e.ok()) {
+                                let i = i.path();
+                                from.paths.retain(|p| &i != p);
+                                if from.paths.is_empty() {
+                                    log::info!("All dropped files already in target directory.");
+                                    return commands;
+                                }
+                            }
+                        }
+                        commands.push(Command::DropFiles(to, from))
+                    }
+                    Location::Trash if matches!(from.kind, ClipboardKind::Cut { .. }) => {
+                        commands.push(Command::Delete(from.paths))
+                    }
+                    _ => {
+                        log::warn!("{:?} to {:?} is not supported.", from.kind, to);
+                    }
+                };
+            }
+            Message::Drop(None) => {
+                self.dnd_hovered = None;
+            }
+            Message::DndHover(loc) => {
+                if self
+                    .dnd_hovered
+                    .as_ref()
+                    .is_some_and(|(l, i)| *l == loc && i.elapsed() > HOVER_DURATION)
+                    // Archives are drop targets but cannot be opened as folders
+                    && loc.path_opt().map_or(true, |path| path.is_dir())
+                {
+                    cd = Some(loc);
+                }
+            }
+            Message::DndEnter(loc) => {
+                self.dnd_hovered = Some((loc.clone(), Instant::now()));
+                if loc != self.location {
+                    commands.push(Command::Iced(
+                        cosmic::Task::perform(
+                            async move {
+                                tokio::time::sleep(HOVER_DURATION).await;
+                                Message::DndHover(loc)
+                            },
+                            |x| x,
+                        )
+                        .into(),
+                    ));
+                }
+            }
+            Message::DndLeave(loc) => {
+                if Some(&loc) == self.dnd_hovered.as_ref().map(|(l, _)| l) {
+                    self.dnd_hovered = None;
+                }
+            }
+            Message::WindowDrag => {
+                commands.push(Command::WindowDrag);
+            }
+            Message::WindowToggleMaximize => {
+                commands.push(Command::WindowToggleMaximize);
+            }
+            Message::ZoomDefault => {
+                commands.push(Command::Action(Action::ZoomDefault));
+            }
+            Message::ZoomIn => {
+                commands.push(Command::Action(Action::ZoomIn));
+            }
+            Message::ZoomOut => {
+                commands.push(Command::Action(Action::ZoomOut));
+            }
+            Message::ZoomIndicator => {
+                self.zoom_indicator_opt = Some(Instant::now());
+                commands.push(Command::Iced(
+                    cosmic::Task::perform(
+                        async move {
+                            tokio::time::sleep(ZOOM_INDICATOR_DURATION).await;
+                            Message::ZoomIndicatorTimeout
+                        },
+                        |x| x,
+                    )
+                    .into(),
+                ));
+            }
+            Message::ZoomIndicatorTimeout => {
+                // Zooming again keeps the indicator shown
+                if self
+                    .zoom_indicator_opt
+                    .is_some_and(|shown| shown.elapsed() >= ZOOM_INDICATOR_DURATION)
+                {
+                    self.zoom_indicator_opt = None;
+                }
+            }
+            Message::DirectorySize(path, dir_size) => {
+                self.set_dir_size(path, dir_size);
+            }
+            Message::DirectorySizeProgress(_path) => {}
+            Message::DirectorySizeCancel(path) => {
+                // Dropping the controller cancels the calculation
+                self.set_dir_size(path, DirSize::Error(fl!("cancelled")));
+            }
+            Message::DirectorySizeCalculate(path) => {
+                self.set_dir_size(path, DirSize::Calculating(Controller::default()));
+            }
+            Message::FsInfo(path, fs_info) => {
+                let location = Location::Path(path);
+                if let Some(ref mut item) = self.parent_item_opt {
+                    if item.location_opt.as_ref() == Some(&location) {
+                        item.fs_info_opt = Some(fs_info.clone());
+                    }
+                }
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.fs_info_opt = Some(fs_info);
+                            break;
+                        }
+                    }
+                }
+            }
+            Message::Versions(path, versions) => {
+                let location = Location::Path(path);
+                if let Some(ref mut item) = self.parent_item_opt {
+                    if item.location_opt.as_ref() == Some(&location) {
+                        item.versions_opt = Some(versions.clone());
+                    }
+                }
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.versions_opt = Some(versions);
+                            break;
+                        }
+                    }
+                }
+            }
+            Message::Attributes(path, attributes) => {
+                self.set_attributes(path, attributes);
+            }
+            Message::AttributesChanged(path, result) => match result {
+                Ok(attributes) => {
+                    self.attributes_edit = AttributesEdit::default();
+                    self.set_attributes(path, Ok(attributes));
+                }
+                Err(err) => {
+                    if self.attributes_edit.path_opt.as_ref() != Some(&path) {
+                        self.attributes_edit = AttributesEdit {
+                            path_opt: Some(path),
+                            ..Default::default()
+                        };
+                    }
+                    self.attributes_edit.error_opt = Some(err);
+                }
+            },
+            Message::AttributesEdit(edit) => {
+                self.attributes_edit = edit;
+            }
+            Message::AclAdd(path) => {
+                if self.attributes_edit.path_opt.as_ref() == Some(&path) {
+                    let name = self.attributes_edit.acl_name.trim().to_string();
+                    // Ids can be typed for users and groups without a name
+                    let tag_opt = if self.attributes_edit.acl_group {
+                        name.parse()
+                            .ok()
+                            .or_else(|| get_group_by_name(&name).map(|group| group.gid()))
+                            .map(AclTag::Group)
+                    } else {
+                        name.parse()
+                            .ok()
+                            .or_else(|| get_user_by_name(&name).map(|user| user.uid()))
+                            .map(AclTag::User)
+                    };
+                    match tag_opt {
+                        Some(tag) => {
+                            // New entries can read, which is changed afterwards like other entries
+                            let entry = AclEntry {
+                                tag,
+                                perm: 0o4,
+                                default: self.attributes_edit.acl_default,
+                            };
+                            commands.push(change_attributes(path, move |path| {
+                                attributes::set_acl_entry(path, entry)
+                            }));
+                        }
+                        None => {
+                            self.attributes_edit.error_opt =
+                                Some(fl!("acl-unknown-name", name = name));
+                        }
+                    }
                 }
             }
-            Message::Drop(Some((to, mut from))) => {
-                self.dnd_hovered = None;
-                match to {
-                    Location::Desktop(to, ..) | Location::Path(to) => {
-                        if let Ok(entries) = fs::read_dir(&to) {
-                            for i in entries.into_iter().filter_map(|e| e.ok()) {
-                                let i = i.path();
-                                from.paths.retain(|p| &i != p);
-                                if from.paths.is_empty() {
-                                    log::info!("All dropped files already in target directory.");
-                                    return commands;
-                                }
-                            }
+            Message::AclRemove(path, tag, default) => {
+                commands.push(change_attributes(path, move |path| {
+                    attributes::remove_acl_entry(path, tag, default)
+                }));
+            }
+            Message::AclSet(path, entry) => {
+                commands.push(change_attributes(path, move |path| {
+                    attributes::set_acl_entry(path, entry)
+                }));
+            }
+            Message::XattrAdd(path) => {
+                if self.attributes_edit.path_opt.as_ref() == Some(&path) {
+                    match attributes::user_xattr_name(&self.attributes_edit.xattr_name) {
+                        Some(name) => {
+                            let value = self.attributes_edit.xattr_value.clone();
+                            commands.push(change_attributes(path, move |path| {
+                                attributes::set_xattr(path, &name, value.as_bytes())
+                            }));
+                        }
+                        None => {
+                            self.attributes_edit.error_opt = Some(fl!("xattr-invalid-name"));
                         }
-                        commands.push(Command::DropFiles(to, from))
-                    }
-                    Location::Trash if matches!(from.kind, ClipboardKind::Cut { .. }) => {
-                        commands.push(Command::Delete(from.paths))
-                    }
-                    _ => {
-                        log::warn!("{:?} to {:?} is not supported.", from.kind, to);
                     }
-                };
+                }
             }
-            Message::Drop(None) => {
-                self.dnd_hovered = None;
+            Message::XattrRemove(path, name) => {
+                commands.push(change_attributes(path, move |path| {
+                    attributes::remove_xattr(path, &name)
+                }));
             }
-            Message::DndHover(loc) => {
-                if self
-                    .dnd_hovered
-                    .as_ref()
-                    .is_some_and(|(l, i)| *l == loc && i.elapsed() > HOVER_DURATION)
-                {
-                    cd = Some(loc);
+            Message::XattrSet(path, name) => {
+                if self.attributes_edit.path_opt.as_ref() == Some(&path) {
+                    if let Some(value) = self.attributes_edit.xattr_values.get(&name).cloned() {
+                        commands.push(change_attributes(path, move |path| {
+                            attributes::set_xattr(path, &name, value.as_bytes())
+                        }));
+                    }
                 }
             }
-            Message::DndEnter(loc) => {
-                self.dnd_hovered = Some((loc.clone(), Instant::now()));
-                if loc != self.location {
-                    commands.push(Command::Iced(
-                        cosmic::Task::perform(
-                            async move {
-                                tokio::time::sleep(HOVER_DURATION).await;
-                                Message::DndHover(loc)
-                            },
-                            |x| x,
-                        )
-                        .into(),
-                    ));
+            Message::Capabilities(location, capabilities) => {
+                if location == self.location {
+                    self.capabilities = capabilities;
                 }
             }
-            Message::DndLeave(loc) => {
-                if Some(&loc) == self.dnd_hovered.as_ref().map(|(l, _)| l) {
-                    self.dnd_hovered = None;
+            Message::Checksums(path, checksums) => {
+                self.set_checksums(path, checksums);
+            }
+            Message::ChecksumsCalculate(path) => {
+                self.set_checksums(path, Checksums::Calculating(Controller::default()));
+            }
+            Message::ChecksumsCancel(path) => {
+                // Dropping the controller cancels the calculation
+                self.set_checksums(path, Checksums::NotCalculated);
+            }
+            Message::ChecksumCopy(checksum) => {
+                commands.push(Command::Iced(
+                    cosmic::iced::clipboard::write(checksum).into(),
+                ));
+            }
+            Message::ImageInfo(path, image_info) => {
+                let location = Location::Path(path);
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.image_info = image_info;
+                            break;
+                        }
+                    }
                 }
             }
-            Message::WindowDrag => {
-                commands.push(Command::WindowDrag);
+            Message::AudioInfo(path, audio_info) => {
+                let location = Location::Path(path);
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.audio_info = audio_info;
+                            break;
+                        }
+                    }
+                }
             }
-            Message::WindowToggleMaximize => {
-                commands.push(Command::WindowToggleMaximize);
+            Message::AudioSeek(seconds) => {
+                if let Some(player) = self.audio_player() {
+                    if let Err(err) = player.seek(Duration::from_secs_f32(seconds.max(0.0))) {
+                        log::warn!("failed to seek in {:?}: {}", player.path(), err);
+                    }
+                }
             }
-            Message::ZoomIn => {
-                commands.push(Command::Action(Action::ZoomIn));
+            Message::AudioTick => {
+                let selected_path_opt = self
+                    .items_opt
+                    .as_ref()
+                    .and_then(|items| items.iter().find(|item| item.selected))
+                    .and_then(|item| item.path_opt());
+                if let Some(player) = &mut self.audio_player_opt {
+                    // Playback stops once another item is selected
+                    if selected_path_opt.map(PathBuf::as_path) == Some(player.path()) {
+                        player.poll();
+                    } else {
+                        self.audio_player_opt = None;
+                    }
+                }
             }
-            Message::ZoomOut => {
-                commands.push(Command::Action(Action::ZoomOut));
+            Message::DocumentPreview(path, document_preview) => {
+                let location = Location::Path(path);
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            item.document_preview = document_preview;
+                            break;
+                        }
+                    }
+                }
             }
-            Message::DirectorySize(path, dir_size) => {
+            Message::DocumentPage(path, page) => {
                 let location = Location::Path(path);
-                if let Some(ref mut item) = self.parent_item_opt {
-                    if item.location_opt.as_ref() == Some(&location) {
-                        item.dir_size = dir_size.clone();
+                if let Some(ref mut items) = self.items_opt {
+                    for item in items.iter_mut() {
+                        if item.location_opt.as_ref() == Some(&location) {
+                            if let DocumentPreview::Page(_, pages, _)
+                            | DocumentPreview::LoadingPage(_, pages) = item.document_preview
+                            {
+                                item.document_preview =
+                                    DocumentPreview::LoadingPage(page.clamp(1, pages), pages);
+                            }
+                            break;
+                        }
                     }
                 }
+            }
+            Message::ArchiveEntries(path, archive_entries) => {
+                let location = Location::Path(path);
                 if let Some(ref mut items) = self.items_opt {
                     for item in items.iter_mut() {
                         if item.location_opt.as_ref() == Some(&location) {
-                            item.dir_size = dir_size;
+                            item.archive_entries = archive_entries;
                             break;
                         }
                     }
@@ -3633,7 +8326,7 @@ impl Tab {
                 }
             } else {
                 // Select parent if location is not directory
-                let mut selected_paths = None;
+                let mut selected_paths = cd_selected_paths;
                 if let Some(path) = location.path_opt() {
                     if !path.is_dir() {
                         if let Some(parent) = path.parent() {
@@ -3666,6 +8359,84 @@ impl Tab {
         commands
     }
 
+    fn set_column_width(&mut self, column: Column, width: f32) {
+        let width = width.clamp(COLUMN_WIDTH_MIN.into(), COLUMN_WIDTH_MAX.into()) as u16;
+        let mut column_widths = self.column_widths();
+        match column {
+            Column::Modified => column_widths.modified = width,
+            Column::Size => column_widths.size = width,
+        }
+        match &self.location {
+            Location::Path(path) => {
+                self.folder_column_widths
+                    .insert(path.clone(), column_widths);
+            }
+            _ => self.config.column_widths = column_widths,
+        }
+    }
+
+    fn column_widths_command(&self) -> Command {
+        match &self.location {
+            Location::Path(path) => Command::ColumnWidths(Some(path.clone()), self.column_widths()),
+            _ => Command::ColumnWidths(None, self.column_widths()),
+        }
+    }
+
+    fn modified_text(&self, item: &Item) -> String {
+        match &item.metadata {
+            ItemMetadata::Path { metadata, .. } => match metadata.modified() {
+                Ok(time) => self.format_time(time).to_string(),
+                Err(_) => String::new(),
+            },
+            ItemMetadata::Trash { entry, .. } => FormatTime::from_secs(
+                entry.time_deleted,
+                &self.date_time_formatter,
+                &self.time_formatter,
+            )
+            .map(|t| t.to_string())
+            .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    /// Set the secondary sort key, reverse it if it is already set, and remove it if it is
+    /// already reversed
+    fn toggle_sort_secondary(&mut self, heading_option: HeadingOptions) {
+        if matches!(self.location, Location::Search(..)) {
+            return;
+        }
+        // Default modified to descending, and others to ascending.
+        let default_direction = heading_option != HeadingOptions::Modified;
+        self.sort_secondary_opt = match self.sort_secondary_opt {
+            Some((heading, direction)) if heading == heading_option => {
+                if direction == default_direction {
+                    Some((heading, !direction))
+                } else {
+                    None
+                }
+            }
+            _ => Some((heading_option, default_direction)),
+        };
+    }
+
+    /// Secondary sort key and direction, used for items that are equal by the primary key
+    /// Icon size of the current view, in percent
+    pub(crate) fn zoom(&self) -> u16 {
+        match self.config.view {
+            View::List => self.config.icon_sizes.list.get(),
+            View::Grid => self.config.icon_sizes.grid.get(),
+        }
+    }
+
+    pub(crate) fn sort_secondary(&self) -> Option<(HeadingOptions, bool)> {
+        match self.location {
+            Location::Search(..) => None,
+            _ => self
+                .sort_secondary_opt
+                .filter(|(heading, _)| *heading != self.sort_name),
+        }
+    }
+
     pub(crate) fn sort_options(&self) -> (HeadingOptions, bool, bool) {
         match self.location {
             Location::Search(..) => (HeadingOptions::Modified, false, false),
@@ -3677,7 +8448,57 @@ impl Tab {
         }
     }
 
+    /// Size of the trashed items shown under each group heading, once the sizes are known
+    fn trash_group_sizes(&self) -> HashMap<String, u64> {
+        let mut group_sizes = HashMap::new();
+        let (Some(items), Some(sizes)) = (self.items_opt(), &self.trash_sizes_opt) else {
+            return group_sizes;
+        };
+        for item in items.iter().filter(|item| self.trash_filter.matches(item)) {
+            let ItemMetadata::Trash { entry, .. } = &item.metadata else {
+                continue;
+            };
+            if let (Some(group), Some(size)) =
+                (self.trash_filter.group_name(item), sizes.get(&entry.id))
+            {
+                *group_sizes.entry(group).or_insert(0) += size;
+            }
+        }
+        group_sizes
+    }
+
     fn column_sort(&self) -> Option<Vec<(usize, &Item)>> {
+        fn sort_size(item: &Item, folder_sizes: bool) -> (bool, u64) {
+            // entries take precedence over size
+            match &item.metadata {
+                ItemMetadata::Path {
+                    metadata,
+                    children_opt,
+                } => {
+                    if let (true, DirSize::Directory(size)) = (folder_sizes, &item.dir_size) {
+                        // Calculated folder sizes are sorted with the sizes of files
+                        (false, *size)
+                    } else if metadata.is_dir() {
+                        (true, children_opt.unwrap_or_default() as u64)
+                    } else {
+                        (false, metadata.len())
+                    }
+                }
+                ItemMetadata::Trash { metadata, .. } => match metadata.size {
+                    trash::TrashItemSize::Entries(entries) => (true, entries as u64),
+                    trash::TrashItemSize::Bytes(bytes) => (false, bytes),
+                },
+                ItemMetadata::SimpleDir { entries } => (true, *entries),
+                ItemMetadata::SimpleFile { size } => (false, *size),
+            }
+        }
+        fn sort_time_deleted(item: &Item) -> Option<i64> {
+            match &item.metadata {
+                ItemMetadata::Trash { entry, .. } => Some(entry.time_deleted),
+                _ => None,
+            }
+        }
+
         let check_reverse = |ord: Ordering, sort: bool| {
             if sort {
                 ord
@@ -3686,96 +8507,192 @@ impl Tab {
             }
         };
         let mut items: Vec<_> = self.items_opt.as_ref()?.iter().enumerate().collect();
+        // Date folders are scanned in the order they are shown, the most recent first
+        if let Location::Dates(_, folder_opt) = &self.location {
+            if !folder_opt.is_some_and(|folder| folder.has_files()) {
+                return Some(items);
+            }
+        }
         let (sort_name, sort_direction, folders_first) = self.sort_options();
-        match sort_name {
+        let folder_sizes = self.folder_sizes();
+        let primary = |a: &Item, b: &Item| match sort_name {
             HeadingOptions::Size => {
-                items.sort_by(|a, b| {
-                    // entries take precedence over size
-                    let get_size = |x: &Item| match &x.metadata {
-                        ItemMetadata::Path {
-                            metadata,
-                            children_opt,
-                        } => {
-                            if metadata.is_dir() {
-                                (true, children_opt.unwrap_or_default() as u64)
-                            } else {
-                                (false, metadata.len())
-                            }
-                        }
-                        ItemMetadata::Trash { metadata, .. } => match metadata.size {
-                            trash::TrashItemSize::Entries(entries) => (true, entries as u64),
-                            trash::TrashItemSize::Bytes(bytes) => (false, bytes),
-                        },
-                        ItemMetadata::SimpleDir { entries } => (true, *entries),
-                        ItemMetadata::SimpleFile { size } => (false, *size),
-                    };
-                    let (a_is_entry, a_size) = get_size(a.1);
-                    let (b_is_entry, b_size) = get_size(b.1);
+                let (a_is_entry, a_size) = sort_size(a, folder_sizes);
+                let (b_is_entry, b_size) = sort_size(b, folder_sizes);
 
-                    //TODO: use folders_first?
-                    match (a_is_entry, b_is_entry) {
-                        (true, false) => Ordering::Less,
-                        (false, true) => Ordering::Greater,
-                        _ => check_reverse(a_size.cmp(&b_size), sort_direction),
-                    }
-                })
+                //TODO: use folders_first?
+                match (a_is_entry, b_is_entry) {
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    _ => check_reverse(a_size.cmp(&b_size), sort_direction),
+                }
             }
-            HeadingOptions::Name => items.sort_by(|a, b| {
+            HeadingOptions::Name => {
                 if folders_first {
-                    match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
+                    match (a.metadata.is_dir(), b.metadata.is_dir()) {
                         (true, false) => Ordering::Less,
                         (false, true) => Ordering::Greater,
                         _ => check_reverse(
-                            LANGUAGE_SORTER.compare(&a.1.display_name, &b.1.display_name),
+                            LANGUAGE_SORTER.compare(&a.display_name, &b.display_name),
                             sort_direction,
                         ),
                     }
                 } else {
                     check_reverse(
-                        LANGUAGE_SORTER.compare(&a.1.display_name, &b.1.display_name),
+                        LANGUAGE_SORTER.compare(&a.display_name, &b.display_name),
                         sort_direction,
                     )
                 }
-            }),
+            }
             HeadingOptions::Modified => {
-                items.sort_by(|a, b| {
-                    let a_modified = a.1.metadata.modified();
-                    let b_modified = b.1.metadata.modified();
-                    if folders_first {
-                        match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
-                            (true, false) => Ordering::Less,
-                            (false, true) => Ordering::Greater,
-                            _ => check_reverse(a_modified.cmp(&b_modified), sort_direction),
-                        }
-                    } else {
-                        check_reverse(a_modified.cmp(&b_modified), sort_direction)
+                let a_modified = a.metadata.modified();
+                let b_modified = b.metadata.modified();
+                if folders_first {
+                    match (a.metadata.is_dir(), b.metadata.is_dir()) {
+                        (true, false) => Ordering::Less,
+                        (false, true) => Ordering::Greater,
+                        _ => check_reverse(a_modified.cmp(&b_modified), sort_direction),
                     }
-                });
+                } else {
+                    check_reverse(a_modified.cmp(&b_modified), sort_direction)
+                }
             }
             HeadingOptions::TrashedOn => {
-                let time_deleted = |x: &Item| match &x.metadata {
-                    ItemMetadata::Trash { entry, .. } => Some(entry.time_deleted),
-                    _ => None,
-                };
-
-                items.sort_by(|a, b| {
-                    let a_time_deleted = time_deleted(a.1);
-                    let b_time_deleted = time_deleted(b.1);
-                    if folders_first {
-                        match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
-                            (true, false) => Ordering::Less,
-                            (false, true) => Ordering::Greater,
-                            _ => check_reverse(a_time_deleted.cmp(&b_time_deleted), sort_direction),
-                        }
-                    } else {
-                        check_reverse(b_time_deleted.cmp(&a_time_deleted), sort_direction)
+                let a_time_deleted = sort_time_deleted(a);
+                let b_time_deleted = sort_time_deleted(b);
+                if folders_first {
+                    match (a.metadata.is_dir(), b.metadata.is_dir()) {
+                        (true, false) => Ordering::Less,
+                        (false, true) => Ordering::Greater,
+                        _ => check_reverse(a_time_deleted.cmp(&b_time_deleted), sort_direction),
                     }
-                });
+                } else {
+                    check_reverse(b_time_deleted.cmp(&a_time_deleted), sort_direction)
+                }
+            }
+            heading => {
+                let ordering = column_ordering(heading, a, b);
+                if folders_first {
+                    match (a.metadata.is_dir(), b.metadata.is_dir()) {
+                        (true, false) => Ordering::Less,
+                        (false, true) => Ordering::Greater,
+                        _ => check_reverse(ordering, sort_direction),
+                    }
+                } else {
+                    check_reverse(ordering, sort_direction)
+                }
+            }
+        };
+        // Items that are equal by the primary sort key are ordered by the secondary one
+        let secondary = |a: &Item, b: &Item| match self.sort_secondary() {
+            Some((heading, direction)) => check_reverse(
+                match heading {
+                    HeadingOptions::Name => {
+                        LANGUAGE_SORTER.compare(&a.display_name, &b.display_name)
+                    }
+                    HeadingOptions::Modified => a.metadata.modified().cmp(&b.metadata.modified()),
+                    HeadingOptions::Size => {
+                        sort_size(a, folder_sizes).cmp(&sort_size(b, folder_sizes))
+                    }
+                    HeadingOptions::TrashedOn => sort_time_deleted(a).cmp(&sort_time_deleted(b)),
+                    heading => column_ordering(heading, a, b),
+                },
+                direction,
+            ),
+            None => Ordering::Equal,
+        };
+        // Groups are ordered by their translated kind, which is looked up once for each item
+        let kinds_opt: Option<Vec<String>> = self
+            .group_by_kind()
+            .then(|| items.iter().map(|(_, item)| kind_text(item)).collect());
+        let group = |a: (usize, &Item), b: (usize, &Item)| match &kinds_opt {
+            Some(kinds) => {
+                if folders_first {
+                    match (a.1.metadata.is_dir(), b.1.metadata.is_dir()) {
+                        (true, false) => return Ordering::Less,
+                        (false, true) => return Ordering::Greater,
+                        _ => {}
+                    }
+                }
+                LANGUAGE_SORTER.compare(&kinds[a.0], &kinds[b.0])
+            }
+            None => Ordering::Equal,
+        };
+        items.sort_by(|a, b| {
+            group(*a, *b)
+                .then_with(|| primary(a.1, b.1))
+                .then_with(|| secondary(a.1, b.1))
+        });
+        // Expanded folders in the tree view are followed by their sorted contents
+        if items.iter().any(|(_, item)| item.depth > 0) {
+            let mut children: HashMap<&Path, Vec<(usize, &Item)>> = HashMap::new();
+            let mut stack = Vec::with_capacity(items.len());
+            for (i, item) in items.drain(..) {
+                match item.path_opt().and_then(|path| path.parent()) {
+                    Some(parent) if item.depth > 0 => {
+                        children.entry(parent).or_default().push((i, item))
+                    }
+                    _ => stack.push((i, item)),
+                }
+            }
+            stack.reverse();
+            while let Some((i, item)) = stack.pop() {
+                items.push((i, item));
+                if item.expanded && (self.config.show_hidden || !item.hidden) {
+                    if let Some(contents) = item
+                        .path_opt()
+                        .and_then(|path| children.remove(path.as_path()))
+                    {
+                        stack.extend(contents.into_iter().rev());
+                    }
+                }
+            }
+            // Contents of hidden folders are not shown
+            for (_, item) in children.values().flatten() {
+                item.pos_opt.set(None);
+                item.rect_opt.set(None);
+            }
+        }
+        if self.location == Location::Trash {
+            items.retain(|(_, item)| {
+                let matches = self.trash_filter.matches(item);
+                if !matches {
+                    item.pos_opt.set(None);
+                    item.rect_opt.set(None);
+                }
+                matches
+            });
+            fn trash_entry(item: &Item) -> Option<&trash::TrashItem> {
+                match &item.metadata {
+                    ItemMetadata::Trash { entry, .. } => Some(entry),
+                    _ => None,
+                }
+            }
+            // Stable sort keeps the selected sort order inside of each group
+            match self.trash_filter.group {
+                TrashGroup::None => {}
+                TrashGroup::Date => items.sort_by_key(|(_, item)| {
+                    trash_entry(item).map(|entry| TrashDate::from_secs(entry.time_deleted))
+                }),
+                TrashGroup::Folder => items.sort_by(|a, b| {
+                    let a_parent = trash_entry(a.1).map(|entry| &entry.original_parent);
+                    let b_parent = trash_entry(b.1).map(|entry| &entry.original_parent);
+                    a_parent.cmp(&b_parent)
+                }),
             }
         }
         Some(items)
     }
 
+    /// Folders accept dropped files, archives create a new archive from them
+    fn is_drop_target(&self, item: &Item) -> bool {
+        item.metadata.is_dir()
+            || (matches!(self.mode, Mode::App | Mode::Desktop)
+                && item
+                    .path_opt()
+                    .is_some_and(|path| ArchiveType::from_path(path).is_some()))
+    }
+
     fn dnd_dest<'a>(
         &self,
         location: &Location,
@@ -3821,7 +8738,120 @@ impl Tab {
                 a
             });
         }
-        container.into()
+        container.into()
+    }
+
+    /// Slideshow of the selected items that can be shown in the gallery, or of all of them if
+    /// fewer than two are selected, starting from the focused item
+    fn slideshow(&self) -> Option<Slideshow> {
+        let indices = self.column_sort()?;
+        let selected = indices
+            .iter()
+            .filter(|(_, item)| item.selected && item.can_gallery())
+            .count();
+        let selected_only = selected > 1;
+        let mut paths: Vec<PathBuf> = indices
+            .iter()
+            .filter(|(_, item)| item.can_gallery() && (item.selected || !selected_only))
+            .filter_map(|(_, item)| item.path_opt().cloned())
+            .collect();
+        if paths.is_empty() {
+            return None;
+        }
+        let current_opt = self
+            .select_focus
+            .and_then(|index| self.items_opt.as_ref()?.get(index)?.path_opt());
+        let position = if self.config.slideshow.shuffle {
+            fastrand::shuffle(&mut paths);
+            // The focused item is shown first
+            if let Some(position) =
+                current_opt.and_then(|current| paths.iter().position(|path| path == current))
+            {
+                paths.swap(0, position);
+            }
+            0
+        } else {
+            current_opt
+                .and_then(|current| paths.iter().position(|path| path == current))
+                .unwrap_or(0)
+        };
+        Some(Slideshow {
+            paths,
+            position,
+            selected_only,
+        })
+    }
+
+    /// Close the gallery, stopping what plays in it
+    pub fn close_gallery(&mut self) {
+        self.gallery = false;
+        self.slideshow_opt = None;
+        self.update_gallery_media();
+    }
+
+    /// Play the focused item in the gallery if it is a video or an animation, and stop playing
+    /// at the gallery size when the gallery is closed
+    fn update_gallery_media(&mut self) {
+        let focused_opt = self
+            .select_focus
+            .filter(|_| self.gallery)
+            .and_then(|index| self.items_opt.as_ref()?.get(index))
+            .filter(|item| is_animated_mime(&item.mime))
+            .and_then(|item| Some((item.path_opt()?.clone(), item.mime.clone())));
+
+        // Videos are streamed, the frames of animations are loaded at once
+        #[cfg(feature = "video-thumbnails")]
+        let focused_opt = {
+            match focused_opt
+                .as_ref()
+                .filter(|(_, mime)| mime.type_() == mime::VIDEO)
+            {
+                Some((path, _)) => {
+                    if !self
+                        .gallery_video_opt
+                        .as_ref()
+                        .is_some_and(|video| video.player.path() == path)
+                    {
+                        let mut player = crate::video_player::VideoPlayer::new(path.clone());
+                        player.play();
+                        self.gallery_video_opt = Some(GalleryVideo {
+                            player,
+                            frame_opt: None,
+                            frame_generation_opt: None,
+                        });
+                    }
+                }
+                None => self.gallery_video_opt = None,
+            }
+            focused_opt.filter(|(_, mime)| mime.type_() != mime::VIDEO)
+        };
+        match focused_opt {
+            Some((path, mime)) => {
+                if !self
+                    .media_preview_opt
+                    .as_ref()
+                    .is_some_and(|media| media.path == path && media.size == GALLERY_MEDIA_SIZE)
+                {
+                    self.media_preview_opt = Some(MediaPreview {
+                        path,
+                        mime,
+                        size: GALLERY_MEDIA_SIZE,
+                        frames_opt: None,
+                        frame: 0,
+                        playing: true,
+                    });
+                }
+            }
+            None => {
+                if self
+                    .media_preview_opt
+                    .as_ref()
+                    .is_some_and(|media| media.size == GALLERY_MEDIA_SIZE)
+                {
+                    self.media_preview_opt = None;
+                }
+            }
+        }
     }
 
     pub fn gallery_view(&self) -> Element<Message> {
@@ -3835,72 +8865,131 @@ impl Tab {
         //TODO: display error messages when image not found?
         let mut name_opt = None;
         let mut element_opt: Option<Element<Message>> = None;
+        let mut controls_opt = None;
         if let Some(index) = self.select_focus {
             if let Some(items) = &self.items_opt {
                 if let Some(item) = items.get(index) {
                     name_opt = Some(widget::text::heading(&item.display_name));
-                    match item
-                        .thumbnail_opt
+                    // Videos and animations play once their frames are loaded
+                    let media_opt = self
+                        .media_preview_opt
                         .as_ref()
-                        .unwrap_or(&ItemThumbnail::NotImage)
+                        .filter(|media| Some(&media.path) == item.path_opt());
+                    if is_animated_mime(&item.mime) {
+                        controls_opt =
+                            Some(media_controls(media_opt.is_some_and(|media| media.playing)));
+                    }
+                    if let Some((handle, _)) =
+                        media_opt.and_then(|media| media.frames_opt.as_ref()?.get(media.frame))
                     {
-                        ItemThumbnail::NotImage => {}
-                        ItemThumbnail::Image(handle, _) => {
-                            if let Some(path) = item.path_opt() {
+                        element_opt = Some(
+                            widget::container(
+                                widget::icon::icon(handle.clone())
+                                    .content_fit(ContentFit::Contain)
+                                    .width(Length::Fill)
+                                    .height(Length::Fill),
+                            )
+                            .center(Length::Fill)
+                            .into(),
+                        );
+                    } else {
+                        match item
+                            .thumbnail_opt
+                            .as_ref()
+                            .unwrap_or(&ItemThumbnail::NotImage)
+                        {
+                            ItemThumbnail::NotImage => {}
+                            ItemThumbnail::Image(handle, _) => {
+                                // Videos cannot be loaded as images, so their thumbnail is shown
+                                if let Some(path) =
+                                    item.path_opt().filter(|_| item.mime.type_() != mime::VIDEO)
+                                {
+                                    element_opt = Some(
+                                        widget::container(
+                                            //TODO: use widget::image::viewer, when its zoom can be reset
+                                            widget::image(widget::image::Handle::from_path(path)),
+                                        )
+                                        .center(Length::Fill)
+                                        .into(),
+                                    );
+                                } else {
+                                    element_opt = Some(
+                                        widget::container(
+                                            //TODO: use widget::image::viewer, when its zoom can be reset
+                                            widget::image(handle.clone()),
+                                        )
+                                        .center(Length::Fill)
+                                        .into(),
+                                    );
+                                }
+                            }
+                            ItemThumbnail::Svg(handle) => {
                                 element_opt = Some(
-                                    widget::container(
-                                        //TODO: use widget::image::viewer, when its zoom can be reset
-                                        widget::image(widget::image::Handle::from_path(path)),
-                                    )
-                                    .center(Length::Fill)
-                                    .into(),
+                                    widget::svg(handle.clone())
+                                        .width(Length::Fill)
+                                        .height(Length::Fill)
+                                        .into(),
                                 );
-                            } else {
+                            }
+                            ItemThumbnail::Text(text) => {
                                 element_opt = Some(
                                     widget::container(
-                                        //TODO: use widget::image::viewer, when its zoom can be reset
-                                        widget::image(handle.clone()),
+                                        widget::text_editor(&text).padding(space_xxs).class(
+                                            cosmic::theme::iced::TextEditor::Custom(Box::new(
+                                                text_editor_class,
+                                            )),
+                                        ),
                                     )
                                     .center(Length::Fill)
                                     .into(),
-                                );
-                            }
-                        }
-                        ItemThumbnail::Svg(handle) => {
-                            element_opt = Some(
-                                widget::svg(handle.clone())
-                                    .width(Length::Fill)
-                                    .height(Length::Fill)
-                                    .into(),
-                            );
-                        }
-                        ItemThumbnail::Text(text) => {
-                            element_opt = Some(
-                                widget::container(
-                                    widget::text_editor(&text).padding(space_xxs).class(
-                                        cosmic::theme::iced::TextEditor::Custom(Box::new(
-                                            text_editor_class,
-                                        )),
-                                    ),
                                 )
-                                .center(Length::Fill)
-                                .into(),
-                            )
+                            }
                         }
                     }
                 }
             }
         }
 
-        let mut column = widget::column::with_capacity(2);
+        // Videos show the frame last streamed, or their thumbnail until the first one arrives
+        #[cfg(feature = "video-thumbnails")]
+        if let Some(video) = self.gallery_video_opt.as_ref().filter(|video| {
+            self.select_focus
+                .and_then(|index| self.items_opt.as_ref()?.get(index)?.path_opt())
+                .is_some_and(|path| path == video.player.path())
+        }) {
+            controls_opt = Some(gallery_video_controls(&video.player));
+            if let Some(handle) = &video.frame_opt {
+                element_opt = Some(
+                    widget::container(
+                        widget::image(handle.clone())
+                            .content_fit(ContentFit::Contain)
+                            .width(Length::Fill)
+                            .height(Length::Fill),
+                    )
+                    .center(Length::Fill)
+                    .into(),
+                );
+            }
+        }
+
+        let mut column = widget::column::with_capacity(3);
         column = column.push(widget::Space::with_height(Length::Fixed(space_m.into())));
         {
-            let mut row = widget::row::with_capacity(5).align_y(Alignment::Center);
+            let mut row = widget::row::with_capacity(6).align_y(Alignment::Center);
             row = row.push(widget::horizontal_space());
             if let Some(name) = name_opt {
                 row = row.push(name);
             }
             row = row.push(widget::horizontal_space());
+            row = row.push(
+                widget::button::icon(widget::icon::from_name(if self.slideshow_opt.is_some() {
+                    "media-playback-pause-symbolic"
+                } else {
+                    "media-playback-start-symbolic"
+                }))
+                .class(theme::Button::Standard)
+                .on_press(Message::SlideshowToggle),
+            );
             row = row.push(
                 widget::button::icon(widget::icon::from_name("window-close-symbolic"))
                     .class(theme::Button::Standard)
@@ -3939,6 +9028,13 @@ impl Tab {
             row = row.push(widget::Space::with_width(Length::Fixed(space_m.into())));
             column = column.push(row);
         }
+        if let Some(controls) = controls_opt {
+            column = column.push(
+                widget::container(controls)
+                    .center_x(Length::Fill)
+                    .padding(space_xs),
+            );
+        }
 
         widget::container(column)
             .width(Length::Fill)
@@ -3955,37 +9051,32 @@ impl Tab {
             .into()
     }
 
-    pub fn location_view(&self) -> Element<Message> {
-        //TODO: responsiveness is done in a hacky way, potentially move this to a custom widget?
-        fn text_width<'a>(
-            content: &'a str,
-            font: font::Font,
-            font_size: f32,
-            line_height: f32,
-        ) -> f32 {
-            let text: text::Text<&'a str, font::Font> = text::Text {
-                content,
-                bounds: Size::INFINITY,
-                size: font_size.into(),
-                line_height: text::LineHeight::Absolute(line_height.into()),
-                font,
-                horizontal_alignment: Horizontal::Left,
-                vertical_alignment: Vertical::Top,
-                shaping: text::Shaping::default(),
-                wrapping: text::Wrapping::None,
-            };
-            graphics::text::Paragraph::with_text(text)
-                .min_bounds()
-                .width
-        }
-        fn text_width_body(content: &str) -> f32 {
-            //TODO: should libcosmic set the font when using widget::text::body?
-            text_width(content, font::default(), 14.0, 20.0)
-        }
-        fn text_width_heading(content: &str) -> f32 {
-            text_width(content, font::semibold(), 14.0, 20.0)
+    /// Lock or network icon with a tooltip explaining why actions are limited in a folder of the
+    /// location, or in the whole location if there is no folder
+    fn capability_indicator<'a>(&self, path_opt: Option<&Path>) -> Option<Element<'a, Message>> {
+        let capabilities = &self.capabilities;
+        if path_opt.is_some_and(|path| !capabilities.applies_to(path)) {
+            return None;
         }
+        let (icon_name, tooltip) = if capabilities.read_only {
+            ("changes-prevent-symbolic", fl!("read-only-location"))
+        } else if capabilities.remote {
+            ("network-server-symbolic", fl!("remote-location"))
+        } else {
+            return None;
+        };
+        Some(
+            widget::tooltip(
+                widget::icon::from_name(icon_name).size(16),
+                widget::text::body(tooltip),
+                widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        )
+    }
 
+    pub fn location_view(&self) -> Element<Message> {
+        //TODO: responsiveness is done in a hacky way, potentially move this to a custom widget?
         let cosmic_theme::Spacing {
             space_xxxs,
             space_xxs,
@@ -4024,13 +9115,16 @@ impl Tab {
         row = row.push(widget::Space::with_width(Length::Fixed(space_s.into())));
         w += space_s as f32;
 
-        //TODO: allow resizing?
         let name_width = 300.0;
-        let modified_width = 200.0;
-        let size_width = 100.0;
-        let condensed = size.width < (name_width + modified_width + size_width);
+        let columns = self.columns();
+        let columns_width: f32 = columns
+            .iter()
+            .map(|heading| self.column_width(*heading))
+            .sum();
+        let condensed = size.width < (name_width + columns_width);
 
         let (sort_name, sort_direction, _) = self.sort_options();
+        let sort_secondary_opt = self.sort_secondary();
         let heading_item = |name, width, msg| {
             let mut row = widget::row::with_capacity(2)
                 .align_y(Alignment::Center)
@@ -4046,32 +9140,62 @@ impl Tab {
                 }
                 _ => {}
             }
-            //TODO: make it possible to resize with the mouse
+            match sort_secondary_opt {
+                Some((heading, direction)) if heading == msg => {
+                    row = row.push(
+                        widget::icon::from_name(if direction {
+                            "pan-down-symbolic"
+                        } else {
+                            "pan-up-symbolic"
+                        })
+                        .size(12),
+                    );
+                }
+                _ => {}
+            }
             mouse_area::MouseArea::new(row)
                 .on_press(move |_point_opt| Message::ToggleSort(msg))
                 .into()
         };
+        // Dragging the divider left of a column resizes it, double clicking fits it to its contents
+        let column_divider = |column| {
+            mouse_area::MouseArea::new(
+                widget::container(vertical_rule(1))
+                    .width(Length::Fixed(space_xxs.into()))
+                    .height(Length::Fill)
+                    .align_x(Alignment::Center),
+            )
+            .on_press(move |_| Message::ColumnResizeStart(column))
+            .on_double_click(move |_| Message::ColumnAutoFit(column))
+            .into()
+        };
+        let divider_width = f32::from(space_xxs);
 
-        let heading_row = widget::row::with_children(vec![
-            heading_item(fl!("name"), Length::Fill, HeadingOptions::Name),
-            if self.location == Location::Trash {
-                heading_item(
-                    fl!("trashed-on"),
-                    Length::Fixed(modified_width),
-                    HeadingOptions::TrashedOn,
-                )
-            } else {
-                heading_item(
-                    fl!("modified"),
-                    Length::Fixed(modified_width),
-                    HeadingOptions::Modified,
-                )
-            },
-            heading_item(fl!("size"), Length::Fixed(size_width), HeadingOptions::Size),
-        ])
-        .align_y(Alignment::Center)
-        .height(Length::Fixed((space_m + 4).into()))
-        .padding([0, space_xxs]);
+        let mut heading_children: Vec<Element<_>> = Vec::with_capacity(1 + 2 * columns.len());
+        heading_children.push(heading_item(
+            HeadingOptions::Name.to_string(),
+            Length::Fill,
+            HeadingOptions::Name,
+        ));
+        for heading in columns.iter().copied() {
+            heading_children.push(match heading.resize_column() {
+                Some(column) => column_divider(column),
+                None => Space::with_width(Length::Fixed(divider_width)).into(),
+            });
+            heading_children.push(heading_item(
+                heading.to_string(),
+                Length::Fixed(self.column_width(heading) - divider_width),
+                heading,
+            ));
+        }
+        // Right clicking the headings opens the column chooser
+        let heading_row = crate::mouse_area::MouseArea::new(
+            widget::row::with_children(heading_children)
+                .align_y(Alignment::Center)
+                .height(Length::Fixed((space_m + 4).into()))
+                .padding([0, space_xxs]),
+        )
+        .on_right_press_no_capture(|_point_opt| Message::ColumnMenu(true));
 
         let accent_rule =
             horizontal_rule(1).class(theme::Rule::Custom(Box::new(|theme| rule::Style {
@@ -4200,6 +9324,10 @@ impl Tab {
                         row = row.push(name_text);
                         w += name_width;
                     }
+                    if let Some(indicator) = self.capability_indicator(Some(ancestor)) {
+                        row = row.push(indicator);
+                        w += 16.0 + space_xxxs as f32;
+                    }
 
                     let location = self.location.with_path(ancestor.to_path_buf());
                     let mut mouse_area = crate::mouse_area::MouseArea::new(
@@ -4256,9 +9384,74 @@ impl Tab {
                         .into(),
                 );
             }
+            Location::Computer => {
+                children.push(
+                    widget::button::custom(widget::text::heading(fl!("computer")))
+                        .padding(space_xxxs)
+                        .on_press(Message::Location(Location::Computer))
+                        .class(theme::Button::Text)
+                        .into(),
+                );
+            }
+            Location::Dates(path, folder_opt) => {
+                let (name, _) = folder_name(path);
+                children.push(
+                    widget::button::custom(widget::text::heading(fl!("by-date", name = name)))
+                        .padding(space_xxxs)
+                        .on_press(Message::Location(Location::Dates(path.clone(), None)))
+                        .class(theme::Button::Text)
+                        .into(),
+                );
+                let mut folders: Vec<_> =
+                    std::iter::successors(*folder_opt, |folder| folder.parent()).collect();
+                folders.reverse();
+                for folder in folders {
+                    children.push(
+                        widget::icon::from_name("go-next-symbolic")
+                            .size(16)
+                            .icon()
+                            .into(),
+                    );
+                    children.push(
+                        widget::button::custom(widget::text::heading(folder.name()))
+                            .padding(space_xxxs)
+                            .on_press(Message::Location(Location::Dates(
+                                path.clone(),
+                                Some(folder),
+                            )))
+                            .class(theme::Button::Text)
+                            .into(),
+                    );
+                }
+            }
+            Location::Favorites => {
+                children.push(
+                    widget::button::custom(widget::text::heading(fl!("starred")))
+                        .padding(space_xxxs)
+                        .on_press(Message::Location(Location::Favorites))
+                        .class(theme::Button::Text)
+                        .into(),
+                );
+            }
+            Location::Tag(tag) => {
+                children.push(
+                    widget::button::custom(widget::text::heading(tag.name()))
+                        .padding(space_xxxs)
+                        .on_press(Message::Location(Location::Tag(*tag)))
+                        .class(theme::Button::Text)
+                        .into(),
+                );
+            }
             Location::Network(uri, display_name) => {
+                let mut row = widget::row::with_capacity(2)
+                    .align_y(Alignment::Center)
+                    .spacing(space_xxxs)
+                    .push(widget::text::heading(display_name));
+                if let Some(indicator) = self.capability_indicator(None) {
+                    row = row.push(indicator);
+                }
                 children.push(
-                    widget::button::custom(widget::text::heading(display_name))
+                    widget::button::custom(row)
                         .padding(space_xxxs)
                         .on_press(Message::Location(Location::Network(
                             uri.clone(),
@@ -4291,7 +9484,14 @@ impl Tab {
             self.location_context_menu_index,
         ) {
             popover = popover
-                .popup(menu::location_context_menu(index))
+                .popup(menu::location_context_menu(
+                    index,
+                    self.location_context_menu_mount.as_ref(),
+                ))
+                .position(widget::popover::Position::Point(point))
+        } else if let (Some(point), true) = (self.location_context_menu_point, self.column_menu) {
+            popover = popover
+                .popup(menu::column_menu(&columns))
                 .position(widget::popover::Position::Point(point))
         }
 
@@ -4310,7 +9510,9 @@ impl Tab {
                         .into(),
                     widget::text::body(if has_hidden {
                         fl!("empty-folder-hidden")
-                    } else if matches!(self.location, Location::Search(..)) {
+                    } else if matches!(self.location, Location::Search(..))
+                        || (self.location == Location::Trash && self.trash_filter.is_active())
+                    {
                         fl!("no-results")
                     } else {
                         fl!("empty-folder")
@@ -4325,6 +9527,7 @@ impl Tab {
         .center(Length::Fill)
         .into()]))
         .on_press(|_| Message::Click(None))
+        .on_double_click(|_| Message::DoubleClick(None))
         .into()
     }
 
@@ -4366,12 +9569,22 @@ impl Tab {
         let (cols, column_spacing) = {
             let width_m1 = width.saturating_sub(item_width);
             let cols_m1 = width_m1 / (item_width + grid_spacing as usize);
-            let cols = cols_m1 + 1;
-            let spacing = width_m1
-                .checked_div(cols_m1)
-                .unwrap_or(0)
-                .saturating_sub(item_width);
-            (cols, spacing as u16)
+            match self.config.grid_columns.map(|cols| usize::from(cols.get())) {
+                // Fewer columns than fit keep their spacing instead of spreading over the width
+                Some(cols)
+                    if cols <= cols_m1 && !matches!(self.location, Location::Desktop(..)) =>
+                {
+                    (cols, grid_spacing)
+                }
+                _ => {
+                    let cols = cols_m1 + 1;
+                    let spacing = width_m1
+                        .checked_div(cols_m1)
+                        .unwrap_or(0)
+                        .saturating_sub(item_width);
+                    (cols, spacing as u16)
+                }
+            }
         };
 
         let rows = {
@@ -4399,6 +9612,10 @@ impl Tab {
             let mut page_row = 0;
             let mut hidden = 0;
             let mut grid_elements = Vec::new();
+            // Rows that start a group of items of the same kind, and the heading above them
+            let group_by_kind = self.group_by_kind();
+            let mut group_rows: Vec<(usize, String)> = Vec::new();
+            let mut group_offset = 0;
             for &(i, item) in items.iter() {
                 if !show_hidden && item.hidden {
                     item.pos_opt.set(None);
@@ -4406,33 +9623,96 @@ impl Tab {
                     hidden += 1;
                     continue;
                 }
+                if group_by_kind {
+                    let kind = kind_text(item);
+                    if group_rows.last().map(|(_, last)| last) != Some(&kind) {
+                        if col > 0 {
+                            col = 0;
+                            row += 1;
+                        }
+                        // Each group is a separate grid, with padding around it
+                        group_offset += if group_rows.is_empty() {
+                            GROUP_HEADING_HEIGHT
+                        } else {
+                            GROUP_HEADING_HEIGHT + 2 * usize::from(space_xxs)
+                                - usize::from(grid_spacing)
+                        };
+                        group_rows.push((row, kind));
+                    }
+                }
                 item.pos_opt.set(Some((row, col)));
                 item.rect_opt.set(Some(Rectangle::new(
                     Point::new(
                         (col * (item_width + column_spacing as usize) + space_m as usize) as f32,
-                        (row * (item_height + grid_spacing as usize)) as f32,
+                        (row * (item_height + grid_spacing as usize) + group_offset) as f32,
                     ),
                     Size::new(item_width as f32, item_height as f32),
                 )));
 
                 //TODO: one focus group per grid item (needs custom widget)
-                let buttons: Vec<Element<Message>> = vec![
-                    widget::button::custom(
-                        widget::icon::icon(item.icon_handle_grid.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_sizes.grid())
-                            .width(Length::Shrink),
+                let icon_button: Element<Message> = widget::button::custom(emblem_icon(
+                    widget::icon::icon(
+                        self.animation_opt
+                            .as_ref()
+                            .filter(|animation| animation.index == i)
+                            .and_then(|animation| {
+                                animation.frames_opt.as_ref()?.get(animation.frame)
+                            })
+                            .map_or_else(
+                                || item.icon_handle_grid.clone(),
+                                |(handle, _)| handle.clone(),
+                            ),
                     )
-                    .padding(space_xxxs)
-                    .class(button_style(
-                        item.selected,
-                        item.highlighted,
-                        item.cut,
-                        false,
-                        false,
-                        false,
-                    ))
-                    .into(),
+                    .content_fit(ContentFit::Contain)
+                    .size(icon_sizes.grid())
+                    .width(Length::Shrink),
+                    &item.emblems,
+                    (icon_sizes.grid() / 4).max(12),
+                ))
+                .padding(space_xxxs)
+                .class(button_style(
+                    item.selected,
+                    item.highlighted,
+                    item.dimmed(),
+                    false,
+                    false,
+                    false,
+                ))
+                .into();
+                let mut icon_layers = vec![icon_button];
+                // Show the extension on the top left corner of the icon
+                if let Some(extension) = item
+                    .extension_badge_text()
+                    .filter(|_| self.config.extension_badges)
+                {
+                    icon_layers.push(
+                        widget::container(extension_badge(extension))
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .padding(space_xxxs)
+                            .align_x(Horizontal::Left)
+                            .align_y(Vertical::Top)
+                            .into(),
+                    );
+                }
+                // Show tags on the bottom right corner of the icon
+                if !item.tags.is_empty() || self.is_starred(item) {
+                    icon_layers.push(
+                        widget::container(tag_strip(&item.tags, self.is_starred(item), i))
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                            .padding(space_xxxs)
+                            .align_x(Horizontal::Right)
+                            .align_y(Vertical::Bottom)
+                            .into(),
+                    );
+                }
+                let buttons: Vec<Element<Message>> = vec![
+                    if icon_layers.len() == 1 {
+                        icon_layers.remove(0)
+                    } else {
+                        cosmic::iced::widget::Stack::with_children(icon_layers).into()
+                    },
                     widget::tooltip(
                         widget::button::custom(widget::text::body(&item.display_name))
                             .id(item.button_id.clone())
@@ -4440,7 +9720,7 @@ impl Tab {
                             .class(button_style(
                                 item.selected,
                                 item.highlighted,
-                                item.cut,
+                                item.dimmed(),
                                 true,
                                 true,
                                 matches!(self.mode, Mode::Desktop),
@@ -4468,7 +9748,7 @@ impl Tab {
                 }
 
                 let column: Element<Message> =
-                    if item.metadata.is_dir() && item.location_opt.is_some() {
+                    if self.is_drop_target(item) && item.location_opt.is_some() {
                         self.dnd_dest(&item.location_opt.clone().unwrap(), column)
                     } else {
                         column.into()
@@ -4481,13 +9761,22 @@ impl Tab {
                     drag_e_i = drag_e_i.max(col);
                     drag_s_i = drag_s_i.max(row);
                 }
-                let mouse_area = crate::mouse_area::MouseArea::new(column)
+                let mut mouse_area = crate::mouse_area::MouseArea::new(column)
                     .on_press(move |_| Message::Click(Some(i)))
                     .on_double_click(move |_| Message::DoubleClick(Some(i)))
                     .on_release(move |_| Message::ClickRelease(Some(i)))
                     .on_middle_press(move |_| Message::MiddleClick(i))
                     .on_enter(move || Message::HighlightActivate(i))
                     .on_exit(move || Message::HighlightDeactivate(i));
+                if self
+                    .animation_opt
+                    .as_ref()
+                    .is_some_and(|animation| animation.index == i && animation.scrub_opt.is_some())
+                {
+                    mouse_area = mouse_area.on_move(move |position, size| {
+                        Message::HoverScrub(i, position.x / size.width)
+                    });
+                }
 
                 //TODO: error if the row or col is already set?
                 while grid_elements.len() <= row {
@@ -4516,17 +9805,34 @@ impl Tab {
                 }
             }
 
-            for row_elements in grid_elements {
+            if count == 0 {
+                return (None, self.empty_view(hidden > 0), false);
+            }
+
+            let mut group_rows = group_rows.into_iter().peekable();
+            for (row, row_elements) in grid_elements.into_iter().enumerate() {
+                if let Some((_, kind)) = group_rows.next_if(|(group_row, _)| *group_row == row) {
+                    if row > 0 {
+                        children.push(grid.into());
+                        grid = widget::grid()
+                            .column_spacing(column_spacing)
+                            .row_spacing(grid_spacing)
+                            .padding(space_xxs.into());
+                    }
+                    children.push(
+                        widget::container(widget::text::heading(kind))
+                            .align_y(Vertical::Bottom)
+                            .height(Length::Fixed(GROUP_HEADING_HEIGHT as f32))
+                            .padding([0, space_xxs])
+                            .into(),
+                    );
+                }
                 for element in row_elements {
                     grid = grid.push(element);
                 }
                 grid = grid.insert_row();
             }
 
-            if count == 0 {
-                return (None, self.empty_view(hidden > 0), false);
-            }
-
             children.push(grid.into());
 
             //TODO: HACK If we don't reach the bottom of the view, go ahead and add a spacer to do that
@@ -4585,7 +9891,7 @@ impl Tab {
                                 .class(button_style(
                                     item.selected,
                                     item.highlighted,
-                                    item.cut,
+                                    item.dimmed(),
                                     false,
                                     false,
                                     false,
@@ -4599,7 +9905,7 @@ impl Tab {
                                 .class(button_style(
                                     item.selected,
                                     item.highlighted,
-                                    item.cut,
+                                    item.dimmed(),
                                     true,
                                     true,
                                     false,
@@ -4628,6 +9934,7 @@ impl Tab {
             }),
             mouse_area::MouseArea::new(widget::column::with_children(children).width(Length::Fill))
                 .on_press(|_| Message::Click(None))
+                .on_double_click(|_| Message::DoubleClick(None))
                 .on_drag(Message::Drag)
                 .on_drag_end(|_| Message::DragEnd(None))
                 .show_drag_rect(self.mode.multiple())
@@ -4652,13 +9959,29 @@ impl Tab {
         } = self.config;
 
         let size = self.size_opt.get().unwrap_or_else(|| Size::new(0.0, 0.0));
-        //TODO: allow resizing?
         let name_width = 300.0;
-        let modified_width = 200.0;
-        let size_width = 100.0;
-        let condensed = size.width < (name_width + modified_width + size_width);
-        let is_search = matches!(self.location, Location::Search(..));
-        let icon_size = if condensed || is_search {
+        let columns = self.columns();
+        let columns_width: f32 = columns
+            .iter()
+            .map(|heading| self.column_width(*heading))
+            .sum();
+        let condensed = size.width < (name_width + columns_width);
+        // Texts of the chosen columns, right of the name
+        let column_cells = |item: &Item| -> Vec<Element<'static, Message>> {
+            columns
+                .iter()
+                .map(|heading| {
+                    widget::text::body(self.column_text(item, *heading))
+                        .width(Length::Fixed(self.column_width(*heading)))
+                        .into()
+                })
+                .collect()
+        };
+        let show_parent = matches!(
+            self.location,
+            Location::Search(..) | Location::Recents | Location::Tag(..) | Location::Favorites
+        );
+        let icon_size = if condensed || show_parent {
             icon_sizes.list_condensed()
         } else {
             icon_sizes.list()
@@ -4670,11 +9993,37 @@ impl Tab {
 
         let rule_padding = theme::active().cosmic().corner_radii.radius_xs[0] as u16;
 
+        // Folders can be expanded with a disclosure button before the icon
+        let tree = self.config.tree_view && matches!(self.location, Location::Path(_));
+        let disclosure = |item: &Item, i: usize| -> Element<'static, Message> {
+            let indent = Space::with_width(Length::Fixed(
+                f32::from(item.depth) * f32::from(TREE_INDENT),
+            ));
+            let toggle: Element<_> = if item.metadata.is_dir() {
+                widget::button::icon(widget::icon::from_name(if item.expanded {
+                    "pan-down-symbolic"
+                } else {
+                    "pan-end-symbolic"
+                }))
+                .icon_size(TREE_INDENT)
+                .padding(0)
+                .on_press(Message::ExpandFolder(i))
+                .into()
+            } else {
+                Space::with_width(Length::Fixed(f32::from(TREE_INDENT))).into()
+            };
+            widget::row::with_children(vec![indent.into(), toggle])
+                .align_y(Alignment::Center)
+                .into()
+        };
+
         let items = self.column_sort();
+        let group_sizes = self.trash_group_sizes();
         let mut drag_items = Vec::new();
         if let Some(items) = items {
             let mut count = 0;
             let mut hidden = 0;
+            let mut group_opt = None;
             for (i, item) in items {
                 if item.hidden && !show_hidden {
                     item.pos_opt.set(None);
@@ -4682,6 +10031,27 @@ impl Tab {
                     hidden += 1;
                     continue;
                 }
+                if let Some(group) = self.trash_filter.group_name(item) {
+                    if group_opt.as_ref() != Some(&group) {
+                        let heading = match group_sizes.get(&group) {
+                            Some(size) => fl!(
+                                "trash-group-size",
+                                group = group.clone(),
+                                size = format_size(*size)
+                            ),
+                            None => group.clone(),
+                        };
+                        children.push(
+                            widget::container(widget::text::heading(heading))
+                                .align_y(Vertical::Bottom)
+                                .height(Length::Fixed(row_height as f32))
+                                .padding([0, space_xxs])
+                                .into(),
+                        );
+                        y += row_height;
+                        group_opt = Some(group);
+                    }
+                }
                 item.pos_opt.set(Some((count, 0)));
                 item.rect_opt.set(Some(Rectangle::new(
                     Point::new(space_m as f32, y as f32),
@@ -4697,127 +10067,92 @@ impl Tab {
                     y += 1;
                 }
 
-                let modified_text = match &item.metadata {
-                    ItemMetadata::Path { metadata, .. } => match metadata.modified() {
-                        Ok(time) => self.format_time(time).to_string(),
-                        Err(_) => String::new(),
-                    },
-                    ItemMetadata::Trash { entry, .. } => FormatTime::from_secs(
-                        entry.time_deleted,
-                        &self.date_time_formatter,
-                        &self.time_formatter,
-                    )
-                    .map(|t| t.to_string())
-                    .unwrap_or_default(),
-                    _ => String::new(),
-                };
-
-                let size_text = match &item.metadata {
-                    ItemMetadata::Path {
-                        metadata,
-                        children_opt,
-                    } => {
-                        if metadata.is_dir() {
-                            //TODO: translate
-                            if let Some(children) = children_opt {
-                                if *children == 1 {
-                                    format!("{} item", children)
-                                } else {
-                                    format!("{} items", children)
-                                }
-                            } else {
-                                String::new()
-                            }
-                        } else {
-                            format_size(metadata.len())
-                        }
-                    }
-                    ItemMetadata::Trash { metadata, .. } => match metadata.size {
-                        trash::TrashItemSize::Entries(entries) => {
-                            //TODO: translate
-                            if entries == 1 {
-                                format!("{} item", entries)
-                            } else {
-                                format!("{} items", entries)
-                            }
-                        }
-                        trash::TrashItemSize::Bytes(bytes) => format_size(bytes),
-                    },
-                    ItemMetadata::SimpleDir { entries } => {
-                        //TODO: translate
-                        if *entries == 1 {
-                            format!("{} item", entries)
-                        } else {
-                            format!("{} items", entries)
-                        }
-                    }
-                    ItemMetadata::SimpleFile { size } => format_size(*size),
-                };
+                let modified_text = self.modified_text(item);
+                let size_text = self.column_text(item, HeadingOptions::Size);
 
                 let row = if condensed {
                     widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list_condensed.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
+                        emblem_icon(
+                            widget::icon::icon(item.icon_handle_list_condensed.clone())
+                                .content_fit(ContentFit::Contain)
+                                .size(icon_size),
+                            &item.emblems,
+                            icon_size / 2,
+                        ),
                         widget::column::with_children(vec![
                             widget::text::body(item.display_name.clone()).into(),
                             //TODO: translate?
                             widget::text::caption(format!("{} - {}", modified_text, size_text))
                                 .into(),
                         ])
+                        .width(Length::Fill)
                         .into(),
+                        tag_strip(&item.tags, self.is_starred(item), i),
                     ])
                     .height(Length::Fixed(row_height as f32))
                     .align_y(Alignment::Center)
                     .spacing(space_xxs)
-                } else if is_search {
+                } else if show_parent {
                     widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list_condensed.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
+                        emblem_icon(
+                            widget::icon::icon(item.icon_handle_list_condensed.clone())
+                                .content_fit(ContentFit::Contain)
+                                .size(icon_size),
+                            &item.emblems,
+                            icon_size / 2,
+                        ),
                         widget::column::with_children(vec![
                             widget::text::body(item.display_name.clone()).into(),
-                            widget::text::caption(match item.path_opt() {
-                                Some(path) => path.display().to_string(),
-                                None => String::new(),
-                            })
-                            .into(),
+                            match item
+                                .path_opt()
+                                .and_then(|path| Some((path, path.parent()?)))
+                            {
+                                Some((path, parent)) => {
+                                    widget::button::link(parent.display().to_string())
+                                        .on_press(Message::LocationSelect(path.clone()))
+                                        .padding(0)
+                                        .into()
+                                }
+                                None => widget::text::caption(String::new()).into(),
+                            },
                         ])
                         .width(Length::Fill)
                         .into(),
-                        widget::text::body(modified_text.clone())
-                            .width(Length::Fixed(modified_width))
-                            .into(),
-                        widget::text::body(size_text.clone())
-                            .width(Length::Fixed(size_width))
-                            .into(),
+                        tag_strip(&item.tags, self.is_starred(item), i),
                     ])
+                    .extend(column_cells(item))
                     .height(Length::Fixed(row_height as f32))
                     .align_y(Alignment::Center)
                     .spacing(space_xxs)
                 } else {
                     widget::row::with_children(vec![
-                        widget::icon::icon(item.icon_handle_list.clone())
-                            .content_fit(ContentFit::Contain)
-                            .size(icon_size)
-                            .into(),
+                        emblem_icon(
+                            widget::icon::icon(item.icon_handle_list.clone())
+                                .content_fit(ContentFit::Contain)
+                                .size(icon_size),
+                            &item.emblems,
+                            icon_size / 2,
+                        ),
                         widget::text::body(item.display_name.clone())
-                            .width(Length::Fill)
-                            .into(),
-                        widget::text::body(modified_text.clone())
-                            .width(Length::Fixed(modified_width))
-                            .into(),
-                        widget::text::body(size_text.clone())
-                            .width(Length::Fixed(size_width))
+                            .width(Length::Fill)
                             .into(),
+                        tag_strip(&item.tags, self.is_starred(item), i),
                     ])
+                    .extend(column_cells(item))
                     .height(Length::Fixed(row_height as f32))
                     .align_y(Alignment::Center)
                     .spacing(space_xxs)
                 };
 
+                let row = if tree && !show_parent {
+                    widget::row::with_children(vec![disclosure(item, i), row.into()])
+                        .height(Length::Fixed(row_height as f32))
+                        .align_y(Alignment::Center)
+                        .spacing(space_xxs)
+                } else {
+                    row
+                };
+
                 let button = |row| {
                     let mouse_area = crate::mouse_area::MouseArea::new(
                         widget::button::custom(row)
@@ -4827,7 +10162,7 @@ impl Tab {
                             .class(button_style(
                                 item.selected,
                                 item.highlighted,
-                                item.cut,
+                                item.dimmed(),
                                 true,
                                 true,
                                 false,
@@ -4851,7 +10186,7 @@ impl Tab {
 
                 let button_row = button(row.into());
                 let button_row: Element<_> =
-                    if item.metadata.is_dir() && item.location_opt.is_some() {
+                    if self.is_drop_target(item) && item.location_opt.is_some() {
                         self.dnd_dest(item.location_opt.as_ref().unwrap(), button_row)
                     } else {
                         button_row.into()
@@ -4877,7 +10212,7 @@ impl Tab {
                         .align_y(Alignment::Center)
                         .spacing(space_xxs)
                         .into()
-                    } else if is_search {
+                    } else if show_parent {
                         widget::row::with_children(vec![
                             widget::icon::icon(item.icon_handle_list_condensed.clone())
                                 .content_fit(ContentFit::Contain)
@@ -4893,13 +10228,8 @@ impl Tab {
                             ])
                             .width(Length::Fill)
                             .into(),
-                            widget::text::body(modified_text.clone())
-                                .width(Length::Fixed(modified_width))
-                                .into(),
-                            widget::text::body(size_text.clone())
-                                .width(Length::Fixed(size_width))
-                                .into(),
                         ])
+                        .extend(column_cells(item))
                         .align_y(Alignment::Center)
                         .spacing(space_xxs)
                         .into()
@@ -4912,13 +10242,8 @@ impl Tab {
                             widget::text::body(item.display_name.clone())
                                 .width(Length::Fill)
                                 .into(),
-                            widget::text(modified_text)
-                                .width(Length::Fixed(modified_width))
-                                .into(),
-                            widget::text::body(size_text)
-                                .width(Length::Fixed(size_width))
-                                .into(),
                         ])
+                        .extend(column_cells(item))
                         .align_y(Alignment::Center)
                         .spacing(space_xxs)
                         .into()
@@ -4945,7 +10270,7 @@ impl Tab {
         }
         //TODO: HACK If we don't reach the bottom of the view, go ahead and add a spacer to do that
         {
-            let top_deduct = (if condensed || is_search { 6 } else { 9 }) * space_xxs;
+            let top_deduct = (if condensed || show_parent { 6 } else { 9 }) * space_xxs;
 
             self.item_view_size_opt
                 .set(self.size_opt.get().map(|s| Size {
@@ -4970,6 +10295,7 @@ impl Tab {
             )
             .with_id(Id::new("list-view"))
             .on_press(|_| Message::Click(None))
+            .on_double_click(|_| Message::DoubleClick(None))
             .on_drag(Message::Drag)
             .on_drag_end(|_| Message::DragEnd(None))
             .show_drag_rect(self.mode.multiple())
@@ -4990,6 +10316,7 @@ impl Tab {
         let cosmic_theme::Spacing {
             space_xxs,
             space_xs,
+            space_m,
             ..
         } = theme::active().cosmic().spacing;
 
@@ -5035,6 +10362,7 @@ impl Tab {
         let tab_location = self.location.clone();
         let mut mouse_area = mouse_area::MouseArea::new(item_view)
             .on_press(move |_point_opt| Message::Click(None))
+            .on_double_click(|_| Message::DoubleClick(None))
             .on_release(|_| Message::ClickRelease(None))
             //TODO: better way to keep focused item in view
             .on_resize(|_, _| Message::ScrollToFocus)
@@ -5057,10 +10385,26 @@ impl Tab {
                 .position(widget::popover::Position::Point(point));
         }
 
-        let mut tab_column = widget::column::with_capacity(3);
+        let mut tab_column = widget::column::with_capacity(4);
         if let Some(location_view) = location_view_opt {
             tab_column = tab_column.push(location_view);
         }
+        if self.offline {
+            tab_column = tab_column.push(
+                widget::layer_container(
+                    widget::row::with_children(vec![
+                        widget::icon::from_name("network-offline-symbolic")
+                            .size(16)
+                            .into(),
+                        widget::text::body(fl!("offline-listing")).into(),
+                    ])
+                    .align_y(Alignment::Center)
+                    .spacing(space_xxs),
+                )
+                .padding([space_xxs, space_xs])
+                .layer(cosmic_theme::Layer::Primary),
+            );
+        }
         if can_scroll {
             tab_column = tab_column.push(
                 widget::scrollable(popover)
@@ -5076,19 +10420,121 @@ impl Tab {
             Location::Trash => {
                 if let Some(items) = self.items_opt() {
                     if !items.is_empty() {
+                        let date_filter = self.trash_filter.clone();
+                        let folder_filter = self.trash_filter.clone();
+                        let group_filter = self.trash_filter.clone();
+                        let folders = self.trash_folders.clone();
+                        let folder_index = match &self.trash_filter.folder_opt {
+                            Some(folder) => self
+                                .trash_folders
+                                .iter()
+                                .position(|x| x == folder)
+                                .map(|i| i + 1),
+                            None => Some(0),
+                        };
                         tab_column = tab_column.push(
-                            widget::layer_container(widget::row::with_children(vec![
-                                widget::horizontal_space().into(),
-                                widget::button::standard(fl!("empty-trash"))
-                                    .on_press(Message::EmptyTrash)
+                            widget::layer_container(
+                                widget::row::with_children(vec![
+                                    widget::dropdown(
+                                        &TRASH_DATE_NAMES,
+                                        TRASH_DATE_FILTERS
+                                            .iter()
+                                            .position(|x| *x == self.trash_filter.date),
+                                        move |index| {
+                                            Message::TrashFilter(TrashFilter {
+                                                date: TRASH_DATE_FILTERS[index],
+                                                ..date_filter.clone()
+                                            })
+                                        },
+                                    )
+                                    .into(),
+                                    widget::dropdown(
+                                        &self.trash_folder_names,
+                                        folder_index,
+                                        move |index| {
+                                            Message::TrashFilter(TrashFilter {
+                                                folder_opt: index
+                                                    .checked_sub(1)
+                                                    .and_then(|i| folders.get(i).cloned()),
+                                                ..folder_filter.clone()
+                                            })
+                                        },
+                                    )
+                                    .into(),
+                                    widget::dropdown(
+                                        &TRASH_GROUP_NAMES,
+                                        TRASH_GROUPS
+                                            .iter()
+                                            .position(|x| *x == self.trash_filter.group),
+                                        move |index| {
+                                            Message::TrashFilter(TrashFilter {
+                                                group: TRASH_GROUPS[index],
+                                                ..group_filter.clone()
+                                            })
+                                        },
+                                    )
+                                    .into(),
+                                    widget::horizontal_space().into(),
+                                    widget::text::body(self.trash_sizes_opt.as_ref().map_or_else(
+                                        String::new,
+                                        |sizes| {
+                                            fl!(
+                                                "trash-size",
+                                                size = format_size(sizes.values().sum())
+                                            )
+                                        },
+                                    ))
                                     .into(),
-                            ]))
+                                    widget::button::standard(fl!("empty-trash"))
+                                        .on_press(Message::EmptyTrash)
+                                        .into(),
+                                ])
+                                .align_y(Alignment::Center)
+                                .spacing(space_xxs),
+                            )
                             .padding([space_xxs, space_xs])
                             .layer(cosmic_theme::Layer::Primary),
                         );
                     }
                 }
             }
+            Location::Search(.., start) => {
+                let count = self.items_opt().map_or(0, |items| items.len());
+                let text = match self.search_finished_opt {
+                    Some((elapsed, true)) => {
+                        fl!("search-stopped", count = count, seconds = elapsed.as_secs())
+                    }
+                    Some((elapsed, false)) => {
+                        fl!(
+                            "search-finished",
+                            count = count,
+                            seconds = elapsed.as_secs()
+                        )
+                    }
+                    None => fl!(
+                        "search-running",
+                        count = count,
+                        seconds = start.elapsed().as_secs()
+                    ),
+                };
+                let mut row = widget::row::with_children(vec![
+                    widget::text::body(text).into(),
+                    widget::horizontal_space().into(),
+                ])
+                .align_y(Alignment::Center)
+                .spacing(space_xxs);
+                if self.search_context.is_some() {
+                    row = row.push(
+                        widget::button::destructive(fl!("search-stop"))
+                            .on_press(Message::SearchStop),
+                    );
+                }
+                tab_column = tab_column.push(
+                    widget::layer_container(row)
+                        .padding([space_xxs, space_xs])
+                        .layer(cosmic_theme::Layer::Primary),
+                );
+            }
             Location::Network(uri, _display_name) if uri == "network:///" => {
                 tab_column = tab_column.push(
                     widget::layer_container(widget::row::with_children(vec![
@@ -5103,7 +10549,34 @@ impl Tab {
             }
             _ => {}
         }
-        let mut tab_view = widget::container(tab_column)
+        let tab_content: Element<_> = if self.zoom_indicator_opt.is_some() {
+            let zoom_indicator = widget::layer_container(
+                widget::row::with_children(vec![
+                    widget::text::body(fl!("zoom-level", zoom = self.zoom())).into(),
+                    widget::button::text(fl!("zoom-reset"))
+                        .on_press(Message::ZoomDefault)
+                        .into(),
+                ])
+                .align_y(Alignment::Center)
+                .spacing(space_xs),
+            )
+            .padding([space_xxs, space_xs])
+            .layer(cosmic_theme::Layer::Primary);
+            cosmic::iced::widget::Stack::with_children(vec![
+                tab_column.into(),
+                widget::container(zoom_indicator)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .padding(space_m)
+                    .align_x(Horizontal::Center)
+                    .align_y(Vertical::Bottom)
+                    .into(),
+            ])
+            .into()
+        } else {
+            tab_column.into()
+        };
+        let mut tab_view = widget::container(tab_content)
             .height(Length::Fill)
             .width(Length::Fill);
 
@@ -5150,11 +10623,52 @@ impl Tab {
         widget::responsive(|size| self.view_responsive(key_binds, size)).into()
     }
 
-    pub fn subscription(&self, preview: bool) -> Subscription<Message> {
+    pub fn subscription(
+        &self,
+        preview: bool,
+        reduce_background_work: bool,
+    ) -> Subscription<Message> {
         //TODO: how many thumbnail loads should be in flight at once?
-        let jobs = 8;
+        let jobs = if reduce_background_work { 2 } else { 8 };
         let mut subscriptions = Vec::with_capacity(jobs + 3);
 
+        if self.location == Location::Trash && self.trash_sizes_opt.is_none() {
+            if let Some(items) = &self.items_opt {
+                let mut sizes = HashMap::with_capacity(items.len());
+                let mut folders = Vec::new();
+                for item in items.iter() {
+                    if let ItemMetadata::Trash { metadata, entry } = &item.metadata {
+                        match metadata.size {
+                            trash::TrashItemSize::Bytes(bytes) => {
+                                sizes.insert(entry.id.clone(), bytes);
+                            }
+                            trash::TrashItemSize::Entries(_) => folders.push(entry.clone()),
+                        }
+                    }
+                }
+                subscriptions.push(Subscription::run_with_id(
+                    ("trash_sizes", items.len()),
+                    stream::channel(1, |mut output| async move {
+                        let message = tokio::task::spawn_blocking(move || {
+                            for entry in folders {
+                                let size = trash_policy::folder_size(&entry);
+                                sizes.insert(entry.id, size);
+                            }
+                            Message::TrashSizes(sizes)
+                        })
+                        .await
+                        .unwrap();
+
+                        if let Err(err) = output.send(message).await {
+                            log::warn!("failed to send trash sizes: {}", err);
+                        }
+
+                        std::future::pending().await
+                    }),
+                ));
+            }
+        }
+
         if let Some(items) = &self.items_opt {
             //TODO: move to function
             let visible_rect = {
@@ -5198,41 +10712,189 @@ impl Tab {
                     continue;
                 };
                 let mime = item.mime.clone();
+                // Only local files are sniffed, reading remote files is too slow
+                let sniff = metadata.is_file() && matches!(fs_kind(&metadata), FsKind::Local);
 
                 subscriptions.push(Subscription::run_with_id(
                     ("thumbnail", path.clone()),
                     stream::channel(1, |mut output| async move {
-                        let message = {
+                        let messages = {
                             let path = path.clone();
                             tokio::task::spawn_blocking(move || {
+                                let mut messages = Vec::with_capacity(2);
+                                // The name may not say what the file is, so check its contents
+                                let mime = match sniff.then(|| sniff_mime(&path, &metadata)) {
+                                    Some(Ok(sniffed)) if sniffed != mime => {
+                                        log::debug!(
+                                            "sniffed {:?} as {} instead of {}",
+                                            path,
+                                            sniffed,
+                                            mime
+                                        );
+                                        messages.push(Message::MimeSniffed(
+                                            path.clone(),
+                                            sniffed.clone(),
+                                        ));
+                                        sniffed
+                                    }
+                                    Some(Err(err)) => {
+                                        log::warn!("failed to sniff type of {:?}: {}", path, err);
+                                        mime
+                                    }
+                                    _ => mime,
+                                };
                                 let start = Instant::now();
                                 let thumbnail =
                                     ItemThumbnail::new(&path, metadata, mime, THUMBNAIL_SIZE);
                                 log::debug!("thumbnailed {:?} in {:?}", path, start.elapsed());
-                                Message::Thumbnail(path.clone(), thumbnail)
+                                messages.push(Message::Thumbnail(path.clone(), thumbnail));
+                                messages
                             })
                             .await
                             .unwrap()
                         };
 
+                        for message in messages {
+                            match output.send(message).await {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!("failed to send thumbnail for {:?}: {}", &path, err);
+                                }
+                            }
+                        }
+
+                        std::future::pending().await
+                    }),
+                ));
+
+                if subscriptions.len() >= jobs {
+                    break;
+                }
+            }
+
+            // Calculate the size of selected folders, of the folder shown in the details without a
+            // selection, and of visible folders when the size column shows folder sizes
+            let mut dir_size_items: Vec<&Item> = Vec::new();
+            if preview {
+                dir_size_items.extend(items.iter().filter(|item| item.selected));
+                if dir_size_items.is_empty() {
+                    dir_size_items.extend(self.parent_item_opt.as_ref());
+                }
+            }
+            if self.folder_sizes() {
+                dir_size_items.extend(items.iter().filter(|item| {
+                    !item.selected
+                        && item
+                            .rect_opt
+                            .get()
+                            .is_some_and(|rect| rect.intersects(&visible_rect))
+                }));
+            }
+            for (path, controller) in dir_size_items
+                .into_iter()
+                .filter_map(|item| match &item.dir_size {
+                    DirSize::Calculating(controller) => {
+                        Some((item.path_opt()?.clone(), controller.clone()))
+                    }
+                    _ => None,
+                })
+                .take(jobs)
+            {
+                subscriptions.push(Subscription::run_with_id(
+                    ("dir_size", path.clone()),
+                    stream::channel(1, move |mut output| async move {
+                        let message = {
+                            let start = Instant::now();
+                            match calculate_dir_size(
+                                &path,
+                                controller,
+                                reduce_background_work,
+                                &mut output,
+                            )
+                            .await
+                            {
+                                Ok(size) => {
+                                    log::debug!(
+                                        "calculated directory size of {:?} in {:?}",
+                                        path,
+                                        start.elapsed()
+                                    );
+                                    Message::DirectorySize(path.clone(), DirSize::Directory(size))
+                                }
+                                Err(err) => {
+                                    log::warn!(
+                                        "failed to calculate directory size of {:?}: {}",
+                                        path,
+                                        err
+                                    );
+                                    Message::DirectorySize(path.clone(), DirSize::Error(err))
+                                }
+                            }
+                        };
+
                         match output.send(message).await {
                             Ok(()) => {}
                             Err(err) => {
-                                log::warn!("failed to send thumbnail for {:?}: {}", &path, err);
+                                log::warn!(
+                                    "failed to send directory size for {:?}: {}",
+                                    &path,
+                                    err
+                                );
                             }
                         }
 
                         std::future::pending().await
                     }),
                 ));
+            }
 
-                if subscriptions.len() >= jobs {
-                    break;
+            // Read ahead the listings of visible local folders, so that they open right away
+            if !reduce_background_work && matches!(self.location, Location::Path(_)) {
+                let sizes = self.config.icon_sizes;
+                let prefetch_paths = items
+                    .iter()
+                    .filter(|item| {
+                        item.rect_opt
+                            .get()
+                            .is_some_and(|rect| rect.intersects(&visible_rect))
+                    })
+                    .filter_map(|item| match &item.metadata {
+                        ItemMetadata::Path { metadata, .. }
+                            if metadata.is_dir() && matches!(fs_kind(metadata), FsKind::Local) =>
+                        {
+                            item.path_opt()
+                        }
+                        _ => None,
+                    })
+                    .filter(|path| self.prefetch.wanted(path))
+                    .take(prefetch::JOBS);
+                for path in prefetch_paths.cloned() {
+                    subscriptions.push(Subscription::run_with_id(
+                        ("prefetch", path.clone()),
+                        stream::channel(1, move |mut output| async move {
+                            let message = {
+                                let path = path.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    let (parent_item_opt, items) =
+                                        Location::Path(path.clone()).scan(sizes);
+                                    Message::Prefetched(path, parent_item_opt, items)
+                                })
+                                .await
+                                .unwrap()
+                            };
+
+                            if let Err(err) = output.send(message).await {
+                                log::warn!("failed to send listing of {:?}: {}", &path, err);
+                            }
+
+                            std::future::pending().await
+                        }),
+                    ));
                 }
             }
 
             if preview {
-                // Load directory size for selected items
+                // Load file system and archive entries for the selected item
                 if let Some(item) = items
                     .iter()
                     .find(|item| item.selected)
@@ -5240,45 +10902,386 @@ impl Tab {
                 {
                     // Item must have a path
                     if let Some(path) = item.path_opt().map(|path| path.to_path_buf()) {
-                        // Item must be calculating directory size
-                        if let DirSize::Calculating(controller) = &item.dir_size {
-                            let controller = controller.clone();
+                        // Item must be a folder whose file system is not loaded yet
+                        if item.metadata.is_dir() && item.fs_info_opt.is_none() {
+                            let path = path.clone();
                             subscriptions.push(Subscription::run_with_id(
-                                ("dir_size", path.clone()),
+                                ("fs_info", path.clone()),
                                 stream::channel(1, |mut output| async move {
                                     let message = {
-                                        let start = Instant::now();
-                                        match calculate_dir_size(&path, controller).await {
-                                            Ok(size) => {
-                                                log::debug!(
-                                                    "calculated directory size of {:?} in {:?}",
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || match fs_info(&path) {
+                                            Ok(fs_info) => Some(Message::FsInfo(path, fs_info)),
+                                            Err(err) => {
+                                                log::warn!(
+                                                    "failed to get file system of {:?}: {}",
                                                     path,
-                                                    start.elapsed()
+                                                    err
                                                 );
-                                                Message::DirectorySize(
-                                                    path.clone(),
-                                                    DirSize::Directory(size),
-                                                )
+                                                None
                                             }
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    if let Some(message) = message {
+                                        match output.send(message).await {
+                                            Ok(()) => {}
                                             Err(err) => {
                                                 log::warn!(
-                                                "failed to calculate directory size of {:?}: {}",
-                                                path,
+                                                    "failed to send file system for {:?}: {}",
+                                                    &path,
+                                                    err
+                                                );
+                                            }
+                                        }
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+
+                        if item.versions_opt.is_none() {
+                            let path = path.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("versions", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let versions = versions::versions(&path);
+                                            Message::Versions(path, versions)
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    if let Err(err) = output.send(message).await {
+                                        log::warn!(
+                                            "failed to send versions for {:?}: {}",
+                                            &path,
+                                            err
+                                        );
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+
+                        if item.attributes_opt.is_none() {
+                            let path = path.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("attributes", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let attributes = match attributes::read(&path) {
+                                                Ok(ok) => Ok(ok),
+                                                // Shown as having no attributes
+                                                Err(err)
+                                                    if err.kind()
+                                                        == std::io::ErrorKind::Unsupported =>
+                                                {
+                                                    Ok(Attributes::default())
+                                                }
+                                                Err(err) => Err(err.to_string()),
+                                            };
+                                            Message::Attributes(path, attributes)
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    if let Err(err) = output.send(message).await {
+                                        log::warn!(
+                                            "failed to send attributes for {:?}: {}",
+                                            &path,
+                                            err
+                                        );
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+
+                        // Checksums are calculated until the item is no longer shown
+                        if let Checksums::Calculating(controller) = &item.checksums {
+                            let controller = controller.clone();
+                            let path = path.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("checksums", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let checksums = match checksum::compute(
+                                                &path,
+                                                &ChecksumAlgorithm::ALL,
+                                                &controller,
+                                            ) {
+                                                Ok(checksums) => Checksums::Calculated(checksums),
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to calculate checksums of {:?}: {}",
+                                                        path,
+                                                        err
+                                                    );
+                                                    Checksums::Error(err)
+                                                }
+                                            };
+                                            Message::Checksums(path, checksums)
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    match output.send(message).await {
+                                        Ok(()) => {}
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to send checksums for {:?}: {}",
+                                                &path,
                                                 err
                                             );
-                                                Message::DirectorySize(
-                                                    path.clone(),
-                                                    DirSize::Error(err),
-                                                )
+                                        }
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+                        match &item.document_preview {
+                            DocumentPreview::Loading => {
+                                let path = path.clone();
+                                let mime = item.mime.clone();
+                                // Highlighting colors are chosen for the current theme
+                                let dark = theme::active().cosmic().is_dark;
+                                subscriptions.push(Subscription::run_with_id(
+                                    ("document_preview", path.clone()),
+                                    stream::channel(1, move |mut output| async move {
+                                        let message = {
+                                            let path = path.clone();
+                                            tokio::task::spawn_blocking(move || {
+                                                let document_preview =
+                                                    match load_document_preview(&path, &mime, dark)
+                                                    {
+                                                        Ok(ok) => ok,
+                                                        Err(err) => {
+                                                            log::warn!(
+                                                            "failed to preview document {:?}: {}",
+                                                            path,
+                                                            err
+                                                        );
+                                                            DocumentPreview::Error(err)
+                                                        }
+                                                    };
+                                                Message::DocumentPreview(path, document_preview)
+                                            })
+                                            .await
+                                            .unwrap()
+                                        };
+
+                                        match output.send(message).await {
+                                            Ok(()) => {}
+                                            Err(err) => {
+                                                log::warn!(
+                                                    "failed to send document preview for {:?}: {}",
+                                                    &path,
+                                                    err
+                                                );
+                                            }
+                                        }
+
+                                        std::future::pending().await
+                                    }),
+                                ));
+                            }
+                            #[cfg(feature = "pdf-preview")]
+                            DocumentPreview::LoadingPage(page, pages) => {
+                                let path = path.clone();
+                                let (page, pages) = (*page, *pages);
+                                subscriptions.push(Subscription::run_with_id(
+                                    ("document_page", path.clone(), page),
+                                    stream::channel(1, move |mut output| async move {
+                                        let message = {
+                                            let path = path.clone();
+                                            tokio::task::spawn_blocking(move || {
+                                                let document_preview =
+                                                    match document_preview::pdf_page(
+                                                        &path,
+                                                        page,
+                                                        THUMBNAIL_SIZE,
+                                                    ) {
+                                                        Ok(data) => DocumentPreview::Page(
+                                                            page,
+                                                            pages,
+                                                            widget::image::Handle::from_bytes(data),
+                                                        ),
+                                                        Err(err) => {
+                                                            log::warn!(
+                                                                "failed to render page {} of {:?}: {}",
+                                                                page,
+                                                                path,
+                                                                err
+                                                            );
+                                                            DocumentPreview::Error(err)
+                                                        }
+                                                    };
+                                                Message::DocumentPreview(path, document_preview)
+                                            })
+                                            .await
+                                            .unwrap()
+                                        };
+
+                                        match output.send(message).await {
+                                            Ok(()) => {}
+                                            Err(err) => {
+                                                log::warn!(
+                                                    "failed to send document page for {:?}: {}",
+                                                    &path,
+                                                    err
+                                                );
                                             }
                                         }
+
+                                        std::future::pending().await
+                                    }),
+                                ));
+                            }
+                            _ => {}
+                        }
+                        if let ImageInfo::Loading = &item.image_info {
+                            // Item must be a photo whose metadata is not read yet
+                            let path = path.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("image_info", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let image_info = match image_metadata::metadata(&path) {
+                                                Ok(Some(metadata)) => {
+                                                    ImageInfo::Loaded(Box::new(metadata))
+                                                }
+                                                Ok(None) => ImageInfo::NoMetadata,
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to read image metadata of {:?}: {}",
+                                                        path,
+                                                        err
+                                                    );
+                                                    ImageInfo::Error(err)
+                                                }
+                                            };
+                                            Message::ImageInfo(path, image_info)
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    match output.send(message).await {
+                                        Ok(()) => {}
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to send image metadata for {:?}: {}",
+                                                &path,
+                                                err
+                                            );
+                                        }
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+                        #[cfg(feature = "audio-preview")]
+                        if let AudioInfo::Loading = &item.audio_info {
+                            // Item must be an audio file whose metadata is not read yet
+                            let path = path.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("audio_info", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let audio_info = match audio_preview::metadata(
+                                                &path,
+                                                THUMBNAIL_SIZE,
+                                            ) {
+                                                Ok(mut metadata) => {
+                                                    let cover_opt = metadata
+                                                        .cover_opt
+                                                        .take()
+                                                        .map(widget::image::Handle::from_bytes);
+                                                    AudioInfo::Loaded(Box::new(metadata), cover_opt)
+                                                }
+                                                Err(err) => {
+                                                    log::warn!(
+                                                        "failed to read audio metadata of {:?}: {}",
+                                                        path,
+                                                        err
+                                                    );
+                                                    AudioInfo::Error(err)
+                                                }
+                                            };
+                                            Message::AudioInfo(path, audio_info)
+                                        })
+                                        .await
+                                        .unwrap()
+                                    };
+
+                                    match output.send(message).await {
+                                        Ok(()) => {}
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to send audio metadata for {:?}: {}",
+                                                &path,
+                                                err
+                                            );
+                                        }
+                                    }
+
+                                    std::future::pending().await
+                                }),
+                            ));
+                        }
+                        if let ArchiveEntries::Loading = &item.archive_entries {
+                            // Item must be an archive whose entries are not listed yet
+                            let mime = item.mime.clone();
+                            subscriptions.push(Subscription::run_with_id(
+                                ("archive_entries", path.clone()),
+                                stream::channel(1, |mut output| async move {
+                                    let message = {
+                                        let path = path.clone();
+                                        tokio::task::spawn_blocking(move || {
+                                            let archive_entries =
+                                                match archive_entries(&path, &mime) {
+                                                    Ok(entries) => ArchiveEntries::Loaded(entries),
+                                                    Err(err) => {
+                                                        log::warn!(
+                                                            "failed to list archive {:?}: {}",
+                                                            path,
+                                                            err
+                                                        );
+                                                        ArchiveEntries::Error(err)
+                                                    }
+                                                };
+                                            Message::ArchiveEntries(path, archive_entries)
+                                        })
+                                        .await
+                                        .unwrap()
                                     };
 
                                     match output.send(message).await {
                                         Ok(()) => {}
                                         Err(err) => {
                                             log::warn!(
-                                                "failed to send directory size for {:?}: {}",
+                                                "failed to send archive entries for {:?}: {}",
                                                 &path,
                                                 err
                                             );
@@ -5294,6 +11297,170 @@ impl Tab {
             }
         }
 
+        if let Some(animation) = &self.animation_opt {
+            match &animation.frames_opt {
+                None => {
+                    let path = animation.path.clone();
+                    let mime = animation.mime.clone();
+                    let scrub = animation.scrub_opt.is_some();
+                    subscriptions.push(Subscription::run_with_id(
+                        ("animation", path.clone()),
+                        stream::channel(1, |mut output| async move {
+                            let message = {
+                                let path = path.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    let frames_res = if scrub {
+                                        scrub_frames(&path, THUMBNAIL_SIZE)
+                                    } else {
+                                        animation_frames(
+                                            &path,
+                                            &mime,
+                                            THUMBNAIL_SIZE,
+                                            ANIMATION_DURATION,
+                                        )
+                                    };
+                                    // Without frames, such as when ffmpeg is not installed, the
+                                    // static thumbnail stays
+                                    let frames = match frames_res {
+                                        Ok(ok) => ok,
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to load animation for {:?}: {}",
+                                                path,
+                                                err
+                                            );
+                                            Vec::new()
+                                        }
+                                    };
+                                    Message::AnimationFrames(path, frames)
+                                })
+                                .await
+                                .unwrap()
+                            };
+
+                            match output.send(message).await {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!("failed to send animation for {:?}: {}", &path, err);
+                                }
+                            }
+
+                            std::future::pending().await
+                        }),
+                    ));
+                }
+                // Scrubbed videos follow the cursor instead of playing
+                Some(frames) if frames.len() > 1 && animation.scrub_opt.is_none() => {
+                    if let Some((_, delay)) = frames.get(animation.frame) {
+                        subscriptions.push(
+                            cosmic::iced::time::every(*delay).map(|_| Message::AnimationTick),
+                        );
+                    }
+                }
+                // Animation has stopped or has a single frame
+                Some(_) => {}
+            }
+        }
+
+        if let Some(media) = &self.media_preview_opt {
+            match &media.frames_opt {
+                None => {
+                    let path = media.path.clone();
+                    let mime = media.mime.clone();
+                    let size = media.size;
+                    subscriptions.push(Subscription::run_with_id(
+                        ("media-preview", path.clone(), size),
+                        stream::channel(1, |mut output| async move {
+                            let message = {
+                                let path = path.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    let frames = match animation_frames(
+                                        &path,
+                                        &mime,
+                                        size,
+                                        MEDIA_PREVIEW_DURATION,
+                                    ) {
+                                        Ok(ok) => ok,
+                                        Err(err) => {
+                                            log::warn!(
+                                                "failed to load media preview for {:?}: {}",
+                                                path,
+                                                err
+                                            );
+                                            Vec::new()
+                                        }
+                                    };
+                                    Message::MediaFrames(path, frames)
+                                })
+                                .await
+                                .unwrap()
+                            };
+
+                            match output.send(message).await {
+                                Ok(()) => {}
+                                Err(err) => {
+                                    log::warn!(
+                                        "failed to send media preview for {:?}: {}",
+                                        &path,
+                                        err
+                                    );
+                                }
+                            }
+
+                            std::future::pending().await
+                        }),
+                    ));
+                }
+                Some(frames) if media.playing => {
+                    if let Some((_, delay)) = frames.get(media.frame) {
+                        subscriptions
+                            .push(cosmic::iced::time::every(*delay).map(|_| Message::MediaTick));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        // A stream is started for each generation of the player, the old one stops when its
+        // subscription is dropped and the frames can no longer be sent
+        #[cfg(feature = "video-thumbnails")]
+        if let Some(video) = self.gallery_video_opt.as_ref().filter(|video| {
+            video.player.playing() || video.frame_generation_opt != Some(video.player.generation())
+        }) {
+            let path = video.player.path().to_path_buf();
+            let generation = video.player.generation();
+            let position = video.player.position();
+            let probe = video.player.duration_opt().is_none();
+            let playing = video.player.playing();
+            subscriptions.push(Subscription::run_with_id(
+                ("gallery-video", path.clone(), generation),
+                stream::channel(1, move |output| async move {
+                    tokio::task::spawn_blocking(move || {
+                        gallery_video_frames(path, generation, position, probe, playing, output)
+                    })
+                    .await
+                    .unwrap();
+
+                    std::future::pending().await
+                }),
+            ));
+        }
+
+        if self
+            .audio_player_opt
+            .as_ref()
+            .is_some_and(|player| player.playing())
+        {
+            subscriptions.push(cosmic::iced::time::every(AUDIO_TICK).map(|_| Message::AudioTick));
+        }
+
+        if self.gallery && self.slideshow_opt.is_some() {
+            subscriptions.push(
+                cosmic::iced::time::every(self.config.slideshow.interval())
+                    .map(|_| Message::SlideshowTick),
+            );
+        }
+
         // Load search items incrementally
         if let Location::Search(path, term, show_hidden, start) = &self.location {
             let location = self.location.clone();
@@ -5308,6 +11475,7 @@ impl Tab {
                     let (results_tx, results_rx) = mpsc::channel(65536);
 
                     let ready = Arc::new(atomic::AtomicBool::new(false));
+                    let stop = Arc::new(atomic::AtomicBool::new(false));
                     let last_modified_opt = Arc::new(RwLock::new(None));
                     output
                         .send(Message::SearchContext(
@@ -5315,6 +11483,7 @@ impl Tab {
                             SearchContextWrapper(Some(SearchContext {
                                 results_rx,
                                 ready: ready.clone(),
+                                stop: stop.clone(),
                                 last_modified_opt: last_modified_opt.clone(),
                             })),
                         ))
@@ -5329,6 +11498,7 @@ impl Tab {
                                 &path,
                                 &term,
                                 show_hidden,
+                                &stop,
                                 move |path, name, metadata| -> bool {
                                     // Don't send if the result is too old
                                     if let Some(last_modified) = *last_modified_opt.read().unwrap()
@@ -5383,6 +11553,11 @@ impl Tab {
                     std::future::pending().await
                 }),
             ));
+            if self.search_context.is_some() {
+                subscriptions.push(
+                    cosmic::iced::time::every(Duration::from_secs(1)).map(|_| Message::SearchTick),
+                );
+            }
         }
 
         if let Some(path) = self
@@ -5425,6 +11600,33 @@ impl Tab {
             ));
         }
 
+        if matches!(self.location, Location::Network(..)) || self.location.path_opt().is_some() {
+            let location = self.location.clone();
+            subscriptions.push(Subscription::run_with_id(
+                ("capabilities", location.clone()),
+                stream::channel(1, |mut output| async move {
+                    let message = {
+                        let location = location.clone();
+                        tokio::task::spawn_blocking(move || {
+                            let capabilities = location.capabilities();
+                            Message::Capabilities(location, capabilities)
+                        })
+                        .await
+                        .unwrap()
+                    };
+
+                    match output.send(message).await {
+                        Ok(()) => {}
+                        Err(err) => {
+                            log::warn!("failed to send capabilities of {}: {}", location, err);
+                        }
+                    }
+
+                    std::future::pending().await
+                }),
+            ));
+        }
+
         Subscription::batch(subscriptions)
     }
 
@@ -5648,113 +11850,665 @@ fn text_editor_class(
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::{fs, io, path::PathBuf};
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs, io,
+        os::unix::fs::PermissionsExt,
+        path::PathBuf,
+        time::{Duration, Instant, SystemTime},
+    };
+
+    use cosmic::{iced::mouse::ScrollDelta, iced_runtime::keyboard::Modifiers};
+    use log::{debug, trace};
+    use tempfile::TempDir;
+    use test_log::test;
+
+    #[cfg(unix)]
+    use super::unescaped_file_name;
+    use super::{
+        archive_entries, escaped_file_name, fs_info, item_from_path, network_location,
+        network_parent, office_embedded_thumbnail, permissions_text, respond_to_scroll_direction,
+        scan_computer, scan_favorites, scan_path, scan_search, selection_stats, AbsoluteOffset,
+        Animation, ArchiveEntry, Checksums, Command, DirSize, HeadingOptions, Location,
+        LocationCapabilities, Message, RelativeTime, SearchQuery, Slideshow, Tab, View,
+        ZOOM_INDICATOR_DURATION,
+    };
+    #[cfg(target_os = "linux")]
+    use super::{parse_quota_output, Quota};
+    use crate::{
+        app::test_utils::{
+            assert_eq_tab_path, empty_fs, eq_path_item, filter_dirs, read_dir_sorted, simple_fs,
+            tab_click_new, NAME_LEN, NUM_DIRS, NUM_FILES, NUM_HIDDEN, NUM_NESTED,
+        },
+        config::{IconSizes, SlideshowConfig, TabConfig},
+    };
+
+    // Boilerplate for tab tests. Checks if simulated clicks selected items.
+    fn tab_selects_item(
+        clicks: &[usize],
+        modifiers: Modifiers,
+        expected_selected: &[bool],
+    ) -> io::Result<()> {
+        let (_fs, mut tab) = tab_click_new(NUM_FILES, NUM_NESTED, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
+
+        // Simulate clicks by triggering Message::Click
+        for &click in clicks {
+            debug!("Emitting Message::Click(Some({click})) with modifiers: {modifiers:?}");
+            tab.update(Message::Click(Some(click)), modifiers);
+        }
+
+        let items = tab
+            .items_opt
+            .as_deref()
+            .expect("tab should be populated with items");
+
+        for (i, (&expected, actual)) in expected_selected.iter().zip(items).enumerate() {
+            assert_eq!(
+                expected,
+                actual.selected,
+                "expected index {i} to be {}",
+                if expected {
+                    "selected but it was deselected"
+                } else {
+                    "deselected but it was selected"
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    fn tab_history() -> io::Result<(TempDir, Tab, Vec<PathBuf>)> {
+        let fs = simple_fs(NUM_FILES, NUM_NESTED, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
+        let path = fs.path();
+        let mut tab = Tab::new(Location::Path(path.into()), TabConfig::default());
+
+        // All directories (simple_fs only produces one nested layer)
+        let dirs: Vec<PathBuf> = filter_dirs(path)?
+            .flat_map(|dir| {
+                filter_dirs(&dir).map(|nested_dirs| std::iter::once(dir).chain(nested_dirs))
+            })
+            .flatten()
+            .collect();
+        assert!(
+            dirs.len() == NUM_DIRS + NUM_DIRS * NUM_NESTED,
+            "Sanity check: Have {} dirs instead of {}",
+            dirs.len(),
+            NUM_DIRS + NUM_DIRS * NUM_NESTED
+        );
+
+        debug!("Building history by emitting Message::Location");
+        for dir in &dirs {
+            debug!(
+                "Emitting Message::Location(Location::Path(\"{}\"))",
+                dir.display()
+            );
+            tab.update(
+                Message::Location(Location::Path(dir.clone())),
+                Modifiers::empty(),
+            );
+        }
+        trace!("Tab history: {:?}", tab.history);
+
+        Ok((fs, tab, dirs))
+    }
+
+    #[test]
+    fn scan_path_succeeds_on_valid_path() -> io::Result<()> {
+        let fs = simple_fs(NUM_FILES, NUM_HIDDEN, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
+        let path = fs.path();
+
+        // Read directory entries and sort as cosmic-files does
+        let entries = read_dir_sorted(path)?;
+
+        debug!("Calling scan_path(\"{}\")", path.display());
+        let actual = scan_path(&path.to_owned(), IconSizes::default());
+
+        // scan_path shouldn't skip any entries
+        assert_eq!(entries.len(), actual.len());
+
+        // Correct files should be scanned
+        assert!(entries
+            .into_iter()
+            .zip(actual.into_iter())
+            .all(|(path, item)| eq_path_item(&path, &item)));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_path_keeps_non_utf8_names() -> io::Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let dir = empty_fs()?;
+        let path = dir.path();
+        let file_path = path.join(OsStr::from_bytes(b"caf\xE9.txt"));
+        fs::File::create(&file_path)?;
+
+        debug!("Calling scan_path(\"{}\")", path.display());
+        let items = scan_path(&path.to_owned(), IconSizes::default());
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "caf\\xE9.txt");
+        // The raw path is kept so the file can still be opened
+        assert_eq!(items[0].path_opt(), Some(&file_path));
+
+        Ok(())
+    }
+
+    #[test]
+    fn office_embedded_thumbnail_reads_package_preview() -> io::Result<()> {
+        use std::io::Write;
+
+        let dir = empty_fs()?;
+        let path = dir.path().join("document.odt");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path)?);
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("mimetype", options)?;
+        zip.write_all(b"application/vnd.oasis.opendocument.text")?;
+        zip.start_file("Thumbnails/thumbnail.png", options)?;
+        zip.write_all(b"thumbnail")?;
+        zip.finish()?;
+
+        assert_eq!(
+            office_embedded_thumbnail(&path),
+            Ok(Some(b"thumbnail".to_vec()))
+        );
+
+        // Packages without a preview fall through to other thumbnailers
+        let path = dir.path().join("empty.docx");
+        zip::ZipWriter::new(fs::File::create(&path)?).finish()?;
+        assert_eq!(office_embedded_thumbnail(&path), Ok(None));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn escaped_file_names() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let name = OsStr::from_bytes(b"caf\xE9 \\x41.txt");
+        let escaped = escaped_file_name(name);
+        assert_eq!(escaped, "caf\\xE9 \\\\x41.txt");
+        assert_eq!(unescaped_file_name(&escaped), name);
+        assert_eq!(escaped_file_name(OsStr::new("a\\x41")), "a\\\\x41");
+        assert_eq!(
+            unescaped_file_name("caf\\xE9 (2).txt").as_bytes(),
+            b"caf\xE9 (2).txt"
+        );
+        assert_eq!(unescaped_file_name("\\xZZ\\x4").as_bytes(), b"\\xZZ\\x4");
+
+        // Every name survives a round trip, with bytes likely to form escapes
+        let mut rng = fastrand::Rng::with_seed(1729);
+        let alphabet = b"\\x4AaE9Ff.\xE9\xFF\xC3\xA9";
+        for _ in 0..10_000 {
+            let bytes: Vec<u8> = (0..rng.usize(1..12))
+                .map(|_| alphabet[rng.usize(..alphabet.len())])
+                .collect();
+            let name = OsStr::from_bytes(&bytes);
+            assert_eq!(unescaped_file_name(&escaped_file_name(name)), name);
+        }
+    }
+
+    #[test]
+    fn search_prefixes() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("photo.PNG"), b"image")?;
+        fs::write(path.join("notes.txt"), b"remember the TODO list")?;
+        fs::write(path.join("todo.md"), b"nothing here")?;
+
+        assert!(SearchQuery::has_prefix("filter:*.png"));
+        assert!(!SearchQuery::has_prefix("content:"));
+        assert!(!SearchQuery::has_prefix("/home/user"));
+
+        let stop = std::sync::atomic::AtomicBool::new(false);
+        let search = |term: &str| {
+            let names = std::sync::Mutex::new(Vec::new());
+            scan_search(
+                &path.to_path_buf(),
+                term,
+                false,
+                &stop,
+                |_path, name, _metadata| {
+                    names.lock().unwrap().push(name.to_string());
+                    true
+                },
+            );
+            let mut names = names.into_inner().unwrap();
+            names.sort();
+            names
+        };
+        assert_eq!(search("filter:*.png"), vec!["photo.PNG"]);
+        assert_eq!(search("content:todo"), vec!["notes.txt"]);
+        assert_eq!(search("todo"), vec!["todo.md"]);
+        assert!(search("tag:unknown").is_empty());
+        // Stopped searches find nothing more
+        stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(search("todo").is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn archive_entries_lists_top_level() -> io::Result<()> {
+        use std::io::Write;
+
+        let dir = empty_fs()?;
+        let path = dir.path().join("archive.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path)?);
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("readme.txt", options)?;
+        zip.write_all(b"readme")?;
+        zip.start_file("src/main.rs", options)?;
+        zip.write_all(b"fn main() {}")?;
+        zip.start_file("src/lib.rs", options)?;
+        zip.write_all(b"lib")?;
+        zip.finish()?;
+
+        let entries = archive_entries(&path, &"application/zip".parse().unwrap())
+            .map_err(io::Error::other)?;
+        assert_eq!(
+            entries,
+            vec![
+                ArchiveEntry {
+                    name: "src".to_string(),
+                    dir: true,
+                    size: 15,
+                    compressed_size: Some(15),
+                },
+                ArchiveEntry {
+                    name: "readme.txt".to_string(),
+                    dir: false,
+                    size: 6,
+                    compressed_size: Some(6),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn fs_info_finds_containing_mount() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let info = fs_info(dir.path()).map_err(io::Error::other)?;
+        assert!(dir.path().starts_with(&info.mount_point));
+        assert!(!info.fs_type.is_empty());
+        assert!(info.free <= info.total);
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn nfs_quota_output() {
+        let output = concat!(
+            "Disk quotas for user alice (uid 1000): \n",
+            "     Filesystem  blocks   quota   limit   grace   files   quota   limit   grace\n",
+            "server:/srv/home  2048*   1024    4096  604800      10       0       0       0\n",
+            "server:/srv/other     1       0       0       0       1       0       0       0\n",
+        );
+        assert_eq!(
+            parse_quota_output(output, "server:/srv/home"),
+            Some(Quota {
+                used: 2048 * 1024,
+                limit: 4096 * 1024,
+            })
+        );
+        // No limit means no quota
+        assert_eq!(parse_quota_output(output, "server:/srv/other"), None);
+        assert_eq!(parse_quota_output(output, "server:/srv/missing"), None);
+    }
+
+    #[test]
+    fn selection_stats_counts_kinds_and_sizes() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("notes.txt"), b"notes")?;
+        fs::write(path.join("large.txt"), b"a larger file")?;
+        fs::create_dir(path.join("folder"))?;
+        fs::write(path.join("folder").join("nested.txt"), b"nested")?;
+
+        let stats = selection_stats(&[
+            path.join("notes.txt"),
+            path.join("large.txt"),
+            path.join("folder"),
+        ]);
+        assert_eq!(stats.count, 3);
+        assert_eq!(
+            stats.kinds,
+            vec![("text".to_string(), 2), ("folder".to_string(), 1)]
+        );
+        assert_eq!(stats.size, 5 + 13 + 6);
+        assert_eq!(stats.largest, Some(("large.txt".to_string(), 13)));
+        assert!(stats.oldest.is_some() && stats.newest.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_items_hides_matching_patterns() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::File::create(path.join("notes.txt"))?;
+        fs::File::create(path.join("notes.txt~"))?;
+        fs::create_dir(path.join("__pycache__"))?;
+
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        assert!(tab.set_hidden_patterns(&["*~".to_string(), "__pycache__".to_string()]));
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+
+        let hidden: Vec<_> = tab
+            .items_opt()
+            .unwrap()
+            .iter()
+            .filter(|item| item.hidden)
+            .map(|item| item.name.as_str())
+            .collect();
+        assert_eq!(hidden, ["__pycache__", "notes.txt~"]);
 
-    use cosmic::{iced::mouse::ScrollDelta, iced_runtime::keyboard::Modifiers};
-    use log::{debug, trace};
-    use tempfile::TempDir;
-    use test_log::test;
+        Ok(())
+    }
 
-    use super::{respond_to_scroll_direction, scan_path, Location, Message, Tab};
-    use crate::{
-        app::test_utils::{
-            assert_eq_tab_path, empty_fs, eq_path_item, filter_dirs, read_dir_sorted, simple_fs,
-            tab_click_new, NAME_LEN, NUM_DIRS, NUM_FILES, NUM_HIDDEN, NUM_NESTED,
-        },
-        config::{IconSizes, TabConfig},
-    };
+    #[cfg(feature = "gvfs")]
+    #[test]
+    fn archive_location_uri() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path().join("my archive.zip");
+        fs::write(&path, b"")?;
+        fs::write(dir.path().join("notes.txt"), b"")?;
+
+        let Some(Location::Network(uri, display_name)) = super::archive_location(&path) else {
+            panic!("no archive location for {:?}", path);
+        };
+        assert_eq!(display_name, "my archive.zip");
+        let file_uri = url::Url::from_file_path(&path).unwrap();
+        let escaped = file_uri
+            .as_str()
+            .replace('%', "%25")
+            .replace(':', "%3A")
+            .replace('/', "%2F");
+        assert_eq!(uri, format!("archive://{}/", escaped));
+
+        assert!(super::archive_location(&dir.path().join("notes.txt")).is_none());
+        assert!(super::archive_location(&dir.path().join("missing.zip")).is_none());
 
-    // Boilerplate for tab tests. Checks if simulated clicks selected items.
-    fn tab_selects_item(
-        clicks: &[usize],
-        modifiers: Modifiers,
-        expected_selected: &[bool],
-    ) -> io::Result<()> {
-        let (_fs, mut tab) = tab_click_new(NUM_FILES, NUM_NESTED, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
+        Ok(())
+    }
 
-        // Simulate clicks by triggering Message::Click
-        for &click in clicks {
-            debug!("Emitting Message::Click(Some({click})) with modifiers: {modifiers:?}");
-            tab.update(Message::Click(Some(click)), modifiers);
-        }
+    #[test]
+    fn set_items_keeps_focus_and_loaded_details() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("a.txt"), b"a")?;
+        fs::write(path.join("c.txt"), b"c")?;
+        let c_path = path.join("c.txt");
 
-        let items = tab
-            .items_opt
-            .as_deref()
-            .expect("tab should be populated with items");
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        tab.select_paths(vec![c_path.clone()]);
+        tab.update(
+            Message::Checksums(c_path.clone(), Checksums::Calculated(Vec::new())),
+            Modifiers::empty(),
+        );
+        let item_at = |tab: &Tab, index: Option<usize>| {
+            index
+                .and_then(|index| tab.items_opt()?.get(index))
+                .and_then(|item| item.path_opt().cloned())
+        };
+        let c_item = |tab: &Tab| {
+            tab.items_opt()
+                .unwrap()
+                .iter()
+                .find(|item| item.path_opt() == Some(&c_path))
+                .map(|item| (item.selected, item.checksums.clone()))
+                .unwrap()
+        };
 
-        for (i, (&expected, actual)) in expected_selected.iter().zip(items).enumerate() {
-            assert_eq!(
-                expected,
-                actual.selected,
-                "expected index {i} to be {}",
-                if expected {
-                    "selected but it was deselected"
-                } else {
-                    "deselected but it was selected"
-                }
-            );
-        }
+        // A file added before the focused one moves it to another index
+        fs::write(path.join("b.txt"), b"b")?;
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        assert_eq!(item_at(&tab, tab.select_focus), Some(c_path.clone()));
+        assert!(matches!(c_item(&tab), (true, Checksums::Calculated(_))));
+
+        // Changed files load their details again
+        fs::write(&c_path, b"changed")?;
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        assert!(matches!(c_item(&tab), (true, Checksums::NotCalculated)));
 
         Ok(())
     }
 
-    fn tab_history() -> io::Result<(TempDir, Tab, Vec<PathBuf>)> {
-        let fs = simple_fs(NUM_FILES, NUM_NESTED, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
-        let path = fs.path();
-        let mut tab = Tab::new(Location::Path(path.into()), TabConfig::default());
+    #[test]
+    fn column_sort_uses_secondary_key() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("a.txt"), b"1")?;
+        fs::write(path.join("b.txt"), b"2")?;
+        fs::write(path.join("c.txt"), b"34")?;
 
-        // All directories (simple_fs only produces one nested layer)
-        let dirs: Vec<PathBuf> = filter_dirs(path)?
-            .flat_map(|dir| {
-                filter_dirs(&dir).map(|nested_dirs| std::iter::once(dir).chain(nested_dirs))
-            })
-            .flatten()
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        tab.update(
+            Message::SetSort(HeadingOptions::Size, true),
+            Modifiers::empty(),
+        );
+        tab.update(Message::ToggleSort(HeadingOptions::Name), Modifiers::SHIFT);
+        tab.update(Message::ToggleSort(HeadingOptions::Name), Modifiers::SHIFT);
+        assert_eq!(tab.sort_secondary(), Some((HeadingOptions::Name, false)));
+
+        let names: Vec<_> = tab
+            .column_sort()
+            .unwrap()
+            .into_iter()
+            .map(|(_, item)| item.name.as_str())
             .collect();
-        assert!(
-            dirs.len() == NUM_DIRS + NUM_DIRS * NUM_NESTED,
-            "Sanity check: Have {} dirs instead of {}",
-            dirs.len(),
-            NUM_DIRS + NUM_DIRS * NUM_NESTED
+        assert_eq!(names, ["b.txt", "a.txt", "c.txt"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn column_chooser_toggles_columns() -> io::Result<()> {
+        assert_eq!(permissions_text(0o40755, true), "drwxr-xr-x");
+        assert_eq!(permissions_text(0o100640, false), "-rw-r-----");
+
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("a.txt"), b"")?;
+        fs::set_permissions(path.join("a.txt"), fs::Permissions::from_mode(0o600))?;
+        fs::write(path.join("b.txt"), b"")?;
+        fs::set_permissions(path.join("b.txt"), fs::Permissions::from_mode(0o644))?;
+
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        assert_eq!(tab.columns(), HeadingOptions::DEFAULT_COLUMNS);
+
+        // Columns are saved for the folder, in display order
+        let commands = tab.update(
+            Message::ToggleColumn(HeadingOptions::Permissions),
+            Modifiers::empty(),
         );
+        let expected = vec![
+            HeadingOptions::Permissions,
+            HeadingOptions::Modified,
+            HeadingOptions::Size,
+        ];
+        assert!(matches!(
+            commands.as_slice(),
+            [Command::SetFolderColumns(folder, columns)]
+                if folder == path && *columns == expected
+        ));
+        assert_eq!(tab.columns(), expected);
 
-        debug!("Building history by emitting Message::Location");
-        for dir in &dirs {
-            debug!(
-                "Emitting Message::Location(Location::Path(\"{}\"))",
-                dir.display()
-            );
-            tab.update(
-                Message::Location(Location::Path(dir.clone())),
-                Modifiers::empty(),
-            );
-        }
-        trace!("Tab history: {:?}", tab.history);
+        tab.update(
+            Message::SetSort(HeadingOptions::Permissions, false),
+            Modifiers::empty(),
+        );
+        let names: Vec<_> = tab
+            .column_sort()
+            .unwrap()
+            .into_iter()
+            .map(|(_, item)| item.name.as_str())
+            .collect();
+        assert_eq!(names, ["b.txt", "a.txt"]);
 
-        Ok((fs, tab, dirs))
+        tab.update(
+            Message::ToggleColumn(HeadingOptions::Modified),
+            Modifiers::empty(),
+        );
+        assert_eq!(
+            tab.columns(),
+            [HeadingOptions::Permissions, HeadingOptions::Size]
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn scan_path_succeeds_on_valid_path() -> io::Result<()> {
-        let fs = simple_fs(NUM_FILES, NUM_HIDDEN, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
-        let path = fs.path();
+    fn sort_and_group_by_kind() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("a.txt"), b"")?;
+        fs::write(path.join("b.png"), b"")?;
+        fs::write(path.join("c.txt"), b"")?;
+
+        let names = |tab: &Tab| -> Vec<String> {
+            tab.column_sort()
+                .unwrap()
+                .into_iter()
+                .map(|(_, item)| item.name.clone())
+                .collect()
+        };
 
-        // Read directory entries and sort as cosmic-files does
-        let entries = read_dir_sorted(path)?;
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        tab.update(
+            Message::SetSort(HeadingOptions::Kind, true),
+            Modifiers::empty(),
+        );
+        assert_eq!(names(&tab), ["b.png", "a.txt", "c.txt"]);
 
-        debug!("Calling scan_path(\"{}\")", path.display());
-        let actual = scan_path(&path.to_owned(), IconSizes::default());
+        // Groups come first, and items are sorted within them
+        tab.config.view = View::Grid;
+        tab.config.group_by_kind = true;
+        tab.update(
+            Message::SetSort(HeadingOptions::Name, false),
+            Modifiers::empty(),
+        );
+        assert_eq!(names(&tab), ["b.png", "c.txt", "a.txt"]);
 
-        // scan_path shouldn't skip any entries
-        assert_eq!(entries.len(), actual.len());
+        Ok(())
+    }
 
-        // Correct files should be scanned
-        assert!(entries
+    #[test]
+    fn folder_sizes_are_cached() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::create_dir(path.join("big"))?;
+        fs::write(path.join("big").join("inner.txt"), [0; 100])?;
+        fs::create_dir(path.join("small"))?;
+        fs::write(path.join("small").join("inner.txt"), [0; 10])?;
+
+        let mut tab = Tab::new(Location::Path(path.to_owned()), TabConfig::default());
+        tab.config.folder_sizes = true;
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        tab.update(
+            Message::DirectorySize(path.join("big"), DirSize::Directory(100)),
+            Modifiers::empty(),
+        );
+        tab.update(
+            Message::DirectorySize(path.join("small"), DirSize::Directory(10)),
+            Modifiers::empty(),
+        );
+        tab.update(
+            Message::SetSort(HeadingOptions::Size, true),
+            Modifiers::empty(),
+        );
+        let names: Vec<_> = tab
+            .column_sort()
+            .unwrap()
             .into_iter()
-            .zip(actual.into_iter())
-            .all(|(path, item)| eq_path_item(&path, &item)));
+            .map(|(_, item)| item.name.clone())
+            .collect();
+        assert_eq!(names, ["small", "big"]);
+
+        // Sizes are kept when the folder is scanned again, until something inside changes
+        tab.forget_dir_sizes(&path.join("small").join("inner.txt"));
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        let sizes: Vec<_> = tab
+            .items_opt()
+            .unwrap()
+            .iter()
+            .map(|item| match item.dir_size {
+                DirSize::Directory(size) => Some(size),
+                _ => None,
+            })
+            .collect();
+        assert!(sizes.contains(&Some(100)));
+        assert!(sizes.contains(&None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tree_view_expands_folders() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let path = dir.path();
+        fs::write(path.join("a.txt"), b"")?;
+        fs::create_dir(path.join("b"))?;
+        fs::write(path.join("b").join("inner.txt"), b"")?;
+        fs::write(path.join("c.txt"), b"")?;
+
+        let names = |tab: &Tab| -> Vec<String> {
+            tab.column_sort()
+                .unwrap()
+                .into_iter()
+                .map(|(_, item)| item.name.clone())
+                .collect()
+        };
+        let folder_index = |tab: &Tab| {
+            tab.items_opt()
+                .unwrap()
+                .iter()
+                .position(|item| item.name == "b")
+                .unwrap()
+        };
+
+        let mut tab = Tab::new(
+            Location::Path(path.to_owned()),
+            TabConfig {
+                tree_view: true,
+                ..Default::default()
+            },
+        );
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        tab.update(
+            Message::ExpandFolder(folder_index(&tab)),
+            Modifiers::empty(),
+        );
+        let folder = path.join("b");
+        tab.update(
+            Message::ExpandItems(folder.clone(), scan_path(&folder, IconSizes::default())),
+            Modifiers::empty(),
+        );
+        assert_eq!(names(&tab), ["b", "inner.txt", "a.txt", "c.txt"]);
+
+        // Rescanning keeps the contents of expanded folders
+        tab.set_items(scan_path(&path.to_owned(), IconSizes::default()));
+        assert_eq!(names(&tab), ["b", "inner.txt", "a.txt", "c.txt"]);
+
+        tab.update(
+            Message::ExpandFolder(folder_index(&tab)),
+            Modifiers::empty(),
+        );
+        assert_eq!(names(&tab), ["b", "a.txt", "c.txt"]);
 
         Ok(())
     }
@@ -5790,6 +12544,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn shorten_path_keeps_last_two_folders() {
+        assert_eq!(
+            "…/icons/hicolor",
+            shorten_path(Path::new("/usr/share/icons/hicolor"))
+        );
+        assert_eq!("/usr/share", shorten_path(Path::new("/usr/share")));
+        assert_eq!(
+            "~/Projects",
+            shorten_path(&crate::home_dir().join("Projects"))
+        );
+    }
+
     #[test]
     fn tab_location_changes_location() -> io::Result<()> {
         let fs = simple_fs(NUM_FILES, NUM_NESTED, NUM_DIRS, NUM_NESTED, NAME_LEN)?;
@@ -5894,6 +12661,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn tab_goprev_restores_scroll_position() -> io::Result<()> {
+        let (_fs, mut tab, dirs) = tab_history()?;
+        let offset = AbsoluteOffset { x: 0.0, y: 42.0 };
+
+        // Scroll in the second to last directory, then leave it and come back
+        tab.update(Message::GoPrevious, Modifiers::empty());
+        tab.scroll_opt = Some(offset);
+        tab.update(Message::GoNext, Modifiers::empty());
+        assert_eq_tab_path(&tab, &dirs[dirs.len() - 1]);
+        tab.update(Message::GoPrevious, Modifiers::empty());
+        assert_eq_tab_path(&tab, &dirs[dirs.len() - 2]);
+
+        debug!("Emitting Message::ScrollRestore");
+        tab.update(Message::ScrollRestore, Modifiers::empty());
+        assert_eq!(tab.scroll_opt, Some(offset));
+
+        Ok(())
+    }
+
     #[test]
     fn tab_scroll_up_with_ctrl_modifier_zooms() -> io::Result<()> {
         let message_maybe =
@@ -6036,4 +12823,246 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn computer_lists_root_and_trash() {
+        let items = scan_computer(IconSizes::default());
+        let root = items.first().expect("file system root missing");
+        assert_eq!(root.path_opt(), Some(&PathBuf::from("/")));
+        assert!(root.fs_info_opt.is_some());
+        assert!(items
+            .iter()
+            .any(|item| item.location_opt == Some(Location::Trash)));
+    }
+
+    #[test]
+    fn animation_scrub() {
+        let frame = (
+            cosmic::widget::icon::from_name("video-x-generic").handle(),
+            Duration::from_millis(100),
+        );
+        let mut animation = Animation {
+            index: 0,
+            path: PathBuf::from("/video.mp4"),
+            mime: "video/mp4".parse().unwrap(),
+            frames_opt: None,
+            frame: 0,
+            start: Instant::now(),
+            scrub_opt: Some(0.0),
+        };
+        // The position is kept until the frames are loaded
+        animation.scrub(0.5);
+        assert_eq!(animation.frame, 0);
+        animation.frames_opt = Some(vec![frame; 4]);
+        animation.scrub(0.5);
+        assert_eq!(animation.frame, 2);
+        animation.scrub(1.0);
+        assert_eq!(animation.frame, 3);
+        animation.scrub(-0.5);
+        assert_eq!(animation.frame, 0);
+    }
+
+    #[test]
+    fn extension_badges() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let badge = |name: &str| -> io::Result<Option<String>> {
+            let path = dir.path().join(name);
+            fs::write(&path, b"")?;
+            let item = item_from_path(path, IconSizes::default()).map_err(io::Error::other)?;
+            Ok(item.extension_badge_text())
+        };
+        assert_eq!(badge("data.csv")?, Some("CSV".to_string()));
+        assert_eq!(badge(".bashrc")?, None);
+        assert_eq!(badge("notes.markdown")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn relative_times() {
+        let now = SystemTime::now();
+        let ago = |secs| RelativeTime::new(now - Duration::from_secs(secs), now);
+        assert_eq!(ago(30), Some(RelativeTime::JustNow));
+        assert_eq!(ago(5 * 60 + 30), Some(RelativeTime::Minutes(5)));
+        assert_eq!(ago(2 * 60 * 60), Some(RelativeTime::Hours(2)));
+        assert_eq!(ago(3 * 24 * 60 * 60), Some(RelativeTime::Days(3)));
+        assert_eq!(ago(30 * 24 * 60 * 60), None);
+        // Clock skew can put times in the future
+        assert_eq!(RelativeTime::new(now + Duration::from_secs(60), now), None);
+    }
+
+    #[test]
+    fn select_all_skips_hidden_items() -> io::Result<()> {
+        let dir = empty_fs()?;
+        for name in ["a.txt", "b.txt", ".hidden"] {
+            fs::write(dir.path().join(name), b"")?;
+        }
+        let location = Location::Path(dir.path().to_path_buf());
+        let (_, items) = location.scan(IconSizes::default());
+        let mut tab = Tab::new(location, TabConfig::default());
+        tab.set_items(items);
+        let selected = |tab: &Tab| {
+            tab.items_opt()
+                .map_or(0, |items| items.iter().filter(|item| item.selected).count())
+        };
+
+        tab.select_all();
+        assert_eq!(selected(&tab), 2);
+        tab.select_all_including_hidden();
+        assert_eq!(selected(&tab), 3);
+
+        tab.config.show_hidden = true;
+        tab.select_none();
+        tab.select_all();
+        assert_eq!(selected(&tab), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn zoom_indicator_hides_after_last_change() {
+        let mut tab = Tab::new(Location::Trash, TabConfig::default());
+        tab.config.icon_sizes.grid = 150.try_into().unwrap();
+        assert_eq!(tab.zoom(), 100);
+        tab.config.view = View::Grid;
+        assert_eq!(tab.zoom(), 150);
+
+        tab.update(Message::ZoomIndicator, Modifiers::empty());
+        assert!(tab.zoom_indicator_opt.is_some());
+        // A timeout from an earlier change leaves it shown
+        tab.update(Message::ZoomIndicatorTimeout, Modifiers::empty());
+        assert!(tab.zoom_indicator_opt.is_some());
+        tab.zoom_indicator_opt = Instant::now().checked_sub(ZOOM_INDICATOR_DURATION);
+        tab.update(Message::ZoomIndicatorTimeout, Modifiers::empty());
+        assert!(tab.zoom_indicator_opt.is_none());
+    }
+
+    #[test]
+    fn slideshow_advances() {
+        let paths: Vec<PathBuf> = ["a.png", "b.png", "c.png"].map(PathBuf::from).into();
+        let mut slideshow = Slideshow {
+            paths: paths.clone(),
+            position: 0,
+            selected_only: false,
+        };
+        let config = SlideshowConfig::default();
+        assert_eq!(slideshow.advance(None, config), Some(paths[1].as_path()));
+        // Showing another item with the gallery buttons continues from it
+        assert_eq!(
+            slideshow.advance(Some(&paths[0]), config),
+            Some(paths[1].as_path())
+        );
+        assert_eq!(slideshow.advance(None, config), Some(paths[2].as_path()));
+        assert_eq!(slideshow.advance(None, config), None);
+
+        let config = SlideshowConfig {
+            repeat: true,
+            shuffle: true,
+            ..config
+        };
+        let next = slideshow
+            .advance(Some(&paths[2]), config)
+            .map(PathBuf::from);
+        assert_eq!(slideshow.position, 0);
+        assert!(next.is_some_and(|next| paths.contains(&next)));
+        assert_eq!(slideshow.paths.len(), paths.len());
+    }
+
+    #[test]
+    fn slideshow_shows_selected_items() -> io::Result<()> {
+        let dir = empty_fs()?;
+        for name in ["a.png", "b.png", "c.png", "d.txt"] {
+            fs::write(dir.path().join(name), b"")?;
+        }
+        fs::create_dir(dir.path().join("folder"))?;
+        let location = Location::Path(dir.path().to_path_buf());
+        let (_, items) = location.scan(IconSizes::default());
+        let mut tab = Tab::new(location, TabConfig::default());
+        tab.set_items(items);
+
+        // Folders are skipped
+        tab.select_paths(vec![dir.path().join("b.png")]);
+        let slideshow = tab.slideshow().unwrap();
+        assert!(!slideshow.selected_only);
+        assert_eq!(slideshow.paths.len(), 4);
+        assert_eq!(
+            slideshow.paths[slideshow.position],
+            dir.path().join("b.png")
+        );
+
+        tab.select_paths(vec![dir.path().join("a.png"), dir.path().join("c.png")]);
+        let slideshow = tab.slideshow().unwrap();
+        assert!(slideshow.selected_only);
+        assert_eq!(
+            slideshow.paths,
+            [dir.path().join("a.png"), dir.path().join("c.png")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn favorites_skip_missing_items() -> io::Result<()> {
+        let dir = empty_fs()?;
+        let file = dir.path().join("starred.txt");
+        fs::write(&file, b"")?;
+        let items = scan_favorites(
+            &[file.clone(), dir.path().join("missing.txt")],
+            IconSizes::default(),
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path_opt(), Some(&file));
+
+        let mut tab = Tab::new(Location::Favorites, TabConfig::default());
+        assert!(!tab.is_starred(&items[0]));
+        assert!(tab.set_starred(&[file].into_iter().collect()));
+        assert!(tab.is_starred(&items[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn location_capabilities() -> io::Result<()> {
+        let capabilities = LocationCapabilities {
+            read_only: true,
+            remote: false,
+            mount_point_opt: Some(PathBuf::from("/media/disc")),
+        };
+        assert!(capabilities.applies_to(&PathBuf::from("/media/disc/photos")));
+        assert!(!capabilities.applies_to(&PathBuf::from("/media")));
+
+        let dir = empty_fs()?;
+        let capabilities = Location::Path(dir.path().to_path_buf()).capabilities();
+        assert!(!capabilities.read_only);
+        assert!(capabilities.applies_to(dir.path()));
+        assert_eq!(
+            Location::Recents.capabilities(),
+            LocationCapabilities::default()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn network_parents() {
+        assert_eq!(
+            network_location("sftp://alice@host/home/alice/My%20Files"),
+            Some(Location::Network(
+                "sftp://alice@host/home/alice/My%20Files".to_string(),
+                "host/home/alice/My Files".to_string()
+            ))
+        );
+        assert_eq!(
+            network_parent("sftp://alice@host/home/alice/My%20Files"),
+            Some(Location::Network(
+                "sftp://alice@host/home/alice/".to_string(),
+                "host/home/alice".to_string()
+            ))
+        );
+        assert_eq!(
+            network_parent("sftp://alice@host/home/"),
+            Some(Location::Network(
+                "sftp://alice@host/".to_string(),
+                "host".to_string()
+            ))
+        );
+        assert_eq!(network_parent("sftp://alice@host/"), None);
+        assert_eq!(network_location("file:///home"), None);
+    }
 }
@@ -0,0 +1,511 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Color tags, stored in the `user.xdg.tags` extended attribute shared with other file managers.
+//! File systems without extended attributes keep their tags in a database instead.
+
+use cosmic::iced::Color;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    fs, io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::fl;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorTag {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+impl ColorTag {
+    pub const ALL: [Self; 7] = [
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+        Self::Gray,
+    ];
+
+    /// Name stored in the extended attribute
+    fn key(self) -> &'static str {
+        match self {
+            Self::Red => "red",
+            Self::Orange => "orange",
+            Self::Yellow => "yellow",
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Purple => "purple",
+            Self::Gray => "gray",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tag| tag.key() == key)
+    }
+
+    /// Find a tag by its stored name or its translated name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tag| {
+            tag.key().eq_ignore_ascii_case(name) || tag.name().to_lowercase() == name.to_lowercase()
+        })
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Red => fl!("tag-red"),
+            Self::Orange => fl!("tag-orange"),
+            Self::Yellow => fl!("tag-yellow"),
+            Self::Green => fl!("tag-green"),
+            Self::Blue => fl!("tag-blue"),
+            Self::Purple => fl!("tag-purple"),
+            Self::Gray => fl!("tag-gray"),
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Red => Color::from_rgb8(0xe0, 0x1b, 0x24),
+            Self::Orange => Color::from_rgb8(0xff, 0x78, 0x00),
+            Self::Yellow => Color::from_rgb8(0xf6, 0xd3, 0x2d),
+            Self::Green => Color::from_rgb8(0x33, 0xd1, 0x7a),
+            Self::Blue => Color::from_rgb8(0x35, 0x84, 0xe4),
+            Self::Purple => Color::from_rgb8(0x91, 0x41, 0xac),
+            Self::Gray => Color::from_rgb8(0x9a, 0x99, 0x96),
+        }
+    }
+}
+
+const XATTR_NAME: &std::ffi::CStr = c"user.xdg.tags";
+
+/// Database of tagged paths. It holds the tags of files on file systems without extended
+/// attributes, and an index of every path tagged here so that a tag can be browsed without
+/// reading every file. Paths may have been moved or retagged by other programs since, so the
+/// index is checked against the files before it is shown.
+pub struct TagDb {
+    connection: rusqlite::Connection,
+}
+
+impl TagDb {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let connection = rusqlite::Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tags (
+                path BLOB NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (path, tag)
+            )",
+        )?;
+        Ok(Self { connection })
+    }
+
+    pub fn tags(&self, path: &Path) -> rusqlite::Result<Vec<ColorTag>> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT tag FROM tags WHERE path = ?1")?;
+        let keys =
+            statement.query_map([path.as_os_str().as_bytes()], |row| row.get::<_, String>(0))?;
+        let mut tags = Vec::new();
+        for key in keys {
+            tags.extend(ColorTag::from_key(&key?));
+        }
+        tags.sort();
+        Ok(tags)
+    }
+
+    pub fn set_tags(&mut self, path: &Path, tags: &[ColorTag]) -> rusqlite::Result<()> {
+        let path = path.as_os_str().as_bytes();
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM tags WHERE path = ?1", [path])?;
+        for tag in tags {
+            transaction.execute(
+                "INSERT OR IGNORE INTO tags (path, tag) VALUES (?1, ?2)",
+                rusqlite::params![path, tag.key()],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Give the tags of `from` to `to`, along with those of everything inside `from` if
+    /// `inside` is set
+    pub fn move_tags(&mut self, from: &Path, to: &Path, inside: bool) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        let paths = select_paths(&transaction, from, inside)?;
+        for path in paths {
+            let Ok(relative) = path.strip_prefix(from) else {
+                continue;
+            };
+            let new_path = if relative.as_os_str().is_empty() {
+                to.to_path_buf()
+            } else {
+                to.join(relative)
+            };
+            transaction.execute(
+                "UPDATE OR REPLACE tags SET path = ?2 WHERE path = ?1",
+                [path.as_os_str().as_bytes(), new_path.as_os_str().as_bytes()],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Forget the tags of a removed path and of everything inside it
+    pub fn remove_tags(&mut self, path: &Path) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        for path in select_paths(&transaction, path, true)? {
+            transaction.execute(
+                "DELETE FROM tags WHERE path = ?1",
+                [path.as_os_str().as_bytes()],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Paths with a tag, in no particular order
+    pub fn paths(&self, tag: ColorTag) -> rusqlite::Result<Vec<PathBuf>> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT path FROM tags WHERE tag = ?1")?;
+        let paths = statement.query_map([tag.key()], |row| row.get::<_, Vec<u8>>(0))?;
+        paths
+            .map(|path_res| path_res.map(|path| PathBuf::from(OsStr::from_bytes(&path))))
+            .collect()
+    }
+
+    /// Tags used by at least one path
+    pub fn used_tags(&self) -> rusqlite::Result<Vec<ColorTag>> {
+        let mut statement = self
+            .connection
+            .prepare_cached("SELECT DISTINCT tag FROM tags")?;
+        let keys = statement.query_map([], |row| row.get::<_, String>(0))?;
+        let mut tags = Vec::new();
+        for key in keys {
+            tags.extend(ColorTag::from_key(&key?));
+        }
+        tags.sort();
+        Ok(tags)
+    }
+}
+
+/// Tagged paths equal to `path`, or inside of it if `inside` is set
+fn select_paths(
+    connection: &rusqlite::Connection,
+    path: &Path,
+    inside: bool,
+) -> rusqlite::Result<Vec<PathBuf>> {
+    let mut prefix = path.as_os_str().as_bytes().to_vec();
+    if !prefix.ends_with(b"/") {
+        prefix.push(b'/');
+    }
+    let mut statement = connection.prepare_cached(
+        "SELECT DISTINCT path FROM tags WHERE path = ?1 OR (?2 AND substr(path, 1, ?3) = ?4)",
+    )?;
+    let paths = statement.query_map(
+        rusqlite::params![path.as_os_str().as_bytes(), inside, prefix.len(), prefix],
+        |row| row.get::<_, Vec<u8>>(0),
+    )?;
+    paths
+        .map(|path_res| path_res.map(|path| PathBuf::from(OsStr::from_bytes(&path))))
+        .collect()
+}
+
+fn tag_db_path() -> Option<PathBuf> {
+    let dir = dirs::data_local_dir()?;
+    Some(dir.join("cosmic-files").join("tags.sqlite"))
+}
+
+static TAG_DB: Lazy<Option<Mutex<TagDb>>> = Lazy::new(|| {
+    let path = tag_db_path()?;
+    if let Some(parent) = path.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            log::warn!("failed to create {:?}: {}", parent, err);
+            return None;
+        }
+    }
+    match TagDb::open(&path) {
+        Ok(db) => Some(Mutex::new(db)),
+        Err(err) => {
+            log::warn!("failed to open tag database {:?}: {}", path, err);
+            None
+        }
+    }
+});
+
+fn with_tag_db<T>(f: impl FnOnce(&mut TagDb) -> rusqlite::Result<T>) -> io::Result<T> {
+    let db = TAG_DB
+        .as_ref()
+        .ok_or_else(|| io::Error::other("tag database is not available"))?;
+    let mut db = db.lock().unwrap();
+    f(&mut db).map_err(io::Error::other)
+}
+
+/// Paths tagged with a tag through this file manager
+pub fn tagged_paths(tag: ColorTag) -> Vec<PathBuf> {
+    with_tag_db(|db| db.paths(tag)).unwrap_or_else(|err| {
+        log::warn!("failed to find paths tagged {:?}: {}", tag, err);
+        Vec::new()
+    })
+}
+
+/// Keep the tags of a moved or renamed item, and of everything inside it if `inside` is set.
+/// Extended attributes move with the files, but the database is keyed by path.
+pub fn moved(from: &Path, to: &Path, inside: bool) {
+    if let Err(err) = with_tag_db(|db| db.move_tags(from, to, inside)) {
+        log::warn!("failed to move tags of {:?} to {:?}: {}", from, to, err);
+    }
+}
+
+/// Forget the tags of a removed item and of everything inside it
+pub fn removed(path: &Path) {
+    if let Err(err) = with_tag_db(|db| db.remove_tags(path)) {
+        log::warn!("failed to remove tags of {:?}: {}", path, err);
+    }
+}
+
+/// Tags that have been given to at least one path, for listing in the sidebar
+pub fn used_tags() -> Vec<ColorTag> {
+    with_tag_db(|db| db.used_tags()).unwrap_or_else(|err| {
+        log::warn!("failed to list used tags: {}", err);
+        Vec::new()
+    })
+}
+
+fn parse_tags(value: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(value)
+        .split(',')
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Read the color tags of a file, ignoring other tags
+pub fn read_tags(path: &Path) -> Vec<ColorTag> {
+    match get_xattr(path) {
+        Ok(Some(value)) => {
+            let mut tags: Vec<_> = parse_tags(&value)
+                .iter()
+                .filter_map(|tag| ColorTag::from_key(tag))
+                .collect();
+            tags.sort();
+            tags.dedup();
+            tags
+        }
+        Ok(None) => Vec::new(),
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => with_tag_db(|db| db.tags(path))
+            .unwrap_or_else(|err| {
+                log::debug!("failed to read tags of {:?} from database: {}", path, err);
+                Vec::new()
+            }),
+        Err(err) => {
+            log::debug!("failed to read tags of {:?}: {}", path, err);
+            Vec::new()
+        }
+    }
+}
+
+/// Replace the color tags of a file, keeping any other tags
+pub fn write_tags(path: &Path, color_tags: &[ColorTag]) -> io::Result<()> {
+    match write_xattr_tags(path, color_tags) {
+        Ok(()) => {
+            // The extended attribute is what counts, the database only indexes it
+            if let Err(err) = with_tag_db(|db| db.set_tags(path, color_tags)) {
+                log::warn!("failed to index tags of {:?}: {}", path, err);
+            }
+            Ok(())
+        }
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+            with_tag_db(|db| db.set_tags(path, color_tags))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+fn write_xattr_tags(path: &Path, color_tags: &[ColorTag]) -> io::Result<()> {
+    let mut tags: Vec<String> = match get_xattr(path)? {
+        Some(value) => parse_tags(&value)
+            .into_iter()
+            .filter(|tag| ColorTag::from_key(tag).is_none())
+            .collect(),
+        None => Vec::new(),
+    };
+    tags.extend(color_tags.iter().map(|tag| tag.key().to_string()));
+    if tags.is_empty() {
+        remove_xattr(path)
+    } else {
+        set_xattr(path, tags.join(",").as_bytes())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(std::ffi::CString::new(path.as_os_str().as_bytes())?)
+}
+
+/// Last error of an attribute call, with file systems that do not support attributes reported
+/// as unsupported
+#[cfg(target_os = "linux")]
+fn last_xattr_error() -> io::Error {
+    let err = io::Error::last_os_error();
+    if err.raw_os_error() == Some(libc::ENOTSUP) {
+        io::ErrorKind::Unsupported.into()
+    } else {
+        err
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn get_xattr(path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let path = c_path(path)?;
+    let mut value = Vec::new();
+    loop {
+        let size = unsafe {
+            libc::getxattr(
+                path.as_ptr(),
+                XATTR_NAME.as_ptr(),
+                value.as_mut_ptr().cast(),
+                value.len(),
+            )
+        };
+        if size < 0 {
+            let err = last_xattr_error();
+            return match err.raw_os_error() {
+                // Attribute is not set
+                Some(libc::ENODATA) => Ok(None),
+                // Attribute grew since the size was checked
+                Some(libc::ERANGE) => {
+                    value.clear();
+                    continue;
+                }
+                _ => Err(err),
+            };
+        }
+        let size = size as usize;
+        if value.is_empty() && size > 0 {
+            // First call only returns the size
+            value.resize(size, 0);
+            continue;
+        }
+        value.truncate(size);
+        return Ok(Some(value));
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_xattr(path: &Path, value: &[u8]) -> io::Result<()> {
+    let path = c_path(path)?;
+    let res = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            XATTR_NAME.as_ptr(),
+            value.as_ptr().cast(),
+            value.len(),
+            0,
+        )
+    };
+    if res < 0 {
+        return Err(last_xattr_error());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn remove_xattr(path: &Path) -> io::Result<()> {
+    let path = c_path(path)?;
+    let res = unsafe { libc::removexattr(path.as_ptr(), XATTR_NAME.as_ptr()) };
+    if res < 0 {
+        let err = last_xattr_error();
+        if err.raw_os_error() != Some(libc::ENODATA) {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_xattr(_path: &Path) -> io::Result<Option<Vec<u8>>> {
+    let _ = XATTR_NAME;
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_xattr(_path: &Path, _value: &[u8]) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn remove_xattr(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorTag, TagDb};
+    use std::{io, path::Path};
+    use tempfile::TempDir;
+
+    #[test]
+    fn tag_database() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let mut db = TagDb::open(&dir.path().join("tags.sqlite")).map_err(io::Error::other)?;
+        let a = Path::new("/music/a.flac");
+        let b = Path::new("/music/b.flac");
+
+        db.set_tags(a, &[ColorTag::Red, ColorTag::Blue])
+            .map_err(io::Error::other)?;
+        db.set_tags(b, &[ColorTag::Red]).map_err(io::Error::other)?;
+        assert_eq!(
+            db.tags(a).map_err(io::Error::other)?,
+            [ColorTag::Red, ColorTag::Blue]
+        );
+        let mut paths = db.paths(ColorTag::Red).map_err(io::Error::other)?;
+        paths.sort();
+        assert_eq!(paths, [a, b]);
+
+        // Setting tags replaces the previous ones
+        db.set_tags(a, &[ColorTag::Green])
+            .map_err(io::Error::other)?;
+        assert_eq!(
+            db.paths(ColorTag::Blue).map_err(io::Error::other)?,
+            Vec::<&Path>::new()
+        );
+        assert_eq!(
+            db.used_tags().map_err(io::Error::other)?,
+            [ColorTag::Red, ColorTag::Green]
+        );
+
+        db.set_tags(b, &[]).map_err(io::Error::other)?;
+        assert!(db.tags(b).map_err(io::Error::other)?.is_empty());
+
+        // Moving a folder moves the tags of its contents, but not of similarly named paths
+        let other = Path::new("/music-old/c.flac");
+        db.set_tags(other, &[ColorTag::Gray])
+            .map_err(io::Error::other)?;
+        db.move_tags(Path::new("/music"), Path::new("/songs"), true)
+            .map_err(io::Error::other)?;
+        assert!(db.tags(a).map_err(io::Error::other)?.is_empty());
+        assert_eq!(
+            db.tags(Path::new("/songs/a.flac"))
+                .map_err(io::Error::other)?,
+            [ColorTag::Green]
+        );
+        assert_eq!(db.tags(other).map_err(io::Error::other)?, [ColorTag::Gray]);
+
+        db.remove_tags(Path::new("/songs"))
+            .map_err(io::Error::other)?;
+        assert_eq!(db.used_tags().map_err(io::Error::other)?, [ColorTag::Gray]);
+
+        Ok(())
+    }
+}
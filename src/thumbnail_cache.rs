@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Thumbnails saved in the cache shared with other apps, as described by the freedesktop.org
+//! thumbnail managing standard. A thumbnail is used until the modification time of its file changes.
+
+use md5::Digest;
+use std::{
+    fs, io,
+    os::unix::fs::DirBuilderExt,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Folders of the cache, by the largest thumbnail they hold
+const THUMBNAIL_FOLDERS: [(u32, &str); 4] = [
+    (128, "normal"),
+    (256, "large"),
+    (512, "x-large"),
+    (1024, "xx-large"),
+];
+
+pub type CachedThumbnail = (image::RgbaImage, Option<(u32, u32)>);
+
+fn cache_root() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("thumbnails"))
+}
+
+/// Folders that may have a thumbnail of this size, the best fit first
+fn folders(thumbnail_size: u32) -> impl Iterator<Item = &'static str> {
+    let fits = THUMBNAIL_FOLDERS
+        .iter()
+        .filter(move |(max_size, _)| *max_size >= thumbnail_size);
+    // Thumbnails larger than all folders are saved in the largest one
+    let largest = THUMBNAIL_FOLDERS
+        .iter()
+        .rev()
+        .take(usize::from(thumbnail_size > THUMBNAIL_FOLDERS[3].0));
+    fits.chain(largest).map(|(_, name)| *name)
+}
+
+fn file_uri(path: &Path) -> io::Result<String> {
+    url::Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|()| io::Error::other(format!("{:?} is not an absolute path", path)))
+}
+
+/// Name of the thumbnail, which is the MD5 hash of the file URI
+fn thumbnail_name(uri: &str) -> String {
+    let hash = md5::Md5::digest(uri.as_bytes());
+    let mut name: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+    name.push_str(".png");
+    name
+}
+
+fn mtime(metadata: &fs::Metadata) -> io::Result<u64> {
+    Ok(metadata
+        .modified()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs()))
+}
+
+/// Cached thumbnail with the original size of images, if it is still up to date
+pub fn load(path: &Path, metadata: &fs::Metadata, thumbnail_size: u32) -> Option<CachedThumbnail> {
+    load_in(&cache_root()?, path, metadata, thumbnail_size)
+}
+
+fn load_in(
+    root: &Path,
+    path: &Path,
+    metadata: &fs::Metadata,
+    thumbnail_size: u32,
+) -> Option<CachedThumbnail> {
+    // Thumbnails are not made for thumbnails
+    if path.starts_with(root) {
+        return None;
+    }
+    let uri = file_uri(path).ok()?;
+    let mtime = mtime(metadata).ok()?;
+    let name = thumbnail_name(&uri);
+    for folder in folders(thumbnail_size) {
+        let thumbnail_path = root.join(folder).join(&name);
+        let data = match fs::read(&thumbnail_path) {
+            Ok(ok) => ok,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                log::warn!("failed to read thumbnail {:?}: {}", thumbnail_path, err);
+                continue;
+            }
+        };
+        match decode(&data, &uri, mtime) {
+            Ok(Some(cached)) => return Some(cached),
+            Ok(None) => log::debug!("outdated thumbnail {:?} for {:?}", thumbnail_path, path),
+            Err(err) => log::warn!("failed to decode thumbnail {:?}: {}", thumbnail_path, err),
+        }
+    }
+    None
+}
+
+/// Thumbnail image, or `None` if it was made for a different file or an older version of it
+fn decode(data: &[u8], uri: &str, mtime: u64) -> Result<Option<CachedThumbnail>, String> {
+    let reader = png::Decoder::new(io::Cursor::new(data))
+        .read_info()
+        .map_err(|err| err.to_string())?;
+    let text = |keyword: &str| {
+        reader
+            .info()
+            .uncompressed_latin1_text
+            .iter()
+            .find(|chunk| chunk.keyword == keyword)
+            .map(|chunk| chunk.text.as_str())
+    };
+    if text("Thumb::URI") != Some(uri)
+        || text("Thumb::MTime").and_then(|text| text.parse().ok()) != Some(mtime)
+    {
+        return Ok(None);
+    }
+    let dimension = |keyword| text(keyword)?.parse().ok();
+    let original_size_opt = dimension("Thumb::Image::Width").zip(dimension("Thumb::Image::Height"));
+    let image = image::load_from_memory_with_format(data, image::ImageFormat::Png)
+        .map_err(|err| err.to_string())?
+        .into_rgba8();
+    Ok(Some((image, original_size_opt)))
+}
+
+/// Save a thumbnail for other views and apps to use
+pub fn save(
+    path: &Path,
+    metadata: &fs::Metadata,
+    thumbnail_size: u32,
+    image: &image::RgbaImage,
+    original_size_opt: Option<(u32, u32)>,
+) -> io::Result<()> {
+    let root = cache_root().ok_or_else(|| io::Error::other("no cache directory"))?;
+    save_in(
+        &root,
+        path,
+        metadata,
+        thumbnail_size,
+        image,
+        original_size_opt,
+    )
+}
+
+fn save_in(
+    root: &Path,
+    path: &Path,
+    metadata: &fs::Metadata,
+    thumbnail_size: u32,
+    image: &image::RgbaImage,
+    original_size_opt: Option<(u32, u32)>,
+) -> io::Result<()> {
+    if path.starts_with(root) {
+        return Ok(());
+    }
+    let uri = file_uri(path)?;
+    let Some(folder) = folders(thumbnail_size).next() else {
+        return Ok(());
+    };
+    let dir = root.join(folder);
+    // The cache is private, as thumbnails show the contents of files
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)?;
+
+    let mut text = vec![
+        ("Thumb::URI", uri.clone()),
+        ("Thumb::MTime", mtime(metadata)?.to_string()),
+        ("Thumb::Size", metadata.len().to_string()),
+        ("Software", "cosmic-files".to_string()),
+    ];
+    if let Some((width, height)) = original_size_opt {
+        text.push(("Thumb::Image::Width", width.to_string()));
+        text.push(("Thumb::Image::Height", height.to_string()));
+    }
+
+    // Written to a temporary file first, so that a partial thumbnail is never read
+    let mut file = tempfile::NamedTempFile::new_in(&dir)?;
+    let mut encoder = png::Encoder::new(file.as_file_mut(), image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    for (keyword, value) in text {
+        encoder
+            .add_text_chunk(keyword.to_string(), value)
+            .map_err(io::Error::other)?;
+    }
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer
+        .write_image_data(image.as_raw())
+        .map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)?;
+    file.persist(dir.join(thumbnail_name(&uri)))
+        .map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Remove the thumbnails of a file that was changed in place, as its modification time may not
+/// have changed enough for them to be outdated
+pub fn remove(path: &Path) -> io::Result<()> {
+    let root = cache_root().ok_or_else(|| io::Error::other("no cache directory"))?;
+    remove_in(&root, path)
+}
+
+fn remove_in(root: &Path, path: &Path) -> io::Result<()> {
+    let name = thumbnail_name(&file_uri(path)?);
+    for (_, folder) in THUMBNAIL_FOLDERS {
+        match fs::remove_file(root.join(folder).join(&name)) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{folders, load_in, remove_in, save_in, thumbnail_name};
+    use std::{fs, io, time::Duration};
+    use tempfile::TempDir;
+
+    #[test]
+    fn thumbnail_cache() -> io::Result<()> {
+        // Example from the thumbnail managing standard
+        assert_eq!(
+            thumbnail_name("file:///home/jens/photos/me.png"),
+            "c6ee772d9e49320e97ec29a7eb5b1697.png"
+        );
+        assert_eq!(folders(320).collect::<Vec<_>>(), ["x-large", "xx-large"]);
+        assert_eq!(folders(2048).collect::<Vec<_>>(), ["xx-large"]);
+
+        let cache = TempDir::new()?;
+        let dir = TempDir::new()?;
+        let path = dir.path().join("photo.png");
+        fs::write(&path, b"photo")?;
+        let metadata = fs::metadata(&path)?;
+        assert!(load_in(cache.path(), &path, &metadata, 320).is_none());
+
+        let image = image::RgbaImage::from_pixel(4, 2, image::Rgba([255, 0, 0, 255]));
+        save_in(cache.path(), &path, &metadata, 320, &image, Some((40, 20)))?;
+        let (cached, original_size_opt) =
+            load_in(cache.path(), &path, &metadata, 320).expect("thumbnail not cached");
+        assert_eq!(cached, image);
+        assert_eq!(original_size_opt, Some((40, 20)));
+
+        // Changing the file makes the thumbnail outdated
+        let file = fs::File::options().write(true).open(&path)?;
+        file.set_modified(metadata.modified()? + Duration::from_secs(60))?;
+        let metadata = fs::metadata(&path)?;
+        assert!(load_in(cache.path(), &path, &metadata, 320).is_none());
+
+        save_in(cache.path(), &path, &metadata, 320, &image, None)?;
+        remove_in(cache.path(), &path)?;
+        assert!(load_in(cache.path(), &path, &metadata, 320).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Sizes of trashed items and automatic emptying of the trash, which deletes items trashed too
+//! long ago and then the oldest items while the trash is larger than the configured limit.
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Sorted indexes of the items to delete, given when each item was trashed in seconds since the
+/// epoch and its size. A limit of 0 days or 0 bytes is not applied.
+pub fn expired(items: &[(i64, u64)], now_secs: u64, max_days: u32, max_size: u64) -> Vec<usize> {
+    let mut expired = vec![false; items.len()];
+    if max_days > 0 {
+        let old_secs = now_secs.saturating_sub(u64::from(max_days) * DAY_SECS);
+        for (i, (time_deleted, _)) in items.iter().enumerate() {
+            if u64::try_from(*time_deleted).is_ok_and(|secs| secs < old_secs) {
+                expired[i] = true;
+            }
+        }
+    }
+    if max_size > 0 {
+        let mut kept: Vec<usize> = (0..items.len()).filter(|&i| !expired[i]).collect();
+        let mut size: u64 = kept.iter().map(|&i| items[i].1).sum();
+        kept.sort_by_key(|&i| items[i].0);
+        for i in kept {
+            if size <= max_size {
+                break;
+            }
+            size -= items[i].1;
+            expired[i] = true;
+        }
+    }
+    (0..items.len()).filter(|&i| expired[i]).collect()
+}
+
+/// Trashed file or folder of an item, which the trash specification keeps in the `files` folder
+/// next to the `info` folder holding the info file that is the ID of the item. This is the same for
+/// the home trash and the `.Trash-$uid` folders of other volumes.
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+))]
+pub(crate) fn trash_file_path(item: &trash::TrashItem) -> Option<std::path::PathBuf> {
+    let info = std::path::Path::new(&item.id);
+    let name = info.file_stem()?;
+    Some(info.parent()?.parent()?.join("files").join(name))
+}
+
+#[cfg(not(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+)))]
+pub(crate) fn trash_file_path(_item: &trash::TrashItem) -> Option<std::path::PathBuf> {
+    None
+}
+
+/// Size of the files inside a trashed folder
+pub fn folder_size(item: &trash::TrashItem) -> u64 {
+    trash_file_path(item).map_or(0, |path| {
+        walkdir::WalkDir::new(path)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum()
+    })
+}
+
+/// Size of a trashed item, adding up the files inside trashed folders
+pub fn item_size(item: &trash::TrashItem, size: &trash::TrashItemSize) -> u64 {
+    match size {
+        trash::TrashItemSize::Bytes(bytes) => *bytes,
+        trash::TrashItemSize::Entries(_) => folder_size(item),
+    }
+}
+
+// This config statement is from trash::os_limited, inverted
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn expired_items(
+    _max_days: u32,
+    _max_size: u64,
+) -> Result<Vec<trash::TrashItem>, trash::Error> {
+    Ok(Vec::new())
+}
+
+/// Trashed items selected by [`expired`], which are deleted with
+/// [`Operation::DeleteTrash`](crate::operation::Operation) together with their info files. Items
+/// without a valid info file are not listed.
+// This config statement is from trash::os_limited
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn expired_items(max_days: u32, max_size: u64) -> Result<Vec<trash::TrashItem>, trash::Error> {
+    if max_days == 0 && max_size == 0 {
+        return Ok(Vec::new());
+    }
+    let items = trash::os_limited::list()?;
+    let sizes: Vec<(i64, u64)> = items
+        .iter()
+        .map(|item| {
+            // Folder sizes are only needed for the size limit
+            let size = if max_size > 0 {
+                match trash::os_limited::metadata(item) {
+                    Ok(metadata) => item_size(item, &metadata.size),
+                    Err(err) => {
+                        log::warn!("failed to get metadata for trash item {:?}: {}", item, err);
+                        0
+                    }
+                }
+            } else {
+                0
+            };
+            (item.time_deleted, size)
+        })
+        .collect();
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+    let expired = expired(&sizes, now_secs, max_days, max_size);
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| expired.binary_search(i).is_ok())
+        .map(|(_, item)| item)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expired, DAY_SECS};
+    use std::path::PathBuf;
+
+    #[test]
+    fn expired_items() {
+        let now = 100 * DAY_SECS;
+        let day = |days: u64| (now - days * DAY_SECS) as i64;
+        let items = [
+            (day(40), 10),
+            (day(2), 50),
+            (day(10), 30),
+            (day(31), 20),
+            (day(0), 40),
+        ];
+
+        assert!(expired(&items, now, 0, 0).is_empty());
+        assert_eq!(expired(&items, now, 30, 0), vec![0, 3]);
+        // Oldest items go first until the rest fits
+        assert_eq!(expired(&items, now, 0, 100), vec![0, 2, 3]);
+        assert_eq!(expired(&items, now, 0, 150), Vec::<usize>::new());
+        // Items deleted for their age no longer count towards the size limit
+        assert_eq!(expired(&items, now, 30, 90), vec![0, 2, 3]);
+        assert_eq!(expired(&items, now, 30, 120), vec![0, 3]);
+    }
+
+    #[cfg(all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    ))]
+    #[test]
+    fn trash_file_location() {
+        let item = |id: &str| trash::TrashItem {
+            id: id.into(),
+            name: "ferris.txt".into(),
+            original_parent: PathBuf::from("/home/user"),
+            time_deleted: 0,
+        };
+        assert_eq!(
+            super::trash_file_path(&item(
+                "/home/user/.local/share/Trash/info/ferris.2.txt.trashinfo"
+            )),
+            Some(PathBuf::from(
+                "/home/user/.local/share/Trash/files/ferris.2.txt"
+            ))
+        );
+        assert_eq!(
+            super::trash_file_path(&item("/mnt/usb/.Trash-1000/info/ferris.txt.trashinfo")),
+            Some(PathBuf::from("/mnt/usb/.Trash-1000/files/ferris.txt"))
+        );
+    }
+}
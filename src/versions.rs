@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Earlier versions of files kept in file system snapshots, made by snapper on btrfs in a
+//! `.snapshots` folder or by ZFS in a `.zfs/snapshot` folder at the root of a snapshotted volume.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileVersion {
+    /// Copy of the file inside the snapshot
+    pub path: PathBuf,
+    /// Name of the snapshot, a number for snapper
+    pub snapshot: String,
+    pub modified_opt: Option<SystemTime>,
+    pub size: u64,
+}
+
+/// Folders holding one folder per snapshot below `root`, with the part of the path inside each
+/// snapshot folder that comes before the snapshotted files
+fn snapshot_dirs(root: &Path) -> [(PathBuf, &'static str); 2] {
+    [
+        (root.join(".snapshots"), "snapshot"),
+        (root.join(".zfs").join("snapshot"), ""),
+    ]
+}
+
+/// Versions of the file or folder that differ from the current one, newest first. Versions with
+/// the same size and modification time are only listed once.
+pub fn versions(path: &Path) -> Vec<FileVersion> {
+    let current_opt = fs::symlink_metadata(path).ok();
+    let mut versions = Vec::new();
+    for root in path.ancestors().skip(1) {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        for (dir, inner) in snapshot_dirs(root) {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let version_path = entry.path().join(inner).join(relative);
+                let Ok(metadata) = fs::symlink_metadata(&version_path) else {
+                    continue;
+                };
+                if current_opt
+                    .as_ref()
+                    .is_some_and(|current| current.is_dir() != metadata.is_dir())
+                {
+                    continue;
+                }
+                versions.push(FileVersion {
+                    path: version_path,
+                    snapshot: entry.file_name().to_string_lossy().into_owned(),
+                    modified_opt: metadata.modified().ok(),
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+
+    let current_opt = current_opt.map(|current| (current.modified().ok(), current.len()));
+    versions.retain(|version| current_opt != Some((version.modified_opt, version.size)));
+    versions.sort_by(|a, b| {
+        b.modified_opt
+            .cmp(&a.modified_opt)
+            .then_with(|| a.snapshot.cmp(&b.snapshot))
+    });
+    versions.dedup_by(|a, b| a.modified_opt == b.modified_opt && a.size == b.size);
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::versions;
+    use std::{fs, io, time::Duration};
+    use tempfile::TempDir;
+
+    #[test]
+    fn snapshot_versions() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::create_dir_all(root.join("docs"))?;
+        let path = root.join("docs").join("notes.txt");
+        fs::write(&path, b"current notes")?;
+
+        // Snapper snapshots, where the newest one is unchanged
+        let old = root.join(".snapshots/1/snapshot/docs/notes.txt");
+        fs::create_dir_all(old.parent().unwrap())?;
+        fs::write(&old, b"old")?;
+        let old_file = fs::File::options().write(true).open(&old)?;
+        old_file.set_modified(fs::metadata(&path)?.modified()? - Duration::from_secs(60))?;
+        let same = root.join(".snapshots/2/snapshot/docs/notes.txt");
+        fs::create_dir_all(same.parent().unwrap())?;
+        fs::copy(&path, &same)?;
+        let same_file = fs::File::options().write(true).open(&same)?;
+        same_file.set_modified(fs::metadata(&path)?.modified()?)?;
+        // ZFS snapshot with the same version as the first snapper snapshot
+        let copy = root.join(".zfs/snapshot/daily/docs/notes.txt");
+        fs::create_dir_all(copy.parent().unwrap())?;
+        fs::copy(&old, &copy)?;
+        let copy_file = fs::File::options().write(true).open(&copy)?;
+        copy_file.set_modified(fs::metadata(&old)?.modified()?)?;
+
+        let versions = versions(&path);
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].snapshot, "1");
+        assert_eq!(versions[0].path, old);
+        assert_eq!(versions[0].size, 3);
+
+        assert!(super::versions(&root.join("docs").join("other.txt")).is_empty());
+
+        Ok(())
+    }
+}
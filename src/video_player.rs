@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Playback of whole videos in the gallery. Frames are streamed from ffmpeg at the pace of the
+//! video, and the sound is played with ffplay when it is installed. Seeking starts both again from
+//! the new position.
+
+use image::RgbaImage;
+use std::{
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// Frames per second streamed, which limits the work done for videos with high frame rates
+const VIDEO_FPS: u32 = 25;
+
+pub struct VideoPlayer {
+    path: PathBuf,
+    duration_opt: Option<Duration>,
+    /// Position when playback last started or stopped
+    offset: Duration,
+    started_opt: Option<Instant>,
+    /// Changed when playback starts, stops, or seeks, so that frames of an earlier stream are
+    /// ignored
+    generation: u64,
+    sound_opt: Option<Child>,
+}
+
+impl VideoPlayer {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            duration_opt: None,
+            offset: Duration::ZERO,
+            started_opt: None,
+            generation: 0,
+            sound_opt: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn duration_opt(&self) -> Option<Duration> {
+        self.duration_opt
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration_opt = Some(duration);
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn playing(&self) -> bool {
+        self.started_opt.is_some()
+    }
+
+    pub fn position(&self) -> Duration {
+        let position = match self.started_opt {
+            Some(started) => self.offset + started.elapsed(),
+            None => self.offset,
+        };
+        match self.duration_opt {
+            Some(duration) => position.min(duration),
+            None => position,
+        }
+    }
+
+    pub fn play(&mut self) {
+        if self.playing() {
+            return;
+        }
+        // Start over when playing again at the end
+        if self
+            .duration_opt
+            .is_some_and(|duration| self.offset >= duration)
+        {
+            self.offset = Duration::ZERO;
+        }
+        self.generation += 1;
+        self.started_opt = Some(Instant::now());
+        if crate::audio_preview::player_available() {
+            match sound(&self.path, self.offset) {
+                Ok(child) => self.sound_opt = Some(child),
+                Err(err) => log::warn!("failed to play sound of {:?}: {}", self.path, err),
+            }
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.offset = self.position();
+        self.started_opt = None;
+        self.generation += 1;
+        if let Some(mut child) = self.sound_opt.take() {
+            // The process may have exited already at the end
+            let _ = child.kill();
+            if let Err(err) = child.wait() {
+                log::warn!("failed to wait for ffplay: {}", err);
+            }
+        }
+    }
+
+    pub fn seek(&mut self, position: Duration) {
+        let playing = self.playing();
+        self.pause();
+        self.offset = match self.duration_opt {
+            Some(duration) => position.min(duration),
+            None => position,
+        };
+        if playing {
+            self.play();
+        }
+    }
+
+    /// Stop at the end of the video, once its last frame was shown
+    pub fn finish(&mut self) {
+        self.pause();
+        if let Some(duration) = self.duration_opt {
+            self.offset = duration;
+        }
+    }
+}
+
+impl Drop for VideoPlayer {
+    fn drop(&mut self) {
+        self.pause();
+    }
+}
+
+/// Sound of the video from `position`, without a window
+fn sound(path: &Path, position: Duration) -> io::Result<Child> {
+    Command::new("ffplay")
+        .arg("-nodisp")
+        .arg("-autoexit")
+        .arg("-vn")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-ss")
+        .arg(format!("{:.3}", position.as_secs_f64()))
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+}
+
+/// Stream the frames of a video from `position` at the pace of the video, scaled and padded to
+/// squares of `size`, until `on_frame` returns false or the video ends
+pub fn stream(
+    path: &Path,
+    position: Duration,
+    size: u32,
+    mut on_frame: impl FnMut(RgbaImage) -> bool,
+) -> Result<(), String> {
+    let mut child = Command::new("ffmpeg")
+        .arg("-loglevel")
+        .arg("error")
+        // Read the input at its own frame rate instead of as fast as possible
+        .arg("-re")
+        .arg("-ss")
+        .arg(format!("{:.3}", position.as_secs_f64()))
+        .arg("-i")
+        .arg(path)
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "fps={VIDEO_FPS},scale={size}:{size}:force_original_aspect_ratio=decrease,pad={size}:{size}:-1:-1:color=black@0"
+        ))
+        .arg("-pix_fmt")
+        .arg("rgba")
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("failed to run ffmpeg: {}", err))?;
+    let Some(mut stdout) = child.stdout.take() else {
+        return Err("failed to read ffmpeg output".to_string());
+    };
+
+    let res = loop {
+        let mut data = vec![0; (size * size * 4) as usize];
+        match stdout.read_exact(&mut data) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break Ok(()),
+            Err(err) => break Err(format!("failed to read ffmpeg output: {}", err)),
+        }
+        let Some(image) = RgbaImage::from_raw(size, size, data) else {
+            break Err("invalid frame size".to_string());
+        };
+        if !on_frame(image) {
+            break Ok(());
+        }
+    };
+
+    // ffmpeg is stopped if the frames are no longer wanted
+    let _ = child.kill();
+    if let Err(err) = child.wait() {
+        log::warn!("failed to wait for ffmpeg: {}", err);
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VideoPlayer;
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn video_player_position() {
+        let mut player = VideoPlayer::new(PathBuf::from("clip.webm"));
+        player.set_duration(Duration::from_secs(30));
+        assert!(!player.playing());
+
+        let generation = player.generation();
+        player.seek(Duration::from_secs(10));
+        assert_eq!(player.position(), Duration::from_secs(10));
+        assert_ne!(player.generation(), generation, "Seeking drops old frames");
+
+        // Seeking past the end stops at the end
+        player.seek(Duration::from_secs(60));
+        assert_eq!(player.position(), Duration::from_secs(30));
+
+        player.finish();
+        assert!(!player.playing());
+        assert_eq!(player.position(), Duration::from_secs(30));
+    }
+}
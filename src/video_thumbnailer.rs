@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Thumbnails for videos made with ffmpeg: a poster frame, frames spread over the whole video
+//! for scrubbing by hovering over the thumbnail in grid view, and the start of the video for
+//! animated previews.
+
+use image::RgbaImage;
+use std::{path::Path, process::Command, time::Duration};
+
+/// Position of the poster frame as a fraction of the duration, which skips black title frames
+const POSTER_POSITION: f64 = 0.1;
+
+/// Frames shown when scrubbing across a thumbnail
+const SCRUB_FRAMES: u32 = 20;
+
+pub fn duration(path: &Path) -> Result<Duration, String> {
+    let output = Command::new("ffprobe")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-show_entries")
+        .arg("format=duration")
+        .arg("-of")
+        .arg("default=noprint_wrappers=1:nokey=1")
+        .arg(path)
+        .output()
+        .map_err(|err| format!("failed to run ffprobe: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let seconds: f64 = stdout
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid duration {:?}", stdout.trim()))?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+/// Frame at the position, scaled and padded to a square of the thumbnail size like the frames of
+/// animated previews
+pub fn frame_at(path: &Path, position: Duration, thumbnail_size: u32) -> Result<RgbaImage, String> {
+    let output = Command::new("ffmpeg")
+        .arg("-loglevel")
+        .arg("error")
+        // Seeking before the input is fast, as it jumps to the nearest keyframe
+        .arg("-ss")
+        .arg(format!("{:.3}", position.as_secs_f64()))
+        .arg("-i")
+        .arg(path)
+        .arg("-an")
+        .arg("-frames:v")
+        .arg("1")
+        .arg("-vf")
+        .arg(format!(
+            "scale={size}:{size}:force_original_aspect_ratio=decrease,pad={size}:{size}:-1:-1:color=black@0",
+            size = thumbnail_size
+        ))
+        .arg("-pix_fmt")
+        .arg("rgba")
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-")
+        .output()
+        .map_err(|err| format!("failed to run ffmpeg: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    RgbaImage::from_raw(thumbnail_size, thumbnail_size, output.stdout)
+        .ok_or_else(|| format!("no frame at {:?}", position))
+}
+
+pub fn poster_frame(path: &Path, thumbnail_size: u32) -> Result<RgbaImage, String> {
+    // Streams may not have a duration, so start at the beginning
+    let position =
+        duration(path).map_or(Duration::ZERO, |duration| duration.mul_f64(POSTER_POSITION));
+    frame_at(path, position, thumbnail_size)
+}
+
+/// Frames of the start of the video at `fps`, scaled and padded to squares of the thumbnail size
+pub fn frames(
+    path: &Path,
+    thumbnail_size: u32,
+    duration: Duration,
+    fps: u128,
+) -> Result<Vec<RgbaImage>, String> {
+    let output = Command::new("ffmpeg")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-t")
+        .arg(duration.as_secs().to_string())
+        .arg("-i")
+        .arg(path)
+        .arg("-an")
+        .arg("-vf")
+        .arg(format!(
+            "fps={fps},scale={size}:{size}:force_original_aspect_ratio=decrease,pad={size}:{size}:-1:-1:color=black@0",
+            size = thumbnail_size
+        ))
+        .arg("-pix_fmt")
+        .arg("rgba")
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-")
+        .output()
+        .map_err(|err| format!("failed to run ffmpeg: {}", err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let frame_len = (thumbnail_size * thumbnail_size * 4) as usize;
+    Ok(output
+        .stdout
+        .chunks_exact(frame_len)
+        .filter_map(|chunk| RgbaImage::from_raw(thumbnail_size, thumbnail_size, chunk.to_vec()))
+        .collect())
+}
+
+/// Frames from the middle of equal parts of the video
+pub fn scrub_frames(path: &Path, thumbnail_size: u32) -> Result<Vec<RgbaImage>, String> {
+    let duration = duration(path)?;
+    (0..SCRUB_FRAMES)
+        .map(|i| {
+            let position = duration.mul_f64((f64::from(i) + 0.5) / f64::from(SCRUB_FRAMES));
+            frame_at(path, position, thumbnail_size)
+        })
+        .collect()
+}